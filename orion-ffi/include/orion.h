@@ -0,0 +1,68 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 The orion Developers
+ *
+ * C bindings for orion's high-level API. See orion-ffi/README.md.
+ */
+
+#ifndef ORION_H
+#define ORION_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes. These values are stable, new ones are only ever appended. */
+typedef enum orion_status {
+    ORION_OK = 0,
+    ORION_ERR_NULL_POINTER = 1,
+    ORION_ERR_BUFFER_TOO_SMALL = 2,
+    ORION_ERR_INVALID_LENGTH = 3,
+    ORION_ERR_INVALID_PARAMETER = 4,
+    ORION_ERR_INVALID_STATE = 5,
+    ORION_ERR_INVALID_ENCODING = 6,
+    ORION_ERR_AUTHENTICATION = 7,
+    ORION_ERR_NONCE_OVERFLOW = 8,
+    ORION_ERR_RNG = 9,
+    ORION_ERR_IO = 10,
+    ORION_ERR_UNKNOWN = 255
+} orion_status;
+
+/* Size of a key generated by orion_aead_keygen. */
+#define ORION_AEAD_KEYBYTES 32
+/* Bytes added to a plaintext by orion_aead_seal (24-byte nonce and 16-byte tag). */
+#define ORION_AEAD_ABYTES 40
+/* Size of a buffer that can hold any encoded password hash, including the NUL terminator. */
+#define ORION_PWHASH_STRBYTES 186
+/* Size of a digest produced by orion_hash_digest. */
+#define ORION_HASH_BYTES 32
+
+orion_status orion_aead_keygen(uint8_t *key, size_t key_len);
+
+orion_status orion_aead_seal(const uint8_t *key, size_t key_len,
+                             const uint8_t *plaintext, size_t plaintext_len,
+                             uint8_t *out, size_t out_len, size_t *out_written);
+
+orion_status orion_aead_open(const uint8_t *key, size_t key_len,
+                             const uint8_t *ciphertext, size_t ciphertext_len,
+                             uint8_t *out, size_t out_len, size_t *out_written);
+
+orion_status orion_pwhash_hash_password(const uint8_t *password, size_t password_len,
+                                        uint32_t iterations, uint32_t memory,
+                                        char *out, size_t out_len);
+
+orion_status orion_pwhash_verify(const char *password_hash,
+                                 const uint8_t *password, size_t password_len);
+
+orion_status orion_hash_digest(const uint8_t *data, size_t data_len,
+                               uint8_t *out, size_t out_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ORION_H */
@@ -0,0 +1,472 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! C bindings for orion's high-level API.
+//!
+//! See `include/orion.h` for the C declarations and `README.md` for the conventions
+//! shared by all functions.
+
+#![deny(missing_docs)]
+#![allow(clippy::missing_safety_doc)]
+
+use orion::errors::{ErrorKind, UnknownCryptoError};
+use orion::{aead, hash, pwhash};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Size of a key generated by [`orion_aead_keygen`].
+pub const ORION_AEAD_KEYBYTES: usize = 32;
+/// Bytes added to a plaintext by [`orion_aead_seal`].
+pub const ORION_AEAD_ABYTES: usize = 24 + 16;
+/// Size of a buffer that can hold any encoded password hash, including the NUL terminator.
+pub const ORION_PWHASH_STRBYTES: usize = pwhash::PasswordHash::MAX_ENCODED_LEN + 1;
+/// Size of a digest produced by [`orion_hash_digest`].
+pub const ORION_HASH_BYTES: usize = 32;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Status codes returned by all functions. The values are stable.
+pub enum OrionStatus {
+    /// The operation succeeded.
+    Ok = 0,
+    /// A pointer was `NULL` while its length was not `0`.
+    NullPointer = 1,
+    /// An output buffer was too small.
+    BufferTooSmall = 2,
+    /// See [`ErrorKind::InvalidLength`].
+    InvalidLength = 3,
+    /// See [`ErrorKind::InvalidParameter`].
+    InvalidParameter = 4,
+    /// See [`ErrorKind::InvalidState`].
+    InvalidState = 5,
+    /// See [`ErrorKind::InvalidEncoding`].
+    InvalidEncoding = 6,
+    /// See [`ErrorKind::MacMismatch`].
+    Authentication = 7,
+    /// See [`ErrorKind::NonceOverflow`].
+    NonceOverflow = 8,
+    /// See [`ErrorKind::RngFailure`].
+    Rng = 9,
+    /// See [`ErrorKind::Io`].
+    Io = 10,
+    /// Any other error, including a panic that was caught at the C boundary.
+    Unknown = 255,
+}
+
+impl From<UnknownCryptoError> for OrionStatus {
+    fn from(err: UnknownCryptoError) -> Self {
+        match err.kind() {
+            ErrorKind::InvalidLength => OrionStatus::InvalidLength,
+            ErrorKind::InvalidParameter => OrionStatus::InvalidParameter,
+            ErrorKind::InvalidState => OrionStatus::InvalidState,
+            ErrorKind::InvalidEncoding => OrionStatus::InvalidEncoding,
+            ErrorKind::MacMismatch => OrionStatus::Authentication,
+            ErrorKind::NonceOverflow => OrionStatus::NonceOverflow,
+            ErrorKind::RngFailure => OrionStatus::Rng,
+            ErrorKind::Io => OrionStatus::Io,
+            _ => OrionStatus::Unknown,
+        }
+    }
+}
+
+/// Run `f`, making sure that a panic never unwinds across the C boundary.
+fn ffi_boundary<F>(f: F) -> OrionStatus
+where
+    F: FnOnce() -> Result<(), OrionStatus>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => OrionStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => OrionStatus::Unknown,
+    }
+}
+
+/// Build a slice from a C pointer and length. `ptr` may only be `NULL` if `len` is `0`.
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], OrionStatus> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(OrionStatus::NullPointer)
+    } else {
+        Ok(std::slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Build a mutable slice from a C pointer and length. `ptr` may only be `NULL` if `len` is `0`.
+unsafe fn output<'a>(ptr: *mut u8, len: usize) -> Result<&'a mut [u8], OrionStatus> {
+    if len == 0 {
+        Ok(&mut [])
+    } else if ptr.is_null() {
+        Err(OrionStatus::NullPointer)
+    } else {
+        Ok(std::slice::from_raw_parts_mut(ptr, len))
+    }
+}
+
+/// Copy `src` into the beginning of `dst`, failing if `dst` is too small.
+fn copy_out(src: &[u8], dst: &mut [u8]) -> Result<usize, OrionStatus> {
+    if dst.len() < src.len() {
+        return Err(OrionStatus::BufferTooSmall);
+    }
+    dst[..src.len()].copy_from_slice(src);
+
+    Ok(src.len())
+}
+
+/// Zero `dst` if `status` is an error, so that output buffers are never partially written.
+fn zero_on_error(status: OrionStatus, dst: *mut u8, dst_len: usize) -> OrionStatus {
+    if status != OrionStatus::Ok && status != OrionStatus::NullPointer {
+        if let Ok(dst) = unsafe { output(dst, dst_len) } {
+            dst.iter_mut().for_each(|b| *b = 0);
+        }
+    }
+
+    status
+}
+
+#[no_mangle]
+/// Generate a random key of `key_len` bytes into `key`. `ORION_AEAD_KEYBYTES` should be used,
+/// unless a protocol requires another length.
+pub unsafe extern "C" fn orion_aead_keygen(key: *mut u8, key_len: usize) -> OrionStatus {
+    let status = ffi_boundary(|| {
+        let key_out = output(key, key_len)?;
+        let secret_key = aead::SecretKey::generate(key_len)?;
+        copy_out(secret_key.unprotected_as_bytes(), key_out).map(drop)
+    });
+
+    zero_on_error(status, key, key_len)
+}
+
+#[no_mangle]
+/// Encrypt and authenticate `plaintext` with XChaCha20Poly1305, see `orion::aead::seal()`.
+///
+/// `out` must be at least `plaintext_len + ORION_AEAD_ABYTES` bytes. On success, the number
+/// of bytes written is stored in `out_written`, if it is not `NULL`.
+pub unsafe extern "C" fn orion_aead_seal(
+    key: *const u8,
+    key_len: usize,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    out: *mut u8,
+    out_len: usize,
+    out_written: *mut usize,
+) -> OrionStatus {
+    let status = ffi_boundary(|| {
+        let secret_key = aead::SecretKey::from_slice(input(key, key_len)?)?;
+        let plaintext = input(plaintext, plaintext_len)?;
+        let dst = output(out, out_len)?;
+        if dst.len() < plaintext.len().saturating_add(ORION_AEAD_ABYTES) {
+            return Err(OrionStatus::BufferTooSmall);
+        }
+
+        let written = copy_out(&aead::seal(&secret_key, plaintext)?, dst)?;
+        if !out_written.is_null() {
+            *out_written = written;
+        }
+
+        Ok(())
+    });
+
+    zero_on_error(status, out, out_len)
+}
+
+#[no_mangle]
+/// Authenticate and decrypt `ciphertext`, see `orion::aead::open()`.
+///
+/// `out` must be at least `ciphertext_len - ORION_AEAD_ABYTES` bytes. On success, the number
+/// of bytes written is stored in `out_written`, if it is not `NULL`.
+pub unsafe extern "C" fn orion_aead_open(
+    key: *const u8,
+    key_len: usize,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    out: *mut u8,
+    out_len: usize,
+    out_written: *mut usize,
+) -> OrionStatus {
+    let status = ffi_boundary(|| {
+        let secret_key = aead::SecretKey::from_slice(input(key, key_len)?)?;
+        let ciphertext = input(ciphertext, ciphertext_len)?;
+        let dst = output(out, out_len)?;
+        if dst.len() < ciphertext.len().saturating_sub(ORION_AEAD_ABYTES) {
+            return Err(OrionStatus::BufferTooSmall);
+        }
+
+        let plaintext = aead::open_secret(&secret_key, ciphertext)?;
+        let written = copy_out(plaintext.unprotected_as_bytes(), dst)?;
+        if !out_written.is_null() {
+            *out_written = written;
+        }
+
+        Ok(())
+    });
+
+    zero_on_error(status, out, out_len)
+}
+
+#[no_mangle]
+/// Hash a password with Argon2i, see `orion::pwhash::hash_password()`.
+///
+/// The encoded password hash is written to `out` as a NUL-terminated string. `out` should
+/// be at least `ORION_PWHASH_STRBYTES` bytes.
+pub unsafe extern "C" fn orion_pwhash_hash_password(
+    password: *const u8,
+    password_len: usize,
+    iterations: u32,
+    memory: u32,
+    out: *mut c_char,
+    out_len: usize,
+) -> OrionStatus {
+    let status = ffi_boundary(|| {
+        let password = pwhash::Password::from_slice(input(password, password_len)?)?;
+        let dst = output(out as *mut u8, out_len)?;
+
+        let password_hash = pwhash::hash_password(&password, iterations, memory)?;
+        let encoded = password_hash.unprotected_as_encoded().as_bytes();
+        if dst.len() <= encoded.len() {
+            return Err(OrionStatus::BufferTooSmall);
+        }
+        dst[..encoded.len()].copy_from_slice(encoded);
+        dst[encoded.len()] = 0;
+
+        Ok(())
+    });
+
+    zero_on_error(status, out as *mut u8, out_len)
+}
+
+#[no_mangle]
+/// Verify a password against a NUL-terminated encoded password hash, see
+/// `orion::pwhash::hash_password_verify()`.
+pub unsafe extern "C" fn orion_pwhash_verify(
+    password_hash: *const c_char,
+    password: *const u8,
+    password_len: usize,
+) -> OrionStatus {
+    ffi_boundary(|| {
+        if password_hash.is_null() {
+            return Err(OrionStatus::NullPointer);
+        }
+        let encoded = CStr::from_ptr(password_hash)
+            .to_str()
+            .map_err(|_| OrionStatus::InvalidEncoding)?;
+        let expected = pwhash::PasswordHash::from_encoded(encoded)?;
+        let password = pwhash::Password::from_slice(input(password, password_len)?)?;
+
+        pwhash::hash_password_verify(&expected, &password).map_err(OrionStatus::from)
+    })
+}
+
+#[no_mangle]
+/// Hash `data` with BLAKE2b-256, see `orion::hash::digest()`. `out` must be at least
+/// `ORION_HASH_BYTES` bytes.
+pub unsafe extern "C" fn orion_hash_digest(
+    data: *const u8,
+    data_len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> OrionStatus {
+    let status = ffi_boundary(|| {
+        let data = input(data, data_len)?;
+        let dst = output(out, out_len)?;
+
+        copy_out(hash::digest(data)?.as_ref(), dst).map(drop)
+    });
+
+    zero_on_error(status, out, out_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_aead_roundtrip() {
+        let mut key = [0u8; ORION_AEAD_KEYBYTES];
+        let msg = b"Secret message";
+        let mut ct = [0u8; 14 + ORION_AEAD_ABYTES];
+        let mut pt = [0u8; 14];
+        let mut written = 0usize;
+
+        unsafe {
+            assert_eq!(
+                orion_aead_keygen(key.as_mut_ptr(), key.len()),
+                OrionStatus::Ok
+            );
+            assert_eq!(
+                orion_aead_seal(
+                    key.as_ptr(),
+                    key.len(),
+                    msg.as_ptr(),
+                    msg.len(),
+                    ct.as_mut_ptr(),
+                    ct.len(),
+                    &mut written
+                ),
+                OrionStatus::Ok
+            );
+            assert_eq!(written, ct.len());
+            assert_eq!(
+                orion_aead_open(
+                    key.as_ptr(),
+                    key.len(),
+                    ct.as_ptr(),
+                    ct.len(),
+                    pt.as_mut_ptr(),
+                    pt.len(),
+                    &mut written
+                ),
+                OrionStatus::Ok
+            );
+        }
+        assert_eq!(written, msg.len());
+        assert_eq!(&pt, msg);
+    }
+
+    #[test]
+    fn test_aead_errors() {
+        let key = [1u8; ORION_AEAD_KEYBYTES];
+        let msg = b"Secret message";
+        let mut ct = [0u8; 14 + ORION_AEAD_ABYTES];
+        let mut pt = [0xffu8; 14];
+
+        unsafe {
+            assert_eq!(
+                orion_aead_seal(
+                    key.as_ptr(),
+                    key.len(),
+                    msg.as_ptr(),
+                    msg.len(),
+                    ct.as_mut_ptr(),
+                    ct.len() - 1,
+                    ptr::null_mut()
+                ),
+                OrionStatus::BufferTooSmall
+            );
+            assert_eq!(
+                orion_aead_seal(
+                    ptr::null(),
+                    key.len(),
+                    msg.as_ptr(),
+                    msg.len(),
+                    ct.as_mut_ptr(),
+                    ct.len(),
+                    ptr::null_mut()
+                ),
+                OrionStatus::NullPointer
+            );
+            assert_eq!(
+                orion_aead_seal(
+                    key.as_ptr(),
+                    key.len(),
+                    msg.as_ptr(),
+                    msg.len(),
+                    ct.as_mut_ptr(),
+                    ct.len(),
+                    ptr::null_mut()
+                ),
+                OrionStatus::Ok
+            );
+
+            ct[30] ^= 1;
+            assert_eq!(
+                orion_aead_open(
+                    key.as_ptr(),
+                    key.len(),
+                    ct.as_ptr(),
+                    ct.len(),
+                    pt.as_mut_ptr(),
+                    pt.len(),
+                    ptr::null_mut()
+                ),
+                OrionStatus::Authentication
+            );
+        }
+        // The output buffer is zeroed on errors.
+        assert_eq!(pt, [0u8; 14]);
+    }
+
+    #[test]
+    fn test_pwhash_roundtrip() {
+        let password = b"Secret password";
+        let mut encoded = [0 as c_char; ORION_PWHASH_STRBYTES];
+
+        unsafe {
+            assert_eq!(
+                orion_pwhash_hash_password(
+                    password.as_ptr(),
+                    password.len(),
+                    3,
+                    1 << 4,
+                    encoded.as_mut_ptr(),
+                    encoded.len()
+                ),
+                OrionStatus::Ok
+            );
+            assert_eq!(
+                orion_pwhash_verify(encoded.as_ptr(), password.as_ptr(), password.len()),
+                OrionStatus::Ok
+            );
+            assert_eq!(
+                orion_pwhash_verify(encoded.as_ptr(), password.as_ptr(), password.len() - 1),
+                OrionStatus::Authentication
+            );
+            assert_eq!(
+                orion_pwhash_verify(ptr::null(), password.as_ptr(), password.len()),
+                OrionStatus::NullPointer
+            );
+            assert_eq!(
+                orion_pwhash_hash_password(
+                    password.as_ptr(),
+                    password.len(),
+                    1,
+                    1 << 4,
+                    encoded.as_mut_ptr(),
+                    encoded.len()
+                ),
+                OrionStatus::InvalidParameter
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_digest() {
+        let mut digest = [0u8; ORION_HASH_BYTES];
+
+        unsafe {
+            assert_eq!(
+                orion_hash_digest(ptr::null(), 0, digest.as_mut_ptr(), digest.len()),
+                OrionStatus::Ok
+            );
+        }
+        assert_eq!(&digest[..], hash::digest(b"").unwrap().as_ref());
+
+        unsafe {
+            assert_eq!(
+                orion_hash_digest(ptr::null(), 0, digest.as_mut_ptr(), digest.len() - 1),
+                OrionStatus::BufferTooSmall
+            );
+        }
+    }
+}
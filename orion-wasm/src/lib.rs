@@ -0,0 +1,158 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! WebAssembly bindings for orion's high-level API.
+//!
+//! Each exported function is a thin wrapper around a plain Rust function, so that the
+//! bindings can be tested on any target.
+
+#![deny(missing_docs)]
+
+use orion::errors::{ErrorKind, UnknownCryptoError};
+use orion::{aead, hash, pwhash};
+use wasm_bindgen::prelude::*;
+
+fn to_js_error(err: UnknownCryptoError) -> JsError {
+    // The message is as opaque as the `Display` implementation of UnknownCryptoError,
+    // apart from the kind, which callers need in order to tell failures apart.
+    JsError::new(&format!("UnknownCryptoError: {:?}", err.kind()))
+}
+
+fn aead_keygen_impl() -> Vec<u8> {
+    aead::SecretKey::default().unprotected_as_bytes().to_vec()
+}
+
+fn aead_seal_impl(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    aead::seal(&aead::SecretKey::from_slice(key)?, plaintext)
+}
+
+fn aead_open_impl(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    aead::open(&aead::SecretKey::from_slice(key)?, ciphertext)
+}
+
+fn pwhash_hash_password_impl(
+    password: &[u8],
+    iterations: u32,
+    memory: u32,
+) -> Result<String, UnknownCryptoError> {
+    let password = pwhash::Password::from_slice(password)?;
+    let password_hash = pwhash::hash_password(&password, iterations, memory)?;
+
+    Ok(password_hash.unprotected_as_encoded().to_string())
+}
+
+fn pwhash_verify_impl(password_hash: &str, password: &[u8]) -> Result<bool, UnknownCryptoError> {
+    let expected = pwhash::PasswordHash::from_encoded(password_hash)?;
+    let password = pwhash::Password::from_slice(password)?;
+
+    match pwhash::hash_password_verify(&expected, &password) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::MacMismatch => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+fn hash_digest_impl(data: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    Ok(hash::digest(data)?.as_ref().to_vec())
+}
+
+#[wasm_bindgen(js_name = aeadKeygen)]
+/// Generate a random 32-byte key for `aeadSeal` and `aeadOpen`.
+pub fn aead_keygen() -> Vec<u8> {
+    aead_keygen_impl()
+}
+
+#[wasm_bindgen(js_name = aeadSeal)]
+/// Encrypt and authenticate `plaintext` with XChaCha20Poly1305, see `orion::aead::seal()`.
+pub fn aead_seal(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, JsError> {
+    aead_seal_impl(key, plaintext).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = aeadOpen)]
+/// Authenticate and decrypt `ciphertext`, see `orion::aead::open()`.
+pub fn aead_open(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, JsError> {
+    aead_open_impl(key, ciphertext).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = pwhashHashPassword)]
+/// Hash a password with Argon2i and return the encoded password hash, see
+/// `orion::pwhash::hash_password()`.
+pub fn pwhash_hash_password(
+    password: &[u8],
+    iterations: u32,
+    memory: u32,
+) -> Result<String, JsError> {
+    pwhash_hash_password_impl(password, iterations, memory).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = pwhashVerify)]
+/// Verify a password against an encoded password hash, see
+/// `orion::pwhash::hash_password_verify()`.
+pub fn pwhash_verify(password_hash: &str, password: &[u8]) -> Result<bool, JsError> {
+    pwhash_verify_impl(password_hash, password).map_err(to_js_error)
+}
+
+#[wasm_bindgen(js_name = hashDigest)]
+/// Hash `data` with BLAKE2b-256, see `orion::hash::digest()`.
+pub fn hash_digest(data: &[u8]) -> Result<Vec<u8>, JsError> {
+    hash_digest_impl(data).map_err(to_js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aead_roundtrip() {
+        let key = aead_keygen_impl();
+        assert_eq!(key.len(), 32);
+
+        let mut ciphertext = aead_seal_impl(&key, b"Secret message").unwrap();
+        assert_eq!(
+            aead_open_impl(&key, &ciphertext).unwrap(),
+            b"Secret message"
+        );
+
+        ciphertext[30] ^= 1;
+        assert_eq!(
+            aead_open_impl(&key, &ciphertext).unwrap_err().kind(),
+            ErrorKind::MacMismatch
+        );
+        assert!(aead_seal_impl(&[], b"Secret message").is_err());
+    }
+
+    #[test]
+    fn test_pwhash_roundtrip() {
+        let encoded = pwhash_hash_password_impl(b"Secret password", 3, 1 << 4).unwrap();
+        assert!(pwhash_verify_impl(&encoded, b"Secret password").unwrap());
+        assert!(!pwhash_verify_impl(&encoded, b"Wrong password").unwrap());
+        assert!(pwhash_verify_impl("$argon2i$", b"Secret password").is_err());
+    }
+
+    #[test]
+    fn test_hash_digest() {
+        assert_eq!(
+            hash_digest_impl(b"").unwrap(),
+            hash::digest(b"").unwrap().as_ref()
+        );
+    }
+}
@@ -22,9 +22,91 @@
 
 use core::fmt;
 
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The kind of error that caused an [`UnknownCryptoError`].
+///
+/// New kinds may be added in the future, so matching on this should always
+/// include a wildcard arm.
+pub enum ErrorKind {
+    /// An input or output had an invalid length. For example, a key of the wrong size,
+    /// an empty input where one is not allowed or a destination buffer that is too small.
+    InvalidLength,
+    /// A parameter was outside of its accepted range. For example, too few iterations
+    /// or too little memory for a password hash.
+    InvalidParameter,
+    /// A streaming context was used in a way that its current state does not allow.
+    /// For example, calling `update()` after `finalize()` without a `reset()` in between.
+    InvalidState,
+    /// Data to be decoded or parsed was not correctly formatted.
+    InvalidEncoding,
+    /// Authentication or verification failed. For example, a tag, digest or
+    /// password hash did not match the expected value.
+    MacMismatch,
+    /// A nonce sequence or internal counter has been exhausted.
+    NonceOverflow,
+    /// The random number generator of the system failed to provide random bytes.
+    RngFailure,
+    /// An I/O operation failed while reading input or writing output.
+    Io,
+    /// A token or other time-limited value has expired, or is not valid yet.
+    Expired,
+    /// A password was rejected because it is too easy to guess.
+    WeakPassword,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 /// Opaque error.
-#[derive(Clone, Copy, PartialEq)]
-pub struct UnknownCryptoError;
+///
+/// # About:
+/// The [`Display`] and [`Debug`] implementations of this error are intentionally
+/// opaque, so that logging an error never reveals why an operation failed.
+/// Applications that need to tell different failures apart, can use [`kind()`]
+/// to get the [`ErrorKind`].
+///
+/// # Security:
+/// - While the kind of an error is useful to distinguish a caller bug (e.g. a buffer
+///   of the wrong size) from a failed authentication, it should not be revealed to an
+///   untrusted party in protocols where this could serve as an oracle.
+///
+/// # Example:
+/// ```rust
+/// use orion::aead;
+/// use orion::errors::ErrorKind;
+///
+/// let secret_key = aead::SecretKey::default();
+/// let mut ciphertext = aead::seal(&secret_key, b"Secret message")?;
+/// ciphertext[30] ^= 1;
+///
+/// let err = aead::open(&secret_key, &ciphertext).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::MacMismatch);
+/// assert_eq!(format!("{}", err), "UnknownCryptoError");
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`Display`]: core::fmt::Display
+/// [`Debug`]: core::fmt::Debug
+/// [`kind()`]: UnknownCryptoError::kind
+pub struct UnknownCryptoError {
+    kind: ErrorKind,
+}
+
+impl UnknownCryptoError {
+    /// Create a new error of the given kind.
+    pub const fn new(kind: ErrorKind) -> Self {
+        Self { kind }
+    }
+
+    /// Return the kind of this error.
+    pub const fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<ErrorKind> for UnknownCryptoError {
+    fn from(kind: ErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
 
 impl fmt::Display for UnknownCryptoError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -45,23 +127,55 @@ impl std::error::Error for UnknownCryptoError {
     }
 }
 
-#[cfg(feature = "safe_api")]
+#[cfg(feature = "getrandom")]
 impl From<getrandom::Error> for UnknownCryptoError {
     fn from(_: getrandom::Error) -> Self {
-        UnknownCryptoError
+        UnknownCryptoError::new(ErrorKind::RngFailure)
     }
 }
 
 #[cfg(feature = "safe_api")]
 impl From<ct_codecs::Error> for UnknownCryptoError {
     fn from(_: ct_codecs::Error) -> Self {
-        UnknownCryptoError
+        UnknownCryptoError::new(ErrorKind::InvalidEncoding)
+    }
+}
+
+#[cfg(feature = "safe_api")]
+impl From<UnknownCryptoError> for std::io::Error {
+    /// The resulting [`std::io::Error`] wraps the original error, so that it can later
+    /// be converted back to an [`UnknownCryptoError`] without losing its [`ErrorKind`].
+    fn from(err: UnknownCryptoError) -> Self {
+        let io_kind = match err.kind() {
+            ErrorKind::InvalidLength | ErrorKind::InvalidParameter => {
+                std::io::ErrorKind::InvalidInput
+            }
+            ErrorKind::InvalidEncoding | ErrorKind::MacMismatch => std::io::ErrorKind::InvalidData,
+            _ => std::io::ErrorKind::Other,
+        };
+
+        std::io::Error::new(io_kind, err)
+    }
+}
+
+#[cfg(feature = "safe_api")]
+impl From<std::io::Error> for UnknownCryptoError {
+    /// If the [`std::io::Error`] wraps an [`UnknownCryptoError`], that error is returned.
+    /// Otherwise, an error of kind [`ErrorKind::Io`] is returned.
+    fn from(err: std::io::Error) -> Self {
+        match err
+            .get_ref()
+            .and_then(|inner| inner.downcast_ref::<UnknownCryptoError>())
+        {
+            Some(inner) => *inner,
+            None => UnknownCryptoError::new(ErrorKind::Io),
+        }
     }
 }
 
 impl From<core::num::ParseIntError> for UnknownCryptoError {
     fn from(_: core::num::ParseIntError) -> Self {
-        UnknownCryptoError
+        UnknownCryptoError::new(ErrorKind::InvalidEncoding)
     }
 }
 
@@ -70,10 +184,10 @@ impl From<core::num::ParseIntError> for UnknownCryptoError {
 // format! is only available with std
 fn test_unknown_crypto_error_debug_display() {
     // Tests Debug impl through "{:?}"
-    let err = format!("{:?}", UnknownCryptoError);
+    let err = format!("{:?}", UnknownCryptoError::new(ErrorKind::MacMismatch));
     assert_eq!(err, "UnknownCryptoError");
     // Tests Display impl through "{}"
-    let err = format!("{}", UnknownCryptoError);
+    let err = format!("{}", UnknownCryptoError::new(ErrorKind::MacMismatch));
     assert_eq!(err, "UnknownCryptoError");
 }
 
@@ -98,7 +212,9 @@ fn test_unknown_crypto_from_getrandom() {
 #[cfg(feature = "safe_api")]
 fn test_source() {
     use std::error::Error;
-    assert!(UnknownCryptoError.source().is_none());
+    assert!(UnknownCryptoError::new(ErrorKind::InvalidLength)
+        .source()
+        .is_none());
 }
 
 #[test]
@@ -137,3 +253,68 @@ fn test_unknown_crypto_from_parseint_error() {
     );
     assert_eq!(err, "UnknownCryptoError:UnknownCryptoError");
 }
+
+#[test]
+fn test_kind() {
+    let err = UnknownCryptoError::new(ErrorKind::NonceOverflow);
+    assert_eq!(err.kind(), ErrorKind::NonceOverflow);
+    assert_eq!(err, UnknownCryptoError::from(ErrorKind::NonceOverflow));
+    assert_ne!(err, UnknownCryptoError::new(ErrorKind::InvalidLength));
+}
+
+#[test]
+#[cfg(feature = "safe_api")]
+fn test_kind_from_foreign_errors() {
+    use core::num::NonZeroU32;
+
+    let err_foreign: getrandom::Error = getrandom::Error::from(NonZeroU32::new(12).unwrap());
+    assert_eq!(
+        UnknownCryptoError::from(err_foreign).kind(),
+        ErrorKind::RngFailure
+    );
+    assert_eq!(
+        UnknownCryptoError::from(ct_codecs::Error::InvalidInput).kind(),
+        ErrorKind::InvalidEncoding
+    );
+    assert_eq!(
+        UnknownCryptoError::from("j".parse::<u32>().unwrap_err()).kind(),
+        ErrorKind::InvalidEncoding
+    );
+}
+
+#[test]
+#[cfg(feature = "safe_api")]
+fn test_io_error_interop() {
+    let io_err = std::io::Error::from(UnknownCryptoError::new(ErrorKind::MacMismatch));
+    assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(format!("{}", io_err), "UnknownCryptoError");
+    // The original kind survives a round-trip.
+    assert_eq!(
+        UnknownCryptoError::from(io_err).kind(),
+        ErrorKind::MacMismatch
+    );
+
+    let io_err = std::io::Error::from(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    assert_eq!(io_err.kind(), std::io::ErrorKind::InvalidInput);
+    let io_err = std::io::Error::from(UnknownCryptoError::new(ErrorKind::InvalidState));
+    assert_eq!(io_err.kind(), std::io::ErrorKind::Other);
+
+    let io_err = std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof");
+    assert_eq!(UnknownCryptoError::from(io_err).kind(), ErrorKind::Io);
+}
+
+#[test]
+#[cfg(feature = "safe_api")]
+fn test_io_error_question_mark() {
+    fn io_fn() -> std::io::Result<()> {
+        crate::util::secure_cmp(&[0u8; 2], &[1u8; 2])?;
+        Ok(())
+    }
+
+    fn crypto_fn() -> Result<(), UnknownCryptoError> {
+        io_fn()?;
+        Ok(())
+    }
+
+    assert_eq!(crypto_fn().unwrap_err().kind(), ErrorKind::MacMismatch);
+}
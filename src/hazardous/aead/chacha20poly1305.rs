@@ -30,6 +30,10 @@
 //! - `dst_out`: Destination array that will hold the
 //!   `ciphertext_with_tag`/`plaintext` after encryption/decryption.
 //!
+//! [`seal_vectored()`] and [`open_vectored()`] take `plaintext`/`ciphertext_with_tag` and `ad`
+//! as several slices, such as a header and a body stored in separate buffers, and process them
+//! as if they were concatenated.
+//!
 //! `ad`: "A typical use for these data is to authenticate version numbers,
 //! timestamps or monotonically increasing counters in order to discard previous
 //! messages and prevent replay attacks." See [libsodium docs] for more information.
@@ -49,10 +53,8 @@
 //! - The received tag does not match the calculated tag when  calling [`open()`].
 //! - `plaintext.len()` + [`POLY1305_OUTSIZE`] overflows when  calling [`seal()`].
 //! - Converting `usize` to `u64` would be a lossy conversion.
-//!
-//! # Panics:
-//! A panic will occur if:
-//! - More than `2^32-1 * 64` bytes of data are processed.
+//! - More than `2^32-2 * 64` bytes of data would be encrypted or decrypted. One
+//!   keystream block is used for the Poly1305 key.
 //!
 //! # Security:
 //! - It is critical for security that a given nonce is not re-used with a given
@@ -96,55 +98,24 @@
 //! [`POLY1305_OUTSIZE`]: super::mac::poly1305::POLY1305_OUTSIZE
 //! [`seal()`]: chacha20poly1305::seal
 //! [`open()`]: chacha20poly1305::open
+//! [`seal_vectored()`]: chacha20poly1305::seal_vectored
+//! [`open_vectored()`]: chacha20poly1305::open_vectored
 //! [RFC]: https://tools.ietf.org/html/rfc8439#section-3
 //! [libsodium docs]: https://download.libsodium.org/doc/secret-key_cryptography/aead#additional-data
 
 pub use crate::hazardous::stream::chacha20::{Nonce, SecretKey};
 use crate::{
-    errors::UnknownCryptoError,
+    errors::{ErrorKind, UnknownCryptoError},
     hazardous::{
-        mac::poly1305::{OneTimeKey, Poly1305, POLY1305_KEYSIZE, POLY1305_OUTSIZE},
-        stream::chacha20::{self, ChaCha20, CHACHA_BLOCKSIZE},
+        aead::composition::{
+            self, poly1305_key_gen, process_authentication_vectored, total_len, StreamCipher,
+            ENC_CTR,
+        },
+        mac::poly1305::{Poly1305, POLY1305_OUTSIZE},
+        stream::chacha20::ChaCha20,
     },
     util,
 };
-use core::convert::TryInto;
-use zeroize::Zeroizing;
-
-/// The initial counter used for encryption and decryption.
-const ENC_CTR: u32 = 1;
-
-/// The initial counter used for Poly1305 key generation.
-const AUTH_CTR: u32 = 0;
-
-/// Poly1305 key generation using IETF ChaCha20.
-pub(crate) fn poly1305_key_gen(
-    ctx: &mut ChaCha20,
-    tmp_buffer: &mut Zeroizing<[u8; CHACHA_BLOCKSIZE]>,
-) -> OneTimeKey {
-    ctx.keystream_block(AUTH_CTR, tmp_buffer.as_mut());
-    OneTimeKey::from_slice(&tmp_buffer[..POLY1305_KEYSIZE]).unwrap()
-}
-
-/// Authenticates the ciphertext, ad and their lengths.
-fn process_authentication(
-    auth_ctx: &mut Poly1305,
-    ad: &[u8],
-    ciphertext: &[u8],
-) -> Result<(), UnknownCryptoError> {
-    auth_ctx.process_pad_to_blocksize(ad)?;
-    auth_ctx.process_pad_to_blocksize(ciphertext)?;
-
-    let (ad_len, ct_len): (u64, u64) = match (ad.len().try_into(), ciphertext.len().try_into()) {
-        (Ok(alen), Ok(clen)) => (alen, clen),
-        _ => return Err(UnknownCryptoError),
-    };
-
-    let mut tmp_pad = [0u8; 16];
-    tmp_pad[0..8].copy_from_slice(&ad_len.to_le_bytes());
-    tmp_pad[8..16].copy_from_slice(&ct_len.to_le_bytes());
-    auth_ctx.update(tmp_pad.as_ref())
-}
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// AEAD ChaCha20Poly1305 encryption and authentication as specified in the [RFC 8439](https://tools.ietf.org/html/rfc8439).
@@ -155,28 +126,56 @@ pub fn seal(
     ad: Option<&[u8]>,
     dst_out: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
-    match plaintext.len().checked_add(POLY1305_OUTSIZE) {
+    let enc_ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
+    composition::seal(enc_ctx, plaintext, ad, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// AEAD ChaCha20Poly1305 decryption and authentication as specified in the [RFC 8439](https://tools.ietf.org/html/rfc8439).
+pub fn open(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ciphertext_with_tag: &[u8],
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let dec_ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
+    composition::open(dec_ctx, ciphertext_with_tag, ad, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Same as [`seal()`], but with `plaintext` and `ad` each split into several slices. The
+/// result is the same as calling [`seal()`] with the concatenation of the slices.
+pub fn seal_vectored(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    plaintext: &[&[u8]],
+    ad: &[&[u8]],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let pt_len = total_len(plaintext)?;
+    match pt_len.checked_add(POLY1305_OUTSIZE) {
         Some(out_min_len) => {
             if dst_out.len() < out_min_len {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
             }
         }
-        None => return Err(UnknownCryptoError),
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
     };
 
-    let mut enc_ctx =
-        ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
-    let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+    let mut enc_ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
 
-    let pt_len = plaintext.len();
     if pt_len != 0 {
-        dst_out[..pt_len].copy_from_slice(plaintext);
-        chacha20::xor_keystream(&mut enc_ctx, ENC_CTR, tmp.as_mut(), &mut dst_out[..pt_len])?;
+        let mut pos = 0;
+        for slice in plaintext.iter() {
+            dst_out[pos..pos + slice.len()].copy_from_slice(slice);
+            pos += slice.len();
+        }
+        enc_ctx.xor_keystream(ENC_CTR, &mut dst_out[..pt_len])?;
     }
 
-    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut enc_ctx, &mut tmp));
-    let ad = ad.unwrap_or(&[0u8; 0]);
-    process_authentication(&mut auth_ctx, ad, &dst_out[..pt_len])?;
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut enc_ctx)?);
+    process_authentication_vectored(&mut auth_ctx, ad, &dst_out[..pt_len])?;
     dst_out[pt_len..(pt_len + POLY1305_OUTSIZE)]
         .copy_from_slice(auth_ctx.finalize()?.unprotected_as_bytes());
 
@@ -184,42 +183,56 @@ pub fn seal(
 }
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-/// AEAD ChaCha20Poly1305 decryption and authentication as specified in the [RFC 8439](https://tools.ietf.org/html/rfc8439).
-pub fn open(
+/// Same as [`open()`], but with `ciphertext_with_tag` and `ad` each split into several slices,
+/// at any position. The result is the same as calling [`open()`] with the concatenation of the slices.
+pub fn open_vectored(
     secret_key: &SecretKey,
     nonce: &Nonce,
-    ciphertext_with_tag: &[u8],
-    ad: Option<&[u8]>,
+    ciphertext_with_tag: &[&[u8]],
+    ad: &[&[u8]],
     dst_out: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
-    if ciphertext_with_tag.len() < POLY1305_OUTSIZE {
-        return Err(UnknownCryptoError);
+    let total = total_len(ciphertext_with_tag)?;
+    if total < POLY1305_OUTSIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let ciphertext_len = total - POLY1305_OUTSIZE;
+    if dst_out.len() < ciphertext_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
-    if dst_out.len() < ciphertext_with_tag.len() - POLY1305_OUTSIZE {
-        return Err(UnknownCryptoError);
+
+    // Gather the ciphertext into dst_out, where it is decrypted in-place, and the tag
+    // into a separate buffer.
+    let mut tag = [0u8; POLY1305_OUTSIZE];
+    let mut pos = 0;
+    for slice in ciphertext_with_tag.iter() {
+        let (ct_part, tag_part) = slice.split_at(core::cmp::min(
+            slice.len(),
+            ciphertext_len.saturating_sub(pos),
+        ));
+        if !ct_part.is_empty() {
+            dst_out[pos..pos + ct_part.len()].copy_from_slice(ct_part);
+            pos += ct_part.len();
+        }
+        if !tag_part.is_empty() {
+            let tag_pos = pos - ciphertext_len;
+            tag[tag_pos..tag_pos + tag_part.len()].copy_from_slice(tag_part);
+            pos += tag_part.len();
+        }
     }
 
-    let mut dec_ctx =
-        ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
-    let mut tmp = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
-    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut dec_ctx, &mut tmp));
+    let mut dec_ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut dec_ctx)?);
 
-    let ciphertext_len = ciphertext_with_tag.len() - POLY1305_OUTSIZE;
-    let ad = ad.unwrap_or(&[0u8; 0]);
-    process_authentication(&mut auth_ctx, ad, &ciphertext_with_tag[..ciphertext_len])?;
-    util::secure_cmp(
-        auth_ctx.finalize()?.unprotected_as_bytes(),
-        &ciphertext_with_tag[ciphertext_len..],
-    )?;
+    process_authentication_vectored(&mut auth_ctx, ad, &dst_out[..ciphertext_len])?;
+    if let Err(e) = util::secure_cmp(auth_ctx.finalize()?.unprotected_as_bytes(), &tag) {
+        // Don't leave the unauthenticated ciphertext in dst_out.
+        dst_out[..ciphertext_len].iter_mut().for_each(|b| *b = 0u8);
+        return Err(e);
+    }
 
     if ciphertext_len != 0 {
-        dst_out[..ciphertext_len].copy_from_slice(&ciphertext_with_tag[..ciphertext_len]);
-        chacha20::xor_keystream(
-            &mut dec_ctx,
-            ENC_CTR,
-            tmp.as_mut(),
-            &mut dst_out[..ciphertext_len],
-        )?;
+        dec_ctx.xor_keystream(ENC_CTR, &mut dst_out[..ciphertext_len])?;
     }
 
     Ok(())
@@ -230,6 +243,7 @@ pub fn open(
 #[cfg(feature = "safe_api")]
 mod public {
     use super::*;
+    use crate::hazardous::stream::chacha20;
     use crate::test_framework::aead_interface::{test_diff_params_err, AeadTestRunner};
 
     #[quickcheck]
@@ -250,6 +264,87 @@ mod public {
         test_diff_params_err(&seal, &open, &input, POLY1305_OUTSIZE);
         true
     }
+
+    mod test_vectored {
+        use super::*;
+
+        fn key_and_nonce() -> (SecretKey, Nonce) {
+            (SecretKey::from([7u8; 32]), Nonce::from([1u8; 12]))
+        }
+
+        #[test]
+        fn test_open_vectored_every_split() {
+            let (sk, nonce) = key_and_nonce();
+            let plaintext = [9u8; 40];
+            let mut ciphertext = [0u8; 40 + POLY1305_OUTSIZE];
+            seal(&sk, &nonce, &plaintext, Some(b"ad"), &mut ciphertext).unwrap();
+
+            for split in 0..=ciphertext.len() {
+                let (first, second) = ciphertext.split_at(split);
+                let mut dst_out = [0u8; 40];
+                open_vectored(
+                    &sk,
+                    &nonce,
+                    &[first, &[], second],
+                    &[b"a", b"d"],
+                    &mut dst_out,
+                )
+                .unwrap();
+                assert_eq!(dst_out, plaintext);
+            }
+        }
+
+        #[test]
+        fn test_vectored_errors() {
+            let (sk, nonce) = key_and_nonce();
+            let mut ciphertext = [0u8; 5 + POLY1305_OUTSIZE];
+
+            assert!(
+                seal_vectored(&sk, &nonce, &[b"abc", b"de"], &[], &mut ciphertext[..20]).is_err()
+            );
+            seal_vectored(&sk, &nonce, &[b"abc", b"de"], &[], &mut ciphertext).unwrap();
+
+            let mut dst_out = [0u8; 5];
+            assert!(open_vectored(&sk, &nonce, &[&ciphertext[..15]], &[], &mut dst_out).is_err());
+            assert!(open_vectored(&sk, &nonce, &[&ciphertext], &[], &mut dst_out[..4]).is_err());
+            assert!(open_vectored(&sk, &nonce, &[&ciphertext], &[b"ad"], &mut dst_out).is_err());
+            // The unauthenticated ciphertext is not left in dst_out.
+            assert_eq!(dst_out, [0u8; 5]);
+
+            ciphertext[20] ^= 1;
+            assert!(open_vectored(&sk, &nonce, &[&ciphertext], &[], &mut dst_out).is_err());
+            ciphertext[20] ^= 1;
+            open_vectored(&sk, &nonce, &[&ciphertext], &[], &mut dst_out).unwrap();
+            assert_eq!(&dst_out, b"abcde");
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        /// Vectored sealing and opening should give the same result as for the
+        /// concatenated input, regardless of where it is split.
+        fn prop_same_as_contiguous(input: Vec<u8>, ad: Vec<u8>, split: usize) -> bool {
+            let (sk, nonce) = key_and_nonce();
+            let pt_split = if input.is_empty() {
+                0
+            } else {
+                split % input.len()
+            };
+            let ad_split = if ad.is_empty() { 0 } else { split % ad.len() };
+            let (pt1, pt2) = input.split_at(pt_split);
+            let (ad1, ad2) = ad.split_at(ad_split);
+
+            let mut expected = vec![0u8; input.len() + POLY1305_OUTSIZE];
+            seal(&sk, &nonce, &input, Some(&ad), &mut expected).unwrap();
+            let mut actual = vec![0u8; input.len() + POLY1305_OUTSIZE];
+            seal_vectored(&sk, &nonce, &[pt1, pt2], &[ad1, ad2], &mut actual).unwrap();
+
+            let (ct1, ct2) = actual.split_at(split % actual.len());
+            let mut decrypted = vec![0u8; input.len()];
+            open_vectored(&sk, &nonce, &[ct1, ct2], &[ad1, ad2], &mut decrypted).unwrap();
+
+            actual == expected && decrypted == input
+        }
+    }
 }
 
 // Testing any test vectors that aren't put into library's /tests folder.
@@ -272,10 +367,11 @@ mod test_vectors {
 
         let mut chacha20_ctx =
             ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
-        let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
-            poly1305_key_gen(&mut chacha20_ctx, &mut tmp_block).unprotected_as_bytes(),
+            poly1305_key_gen(&mut chacha20_ctx)
+                .unwrap()
+                .unprotected_as_bytes(),
             expected.as_ref()
         );
     }
@@ -300,10 +396,11 @@ mod test_vectors {
 
         let mut chacha20_ctx =
             ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
-        let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
-            poly1305_key_gen(&mut chacha20_ctx, &mut tmp_block).unprotected_as_bytes(),
+            poly1305_key_gen(&mut chacha20_ctx)
+                .unwrap()
+                .unprotected_as_bytes(),
             expected.as_ref()
         );
     }
@@ -328,10 +425,11 @@ mod test_vectors {
 
         let mut chacha20_ctx =
             ChaCha20::new(key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap();
-        let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
 
         assert_eq!(
-            poly1305_key_gen(&mut chacha20_ctx, &mut tmp_block).unprotected_as_bytes(),
+            poly1305_key_gen(&mut chacha20_ctx)
+                .unwrap()
+                .unprotected_as_bytes(),
             expected.as_ref()
         );
     }
@@ -0,0 +1,192 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! ChaCha20Poly1305 and XChaCha20Poly1305 are not key-committing: it is possible
+//! to construct a single ciphertext that decrypts successfully under more than one
+//! key. When an application tries several keys, for example keys derived from
+//! password guesses, this can be turned into a partitioning oracle.
+//!
+//! A commitment binds a ciphertext to exactly one key and nonce. It is computed as
+//! keyed BLAKE2b-256, using the AEAD secret key as the BLAKE2b key, over the
+//! domain separation string `"orion-commit"` followed by the nonce. The commitment
+//! is stored alongside the ciphertext and verified before the ciphertext is opened.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key used with ChaCha20Poly1305 or XChaCha20Poly1305.
+//! - `nonce`: The nonce used with the secret key, as bytes.
+//! - `expected`: The commitment that was stored alongside the ciphertext.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `nonce` is empty.
+//! - The calculated commitment does not match the expected when verifying.
+//!
+//! # Security:
+//! - The commitment does not reveal the secret key, but it can be used to check
+//!   guesses of the secret key. It must only be used with secret keys that have
+//!   been generated using a CSPRNG or derived with a password hash.
+//! - Always call [`verify()`] before decrypting, and never decrypt if it fails.
+//!   Verifying after decryption still rejects the message, but does not hide
+//!   which keys a ciphertext could be decrypted with.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::aead::{commitment, xchacha20poly1305};
+//! use xchacha20poly1305::{Nonce, SecretKey};
+//!
+//! let secret_key = SecretKey::generate();
+//! let nonce = Nonce::generate();
+//!
+//! let mut ciphertext = [0u8; 15 + 16];
+//! xchacha20poly1305::seal(&secret_key, &nonce, b"Data to protect", None, &mut ciphertext)?;
+//! let commitment = commitment::commit(&secret_key, nonce.as_ref())?;
+//!
+//! // Store `commitment` next to `ciphertext`. Before opening, verify it.
+//! commitment::verify(&commitment, &secret_key, nonce.as_ref())?;
+//! let mut plaintext = [0u8; 15];
+//! xchacha20poly1305::open(&secret_key, &nonce, &ciphertext, None, &mut plaintext)?;
+//!
+//! assert!(commitment::verify(&commitment, &SecretKey::generate(), nonce.as_ref()).is_err());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`verify()`]: commitment::verify
+
+pub use crate::hazardous::stream::chacha20::SecretKey;
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::hash::blake2b::{self, Blake2b},
+};
+
+/// The size of a commitment.
+pub const COMMITMENT_SIZE: usize = 32;
+/// The domain separation string that is hashed before the nonce.
+const COMMITMENT_DOMAIN: &[u8] = b"orion-commit";
+
+construct_tag! {
+    /// A type to represent the `Commitment` that [`commit()`] returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (Commitment, test_commitment, COMMITMENT_SIZE, COMMITMENT_SIZE)
+}
+
+impl_from_trait!(Commitment, COMMITMENT_SIZE);
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compute the commitment to `secret_key` and `nonce`.
+pub fn commit(secret_key: &SecretKey, nonce: &[u8]) -> Result<Commitment, UnknownCryptoError> {
+    if nonce.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
+    let mut ctx = Blake2b::new(Some(&key), COMMITMENT_SIZE)?;
+    ctx.update(COMMITMENT_DOMAIN)?;
+    ctx.update(nonce)?;
+
+    Commitment::from_slice(ctx.finalize()?.as_ref())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify in constant time that `expected` is the commitment to `secret_key` and `nonce`.
+pub fn verify(
+    expected: &Commitment,
+    secret_key: &SecretKey,
+    nonce: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    if &commit(secret_key, nonce)? == expected {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::stream::{chacha20::IETF_CHACHA_NONCESIZE, xchacha20::XCHACHA_NONCESIZE};
+
+    #[test]
+    fn test_commit_kat() {
+        let mut key = [0u8; 32];
+        let mut nonce = [0u8; XCHACHA_NONCESIZE];
+        for (i, b) in key.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        for (i, b) in nonce.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+
+        let expected = Commitment::from([
+            0x49, 0xc5, 0xb5, 0x7a, 0x92, 0x43, 0xbf, 0xe9, 0x21, 0xc0, 0xa0, 0x68, 0x90, 0xad,
+            0x49, 0x26, 0xc5, 0x35, 0xfd, 0xe0, 0xc9, 0x9a, 0xad, 0x4c, 0x99, 0xfc, 0x4b, 0xc5,
+            0x4f, 0xf6, 0x35, 0x4f,
+        ]);
+        assert_eq!(commit(&SecretKey::from(key), &nonce).unwrap(), expected);
+
+        let expected = Commitment::from([
+            0x28, 0xc8, 0x34, 0x95, 0x85, 0x71, 0xad, 0x4e, 0xa1, 0x32, 0xb2, 0x38, 0xc4, 0x89,
+            0x7c, 0x7f, 0x2c, 0x55, 0x5f, 0x2e, 0x2a, 0xac, 0xfd, 0x91, 0x66, 0x75, 0x73, 0x22,
+            0xea, 0x0b, 0x77, 0x68,
+        ]);
+        assert_eq!(
+            commit(&SecretKey::from([0u8; 32]), &[0u8; IETF_CHACHA_NONCESIZE]).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_empty_nonce_err() {
+        let sk = SecretKey::from([0u8; 32]);
+        assert!(commit(&sk, &[0u8; 0]).is_err());
+        let c = commit(&sk, &[0u8; 1]).unwrap();
+        assert!(verify(&c, &sk, &[0u8; 0]).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let sk = SecretKey::from([1u8; 32]);
+        let nonce = [2u8; IETF_CHACHA_NONCESIZE];
+        let c = commit(&sk, &nonce).unwrap();
+
+        assert!(verify(&c, &sk, &nonce).is_ok());
+        assert!(verify(&c, &SecretKey::from([3u8; 32]), &nonce).is_err());
+        assert!(verify(&c, &sk, &[3u8; IETF_CHACHA_NONCESIZE]).is_err());
+        assert!(verify(&c, &sk, &[2u8; XCHACHA_NONCESIZE]).is_err());
+        assert!(verify(&Commitment::from([0u8; COMMITMENT_SIZE]), &sk, &nonce).is_err());
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_diff_key_diff_commitment(nonce: Vec<u8>) -> bool {
+        if nonce.is_empty() {
+            return commit(&SecretKey::generate(), &nonce).is_err();
+        }
+
+        let sk = SecretKey::generate();
+        let c = commit(&sk, &nonce).unwrap();
+        verify(&c, &sk, &nonce).is_ok() && verify(&c, &SecretKey::generate(), &nonce).is_err()
+    }
+}
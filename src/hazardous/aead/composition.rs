@@ -0,0 +1,390 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! Composes any stream cipher implementing [`StreamCipher`] with Poly1305, using the
+//! additional data and length framing of [RFC 8439]. The Poly1305 one-time key is
+//! the first 32 bytes of the keystream at block counter 0 and encryption starts at
+//! block counter 1, exactly like [`chacha20poly1305`].
+//!
+//! This can be used to build variants of ChaCha20Poly1305, such as ChaCha12-Poly1305,
+//! without re-implementing the authentication part of the construction.
+//!
+//! # Parameters:
+//! - `cipher`: The stream cipher, set up with a key and nonce.
+//! - `ad`: Additional data to authenticate (this is not encrypted and can be [`None`]).
+//! - `ciphertext_with_tag`: The encrypted data with the corresponding 16 byte
+//!   Poly1305 tag appended to it.
+//! - `plaintext`: The data to be encrypted.
+//! - `dst_out`: Destination array that will hold the
+//!   `ciphertext_with_tag`/`plaintext` after encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` + [`POLY1305_OUTSIZE`] when calling [`seal()`].
+//! - The length of `dst_out` is less than `ciphertext_with_tag` - [`POLY1305_OUTSIZE`] when
+//!   calling [`open()`].
+//! - The length of the `ciphertext_with_tag` is not at least [`POLY1305_OUTSIZE`].
+//! - The received tag does not match the calculated tag when calling [`open()`].
+//! - `plaintext.len()` + [`POLY1305_OUTSIZE`] overflows when calling [`seal()`].
+//! - Converting `usize` to `u64` would be a lossy conversion.
+//! - [`StreamCipher::xor_keystream()`] returns an error.
+//!
+//! # Security:
+//! - [`seal()`] and [`open()`] take `cipher` by value, so that one cipher instance
+//!   cannot be used for more than one message. It is critical for security that a
+//!   given nonce is not re-used with a given key.
+//! - The security of the construction depends entirely on the [`StreamCipher`]
+//!   implementation. Its keystream must never repeat for different block counters
+//!   and must be indistinguishable from random for anyone not knowing the key.
+//! - The length of the `plaintext` is not hidden, only its contents.
+//!
+//! # Example:
+//! ```rust
+//! use orion::errors::UnknownCryptoError;
+//! use orion::hazardous::aead::{chacha20poly1305, composition};
+//! use orion::hazardous::stream::chacha20::{self, Nonce, SecretKey};
+//!
+//! struct IetfChaCha20<'a> {
+//!     secret_key: &'a SecretKey,
+//!     nonce: &'a Nonce,
+//! }
+//!
+//! impl composition::StreamCipher for IetfChaCha20<'_> {
+//!     fn xor_keystream(
+//!         &mut self,
+//!         initial_counter: u32,
+//!         bytes: &mut [u8],
+//!     ) -> Result<(), UnknownCryptoError> {
+//!         let input = bytes.to_vec();
+//!         chacha20::encrypt(self.secret_key, self.nonce, initial_counter, &input, bytes)
+//!     }
+//! }
+//!
+//! let secret_key = SecretKey::generate();
+//! let nonce = Nonce::from([0u8; 12]);
+//! let cipher = IetfChaCha20 { secret_key: &secret_key, nonce: &nonce };
+//!
+//! let mut ciphertext = [0u8; 15 + 16];
+//! composition::seal(cipher, b"Data to protect", Some(b"ad"), &mut ciphertext)?;
+//!
+//! let mut expected = [0u8; 15 + 16];
+//! chacha20poly1305::seal(&secret_key, &nonce, b"Data to protect", Some(b"ad"), &mut expected)?;
+//! assert_eq!(ciphertext, expected);
+//!
+//! let cipher = IetfChaCha20 { secret_key: &secret_key, nonce: &nonce };
+//! let mut plaintext = [0u8; 15];
+//! composition::open(cipher, &ciphertext, Some(b"ad"), &mut plaintext)?;
+//! assert_eq!(&plaintext, b"Data to protect");
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [RFC 8439]: https://tools.ietf.org/html/rfc8439#section-2.8
+//! [`chacha20poly1305`]: super::chacha20poly1305
+//! [`POLY1305_OUTSIZE`]: super::mac::poly1305::POLY1305_OUTSIZE
+//! [`seal()`]: composition::seal
+//! [`open()`]: composition::open
+
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::{
+        mac::poly1305::{OneTimeKey, Poly1305, POLY1305_KEYSIZE, POLY1305_OUTSIZE},
+        stream::chacha20::{self, ChaCha20, KEYSTREAM_BUFSIZE},
+    },
+    util,
+};
+use core::convert::TryInto;
+use zeroize::Zeroizing;
+
+/// The initial counter used for encryption and decryption.
+pub(crate) const ENC_CTR: u32 = 1;
+
+/// The initial counter used for Poly1305 key generation.
+const AUTH_CTR: u32 = 0;
+
+/// A stream cipher, set up with a key and nonce, that can be composed with Poly1305.
+pub trait StreamCipher {
+    /// XOR the keystream into `bytes`, starting at the block given by `initial_counter`.
+    /// `bytes` is never empty. An error must be returned if the block counter would
+    /// overflow.
+    fn xor_keystream(
+        &mut self,
+        initial_counter: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), UnknownCryptoError>;
+}
+
+impl StreamCipher for ChaCha20 {
+    fn xor_keystream(
+        &mut self,
+        initial_counter: u32,
+        bytes: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut tmp = Zeroizing::new([0u8; KEYSTREAM_BUFSIZE]);
+        chacha20::xor_keystream(self, initial_counter, tmp.as_mut(), bytes)
+    }
+}
+
+/// Poly1305 key generation from the first bytes of the keystream.
+pub(crate) fn poly1305_key_gen<C: StreamCipher>(
+    cipher: &mut C,
+) -> Result<OneTimeKey, UnknownCryptoError> {
+    let mut key = Zeroizing::new([0u8; POLY1305_KEYSIZE]);
+    cipher.xor_keystream(AUTH_CTR, key.as_mut())?;
+    OneTimeKey::from_slice(key.as_ref())
+}
+
+/// Authenticates the lengths of the additional data and ciphertext.
+fn process_lengths(
+    auth_ctx: &mut Poly1305,
+    ad_len: usize,
+    ct_len: usize,
+) -> Result<(), UnknownCryptoError> {
+    let (ad_len, ct_len): (u64, u64) = match (ad_len.try_into(), ct_len.try_into()) {
+        (Ok(alen), Ok(clen)) => (alen, clen),
+        _ => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let mut tmp_pad = [0u8; 16];
+    tmp_pad[0..8].copy_from_slice(&ad_len.to_le_bytes());
+    tmp_pad[8..16].copy_from_slice(&ct_len.to_le_bytes());
+    auth_ctx.update(tmp_pad.as_ref())
+}
+
+/// Authenticates the ciphertext, ad and their lengths.
+pub(crate) fn process_authentication(
+    auth_ctx: &mut Poly1305,
+    ad: &[u8],
+    ciphertext: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    auth_ctx.process_pad_to_blocksize(ad)?;
+    auth_ctx.process_pad_to_blocksize(ciphertext)?;
+    process_lengths(auth_ctx, ad.len(), ciphertext.len())
+}
+
+/// Sum of the lengths of `slices`, or an error if it overflows.
+pub(crate) fn total_len(slices: &[&[u8]]) -> Result<usize, UnknownCryptoError> {
+    slices.iter().try_fold(0usize, |acc, slice| {
+        acc.checked_add(slice.len())
+            .ok_or_else(|| UnknownCryptoError::new(ErrorKind::InvalidLength))
+    })
+}
+
+/// Authenticates the ciphertext, ad split into several slices and their lengths.
+pub(crate) fn process_authentication_vectored(
+    auth_ctx: &mut Poly1305,
+    ad: &[&[u8]],
+    ciphertext: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    let ad_len = total_len(ad)?;
+    for slice in ad.iter() {
+        auth_ctx.update(slice)?;
+    }
+    let ad_pad = (16 - (ad_len % 16)) % 16;
+    auth_ctx.update(&[0u8; 16][..ad_pad])?;
+    auth_ctx.process_pad_to_blocksize(ciphertext)?;
+    process_lengths(auth_ctx, ad_len, ciphertext.len())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt and authenticate `plaintext` with `cipher` and Poly1305.
+pub fn seal<C: StreamCipher>(
+    mut cipher: C,
+    plaintext: &[u8],
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    match plaintext.len().checked_add(POLY1305_OUTSIZE) {
+        Some(out_min_len) => {
+            if dst_out.len() < out_min_len {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+        }
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let pt_len = plaintext.len();
+    if pt_len != 0 {
+        dst_out[..pt_len].copy_from_slice(plaintext);
+        cipher.xor_keystream(ENC_CTR, &mut dst_out[..pt_len])?;
+    }
+
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut cipher)?);
+    let ad = ad.unwrap_or(&[0u8; 0]);
+    process_authentication(&mut auth_ctx, ad, &dst_out[..pt_len])?;
+    dst_out[pt_len..(pt_len + POLY1305_OUTSIZE)]
+        .copy_from_slice(auth_ctx.finalize()?.unprotected_as_bytes());
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate and decrypt `ciphertext_with_tag` with `cipher` and Poly1305.
+pub fn open<C: StreamCipher>(
+    mut cipher: C,
+    ciphertext_with_tag: &[u8],
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if ciphertext_with_tag.len() < POLY1305_OUTSIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if dst_out.len() < ciphertext_with_tag.len() - POLY1305_OUTSIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut auth_ctx = Poly1305::new(&poly1305_key_gen(&mut cipher)?);
+
+    let ciphertext_len = ciphertext_with_tag.len() - POLY1305_OUTSIZE;
+    let ad = ad.unwrap_or(&[0u8; 0]);
+    process_authentication(&mut auth_ctx, ad, &ciphertext_with_tag[..ciphertext_len])?;
+    util::secure_cmp(
+        auth_ctx.finalize()?.unprotected_as_bytes(),
+        &ciphertext_with_tag[ciphertext_len..],
+    )?;
+
+    if ciphertext_len != 0 {
+        dst_out[..ciphertext_len].copy_from_slice(&ciphertext_with_tag[..ciphertext_len]);
+        cipher.xor_keystream(ENC_CTR, &mut dst_out[..ciphertext_len])?;
+    }
+
+    Ok(())
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+mod public {
+    use super::*;
+    use crate::hazardous::aead::chacha20poly1305;
+    use crate::hazardous::stream::chacha20::{Nonce, SecretKey};
+
+    /// Keystream where each byte is the block counter.
+    struct CounterCipher;
+
+    impl StreamCipher for CounterCipher {
+        fn xor_keystream(
+            &mut self,
+            initial_counter: u32,
+            bytes: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            assert!(!bytes.is_empty());
+            bytes
+                .iter_mut()
+                .for_each(|b| *b ^= initial_counter as u8 + 0x55);
+            Ok(())
+        }
+    }
+
+    /// Cipher that always fails.
+    struct FailingCipher;
+
+    impl StreamCipher for FailingCipher {
+        fn xor_keystream(&mut self, _: u32, _: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            Err(UnknownCryptoError::new(ErrorKind::InvalidState))
+        }
+    }
+
+    fn chacha20(secret_key: &SecretKey, nonce: &Nonce) -> ChaCha20 {
+        ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap()
+    }
+
+    #[test]
+    fn test_custom_cipher() {
+        let mut dst_out = [0u8; 5 + POLY1305_OUTSIZE];
+        seal(CounterCipher, b"hello", None, &mut dst_out).unwrap();
+        assert_eq!(
+            &dst_out[..5],
+            &[
+                b'h' ^ 0x56,
+                b'e' ^ 0x56,
+                b'l' ^ 0x56,
+                b'l' ^ 0x56,
+                b'o' ^ 0x56
+            ]
+        );
+
+        let mut plaintext = [0u8; 5];
+        open(CounterCipher, &dst_out, None, &mut plaintext).unwrap();
+        assert_eq!(&plaintext, b"hello");
+        assert!(open(CounterCipher, &dst_out, Some(b"ad"), &mut plaintext).is_err());
+    }
+
+    #[test]
+    fn test_cipher_error() {
+        let mut dst_out = [0u8; 5 + POLY1305_OUTSIZE];
+        assert!(seal(FailingCipher, b"hello", None, &mut dst_out).is_err());
+        assert!(open(FailingCipher, &dst_out, None, &mut [0u8; 5]).is_err());
+    }
+
+    #[test]
+    fn test_length_errors() {
+        let secret_key = SecretKey::generate();
+        let nonce = Nonce::from([0u8; 12]);
+        let mut dst_out = [0u8; 5 + POLY1305_OUTSIZE];
+
+        assert!(seal(
+            chacha20(&secret_key, &nonce),
+            b"hello",
+            None,
+            &mut dst_out[..20]
+        )
+        .is_err());
+        seal(chacha20(&secret_key, &nonce), b"hello", None, &mut dst_out).unwrap();
+        assert!(open(
+            chacha20(&secret_key, &nonce),
+            &dst_out[..15],
+            None,
+            &mut [0u8; 5]
+        )
+        .is_err());
+        assert!(open(chacha20(&secret_key, &nonce), &dst_out, None, &mut [0u8; 4]).is_err());
+    }
+
+    #[quickcheck]
+    /// Composing ChaCha20 with Poly1305 should be the same as ChaCha20Poly1305.
+    fn prop_same_as_chacha20poly1305(input: Vec<u8>, ad: Vec<u8>) -> bool {
+        let secret_key = SecretKey::generate();
+        let nonce = Nonce::from([1u8; 12]);
+
+        let mut expected = vec![0u8; input.len() + POLY1305_OUTSIZE];
+        chacha20poly1305::seal(&secret_key, &nonce, &input, Some(&ad), &mut expected).unwrap();
+        let mut actual = vec![0u8; input.len() + POLY1305_OUTSIZE];
+        seal(
+            chacha20(&secret_key, &nonce),
+            &input,
+            Some(&ad),
+            &mut actual,
+        )
+        .unwrap();
+
+        let mut decrypted = vec![0u8; input.len()];
+        open(
+            chacha20(&secret_key, &nonce),
+            &actual,
+            Some(&ad),
+            &mut decrypted,
+        )
+        .unwrap();
+
+        actual == expected && decrypted == input
+    }
+}
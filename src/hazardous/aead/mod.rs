@@ -28,3 +28,12 @@ pub mod xchacha20poly1305;
 
 /// Streaming AEAD based on XChaCha20Poly1305.
 pub mod streaming;
+
+/// Nonce-sequence management for ChaCha20Poly1305 sessions.
+pub mod nonce_sequence;
+
+/// Generic composition of a stream cipher and Poly1305, as in RFC 8439.
+pub mod composition;
+
+/// Key commitment for ChaCha20Poly1305 and XChaCha20Poly1305 ciphertexts.
+pub mod commitment;
@@ -0,0 +1,395 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! [`NonceSequence`] yields strictly increasing 96-bit nonces for use with
+//! [`chacha20poly1305`]. The nonce is the big-endian encoding of an internal counter,
+//! which starts at zero unless another starting nonce is given.
+//!
+//! [`SealingKey`] and [`OpeningKey`] bind a secret key to a [`NonceSequence`], so that
+//! each call to [`SealingKey::seal()`] and [`OpeningKey::open()`] automatically uses
+//! the next nonce in the sequence. An [`OpeningKey`] only advances its sequence when
+//! a message has been successfully authenticated.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `nonce_sequence`: The sequence of nonces to use with `secret_key`.
+//! - `ad`: Additional data to authenticate (this is not encrypted and can be [`None`]).
+//! - `plaintext`: The data to be encrypted.
+//! - `ciphertext_with_tag`: The encrypted data with the corresponding 16 byte
+//!   Poly1305 tag appended to it.
+//! - `dst_out`: Destination array that will hold the
+//!   `ciphertext_with_tag`/`plaintext` after encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - All 2^96 nonces of the [`NonceSequence`] have been used.
+//! - Any of the error conditions of [`chacha20poly1305::seal()`] and [`chacha20poly1305::open()`]
+//!   are met, such as more than `2^32-2 * 64` bytes of data in a single message.
+//!
+//! # Security:
+//! - A [`NonceSequence`] only prevents nonce reuse if it is the only source of nonces
+//!   for a given key. Never create two [`SealingKey`]s from the same key with sequences
+//!   that may overlap.
+//! - The sealing and opening side must use the same starting nonce, and messages must be
+//!   opened in the order they were sealed.
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::aead::nonce_sequence::*;
+//!
+//! let secret_key = SecretKey::generate();
+//! let mut sealing_key = SealingKey::new(&secret_key, NonceSequence::new());
+//! let mut opening_key = OpeningKey::new(&secret_key, NonceSequence::new());
+//!
+//! let message = "Data to protect".as_bytes();
+//! let mut dst_out_ct = [0u8; 15 + 16];
+//! let mut dst_out_pt = [0u8; 15];
+//!
+//! let nonce = sealing_key.seal(message, None, &mut dst_out_ct)?;
+//! assert_eq!(nonce, Nonce::from([0u8; 12]));
+//!
+//! opening_key.open(&dst_out_ct, None, &mut dst_out_pt)?;
+//! assert_eq!(dst_out_pt.as_ref(), message);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`chacha20poly1305`]: super::chacha20poly1305
+//! [`chacha20poly1305::seal()`]: super::chacha20poly1305::seal
+//! [`chacha20poly1305::open()`]: super::chacha20poly1305::open
+//! [`SecretKey::generate()`]: crate::hazardous::stream::chacha20::SecretKey::generate
+
+pub use crate::hazardous::stream::chacha20::{Nonce, SecretKey};
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::{
+        aead::chacha20poly1305,
+        stream::chacha20::{CHACHA_KEYSIZE, IETF_CHACHA_NONCESIZE},
+    },
+};
+use zeroize::Zeroizing;
+
+/// Copy a fixed-size `SecretKey`, which unlike `SecretKey::from_slice()` cannot fail.
+fn copy_secret_key(secret_key: &SecretKey) -> SecretKey {
+    let mut bytes = Zeroizing::new([0u8; CHACHA_KEYSIZE]);
+    bytes.copy_from_slice(secret_key.unprotected_as_bytes());
+
+    SecretKey::from(*bytes)
+}
+
+/// The amount of distinct nonces in a sequence.
+const MAX_NONCES: u128 = 1 << (IETF_CHACHA_NONCESIZE * 8);
+
+#[derive(Debug, PartialEq)]
+/// A sequence of strictly increasing 96-bit nonces.
+pub struct NonceSequence {
+    counter: u128,
+}
+
+impl Default for NonceSequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NonceSequence {
+    /// Initialize a `NonceSequence` starting at the all-zero nonce.
+    pub fn new() -> Self {
+        Self { counter: 0 }
+    }
+
+    /// Initialize a `NonceSequence` where `nonce` is the first nonce returned.
+    pub fn from_nonce(nonce: &Nonce) -> Self {
+        let mut counter = [0u8; 16];
+        counter[16 - IETF_CHACHA_NONCESIZE..].copy_from_slice(nonce.as_ref());
+
+        Self {
+            counter: u128::from_be_bytes(counter),
+        }
+    }
+
+    /// Return the nonce that the next call to `advance()` will return, without
+    /// advancing the sequence.
+    pub fn peek(&self) -> Result<Nonce, UnknownCryptoError> {
+        if self.counter >= MAX_NONCES {
+            return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow));
+        }
+
+        let mut nonce = [0u8; IETF_CHACHA_NONCESIZE];
+        nonce.copy_from_slice(&self.counter.to_be_bytes()[16 - IETF_CHACHA_NONCESIZE..]);
+
+        Ok(Nonce::from(nonce))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return the next nonce in the sequence and advance it.
+    pub fn advance(&mut self) -> Result<Nonce, UnknownCryptoError> {
+        let nonce = self.peek()?;
+        // This cannot overflow, since `peek()` fails once the counter reaches MAX_NONCES.
+        self.counter += 1;
+
+        Ok(nonce)
+    }
+}
+
+/// A ChaCha20Poly1305 secret key bound to a [`NonceSequence`], used for sealing.
+pub struct SealingKey {
+    secret_key: SecretKey,
+    nonce_sequence: NonceSequence,
+}
+
+impl core::fmt::Debug for SealingKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "SealingKey {{ secret_key: [***OMITTED***], nonce_sequence: {:?} }}",
+            self.nonce_sequence
+        )
+    }
+}
+
+impl SealingKey {
+    /// Initialize a `SealingKey` with a given secret key and nonce sequence.
+    pub fn new(secret_key: &SecretKey, nonce_sequence: NonceSequence) -> Self {
+        Self {
+            secret_key: copy_secret_key(secret_key),
+            nonce_sequence,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt and authenticate `plaintext` using the next nonce in the sequence. Returns
+    /// the nonce that was used.
+    pub fn seal(
+        &mut self,
+        plaintext: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<Nonce, UnknownCryptoError> {
+        // The nonce is consumed even if sealing fails, so it can never be reused.
+        let nonce = self.nonce_sequence.advance()?;
+        chacha20poly1305::seal(&self.secret_key, &nonce, plaintext, ad, dst_out)?;
+
+        Ok(nonce)
+    }
+}
+
+/// A ChaCha20Poly1305 secret key bound to a [`NonceSequence`], used for opening.
+pub struct OpeningKey {
+    secret_key: SecretKey,
+    nonce_sequence: NonceSequence,
+}
+
+impl core::fmt::Debug for OpeningKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "OpeningKey {{ secret_key: [***OMITTED***], nonce_sequence: {:?} }}",
+            self.nonce_sequence
+        )
+    }
+}
+
+impl OpeningKey {
+    /// Initialize an `OpeningKey` with a given secret key and nonce sequence.
+    pub fn new(secret_key: &SecretKey, nonce_sequence: NonceSequence) -> Self {
+        Self {
+            secret_key: copy_secret_key(secret_key),
+            nonce_sequence,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Authenticate and decrypt `ciphertext_with_tag` using the next nonce in the sequence.
+    /// Returns the nonce that was used.
+    pub fn open(
+        &mut self,
+        ciphertext_with_tag: &[u8],
+        ad: Option<&[u8]>,
+        dst_out: &mut [u8],
+    ) -> Result<Nonce, UnknownCryptoError> {
+        // Only advance on success, so that forged messages cannot desynchronize the sequence.
+        let nonce = self.nonce_sequence.peek()?;
+        chacha20poly1305::open(&self.secret_key, &nonce, ciphertext_with_tag, ad, dst_out)?;
+        self.nonce_sequence.advance()
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    mod test_nonce_sequence {
+        use super::*;
+
+        #[test]
+        fn test_default_equals_new() {
+            assert_eq!(NonceSequence::default(), NonceSequence::new());
+        }
+
+        #[test]
+        fn test_sequence_increases() {
+            let mut sequence = NonceSequence::new();
+            assert_eq!(sequence.advance().unwrap(), Nonce::from([0u8; 12]));
+            assert_eq!(
+                sequence.advance().unwrap(),
+                Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1])
+            );
+
+            let mut sequence =
+                NonceSequence::from_nonce(&Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255]));
+            assert_eq!(
+                sequence.advance().unwrap(),
+                Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255])
+            );
+            assert_eq!(
+                sequence.advance().unwrap(),
+                Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0])
+            );
+        }
+
+        #[test]
+        fn test_peek_does_not_advance() {
+            let mut sequence = NonceSequence::new();
+            assert_eq!(sequence.peek().unwrap(), sequence.peek().unwrap());
+            assert_eq!(sequence.peek().unwrap(), sequence.advance().unwrap());
+            assert_ne!(sequence.peek().unwrap(), Nonce::from([0u8; 12]));
+        }
+
+        #[test]
+        fn test_sequence_exhausted_err() {
+            let mut sequence = NonceSequence::from_nonce(&Nonce::from([255u8; 12]));
+            assert_eq!(sequence.advance().unwrap(), Nonce::from([255u8; 12]));
+            assert!(sequence.peek().is_err());
+            assert_eq!(
+                sequence.advance().unwrap_err().kind(),
+                ErrorKind::NonceOverflow
+            );
+            // The sequence stays exhausted.
+            assert!(sequence.advance().is_err());
+        }
+    }
+
+    mod test_sealing_opening_key {
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_debug_impl() {
+            let secret_key = SecretKey::from_slice(&[0u8; 32]).unwrap();
+            let sealing_key = SealingKey::new(&secret_key, NonceSequence::new());
+            let opening_key = OpeningKey::new(&secret_key, NonceSequence::new());
+
+            assert_eq!(
+                format!("{:?}", sealing_key),
+                "SealingKey { secret_key: [***OMITTED***], nonce_sequence: NonceSequence { counter: 0 } }"
+            );
+            assert_eq!(
+                format!("{:?}", opening_key),
+                "OpeningKey { secret_key: [***OMITTED***], nonce_sequence: NonceSequence { counter: 0 } }"
+            );
+        }
+
+        #[test]
+        fn test_seal_matches_chacha20poly1305() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let mut sealing_key = SealingKey::new(&secret_key, NonceSequence::new());
+            let plaintext = b"Secret message";
+
+            let mut expected = [0u8; 14 + 16];
+            let mut actual = [0u8; 14 + 16];
+            for counter in 0..3u8 {
+                let nonce = Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, counter]);
+                chacha20poly1305::seal(&secret_key, &nonce, plaintext, None, &mut expected)
+                    .unwrap();
+                assert_eq!(
+                    sealing_key.seal(plaintext, None, &mut actual).unwrap(),
+                    nonce
+                );
+                assert_eq!(expected, actual);
+            }
+        }
+
+        #[test]
+        fn test_seal_open_in_order() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let mut sealing_key = SealingKey::new(&secret_key, NonceSequence::new());
+            let mut opening_key = OpeningKey::new(&secret_key, NonceSequence::new());
+
+            let mut ct1 = [0u8; 5 + 16];
+            let mut ct2 = [0u8; 5 + 16];
+            let mut pt = [0u8; 5];
+            sealing_key.seal(b"first", Some(b"ad"), &mut ct1).unwrap();
+            sealing_key.seal(b"secnd", Some(b"ad"), &mut ct2).unwrap();
+
+            // Out-of-order message fails and does not advance the sequence.
+            assert_eq!(
+                opening_key
+                    .open(&ct2, Some(b"ad"), &mut pt)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::MacMismatch
+            );
+            opening_key.open(&ct1, Some(b"ad"), &mut pt).unwrap();
+            assert_eq!(&pt, b"first");
+            // Replayed message fails.
+            assert!(opening_key.open(&ct1, Some(b"ad"), &mut pt).is_err());
+            opening_key.open(&ct2, Some(b"ad"), &mut pt).unwrap();
+            assert_eq!(&pt, b"secnd");
+        }
+
+        #[test]
+        fn test_seal_open_exhausted_err() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let last_nonce = Nonce::from([255u8; 12]);
+            let mut sealing_key =
+                SealingKey::new(&secret_key, NonceSequence::from_nonce(&last_nonce));
+            let mut opening_key =
+                OpeningKey::new(&secret_key, NonceSequence::from_nonce(&last_nonce));
+
+            let mut ct = [0u8; 5 + 16];
+            let mut pt = [0u8; 5];
+            assert_eq!(
+                sealing_key.seal(b"final", None, &mut ct).unwrap(),
+                last_nonce
+            );
+            assert!(sealing_key.seal(b"final", None, &mut ct).is_err());
+            assert_eq!(opening_key.open(&ct, None, &mut pt).unwrap(), last_nonce);
+            assert!(opening_key.open(&ct, None, &mut pt).is_err());
+        }
+
+        #[test]
+        fn test_failed_seal_consumes_nonce() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let mut sealing_key = SealingKey::new(&secret_key, NonceSequence::new());
+
+            let mut too_small = [0u8; 5];
+            assert!(sealing_key.seal(b"hello", None, &mut too_small).is_err());
+            let mut ct = [0u8; 5 + 16];
+            assert_eq!(
+                sealing_key.seal(b"hello", None, &mut ct).unwrap(),
+                Nonce::from([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1])
+            );
+        }
+    }
+}
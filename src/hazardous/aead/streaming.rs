@@ -26,11 +26,14 @@
 //!
 //! # Parameters:
 //! - `secret_key`: The secret key.
-//! - `nonce`: The nonce value.
+//! - `nonce`: The nonce value. A [`StreamHeader`] can be used instead, with [`from_header()`].
 //! - `ad`: Additional data to authenticate (this is not encrypted and can be `None`).
 //! - `plaintext`: The data to be encrypted.
 //! - `ciphertext`: The encrypted data with, a Poly1305 tag and a [`StreamTag`] indicating its function.
 //! - `dst_out`: Destination array that will hold the `ciphertext`/`plaintext` after encryption/decryption.
+//! - `buf`: The buffer that is encrypted/decrypted in-place with [`seal_chunk_in_place()`]/[`open_chunk_in_place()`].
+//!   The plaintext is at `buf[TAG_SIZE..(buf.len() - 16)]`, the rest of `buf` holds the
+//!   [`ABYTES`] of overhead.
 //! - `tag`: Indicates the type of message. The `tag` is a part of the output when encrypting. It
 //!   is encrypted and authenticated.
 //!
@@ -38,11 +41,12 @@
 //! An error will be returned if:
 //! - The length of `dst_out` is less than `plaintext` + [`ABYTES`] when calling [`seal_chunk()`].
 //! - The length of `dst_out` is less than `ciphertext` - [`ABYTES`] when calling [`open_chunk()`].
-//! - The length of the `ciphertext` is less than [`ABYTES`].
+//! - The length of the `ciphertext` or `buf` is less than [`ABYTES`].
 //! - The received mac does not match the calculated mac when calling [`open_chunk()`]. This can
 //!   indicate a dropped or reordered message within the stream.
 //! - More than `2^32-3 * 64` bytes of data are processed when sealing/opening a single chunk.
 //! - [`ABYTES`] + `plaintext.len()` overflows when encrypting.
+//! - An exported state is not [`STATEBYTES`] long, or its padding is not zero.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -89,12 +93,17 @@
 //! [`StreamTag`]: streaming::StreamTag
 //! [`StreamTag::Finish`]: streaming::StreamTag::Finish
 //! [`ABYTES`]: streaming::ABYTES
+//! [`STATEBYTES`]: streaming::STATEBYTES
+//! [`StreamHeader`]: streaming::StreamHeader
+//! [`from_header()`]: streaming::StreamXChaCha20Poly1305::from_header
 //! [`seal_chunk()`]: streaming::StreamXChaCha20Poly1305::seal_chunk
 //! [`open_chunk()`]: streaming::StreamXChaCha20Poly1305::open_chunk
+//! [`seal_chunk_in_place()`]: streaming::StreamXChaCha20Poly1305::seal_chunk_in_place
+//! [`open_chunk_in_place()`]: streaming::StreamXChaCha20Poly1305::open_chunk_in_place
 //! ["secretstream" API]: https://download.libsodium.org/doc/secret-key_cryptography/secretstream
 
-use crate::errors::UnknownCryptoError;
-use crate::hazardous::aead::chacha20poly1305::poly1305_key_gen;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::aead::composition::poly1305_key_gen;
 use crate::hazardous::mac::poly1305::{Poly1305, Tag as Poly1305Tag, POLY1305_OUTSIZE};
 pub use crate::hazardous::stream::chacha20::SecretKey;
 use crate::hazardous::stream::chacha20::{
@@ -103,12 +112,15 @@ use crate::hazardous::stream::chacha20::{
 };
 use crate::hazardous::stream::xchacha20::subkey_and_nonce;
 pub use crate::hazardous::stream::xchacha20::Nonce;
+use crate::hazardous::stream::xchacha20::XCHACHA_NONCESIZE;
 use core::convert::TryFrom;
 use subtle::ConstantTimeEq;
-use zeroize::{Zeroize, Zeroizing};
+use zeroize::Zeroizing;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 /// Tag that indicates the type of message.
+///
+/// Only these four tags exist. Any other tag byte is rejected with an error when a chunk is opened.
 pub enum StreamTag {
     /// A message with no special meaning.
     Message,
@@ -133,6 +145,19 @@ impl StreamTag {
             StreamTag::Finish => 0b0000_0011, /* StreamTag::Push.as_byte() | StreamTag::Rekey.as_bytes() */
         }
     }
+
+    #[inline]
+    /// Return `true` if this tag ends the stream, i.e. it is [`StreamTag::Finish`].
+    pub fn is_final(&self) -> bool {
+        matches!(self, StreamTag::Finish)
+    }
+
+    #[inline]
+    /// Return `true` if a new secret key is derived after a chunk with this tag, i.e. it is
+    /// [`StreamTag::Rekey`] or [`StreamTag::Finish`].
+    pub fn is_rekey(&self) -> bool {
+        matches!(self, StreamTag::Rekey | StreamTag::Finish)
+    }
 }
 
 impl TryFrom<u8> for StreamTag {
@@ -144,7 +169,7 @@ impl TryFrom<u8> for StreamTag {
             0b0000_0001 => Ok(Self::Push),
             0b0000_0010 => Ok(Self::Rekey),
             0b0000_0011 => Ok(Self::Finish),
-            _ => Err(UnknownCryptoError),
+            _ => Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
         }
     }
 }
@@ -155,6 +180,12 @@ impl PartialEq<StreamTag> for StreamTag {
     }
 }
 
+impl ConstantTimeEq for StreamTag {
+    fn ct_eq(&self, other: &StreamTag) -> subtle::Choice {
+        self.as_byte().ct_eq(&other.as_byte())
+    }
+}
+
 /// The size of the internal counter.
 const COUNTERBYTES: usize = 4;
 /// The size of the internal nonce.
@@ -163,6 +194,39 @@ const INONCEBYTES: usize = 8;
 pub const TAG_SIZE: usize = 1;
 /// Size of additional data appended to each message.
 pub const ABYTES: usize = POLY1305_OUTSIZE + TAG_SIZE;
+/// The size of a [`StreamHeader`].
+pub const HEADERBYTES: usize = XCHACHA_NONCESIZE;
+/// The size of an exported state. See [`StreamXChaCha20Poly1305::export_state()`].
+pub const STATEBYTES: usize = CHACHA_KEYSIZE + COUNTERBYTES + INONCEBYTES + STATE_PADBYTES;
+/// The size of the zero padding at the end of an exported state.
+const STATE_PADBYTES: usize = 8;
+
+construct_public! {
+    /// A type that represents the header which starts a stream. It must be sent to the opening
+    /// side together with the ciphertexts.
+    ///
+    /// The header is used as the XChaCha20 nonce of the stream, but is kept as a separate type
+    /// so that it cannot be confused with nonces used elsewhere.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 24 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (StreamHeader, test_stream_header, HEADERBYTES, HEADERBYTES, HEADERBYTES)
+}
+
+impl_from_trait!(StreamHeader, HEADERBYTES);
+
+impl From<Nonce> for StreamHeader {
+    fn from(nonce: Nonce) -> Self {
+        let mut value = [0u8; HEADERBYTES];
+        value.copy_from_slice(nonce.as_ref());
+        Self::from(value)
+    }
+}
 
 /// Padding size that gives the needed bytes to pad `input` to an integral
 /// multiple of 16.
@@ -180,11 +244,70 @@ fn padding(input: usize) -> usize {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// A policy for automatically deriving a new key, in addition to the rekeying done for
+/// [`StreamTag::Rekey`] and [`StreamTag::Finish`].
+///
+/// Both the sealing and opening side of a stream must use the same policy.
+pub struct RekeyPolicy {
+    messages: Option<u64>,
+    bytes: Option<u64>,
+}
+
+impl RekeyPolicy {
+    /// A policy that never rekeys automatically. This is the default.
+    pub const fn never() -> Self {
+        Self {
+            messages: None,
+            bytes: None,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Rekey once `messages` messages have been processed since the last rekey.
+    ///
+    /// An error is returned if `messages` is 0.
+    pub fn after_messages(self, messages: u64) -> Result<Self, UnknownCryptoError> {
+        if messages == 0 {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+        }
+
+        Ok(Self {
+            messages: Some(messages),
+            ..self
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Rekey once at least `bytes` bytes of plaintext have been processed since the last rekey.
+    ///
+    /// An error is returned if `bytes` is 0.
+    pub fn after_bytes(self, bytes: u64) -> Result<Self, UnknownCryptoError> {
+        if bytes == 0 {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+        }
+
+        Ok(Self {
+            bytes: Some(bytes),
+            ..self
+        })
+    }
+
+    /// Whether this policy requires a rekey, after `messages` messages with `bytes` total length.
+    fn is_due(&self, messages: u64, bytes: u64) -> bool {
+        matches!(self.messages, Some(limit) if messages >= limit)
+            || matches!(self.bytes, Some(limit) if bytes >= limit)
+    }
+}
+
 /// Streaming XChaCha20Poly1305 state.
 pub struct StreamXChaCha20Poly1305 {
     key: SecretKey,
     counter: u32,
     inonce: [u8; INONCEBYTES],
+    rekey_policy: RekeyPolicy,
+    messages_since_rekey: u64,
+    bytes_since_rekey: u64,
 }
 
 impl core::fmt::Debug for StreamXChaCha20Poly1305 {
@@ -222,41 +345,43 @@ impl StreamXChaCha20Poly1305 {
             self.key.unprotected_as_bytes(),
             self.get_nonce().as_ref(),
             true,
-        )
-        .unwrap();
-        let mut tmp_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
-
+        )?;
         let mut pad = [0u8; 16];
-        let mut poly = Poly1305::new(&poly1305_key_gen(&mut chacha20_ctx, &mut tmp_block));
+        let mut poly = Poly1305::new(&poly1305_key_gen(&mut chacha20_ctx)?);
 
         poly.process_pad_to_blocksize(ad)?;
         poly.update(block)?;
         poly.update(&text[textpos..(textpos + msglen)])?;
         poly.update(&pad[..padding(CHACHA_BLOCKSIZE.wrapping_sub(msglen))])?;
         pad[..8].copy_from_slice(&(ad.len() as u64).to_le_bytes());
-        pad[8..16].copy_from_slice(
-            &((CHACHA_BLOCKSIZE as u64)
-                .checked_add(msglen as u64)
-                .unwrap())
-            .to_le_bytes(),
-        );
+        let ct_len = match (CHACHA_BLOCKSIZE as u64).checked_add(msglen as u64) {
+            Some(len) => len,
+            None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+        };
+        pad[8..16].copy_from_slice(&ct_len.to_le_bytes());
         poly.update(&pad)?;
 
         poly.finalize()
     }
 
-    /// Update internal inonce and counter. Performs rekey on overflowing counter and
-    /// designated tags.
+    /// Update internal inonce and counter. Performs rekey on overflowing counter,
+    /// designated tags and when the rekey policy requires it.
     fn advance_state(
         &mut self,
         mac: &Poly1305Tag,
         tag: &StreamTag,
+        msglen: usize,
     ) -> Result<(), UnknownCryptoError> {
         xor_slices!(mac.unprotected_as_bytes()[..INONCEBYTES], self.inonce);
         self.counter = self.counter.wrapping_add(1);
+        self.messages_since_rekey = self.messages_since_rekey.saturating_add(1);
+        self.bytes_since_rekey = self.bytes_since_rekey.saturating_add(msglen as u64);
         if bool::from(
             !(tag.as_byte() & StreamTag::Rekey.as_byte()).ct_eq(&0u8) | self.counter.ct_eq(&0u32),
-        ) {
+        ) || self
+            .rekey_policy
+            .is_due(self.messages_since_rekey, self.bytes_since_rekey)
+        {
             self.rekey()?;
         };
 
@@ -272,23 +397,97 @@ impl StreamXChaCha20Poly1305 {
             key: subkey_and_nonce(&secret_key, &nonce).0,
             counter: 1,
             inonce,
+            rekey_policy: RekeyPolicy::never(),
+            messages_since_rekey: 0,
+            bytes_since_rekey: 0,
         }
     }
 
+    /// Initialize a `StreamXChaCha20Poly1305` struct with a given secret key and stream header.
+    pub fn from_header(secret_key: &SecretKey, header: &StreamHeader) -> Self {
+        let mut nonce = [0u8; XCHACHA_NONCESIZE];
+        nonce.copy_from_slice(header.as_ref());
+        Self::new(secret_key, &Nonce::from(nonce))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the internal state to `dst_out`, which must be [`STATEBYTES`] long.
+    ///
+    /// The layout is the same as libsodium's `crypto_secretstream_xchacha20poly1305_state`:
+    /// the current key (32 bytes), the counter (4 bytes, little-endian), the inonce (8 bytes)
+    /// and 8 bytes of zero padding.
+    ///
+    /// # Security:
+    /// - The exported state contains the current secret key and must be protected as such.
+    /// - A state must only be imported once. Continuing a stream from a copy of an exported
+    ///   state, after the original stream has been continued, re-uses nonces.
+    /// - The [`RekeyPolicy`] and the progress towards it are not part of the state.
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dst_out.len() != STATEBYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let (key, rest) = dst_out.split_at_mut(CHACHA_KEYSIZE);
+        let (counter, rest) = rest.split_at_mut(COUNTERBYTES);
+        let (inonce, pad) = rest.split_at_mut(INONCEBYTES);
+        key.copy_from_slice(self.key.unprotected_as_bytes());
+        counter.copy_from_slice(&self.counter.to_le_bytes());
+        inonce.copy_from_slice(&self.inonce);
+        pad.iter_mut().for_each(|b| *b = 0u8);
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `StreamXChaCha20Poly1305` struct from a state exported with
+    /// [`export_state()`](Self::export_state), or from a libsodium
+    /// `crypto_secretstream_xchacha20poly1305_state`.
+    ///
+    /// An error is returned if `state` is not [`STATEBYTES`] long or its padding is not zero.
+    pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if state.len() != STATEBYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if !bool::from(state[STATEBYTES - STATE_PADBYTES..].ct_eq(&[0u8; STATE_PADBYTES])) {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let mut counter = [0u8; COUNTERBYTES];
+        counter.copy_from_slice(&state[CHACHA_KEYSIZE..CHACHA_KEYSIZE + COUNTERBYTES]);
+        let mut inonce = [0u8; INONCEBYTES];
+        inonce.copy_from_slice(&state[CHACHA_KEYSIZE + COUNTERBYTES..STATEBYTES - STATE_PADBYTES]);
+
+        Ok(Self {
+            key: SecretKey::from_slice(&state[..CHACHA_KEYSIZE])?,
+            counter: u32::from_le_bytes(counter),
+            inonce,
+            rekey_policy: RekeyPolicy::never(),
+            messages_since_rekey: 0,
+            bytes_since_rekey: 0,
+        })
+    }
+
+    /// Set the policy for automatic rekeying. The amount of messages and bytes processed
+    /// since the last rekey are counted regardless of the policy.
+    pub fn set_rekey_policy(&mut self, policy: RekeyPolicy) {
+        self.rekey_policy = policy;
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Derives a new secret key used for encryption and decryption.
     pub fn rekey(&mut self) -> Result<(), UnknownCryptoError> {
-        let mut new_key_and_inonce = [0u8; CHACHA_KEYSIZE + INONCEBYTES];
+        let mut new_key_and_inonce = Zeroizing::new([0u8; CHACHA_KEYSIZE + INONCEBYTES]);
         new_key_and_inonce[..CHACHA_KEYSIZE].copy_from_slice(self.key.unprotected_as_bytes());
         new_key_and_inonce[CHACHA_KEYSIZE..].copy_from_slice(&self.inonce);
 
-        chacha20_xor_stream(&self.key, &self.get_nonce(), 0, &mut new_key_and_inonce)?;
+        chacha20_xor_stream(&self.key, &self.get_nonce(), 0, new_key_and_inonce.as_mut())?;
 
-        self.key = SecretKey::from_slice(&new_key_and_inonce[..CHACHA_KEYSIZE]).unwrap();
+        self.key = SecretKey::from_slice(&new_key_and_inonce[..CHACHA_KEYSIZE])?;
         self.inonce
             .copy_from_slice(&new_key_and_inonce[CHACHA_KEYSIZE..]);
         self.counter = 1;
-        new_key_and_inonce.zeroize();
+        self.messages_since_rekey = 0;
+        self.bytes_since_rekey = 0;
 
         Ok(())
     }
@@ -306,30 +505,30 @@ impl StreamXChaCha20Poly1305 {
         match ABYTES.checked_add(msglen) {
             Some(out_min_len) => {
                 if dst_out.len() < out_min_len {
-                    return Err(UnknownCryptoError);
+                    return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
                 }
             }
-            None => return Err(UnknownCryptoError),
+            None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
         };
 
-        let mut block = [0u8; CHACHA_BLOCKSIZE];
+        let mut block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
         let ad = ad.unwrap_or(&[0u8; 0]);
 
         let macpos = TAG_SIZE + msglen;
         let nonce = self.get_nonce();
 
         block[0] = tag.as_byte();
-        chacha20_xor_stream(&self.key, &nonce, 1, &mut block)?;
+        chacha20_xor_stream(&self.key, &nonce, 1, block.as_mut())?;
         dst_out[0] = block[0];
 
         if msglen != 0 {
             chacha20_enc(&self.key, &nonce, 2, plaintext, &mut dst_out[TAG_SIZE..])?;
         }
 
-        let mac = self.generate_auth_tag(dst_out, ad, msglen, &block, TAG_SIZE)?;
+        let mac = self.generate_auth_tag(dst_out, ad, msglen, block.as_ref(), TAG_SIZE)?;
         dst_out[macpos..(macpos + POLY1305_OUTSIZE)].copy_from_slice(mac.unprotected_as_bytes());
 
-        self.advance_state(&mac, &tag)
+        self.advance_state(&mac, &tag, msglen)
     }
 
     #[allow(clippy::range_plus_one)]
@@ -342,27 +541,30 @@ impl StreamXChaCha20Poly1305 {
         dst_out: &mut [u8],
     ) -> Result<StreamTag, UnknownCryptoError> {
         if ciphertext.len() < ABYTES {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
         let msglen = ciphertext.len() - ABYTES;
         if dst_out.len() < msglen {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
-        let mut block = [0u8; CHACHA_BLOCKSIZE];
+        let mut block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
         let ad = ad.unwrap_or(&[0u8; 0]);
 
         let macpos = TAG_SIZE + msglen;
         let nonce = self.get_nonce();
 
         block[0] = ciphertext[0];
-        chacha20_xor_stream(&self.key, &nonce, 1, &mut block)?;
-        let tag = StreamTag::try_from(block[0])?;
+        chacha20_xor_stream(&self.key, &nonce, 1, block.as_mut())?;
+        // An invalid tag byte can only be the result of a modified ciphertext, so
+        // it should be reported the same way as a failed authentication.
+        let tag = StreamTag::try_from(block[0])
+            .map_err(|_| UnknownCryptoError::new(ErrorKind::MacMismatch))?;
         block[0] = ciphertext[0];
-        let mac = self.generate_auth_tag(ciphertext, ad, msglen, &block, TAG_SIZE)?;
+        let mac = self.generate_auth_tag(ciphertext, ad, msglen, block.as_ref(), TAG_SIZE)?;
         if !(mac == &ciphertext[macpos..macpos + mac.len()]) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::MacMismatch));
         }
         if msglen != 0 {
             chacha20_enc(
@@ -373,7 +575,87 @@ impl StreamXChaCha20Poly1305 {
                 dst_out,
             )?;
         }
-        self.advance_state(&mac, &tag)?;
+        self.advance_state(&mac, &tag, msglen)?;
+
+        Ok(tag)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt and authenticate a single message and tag in `buf`.
+    ///
+    /// The plaintext must be placed at `buf[TAG_SIZE..(buf.len() - 16)]`, leaving
+    /// room for the [`ABYTES`] of overhead. The first [`TAG_SIZE`] bytes and the last 16
+    /// bytes of `buf` are overwritten, so that `buf` holds the same
+    /// ciphertext as [`seal_chunk()`](Self::seal_chunk) produces.
+    pub fn seal_chunk_in_place(
+        &mut self,
+        buf: &mut [u8],
+        ad: Option<&[u8]>,
+        tag: StreamTag,
+    ) -> Result<(), UnknownCryptoError> {
+        if buf.len() < ABYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let msglen = buf.len() - ABYTES;
+        let mut block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+        let ad = ad.unwrap_or(&[0u8; 0]);
+
+        let macpos = TAG_SIZE + msglen;
+        let nonce = self.get_nonce();
+
+        block[0] = tag.as_byte();
+        chacha20_xor_stream(&self.key, &nonce, 1, block.as_mut())?;
+        buf[0] = block[0];
+
+        if msglen != 0 {
+            chacha20_xor_stream(&self.key, &nonce, 2, &mut buf[TAG_SIZE..macpos])?;
+        }
+
+        let mac = self.generate_auth_tag(buf, ad, msglen, block.as_ref(), TAG_SIZE)?;
+        buf[macpos..].copy_from_slice(mac.unprotected_as_bytes());
+
+        self.advance_state(&mac, &tag, msglen)
+    }
+
+    #[allow(clippy::range_plus_one)]
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Authenticate and decrypt a single message and tag in `buf`.
+    ///
+    /// `buf` must hold a ciphertext as produced by [`seal_chunk()`](Self::seal_chunk). On success,
+    /// the plaintext is at `buf[TAG_SIZE..(buf.len() - 16)]`. If authentication
+    /// fails, `buf` is left unmodified.
+    pub fn open_chunk_in_place(
+        &mut self,
+        buf: &mut [u8],
+        ad: Option<&[u8]>,
+    ) -> Result<StreamTag, UnknownCryptoError> {
+        if buf.len() < ABYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let msglen = buf.len() - ABYTES;
+        let mut block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+        let ad = ad.unwrap_or(&[0u8; 0]);
+
+        let macpos = TAG_SIZE + msglen;
+        let nonce = self.get_nonce();
+
+        block[0] = buf[0];
+        chacha20_xor_stream(&self.key, &nonce, 1, block.as_mut())?;
+        // An invalid tag byte can only be the result of a modified ciphertext, so
+        // it should be reported the same way as a failed authentication.
+        let tag = StreamTag::try_from(block[0])
+            .map_err(|_| UnknownCryptoError::new(ErrorKind::MacMismatch))?;
+        block[0] = buf[0];
+        let mac = self.generate_auth_tag(buf, ad, msglen, block.as_ref(), TAG_SIZE)?;
+        if !(mac == &buf[macpos..macpos + mac.len()]) {
+            return Err(UnknownCryptoError::new(ErrorKind::MacMismatch));
+        }
+        if msglen != 0 {
+            chacha20_xor_stream(&self.key, &nonce, 2, &mut buf[TAG_SIZE..macpos])?;
+        }
+        self.advance_state(&mac, &tag, msglen)?;
 
         Ok(tag)
     }
@@ -604,6 +886,24 @@ mod private {
         assert!(StreamTag::try_from(4u8).is_err());
     }
 
+    #[test]
+    fn test_tag_helpers() {
+        assert!(!StreamTag::Message.is_final());
+        assert!(!StreamTag::Push.is_final());
+        assert!(!StreamTag::Rekey.is_final());
+        assert!(StreamTag::Finish.is_final());
+
+        assert!(!StreamTag::Message.is_rekey());
+        assert!(!StreamTag::Push.is_rekey());
+        assert!(StreamTag::Rekey.is_rekey());
+        assert!(StreamTag::Finish.is_rekey());
+
+        for byte in 0u8..=3 {
+            let tag = StreamTag::try_from(byte).unwrap();
+            assert_eq!(tag.is_rekey(), byte & StreamTag::Rekey.as_byte() != 0);
+        }
+    }
+
     #[test]
     fn test_seal_open_with_explicit_rekey() {
         // Encrypt stream
@@ -1503,4 +1803,269 @@ mod private {
         assert_eq!(ctx.counter, u32::from_le_bytes(after_internal_counter));
         assert_eq!(actual.as_ref(), out.as_ref());
     }
+
+    #[test]
+    fn test_from_header_same_as_nonce() {
+        let nonce = Nonce::from([7u8; 24]);
+        let header = StreamHeader::from(nonce);
+        assert_eq!(header, StreamHeader::from([7u8; HEADERBYTES]));
+
+        let key = SecretKey::from([1u8; 32]);
+        let mut from_nonce = StreamXChaCha20Poly1305::new(&key, &nonce);
+        let mut from_header = StreamXChaCha20Poly1305::from_header(&key, &header);
+        assert_eq!(from_nonce.inonce, from_header.inonce);
+
+        let mut out_nonce = [0u8; 3 + ABYTES];
+        let mut out_header = [0u8; 3 + ABYTES];
+        from_nonce
+            .seal_chunk(b"msg", None, &mut out_nonce, StreamTag::Message)
+            .unwrap();
+        from_header
+            .seal_chunk(b"msg", None, &mut out_header, StreamTag::Message)
+            .unwrap();
+        assert_eq!(out_nonce, out_header);
+    }
+
+    mod test_state {
+        use super::*;
+
+        #[test]
+        fn test_length_and_padding_err() {
+            let ctx =
+                StreamXChaCha20Poly1305::new(&SecretKey::from([0u8; 32]), &Nonce::from([0u8; 24]));
+            let mut state = [0u8; STATEBYTES + 1];
+            assert!(ctx.export_state(&mut state[..STATEBYTES - 1]).is_err());
+            assert!(ctx.export_state(&mut state).is_err());
+            assert!(StreamXChaCha20Poly1305::import_state(&state).is_err());
+            assert!(StreamXChaCha20Poly1305::import_state(&state[..STATEBYTES - 1]).is_err());
+
+            state[STATEBYTES - 1] = 1;
+            ctx.export_state(&mut state[..STATEBYTES]).unwrap();
+            assert_eq!(state[STATEBYTES - 1], 0);
+            assert!(StreamXChaCha20Poly1305::import_state(&state[..STATEBYTES]).is_ok());
+            state[STATEBYTES - 1] = 1;
+            assert!(StreamXChaCha20Poly1305::import_state(&state[..STATEBYTES]).is_err());
+        }
+
+        #[test]
+        fn test_layout() {
+            let mut ctx =
+                StreamXChaCha20Poly1305::new(&SecretKey::from([0u8; 32]), &Nonce::from([0u8; 24]));
+            let mut msg = [0u8; ABYTES];
+            ctx.seal_chunk(&[], None, &mut msg, StreamTag::Message)
+                .unwrap();
+
+            let mut state = [0u8; STATEBYTES];
+            ctx.export_state(&mut state).unwrap();
+            assert_eq!(&state[..32], ctx.key.unprotected_as_bytes());
+            assert_eq!(&state[32..36], &2u32.to_le_bytes());
+            assert_eq!(&state[36..44], &ctx.inonce);
+            assert_eq!(&state[44..], &[0u8; 8]);
+        }
+
+        #[test]
+        fn test_import_continues_stream() {
+            let mut sealer =
+                StreamXChaCha20Poly1305::new(&SecretKey::from([1u8; 32]), &Nonce::from([2u8; 24]));
+            let mut opener =
+                StreamXChaCha20Poly1305::new(&SecretKey::from([1u8; 32]), &Nonce::from([2u8; 24]));
+
+            let mut ct = [0u8; 3 + ABYTES];
+            let mut pt = [0u8; 3];
+            for tag in [StreamTag::Message, StreamTag::Rekey, StreamTag::Push].iter() {
+                sealer.seal_chunk(b"msg", None, &mut ct, *tag).unwrap();
+
+                let mut state = [0u8; STATEBYTES];
+                opener.export_state(&mut state).unwrap();
+                opener = StreamXChaCha20Poly1305::import_state(&state).unwrap();
+
+                assert_eq!(opener.open_chunk(&ct, None, &mut pt).unwrap(), *tag);
+                assert_eq!(&pt, b"msg");
+            }
+        }
+    }
+
+    mod test_in_place {
+        use super::*;
+
+        fn new_state() -> StreamXChaCha20Poly1305 {
+            StreamXChaCha20Poly1305::new(&SecretKey::from([0u8; 32]), &Nonce::from([0u8; 24]))
+        }
+
+        #[test]
+        fn test_short_buf_err() {
+            let mut buf = [0u8; ABYTES - 1];
+            assert!(new_state()
+                .seal_chunk_in_place(&mut buf, None, StreamTag::Message)
+                .is_err());
+            assert!(new_state().open_chunk_in_place(&mut buf, None).is_err());
+
+            let mut buf = [0u8; ABYTES];
+            assert!(new_state()
+                .seal_chunk_in_place(&mut buf, None, StreamTag::Message)
+                .is_ok());
+        }
+
+        #[test]
+        fn test_same_as_out_of_place() {
+            let ad = b"ad";
+            let tags = [
+                StreamTag::Message,
+                StreamTag::Push,
+                StreamTag::Rekey,
+                StreamTag::Finish,
+            ];
+            let mut sealer = new_state();
+            let mut sealer_in_place = new_state();
+            let mut opener = new_state();
+
+            for (len, tag) in (0..200).step_by(37).zip(tags.iter().cycle()) {
+                let plaintext = &[len as u8; 200][..len];
+                let mut ciphertext = [0u8; 200 + ABYTES];
+                let ciphertext = &mut ciphertext[..len + ABYTES];
+                sealer
+                    .seal_chunk(plaintext, Some(ad), ciphertext, *tag)
+                    .unwrap();
+
+                let mut buf = [0u8; 200 + ABYTES];
+                let buf = &mut buf[..len + ABYTES];
+                buf[TAG_SIZE..TAG_SIZE + len].copy_from_slice(plaintext);
+                sealer_in_place
+                    .seal_chunk_in_place(buf, Some(ad), *tag)
+                    .unwrap();
+                assert_eq!(buf, ciphertext);
+
+                assert_eq!(opener.open_chunk_in_place(buf, Some(ad)).unwrap(), *tag);
+                assert_eq!(&buf[TAG_SIZE..TAG_SIZE + len], plaintext);
+            }
+        }
+
+        #[test]
+        fn test_modified_buf_err_and_unmodified() {
+            let mut sealer = new_state();
+            let mut buf = [0u8; 5 + ABYTES];
+            buf[TAG_SIZE..TAG_SIZE + 5].copy_from_slice(b"hello");
+            sealer
+                .seal_chunk_in_place(&mut buf, None, StreamTag::Message)
+                .unwrap();
+
+            for idx in 0..buf.len() {
+                let mut modified = buf;
+                modified[idx] ^= 1;
+                let before = modified;
+                assert!(new_state()
+                    .open_chunk_in_place(&mut modified, None)
+                    .is_err());
+                assert_eq!(modified, before);
+            }
+
+            assert!(new_state()
+                .open_chunk_in_place(&mut buf, Some(b"ad"))
+                .is_err());
+            assert!(new_state().open_chunk_in_place(&mut buf, None).is_ok());
+            assert_eq!(&buf[TAG_SIZE..TAG_SIZE + 5], b"hello");
+        }
+    }
+
+    mod test_rekey_policy {
+        use super::*;
+
+        fn new_state(policy: RekeyPolicy) -> StreamXChaCha20Poly1305 {
+            let mut state =
+                StreamXChaCha20Poly1305::new(&SecretKey::from([0u8; 32]), &Nonce::from([0u8; 24]));
+            state.set_rekey_policy(policy);
+            state
+        }
+
+        fn seal(state: &mut StreamXChaCha20Poly1305, input: &[u8]) -> [u8; 8 + ABYTES] {
+            let mut out = [0u8; 8 + ABYTES];
+            state
+                .seal_chunk(
+                    input,
+                    None,
+                    &mut out[..input.len() + ABYTES],
+                    StreamTag::Message,
+                )
+                .unwrap();
+            out
+        }
+
+        #[test]
+        fn test_zero_limits_err() {
+            assert!(RekeyPolicy::never().after_messages(0).is_err());
+            assert!(RekeyPolicy::never().after_bytes(0).is_err());
+            assert!(RekeyPolicy::never().after_messages(1).is_ok());
+            assert!(RekeyPolicy::never().after_bytes(1).is_ok());
+            assert_eq!(RekeyPolicy::default(), RekeyPolicy::never());
+        }
+
+        #[test]
+        fn test_after_messages_same_as_explicit_rekey() {
+            let mut auto = new_state(RekeyPolicy::never().after_messages(2).unwrap());
+            let mut manual = new_state(RekeyPolicy::never());
+
+            for _ in 0..2 {
+                for _ in 0..2 {
+                    assert_eq!(seal(&mut auto, b"msg"), seal(&mut manual, b"msg"));
+                }
+                manual.rekey().unwrap();
+                assert_eq!(auto.key, manual.key.unprotected_as_bytes());
+                assert_eq!(auto.inonce, manual.inonce);
+                assert_eq!(auto.counter, manual.counter);
+            }
+        }
+
+        #[test]
+        fn test_after_bytes_same_as_explicit_rekey() {
+            let mut auto = new_state(RekeyPolicy::never().after_bytes(10).unwrap());
+            let mut manual = new_state(RekeyPolicy::never());
+
+            // 4 + 4 bytes do not reach the limit, the next 4 bytes do.
+            for _ in 0..3 {
+                assert_eq!(seal(&mut auto, b"abcd"), seal(&mut manual, b"abcd"));
+            }
+            manual.rekey().unwrap();
+            assert_eq!(auto.key, manual.key.unprotected_as_bytes());
+            assert_eq!(auto.bytes_since_rekey, 0);
+            assert_eq!(auto.messages_since_rekey, 0);
+        }
+
+        #[test]
+        fn test_tag_rekey_resets_policy_counters() {
+            let mut auto = new_state(RekeyPolicy::never().after_messages(2).unwrap());
+            let mut out = [0u8; ABYTES];
+            seal(&mut auto, b"msg");
+            auto.seal_chunk(&[], None, &mut out, StreamTag::Rekey)
+                .unwrap();
+            assert_eq!(auto.messages_since_rekey, 0);
+            seal(&mut auto, b"msg");
+            assert_eq!(auto.messages_since_rekey, 1);
+        }
+
+        #[test]
+        fn test_open_requires_same_policy() {
+            let policy = RekeyPolicy::never().after_messages(1).unwrap();
+            let mut sealer = new_state(policy);
+            let mut opener_same = new_state(policy);
+            let mut opener_none = new_state(RekeyPolicy::never());
+
+            let mut pt = [0u8; 3];
+            let ct0 = seal(&mut sealer, b"msg");
+            let ct1 = seal(&mut sealer, b"msg");
+
+            assert!(opener_same
+                .open_chunk(&ct0[..3 + ABYTES], None, &mut pt)
+                .is_ok());
+            assert!(opener_same
+                .open_chunk(&ct1[..3 + ABYTES], None, &mut pt)
+                .is_ok());
+
+            assert!(opener_none
+                .open_chunk(&ct0[..3 + ABYTES], None, &mut pt)
+                .is_ok());
+            assert!(opener_none
+                .open_chunk(&ct1[..3 + ABYTES], None, &mut pt)
+                .is_err());
+        }
+    }
 }
@@ -43,10 +43,8 @@
 //! - The received tag does not match the calculated tag when  calling [`open()`].
 //! - `plaintext.len()` + [`POLY1305_OUTSIZE`] overflows when  calling [`seal()`].
 //! - Converting `usize` to `u64` would be a lossy conversion.
-//!
-//! # Panics:
-//! A panic will occur if:
-//! - More than `2^32-1 * 64` bytes of data are processed.
+//! - More than `2^32-2 * 64` bytes of data would be encrypted or decrypted. One
+//!   keystream block is used for the Poly1305 key.
 //!
 //! # Security:
 //! - It is critical for security that a given nonce is not re-used with a given
@@ -116,6 +114,32 @@ pub fn open(
     chacha20poly1305::open(&subkey, &ietf_nonce, ciphertext_with_tag, ad, dst_out)
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Same as [`seal()`], but with `plaintext` and `ad` each split into several slices.
+pub fn seal_vectored(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    plaintext: &[&[u8]],
+    ad: &[&[u8]],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+    chacha20poly1305::seal_vectored(&subkey, &ietf_nonce, plaintext, ad, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Same as [`open()`], but with `ciphertext_with_tag` and `ad` each split into several slices.
+pub fn open_vectored(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ciphertext_with_tag: &[&[u8]],
+    ad: &[&[u8]],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+    chacha20poly1305::open_vectored(&subkey, &ietf_nonce, ciphertext_with_tag, ad, dst_out)
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -142,4 +166,27 @@ mod public {
         test_diff_params_err(&seal, &open, &input, POLY1305_OUTSIZE);
         true
     }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_vectored_same_as_contiguous(input: Vec<u8>, ad: Vec<u8>, split: usize) -> bool {
+        let secret_key = SecretKey::generate();
+        let nonce = Nonce::generate();
+        let (pt1, pt2) = input.split_at(if input.is_empty() {
+            0
+        } else {
+            split % input.len()
+        });
+
+        let mut expected = vec![0u8; input.len() + POLY1305_OUTSIZE];
+        seal(&secret_key, &nonce, &input, Some(&ad), &mut expected).unwrap();
+        let mut actual = vec![0u8; input.len() + POLY1305_OUTSIZE];
+        seal_vectored(&secret_key, &nonce, &[pt1, pt2], &[&ad], &mut actual).unwrap();
+
+        let (ct1, ct2) = actual.split_at(split % actual.len());
+        let mut decrypted = vec![0u8; input.len()];
+        open_vectored(&secret_key, &nonce, &[ct1, ct2], &[&ad], &mut decrypted).unwrap();
+
+        actual == expected && decrypted == input
+    }
 }
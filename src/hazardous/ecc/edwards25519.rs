@@ -0,0 +1,181 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Group operations on the twisted Edwards curve -x^2 + y^2 = 1 + d*x^2*y^2
+//! over GF(2^255 - 19), birationally equivalent to Curve25519.
+//!
+//! Points are stored in extended coordinates (X:Y:Z:T), with x = X/Z, y = Y/Z
+//! and x*y = T/Z, and use the complete addition formulas from
+//! ["Twisted Edwards Curves Revisited"](https://eprint.iacr.org/2008/522).
+
+use super::field25519::FieldElement;
+use super::scalar25519::Scalar52;
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+
+#[derive(Clone, Copy)]
+/// A point on the Edwards curve, in extended coordinates.
+pub(crate) struct EdwardsPoint {
+    pub(crate) x: FieldElement,
+    pub(crate) y: FieldElement,
+    pub(crate) z: FieldElement,
+    pub(crate) t: FieldElement,
+}
+
+impl ConditionallySelectable for EdwardsPoint {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self {
+            x: FieldElement::conditional_select(&a.x, &b.x, choice),
+            y: FieldElement::conditional_select(&a.y, &b.y, choice),
+            z: FieldElement::conditional_select(&a.z, &b.z, choice),
+            t: FieldElement::conditional_select(&a.t, &b.t, choice),
+        }
+    }
+}
+
+impl core::ops::Neg for &EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn neg(self) -> EdwardsPoint {
+        EdwardsPoint {
+            x: -&self.x,
+            y: self.y,
+            z: self.z,
+            t: -&self.t,
+        }
+    }
+}
+
+impl EdwardsPoint {
+    pub(crate) const IDENTITY: Self = Self {
+        x: FieldElement::ZERO,
+        y: FieldElement::ONE,
+        z: FieldElement::ONE,
+        t: FieldElement::ZERO,
+    };
+
+    /// The Ed25519 basepoint, with y = 4/5 and x positive.
+    pub(crate) const BASEPOINT: Self = Self {
+        x: FieldElement([
+            1738742601995546,
+            1146398526822698,
+            2070867633025821,
+            562264141797630,
+            587772402128613,
+        ]),
+        y: FieldElement([
+            1801439850948184,
+            1351079888211148,
+            450359962737049,
+            900719925474099,
+            1801439850948198,
+        ]),
+        z: FieldElement::ONE,
+        t: FieldElement([
+            1841354044333475,
+            16398895984059,
+            755974180946558,
+            900171276175154,
+            1821297809914039,
+        ]),
+    };
+
+    /// Compute self + other, using the "add-2008-hwcd-3" formulas.
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        let a = self.y.sub(&self.x).mul(&other.y.sub(&other.x));
+        let b = self.y.add(&self.x).mul(&other.y.add(&other.x));
+        let c = self.t.mul(&FieldElement::EDWARDS_D2).mul(&other.t);
+        let d = self.z.add(&self.z).mul(&other.z);
+
+        let e = b.sub(&a);
+        let f = d.sub(&c);
+        let g = d.add(&c);
+        let h = b.add(&a);
+
+        Self {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+
+    /// Compute self - other.
+    pub(crate) fn sub(&self, other: &Self) -> Self {
+        self.add(&-other)
+    }
+
+    /// Compute 2 * self, using the "dbl-2008-hwcd" formulas with a = -1.
+    pub(crate) fn double(&self) -> Self {
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = self.z.square();
+        let c = c.add(&c);
+
+        // D = a*A = -A
+        let e = self.x.add(&self.y).square().sub(&a).sub(&b);
+        let g = b.sub(&a);
+        let f = g.sub(&c);
+        let h = (-&a).sub(&b);
+
+        Self {
+            x: e.mul(&f),
+            y: g.mul(&h),
+            z: f.mul(&g),
+            t: e.mul(&h),
+        }
+    }
+
+    /// Select `table[|digit| - 1]`, or the identity if `digit` is zero,
+    /// negated if `digit` is negative, in constant time.
+    fn select(table: &[Self; 8], digit: i8) -> Self {
+        debug_assert!((-8..=8).contains(&digit));
+        let digit_mask = (digit as i16) >> 7;
+        let digit_abs = (((digit as i16) + digit_mask) ^ digit_mask) as u8;
+        let digit_is_negative = Choice::from((digit_mask & 1) as u8);
+
+        let mut ret = Self::IDENTITY;
+        for (j, point) in (1u8..).zip(table.iter()) {
+            ret.conditional_assign(point, digit_abs.ct_eq(&j));
+        }
+        ret.conditional_negate(digit_is_negative);
+
+        ret
+    }
+
+    /// Compute scalar * self, in constant time.
+    pub(crate) fn mul(&self, scalar: &Scalar52) -> Self {
+        // table[i] = (i + 1) * self
+        let mut table = [*self; 8];
+        for i in 1..8 {
+            table[i] = table[i - 1].add(self);
+        }
+
+        let digits = scalar.to_radix_16();
+        let mut ret = Self::select(&table, digits[63]);
+        for digit in digits[..63].iter().rev() {
+            ret = ret.double().double().double().double();
+            ret = ret.add(&Self::select(&table, *digit));
+        }
+
+        ret
+    }
+}
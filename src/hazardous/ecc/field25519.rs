@@ -0,0 +1,455 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Arithmetic in the field of integers modulo p = 2^255 - 19.
+//!
+//! Field elements are stored as five 51-bit limbs. All operations, apart from
+//! those explicitly documented as variable-time, run in constant time.
+
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+const MASK51: u64 = (1u64 << 51) - 1;
+
+#[derive(Clone, Copy)]
+/// An element of the field GF(2^255 - 19), in radix 2^51.
+///
+/// After every operation, each limb is less than 2^52, but the representation
+/// is not necessarily canonical. Use [`FieldElement::to_bytes()`] for a canonical encoding.
+pub(crate) struct FieldElement(pub(crate) [u64; 5]);
+
+impl Zeroize for FieldElement {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ConditionallySelectable for FieldElement {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        Self([
+            u64::conditional_select(&a.0[0], &b.0[0], choice),
+            u64::conditional_select(&a.0[1], &b.0[1], choice),
+            u64::conditional_select(&a.0[2], &b.0[2], choice),
+            u64::conditional_select(&a.0[3], &b.0[3], choice),
+            u64::conditional_select(&a.0[4], &b.0[4], choice),
+        ])
+    }
+}
+
+impl ConstantTimeEq for FieldElement {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.to_bytes().ct_eq(&other.to_bytes())
+    }
+}
+
+#[inline(always)]
+/// Multiply two 64-bit integers into a 128-bit integer.
+fn m(a: u64, b: u64) -> u128 {
+    (a as u128) * (b as u128)
+}
+
+impl FieldElement {
+    pub(crate) const ZERO: Self = Self([0, 0, 0, 0, 0]);
+
+    pub(crate) const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// -121665/121666
+    pub(crate) const EDWARDS_D: Self = Self([
+        929955233495203,
+        466365720129213,
+        1662059464998953,
+        2033849074728123,
+        1442794654840575,
+    ]);
+
+    /// 2 * EDWARDS_D
+    pub(crate) const EDWARDS_D2: Self = Self([
+        1859910466990425,
+        932731440258426,
+        1072319116312658,
+        1815898335770999,
+        633789495995903,
+    ]);
+
+    /// The non-negative square root of -1.
+    pub(crate) const SQRT_M1: Self = Self([
+        1718705420411056,
+        234908883556509,
+        2233514472574048,
+        2117202627021982,
+        765476049583133,
+    ]);
+
+    /// Carry the limbs, so that each limb is less than 2^52.
+    fn reduce(mut limbs: [u64; 5]) -> Self {
+        let c0 = limbs[0] >> 51;
+        let c1 = limbs[1] >> 51;
+        let c2 = limbs[2] >> 51;
+        let c3 = limbs[3] >> 51;
+        let c4 = limbs[4] >> 51;
+
+        limbs[0] &= MASK51;
+        limbs[1] &= MASK51;
+        limbs[2] &= MASK51;
+        limbs[3] &= MASK51;
+        limbs[4] &= MASK51;
+
+        // 2^255 = 19 (mod p)
+        limbs[0] += c4 * 19;
+        limbs[1] += c0;
+        limbs[2] += c1;
+        limbs[3] += c2;
+        limbs[4] += c3;
+
+        Self(limbs)
+    }
+
+    /// Decode a field element from 32 little-endian bytes. The most significant
+    /// bit is ignored and the value is not required to be reduced modulo p.
+    pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load8 = |input: &[u8]| -> u64 {
+            let mut tmp = [0u8; 8];
+            tmp.copy_from_slice(&input[..8]);
+            u64::from_le_bytes(tmp)
+        };
+
+        Self([
+            load8(&bytes[0..]) & MASK51,
+            (load8(&bytes[6..]) >> 3) & MASK51,
+            (load8(&bytes[12..]) >> 6) & MASK51,
+            (load8(&bytes[19..]) >> 1) & MASK51,
+            (load8(&bytes[24..]) >> 12) & MASK51,
+        ])
+    }
+
+    /// Encode the field element as 32 little-endian bytes, fully reduced modulo p.
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = Self::reduce(self.0).0;
+
+        // Compute q = 1 if limbs >= p, else 0. Then limbs - q*p is the canonical value.
+        let mut q = (limbs[0] + 19) >> 51;
+        q = (limbs[1] + q) >> 51;
+        q = (limbs[2] + q) >> 51;
+        q = (limbs[3] + q) >> 51;
+        q = (limbs[4] + q) >> 51;
+
+        limbs[0] += 19 * q;
+
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= MASK51;
+        limbs[2] += limbs[1] >> 51;
+        limbs[1] &= MASK51;
+        limbs[3] += limbs[2] >> 51;
+        limbs[2] &= MASK51;
+        limbs[4] += limbs[3] >> 51;
+        limbs[3] &= MASK51;
+        // The carry out of the top limb is 2^255, which is discarded.
+        limbs[4] &= MASK51;
+
+        let mut out = [0u8; 32];
+        let packed: [u64; 4] = [
+            limbs[0] | (limbs[1] << 51),
+            (limbs[1] >> 13) | (limbs[2] << 38),
+            (limbs[2] >> 26) | (limbs[3] << 25),
+            (limbs[3] >> 39) | (limbs[4] << 12),
+        ];
+        for (chunk, word) in out.chunks_exact_mut(8).zip(packed.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        out
+    }
+
+    pub(crate) fn add(&self, rhs: &Self) -> Self {
+        Self::reduce([
+            self.0[0] + rhs.0[0],
+            self.0[1] + rhs.0[1],
+            self.0[2] + rhs.0[2],
+            self.0[3] + rhs.0[3],
+            self.0[4] + rhs.0[4],
+        ])
+    }
+
+    pub(crate) fn sub(&self, rhs: &Self) -> Self {
+        // Add 16*p to avoid underflow, since each limb of rhs is less than 2^52.
+        Self::reduce([
+            (self.0[0] + 36028797018963664u64) - rhs.0[0],
+            (self.0[1] + 36028797018963952u64) - rhs.0[1],
+            (self.0[2] + 36028797018963952u64) - rhs.0[2],
+            (self.0[3] + 36028797018963952u64) - rhs.0[3],
+            (self.0[4] + 36028797018963952u64) - rhs.0[4],
+        ])
+    }
+
+    pub(crate) fn mul(&self, rhs: &Self) -> Self {
+        let a = &self.0;
+        let b = &rhs.0;
+
+        let b1_19 = b[1] * 19;
+        let b2_19 = b[2] * 19;
+        let b3_19 = b[3] * 19;
+        let b4_19 = b[4] * 19;
+
+        let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+        let mut c1 =
+            m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+        let mut c2 =
+            m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+        Self::carry_wide(c0, &mut c1, &mut c2, &mut c3, &mut c4)
+    }
+
+    pub(crate) fn square(&self) -> Self {
+        let a = &self.0;
+
+        let a3_19 = a[3] * 19;
+        let a4_19 = a[4] * 19;
+
+        let c0 = m(a[0], a[0]) + 2 * (m(a[1], a4_19) + m(a[2], a3_19));
+        let mut c1 = m(a[3], a3_19) + 2 * (m(a[0], a[1]) + m(a[2], a4_19));
+        let mut c2 = m(a[1], a[1]) + 2 * (m(a[0], a[2]) + m(a[4], a3_19));
+        let mut c3 = m(a[4], a4_19) + 2 * (m(a[0], a[3]) + m(a[1], a[2]));
+        let mut c4 = m(a[2], a[2]) + 2 * (m(a[0], a[4]) + m(a[1], a[3]));
+
+        Self::carry_wide(c0, &mut c1, &mut c2, &mut c3, &mut c4)
+    }
+
+    /// Reduce the 128-bit limbs of a product into a field element.
+    fn carry_wide(c0: u128, c1: &mut u128, c2: &mut u128, c3: &mut u128, c4: &mut u128) -> Self {
+        let mut out = [0u64; 5];
+
+        *c1 += c0 >> 51;
+        out[0] = (c0 as u64) & MASK51;
+        *c2 += *c1 >> 51;
+        out[1] = (*c1 as u64) & MASK51;
+        *c3 += *c2 >> 51;
+        out[2] = (*c2 as u64) & MASK51;
+        *c4 += *c3 >> 51;
+        out[3] = (*c3 as u64) & MASK51;
+        let carry = (*c4 >> 51) as u64;
+        out[4] = (*c4 as u64) & MASK51;
+
+        out[0] += carry * 19;
+        out[1] += out[0] >> 51;
+        out[0] &= MASK51;
+
+        Self(out)
+    }
+
+    /// Compute self^(2^k), for k > 0.
+    fn pow2k(&self, k: u32) -> Self {
+        debug_assert!(k > 0);
+        let mut out = self.square();
+        for _ in 1..k {
+            out = out.square();
+        }
+
+        out
+    }
+
+    /// Compute self^(2^250 - 1).
+    fn pow22501(&self) -> Self {
+        let t0 = self.square(); // 2
+        let t1 = t0.square().square(); // 8
+        let t2 = self.mul(&t1); // 9
+        let t3 = t0.mul(&t2); // 11
+        let t4 = t3.square(); // 22
+        let t5 = t2.mul(&t4); // 2^5 - 1
+        let t6 = t5.pow2k(5); // 2^10 - 2^5
+        let t7 = t6.mul(&t5); // 2^10 - 1
+        let t8 = t7.pow2k(10); // 2^20 - 2^10
+        let t9 = t8.mul(&t7); // 2^20 - 1
+        let t10 = t9.pow2k(20); // 2^40 - 2^20
+        let t11 = t10.mul(&t9); // 2^40 - 1
+        let t12 = t11.pow2k(10); // 2^50 - 2^10
+        let t13 = t12.mul(&t7); // 2^50 - 1
+        let t14 = t13.pow2k(50); // 2^100 - 2^50
+        let t15 = t14.mul(&t13); // 2^100 - 1
+        let t16 = t15.pow2k(100); // 2^200 - 2^100
+        let t17 = t16.mul(&t15); // 2^200 - 1
+        let t18 = t17.pow2k(50); // 2^250 - 2^50
+        t18.mul(&t13) // 2^250 - 1
+    }
+
+    /// Compute self^(p - 2) = self^-1. Returns zero if self is zero.
+    pub(crate) fn invert(&self) -> Self {
+        let t0 = self.square(); // 2
+        let t1 = t0.square().square(); // 8
+        let t2 = t0.mul(&self.mul(&t1)); // 11
+                                         // 2^255 - 2^5 + 11 = 2^255 - 21
+        self.pow22501().pow2k(5).mul(&t2)
+    }
+
+    /// Compute self^((p - 5) / 8) = self^(2^252 - 3).
+    fn pow_p58(&self) -> Self {
+        // 2^252 - 4 + 1 = 2^252 - 3
+        self.pow22501().pow2k(2).mul(self)
+    }
+
+    /// Return whether the canonical encoding of the element is odd.
+    pub(crate) fn is_negative(&self) -> Choice {
+        Choice::from(self.to_bytes()[0] & 1)
+    }
+
+    pub(crate) fn is_zero(&self) -> Choice {
+        self.to_bytes().ct_eq(&[0u8; 32])
+    }
+
+    /// Return the absolute (non-negative) value of the element.
+    pub(crate) fn abs(&self) -> Self {
+        let mut out = *self;
+        out.conditional_negate(self.is_negative());
+        out
+    }
+
+    /// Compute the non-negative square root of u/v, as specified in RFC 9496, Section 4.2.
+    ///
+    /// Returns `(Choice(1), sqrt(u/v))` if u/v is square, `(Choice(1), 0)` if u is zero,
+    /// `(Choice(0), 0)` if v is zero and u is nonzero and `(Choice(0), sqrt(i*u/v))` otherwise.
+    pub(crate) fn sqrt_ratio_m1(u: &Self, v: &Self) -> (Choice, Self) {
+        let v3 = v.square().mul(v);
+        let v7 = v3.square().mul(v);
+        let mut r = u.mul(&v3).mul(&u.mul(&v7).pow_p58());
+        let check = v.mul(&r.square());
+
+        let u_neg = -u;
+        let correct_sign_sqrt = check.ct_eq(u);
+        let flipped_sign_sqrt = check.ct_eq(&u_neg);
+        let flipped_sign_sqrt_i = check.ct_eq(&u_neg.mul(&Self::SQRT_M1));
+
+        let r_prime = Self::SQRT_M1.mul(&r);
+        r.conditional_assign(&r_prime, flipped_sign_sqrt | flipped_sign_sqrt_i);
+
+        (correct_sign_sqrt | flipped_sign_sqrt, r.abs())
+    }
+}
+
+impl core::ops::Neg for &FieldElement {
+    type Output = FieldElement;
+
+    fn neg(self) -> FieldElement {
+        FieldElement::ZERO.sub(self)
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    fn fe(n: u64) -> FieldElement {
+        FieldElement([n, 0, 0, 0, 0])
+    }
+
+    #[test]
+    fn test_invert() {
+        assert_eq!(FieldElement::ZERO.invert().to_bytes(), [0u8; 32]);
+        assert_eq!(
+            FieldElement::ONE.invert().to_bytes(),
+            FieldElement::ONE.to_bytes()
+        );
+        for n in [2u64, 19, 121665, 1 << 50].iter() {
+            assert_eq!(
+                fe(*n).invert().mul(&fe(*n)).to_bytes(),
+                FieldElement::ONE.to_bytes()
+            );
+        }
+        assert_eq!(
+            FieldElement::SQRT_M1.invert().to_bytes(),
+            (-&FieldElement::SQRT_M1).to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_to_bytes_canonical() {
+        // p
+        let mut p = [0xffu8; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(FieldElement::from_bytes(&p).to_bytes(), [0u8; 32]);
+
+        // p + 1
+        p[0] = 0xee;
+        let mut one = [0u8; 32];
+        one[0] = 1;
+        assert_eq!(FieldElement::from_bytes(&p).to_bytes(), one);
+
+        // 2^255 - 1, which is p + 18
+        let mut max = [0xffu8; 32];
+        max[31] = 0x7f;
+        let mut eighteen = [0u8; 32];
+        eighteen[0] = 18;
+        assert_eq!(FieldElement::from_bytes(&max).to_bytes(), eighteen);
+
+        // The most significant bit is ignored.
+        max[31] = 0xff;
+        assert_eq!(FieldElement::from_bytes(&max).to_bytes(), eighteen);
+    }
+
+    #[test]
+    fn test_add_sub_neg() {
+        assert!(bool::from(fe(5).add(&fe(7)).ct_eq(&fe(12))));
+        assert!(bool::from(fe(5).sub(&fe(7)).add(&fe(2)).is_zero()));
+        assert!(bool::from((-&fe(1)).add(&fe(1)).is_zero()));
+        assert!(bool::from((-&FieldElement::ZERO).is_zero()));
+        // -1 = p - 1 is even, so it's not negative, but 1 is.
+        assert!(!bool::from((-&fe(1)).is_negative()));
+        assert!(bool::from(fe(1).is_negative()));
+    }
+
+    #[test]
+    fn test_mul_square() {
+        let x = FieldElement::EDWARDS_D;
+        assert!(bool::from(x.square().ct_eq(&x.mul(&x))));
+        assert!(bool::from(x.mul(&fe(2)).ct_eq(&FieldElement::EDWARDS_D2)));
+        assert!(bool::from(x.pow2k(3).ct_eq(&x.square().square().square())));
+        // SQRT_M1^2 = -1
+        assert!(bool::from(
+            FieldElement::SQRT_M1.square().ct_eq(&-&FieldElement::ONE)
+        ));
+    }
+
+    #[test]
+    fn test_sqrt_ratio_m1() {
+        let (was_square, r) = FieldElement::sqrt_ratio_m1(&FieldElement::ZERO, &fe(3));
+        assert!(bool::from(was_square));
+        assert!(bool::from(r.is_zero()));
+
+        let (was_square, r) = FieldElement::sqrt_ratio_m1(&fe(3), &FieldElement::ZERO);
+        assert!(!bool::from(was_square));
+        assert!(bool::from(r.is_zero()));
+
+        let (was_square, r) = FieldElement::sqrt_ratio_m1(&fe(36), &fe(9));
+        assert!(bool::from(was_square));
+        assert!(bool::from(r.ct_eq(&fe(2))));
+
+        // 2 is not a square modulo p, so the result is sqrt(i * 2).
+        let (was_square, r) = FieldElement::sqrt_ratio_m1(&fe(2), &FieldElement::ONE);
+        assert!(!bool::from(was_square));
+        assert!(!bool::from(r.is_negative()));
+        assert!(bool::from(
+            r.square().ct_eq(&FieldElement::SQRT_M1.mul(&fe(2)))
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/// Arithmetic modulo 2^255 - 19.
+mod field25519;
+
+/// Arithmetic modulo the order of the Curve25519 prime-order subgroup.
+mod scalar25519;
+
+/// Group operations on the Edwards form of Curve25519.
+mod edwards25519;
+
+/// X25519, as specified in the [RFC 7748](https://www.rfc-editor.org/rfc/rfc7748.html).
+pub(crate) mod x25519;
+
+/// The ristretto255 prime-order group, as specified in the [RFC 9496](https://www.rfc-editor.org/rfc/rfc9496.html).
+pub mod ristretto255;
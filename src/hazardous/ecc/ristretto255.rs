@@ -0,0 +1,919 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! The ristretto255 prime-order group, as specified in the [RFC 9496], built on
+//! Curve25519. It provides a group of prime order `l = 2^252 + 27742317777372353535851937790883648493`
+//! without the cofactor pitfalls of the underlying curve, which makes it a suitable
+//! foundation for protocols such as PAKEs, VRFs, OPRFs and anonymous credentials.
+//!
+//! [`Point`] is an element of the group and [`Scalar`] is an integer modulo `l`.
+//! Points support addition, subtraction and negation, and can be multiplied by a
+//! scalar. Scalars support addition, subtraction, multiplication, negation and
+//! inversion. All of these are available through the standard operator traits.
+//!
+//! [`Point::hash_to_group()`] and [`Scalar::hash_to_scalar()`] implement
+//! `hash_to_ristretto255` from the [RFC 9380] and `HashToScalar` from the [RFC 9497],
+//! both using `expand_message_xmd` with SHA-512.
+//!
+//! # Parameters:
+//! - `bytes`: The encoding of a point or scalar, or uniformly random bytes.
+//! - `input`: The input to hash to a point or scalar.
+//! - `dst`: A domain separation tag, unique to the protocol and the use of the hash.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `bytes` is not [`RISTRETTO255_POINTSIZE`] when decoding a [`Point`].
+//! - The length of `bytes` is not [`RISTRETTO255_SCALARSIZE`] when decoding a [`Scalar`].
+//! - The length of `bytes` is not [`RISTRETTO255_UNIFORMSIZE`] when using `from_uniform_bytes()`.
+//! - `bytes` is not the canonical encoding of a [`Point`] or [`Scalar`].
+//! - The length of `dst` is less than 1 or greater than 255.
+//! - [`Scalar::invert()`] is called on a zero scalar.
+//!
+//! # Security:
+//! - All operations on points and scalars run in constant time.
+//! - Decoding a [`Point`] checks that the encoding is canonical, so every
+//!   point has exactly one valid encoding.
+//! - A scalar that is used as a secret key should be generated with [`Scalar::generate()`].
+//! - Secret scalars are zeroized when dropped. Points are considered public.
+//! - The identity point is a valid group element. Protocols that must reject it, such as
+//!   Diffie-Hellman, should check [`Point::is_identity()`] on the received or computed points.
+//! - `dst` should be at least 16 bytes long, as recommended by the [RFC 9380].
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::ecc::ristretto255::{Point, Scalar};
+//!
+//! // Diffie-Hellman over ristretto255.
+//! let alice_sk = Scalar::generate();
+//! let alice_pk = Point::mul_base(&alice_sk);
+//! let bob_sk = Scalar::generate();
+//! let bob_pk = Point::mul_base(&bob_sk);
+//!
+//! let alice_shared = Point::from_slice(&bob_pk.to_bytes())? * &alice_sk;
+//! let bob_shared = Point::from_slice(&alice_pk.to_bytes())? * &bob_sk;
+//! assert_eq!(alice_shared, bob_shared);
+//! assert!(!alice_shared.is_identity());
+//!
+//! // Hashing to the group.
+//! let dst = b"orion-example-ristretto255-v1";
+//! let h = Point::hash_to_group(b"some input", dst)?;
+//! let r = Scalar::hash_to_scalar(b"other input", dst)?;
+//! assert_eq!((h * &r) * &r.invert()?, h);
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [RFC 9496]: https://www.rfc-editor.org/rfc/rfc9496.html
+//! [RFC 9380]: https://www.rfc-editor.org/rfc/rfc9380.html
+//! [RFC 9497]: https://www.rfc-editor.org/rfc/rfc9497.html
+
+use super::edwards25519::EdwardsPoint;
+use super::field25519::FieldElement;
+use super::scalar25519::Scalar52;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::sha2::sha512::{Sha512, SHA512_OUTSIZE};
+use subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// The size of an encoded ristretto255 [`Point`].
+pub const RISTRETTO255_POINTSIZE: usize = 32;
+
+/// The size of an encoded ristretto255 [`Scalar`].
+pub const RISTRETTO255_SCALARSIZE: usize = 32;
+
+/// The number of uniformly random bytes used by `from_uniform_bytes()`.
+pub const RISTRETTO255_UNIFORMSIZE: usize = 64;
+
+/// 1/sqrt(a - d)
+const INVSQRT_A_MINUS_D: FieldElement = FieldElement([
+    278908739862762,
+    821645201101625,
+    8113234426968,
+    1777959178193151,
+    2118520810568447,
+]);
+
+/// sqrt(a*d - 1)
+const SQRT_AD_MINUS_ONE: FieldElement = FieldElement([
+    2241493124984347,
+    425987919032274,
+    2207028919301688,
+    1220490630685848,
+    974799131293748,
+]);
+
+/// 1 - d^2
+const ONE_MINUS_D_SQ: FieldElement = FieldElement([
+    1136626929484150,
+    1998550399581263,
+    496427632559748,
+    118527312129759,
+    45110755273534,
+]);
+
+/// (d - 1)^2
+const D_MINUS_ONE_SQ: FieldElement = FieldElement([
+    1507062230895904,
+    1572317787530805,
+    683053064812840,
+    317374165784489,
+    1572899562415810,
+]);
+
+/// `expand_message_xmd` from the RFC 9380, using SHA-512 and producing
+/// [`RISTRETTO255_UNIFORMSIZE`] bytes.
+fn expand_message_xmd(
+    input: &[u8],
+    dst: &[u8],
+) -> Result<[u8; RISTRETTO255_UNIFORMSIZE], UnknownCryptoError> {
+    if dst.is_empty() || dst.len() > 255 {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    // The output is exactly one SHA-512 block, so only b_0 and b_1 are needed.
+    debug_assert_eq!(RISTRETTO255_UNIFORMSIZE, SHA512_OUTSIZE);
+    let dst_len = [dst.len() as u8];
+
+    let mut b_0 = [0u8; SHA512_OUTSIZE];
+    let mut ctx = Sha512::new();
+    ctx.update(&[0u8; 128])?;
+    ctx.update(input)?;
+    ctx.update(&(RISTRETTO255_UNIFORMSIZE as u16).to_be_bytes())?;
+    ctx.update(&[0u8])?;
+    ctx.update(dst)?;
+    ctx.update(&dst_len)?;
+    ctx._finalize_internal(&mut b_0)?;
+
+    let mut b_1 = [0u8; RISTRETTO255_UNIFORMSIZE];
+    ctx.reset();
+    ctx.update(&b_0)?;
+    ctx.update(&[1u8])?;
+    ctx.update(dst)?;
+    ctx.update(&dst_len)?;
+    ctx._finalize_internal(&mut b_1)?;
+    b_0.zeroize();
+
+    Ok(b_1)
+}
+
+#[derive(Clone, Copy)]
+/// An element of the ristretto255 group.
+///
+/// # Security:
+/// - Points are considered public values and are not zeroized on drop.
+/// - The [`PartialEq`] implementation is constant time and compares the
+///   group elements, not the internal representatives.
+///
+/// With the `serde` feature, a point is serialized as its canonical encoding, and
+/// deserializing it performs the same checks as [`Point::from_slice()`].
+pub struct Point {
+    repr: EdwardsPoint,
+}
+
+impl_serde_traits!(Point, to_bytes);
+impl_try_from_traits!(Point);
+
+impl Point {
+    /// The identity element of the group.
+    pub fn identity() -> Self {
+        Self {
+            repr: EdwardsPoint::IDENTITY,
+        }
+    }
+
+    /// The generator of the group, which is the ristretto255 encoding
+    /// of the Ed25519 basepoint.
+    pub fn generator() -> Self {
+        Self {
+            repr: EdwardsPoint::BASEPOINT,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decode a point from its canonical encoding, as specified in the RFC 9496, Section 4.3.1.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if bytes.len() != RISTRETTO255_POINTSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        let mut s_bytes = [0u8; RISTRETTO255_POINTSIZE];
+        s_bytes.copy_from_slice(bytes);
+
+        let s = FieldElement::from_bytes(&s_bytes);
+        // Checks that s is less than p and that the most significant bit is unset.
+        let s_is_canonical = s.to_bytes().ct_eq(&s_bytes);
+        let s_is_negative = s.is_negative();
+
+        let ss = s.square();
+        let u1 = FieldElement::ONE.sub(&ss);
+        let u2 = FieldElement::ONE.add(&ss);
+        let u2_sqr = u2.square();
+
+        let v = (-&FieldElement::EDWARDS_D.mul(&u1.square())).sub(&u2_sqr);
+        let (was_square, invsqrt) =
+            FieldElement::sqrt_ratio_m1(&FieldElement::ONE, &v.mul(&u2_sqr));
+
+        let den_x = invsqrt.mul(&u2);
+        let den_y = invsqrt.mul(&den_x).mul(&v);
+
+        let x = s.add(&s).mul(&den_x).abs();
+        let y = u1.mul(&den_y);
+        let t = x.mul(&y);
+
+        let is_valid =
+            s_is_canonical & !s_is_negative & was_square & !t.is_negative() & !y.is_zero();
+        if bool::from(is_valid) {
+            Ok(Self {
+                repr: EdwardsPoint {
+                    x,
+                    y,
+                    z: FieldElement::ONE,
+                    t,
+                },
+            })
+        } else {
+            Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding))
+        }
+    }
+
+    /// Encode the point, as specified in the RFC 9496, Section 4.3.2.
+    pub fn to_bytes(&self) -> [u8; RISTRETTO255_POINTSIZE] {
+        let EdwardsPoint {
+            x: x0,
+            y: y0,
+            z: z0,
+            t: t0,
+        } = self.repr;
+
+        let u1 = z0.add(&y0).mul(&z0.sub(&y0));
+        let u2 = x0.mul(&y0);
+        let (_, invsqrt) = FieldElement::sqrt_ratio_m1(&FieldElement::ONE, &u1.mul(&u2.square()));
+
+        let den1 = invsqrt.mul(&u1);
+        let den2 = invsqrt.mul(&u2);
+        let z_inv = den1.mul(&den2).mul(&t0);
+
+        let ix0 = x0.mul(&FieldElement::SQRT_M1);
+        let iy0 = y0.mul(&FieldElement::SQRT_M1);
+        let enchanted_denominator = den1.mul(&INVSQRT_A_MINUS_D);
+
+        let rotate = t0.mul(&z_inv).is_negative();
+        let x = FieldElement::conditional_select(&x0, &iy0, rotate);
+        let mut y = FieldElement::conditional_select(&y0, &ix0, rotate);
+        let den_inv = FieldElement::conditional_select(&den2, &enchanted_denominator, rotate);
+
+        y.conditional_negate(x.mul(&z_inv).is_negative());
+
+        den_inv.mul(&z0.sub(&y)).abs().to_bytes()
+    }
+
+    /// The MAP function from the RFC 9496, Section 4.3.4.
+    fn map(t: &FieldElement) -> EdwardsPoint {
+        let r = FieldElement::SQRT_M1.mul(&t.square());
+        let u = r.add(&FieldElement::ONE).mul(&ONE_MINUS_D_SQ);
+        let v = (-&FieldElement::ONE)
+            .sub(&r.mul(&FieldElement::EDWARDS_D))
+            .mul(&r.add(&FieldElement::EDWARDS_D));
+
+        let (was_square, mut s) = FieldElement::sqrt_ratio_m1(&u, &v);
+        let s_prime = -&s.mul(t).abs();
+        s.conditional_assign(&s_prime, !was_square);
+        let c = FieldElement::conditional_select(&r, &-&FieldElement::ONE, was_square);
+
+        let n = c
+            .mul(&r.sub(&FieldElement::ONE))
+            .mul(&D_MINUS_ONE_SQ)
+            .sub(&v);
+
+        let s_sq = s.square();
+        let w0 = s.add(&s).mul(&v);
+        let w1 = n.mul(&SQRT_AD_MINUS_ONE);
+        let w2 = FieldElement::ONE.sub(&s_sq);
+        let w3 = FieldElement::ONE.add(&s_sq);
+
+        EdwardsPoint {
+            x: w0.mul(&w3),
+            y: w2.mul(&w1),
+            z: w1.mul(&w3),
+            t: w0.mul(&w2),
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a point from [`RISTRETTO255_UNIFORMSIZE`] uniformly random bytes,
+    /// as specified in the RFC 9496, Section 4.3.4.
+    pub fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if bytes.len() != RISTRETTO255_UNIFORMSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let mut half = [0u8; 32];
+        half.copy_from_slice(&bytes[..32]);
+        // FieldElement::from_bytes() ignores the most significant bit.
+        let p1 = Self::map(&FieldElement::from_bytes(&half));
+        half.copy_from_slice(&bytes[32..]);
+        let p2 = Self::map(&FieldElement::from_bytes(&half));
+
+        Ok(Self { repr: p1.add(&p2) })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Hash `input` to a point, using `hash_to_ristretto255` from the RFC 9380
+    /// with the domain separation tag `dst`.
+    pub fn hash_to_group(input: &[u8], dst: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut uniform_bytes = expand_message_xmd(input, dst)?;
+        let ret = Self::from_uniform_bytes(&uniform_bytes);
+        uniform_bytes.zeroize();
+
+        ret
+    }
+
+    /// Compute `scalar * G`, where `G` is the [`Point::generator()`].
+    pub fn mul_base(scalar: &Scalar) -> Self {
+        Self::generator() * scalar
+    }
+
+    /// Return `true` if this is the identity element of the group.
+    pub fn is_identity(&self) -> bool {
+        self == &Self::identity()
+    }
+}
+
+impl ConstantTimeEq for Point {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // RFC 9496, Section 4.3.3.
+        let x1y2 = self.repr.x.mul(&other.repr.y);
+        let y1x2 = self.repr.y.mul(&other.repr.x);
+        let y1y2 = self.repr.y.mul(&other.repr.y);
+        let x1x2 = self.repr.x.mul(&other.repr.x);
+
+        x1y2.ct_eq(&y1x2) | y1y2.ct_eq(&x1x2)
+    }
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Point {}
+
+impl core::fmt::Debug for Point {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Point {:?}", &self.to_bytes()[..])
+    }
+}
+
+impl core::ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Point) -> Point {
+        Point {
+            repr: self.repr.add(&rhs.repr),
+        }
+    }
+}
+
+impl core::ops::Sub for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Point) -> Point {
+        Point {
+            repr: self.repr.sub(&rhs.repr),
+        }
+    }
+}
+
+impl core::ops::Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point { repr: -&self.repr }
+    }
+}
+
+impl core::ops::Mul<&Scalar> for Point {
+    type Output = Point;
+
+    fn mul(self, rhs: &Scalar) -> Point {
+        let mut s = rhs.to_scalar52();
+        let ret = Point {
+            repr: self.repr.mul(&s),
+        };
+        s.zeroize();
+
+        ret
+    }
+}
+
+/// An integer modulo the group order `l`.
+///
+/// # Security:
+/// - Scalars are zeroized when dropped, since they are often used as secret keys.
+/// - The [`Debug`](core::fmt::Debug) implementation omits the value and the
+///   [`PartialEq`] implementation is constant time.
+pub struct Scalar {
+    value: [u8; RISTRETTO255_SCALARSIZE],
+}
+
+impl Scalar {
+    fn from_scalar52(s: &Scalar52) -> Self {
+        Self {
+            value: s.to_bytes(),
+        }
+    }
+
+    fn to_scalar52(&self) -> Scalar52 {
+        // NOTE: self.value is always fully reduced.
+        Scalar52::from_reduced_bytes(&self.value)
+    }
+
+    /// The scalar zero.
+    pub fn zero() -> Self {
+        Self::from_scalar52(&Scalar52::ZERO)
+    }
+
+    /// The scalar one.
+    pub fn one() -> Self {
+        Self::from_scalar52(&Scalar52::ONE)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decode a scalar from its canonical little-endian encoding, which must be less than `l`.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if bytes.len() != RISTRETTO255_SCALARSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        let mut tmp = [0u8; RISTRETTO255_SCALARSIZE];
+        tmp.copy_from_slice(bytes);
+        let (is_canonical, mut s) = Scalar52::from_canonical_bytes(&tmp);
+        tmp.zeroize();
+
+        let ret = if bool::from(is_canonical) {
+            Ok(Self::from_scalar52(&s))
+        } else {
+            Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding))
+        };
+        s.zeroize();
+
+        ret
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a scalar from [`RISTRETTO255_UNIFORMSIZE`] uniformly random bytes,
+    /// by interpreting them as a little-endian integer and reducing it modulo `l`.
+    pub fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if bytes.len() != RISTRETTO255_UNIFORMSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        let mut tmp = [0u8; RISTRETTO255_UNIFORMSIZE];
+        tmp.copy_from_slice(bytes);
+        let mut s = Scalar52::from_bytes_wide(&tmp);
+        let ret = Self::from_scalar52(&s);
+        tmp.zeroize();
+        s.zeroize();
+
+        Ok(ret)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Hash `input` to a scalar, using `HashToScalar` for ristretto255 from the RFC 9497
+    /// with the domain separation tag `dst`.
+    pub fn hash_to_scalar(input: &[u8], dst: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let mut uniform_bytes = expand_message_xmd(input, dst)?;
+        let ret = Self::from_uniform_bytes(&uniform_bytes);
+        uniform_bytes.zeroize();
+
+        ret
+    }
+
+    #[cfg(feature = "safe_api")]
+    /// Randomly generate a scalar using a CSPRNG. Not available in `no_std` context.
+    pub fn generate() -> Self {
+        let mut tmp = [0u8; RISTRETTO255_UNIFORMSIZE];
+        crate::util::secure_rand_bytes_or_panic(&mut tmp);
+        let mut s = Scalar52::from_bytes_wide(&tmp);
+        let ret = Self::from_scalar52(&s);
+        tmp.zeroize();
+        s.zeroize();
+
+        ret
+    }
+
+    /// Return the canonical little-endian encoding of the scalar.
+    ///
+    /// # Security:
+    /// - Avoid using this function in most cases. Comparing scalars should
+    ///   be done using the [`PartialEq`] implementation, which runs in constant time.
+    pub fn unprotected_as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the multiplicative inverse of the scalar, modulo `l`.
+    pub fn invert(&self) -> Result<Self, UnknownCryptoError> {
+        if bool::from(self.value.ct_eq(&[0u8; RISTRETTO255_SCALARSIZE])) {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+        }
+
+        let mut s = self.to_scalar52();
+        let mut inv = Scalar52::invert(&s);
+        let ret = Self::from_scalar52(&inv);
+        s.zeroize();
+        inv.zeroize();
+
+        Ok(ret)
+    }
+}
+
+impl_try_from_traits!(secret Scalar);
+
+impl From<u64> for Scalar {
+    fn from(value: u64) -> Self {
+        let mut bytes = [0u8; RISTRETTO255_SCALARSIZE];
+        bytes[..8].copy_from_slice(&value.to_le_bytes());
+        // NOTE: Any u64 is less than l, so the encoding is already canonical.
+        Self { value: bytes }
+    }
+}
+
+impl Clone for Scalar {
+    fn clone(&self) -> Self {
+        Self { value: self.value }
+    }
+}
+
+impl Drop for Scalar {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl ConstantTimeEq for Scalar {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.value.ct_eq(&other.value)
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for Scalar {}
+
+impl core::fmt::Debug for Scalar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Scalar {{***OMITTED***}}")
+    }
+}
+
+/// Implement a binary operator on references to [`Scalar`], through a function on [`Scalar52`].
+macro_rules! impl_scalar_binop (($trait:ident, $method:ident, $func:path) => (
+    impl core::ops::$trait<&Scalar> for &Scalar {
+        type Output = Scalar;
+
+        fn $method(self, rhs: &Scalar) -> Scalar {
+            let mut a = self.to_scalar52();
+            let mut b = rhs.to_scalar52();
+            let mut res = $func(&a, &b);
+            let ret = Scalar::from_scalar52(&res);
+            a.zeroize();
+            b.zeroize();
+            res.zeroize();
+
+            ret
+        }
+    }
+));
+
+impl_scalar_binop!(Add, add, Scalar52::add);
+impl_scalar_binop!(Sub, sub, Scalar52::sub);
+impl_scalar_binop!(Mul, mul, Scalar52::mul);
+
+impl core::ops::Neg for &Scalar {
+    type Output = Scalar;
+
+    fn neg(self) -> Scalar {
+        let mut a = self.to_scalar52();
+        let mut res = Scalar52::neg(&a);
+        let ret = Scalar::from_scalar52(&res);
+        a.zeroize();
+        res.zeroize();
+
+        ret
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const GENERATOR: &str = "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76";
+
+    /// l - 1, the largest canonical scalar.
+    const L_MINUS_ONE: &str = "ecd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010";
+
+    fn scalar(hex_str: &str) -> Scalar {
+        Scalar::from_slice(&hex::decode(hex_str).unwrap()).unwrap()
+    }
+
+    mod test_point {
+        use super::*;
+
+        #[test]
+        fn test_identity_encoding() {
+            assert_eq!(Point::identity().to_bytes(), [0u8; RISTRETTO255_POINTSIZE]);
+            assert_eq!(
+                Point::from_slice(&[0u8; RISTRETTO255_POINTSIZE]).unwrap(),
+                Point::identity()
+            );
+            assert!(Point::identity().is_identity());
+            assert!(!Point::generator().is_identity());
+        }
+
+        #[test]
+        fn test_generator_encoding() {
+            assert_eq!(
+                Point::generator().to_bytes()[..],
+                hex::decode(GENERATOR).unwrap()[..]
+            );
+            assert_eq!(
+                Point::from_slice(&hex::decode(GENERATOR).unwrap()).unwrap(),
+                Point::generator()
+            );
+        }
+
+        #[test]
+        fn test_from_slice_bad_length() {
+            assert_eq!(
+                Point::from_slice(&[0u8; RISTRETTO255_POINTSIZE - 1])
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidLength
+            );
+            assert_eq!(
+                Point::from_slice(&[0u8; RISTRETTO255_POINTSIZE + 1])
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidLength
+            );
+            assert!(Point::from_slice(&[]).is_err());
+        }
+
+        #[test]
+        fn test_from_slice_bad_encoding() {
+            // s = 1 is negative.
+            let mut bytes = [0u8; RISTRETTO255_POINTSIZE];
+            bytes[0] = 1;
+            assert_eq!(
+                Point::from_slice(&bytes).unwrap_err().kind(),
+                ErrorKind::InvalidEncoding
+            );
+            // The most significant bit is set.
+            let mut bytes = [0u8; RISTRETTO255_POINTSIZE];
+            bytes[31] = 0x80;
+            assert_eq!(
+                Point::from_slice(&bytes).unwrap_err().kind(),
+                ErrorKind::InvalidEncoding
+            );
+        }
+
+        #[test]
+        fn test_uniform_bytes_bad_length() {
+            assert!(Point::from_uniform_bytes(&[0u8; RISTRETTO255_UNIFORMSIZE]).is_ok());
+            assert!(Point::from_uniform_bytes(&[0u8; RISTRETTO255_UNIFORMSIZE - 1]).is_err());
+            assert!(Point::from_uniform_bytes(&[0u8; RISTRETTO255_UNIFORMSIZE + 1]).is_err());
+        }
+
+        #[test]
+        fn test_hash_to_group_dst_length() {
+            assert!(Point::hash_to_group(b"", &[0u8; 1]).is_ok());
+            assert!(Point::hash_to_group(b"", &[0u8; 255]).is_ok());
+            assert_eq!(
+                Point::hash_to_group(b"", &[]).unwrap_err().kind(),
+                ErrorKind::InvalidLength
+            );
+            assert_eq!(
+                Point::hash_to_group(b"", &[0u8; 256]).unwrap_err().kind(),
+                ErrorKind::InvalidLength
+            );
+        }
+
+        #[test]
+        fn test_hash_to_group_domain_separation() {
+            let a = Point::hash_to_group(b"input", b"orion-test-dst-one").unwrap();
+            let b = Point::hash_to_group(b"input", b"orion-test-dst-two").unwrap();
+            let c = Point::hash_to_group(b"inpuu", b"orion-test-dst-one").unwrap();
+            assert_ne!(a, b);
+            assert_ne!(a, c);
+            assert_eq!(
+                a,
+                Point::hash_to_group(b"input", b"orion-test-dst-one").unwrap()
+            );
+        }
+
+        #[test]
+        fn test_group_laws() {
+            let g = Point::generator();
+            let id = Point::identity();
+            let two_g = g + g;
+
+            assert_eq!(g + id, g);
+            assert_eq!(g - g, id);
+            assert_eq!(g + (-g), id);
+            assert_eq!(-id, id);
+            assert_eq!(two_g - g, g);
+            assert_eq!(g * &Scalar::from(2u64), two_g);
+            assert_eq!((two_g + g) + g, two_g + (g + g));
+        }
+
+        #[test]
+        fn test_mul_special_scalars() {
+            let g = Point::generator();
+            assert_eq!(g * &Scalar::zero(), Point::identity());
+            assert_eq!(g * &Scalar::one(), g);
+            assert_eq!(Point::identity() * &scalar(L_MINUS_ONE), Point::identity());
+            // (l - 1) * G = -G
+            assert_eq!(g * &scalar(L_MINUS_ONE), -g);
+            assert_eq!(Point::mul_base(&scalar(L_MINUS_ONE)), -g);
+        }
+
+        #[test]
+        fn test_encoding_roundtrip() {
+            let mut p = Point::generator();
+            for _ in 0..16 {
+                let encoded = p.to_bytes();
+                let decoded = Point::from_slice(&encoded).unwrap();
+                assert_eq!(decoded, p);
+                assert_eq!(decoded.to_bytes(), encoded);
+                p = p + p + Point::generator();
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_debug_impl() {
+            let debug = format!("{:?}", Point::identity());
+            assert_eq!(debug, format!("Point {:?}", &[0u8; 32][..]));
+        }
+
+        #[cfg(feature = "safe_api")]
+        #[quickcheck]
+        /// Scalar multiplication distributes over scalar addition.
+        fn prop_mul_distributes(a: u64, b: u64) -> bool {
+            let (a, b) = (Scalar::from(a), Scalar::from(b));
+            let g = Point::generator();
+
+            Point::mul_base(&(&a + &b)) == (g * &a) + (g * &b)
+        }
+
+        #[cfg(feature = "safe_api")]
+        #[quickcheck]
+        /// Scalar multiplication is compatible with scalar multiplication.
+        fn prop_mul_associative(a: u64, b: u64) -> bool {
+            let (a, b) = (Scalar::from(a), Scalar::from(b));
+
+            Point::mul_base(&(&a * &b)) == Point::mul_base(&a) * &b
+        }
+
+        #[cfg(feature = "safe_api")]
+        #[quickcheck]
+        /// Every point produced by hashing has a canonical encoding that decodes to itself.
+        fn prop_hash_to_group_roundtrip(input: Vec<u8>) -> bool {
+            let p = Point::hash_to_group(&input, b"orion-test-ristretto255").unwrap();
+
+            Point::from_slice(&p.to_bytes()).unwrap() == p
+        }
+    }
+
+    mod test_scalar {
+        use super::*;
+
+        #[test]
+        fn test_from_slice_canonical() {
+            assert!(Scalar::from_slice(&hex::decode(L_MINUS_ONE).unwrap()).is_ok());
+
+            // l
+            let mut bytes = hex::decode(L_MINUS_ONE).unwrap();
+            bytes[0] += 1;
+            assert_eq!(
+                Scalar::from_slice(&bytes).unwrap_err().kind(),
+                ErrorKind::InvalidEncoding
+            );
+            assert!(Scalar::from_slice(&[0xffu8; RISTRETTO255_SCALARSIZE]).is_err());
+        }
+
+        #[test]
+        fn test_from_slice_bad_length() {
+            assert_eq!(
+                Scalar::from_slice(&[0u8; RISTRETTO255_SCALARSIZE - 1])
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidLength
+            );
+            assert!(Scalar::from_slice(&[0u8; RISTRETTO255_SCALARSIZE + 1]).is_err());
+            assert!(Scalar::from_uniform_bytes(&[0u8; RISTRETTO255_UNIFORMSIZE - 1]).is_err());
+            assert!(Scalar::from_uniform_bytes(&[0u8; RISTRETTO255_UNIFORMSIZE + 1]).is_err());
+        }
+
+        #[test]
+        fn test_from_uniform_bytes_reduces() {
+            // 2^256 - 1 reduced by l is canonical.
+            let mut bytes = [0u8; RISTRETTO255_UNIFORMSIZE];
+            bytes[..32].copy_from_slice(&[0xffu8; 32]);
+            let s = Scalar::from_uniform_bytes(&bytes).unwrap();
+            assert!(Scalar::from_slice(s.unprotected_as_bytes()).is_ok());
+
+            // l reduces to zero.
+            let mut bytes = [0u8; RISTRETTO255_UNIFORMSIZE];
+            bytes[..32].copy_from_slice(&hex::decode(L_MINUS_ONE).unwrap());
+            bytes[0] += 1;
+            assert_eq!(Scalar::from_uniform_bytes(&bytes).unwrap(), Scalar::zero());
+        }
+
+        #[test]
+        fn test_arithmetic() {
+            let a = Scalar::from(7u64);
+            let b = Scalar::from(5u64);
+
+            assert_eq!(&a + &b, Scalar::from(12u64));
+            assert_eq!(&a - &b, Scalar::from(2u64));
+            assert_eq!(&a * &b, Scalar::from(35u64));
+            assert_eq!(&(&b - &a) + &Scalar::from(2u64), Scalar::zero());
+            assert_eq!(-&Scalar::one(), scalar(L_MINUS_ONE));
+            assert_eq!(-&Scalar::zero(), Scalar::zero());
+            assert_eq!(&scalar(L_MINUS_ONE) + &Scalar::one(), Scalar::zero());
+            assert_eq!(&scalar(L_MINUS_ONE) * &scalar(L_MINUS_ONE), Scalar::one());
+        }
+
+        #[test]
+        fn test_invert() {
+            assert_eq!(
+                Scalar::zero().invert().unwrap_err().kind(),
+                ErrorKind::InvalidParameter
+            );
+            assert_eq!(Scalar::one().invert().unwrap(), Scalar::one());
+            assert_eq!(scalar(L_MINUS_ONE).invert().unwrap(), scalar(L_MINUS_ONE));
+
+            let a = Scalar::from(123456789u64);
+            assert_eq!(&a * &a.invert().unwrap(), Scalar::one());
+        }
+
+        #[test]
+        fn test_hash_to_scalar_dst_length() {
+            assert!(Scalar::hash_to_scalar(b"", &[0u8; 255]).is_ok());
+            assert!(Scalar::hash_to_scalar(b"", &[]).is_err());
+            assert!(Scalar::hash_to_scalar(b"", &[0u8; 256]).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_debug_impl() {
+            let debug = format!("{:?}", Scalar::one());
+            assert_eq!(debug, "Scalar {***OMITTED***}");
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_generate() {
+            let a = Scalar::generate();
+            let b = Scalar::generate();
+            assert_ne!(a, b);
+            assert!(Scalar::from_slice(a.unprotected_as_bytes()).is_ok());
+        }
+
+        #[cfg(feature = "safe_api")]
+        #[quickcheck]
+        /// Inverting a nonzero scalar twice returns the original scalar.
+        fn prop_invert_twice(a: u64) -> bool {
+            if a == 0 {
+                return true;
+            }
+            let a = Scalar::from(a);
+
+            a.invert().unwrap().invert().unwrap() == a
+        }
+    }
+}
@@ -0,0 +1,402 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Arithmetic modulo the prime order of the Curve25519 prime-order subgroup,
+//! l = 2^252 + 27742317777372353535851937790883648493.
+//!
+//! Scalars are stored as five 52-bit limbs and multiplied using Montgomery
+//! reduction, with R = 2^260. All operations run in constant time.
+
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroize;
+
+const MASK52: u64 = (1u64 << 52) - 1;
+
+/// l
+const L: Scalar52 = Scalar52([
+    0x0002_631a_5cf5_d3ed,
+    0x000d_ea2f_79cd_6581,
+    0x0000_0000_0014_def9,
+    0x0000_0000_0000_0000,
+    0x0000_1000_0000_0000,
+]);
+
+/// -l^-1 mod 2^52
+const LFACTOR: u64 = 0x0005_1da3_1254_7e1b;
+
+/// R = 2^260 mod l
+const R: Scalar52 = Scalar52([
+    0x000f_48bd_6721_e6ed,
+    0x0003_bab5_ac67_e45a,
+    0x000f_ffff_eb35_e51b,
+    0x000f_ffff_ffff_ffff,
+    0x0000_0fff_ffff_ffff,
+]);
+
+/// RR = R^2 mod l
+const RR: Scalar52 = Scalar52([
+    0x0009_d265_e952_d13b,
+    0x000d_63c7_15be_a69f,
+    0x0005_be65_cb68_7604,
+    0x0003_dcee_c73d_217f,
+    0x0000_0941_1b7c_309a,
+]);
+
+#[derive(Clone, Copy)]
+/// An integer modulo l, in radix 2^52. Unless documented otherwise, values
+/// are always fully reduced modulo l.
+pub(crate) struct Scalar52(pub(crate) [u64; 5]);
+
+impl Zeroize for Scalar52 {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ConstantTimeEq for Scalar52 {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0[0].ct_eq(&other.0[0])
+            & self.0[1].ct_eq(&other.0[1])
+            & self.0[2].ct_eq(&other.0[2])
+            & self.0[3].ct_eq(&other.0[3])
+            & self.0[4].ct_eq(&other.0[4])
+    }
+}
+
+#[inline(always)]
+/// Multiply two 64-bit integers into a 128-bit integer.
+fn m(a: u64, b: u64) -> u128 {
+    (a as u128) * (b as u128)
+}
+
+/// Load little-endian 64-bit words from `src`.
+fn load_words<const N: usize>(src: &[u8]) -> [u64; N] {
+    debug_assert_eq!(src.len(), N * 8);
+    let mut words = [0u64; N];
+    for (word, chunk) in words.iter_mut().zip(src.chunks_exact(8)) {
+        let mut tmp = [0u8; 8];
+        tmp.copy_from_slice(chunk);
+        *word = u64::from_le_bytes(tmp);
+    }
+
+    words
+}
+
+impl Scalar52 {
+    pub(crate) const ZERO: Self = Self([0, 0, 0, 0, 0]);
+
+    pub(crate) const ONE: Self = Self([1, 0, 0, 0, 0]);
+
+    /// Unpack 32 little-endian bytes, which must already be reduced modulo l.
+    pub(crate) fn from_reduced_bytes(bytes: &[u8; 32]) -> Self {
+        let words: [u64; 4] = load_words(bytes);
+
+        Self([
+            words[0] & MASK52,
+            ((words[0] >> 52) | (words[1] << 12)) & MASK52,
+            ((words[1] >> 40) | (words[2] << 24)) & MASK52,
+            ((words[2] >> 28) | (words[3] << 36)) & MASK52,
+            words[3] >> 16,
+        ])
+    }
+
+    /// Reduce 64 little-endian bytes modulo l.
+    pub(crate) fn from_bytes_wide(bytes: &[u8; 64]) -> Self {
+        let words: [u64; 8] = load_words(bytes);
+
+        let lo = Self([
+            words[0] & MASK52,
+            ((words[0] >> 52) | (words[1] << 12)) & MASK52,
+            ((words[1] >> 40) | (words[2] << 24)) & MASK52,
+            ((words[2] >> 28) | (words[3] << 36)) & MASK52,
+            ((words[3] >> 16) | (words[4] << 48)) & MASK52,
+        ]);
+        let hi = Self([
+            (words[4] >> 4) & MASK52,
+            ((words[4] >> 56) | (words[5] << 8)) & MASK52,
+            ((words[5] >> 44) | (words[6] << 20)) & MASK52,
+            ((words[6] >> 32) | (words[7] << 32)) & MASK52,
+            words[7] >> 20,
+        ]);
+
+        // The input is lo + hi * 2^260 = lo + hi * R.
+        // (lo * R) / R = lo, and (hi * R^2) / R = hi * R.
+        let lo = Self::montgomery_mul(&lo, &R);
+        let hi = Self::montgomery_mul(&hi, &RR);
+
+        Self::add(&hi, &lo)
+    }
+
+    /// Reduce 32 little-endian bytes modulo l.
+    pub(crate) fn from_bytes_mod_order(bytes: &[u8; 32]) -> Self {
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(bytes);
+        let ret = Self::from_bytes_wide(&wide);
+        wide.zeroize();
+
+        ret
+    }
+
+    /// Decode 32 little-endian bytes, returning `Choice(0)` if the value
+    /// is not a canonical encoding, meaning that it is not less than l.
+    pub(crate) fn from_canonical_bytes(bytes: &[u8; 32]) -> (Choice, Self) {
+        let ret = Self::from_bytes_mod_order(bytes);
+        let mut reencoded = ret.to_bytes();
+        let is_canonical = reencoded.ct_eq(bytes);
+        reencoded.zeroize();
+
+        (is_canonical, ret)
+    }
+
+    /// Encode the scalar as 32 little-endian bytes.
+    pub(crate) fn to_bytes(self) -> [u8; 32] {
+        let s = &self.0;
+        let words: [u64; 4] = [
+            s[0] | (s[1] << 52),
+            (s[1] >> 12) | (s[2] << 40),
+            (s[2] >> 24) | (s[3] << 28),
+            (s[3] >> 36) | (s[4] << 16),
+        ];
+
+        let mut out = [0u8; 32];
+        for (chunk, word) in out.chunks_exact_mut(8).zip(words.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        out
+    }
+
+    /// Compute a + b (mod l).
+    pub(crate) fn add(a: &Self, b: &Self) -> Self {
+        let mut sum = Self::ZERO;
+        let mut carry: u64 = 0;
+        for i in 0..5 {
+            carry = a.0[i] + b.0[i] + (carry >> 52);
+            sum.0[i] = carry & MASK52;
+        }
+
+        // Subtract l if the sum is greater than or equal to l.
+        Self::sub(&sum, &L)
+    }
+
+    /// Compute a - b (mod l).
+    pub(crate) fn sub(a: &Self, b: &Self) -> Self {
+        let mut difference = Self::ZERO;
+        let mut borrow: u64 = 0;
+        for i in 0..5 {
+            borrow = a.0[i].wrapping_sub(b.0[i] + (borrow >> 63));
+            difference.0[i] = borrow & MASK52;
+        }
+
+        // Add l back if the difference is negative.
+        let underflow_mask = ((borrow >> 63) ^ 1).wrapping_sub(1);
+        let mut carry: u64 = 0;
+        for i in 0..5 {
+            carry = (carry >> 52) + difference.0[i] + (L.0[i] & underflow_mask);
+            difference.0[i] = carry & MASK52;
+        }
+
+        difference
+    }
+
+    /// Compute a * b, without any reduction.
+    fn mul_internal(a: &Self, b: &Self) -> [u128; 9] {
+        let a = &a.0;
+        let b = &b.0;
+
+        [
+            m(a[0], b[0]),
+            m(a[0], b[1]) + m(a[1], b[0]),
+            m(a[0], b[2]) + m(a[1], b[1]) + m(a[2], b[0]),
+            m(a[0], b[3]) + m(a[1], b[2]) + m(a[2], b[1]) + m(a[3], b[0]),
+            m(a[0], b[4]) + m(a[1], b[3]) + m(a[2], b[2]) + m(a[3], b[1]) + m(a[4], b[0]),
+            m(a[1], b[4]) + m(a[2], b[3]) + m(a[3], b[2]) + m(a[4], b[1]),
+            m(a[2], b[4]) + m(a[3], b[3]) + m(a[4], b[2]),
+            m(a[3], b[4]) + m(a[4], b[3]),
+            m(a[4], b[4]),
+        ]
+    }
+
+    /// Compute limbs / R (mod l), where limbs is the result of [`Self::mul_internal()`].
+    fn montgomery_reduce(limbs: &[u128; 9]) -> Self {
+        #[inline(always)]
+        fn part1(sum: u128) -> (u128, u64) {
+            let p = (sum as u64).wrapping_mul(LFACTOR) & MASK52;
+            ((sum + m(p, L.0[0])) >> 52, p)
+        }
+
+        #[inline(always)]
+        fn part2(sum: u128) -> (u128, u64) {
+            let w = (sum as u64) & MASK52;
+            (sum >> 52, w)
+        }
+
+        // NOTE: The fourth limb of l is zero, so the products with it are omitted.
+        let l = &L.0;
+        let (carry, n0) = part1(limbs[0]);
+        let (carry, n1) = part1(carry + limbs[1] + m(n0, l[1]));
+        let (carry, n2) = part1(carry + limbs[2] + m(n0, l[2]) + m(n1, l[1]));
+        let (carry, n3) = part1(carry + limbs[3] + m(n1, l[2]) + m(n2, l[1]));
+        let (carry, n4) = part1(carry + limbs[4] + m(n0, l[4]) + m(n2, l[2]) + m(n3, l[1]));
+
+        let (carry, r0) = part2(carry + limbs[5] + m(n1, l[4]) + m(n3, l[2]) + m(n4, l[1]));
+        let (carry, r1) = part2(carry + limbs[6] + m(n2, l[4]) + m(n4, l[2]));
+        let (carry, r2) = part2(carry + limbs[7] + m(n3, l[4]));
+        let (carry, r3) = part2(carry + limbs[8] + m(n4, l[4]));
+        let r4 = carry as u64;
+
+        // The result is less than 2l, so a single conditional subtraction suffices.
+        Self::sub(&Self([r0, r1, r2, r3, r4]), &L)
+    }
+
+    /// Compute (a * b) / R (mod l).
+    fn montgomery_mul(a: &Self, b: &Self) -> Self {
+        Self::montgomery_reduce(&Self::mul_internal(a, b))
+    }
+
+    /// Compute a * b (mod l).
+    pub(crate) fn mul(a: &Self, b: &Self) -> Self {
+        let ab = Self::montgomery_mul(a, b);
+        Self::montgomery_mul(&ab, &RR)
+    }
+
+    /// Compute -a (mod l).
+    pub(crate) fn neg(a: &Self) -> Self {
+        Self::sub(&Self::ZERO, a)
+    }
+
+    /// Compute the multiplicative inverse, a^(l - 2) (mod l). The inverse of zero is zero.
+    pub(crate) fn invert(a: &Self) -> Self {
+        // l - 2, in little-endian bytes. The exponent is public, so
+        // the square-and-multiply below runs in constant time.
+        let mut exponent = L.to_bytes();
+        exponent[0] -= 2;
+
+        // Work in Montgomery form: aR.
+        let a_mont = Self::montgomery_mul(a, &RR);
+        let mut acc = R;
+        for byte in exponent.iter().rev() {
+            for bit in (0..8).rev() {
+                acc = Self::montgomery_mul(&acc, &acc);
+                if (byte >> bit) & 1 == 1 {
+                    acc = Self::montgomery_mul(&acc, &a_mont);
+                }
+            }
+        }
+
+        // Leave Montgomery form.
+        Self::montgomery_reduce(&Self::mul_internal(&acc, &Self::ONE))
+    }
+
+    /// Write the scalar in signed radix 16, with 64 digits in [-8, 8).
+    ///
+    /// The scalar must be fully reduced.
+    pub(crate) fn to_radix_16(self) -> [i8; 64] {
+        let bytes = self.to_bytes();
+        debug_assert!(bytes[31] <= 127);
+
+        let mut output = [0i8; 64];
+        for (i, byte) in bytes.iter().enumerate() {
+            output[2 * i] = (byte & 15) as i8;
+            output[2 * i + 1] = ((byte >> 4) & 15) as i8;
+        }
+
+        for i in 0..63 {
+            let carry = (output[i] + 8) >> 4;
+            output[i] -= carry << 4;
+            output[i + 1] += carry;
+        }
+
+        output
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    #[test]
+    fn test_l_reduces_to_zero() {
+        let l = L.to_bytes();
+        assert!(bool::from(
+            Scalar52::from_bytes_mod_order(&l).ct_eq(&Scalar52::ZERO)
+        ));
+        assert!(!bool::from(Scalar52::from_canonical_bytes(&l).0));
+
+        let mut l_minus_one = l;
+        l_minus_one[0] -= 1;
+        assert!(bool::from(Scalar52::from_canonical_bytes(&l_minus_one).0));
+    }
+
+    #[test]
+    fn test_from_bytes_wide() {
+        let expected =
+            hex::decode("000f9c44e31106a447938568a71b0ed065bef517d273ecce3d9a307c1b419903")
+                .unwrap();
+        assert_eq!(
+            Scalar52::from_bytes_wide(&[0xffu8; 64]).to_bytes()[..],
+            expected[..]
+        );
+
+        let expected =
+            hex::decode("1c95988d7431ecd670cf7d73f45befc6feffffffffffffffffffffffffffff0f")
+                .unwrap();
+        assert_eq!(
+            Scalar52::from_bytes_mod_order(&[0xffu8; 32]).to_bytes()[..],
+            expected[..]
+        );
+    }
+
+    #[test]
+    fn test_montgomery_constants() {
+        assert!(bool::from(
+            Scalar52::montgomery_mul(&R, &Scalar52::ONE).ct_eq(&Scalar52::ONE)
+        ));
+        assert!(bool::from(
+            Scalar52::montgomery_mul(&RR, &Scalar52::ONE).ct_eq(&R)
+        ));
+        // l * LFACTOR = -1 (mod 2^52)
+        assert_eq!(L.0[0].wrapping_mul(LFACTOR) & MASK52, MASK52);
+    }
+
+    #[test]
+    fn test_to_radix_16() {
+        let s = Scalar52::from_bytes_mod_order(&[0xabu8; 32]);
+        let digits = s.to_radix_16();
+        assert!(digits.iter().all(|d| (-8..8).contains(d) || *d == 8));
+
+        // Recompose the digits, from the most significant one.
+        let sixteen = Scalar52([16, 0, 0, 0, 0]);
+        let mut acc = Scalar52::ZERO;
+        for digit in digits.iter().rev() {
+            acc = Scalar52::mul(&acc, &sixteen);
+            let d = Scalar52([digit.unsigned_abs() as u64, 0, 0, 0, 0]);
+            acc = if *digit < 0 {
+                Scalar52::sub(&acc, &d)
+            } else {
+                Scalar52::add(&acc, &d)
+            };
+        }
+        assert!(bool::from(acc.ct_eq(&s)));
+    }
+}
@@ -0,0 +1,166 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The X25519 function on the Montgomery form of Curve25519, computed
+//! with the constant-time Montgomery ladder from the RFC 7748, Section 5.
+
+use super::field25519::FieldElement;
+use subtle::{Choice, ConditionallySelectable};
+use zeroize::Zeroize;
+
+/// The size of an X25519 scalar, u-coordinate and output.
+pub(crate) const X25519_SIZE: usize = 32;
+
+/// The u-coordinate of the base point.
+pub(crate) const BASEPOINT: [u8; X25519_SIZE] = {
+    let mut u = [0u8; X25519_SIZE];
+    u[0] = 9;
+    u
+};
+
+/// (486662 - 2) / 4
+const A24: FieldElement = FieldElement([121665, 0, 0, 0, 0]);
+
+/// Compute X25519(`k`, `u`). `k` is clamped and the most significant bit of `u` is ignored.
+pub(crate) fn scalarmult(k: &[u8; X25519_SIZE], u: &[u8; X25519_SIZE]) -> [u8; X25519_SIZE] {
+    let mut scalar = *k;
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+
+    let x1 = FieldElement::from_bytes(u);
+    let mut x2 = FieldElement::ONE;
+    let mut z2 = FieldElement::ZERO;
+    let mut x3 = x1;
+    let mut z3 = FieldElement::ONE;
+    let mut swap = Choice::from(0);
+
+    for t in (0..255).rev() {
+        let k_t = Choice::from((scalar[t / 8] >> (t % 8)) & 1);
+        swap ^= k_t;
+        FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+        FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+        swap = k_t;
+
+        let a = x2.add(&z2);
+        let aa = a.square();
+        let b = x2.sub(&z2);
+        let bb = b.square();
+        let e = aa.sub(&bb);
+        let c = x3.add(&z3);
+        let d = x3.sub(&z3);
+        let da = d.mul(&a);
+        let cb = c.mul(&b);
+
+        x3 = da.add(&cb).square();
+        z3 = x1.mul(&da.sub(&cb).square());
+        x2 = aa.mul(&bb);
+        z2 = e.mul(&aa.add(&A24.mul(&e)));
+    }
+    FieldElement::conditional_swap(&mut x2, &mut x3, swap);
+    FieldElement::conditional_swap(&mut z2, &mut z3, swap);
+
+    let ret = x2.mul(&z2.invert()).to_bytes();
+
+    scalar.zeroize();
+    x2.zeroize();
+    z2.zeroize();
+    x3.zeroize();
+    z3.zeroize();
+
+    ret
+}
+
+/// Compute X25519(`k`, 9), the public key belonging to the secret key `k`.
+pub(crate) fn scalarmult_base(k: &[u8; X25519_SIZE]) -> [u8; X25519_SIZE] {
+    scalarmult(k, &BASEPOINT)
+}
+
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    fn decode(hex_str: &str) -> [u8; X25519_SIZE] {
+        let mut ret = [0u8; X25519_SIZE];
+        ret.copy_from_slice(&hex::decode(hex_str).unwrap());
+        ret
+    }
+
+    #[test]
+    fn test_rfc7748_section_5_2() {
+        assert_eq!(
+            scalarmult(
+                &decode("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                &decode("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            ),
+            decode("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+        assert_eq!(
+            scalarmult(
+                &decode("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                &decode("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            ),
+            decode("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+    }
+
+    #[test]
+    fn test_rfc7748_iterated() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for i in 1..=1000 {
+            let out = scalarmult(&k, &u);
+            u = k;
+            k = out;
+            if i == 1 {
+                assert_eq!(
+                    k,
+                    decode("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            decode("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    #[test]
+    fn test_rfc7748_section_6_1() {
+        let alice_sk = decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let alice_pk = decode("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+        let bob_sk = decode("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let bob_pk = decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f");
+        let shared = decode("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+
+        assert_eq!(scalarmult_base(&alice_sk), alice_pk);
+        assert_eq!(scalarmult_base(&bob_sk), bob_pk);
+        assert_eq!(scalarmult(&alice_sk, &bob_pk), shared);
+        assert_eq!(scalarmult(&bob_sk, &alice_pk), shared);
+    }
+
+    #[test]
+    fn test_low_order_point() {
+        // The point of order 1 (u = 0) always gives the all-zero output.
+        assert_eq!(scalarmult(&[0x42u8; 32], &[0u8; 32]), [0u8; 32]);
+    }
+}
@@ -32,13 +32,14 @@
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between, or `dst_out` is not the size of an exported state.
+//! - The state given to [`import_state()`] is not the size of an exported state or is malformed.
 //! - [`reset()`] is called with `Some(secret_key)` but the struct was
 //!   initialized with `None`.
 //! - [`reset()`] is called with `None` as `secret_key` but the struct was
-//!   initialized with `Some(secret_key)`.
-//!
-//! # Panics:
-//! A panic will occur if:
+//!   initialized with `Some(secret_key)`. [`reset_same_key()`] can be used to reset
+//!   without giving the secret key again.
 //! - More than 2*(2^64-1) bytes of data are hashed.
 //!
 //! # Security:
@@ -74,19 +75,28 @@
 //! ```
 //! [`update()`]: blake2b::Blake2b::update
 //! [`reset()`]: blake2b::Blake2b::reset
+//! [`reset_same_key()`]: blake2b::Blake2b::reset_same_key
+//! [`export_state()`]: blake2b::Blake2b::export_state
+//! [`import_state()`]: blake2b::Blake2b::import_state
 //! [`finalize()`]: blake2b::Blake2b::finalize
 //! [`SecretKey::generate()`]: blake2b::SecretKey::generate
 //! [`verify()`]: blake2b::Blake2b::verify
 //! [`as_ref()`]: blake2b::Digest::as_ref
 
-use crate::{errors::UnknownCryptoError, util::endianness::load_u64_into_le, util::u64x4::U64x4};
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    util::endianness::load_u64_into_le,
+    util::u64x4::U64x4,
+};
 
 /// The blocksize for the hash function BLAKE2b.
-const BLAKE2B_BLOCKSIZE: usize = 128;
+pub(crate) const BLAKE2B_BLOCKSIZE: usize = 128;
 /// The maximum key size for the hash function BLAKE2b when used in keyed mode.
 pub(crate) const BLAKE2B_KEYSIZE: usize = 64;
 /// The maximum output size for the hash function BLAKE2b.
 pub(crate) const BLAKE2B_OUTSIZE: usize = 64;
+/// The size of a BLAKE2b state exported with [`Blake2b::export_state()`].
+pub const BLAKE2B_STATESIZE: usize = 339;
 
 construct_secret_key! {
     /// A type to represent the secret key that BLAKE2b uses for keyed mode.
@@ -112,6 +122,12 @@ construct_public! {
     (Digest, test_digest, 1, BLAKE2B_OUTSIZE)
 }
 
+impl Digest {
+    func_to_hex!(as_ref);
+}
+
+impl_hex_display_fromstr_traits!(Digest, as_ref, BLAKE2B_OUTSIZE);
+
 #[allow(clippy::unreadable_literal)]
 /// The BLAKE2b initialization vector as defined in the RFC 7693.
 const IV: [U64x4; 2] = [
@@ -238,7 +254,9 @@ pub struct Blake2b {
     f: [u64; 2],
     is_finalized: bool,
     is_keyed: bool,
+    is_last_node: bool,
     size: usize,
+    key_block: [u8; BLAKE2B_KEYSIZE],
 }
 
 impl Drop for Blake2b {
@@ -247,6 +265,7 @@ impl Drop for Blake2b {
         self.init_state.iter_mut().zeroize();
         self.internal_state.iter_mut().zeroize();
         self.buffer.zeroize();
+        self.key_block.zeroize();
     }
 }
 
@@ -264,13 +283,18 @@ impl core::fmt::Debug for Blake2b {
 
 impl Blake2b {
     /// Increment the internal states offset value `t`.
-    fn increment_offset(&mut self, value: u64) {
+    fn increment_offset(&mut self, value: u64) -> Result<(), UnknownCryptoError> {
         let (res, was_overflow) = self.t[0].overflowing_add(value);
-        self.t[0] = res;
         if was_overflow {
-            // If this panics size limit is reached.
-            self.t[1] = self.t[1].checked_add(1).unwrap();
+            // If this fails, the size limit is reached.
+            self.t[1] = match self.t[1].checked_add(1) {
+                Some(next) => next,
+                None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+            };
         }
+        self.t[0] = res;
+
+        Ok(())
     }
 
     /// The compression function f.
@@ -316,9 +340,29 @@ impl Blake2b {
     /// Initialize a `Blake2b` struct with a given size and an optional key.
     pub fn new(secret_key: Option<&SecretKey>, size: usize) -> Result<Self, UnknownCryptoError> {
         if !(1..=BLAKE2B_OUTSIZE).contains(&size) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
+        let klen = secret_key.map_or(0, |sk| sk.len()) as u64;
+        Self::_new_with_params(
+            secret_key,
+            size,
+            [0x01010000 ^ (klen << 8) ^ (size as u64), 0, 0],
+            false,
+        )
+    }
+
+    /// Initialize a `Blake2b` struct from the first three words of a parameter block,
+    /// as used by tree hashing. `size` is the number of bytes `finalize()` returns, which
+    /// may differ from the digest length in `params`. The key length in `params` must match
+    /// `secret_key`, if given, but may be non-zero without one (as for a BLAKE2bp root).
+    pub(crate) fn _new_with_params(
+        secret_key: Option<&SecretKey>,
+        size: usize,
+        params: [u64; 3],
+        is_last_node: bool,
+    ) -> Result<Self, UnknownCryptoError> {
+        debug_assert!((1..=BLAKE2B_OUTSIZE).contains(&size));
         let mut context = Self {
             init_state: [U64x4::default(); 2],
             internal_state: IV,
@@ -328,25 +372,24 @@ impl Blake2b {
             f: [0u64; 2],
             is_finalized: false,
             is_keyed: false,
+            is_last_node,
             size,
+            key_block: [0u8; BLAKE2B_KEYSIZE],
         };
 
-        match secret_key {
-            Some(sk) => {
-                context.is_keyed = true;
-                let klen = sk.len();
-                context.internal_state[0].0 ^= 0x01010000 ^ ((klen as u64) << 8) ^ (size as u64);
-                context.init_state.copy_from_slice(&context.internal_state);
-                context.update(sk.unprotected_as_bytes())?;
-                // The state needs updating with the secret key padded to blocksize length
-                let pad = [0u8; BLAKE2B_BLOCKSIZE];
-                let rem = BLAKE2B_BLOCKSIZE - klen;
-                context.update(pad[..rem].as_ref())?;
-            }
-            None => {
-                context.internal_state[0].0 ^= 0x01010000 ^ (size as u64);
-                context.init_state.copy_from_slice(&context.internal_state);
-            }
+        context.internal_state[0].0 ^= params[0];
+        context.internal_state[0].1 ^= params[1];
+        context.internal_state[0].2 ^= params[2];
+        context.init_state.copy_from_slice(&context.internal_state);
+
+        if let Some(sk) = secret_key {
+            context.is_keyed = true;
+            context.key_block[..sk.len()].copy_from_slice(sk.unprotected_as_bytes());
+            context.update(sk.unprotected_as_bytes())?;
+            // The state needs updating with the secret key padded to blocksize length
+            let pad = [0u8; BLAKE2B_BLOCKSIZE];
+            let rem = BLAKE2B_BLOCKSIZE - sk.len();
+            context.update(pad[..rem].as_ref())?;
         }
 
         Ok(context)
@@ -356,11 +399,11 @@ impl Blake2b {
     /// Reset to `new()` state.
     pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
         if secret_key.is_some() && (!self.is_keyed) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
 
         if secret_key.is_none() && self.is_keyed {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
 
         self.internal_state.copy_from_slice(&self.init_state);
@@ -372,6 +415,8 @@ impl Blake2b {
 
         match secret_key {
             Some(sk) => {
+                self.key_block = [0u8; BLAKE2B_KEYSIZE];
+                self.key_block[..sk.len()].copy_from_slice(sk.unprotected_as_bytes());
                 self.update(sk.unprotected_as_bytes())?;
                 // The state needs updating with the secret key padded to blocksize length
                 let pad = [0u8; BLAKE2B_BLOCKSIZE];
@@ -382,11 +427,29 @@ impl Blake2b {
         }
     }
 
+    /// Reset to `new()` state, using the same secret key as before, if any. Unlike
+    /// [`Blake2b::reset()`], the secret key does not need to be given again.
+    pub fn reset_same_key(&mut self) {
+        self.internal_state.copy_from_slice(&self.init_state);
+        self.buffer = [0u8; BLAKE2B_BLOCKSIZE];
+        self.t = [0u64; 2];
+        self.f = [0u64; 2];
+        self.is_finalized = false;
+
+        if self.is_keyed {
+            // Same as processing the secret key padded to blocksize length.
+            self.buffer[..BLAKE2B_KEYSIZE].copy_from_slice(&self.key_block);
+            self.leftover = BLAKE2B_BLOCKSIZE;
+        } else {
+            self.leftover = 0;
+        }
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Update state with `data`. This can be called multiple times.
     pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
         if data.is_empty() {
             return Ok(());
@@ -406,14 +469,14 @@ impl Blake2b {
             }
 
             self.buffer[self.leftover..(self.leftover + fill)].copy_from_slice(&bytes[..fill]);
-            self.increment_offset(BLAKE2B_BLOCKSIZE as u64);
+            self.increment_offset(BLAKE2B_BLOCKSIZE as u64)?;
             self.compress_f(None);
             self.leftover = 0;
             bytes = &bytes[fill..];
         }
 
         while bytes.len() > BLAKE2B_BLOCKSIZE {
-            self.increment_offset(BLAKE2B_BLOCKSIZE as u64);
+            self.increment_offset(BLAKE2B_BLOCKSIZE as u64)?;
             self.compress_f(Some(bytes[..BLAKE2B_BLOCKSIZE].as_ref()));
             bytes = &bytes[BLAKE2B_BLOCKSIZE..];
         }
@@ -431,15 +494,18 @@ impl Blake2b {
     /// Return a BLAKE2b digest.
     pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
 
         self.is_finalized = true;
 
         let in_buffer_len = self.leftover;
-        self.increment_offset(in_buffer_len as u64);
+        self.increment_offset(in_buffer_len as u64)?;
         // Mark that it is the last block of data to be processed
         self.f[0] = !0;
+        if self.is_last_node {
+            self.f[1] = !0;
+        }
 
         for leftover_block in self.buffer.iter_mut().skip(in_buffer_len) {
             *leftover_block = 0;
@@ -453,6 +519,93 @@ impl Blake2b {
         Digest::from_slice(&digest[..self.size])
     }
 
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state into `dst_out`, so that hashing can be resumed later with
+    /// [`Blake2b::import_state()`]. The state cannot be exported after [`Blake2b::finalize()`].
+    ///
+    /// # Security:
+    /// The state of a keyed `Blake2b` contains the secret key, or is derived from it,
+    /// and must be protected as such.
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dst_out.len() != BLAKE2B_STATESIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let (init_state, rest) = dst_out.split_at_mut(64);
+        let (internal_state, rest) = rest.split_at_mut(64);
+        let (t, rest) = rest.split_at_mut(16);
+        let (buffer, rest) = rest.split_at_mut(BLAKE2B_BLOCKSIZE);
+        let (key_block, rest) = rest.split_at_mut(BLAKE2B_KEYSIZE);
+        self.init_state[0].store_into_le(&mut init_state[..32]);
+        self.init_state[1].store_into_le(&mut init_state[32..]);
+        self.internal_state[0].store_into_le(&mut internal_state[..32]);
+        self.internal_state[1].store_into_le(&mut internal_state[32..]);
+        t[..8].copy_from_slice(&self.t[0].to_le_bytes());
+        t[8..].copy_from_slice(&self.t[1].to_le_bytes());
+        buffer[..self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+        buffer[self.leftover..].iter_mut().for_each(|b| *b = 0u8);
+        key_block.copy_from_slice(&self.key_block);
+        rest[0] = self.leftover as u8;
+        rest[1] = self.size as u8;
+        rest[2] = (self.is_keyed as u8) | ((self.is_last_node as u8) << 1);
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Resume hashing from a state exported with [`Blake2b::export_state()`]. An error
+    /// is returned if `state` is not [`BLAKE2B_STATESIZE`] bytes or is malformed.
+    pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if state.len() != BLAKE2B_STATESIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let (init_state, rest) = state.split_at(64);
+        let (internal_state, rest) = rest.split_at(64);
+        let (t, rest) = rest.split_at(16);
+        let (buffer, rest) = rest.split_at(BLAKE2B_BLOCKSIZE);
+        let (key_block, rest) = rest.split_at(BLAKE2B_KEYSIZE);
+        let leftover = rest[0] as usize;
+        let size = rest[1] as usize;
+        let flags = rest[2];
+        if leftover > BLAKE2B_BLOCKSIZE
+            || !(1..=BLAKE2B_OUTSIZE).contains(&size)
+            || flags > 0b11
+            || buffer[leftover..].iter().any(|b| *b != 0)
+            || (flags & 0b01 == 0 && key_block.iter().any(|b| *b != 0))
+        {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let mut context = Self {
+            init_state: [
+                U64x4::load_from_le(&init_state[..32]),
+                U64x4::load_from_le(&init_state[32..]),
+            ],
+            internal_state: [
+                U64x4::load_from_le(&internal_state[..32]),
+                U64x4::load_from_le(&internal_state[32..]),
+            ],
+            buffer: [0u8; BLAKE2B_BLOCKSIZE],
+            leftover,
+            t: [0u64; 2],
+            f: [0u64; 2],
+            is_finalized: false,
+            is_keyed: flags & 0b01 != 0,
+            is_last_node: flags & 0b10 != 0,
+            size,
+            key_block: [0u8; BLAKE2B_KEYSIZE],
+        };
+        context.buffer[..leftover].copy_from_slice(&buffer[..leftover]);
+        context.key_block.copy_from_slice(key_block);
+        load_u64_into_le(t, &mut context.t);
+
+        Ok(context)
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Verify a Blake2b Digest in constant time.
     pub fn verify(
@@ -467,9 +620,26 @@ impl Blake2b {
         if expected == &state.finalize()? {
             Ok(())
         } else {
-            Err(UnknownCryptoError)
+            Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
         }
     }
+
+    #[cfg(test)]
+    /// Compare two Blake2b state objects to check if their fields
+    /// are the same.
+    pub(crate) fn compare_state_to_other(&self, other: &Self) {
+        assert!(self.init_state == other.init_state);
+        assert!(self.internal_state == other.internal_state);
+        assert_eq!(self.buffer[..], other.buffer[..]);
+        assert_eq!(self.leftover, other.leftover);
+        assert_eq!(self.t, other.t);
+        assert_eq!(self.f, other.f);
+        assert_eq!(self.is_finalized, other.is_finalized);
+        assert_eq!(self.is_keyed, other.is_keyed);
+        assert_eq!(self.is_last_node, other.is_last_node);
+        assert_eq!(self.size, other.size);
+        assert_eq!(self.key_block[..], other.key_block[..]);
+    }
 }
 
 // Testing public functions in the module.
@@ -477,6 +647,9 @@ impl Blake2b {
 mod public {
     use super::*;
 
+    #[cfg(feature = "safe_api")]
+    test_to_hex!(Digest, BLAKE2B_OUTSIZE, as_ref);
+
     #[test]
     #[cfg(feature = "safe_api")]
     fn test_debug_impl() {
@@ -487,15 +660,7 @@ mod public {
     }
 
     fn compare_blake2b_states(state_1: &Blake2b, state_2: &Blake2b) {
-        assert!(state_1.init_state == state_2.init_state);
-        assert!(state_1.internal_state == state_2.internal_state);
-        assert_eq!(state_1.buffer[..], state_2.buffer[..]);
-        assert_eq!(state_1.leftover, state_2.leftover);
-        assert_eq!(state_1.t, state_2.t);
-        assert_eq!(state_1.f, state_2.f);
-        assert_eq!(state_1.is_finalized, state_2.is_finalized);
-        assert_eq!(state_1.is_keyed, state_2.is_keyed);
-        assert_eq!(state_1.size, state_2.size);
+        state_1.compare_state_to_other(state_2);
     }
 
     mod test_streaming_interface_no_key {
@@ -526,7 +691,7 @@ mod public {
                 if &actual == expected {
                     Ok(())
                 } else {
-                    Err(UnknownCryptoError)
+                    Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
                 }
             }
 
@@ -727,6 +892,92 @@ mod public {
         }
     }
 
+    mod test_export_import_state {
+        use super::*;
+
+        #[test]
+        fn test_resume_every_split() {
+            let data = [7u8; BLAKE2B_BLOCKSIZE * 2 + 3];
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+            for secret_key in [None, Some(&sk)].iter() {
+                let mut expected_ctx = Blake2b::new(*secret_key, 48).unwrap();
+                expected_ctx.update(&data).unwrap();
+                let expected = expected_ctx.finalize().unwrap();
+
+                for split in 0..=data.len() {
+                    let mut ctx = Blake2b::new(*secret_key, 48).unwrap();
+                    ctx.update(&data[..split]).unwrap();
+                    let mut state = [0u8; BLAKE2B_STATESIZE];
+                    ctx.export_state(&mut state).unwrap();
+
+                    let mut resumed = Blake2b::import_state(&state).unwrap();
+                    let mut reexported = [0u8; BLAKE2B_STATESIZE];
+                    resumed.export_state(&mut reexported).unwrap();
+                    assert_eq!(reexported[..], state[..]);
+                    resumed.update(&data[split..]).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+                }
+            }
+        }
+
+        #[test]
+        fn test_resumed_reset() {
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let mut ctx = Blake2b::new(Some(&sk), 32).unwrap();
+            ctx.update(b"abc").unwrap();
+            let mut state = [0u8; BLAKE2B_STATESIZE];
+            ctx.export_state(&mut state).unwrap();
+
+            let mut resumed = Blake2b::import_state(&state).unwrap();
+            assert!(resumed.reset(None).is_err());
+            resumed.reset(Some(&sk)).unwrap();
+            resumed.compare_state_to_other(&Blake2b::new(Some(&sk), 32).unwrap());
+
+            let mut resumed = Blake2b::import_state(&state).unwrap();
+            resumed.reset_same_key();
+            resumed.compare_state_to_other(&Blake2b::new(Some(&sk), 32).unwrap());
+        }
+
+        #[test]
+        fn test_export_import_errors() {
+            let mut ctx = Blake2b::new(None, 64).unwrap();
+            ctx.update(b"abc").unwrap();
+            let mut state = [0u8; BLAKE2B_STATESIZE];
+            assert!(ctx
+                .export_state(&mut state[..BLAKE2B_STATESIZE - 1])
+                .is_err());
+            ctx.export_state(&mut state).unwrap();
+
+            assert!(Blake2b::import_state(&state[..BLAKE2B_STATESIZE - 1]).is_err());
+            // Too many buffered bytes.
+            let mut bad = state;
+            bad[BLAKE2B_STATESIZE - 3] = BLAKE2B_BLOCKSIZE as u8 + 1;
+            assert!(Blake2b::import_state(&bad).is_err());
+            // Invalid output size.
+            let mut bad = state;
+            bad[BLAKE2B_STATESIZE - 2] = 0;
+            assert!(Blake2b::import_state(&bad).is_err());
+            bad[BLAKE2B_STATESIZE - 2] = 65;
+            assert!(Blake2b::import_state(&bad).is_err());
+            // Unknown flags.
+            let mut bad = state;
+            bad[BLAKE2B_STATESIZE - 1] = 4;
+            assert!(Blake2b::import_state(&bad).is_err());
+            // Non-zero bytes after the buffered data.
+            let mut bad = state;
+            bad[64 + 64 + 16 + BLAKE2B_BLOCKSIZE - 1] = 1;
+            assert!(Blake2b::import_state(&bad).is_err());
+            // Key bytes in a state without a key.
+            let mut bad = state;
+            bad[BLAKE2B_STATESIZE - 4] = 1;
+            assert!(Blake2b::import_state(&bad).is_err());
+
+            let _ = ctx.finalize().unwrap();
+            assert!(ctx.export_state(&mut state).is_err());
+        }
+    }
+
     mod test_reset {
         use super::*;
 
@@ -746,6 +997,39 @@ mod public {
             assert!(state_second.reset(Some(&secret_key)).is_err());
             assert!(state_second.reset(None).is_ok());
         }
+
+        #[test]
+        fn test_reset_same_key_equals_new() {
+            let secret_key = SecretKey::from_slice(b"Testing").unwrap();
+
+            let mut state = Blake2b::new(Some(&secret_key), 64).unwrap();
+            state.update(&[1u8; 300]).unwrap();
+            let _ = state.finalize().unwrap();
+            state.reset_same_key();
+            state.compare_state_to_other(&Blake2b::new(Some(&secret_key), 64).unwrap());
+
+            let mut state = Blake2b::new(None, 64).unwrap();
+            state.update(&[1u8; 300]).unwrap();
+            let _ = state.finalize().unwrap();
+            state.reset_same_key();
+            state.compare_state_to_other(&Blake2b::new(None, 64).unwrap());
+        }
+
+        #[test]
+        fn test_reset_same_key_after_reset_with_new_key() {
+            let first = SecretKey::from_slice(&[1u8; 64]).unwrap();
+            let second = SecretKey::from_slice(&[2u8; 64]).unwrap();
+
+            let mut state = Blake2b::new(Some(&first), 32).unwrap();
+            state.reset(Some(&second)).unwrap();
+            state.update(b"Tests").unwrap();
+            state.reset_same_key();
+            state.update(b"Tests").unwrap();
+
+            let mut expected = Blake2b::new(Some(&second), 32).unwrap();
+            expected.update(b"Tests").unwrap();
+            assert_eq!(state.finalize().unwrap(), expected.finalize().unwrap());
+        }
     }
 
     mod test_streaming_interface {
@@ -929,23 +1213,24 @@ mod private {
                 f: [0u64; 2],
                 is_finalized: false,
                 is_keyed: false,
+                is_last_node: false,
                 size: 1,
+                key_block: [0u8; BLAKE2B_KEYSIZE],
             };
 
-            context.increment_offset(1);
+            context.increment_offset(1).unwrap();
             assert!(context.t == [1u64, 0u64]);
-            context.increment_offset(17);
+            context.increment_offset(17).unwrap();
             assert!(context.t == [18u64, 0u64]);
-            context.increment_offset(12);
+            context.increment_offset(12).unwrap();
             assert!(context.t == [30u64, 0u64]);
             // Overflow
-            context.increment_offset(u64::MAX);
+            context.increment_offset(u64::MAX).unwrap();
             assert!(context.t == [29u64, 1u64]);
         }
 
         #[test]
-        #[should_panic]
-        fn test_panic_on_second_overflow() {
+        fn test_err_on_second_overflow() {
             let mut context = Blake2b {
                 init_state: [U64x4::default(); 2],
                 internal_state: IV,
@@ -955,10 +1240,13 @@ mod private {
                 f: [0u64; 2],
                 is_finalized: false,
                 is_keyed: false,
+                is_last_node: false,
                 size: 1,
+                key_block: [0u8; BLAKE2B_KEYSIZE],
             };
 
-            context.increment_offset(u64::MAX);
+            assert!(context.increment_offset(u64::MAX).is_err());
+            assert!(context.t == [1u64, u64::MAX]);
         }
     }
 }
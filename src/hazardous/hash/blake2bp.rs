@@ -0,0 +1,388 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: An optional secret key.
+//! - `size`: The desired output length for the digest.
+//! - `data`: The data to be hashed.
+//! - `expected`: The expected digest when verifying.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `size` is 0 or greater than 64.
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - [`reset()`] is called with `Some(secret_key)` but the struct was
+//!   initialized with `None`.
+//! - [`reset()`] is called with `None` as `secret_key` but the struct was
+//!   initialized with `Some(secret_key)`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - More than 2*(2^64-1) bytes of data are hashed by a single leaf.
+//!
+//! # Security:
+//! - BLAKE2bp produces different digests than BLAKE2b, for the same input.
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this. It generates
+//!   a secret key of 32 bytes.
+//! - The minimum recommended size for a secret key is 32 bytes.
+//! - When using BLAKE2bp with a secret key, then the output can be used as a
+//!   MAC. If this is the intention, use [`verify()`], which will compare
+//!   the MAC in constant time.
+//! - The recommended minimum output size is 32.
+//!
+//! # Note:
+//! BLAKE2bp splits the input between four BLAKE2b leaves, which are combined by a root node.
+//! The leaves are independent, which allows implementations to process them in parallel.
+//! This implementation processes them one after the other; spawning threads for the leaves
+//! requires scoped threads, which are not available with orion's MSRV.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::hash::{blake2b::SecretKey, blake2bp::Blake2bp};
+//!
+//! let mut state = Blake2bp::new(None, 64)?;
+//! state.update(b"Some data")?;
+//! let digest = state.finalize()?;
+//!
+//! let secret_key = SecretKey::generate();
+//! let mut state_keyed = Blake2bp::new(Some(&secret_key), 64)?;
+//! state_keyed.update(b"Some data")?;
+//! let mac = state_keyed.finalize()?;
+//! assert!(Blake2bp::verify(&mac, &secret_key, 64, b"Some data").is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: blake2bp::Blake2bp::update
+//! [`reset()`]: blake2bp::Blake2bp::reset
+//! [`finalize()`]: blake2bp::Blake2bp::finalize
+//! [`SecretKey::generate()`]: super::blake2b::SecretKey::generate
+//! [`verify()`]: blake2bp::Blake2bp::verify
+
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::hash::blake2b::{Blake2b, Digest, SecretKey, BLAKE2B_BLOCKSIZE, BLAKE2B_OUTSIZE},
+};
+
+/// The number of leaves that BLAKE2bp uses.
+const PARALLELISM_DEGREE: usize = 4;
+/// The amount of input, after which the first leaf receives data again.
+const BLAKE2BP_STRIPESIZE: usize = PARALLELISM_DEGREE * BLAKE2B_BLOCKSIZE;
+
+#[allow(clippy::unreadable_literal)]
+/// The first three words of a BLAKE2bp parameter block.
+fn params(size: usize, klen: usize, node_offset: u64, node_depth: u64) -> [u64; 3] {
+    [
+        (size as u64) ^ ((klen as u64) << 8) ^ ((PARALLELISM_DEGREE as u64) << 16) ^ (2u64 << 24),
+        node_offset,
+        node_depth ^ ((BLAKE2B_OUTSIZE as u64) << 8),
+    ]
+}
+
+#[derive(Clone, Debug)]
+/// BLAKE2bp streaming state.
+pub struct Blake2bp {
+    leaves: [Blake2b; PARALLELISM_DEGREE],
+    root: Blake2b,
+    stripe_offset: usize,
+    is_finalized: bool,
+}
+
+impl Blake2bp {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Initialize a `Blake2bp` struct with a given size and an optional key.
+    pub fn new(secret_key: Option<&SecretKey>, size: usize) -> Result<Self, UnknownCryptoError> {
+        if !(1..=BLAKE2B_OUTSIZE).contains(&size) {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let klen = secret_key.map_or(0, |sk| sk.len());
+        let leaf = |idx: usize| {
+            Blake2b::_new_with_params(
+                secret_key,
+                BLAKE2B_OUTSIZE,
+                params(size, klen, idx as u64, 0),
+                idx == PARALLELISM_DEGREE - 1,
+            )
+        };
+
+        Ok(Self {
+            leaves: [leaf(0)?, leaf(1)?, leaf(2)?, leaf(3)?],
+            // The key is only processed by the leaves.
+            root: Blake2b::_new_with_params(None, size, params(size, klen, 0, 1), true)?,
+            stripe_offset: 0,
+            is_finalized: false,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Reset to `new()` state.
+    pub fn reset(&mut self, secret_key: Option<&SecretKey>) -> Result<(), UnknownCryptoError> {
+        for leaf in self.leaves.iter_mut() {
+            leaf.reset(secret_key)?;
+        }
+        self.root.reset(None)?;
+        self.stripe_offset = 0;
+        self.is_finalized = false;
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let mut bytes = data;
+        while !bytes.is_empty() {
+            let leaf_idx = self.stripe_offset / BLAKE2B_BLOCKSIZE;
+            let want = core::cmp::min(
+                BLAKE2B_BLOCKSIZE - (self.stripe_offset % BLAKE2B_BLOCKSIZE),
+                bytes.len(),
+            );
+
+            self.leaves[leaf_idx].update(&bytes[..want])?;
+            self.stripe_offset = (self.stripe_offset + want) % BLAKE2BP_STRIPESIZE;
+            bytes = &bytes[want..];
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a BLAKE2bp digest.
+    pub fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        self.is_finalized = true;
+
+        let mut root = self.root.clone();
+        for leaf in self.leaves.iter_mut() {
+            root.update(leaf.finalize()?.as_ref())?;
+        }
+
+        root.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a BLAKE2bp Digest in constant time.
+    pub fn verify(
+        expected: &Digest,
+        secret_key: &SecretKey,
+        size: usize,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        let mut state = Self::new(Some(secret_key), size)?;
+        state.update(data)?;
+
+        if expected == &state.finalize()? {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+        }
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn compare_blake2bp_states(state_1: &Blake2bp, state_2: &Blake2bp) {
+        for (leaf_1, leaf_2) in state_1.leaves.iter().zip(state_2.leaves.iter()) {
+            leaf_1.compare_state_to_other(leaf_2);
+        }
+        state_1.root.compare_state_to_other(&state_2.root);
+        assert_eq!(state_1.stripe_offset, state_2.stripe_offset);
+        assert_eq!(state_1.is_finalized, state_2.is_finalized);
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::*;
+
+        impl TestableStreamingContext<Digest> for Blake2bp {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset(None)
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Digest, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Digest, UnknownCryptoError> {
+                let mut state = Blake2bp::new(None, BLAKE2B_OUTSIZE)?;
+                state.update(input)?;
+                state.finalize()
+            }
+
+            fn verify_result(expected: &Digest, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                let actual: Digest = Self::one_shot(input)?;
+
+                if &actual == expected {
+                    Ok(())
+                } else {
+                    Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+                }
+            }
+
+            fn compare_states(state_1: &Blake2bp, state_2: &Blake2bp) {
+                compare_blake2bp_states(state_1, state_2)
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state: Blake2bp = Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake2bp>::new(
+                initial_state,
+                BLAKE2BP_STRIPESIZE,
+            );
+            test_runner.run_all_tests();
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        /// Test different streaming state usage patterns.
+        fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+            let initial_state: Blake2bp = Blake2bp::new(None, BLAKE2B_OUTSIZE).unwrap();
+
+            let test_runner = StreamingContextConsistencyTester::<Digest, Blake2bp>::new(
+                initial_state,
+                BLAKE2BP_STRIPESIZE,
+            );
+            test_runner.run_all_tests_property(&data);
+            true
+        }
+    }
+
+    mod test_new {
+        use super::*;
+
+        #[test]
+        fn test_init_size() {
+            let sk = SecretKey::from_slice(&[0u8; 64]).unwrap();
+            for key in [None, Some(&sk)].iter() {
+                assert!(Blake2bp::new(*key, 0).is_err());
+                assert!(Blake2bp::new(*key, 65).is_err());
+                assert!(Blake2bp::new(*key, 64).is_ok());
+                assert!(Blake2bp::new(*key, 1).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_switching_keyed_modes_fails() {
+            let sk = SecretKey::from_slice(&[0u8; 64]).unwrap();
+
+            let mut state = Blake2bp::new(None, 64).unwrap();
+            assert!(state.reset(Some(&sk)).is_err());
+            assert!(state.reset(None).is_ok());
+
+            let mut state_keyed = Blake2bp::new(Some(&sk), 64).unwrap();
+            assert!(state_keyed.reset(None).is_err());
+            assert!(state_keyed.reset(Some(&sk)).is_ok());
+        }
+    }
+
+    mod test_verify {
+        use super::*;
+
+        #[test]
+        fn test_verify() {
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let mut state = Blake2bp::new(Some(&sk), 32).unwrap();
+            state.update(&[0u8; 1000]).unwrap();
+            let mac = state.finalize().unwrap();
+
+            assert!(Blake2bp::verify(&mac, &sk, 32, &[0u8; 1000]).is_ok());
+            assert!(Blake2bp::verify(&mac, &sk, 32, &[0u8; 999]).is_err());
+            assert!(Blake2bp::verify(&mac, &sk, 64, &[0u8; 1000]).is_err());
+
+            let bad_sk = SecretKey::from_slice(&[2u8; 32]).unwrap();
+            assert!(Blake2bp::verify(&mac, &bad_sk, 32, &[0u8; 1000]).is_err());
+        }
+
+        #[test]
+        fn test_keyed_differs_from_unkeyed() {
+            let sk = SecretKey::from_slice(&[0u8; 32]).unwrap();
+            let mut keyed = Blake2bp::new(Some(&sk), 64).unwrap();
+            let mut unkeyed = Blake2bp::new(None, 64).unwrap();
+            keyed.update(b"data").unwrap();
+            unkeyed.update(b"data").unwrap();
+
+            assert_ne!(keyed.finalize().unwrap(), unkeyed.finalize().unwrap());
+        }
+    }
+
+    mod test_vectors {
+        use super::*;
+
+        fn input(len: usize) -> [u8; 5000] {
+            let mut data = [0u8; 5000];
+            for (idx, byte) in data.iter_mut().enumerate().take(len) {
+                *byte = (idx % 251) as u8;
+            }
+            data
+        }
+
+        #[test]
+        fn test_unkeyed_vectors() {
+            // Computed with the BLAKE2b tree parameters of Python's hashlib.
+            let vectors: [(usize, &str); 11] = [
+                (0, "b5ef811a8038f70b628fa8b294daae7492b1ebe343a80eaabbf1f6ae664dd67b9d90b0120791eab81dc96985f28849f6a305186a85501b405114bfa678df9380"),
+                (1, "a139280e72757b723e6473d5be59f36e9d50fc5cd7d4585cbc09804895a36c521242fb2789f85cb9e35491f31d4a6952f9d8e097aef94fa1ca0b12525721f03d"),
+                (127, "ea64b003a135766121cfbccbdc08dca2402926be78cea3d0a7253d9ec9e63b8acdd994559917e0e03b5e155f944d7198d99245a794ce19c9b4df4da4a3399334"),
+                (128, "05ad0f271faf7e361320518452813ff9fb9976ac378050b6eefb05f7867b577b8f14475794cff61b2bc062d346a7c65c6e0067c60a374af7940f10aa449d5fb9"),
+                (129, "b545880294afa153f8b9f49c73d952b5d1228f1a1ab5ebcb05ff79e560c030f7500fe256a40b6a0e6cb3d42acd4b98595c5b51eaec5ad69cd40f1fc16d2d5f50"),
+                (511, "c86d92d70ab59ba357a987bd6f90e938a8ed5a8541bb387648a992f11063bfa9b339562efaccb7553c9e4af5f02b16a73b51c2665d9e817bfc94c5b192b43a5f"),
+                (512, "61c4dabacdfb1352185aae9dbc04b348af681478b0c4aa7291c7bab11783e8afe05830d87b6e003bbd95a08d9db6b053f12e75602fd5f1c1f49d39cd6c12b40b"),
+                (513, "c62cf13185f8eb971737218c9ae187f6447dfd286d206c7d42f442c719527c59d4655ca5829bf3912d284b916f5bdaa36672363bdca29b0ed2047ba98404a2ad"),
+                (1024, "1d37eac00a55afe13b8affbf6c3fd60e3608ef9479bb48e88a26a7fc5667a8c57845ecdc1e9e4b45a03bae187a150af93fb09be6cd96ccd954cbbe30c9be7d25"),
+                (1025, "628ba9706b121c0e05d24c9d72538d22e8e6f6d5ab99ba04b95744e8e4e878b4353d10a354a44788f8b867550b64af60a71ca33290e67d24d8b811a7a8b3f644"),
+                (5000, "8b33d6034750171716d5951a7e8df9cd7188ed2b859de92a53c0c61f48007b85ee78679522636897950b0143c49c522e58f0531ab3c88329e276428ac98938bd"),
+            ];
+
+            for (len, expected) in vectors.iter() {
+                let data = input(*len);
+                let mut state = Blake2bp::new(None, 64).unwrap();
+                state.update(&data[..*len]).unwrap();
+                let digest = state.finalize().unwrap();
+
+                let mut expected_bytes = [0u8; 64];
+                for (idx, byte) in expected_bytes.iter_mut().enumerate() {
+                    *byte = u8::from_str_radix(&expected[idx * 2..idx * 2 + 2], 16).unwrap();
+                }
+                assert_eq!(digest.as_ref(), &expected_bytes[..]);
+            }
+        }
+    }
+}
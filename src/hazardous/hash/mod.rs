@@ -23,5 +23,11 @@
 /// BLAKE2b as specified in the [RFC 7693](https://tools.ietf.org/html/rfc7693).
 pub mod blake2b;
 
+/// BLAKE2bp as specified in the [BLAKE2 paper](https://www.blake2.net/blake2.pdf).
+pub mod blake2bp;
+
 /// SHA2 as specified in the [FIPS PUB 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf).
 pub mod sha2;
+
+/// SHA3 and SHAKE as specified in the [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf).
+pub(crate) mod sha3;
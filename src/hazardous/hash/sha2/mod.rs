@@ -30,9 +30,10 @@ pub mod sha384;
 pub mod sha512;
 
 pub(crate) mod sha2_core {
-    use crate::errors::UnknownCryptoError;
+    use crate::errors::{ErrorKind, UnknownCryptoError};
     use core::fmt::Debug;
     use core::marker::PhantomData;
+    use core::mem::size_of;
     use core::ops::*;
     use zeroize::Zeroize;
 
@@ -179,7 +180,7 @@ pub(crate) mod sha2_core {
         > State<W, T, BLOCKSIZE, OUTSIZE, N_CONSTS>
     {
         /// Increment the message length during processing of data.
-        pub(crate) fn increment_mlen(&mut self, length: &W) {
+        pub(crate) fn increment_mlen(&mut self, length: &W) -> Result<(), UnknownCryptoError> {
             // The checked shift checks that the right-hand side is a legal shift.
             // The result can still overflow if length > $primitive::MAX / 8.
             // Should be impossible for a user to trigger, because update() processes
@@ -188,15 +189,22 @@ pub(crate) mod sha2_core {
             debug_assert!(length.less_than_or_equal(W::MAX / W::from(8)));
 
             // left-shift to get bit-sized representation of length
-            // using .unwrap() because it should not panic in practice
-            let len = length.checked_shl(3).unwrap();
+            let len = match length.checked_shl(3) {
+                Some(len) => len,
+                None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+            };
             let (res, was_overflow) = self.message_len[1].overflowing_add(len);
-            self.message_len[1] = res;
 
             if was_overflow {
-                // If this panics size limit is reached.
-                self.message_len[0] = self.message_len[0].checked_add(W::one()).unwrap();
+                // If this fails, the size limit is reached.
+                self.message_len[0] = match self.message_len[0].checked_add(W::one()) {
+                    Some(next) => next,
+                    None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+                };
             }
+            self.message_len[1] = res;
+
+            Ok(())
         }
 
         #[allow(clippy::many_single_char_names)]
@@ -303,7 +311,7 @@ pub(crate) mod sha2_core {
         /// Update state with `data`. This can be called multiple times.
         pub(crate) fn _update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
             if self.is_finalized {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
             }
             if data.is_empty() {
                 return Ok(());
@@ -325,7 +333,7 @@ pub(crate) mod sha2_core {
 
                 bytes = &bytes[want..];
                 self.leftover += want;
-                self.increment_mlen(&W::from(want));
+                self.increment_mlen(&W::from(want))?;
 
                 if self.leftover < BLOCKSIZE {
                     return Ok(());
@@ -337,7 +345,7 @@ pub(crate) mod sha2_core {
 
             while bytes.len() >= BLOCKSIZE {
                 self.process(Some(bytes[..BLOCKSIZE].as_ref()));
-                self.increment_mlen(&W::from(BLOCKSIZE));
+                self.increment_mlen(&W::from(BLOCKSIZE))?;
                 bytes = &bytes[BLOCKSIZE..];
             }
 
@@ -345,7 +353,7 @@ pub(crate) mod sha2_core {
                 debug_assert!(self.leftover == 0);
                 self.buffer[..bytes.len()].copy_from_slice(bytes);
                 self.leftover = bytes.len();
-                self.increment_mlen(&W::from(bytes.len()));
+                self.increment_mlen(&W::from(bytes.len()))?;
             }
 
             Ok(())
@@ -356,7 +364,7 @@ pub(crate) mod sha2_core {
             // NOTE: We need to support less than OUTSIZE in HKDF through HMAC.
             // debug_assert!(dest.len() == OUTSIZE);
             if self.is_finalized {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
             }
 
             self.is_finalized = true;
@@ -390,6 +398,63 @@ pub(crate) mod sha2_core {
             Ok(())
         }
 
+        /// The size of an exported state: the working state and message length,
+        /// followed by the number of buffered bytes and the buffer.
+        pub(crate) const _STATESIZE: usize = (size_of::<W>() * 10) + 1 + BLOCKSIZE;
+
+        /// Export the state into `dest`. The state must not be finalized.
+        pub(crate) fn _export_state(&self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            if dest.len() != Self::_STATESIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+            if self.is_finalized {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+            }
+            debug_assert!(self.leftover < BLOCKSIZE);
+
+            let (working_state, rest) = dest.split_at_mut(W::size_of() * 8);
+            let (message_len, rest) = rest.split_at_mut(W::size_of() * 2);
+            W::as_be_bytes(&self.working_state, working_state);
+            W::as_be_bytes(&self.message_len, message_len);
+            rest[0] = self.leftover as u8;
+            rest[1..=self.leftover].copy_from_slice(&self.buffer[..self.leftover]);
+            rest[self.leftover + 1..].iter_mut().for_each(|b| *b = 0u8);
+
+            Ok(())
+        }
+
+        /// Import a state previously exported with `_export_state()`.
+        pub(crate) fn _import_state(src: &[u8]) -> Result<Self, UnknownCryptoError> {
+            if src.len() != Self::_STATESIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let (working_state, rest) = src.split_at(W::size_of() * 8);
+            let (message_len, rest) = rest.split_at(W::size_of() * 2);
+            let leftover = rest[0] as usize;
+            // The number of buffered bytes must be consistent with the message length
+            // in bits, whose lowest bits are at the end of `message_len`.
+            let len_low = u16::from_be_bytes([
+                message_len[message_len.len() - 2],
+                message_len[message_len.len() - 1],
+            ]) as usize;
+            if leftover >= BLOCKSIZE
+                || len_low & 7 != 0
+                || (len_low / 8) % BLOCKSIZE != leftover
+                || rest[leftover + 1..].iter().any(|b| *b != 0)
+            {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+            }
+
+            let mut state = Self::_new();
+            W::from_be_bytes(working_state, &mut state.working_state);
+            W::from_be_bytes(message_len, &mut state.message_len);
+            state.leftover = leftover;
+            state.buffer[..leftover].copy_from_slice(&rest[1..=leftover]);
+
+            Ok(state)
+        }
+
         #[cfg(test)]
         /// Compare two Sha2 state objects to check if their fields
         /// are the same.
@@ -407,7 +472,7 @@ pub(crate) mod sha2_core {
 }
 
 pub(crate) mod w32 {
-    use core::convert::{From, TryFrom, TryInto};
+    use core::convert::{From, TryFrom};
     use core::ops::*;
     use zeroize::Zeroize;
 
@@ -451,8 +516,9 @@ pub(crate) mod w32 {
     }
 
     impl From<usize> for WordU32 {
+        #[allow(clippy::unwrap_used)]
         fn from(value: usize) -> Self {
-            // NOTE: Should never panic
+            // NOTE: Only called with lengths up to BLOCKSIZE, so this never panics.
             Self(u32::try_from(value).unwrap())
         }
     }
@@ -528,7 +594,9 @@ pub(crate) mod w32 {
 
         #[inline]
         fn from_be(src: &[u8]) -> Self {
-            Self(u32::from_be_bytes(src.try_into().unwrap()))
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(src);
+            Self(u32::from_be_bytes(bytes))
         }
 
         #[inline]
@@ -555,7 +623,7 @@ pub(crate) mod w32 {
 }
 
 pub(crate) mod w64 {
-    use core::convert::{From, TryFrom, TryInto};
+    use core::convert::{From, TryFrom};
     use core::ops::*;
     use zeroize::Zeroize;
 
@@ -599,8 +667,9 @@ pub(crate) mod w64 {
     }
 
     impl From<usize> for WordU64 {
+        #[allow(clippy::unwrap_used)]
         fn from(value: usize) -> Self {
-            // NOTE: Should never panic
+            // NOTE: Only called with lengths up to BLOCKSIZE, so this never panics.
             Self(u64::try_from(value).unwrap())
         }
     }
@@ -676,7 +745,9 @@ pub(crate) mod w64 {
 
         #[inline]
         fn from_be(src: &[u8]) -> Self {
-            Self(u64::from_be_bytes(src.try_into().unwrap()))
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(src);
+            Self(u64::from_be_bytes(bytes))
         }
 
         #[inline]
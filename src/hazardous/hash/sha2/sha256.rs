@@ -28,9 +28,9 @@
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
-//!
-//! # Panics:
-//! A panic will occur if:
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between, or `dst_out` is not the size of an exported state.
+//! - The state given to [`import_state()`] is not the size of an exported state or is malformed.
 //! - More than 2*(2^32-1) __bits__ of data are hashed.
 //!
 //! # Security:
@@ -56,6 +56,8 @@
 //! ```
 //! [`update()`]: sha256::Sha256::update
 //! [`reset()`]: sha256::Sha256::reset
+//! [`export_state()`]: sha256::Sha256::export_state
+//! [`import_state()`]: sha256::Sha256::import_state
 //! [`finalize()`]: sha256::Sha256::finalize
 //! [BLAKE2b]: super::blake2b
 
@@ -65,6 +67,8 @@ use crate::errors::UnknownCryptoError;
 pub const SHA256_BLOCKSIZE: usize = 64;
 /// The output size for the hash function SHA256.
 pub const SHA256_OUTSIZE: usize = 32;
+/// The size of a SHA256 state exported with [`Sha256::export_state()`].
+pub const SHA256_STATESIZE: usize = 105;
 /// The number of constants for the hash function SHA256.
 const N_CONSTS: usize = 64;
 
@@ -79,6 +83,10 @@ construct_public! {
 
 impl_from_trait!(Digest, SHA256_OUTSIZE);
 
+impl Digest {
+    func_to_hex!(as_ref);
+}
+
 use super::sha2_core::{State, Variant, Word};
 use super::w32::WordU32;
 
@@ -185,6 +193,22 @@ impl Sha256 {
         Ok(Digest::from(digest))
     }
 
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state into `dst_out`, so that hashing can be resumed later with
+    /// [`Sha256::import_state()`]. The state cannot be exported after [`Sha256::finalize()`].
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self._state._export_state(dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Resume hashing from a state exported with [`Sha256::export_state()`]. An error
+    /// is returned if `state` is not [`SHA256_STATESIZE`] bytes or is malformed.
+    pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            _state: State::_import_state(state)?,
+        })
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Calculate a SHA256 digest of some `data`.
     pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
@@ -223,6 +247,19 @@ impl crate::hazardous::mac::hmac::HmacHashFunction for Sha256 {
         ctx._finalize_internal(dest)
     }
 
+    /// The size of an exported state of the hash function.
+    const _STATESIZE: usize = SHA256_STATESIZE;
+
+    /// Export the state into `dest`.
+    fn _export_state(&self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.export_state(dest)
+    }
+
+    /// Import a state previously exported with `_export_state()`.
+    fn _import_state(src: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Self::import_state(src)
+    }
+
     #[cfg(test)]
     fn compare_state_to_other(&self, other: &Self) {
         self._state.compare_state_to_other(&other._state);
@@ -234,6 +271,9 @@ impl crate::hazardous::mac::hmac::HmacHashFunction for Sha256 {
 mod public {
     use super::*;
 
+    #[cfg(feature = "safe_api")]
+    test_to_hex!(Digest, SHA256_OUTSIZE, as_ref);
+
     #[test]
     fn test_default_equals_new() {
         let new = Sha256::new();
@@ -250,6 +290,60 @@ mod public {
         assert_eq!(debug, expected);
     }
 
+    mod test_export_import_state {
+        use super::*;
+
+        #[test]
+        fn test_statesize() {
+            assert_eq!(
+                SHA256_STATESIZE,
+                State::<WordU32, V256, { SHA256_BLOCKSIZE }, { SHA256_OUTSIZE }, { N_CONSTS }>::_STATESIZE
+            );
+        }
+
+        #[test]
+        fn test_resume_every_split() {
+            let data = [7u8; SHA256_BLOCKSIZE * 2 + 3];
+            let expected = Sha256::digest(&data).unwrap();
+
+            for split in 0..=data.len() {
+                let mut ctx = Sha256::new();
+                ctx.update(&data[..split]).unwrap();
+                let mut state = [0u8; SHA256_STATESIZE];
+                ctx.export_state(&mut state).unwrap();
+
+                let mut resumed = Sha256::import_state(&state).unwrap();
+                resumed._state.compare_state_to_other(&ctx._state);
+                resumed.update(&data[split..]).unwrap();
+                assert_eq!(resumed.finalize().unwrap(), expected);
+            }
+        }
+
+        #[test]
+        fn test_export_import_errors() {
+            let mut ctx = Sha256::new();
+            ctx.update(b"abc").unwrap();
+            let mut state = [0u8; SHA256_STATESIZE];
+            assert!(ctx
+                .export_state(&mut state[..SHA256_STATESIZE - 1])
+                .is_err());
+            ctx.export_state(&mut state).unwrap();
+
+            assert!(Sha256::import_state(&state[..SHA256_STATESIZE - 1]).is_err());
+            // Number of buffered bytes not matching the message length.
+            let mut bad = state;
+            bad[SHA256_STATESIZE - SHA256_BLOCKSIZE - 1] = 4;
+            assert!(Sha256::import_state(&bad).is_err());
+            // Non-zero bytes after the buffered data.
+            let mut bad = state;
+            bad[SHA256_STATESIZE - 1] = 1;
+            assert!(Sha256::import_state(&bad).is_err());
+
+            let _ = ctx.finalize().unwrap();
+            assert!(ctx.export_state(&mut state).is_err());
+        }
+    }
+
     mod test_streaming_interface {
         use super::*;
         use crate::test_framework::incremental_interface::*;
@@ -277,7 +371,9 @@ mod public {
                 if &actual == expected {
                     Ok(())
                 } else {
-                    Err(UnknownCryptoError)
+                    Err(UnknownCryptoError::new(
+                        crate::errors::ErrorKind::MacMismatch,
+                    ))
                 }
             }
 
@@ -326,32 +422,40 @@ mod private {
         fn test_mlen_increase_values() {
             let mut context = Sha256::default();
 
-            context._state.increment_mlen(&WordU32::from(1u32));
+            context._state.increment_mlen(&WordU32::from(1u32)).unwrap();
             assert!(context._state.message_len[0] == WordU32::from(0u32));
             assert!(context._state.message_len[1] == WordU32::from(8u32));
 
-            context._state.increment_mlen(&WordU32::from(17u32));
+            context
+                ._state
+                .increment_mlen(&WordU32::from(17u32))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU32::from(0u32));
             assert!(context._state.message_len[1] == WordU32::from(144u32));
 
-            context._state.increment_mlen(&WordU32::from(12u32));
+            context
+                ._state
+                .increment_mlen(&WordU32::from(12u32))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU32::from(0u32));
             assert!(context._state.message_len[1] == WordU32::from(240u32));
 
             // Overflow
-            context._state.increment_mlen(&WordU32::from(u32::MAX / 8));
+            context
+                ._state
+                .increment_mlen(&WordU32::from(u32::MAX / 8))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU32::from(1u32));
             assert!(context._state.message_len[1] == WordU32::from(232u32));
         }
 
         #[test]
-        #[should_panic]
-        fn test_panic_on_second_overflow() {
+        fn test_err_on_second_overflow() {
             let mut context = Sha256::default();
             context._state.message_len = [WordU32::MAX, WordU32::from(u32::MAX - 7)];
             // u32::MAX - 7, to leave so that the length represented
             // in bites should overflow by exactly one.
-            context._state.increment_mlen(&WordU32::from(1u32));
+            assert!(context._state.increment_mlen(&WordU32::from(1u32)).is_err());
         }
     }
 }
@@ -28,9 +28,9 @@
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
-//!
-//! # Panics:
-//! A panic will occur if:
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between, or `dst_out` is not the size of an exported state.
+//! - The state given to [`import_state()`] is not the size of an exported state or is malformed.
 //! - More than 2*(2^64-1) __bits__ of data are hashed.
 //!
 //! # Security:
@@ -56,6 +56,8 @@
 //! ```
 //! [`update()`]: sha384::Sha384::update
 //! [`reset()`]: sha384::Sha384::reset
+//! [`export_state()`]: sha384::Sha384::export_state
+//! [`import_state()`]: sha384::Sha384::import_state
 //! [`finalize()`]: sha384::Sha384::finalize
 //! [BLAKE2b]: super::blake2b
 
@@ -72,6 +74,10 @@ construct_public! {
 
 impl_from_trait!(Digest, SHA384_OUTSIZE);
 
+impl Digest {
+    func_to_hex!(as_ref);
+}
+
 use super::sha2_core::{State, Variant};
 use super::w64::WordU64;
 
@@ -79,6 +85,8 @@ use super::w64::WordU64;
 pub const SHA384_BLOCKSIZE: usize = 128;
 /// The output size for the hash function SHA384.
 pub const SHA384_OUTSIZE: usize = 48;
+/// The size of a SHA384 state exported with [`Sha384::export_state()`].
+pub const SHA384_STATESIZE: usize = 209;
 /// The number of constants for the hash function SHA384.
 const N_CONSTS: usize = 80;
 
@@ -165,6 +173,22 @@ impl Sha384 {
         Ok(Digest::from(digest))
     }
 
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state into `dst_out`, so that hashing can be resumed later with
+    /// [`Sha384::import_state()`]. The state cannot be exported after [`Sha384::finalize()`].
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self._state._export_state(dst_out)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Resume hashing from a state exported with [`Sha384::export_state()`]. An error
+    /// is returned if `state` is not [`SHA384_STATESIZE`] bytes or is malformed.
+    pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            _state: State::_import_state(state)?,
+        })
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Calculate a SHA384 digest of some `data`.
     pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
@@ -203,6 +227,19 @@ impl crate::hazardous::mac::hmac::HmacHashFunction for Sha384 {
         ctx._finalize_internal(dest)
     }
 
+    /// The size of an exported state of the hash function.
+    const _STATESIZE: usize = SHA384_STATESIZE;
+
+    /// Export the state into `dest`.
+    fn _export_state(&self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        self.export_state(dest)
+    }
+
+    /// Import a state previously exported with `_export_state()`.
+    fn _import_state(src: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Self::import_state(src)
+    }
+
     #[cfg(test)]
     fn compare_state_to_other(&self, other: &Self) {
         self._state.compare_state_to_other(&other._state);
@@ -214,6 +251,9 @@ impl crate::hazardous::mac::hmac::HmacHashFunction for Sha384 {
 mod public {
     use super::*;
 
+    #[cfg(feature = "safe_api")]
+    test_to_hex!(Digest, SHA384_OUTSIZE, as_ref);
+
     #[test]
     fn test_default_equals_new() {
         let new = Sha384::new();
@@ -230,6 +270,60 @@ mod public {
         assert_eq!(debug, expected);
     }
 
+    mod test_export_import_state {
+        use super::*;
+
+        #[test]
+        fn test_statesize() {
+            assert_eq!(
+                SHA384_STATESIZE,
+                State::<WordU64, V384, { SHA384_BLOCKSIZE }, { SHA384_OUTSIZE }, { N_CONSTS }>::_STATESIZE
+            );
+        }
+
+        #[test]
+        fn test_resume_every_split() {
+            let data = [7u8; SHA384_BLOCKSIZE * 2 + 3];
+            let expected = Sha384::digest(&data).unwrap();
+
+            for split in 0..=data.len() {
+                let mut ctx = Sha384::new();
+                ctx.update(&data[..split]).unwrap();
+                let mut state = [0u8; SHA384_STATESIZE];
+                ctx.export_state(&mut state).unwrap();
+
+                let mut resumed = Sha384::import_state(&state).unwrap();
+                resumed._state.compare_state_to_other(&ctx._state);
+                resumed.update(&data[split..]).unwrap();
+                assert_eq!(resumed.finalize().unwrap(), expected);
+            }
+        }
+
+        #[test]
+        fn test_export_import_errors() {
+            let mut ctx = Sha384::new();
+            ctx.update(b"abc").unwrap();
+            let mut state = [0u8; SHA384_STATESIZE];
+            assert!(ctx
+                .export_state(&mut state[..SHA384_STATESIZE - 1])
+                .is_err());
+            ctx.export_state(&mut state).unwrap();
+
+            assert!(Sha384::import_state(&state[..SHA384_STATESIZE - 1]).is_err());
+            // Number of buffered bytes not matching the message length.
+            let mut bad = state;
+            bad[SHA384_STATESIZE - SHA384_BLOCKSIZE - 1] = 4;
+            assert!(Sha384::import_state(&bad).is_err());
+            // Non-zero bytes after the buffered data.
+            let mut bad = state;
+            bad[SHA384_STATESIZE - 1] = 1;
+            assert!(Sha384::import_state(&bad).is_err());
+
+            let _ = ctx.finalize().unwrap();
+            assert!(ctx.export_state(&mut state).is_err());
+        }
+    }
+
     mod test_streaming_interface {
         use super::*;
         use crate::test_framework::incremental_interface::*;
@@ -257,7 +351,9 @@ mod public {
                 if &actual == expected {
                     Ok(())
                 } else {
-                    Err(UnknownCryptoError)
+                    Err(UnknownCryptoError::new(
+                        crate::errors::ErrorKind::MacMismatch,
+                    ))
                 }
             }
 
@@ -306,34 +402,42 @@ mod private {
         fn test_mlen_increase_values() {
             let mut context = Sha384::default();
 
-            context._state.increment_mlen(&WordU64::from(1u64));
+            context._state.increment_mlen(&WordU64::from(1u64)).unwrap();
             assert!(context._state.message_len[0] == WordU64::from(0u64));
             assert!(context._state.message_len[1] == WordU64::from(8u64));
 
-            context._state.increment_mlen(&WordU64::from(17u64));
+            context
+                ._state
+                .increment_mlen(&WordU64::from(17u64))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU64::from(0u64));
             assert!(context._state.message_len[1] == WordU64::from(144u64));
 
-            context._state.increment_mlen(&WordU64::from(12u64));
+            context
+                ._state
+                .increment_mlen(&WordU64::from(12u64))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU64::from(0u64));
             assert!(context._state.message_len[1] == WordU64::from(240u64));
 
             // Overflow
-            context._state.increment_mlen(&WordU64::from(u64::MAX / 8));
+            context
+                ._state
+                .increment_mlen(&WordU64::from(u64::MAX / 8))
+                .unwrap();
             assert!(context._state.message_len[0] == WordU64::from(1u64));
             assert!(context._state.message_len[1] == WordU64::from(232u64));
         }
 
         #[test]
-        #[should_panic]
-        fn test_panic_on_second_overflow() {
+        fn test_err_on_second_overflow() {
             use crate::hazardous::hash::sha2::sha2_core::Word;
 
             let mut context = Sha384::default();
             context._state.message_len = [WordU64::MAX, WordU64::from(u64::MAX - 7)];
             // u64::MAX - 7, to leave so that the length represented
             // in bites should overflow by exactly one.
-            context._state.increment_mlen(&WordU64::from(1u64));
+            assert!(context._state.increment_mlen(&WordU64::from(1u64)).is_err());
         }
     }
 }
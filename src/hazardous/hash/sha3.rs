@@ -0,0 +1,291 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! SHA3-256, SHA3-512, SHAKE128 and SHAKE256, as specified in the
+//! [FIPS PUB 202](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf).
+//!
+//! These are only used internally by the post-quantum schemes, for now.
+
+use zeroize::Zeroize;
+
+/// The Keccak-f\[1600\] round constants.
+const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, in the order lanes are visited by the pi step.
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// The lane permutation of the pi step.
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// The Keccak-f\[1600\] permutation.
+fn keccak_f(a: &mut [u64; 25]) {
+    for rc in RC.iter() {
+        // Theta
+        let mut c = [0u64; 5];
+        for (x, lane) in c.iter_mut().enumerate() {
+            *lane = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // Rho and pi
+        let mut last = a[1];
+        for (pi, rho) in PI.iter().zip(RHO.iter()) {
+            let tmp = a[*pi];
+            a[*pi] = last.rotate_left(*rho);
+            last = tmp;
+        }
+
+        // Chi
+        for y in 0..5 {
+            let mut row = [0u64; 5];
+            row.copy_from_slice(&a[5 * y..5 * y + 5]);
+            for x in 0..5 {
+                a[5 * y + x] = row[x] ^ ((!row[(x + 1) % 5]) & row[(x + 2) % 5]);
+            }
+        }
+
+        // Iota
+        a[0] ^= rc;
+    }
+}
+
+#[derive(Clone)]
+/// A Keccak sponge with a rate of `RATE` bytes and the domain separation byte `DS`.
+pub(crate) struct Sponge<const RATE: usize, const DS: u8> {
+    state: [u64; 25],
+    pos: usize,
+    is_squeezing: bool,
+}
+
+impl<const RATE: usize, const DS: u8> Drop for Sponge<RATE, DS> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+impl<const RATE: usize, const DS: u8> Sponge<RATE, DS> {
+    pub(crate) fn new() -> Self {
+        debug_assert!(RATE < 200 && (RATE & 7) == 0);
+
+        Self {
+            state: [0u64; 25],
+            pos: 0,
+            is_squeezing: false,
+        }
+    }
+
+    /// XOR `byte` into the state at byte position `idx`.
+    fn xor_byte(&mut self, idx: usize, byte: u8) {
+        self.state[idx / 8] ^= (byte as u64) << (8 * (idx % 8));
+    }
+
+    /// Absorb `data`. Must not be called after the first call to [`Self::squeeze()`].
+    pub(crate) fn absorb(&mut self, data: &[u8]) {
+        debug_assert!(!self.is_squeezing);
+        for byte in data.iter() {
+            self.xor_byte(self.pos, *byte);
+            self.pos += 1;
+            if self.pos == RATE {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Squeeze output into `dst_out`. May be called repeatedly, to produce more output.
+    pub(crate) fn squeeze(&mut self, dst_out: &mut [u8]) {
+        if !self.is_squeezing {
+            self.xor_byte(self.pos, DS);
+            self.xor_byte(RATE - 1, 0x80);
+            keccak_f(&mut self.state);
+            self.pos = 0;
+            self.is_squeezing = true;
+        }
+
+        for byte in dst_out.iter_mut() {
+            if self.pos == RATE {
+                keccak_f(&mut self.state);
+                self.pos = 0;
+            }
+            *byte = (self.state[self.pos / 8] >> (8 * (self.pos % 8))) as u8;
+            self.pos += 1;
+        }
+    }
+}
+
+/// SHA3-256.
+pub(crate) type Sha3_256 = Sponge<136, 0x06>;
+
+/// SHA3-512.
+pub(crate) type Sha3_512 = Sponge<72, 0x06>;
+
+/// SHAKE128.
+pub(crate) type Shake128 = Sponge<168, 0x1f>;
+
+/// SHAKE256.
+pub(crate) type Shake256 = Sponge<136, 0x1f>;
+
+/// Compute the SHA3-256 digest of the concatenation of `parts`.
+pub(crate) fn sha3_256(parts: &[&[u8]]) -> [u8; 32] {
+    let mut ctx = Sha3_256::new();
+    for part in parts.iter() {
+        ctx.absorb(part);
+    }
+    let mut digest = [0u8; 32];
+    ctx.squeeze(&mut digest);
+
+    digest
+}
+
+/// Compute the SHA3-512 digest of the concatenation of `parts`.
+pub(crate) fn sha3_512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut ctx = Sha3_512::new();
+    for part in parts.iter() {
+        ctx.absorb(part);
+    }
+    let mut digest = [0u8; 64];
+    ctx.squeeze(&mut digest);
+
+    digest
+}
+
+/// Compute SHAKE256 of the concatenation of `parts`, filling `dst_out`.
+pub(crate) fn shake256(parts: &[&[u8]], dst_out: &mut [u8]) {
+    let mut ctx = Shake256::new();
+    for part in parts.iter() {
+        ctx.absorb(part);
+    }
+    ctx.squeeze(dst_out);
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    #[test]
+    fn test_sha3_256() {
+        assert_eq!(
+            sha3_256(&[b""])[..],
+            hex::decode("a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a")
+                .unwrap()[..]
+        );
+        assert_eq!(
+            sha3_256(&[b"a", b"bc"])[..],
+            hex::decode("3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532")
+                .unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_sha3_512() {
+        assert_eq!(
+            sha3_512(&[b"abc"])[..],
+            hex::decode(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e\
+                 10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0"
+            )
+            .unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_shake() {
+        let mut out = [0u8; 32];
+        shake256(&[b""], &mut out);
+        assert_eq!(
+            out[..],
+            hex::decode("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f")
+                .unwrap()[..]
+        );
+
+        let mut ctx = Shake128::new();
+        ctx.absorb(b"");
+        let mut out = [0u8; 32];
+        ctx.squeeze(&mut out);
+        assert_eq!(
+            out[..],
+            hex::decode("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26")
+                .unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_multi_block_absorb_and_squeeze() {
+        // Absorbing and squeezing across several blocks, in uneven pieces, must be
+        // the same as doing it in one go.
+        let data = [0x5au8; 1000];
+        let mut expected = [0u8; 500];
+        let mut ctx = Shake128::new();
+        ctx.absorb(&data);
+        ctx.squeeze(&mut expected);
+
+        let mut actual = [0u8; 500];
+        let mut ctx = Shake128::new();
+        for chunk in data.chunks(167) {
+            ctx.absorb(chunk);
+        }
+        for chunk in actual.chunks_mut(169) {
+            ctx.squeeze(chunk);
+        }
+
+        assert_eq!(expected[..], actual[..]);
+        assert_eq!(
+            actual[490..],
+            hex::decode("d5dcaa4661c129a2a5d3").unwrap()[..]
+        );
+    }
+}
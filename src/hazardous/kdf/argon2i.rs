@@ -24,7 +24,10 @@
 //! Argon2i version 1.3. This implementation is available with features `safe_api` and `alloc`.
 //!
 //! # Note:
-//! This implementation only supports a single thread/lane.
+//! The functions of this module only support Argon2i version 1.3 with a single lane.
+//! Other variants, versions and multiple lanes are only supported for verifying password
+//! hashes with [`orion::pwhash`](crate::pwhash), where the lanes are computed one after
+//! the other, on a single thread.
 //!
 //! # Parameters:
 //! - `expected`: The expected derived key.
@@ -92,11 +95,11 @@
 //! [`secure_rand_bytes()`]: crate::util::secure_rand_bytes
 //! [`zeroize` crate]: https://crates.io/crates/zeroize
 
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
 use crate::hazardous::hash::blake2b::{Blake2b, BLAKE2B_OUTSIZE};
 use crate::util;
 use crate::util::endianness::{load_u64_into_le, store_u64_into_le};
-use zeroize::Zeroize;
+use zeroize::{Zeroize, Zeroizing};
 
 /// The Argon2 version (0x13).
 pub const ARGON2_VERSION: u32 = 0x13;
@@ -104,9 +107,25 @@ pub const ARGON2_VERSION: u32 = 0x13;
 /// The Argon2 variant (i).
 pub const ARGON2_VARIANT: u32 = 1;
 
+/// The Argon2 version 0x10, which overwrites blocks instead of XORing them in
+/// passes after the first.
+pub(crate) const ARGON2_VERSION_10: u32 = 0x10;
+
+/// The Argon2d variant.
+pub(crate) const ARGON2D_VARIANT: u32 = 0;
+
+/// The Argon2id variant.
+pub(crate) const ARGON2ID_VARIANT: u32 = 2;
+
+/// The maximum amount of lanes allowed by the spec.
+pub(crate) const MAX_LANES: u32 = 0x00FF_FFFF;
+
 /// The amount of segments per lane, as defined in the spec.
 const SEGMENTS_PER_LANE: usize = 4;
 
+/// Returned if a block index is out of bounds, which the parameter checks prevent.
+const BAD_INDEX: UnknownCryptoError = UnknownCryptoError::new(ErrorKind::InvalidState);
+
 /// The amount of lanes supported.
 pub(crate) const LANES: u32 = 1;
 
@@ -166,7 +185,11 @@ fn permutation_p(
 }
 
 /// H0 as defined in the specification.
+#[allow(clippy::too_many_arguments)]
 fn initial_hash(
+    variant: u32,
+    version: u32,
+    lanes: u32,
     hash_length: u32,
     memory_kib: u32,
     passes: u32,
@@ -181,12 +204,12 @@ fn initial_hash(
     let mut hasher = Blake2b::new(None, BLAKE2B_OUTSIZE)?;
 
     // Collect the first part to reduce times we update the hasher state.
-    h0[0..4].copy_from_slice(&LANES.to_le_bytes());
+    h0[0..4].copy_from_slice(&lanes.to_le_bytes());
     h0[4..8].copy_from_slice(&hash_length.to_le_bytes());
     h0[8..12].copy_from_slice(&memory_kib.to_le_bytes());
     h0[12..16].copy_from_slice(&passes.to_le_bytes());
-    h0[16..20].copy_from_slice(&ARGON2_VERSION.to_le_bytes());
-    h0[20..24].copy_from_slice(&ARGON2_VARIANT.to_le_bytes());
+    h0[16..20].copy_from_slice(&version.to_le_bytes());
+    h0[20..24].copy_from_slice(&variant.to_le_bytes());
     h0[24..28].copy_from_slice(&(p.len() as u32).to_le_bytes());
 
     hasher.update(&h0[..28])?;
@@ -205,7 +228,7 @@ fn initial_hash(
 /// H' as defined in the specification.
 fn extended_hash(input: &[u8], dst: &mut [u8]) -> Result<(), UnknownCryptoError> {
     if dst.is_empty() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
     let outlen = dst.len() as u32;
@@ -301,32 +324,37 @@ fn fill_block(w: &mut [u64; 128]) {
 	}
 }
 
-/// Data-independent indexing.
+/// Data-independent addressing.
 struct Gidx {
     block: [u64; 128],
     addresses: [u64; 128],
-    segment_length: u32,
     offset: u32,
 }
 
 impl Gidx {
-    fn new(blocks: u32, passes: u32, segment_length: u32) -> Self {
+    fn new(blocks: u32, passes: u32, variant: u32) -> Self {
         let mut block = [0u64; 128];
-        block[1] = 0u64; // Lane number, we only support one (0u64).
         block[3] = u64::from(blocks);
         block[4] = u64::from(passes);
-        block[5] = u64::from(ARGON2_VARIANT); // The Argon2i variant
+        block[5] = u64::from(variant);
 
         Self {
             block,
             addresses: [0u64; 128],
-            segment_length,
             offset: 0,
         }
     }
 
-    fn init(&mut self, pass_n: u32, segment_n: u32, offset: u32, tmp_block: &mut [u64; 128]) {
+    fn init(
+        &mut self,
+        pass_n: u32,
+        lane_n: u32,
+        segment_n: u32,
+        offset: u32,
+        tmp_block: &mut [u64; 128],
+    ) {
         self.block[0] = u64::from(pass_n);
+        self.block[1] = u64::from(lane_n);
         self.block[2] = u64::from(segment_n);
         self.block[6] = 0u64; // Counter
         self.offset = offset;
@@ -350,42 +378,64 @@ impl Gidx {
         xor_slices!(tmp_block, self.addresses);
     }
 
-    fn get_next(&mut self, segment_idx: u32, tmp_block: &mut [u64; 128]) -> u32 {
-        // We get J1 and discard J2, as J2 is only relevant if we had more than
-        // a single lane.
-        let j1: u64 = self.addresses[self.offset as usize] & 0xFFFF_FFFFu64;
+    /// Get the next pseudo-random value, J1 in the lower and J2 in the upper 32 bits.
+    fn get_next(&mut self, tmp_block: &mut [u64; 128]) -> u64 {
+        let pseudo_rand = self.addresses[self.offset as usize];
         self.offset = (self.offset + 1) % 128; // Wrap-around on block length.
         if self.offset == 0 {
             self.next_addresses(tmp_block);
         }
 
-        // The Argon2 specification for this version (1.3) does not conform
-        // to the official reference implementation. This implementation follows
-        // the reference implementation and ignores the specification where they
-        // disagree. See https://github.com/P-H-C/phc-winner-argon2/issues/183.
-
-        let n_blocks = self.block[3] as u32;
-        let pass_n = self.block[0] as u32;
-        let segment_n = self.block[2] as u32;
+        pseudo_rand
+    }
+}
 
-        let ref_start_pos: u32 = if pass_n == 0 && segment_n == 0 {
-            segment_idx - 1
-        } else if pass_n == 0 {
-            segment_n * self.segment_length + segment_idx - 1
+/// Map J1 to the index of the reference block within its lane.
+///
+/// The Argon2 specification for version 1.3 does not conform to the official
+/// reference implementation. This implementation follows the reference
+/// implementation and ignores the specification where they disagree.
+/// See https://github.com/P-H-C/phc-winner-argon2/issues/183.
+fn reference_index(
+    pass_n: u32,
+    segment_n: u32,
+    segment_idx: u32,
+    j1: u64,
+    same_lane: bool,
+    segment_length: u32,
+) -> u32 {
+    let lane_length = segment_length * SEGMENTS_PER_LANE as u32;
+    // Blocks in other lanes can only be referenced once their segment has been
+    // completed. The block right before the current one is never referenced.
+    let ref_area_size: u32 = if pass_n == 0 && segment_n == 0 {
+        segment_idx - 1
+    } else if pass_n == 0 {
+        if same_lane {
+            segment_n * segment_length + segment_idx - 1
+        } else if segment_idx == 0 {
+            segment_n * segment_length - 1
         } else {
-            n_blocks - self.segment_length + segment_idx - 1
-        };
+            segment_n * segment_length
+        }
+    } else if same_lane {
+        lane_length - segment_length + segment_idx - 1
+    } else if segment_idx == 0 {
+        lane_length - segment_length - 1
+    } else {
+        lane_length - segment_length
+    };
 
-        let mut ref_pos: u64 = (j1 * j1) >> 32;
-        ref_pos = (ref_start_pos as u64 * ref_pos) >> 32;
-        ref_pos = (ref_start_pos as u64 - 1) - ref_pos;
+    let mut ref_pos: u64 = (j1 * j1) >> 32;
+    ref_pos = (u64::from(ref_area_size) * ref_pos) >> 32;
+    ref_pos = (u64::from(ref_area_size) - 1) - ref_pos;
 
-        if pass_n == 0 || segment_n == 3 {
-            ref_pos as u32 % n_blocks
-        } else {
-            (self.segment_length * (segment_n + 1) + ref_pos as u32) % n_blocks
-        }
-    }
+    let ref_start_pos: u64 = if pass_n == 0 || segment_n == 3 {
+        0
+    } else {
+        u64::from(segment_length * (segment_n + 1))
+    };
+
+    ((ref_start_pos + ref_pos) % u64::from(lane_length)) as u32
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -400,23 +450,64 @@ pub fn derive_key(
     ad: Option<&[u8]>,
     dst_out: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
+    derive_key_with(
+        ARGON2_VARIANT,
+        ARGON2_VERSION,
+        LANES,
+        password,
+        salt,
+        iterations,
+        memory,
+        secret,
+        ad,
+        dst_out,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Any Argon2 variant and version, with `lanes` computed one after the other.
+///
+/// This is only used to verify password hashes produced elsewhere
+/// (see [`PasswordHash::from_encoded()`](crate::pwhash::PasswordHash::from_encoded)).
+pub(crate) fn derive_key_with(
+    variant: u32,
+    version: u32,
+    lanes: u32,
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if variant != ARGON2D_VARIANT && variant != ARGON2_VARIANT && variant != ARGON2ID_VARIANT {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+    if version != ARGON2_VERSION && version != ARGON2_VERSION_10 {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+    if !(1..=MAX_LANES).contains(&lanes) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
     if password.len() > 0xFFFF_FFFF {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
     if salt.len() > 0xFFFF_FFFF || salt.len() < 8 {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
     if iterations < MIN_ITERATIONS {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
     }
-    if memory < MIN_MEMORY {
-        return Err(UnknownCryptoError);
+    if memory < MIN_MEMORY * lanes {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
     }
 
     let k = match secret {
         Some(n_val) => {
             if n_val.len() > 0xFFFF_FFFF {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
             }
 
             n_val
@@ -427,7 +518,7 @@ pub fn derive_key(
     let x = match ad {
         Some(n_val) => {
             if n_val.len() > 0xFFFF_FFFF {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
             }
 
             n_val
@@ -436,18 +527,21 @@ pub fn derive_key(
     };
 
     if dst_out.len() > 0xFFFF_FFFF || dst_out.len() < 4 {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
-    // Round down to 4 * p threads
-    let n_blocks = memory - (memory & 3);
-    // Divide by 4 (SEGMENTS_PER_LANE)
-    let segment_length = n_blocks >> 2;
+    // Divide by 4 (SEGMENTS_PER_LANE) * p
+    let segment_length = memory / (SEGMENTS_PER_LANE as u32 * lanes);
+    let lane_length = segment_length * SEGMENTS_PER_LANE as u32;
+    // Round down to 4 * p
+    let n_blocks = lane_length * lanes;
 
-    let mut blocks = vec![[0u64; 128]; n_blocks as usize];
+    let mut blocks = Zeroizing::new(vec![[0u64; 128]; n_blocks as usize]);
 
-    // Fill first two blocks
-    let mut h0 = initial_hash(
+    let mut h0 = Zeroizing::new(initial_hash(
+        variant,
+        version,
+        lanes,
         dst_out.len() as u32,
         memory,
         iterations,
@@ -455,73 +549,111 @@ pub fn derive_key(
         salt,
         k,
         x,
-    )?;
-    let mut tmp = [0u8; 1024];
+    )?);
+    let mut tmp = Zeroizing::new([0u8; 1024]);
     debug_assert!(h0.len() == ((core::mem::size_of::<u32>() * 2) + BLAKE2B_OUTSIZE));
-    debug_assert!(
+
+    // H' into the first two blocks of each lane
+    for lane_n in 0..lanes {
+        let lane_start = (lane_n * lane_length) as usize;
+        h0[BLAKE2B_OUTSIZE + core::mem::size_of::<u32>()..].copy_from_slice(&lane_n.to_le_bytes()); // Lane
+
         h0[BLAKE2B_OUTSIZE..(BLAKE2B_OUTSIZE + core::mem::size_of::<u32>())]
-            == [0u8; core::mem::size_of::<u32>()]
-    ); // Block 0
-    debug_assert!(
-        h0[BLAKE2B_OUTSIZE + core::mem::size_of::<u32>()..] == [0u8; core::mem::size_of::<u32>()]
-    ); // Lane
-
-    // H' into the first two blocks
-    extended_hash(&h0, &mut tmp)?;
-    load_u64_into_le(&tmp, &mut blocks[0]);
-    h0[BLAKE2B_OUTSIZE..(BLAKE2B_OUTSIZE + core::mem::size_of::<u32>())]
-        .copy_from_slice(&1u32.to_le_bytes()); // Block 1
-    extended_hash(&h0, &mut tmp)?;
-    load_u64_into_le(&tmp, &mut blocks[1]);
-
-    let mut gidx = Gidx::new(n_blocks, iterations, segment_length);
-    let mut working_block = [0u64; 128];
-
-    for pass_n in 0..iterations as usize {
-        for segment_n in 0..SEGMENTS_PER_LANE {
-            let offset = match (pass_n, segment_n) {
-                (0, 0) => 2, // The first two blocks have already been processed
-                _ => 0,
-            };
+            .copy_from_slice(&0u32.to_le_bytes()); // Block 0
+        extended_hash(&h0[..], &mut tmp[..])?;
+        load_u64_into_le(&tmp[..], &mut blocks[lane_start]);
 
-            gidx.init(pass_n as u32, segment_n as u32, offset, &mut working_block);
+        h0[BLAKE2B_OUTSIZE..(BLAKE2B_OUTSIZE + core::mem::size_of::<u32>())]
+            .copy_from_slice(&1u32.to_le_bytes()); // Block 1
+        extended_hash(&h0[..], &mut tmp[..])?;
+        load_u64_into_le(&tmp[..], &mut blocks[lane_start + 1]);
+    }
 
-            for segment_idx in offset..segment_length {
-                let reference_idx = gidx.get_next(segment_idx, &mut working_block);
-                let current_idx = segment_n as u32 * segment_length + segment_idx as u32;
-                let previous_idx = if current_idx > 0 {
-                    current_idx - 1
-                } else {
-                    n_blocks - 1
+    let mut gidx = Gidx::new(n_blocks, iterations, variant);
+    let mut working_block = Zeroizing::new([0u64; 128]);
+
+    for pass_n in 0..iterations {
+        for segment_n in 0..SEGMENTS_PER_LANE as u32 {
+            // Argon2id only uses data-independent addressing for the
+            // first half of the first pass.
+            let data_independent = variant == ARGON2_VARIANT
+                || (variant == ARGON2ID_VARIANT && pass_n == 0 && segment_n < 2);
+
+            // Lanes are processed sequentially. Within a segment, no lane
+            // references the segment that is being computed in another lane.
+            for lane_n in 0..lanes {
+                let offset = match (pass_n, segment_n) {
+                    (0, 0) => 2, // The first two blocks have already been processed
+                    _ => 0,
                 };
 
-                let prev_b = blocks.get(previous_idx as usize).unwrap();
-                let ref_b = blocks.get(reference_idx as usize).unwrap();
+                if data_independent {
+                    gidx.init(pass_n, lane_n, segment_n, offset, &mut working_block);
+                }
 
-                // G-xor operation
-                for (el_tmp, (el_prev, el_ref)) in working_block
-                    .iter_mut()
-                    .zip(prev_b.iter().zip(ref_b.iter()))
-                {
-                    *el_tmp = el_prev ^ el_ref;
+                for segment_idx in offset..segment_length {
+                    let lane_start = lane_n * lane_length;
+                    let current_idx = lane_start + segment_n * segment_length + segment_idx;
+                    let previous_idx = if current_idx > lane_start {
+                        current_idx - 1
+                    } else {
+                        lane_start + lane_length - 1
+                    };
+
+                    let pseudo_rand = if data_independent {
+                        gidx.get_next(&mut working_block)
+                    } else {
+                        blocks.get(previous_idx as usize).ok_or(BAD_INDEX)?[0]
+                    };
+
+                    let ref_lane = if pass_n == 0 && segment_n == 0 {
+                        lane_n
+                    } else {
+                        ((pseudo_rand >> 32) % u64::from(lanes)) as u32
+                    };
+                    let reference_idx = ref_lane * lane_length
+                        + reference_index(
+                            pass_n,
+                            segment_n,
+                            segment_idx,
+                            pseudo_rand & 0xFFFF_FFFFu64,
+                            ref_lane == lane_n,
+                            segment_length,
+                        );
+
+                    let prev_b = blocks.get(previous_idx as usize).ok_or(BAD_INDEX)?;
+                    let ref_b = blocks.get(reference_idx as usize).ok_or(BAD_INDEX)?;
+
+                    // G-xor operation
+                    for (el_tmp, (el_prev, el_ref)) in working_block
+                        .iter_mut()
+                        .zip(prev_b.iter().zip(ref_b.iter()))
+                    {
+                        *el_tmp = el_prev ^ el_ref;
+                    }
+                    let cur_b = blocks.get_mut(current_idx as usize).ok_or(BAD_INDEX)?;
+                    if version == ARGON2_VERSION_10 {
+                        // Version 0x10 overwrites the block instead of XORing into it.
+                        cur_b.zeroize();
+                    }
+                    xor_slices!(working_block, cur_b);
+                    fill_block(&mut working_block);
+                    xor_slices!(working_block, cur_b);
                 }
-                let cur_b = blocks.get_mut(current_idx as usize).unwrap();
-                xor_slices!(working_block, cur_b);
-                fill_block(&mut working_block);
-                xor_slices!(working_block, cur_b);
             }
         }
     }
 
-    store_u64_into_le(blocks.get(n_blocks as usize - 1).unwrap(), &mut tmp);
-    extended_hash(&tmp, dst_out)?;
-
-    working_block.zeroize();
-    tmp.zeroize();
-    h0.zeroize();
-    for block in blocks.iter_mut() {
-        block.zeroize();
+    // XOR the last block of each lane
+    let mut final_block = Zeroizing::new([0u64; 128]);
+    for lane_n in 0..lanes {
+        let last_b = blocks
+            .get((lane_n * lane_length + lane_length - 1) as usize)
+            .ok_or(BAD_INDEX)?;
+        xor_slices!(last_b, final_block);
     }
+    store_u64_into_le(&final_block[..], &mut tmp[..]);
+    extended_hash(&tmp[..], dst_out)?;
 
     Ok(())
 }
@@ -543,6 +675,29 @@ pub fn verify(
     util::secure_cmp(&dst_out, expected)
 }
 
+#[cfg(feature = "safe_api")]
+#[allow(clippy::too_many_arguments)]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a derived key of any Argon2 variant and version in constant time.
+pub(crate) fn verify_with(
+    variant: u32,
+    version: u32,
+    lanes: u32,
+    expected: &[u8],
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    memory: u32,
+    secret: Option<&[u8]>,
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    derive_key_with(
+        variant, version, lanes, password, salt, iterations, memory, secret, ad, dst_out,
+    )?;
+    util::secure_cmp(dst_out, expected)
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 mod public {
@@ -1008,6 +1163,180 @@ mod public {
 mod private {
     use super::*;
 
+    /// Test vectors from RFC 9106 (section 5), the reference implementation's
+    /// test suite (version 0x10) and the `cryptography` Python package (lanes
+    /// not dividing the memory).
+    mod test_derive_key_with {
+        use super::*;
+
+        fn rfc_9106(variant: u32) -> [u8; 32] {
+            let mut dst_out = [0u8; 32];
+            derive_key_with(
+                variant,
+                ARGON2_VERSION,
+                4,
+                &[1u8; 32],
+                &[2u8; 16],
+                3,
+                32,
+                Some(&[3u8; 8]),
+                Some(&[4u8; 12]),
+                &mut dst_out,
+            )
+            .unwrap();
+
+            dst_out
+        }
+
+        #[test]
+        fn test_rfc_9106_argon2d() {
+            assert_eq!(
+                rfc_9106(ARGON2D_VARIANT).as_ref(),
+                &hex::decode("512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb")
+                    .unwrap()[..]
+            );
+        }
+
+        #[test]
+        fn test_rfc_9106_argon2i() {
+            assert_eq!(
+                rfc_9106(ARGON2_VARIANT).as_ref(),
+                &hex::decode("c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8")
+                    .unwrap()[..]
+            );
+        }
+
+        #[test]
+        fn test_rfc_9106_argon2id() {
+            assert_eq!(
+                rfc_9106(ARGON2ID_VARIANT).as_ref(),
+                &hex::decode("0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659")
+                    .unwrap()[..]
+            );
+        }
+
+        #[test]
+        fn test_ref_version_10() {
+            let mut dst_out = [0u8; 32];
+            derive_key_with(
+                ARGON2_VARIANT,
+                ARGON2_VERSION_10,
+                1,
+                b"password",
+                b"somesalt",
+                2,
+                1 << 16,
+                None,
+                None,
+                &mut dst_out,
+            )
+            .unwrap();
+
+            assert_eq!(
+                dst_out.as_ref(),
+                &hex::decode("f6c4db4a54e2a370627aff3db6176b94a2a209a62c8e36152711802f7b30c694")
+                    .unwrap()[..]
+            );
+        }
+
+        #[test]
+        fn test_memory_rounded_down_to_lanes() {
+            let mut dst_out = [0u8; 32];
+            derive_key_with(
+                ARGON2ID_VARIANT,
+                ARGON2_VERSION,
+                3,
+                b"password",
+                b"somesaltsomesalt",
+                2,
+                100,
+                None,
+                None,
+                &mut dst_out,
+            )
+            .unwrap();
+
+            assert_eq!(
+                dst_out.as_ref(),
+                &hex::decode("b2e09be5fde45e9e39e720f17158c86353c3455f75a01f3cc290f14f6dda51a7")
+                    .unwrap()[..]
+            );
+        }
+
+        #[test]
+        fn test_same_as_derive_key() {
+            let mut dst_out = [0u8; 32];
+            let mut expected = [0u8; 32];
+            derive_key(b"password", &[0u8; 16], 3, 64, None, None, &mut expected).unwrap();
+            derive_key_with(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                b"password",
+                &[0u8; 16],
+                3,
+                64,
+                None,
+                None,
+                &mut dst_out,
+            )
+            .unwrap();
+
+            assert_eq!(dst_out, expected);
+        }
+
+        #[test]
+        fn test_invalid_params() {
+            let mut dst_out = [0u8; 32];
+            let mut derive = |variant: u32, version: u32, lanes: u32, memory: u32| {
+                derive_key_with(
+                    variant,
+                    version,
+                    lanes,
+                    b"password",
+                    &[0u8; 16],
+                    1,
+                    memory,
+                    None,
+                    None,
+                    &mut dst_out,
+                )
+            };
+
+            assert!(derive(3, ARGON2_VERSION, 1, 8).is_err());
+            assert!(derive(ARGON2D_VARIANT, 0x12, 1, 8).is_err());
+            assert!(derive(ARGON2D_VARIANT, ARGON2_VERSION, 0, 8).is_err());
+            assert!(derive(ARGON2D_VARIANT, ARGON2_VERSION, MAX_LANES + 1, 8).is_err());
+            assert!(derive(ARGON2D_VARIANT, ARGON2_VERSION, 2, 15).is_err());
+            assert!(derive(ARGON2D_VARIANT, ARGON2_VERSION, 2, 16).is_ok());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_verify_with() {
+            let mut dst_out = [0u8; 32];
+            let expected = rfc_9106(ARGON2ID_VARIANT);
+            let verify = |variant: u32, dst_out: &mut [u8]| {
+                verify_with(
+                    variant,
+                    ARGON2_VERSION,
+                    4,
+                    &expected,
+                    &[1u8; 32],
+                    &[2u8; 16],
+                    3,
+                    32,
+                    Some(&[3u8; 8]),
+                    Some(&[4u8; 12]),
+                    dst_out,
+                )
+            };
+
+            assert!(verify(ARGON2ID_VARIANT, &mut dst_out).is_ok());
+            assert!(verify(ARGON2D_VARIANT, &mut dst_out).is_err());
+        }
+    }
+
     mod test_initial_hash {
         use super::*;
 
@@ -1039,7 +1368,19 @@ mod private {
                 17, 49, 11, 228, 22, 128, 161, 57, 188, 136, 75, 96, 197, 3, 206, 224, 204, 65,
                 149, 190, 101, 231, 161, 232, 35, 87, 64, 0, 0, 0, 0, 0, 0, 0, 0,
             ];
-            let actual = initial_hash(hlen, kib, passes, &p, &s, &k, &x).unwrap();
+            let actual = initial_hash(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                hlen,
+                kib,
+                passes,
+                &p,
+                &s,
+                &k,
+                &x,
+            )
+            .unwrap();
             assert_eq!(expected.as_ref(), actual.as_ref());
         }
 
@@ -1077,7 +1418,19 @@ mod private {
                 15, 239, 64, 239, 203, 191, 226, 71, 213, 149, 238, 65, 124, 102, 1, 150, 230, 41,
                 132, 23, 176, 221, 217, 237, 150, 154, 249, 0, 0, 0, 0, 0, 0, 0, 0,
             ];
-            let actual = initial_hash(hlen, kib, passes, &p, &s, &k, &x).unwrap();
+            let actual = initial_hash(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                hlen,
+                kib,
+                passes,
+                &p,
+                &s,
+                &k,
+                &x,
+            )
+            .unwrap();
             assert_eq!(expected.as_ref(), actual.as_ref());
         }
 
@@ -1127,7 +1480,19 @@ mod private {
                 236, 58, 237, 193, 139, 30, 191, 244, 2, 176, 123, 134, 44, 251, 101, 255, 220,
                 218, 109, 249, 231, 200, 45, 232, 240, 155, 10, 93, 111, 0, 0, 0, 0, 0, 0, 0, 0,
             ];
-            let actual = initial_hash(hlen, kib, passes, &p, &s, &k, &x).unwrap();
+            let actual = initial_hash(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                hlen,
+                kib,
+                passes,
+                &p,
+                &s,
+                &k,
+                &x,
+            )
+            .unwrap();
             assert_eq!(expected.as_ref(), actual.as_ref());
         }
 
@@ -1142,8 +1507,32 @@ mod private {
             k: Vec<u8>,
             x: Vec<u8>,
         ) -> bool {
-            let first = initial_hash(hlen, kib, passes, &p, &s, &k, &x).unwrap();
-            let second = initial_hash(hlen, kib, passes, &p, &s, &k, &x).unwrap();
+            let first = initial_hash(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                hlen,
+                kib,
+                passes,
+                &p,
+                &s,
+                &k,
+                &x,
+            )
+            .unwrap();
+            let second = initial_hash(
+                ARGON2_VARIANT,
+                ARGON2_VERSION,
+                LANES,
+                hlen,
+                kib,
+                passes,
+                &p,
+                &s,
+                &k,
+                &x,
+            )
+            .unwrap();
 
             first.as_ref() == second.as_ref()
         }
@@ -1395,14 +1784,14 @@ mod private {
             let segment_length = 1024;
             let passes = 3;
 
-            let mut gidx = Gidx::new(n_blocks, passes, segment_length);
+            let mut gidx = Gidx::new(n_blocks, passes, ARGON2_VARIANT);
             let mut tmp_block = [0u64; 128];
 
             let offset = 2;
             let pass_n = 0;
             let segment_n = 0;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1022] = [
                 0, 1, 0, 3, 2, 1, 6, 3, 7, 8, 10, 11, 11, 4, 14, 7, 16, 13, 6, 17, 5, 20, 11, 19,
@@ -1471,7 +1860,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1482,7 +1878,7 @@ mod private {
             let pass_n = 0;
             let segment_n = 1;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 63, 227, 849, 503, 967, 726, 979, 1023, 1025, 514, 762, 161, 674, 961, 1033, 1035,
@@ -1562,7 +1958,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1573,7 +1976,7 @@ mod private {
             let pass_n = 0;
             let segment_n = 2;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 1171, 1043, 2046, 209, 1914, 1872, 570, 1427, 931, 1485, 2024, 1760, 2049, 1955,
@@ -1656,7 +2059,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1667,7 +2077,7 @@ mod private {
             let pass_n = 0;
             let segment_n = 3;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 1994, 1933, 3035, 2492, 2197, 748, 349, 140, 1121, 3079, 2168, 1970, 2197, 2830,
@@ -1750,7 +2160,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1761,7 +2178,7 @@ mod private {
             let pass_n = 1;
             let segment_n = 0;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 3988, 3490, 1643, 1572, 2, 2045, 1188, 3185, 1866, 1635, 3419, 1196, 3779, 2875,
@@ -1841,7 +2258,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1852,7 +2276,7 @@ mod private {
             let pass_n = 1;
             let segment_n = 1;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 3423, 3780, 3751, 584, 2089, 3394, 594, 952, 3177, 3537, 2536, 2720, 3812, 1004,
@@ -1934,7 +2358,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -1945,7 +2376,7 @@ mod private {
             let pass_n = 1;
             let segment_n = 2;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 1513, 2045, 1126, 245, 3943, 2044, 513, 1861, 1947, 1963, 2051, 1644, 1154, 1569,
@@ -2028,7 +2459,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -2039,7 +2477,7 @@ mod private {
             let pass_n = 1;
             let segment_n = 3;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 1181, 2089, 2541, 1308, 2531, 2731, 2917, 2994, 551, 2431, 3080, 1148, 2328, 3025,
@@ -2122,7 +2560,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -2133,7 +2578,7 @@ mod private {
             let pass_n = 2;
             let segment_n = 0;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 3058, 3853, 3996, 3939, 1160, 3958, 2783, 2771, 3906, 3745, 2022, 3383, 2446, 4078,
@@ -2214,7 +2659,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -2225,7 +2677,7 @@ mod private {
             let pass_n = 2;
             let segment_n = 1;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 245, 305, 3307, 2804, 3940, 209, 4024, 3645, 611, 3393, 168, 3928, 855, 917, 956,
@@ -2307,7 +2759,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -2318,7 +2777,7 @@ mod private {
             let pass_n = 2;
             let segment_n = 2;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 2035, 1527, 238, 1387, 1882, 1932, 772, 2047, 1735, 918, 619, 450, 1107, 783, 1692,
@@ -2401,7 +2860,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
@@ -2412,7 +2878,7 @@ mod private {
             let pass_n = 2;
             let segment_n = 3;
 
-            gidx.init(pass_n, segment_n, offset, &mut tmp_block);
+            gidx.init(pass_n, 0, segment_n, offset, &mut tmp_block);
 
             let expected_ref_idx: [u32; 1024] = [
                 1775, 3037, 2997, 1590, 386, 2714, 2140, 2437, 2432, 3079, 3079, 3044, 2964, 1089,
@@ -2495,7 +2961,14 @@ mod private {
                 // Mimic offset..segment_length runs with idx
                 assert_eq!(
                     *expected,
-                    gidx.get_next(idx as u32, &mut tmp_block),
+                    reference_index(
+                        pass_n,
+                        segment_n,
+                        idx as u32,
+                        gidx.get_next(&mut tmp_block) & 0xFFFF_FFFFu64,
+                        true,
+                        segment_length
+                    ),
                     "Invalid at {}",
                     idx
                 );
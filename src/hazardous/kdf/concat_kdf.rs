@@ -0,0 +1,189 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `shared_secret`: The shared secret `Z`, e.g. the output of an ECDH key agreement.
+//! - `fixed_info`: Context and application-specific information, as defined
+//!   by the protocol using the KDF. Called `OtherInfo` in older revisions of
+//!   NIST SP 800-56A and in RFC 7518.
+//! - `dst_out`: Destination buffer for the derived key. The length of the
+//!   derived key is implied by the length of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than 1.
+//! - The length of `dst_out` is greater than (2^32 - 1) * SHA(256/512)_OUTSIZE.
+//!
+//! # Security:
+//! - Concat KDF is a one-step KDF without a salt. The `shared_secret` must
+//!   already contain enough entropy, such as the output of an ECDH key agreement.
+//! - `fixed_info` should bind the derived key to its intended use, e.g. the
+//!   algorithm identifier and the identities of both parties.
+//! - Concat KDF is not suitable for password storage.
+//! - Unless a protocol mandates Concat KDF, prefer [`hkdf`](super::hkdf).
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::kdf::concat_kdf;
+//!
+//! // The shared secret from an ECDH key agreement.
+//! let shared_secret = [0x9e; 32];
+//! // JOSE ECDH-ES: AlgorithmID || PartyUInfo || PartyVInfo || SuppPubInfo
+//! let fixed_info = b"\x00\x00\x00\x07A128GCM\x00\x00\x00\x05Alice\x00\x00\x00\x03Bob\x00\x00\x00\x80";
+//! let mut cek = [0u8; 16];
+//!
+//! concat_kdf::sha256::derive_key(&shared_secret, fixed_info, &mut cek)?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::mac::hmac::HmacHashFunction;
+use zeroize::Zeroize;
+
+/// The one-step key derivation function with a hash function as the
+/// auxiliary function, as specified in NIST SP 800-56C Rev. 2, Section 4.1, Option 1.
+fn _derive_key<Hash, const OUTSIZE: usize>(
+    shared_secret: &[u8],
+    fixed_info: &[u8],
+    dest: &mut [u8],
+) -> Result<(), UnknownCryptoError>
+where
+    Hash: HmacHashFunction,
+{
+    debug_assert!(OUTSIZE == Hash::_OUTSIZE);
+    if dest.is_empty() || (dest.chunks(OUTSIZE).len() as u64) > u64::from(u32::MAX) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut tmp = [0u8; OUTSIZE];
+    let mut counter: u32 = 1;
+    for hlen_block in dest.chunks_mut(OUTSIZE) {
+        let mut ctx = Hash::_new();
+        ctx._update(&counter.to_be_bytes())?;
+        ctx._update(shared_secret)?;
+        ctx._update(fixed_info)?;
+        ctx._finalize(&mut tmp)?;
+        hlen_block.copy_from_slice(&tmp[..hlen_block.len()]);
+        // The length check above ensures this doesn't overflow.
+        counter = counter.wrapping_add(1);
+    }
+
+    tmp.zeroize();
+
+    Ok(())
+}
+
+/// Concat KDF using SHA256.
+pub mod sha256 {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha256::{Sha256, SHA256_OUTSIZE};
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a key from `shared_secret` and `fixed_info`.
+    pub fn derive_key(
+        shared_secret: &[u8],
+        fixed_info: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        _derive_key::<Sha256, { SHA256_OUTSIZE }>(shared_secret, fixed_info, dst_out)
+    }
+}
+
+/// Concat KDF using SHA512.
+pub mod sha512 {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha512::{Sha512, SHA512_OUTSIZE};
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a key from `shared_secret` and `fixed_info`.
+    pub fn derive_key(
+        shared_secret: &[u8],
+        fixed_info: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        _derive_key::<Sha512, { SHA512_OUTSIZE }>(shared_secret, fixed_info, dst_out)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::sha2::{sha256::SHA256_OUTSIZE, sha512::SHA512_OUTSIZE};
+
+    #[test]
+    fn concat_kdf_zero_length_err() {
+        let mut okm_out = [0u8; 0];
+        assert!(sha256::derive_key(b"", b"", &mut okm_out).is_err());
+        assert!(sha512::derive_key(b"", b"", &mut okm_out).is_err());
+    }
+
+    #[test]
+    fn concat_kdf_multiple_blocks_ok() {
+        let mut okm_out = [0u8; 3 * SHA256_OUTSIZE + 1];
+        assert!(sha256::derive_key(b"", b"", &mut okm_out).is_ok());
+
+        let mut okm_out = [0u8; 3 * SHA512_OUTSIZE + 1];
+        assert!(sha512::derive_key(b"", b"", &mut okm_out).is_ok());
+    }
+
+    #[test]
+    fn concat_kdf_truncated_is_prefix() {
+        let mut okm_out = [0u8; 2 * SHA256_OUTSIZE];
+        let mut okm_out_short = [0u8; SHA256_OUTSIZE + 5];
+        sha256::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha256::derive_key(b"Z", b"info", &mut okm_out_short).unwrap();
+        assert_eq!(okm_out[..okm_out_short.len()], okm_out_short[..]);
+
+        let mut okm_out = [0u8; 2 * SHA512_OUTSIZE];
+        let mut okm_out_short = [0u8; SHA512_OUTSIZE + 5];
+        sha512::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha512::derive_key(b"Z", b"info", &mut okm_out_short).unwrap();
+        assert_eq!(okm_out[..okm_out_short.len()], okm_out_short[..]);
+    }
+
+    #[test]
+    fn concat_kdf_diff_fixed_info() {
+        let mut okm_out = [0u8; 32];
+        let mut okm_out_verify = [0u8; 32];
+        sha256::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha256::derive_key(b"Z", b"infp", &mut okm_out_verify).unwrap();
+        assert_ne!(okm_out, okm_out_verify);
+
+        sha512::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha512::derive_key(b"Z", b"infp", &mut okm_out_verify).unwrap();
+        assert_ne!(okm_out, okm_out_verify);
+    }
+
+    #[test]
+    fn concat_kdf_diff_shared_secret() {
+        let mut okm_out = [0u8; 32];
+        let mut okm_out_verify = [0u8; 32];
+        sha256::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha256::derive_key(b"Y", b"info", &mut okm_out_verify).unwrap();
+        assert_ne!(okm_out, okm_out_verify);
+
+        sha512::derive_key(b"Z", b"info", &mut okm_out).unwrap();
+        sha512::derive_key(b"Y", b"info", &mut okm_out_verify).unwrap();
+        assert_ne!(okm_out, okm_out_verify);
+    }
+}
@@ -50,13 +50,21 @@
 //!
 //! hkdf::sha512::derive_key(&salt, "IKM".as_bytes(), None, &mut okm_out)?;
 //!
+//! // When deriving several keys from the same input keying material, a `Prk`
+//! // can be reused for each expand step.
+//! let prk = hkdf::sha512::Prk::extract(&salt, "IKM".as_bytes())?;
+//! let mut enc_key = [0u8; 32];
+//! let mut mac_key = [0u8; 32];
+//! prk.expand(Some(b"encryption"), &mut enc_key)?;
+//! prk.expand(Some(b"authentication"), &mut mac_key)?;
+//!
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`secure_rand_bytes()`]: crate::util::secure_rand_bytes
 
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
 use crate::hazardous::mac::hmac;
-use zeroize::Zeroize;
+use zeroize::Zeroizing;
 
 /// The HKDF extract step.
 ///
@@ -89,24 +97,35 @@ fn _expand<Hmac, const OUTSIZE: usize>(
 where
     Hmac: hmac::HmacFunction,
 {
-    debug_assert!(OUTSIZE == Hmac::HASH_FUNC_OUTSIZE);
     debug_assert!(prk.len() == Hmac::HASH_FUNC_OUTSIZE);
+    _expand_with_ctx::<Hmac, { OUTSIZE }>(Hmac::_new(prk)?, info, dest)
+}
+
+/// The HKDF expand step, using a HMAC state that has already been keyed with the PRK.
+fn _expand_with_ctx<Hmac, const OUTSIZE: usize>(
+    mut ctx: Hmac,
+    info: Option<&[u8]>,
+    dest: &mut [u8],
+) -> Result<(), UnknownCryptoError>
+where
+    Hmac: hmac::HmacFunction,
+{
+    debug_assert!(OUTSIZE == Hmac::HASH_FUNC_OUTSIZE);
     if dest.is_empty() || dest.len() > 255 * Hmac::HASH_FUNC_OUTSIZE {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
     let optional_info = info.unwrap_or(&[0u8; 0]);
-    let mut ctx = Hmac::_new(prk)?;
 
     // We require a temporary buffer in case the requested bytes
     // to derive are lower than the HMAC functions output size.
-    let mut tmp = [0u8; OUTSIZE];
+    let mut tmp = Zeroizing::new([0u8; OUTSIZE]);
     let mut idx: u8 = 1;
     for hlen_block in dest.chunks_mut(Hmac::HASH_FUNC_OUTSIZE) {
         ctx._update(optional_info)?;
         ctx._update(&[idx])?;
         debug_assert!(!hlen_block.is_empty() && hlen_block.len() <= Hmac::HASH_FUNC_OUTSIZE);
-        ctx._finalize(&mut tmp)?;
+        ctx._finalize(tmp.as_mut())?;
         hlen_block.copy_from_slice(&tmp[..hlen_block.len()]);
 
         if hlen_block.len() < Hmac::HASH_FUNC_OUTSIZE {
@@ -124,8 +143,6 @@ where
         };
     }
 
-    tmp.iter_mut().zeroize();
-
     Ok(())
 }
 
@@ -141,7 +158,8 @@ fn _derive_key<Hmac, const OUTSIZE: usize>(
 where
     Hmac: hmac::HmacFunction,
 {
-    _expand::<Hmac, { OUTSIZE }>(&_extract::<Hmac, { OUTSIZE }>(salt, ikm)?, info, dest)
+    let prk = Zeroizing::new(_extract::<Hmac, { OUTSIZE }>(salt, ikm)?);
+    _expand::<Hmac, { OUTSIZE }>(prk.as_ref(), info, dest)
 }
 
 /// HKDF-HMAC-SHA256 (HMAC-based Extract-and-Expand Key Derivation Function) as specified in the [RFC 5869](https://tools.ietf.org/html/rfc5869).
@@ -184,6 +202,93 @@ pub mod sha256 {
         _derive_key::<hmac::sha256::HmacSha256, { SHA256_OUTSIZE }>(salt, ikm, info, dst_out)
     }
 
+    #[derive(Clone, Debug)]
+    /// A pseudorandom key, produced by the HKDF extract step, that can be used
+    /// for any number of expand steps.
+    ///
+    /// The HMAC key schedule for the PRK is computed once, when the `Prk` is created,
+    /// so each call to [`Prk::expand()`] only pays for the expand step itself.
+    pub struct Prk {
+        ctx: hmac::sha256::HmacSha256,
+    }
+
+    impl Prk {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF extract step, returning a reusable `Prk`.
+        pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self::from_tag(&extract(salt, ikm)?))
+        }
+
+        /// Create a `Prk` from the output of a previous [`extract()`].
+        #[allow(clippy::unwrap_used)]
+        pub fn from_tag(prk: &Tag) -> Self {
+            // NOTE: A PRK is always the size of the hash function output,
+            // which is less than its blocksize, so .unwrap() is OK.
+            Self {
+                ctx: hmac::HmacFunction::_new(prk.unprotected_as_bytes()).unwrap(),
+            }
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF expand step.
+        pub fn expand(
+            &self,
+            info: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            _expand_with_ctx::<hmac::sha256::HmacSha256, { SHA256_OUTSIZE }>(
+                self.ctx.clone(),
+                info,
+                dst_out,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod test_prk {
+        use super::*;
+
+        #[test]
+        fn test_prk_expand_same_as_expand() {
+            let prk = extract(b"salt", b"ikm").unwrap();
+            let reusable = Prk::from_tag(&prk);
+
+            for info in [&b""[..], &b"first"[..], &b"second"[..]].iter() {
+                let mut out = [0u8; 2 * SHA256_OUTSIZE + 3];
+                let mut out_prk = [0u8; 2 * SHA256_OUTSIZE + 3];
+                expand(&prk, Some(info), &mut out).unwrap();
+                reusable.expand(Some(info), &mut out_prk).unwrap();
+                assert_eq!(out[..], out_prk[..]);
+            }
+        }
+
+        #[test]
+        fn test_prk_extract_same_as_from_tag() {
+            let mut out = [0u8; SHA256_OUTSIZE];
+            let mut out_extract = [0u8; SHA256_OUTSIZE];
+            Prk::from_tag(&extract(b"salt", b"ikm").unwrap())
+                .expand(None, &mut out)
+                .unwrap();
+            Prk::extract(b"salt", b"ikm")
+                .unwrap()
+                .expand(None, &mut out_extract)
+                .unwrap();
+            assert_eq!(out, out_extract);
+        }
+
+        #[test]
+        fn test_prk_expand_length_err() {
+            let reusable = Prk::extract(b"", b"").unwrap();
+            assert!(reusable.expand(None, &mut [0u8; 0]).is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA256_OUTSIZE + 1])
+                .is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA256_OUTSIZE])
+                .is_ok());
+        }
+    }
+
     #[cfg(test)]
     #[cfg(feature = "safe_api")]
     // Mark safe_api because currently it only contains proptests.
@@ -260,6 +365,93 @@ pub mod sha384 {
         _derive_key::<hmac::sha384::HmacSha384, { SHA384_OUTSIZE }>(salt, ikm, info, dst_out)
     }
 
+    #[derive(Clone, Debug)]
+    /// A pseudorandom key, produced by the HKDF extract step, that can be used
+    /// for any number of expand steps.
+    ///
+    /// The HMAC key schedule for the PRK is computed once, when the `Prk` is created,
+    /// so each call to [`Prk::expand()`] only pays for the expand step itself.
+    pub struct Prk {
+        ctx: hmac::sha384::HmacSha384,
+    }
+
+    impl Prk {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF extract step, returning a reusable `Prk`.
+        pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self::from_tag(&extract(salt, ikm)?))
+        }
+
+        /// Create a `Prk` from the output of a previous [`extract()`].
+        #[allow(clippy::unwrap_used)]
+        pub fn from_tag(prk: &Tag) -> Self {
+            // NOTE: A PRK is always the size of the hash function output,
+            // which is less than its blocksize, so .unwrap() is OK.
+            Self {
+                ctx: hmac::HmacFunction::_new(prk.unprotected_as_bytes()).unwrap(),
+            }
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF expand step.
+        pub fn expand(
+            &self,
+            info: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            _expand_with_ctx::<hmac::sha384::HmacSha384, { SHA384_OUTSIZE }>(
+                self.ctx.clone(),
+                info,
+                dst_out,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod test_prk {
+        use super::*;
+
+        #[test]
+        fn test_prk_expand_same_as_expand() {
+            let prk = extract(b"salt", b"ikm").unwrap();
+            let reusable = Prk::from_tag(&prk);
+
+            for info in [&b""[..], &b"first"[..], &b"second"[..]].iter() {
+                let mut out = [0u8; 2 * SHA384_OUTSIZE + 3];
+                let mut out_prk = [0u8; 2 * SHA384_OUTSIZE + 3];
+                expand(&prk, Some(info), &mut out).unwrap();
+                reusable.expand(Some(info), &mut out_prk).unwrap();
+                assert_eq!(out[..], out_prk[..]);
+            }
+        }
+
+        #[test]
+        fn test_prk_extract_same_as_from_tag() {
+            let mut out = [0u8; SHA384_OUTSIZE];
+            let mut out_extract = [0u8; SHA384_OUTSIZE];
+            Prk::from_tag(&extract(b"salt", b"ikm").unwrap())
+                .expand(None, &mut out)
+                .unwrap();
+            Prk::extract(b"salt", b"ikm")
+                .unwrap()
+                .expand(None, &mut out_extract)
+                .unwrap();
+            assert_eq!(out, out_extract);
+        }
+
+        #[test]
+        fn test_prk_expand_length_err() {
+            let reusable = Prk::extract(b"", b"").unwrap();
+            assert!(reusable.expand(None, &mut [0u8; 0]).is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA384_OUTSIZE + 1])
+                .is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA384_OUTSIZE])
+                .is_ok());
+        }
+    }
+
     #[cfg(test)]
     #[cfg(feature = "safe_api")]
     // Mark safe_api because currently it only contains proptests.
@@ -336,6 +528,93 @@ pub mod sha512 {
         _derive_key::<hmac::sha512::HmacSha512, { SHA512_OUTSIZE }>(salt, ikm, info, dst_out)
     }
 
+    #[derive(Clone, Debug)]
+    /// A pseudorandom key, produced by the HKDF extract step, that can be used
+    /// for any number of expand steps.
+    ///
+    /// The HMAC key schedule for the PRK is computed once, when the `Prk` is created,
+    /// so each call to [`Prk::expand()`] only pays for the expand step itself.
+    pub struct Prk {
+        ctx: hmac::sha512::HmacSha512,
+    }
+
+    impl Prk {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF extract step, returning a reusable `Prk`.
+        pub fn extract(salt: &[u8], ikm: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self::from_tag(&extract(salt, ikm)?))
+        }
+
+        /// Create a `Prk` from the output of a previous [`extract()`].
+        #[allow(clippy::unwrap_used)]
+        pub fn from_tag(prk: &Tag) -> Self {
+            // NOTE: A PRK is always the size of the hash function output,
+            // which is less than its blocksize, so .unwrap() is OK.
+            Self {
+                ctx: hmac::HmacFunction::_new(prk.unprotected_as_bytes()).unwrap(),
+            }
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// The HKDF expand step.
+        pub fn expand(
+            &self,
+            info: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            _expand_with_ctx::<hmac::sha512::HmacSha512, { SHA512_OUTSIZE }>(
+                self.ctx.clone(),
+                info,
+                dst_out,
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod test_prk {
+        use super::*;
+
+        #[test]
+        fn test_prk_expand_same_as_expand() {
+            let prk = extract(b"salt", b"ikm").unwrap();
+            let reusable = Prk::from_tag(&prk);
+
+            for info in [&b""[..], &b"first"[..], &b"second"[..]].iter() {
+                let mut out = [0u8; 2 * SHA512_OUTSIZE + 3];
+                let mut out_prk = [0u8; 2 * SHA512_OUTSIZE + 3];
+                expand(&prk, Some(info), &mut out).unwrap();
+                reusable.expand(Some(info), &mut out_prk).unwrap();
+                assert_eq!(out[..], out_prk[..]);
+            }
+        }
+
+        #[test]
+        fn test_prk_extract_same_as_from_tag() {
+            let mut out = [0u8; SHA512_OUTSIZE];
+            let mut out_extract = [0u8; SHA512_OUTSIZE];
+            Prk::from_tag(&extract(b"salt", b"ikm").unwrap())
+                .expand(None, &mut out)
+                .unwrap();
+            Prk::extract(b"salt", b"ikm")
+                .unwrap()
+                .expand(None, &mut out_extract)
+                .unwrap();
+            assert_eq!(out, out_extract);
+        }
+
+        #[test]
+        fn test_prk_expand_length_err() {
+            let reusable = Prk::extract(b"", b"").unwrap();
+            assert!(reusable.expand(None, &mut [0u8; 0]).is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA512_OUTSIZE + 1])
+                .is_err());
+            assert!(reusable
+                .expand(None, &mut [0u8; 255 * SHA512_OUTSIZE])
+                .is_ok());
+        }
+    }
+
     #[cfg(test)]
     #[cfg(feature = "safe_api")]
     // Mark safe_api because currently it only contains proptests.
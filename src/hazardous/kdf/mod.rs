@@ -26,6 +26,12 @@ pub mod hkdf;
 /// PBKDF2(Password-Based Key Derivation Function 2) as specified in the [RFC 8018](https://tools.ietf.org/html/rfc8018).
 pub mod pbkdf2;
 
+/// Concat KDF (the one-step key derivation function) as specified in [NIST SP 800-56C](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-56Cr2.pdf).
+pub mod concat_kdf;
+
+/// bcrypt_pbkdf, as used by OpenSSH for encrypted private keys.
+pub mod bcrypt_pbkdf;
+
 #[cfg(any(feature = "safe_api", feature = "alloc"))]
 /// Argon2i password hashing function as described in the [P-H-C specification](https://github.com/P-H-C/phc-winner-argon2/blob/master/argon2-specs.pdf).
 pub mod argon2i;
@@ -33,9 +33,6 @@
 //! - The length of `dst_out` is less than 1.
 //! - The specified iteration count is less than 1.
 //! - The hashed password does not match the expected when verifying.
-//!
-//! # Panics:
-//! A panic will occur if:
 //! - The length of `dst_out` is greater than (2^32 - 1) * SHA(256/384/512)_OUTSIZE.
 //!
 //! # Security:
@@ -71,7 +68,12 @@
 //! [`secure_rand_bytes()`]: crate::util::secure_rand_bytes
 //! [`zeroize` crate]: https://crates.io/crates/zeroize
 
-use crate::{errors::UnknownCryptoError, hazardous::mac::hmac};
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::mac::hmac,
+};
+use core::convert::TryFrom;
+use zeroize::Zeroizing;
 
 /// The F function as described in the RFC.
 fn _function_f<Hmac>(
@@ -120,15 +122,24 @@ where
     Hmac: hmac::HmacFunction,
 {
     debug_assert!(OUTSIZE == Hmac::HASH_FUNC_OUTSIZE);
-    if dest.is_empty() || iterations < 1 {
-        return Err(UnknownCryptoError);
+    if dest.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if iterations < 1 {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
     }
 
-    let mut u_step = [0u8; OUTSIZE];
+    let mut u_step = Zeroizing::new([0u8; OUTSIZE]);
+    // The ipad/opad hash states are computed once here. Each iteration and block
+    // only restores them through `_reset()`, which clones the precomputed states
+    // instead of re-processing the password.
     let mut hmac = Hmac::_new(padded_password)?;
     for (idx, dk_block) in dest.chunks_mut(Hmac::HASH_FUNC_OUTSIZE).enumerate() {
-        // If this panics, then the size limit for PBKDF2 is reached.
-        let block_idx: u32 = 1u32.checked_add(idx as u32).unwrap();
+        // If this fails, then the size limit for PBKDF2 is reached.
+        let block_idx = match u32::try_from(idx).ok().and_then(|idx| idx.checked_add(1)) {
+            Some(block_idx) => block_idx,
+            None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+        };
 
         _function_f(
             salt,
@@ -136,7 +147,7 @@ where
             block_idx,
             dk_block,
             dk_block.len(),
-            &mut u_step,
+            u_step.as_mut(),
             &mut hmac,
         )?;
 
@@ -0,0 +1,467 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! A hybrid KEM that combines X25519 and ML-KEM-768 into a single shared secret,
+//! following the construction of [X-Wing]. The shared secret stays secure as long as
+//! either of the two is unbroken, which makes this the recommended way to start
+//! using post-quantum key exchange.
+//!
+//! The [`DecapsulationKey`] is a 32-byte seed. It is expanded with SHAKE256 into an
+//! ML-KEM-768 key pair and an X25519 secret key. The [`EncapsulationKey`] is the
+//! concatenation of both public keys and the [`Ciphertext`] the concatenation of the
+//! ML-KEM-768 ciphertext and an ephemeral X25519 public key.
+//!
+//! The shared secret is computed as:
+//! `SHA3-256(ss_M || ss_X || ct_X || pk_X || XWingLabel)`.
+//!
+//! # Parameters:
+//! - `ek`: The encapsulation key of the recipient.
+//! - `dk`: The decapsulation key of the recipient.
+//! - `ciphertext`: The ciphertext produced by [`encapsulate()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of a key or ciphertext passed to `from_slice()` is incorrect.
+//! - The ML-KEM-768 part of an [`EncapsulationKey`] is not valid.
+//! - Generating random bytes fails, when using [`encapsulate()`].
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`KeyPair::generate()`]
+//!   or [`DecapsulationKey::generate()`].
+//!
+//! # Security:
+//! - Like [`mlkem768`](super::mlkem768), [`decapsulate()`] uses implicit rejection and returns
+//!   a pseudorandom shared secret, instead of an error, for an invalid ciphertext.
+//! - The X25519 output is not checked for low-order points. This is not needed for the
+//!   security of the combined shared secret, which also binds the X25519 ciphertext and
+//!   public key.
+//! - The KEM does not authenticate either party, so the encapsulation key must be
+//!   obtained from a trusted source.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::kem::hybrid::{self, KeyPair};
+//!
+//! let recipient = KeyPair::generate();
+//!
+//! let (sender_secret, ciphertext) = hybrid::encapsulate(recipient.public())?;
+//! let recipient_secret = hybrid::decapsulate(recipient.private(), &ciphertext)?;
+//! assert_eq!(sender_secret, recipient_secret);
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [X-Wing]: https://datatracker.ietf.org/doc/draft-connolly-cfrg-xwing-kem/
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::ecc::x25519::{scalarmult, scalarmult_base, X25519_SIZE};
+use crate::hazardous::hash::sha3::{shake256, Sha3_256};
+use crate::hazardous::kem::mlkem768::{
+    self, MLKEM768_CIPHERTEXTSIZE, MLKEM768_ENCAPSULATION_KEYSIZE, MLKEM768_SEEDSIZE,
+};
+use crate::util::Fingerprint;
+use zeroize::Zeroize;
+
+/// The size of a hybrid encapsulation key.
+pub const HYBRID_ENCAPSULATION_KEYSIZE: usize = MLKEM768_ENCAPSULATION_KEYSIZE + X25519_SIZE;
+
+/// The size of a hybrid decapsulation key.
+pub const HYBRID_DECAPSULATION_KEYSIZE: usize = 32;
+
+/// The size of a hybrid ciphertext.
+pub const HYBRID_CIPHERTEXTSIZE: usize = MLKEM768_CIPHERTEXTSIZE + X25519_SIZE;
+
+/// The size of a shared secret produced by the hybrid KEM.
+pub const HYBRID_SHAREDSECRETSIZE: usize = 32;
+
+/// The domain separation label of X-Wing.
+const XWING_LABEL: &[u8; 6] = b"\\.//^\\";
+
+/// Compute the shared secret from both shared secrets, the X25519 ciphertext
+/// and X25519 public key.
+fn combiner(ss_m: &[u8], ss_x: &[u8], ct_x: &[u8], pk_x: &[u8]) -> SharedSecret {
+    let mut ctx = Sha3_256::new();
+    ctx.absorb(ss_m);
+    ctx.absorb(ss_x);
+    ctx.absorb(ct_x);
+    ctx.absorb(pk_x);
+    ctx.absorb(XWING_LABEL);
+
+    let mut value = [0u8; HYBRID_SHAREDSECRETSIZE];
+    ctx.squeeze(&mut value);
+
+    SharedSecret { value }
+}
+
+/// Expand the decapsulation key into an ML-KEM-768 key pair and an X25519 secret key.
+fn expand(dk: &DecapsulationKey) -> (mlkem768::KeyPair, [u8; X25519_SIZE]) {
+    let mut expanded = [0u8; MLKEM768_SEEDSIZE + X25519_SIZE];
+    shake256(&[dk.unprotected_as_bytes()], &mut expanded);
+
+    let mut seed = [0u8; MLKEM768_SEEDSIZE];
+    seed.copy_from_slice(&expanded[..MLKEM768_SEEDSIZE]);
+    let keypair = mlkem768::KeyPair::from_seed(&mlkem768::Seed::from(seed));
+    let mut sk_x = [0u8; X25519_SIZE];
+    sk_x.copy_from_slice(&expanded[MLKEM768_SEEDSIZE..]);
+
+    seed.zeroize();
+    expanded.zeroize();
+
+    (keypair, sk_x)
+}
+
+#[cfg(any(feature = "safe_api", test))]
+/// Encapsulate deterministically, using `eseed` as the randomness for ML-KEM-768
+/// (the first 32 bytes) and as the ephemeral X25519 secret key (the last 32 bytes).
+pub(crate) fn encapsulate_internal(
+    ek: &EncapsulationKey,
+    eseed: &[u8; 64],
+) -> Result<(SharedSecret, Ciphertext), UnknownCryptoError> {
+    let (ek_m, pk_x) = ek.value.split_at(MLKEM768_ENCAPSULATION_KEYSIZE);
+    let ek_m = mlkem768::EncapsulationKey::from_slice(ek_m)?;
+    let mut pk_x_bytes = [0u8; X25519_SIZE];
+    pk_x_bytes.copy_from_slice(pk_x);
+
+    let mut m = [0u8; 32];
+    m.copy_from_slice(&eseed[..32]);
+    let mut ek_x = [0u8; X25519_SIZE];
+    ek_x.copy_from_slice(&eseed[32..]);
+
+    let ct_x = scalarmult_base(&ek_x);
+    let mut ss_x = scalarmult(&ek_x, &pk_x_bytes);
+    let (ss_m, ct_m) = mlkem768::encapsulate_internal(&ek_m, &m);
+    let shared_secret = combiner(ss_m.unprotected_as_bytes(), &ss_x, &ct_x, &pk_x_bytes);
+
+    let mut ciphertext = [0u8; HYBRID_CIPHERTEXTSIZE];
+    ciphertext[..MLKEM768_CIPHERTEXTSIZE].copy_from_slice(ct_m.as_ref());
+    ciphertext[MLKEM768_CIPHERTEXTSIZE..].copy_from_slice(&ct_x);
+
+    m.zeroize();
+    ek_x.zeroize();
+    ss_x.zeroize();
+
+    Ok((shared_secret, Ciphertext::from(ciphertext)))
+}
+
+construct_secret_key! {
+    /// A type to represent the `DecapsulationKey` of the hybrid KEM, which is a 32-byte seed.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (DecapsulationKey, test_decapsulation_key, HYBRID_DECAPSULATION_KEYSIZE, HYBRID_DECAPSULATION_KEYSIZE, HYBRID_DECAPSULATION_KEYSIZE)
+}
+
+impl_from_trait!(DecapsulationKey, HYBRID_DECAPSULATION_KEYSIZE);
+
+impl DecapsulationKey {
+    /// Return the [`EncapsulationKey`] that belongs to this decapsulation key.
+    pub fn public_key(&self) -> EncapsulationKey {
+        let (keypair, mut sk_x) = expand(self);
+        let mut value = [0u8; HYBRID_ENCAPSULATION_KEYSIZE];
+        value[..MLKEM768_ENCAPSULATION_KEYSIZE].copy_from_slice(keypair.public().as_ref());
+        value[MLKEM768_ENCAPSULATION_KEYSIZE..].copy_from_slice(&scalarmult_base(&sk_x));
+        sk_x.zeroize();
+
+        EncapsulationKey {
+            value,
+            original_length: HYBRID_ENCAPSULATION_KEYSIZE,
+        }
+    }
+}
+
+#[derive(Clone)]
+/// A type to represent the `EncapsulationKey` of the hybrid KEM, which is public.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `slice` is not 1216 bytes.
+/// - The ML-KEM-768 encapsulation key, which are the first 1184 bytes of `slice`, is not valid.
+///
+/// `PartialEq` and [`ConstantTimeEq`](crate::util::ConstantTimeEq) are
+/// implemented for this type in constant time.
+pub struct EncapsulationKey {
+    value: [u8; HYBRID_ENCAPSULATION_KEYSIZE],
+    original_length: usize,
+}
+
+impl_ct_partialeq_trait!(EncapsulationKey, as_ref);
+impl_normal_debug_trait!(EncapsulationKey);
+impl_asref_trait!(EncapsulationKey);
+impl_serde_traits!(EncapsulationKey, as_ref);
+impl_try_from_traits!(EncapsulationKey);
+
+impl EncapsulationKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Construct from a given byte slice.
+    pub fn from_slice(slice: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if slice.len() != HYBRID_ENCAPSULATION_KEYSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        mlkem768::EncapsulationKey::from_slice(&slice[..MLKEM768_ENCAPSULATION_KEYSIZE])?;
+
+        let mut value = [0u8; HYBRID_ENCAPSULATION_KEYSIZE];
+        value.copy_from_slice(slice);
+
+        Ok(Self {
+            value,
+            original_length: HYBRID_ENCAPSULATION_KEYSIZE,
+        })
+    }
+
+    func_len!();
+    func_is_empty!();
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the [`Fingerprint`] of this encapsulation key, for display and pinning.
+    pub fn fingerprint(&self) -> Result<Fingerprint, UnknownCryptoError> {
+        Fingerprint::of(self.as_ref())
+    }
+}
+
+construct_public! {
+    /// A type to represent the `Ciphertext` that hybrid encapsulation produces.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 1120 bytes.
+    (Ciphertext, test_ciphertext, HYBRID_CIPHERTEXTSIZE, HYBRID_CIPHERTEXTSIZE)
+}
+
+impl_from_trait!(Ciphertext, HYBRID_CIPHERTEXTSIZE);
+
+/// A type to represent the `SharedSecret` that the hybrid KEM produces.
+///
+/// # Security:
+/// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
+///   that the type implements.
+///
+/// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
+///   to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
+///   is implemented in such a way that the comparison happens in constant time.
+pub struct SharedSecret {
+    value: [u8; HYBRID_SHAREDSECRETSIZE],
+}
+
+impl_omitted_debug_trait!(SharedSecret);
+impl_drop_trait!(SharedSecret);
+impl_ct_partialeq_trait!(SharedSecret, unprotected_as_bytes);
+
+impl SharedSecret {
+    #[inline]
+    /// Return the object as byte slice. __**Warning**__: Should not be used unless strictly
+    /// needed. This __**breaks protections**__ that the type implements.
+    pub fn unprotected_as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// A key pair of the hybrid KEM, containing an [`EncapsulationKey`] and a [`DecapsulationKey`].
+pub struct KeyPair {
+    public: EncapsulationKey,
+    private: DecapsulationKey,
+}
+
+impl KeyPair {
+    #[cfg(feature = "safe_api")]
+    /// Randomly generate a key pair using a CSPRNG. Not available in `no_std` context.
+    pub fn generate() -> Self {
+        Self::from(DecapsulationKey::generate())
+    }
+
+    /// Return the public [`EncapsulationKey`].
+    pub fn public(&self) -> &EncapsulationKey {
+        &self.public
+    }
+
+    /// Return the secret [`DecapsulationKey`].
+    pub fn private(&self) -> &DecapsulationKey {
+        &self.private
+    }
+}
+
+impl From<DecapsulationKey> for KeyPair {
+    fn from(dk: DecapsulationKey) -> Self {
+        Self {
+            public: dk.public_key(),
+            private: dk,
+        }
+    }
+}
+
+#[cfg(feature = "safe_api")]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Produce a [`SharedSecret`] and a [`Ciphertext`] that encapsulates it, for the holder
+/// of the decapsulation key that belongs to `ek`. Not available in `no_std` context.
+pub fn encapsulate(
+    ek: &EncapsulationKey,
+) -> Result<(SharedSecret, Ciphertext), UnknownCryptoError> {
+    let mut eseed = [0u8; 64];
+    crate::util::secure_rand_bytes(&mut eseed)?;
+    let ret = encapsulate_internal(ek, &eseed);
+    eseed.zeroize();
+
+    ret
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Recover the [`SharedSecret`] encapsulated in `ciphertext`, using `dk`.
+pub fn decapsulate(
+    dk: &DecapsulationKey,
+    ciphertext: &Ciphertext,
+) -> Result<SharedSecret, UnknownCryptoError> {
+    let (ct_m, ct_x) = ciphertext.as_ref().split_at(MLKEM768_CIPHERTEXTSIZE);
+    let mut ct_x_bytes = [0u8; X25519_SIZE];
+    ct_x_bytes.copy_from_slice(ct_x);
+
+    let (keypair, mut sk_x) = expand(dk);
+    let ss_m = mlkem768::decapsulate(keypair.private(), &mlkem768::Ciphertext::from_slice(ct_m)?)?;
+    let mut ss_x = scalarmult(&sk_x, &ct_x_bytes);
+    let pk_x = scalarmult_base(&sk_x);
+    let shared_secret = combiner(ss_m.unprotected_as_bytes(), &ss_x, &ct_x_bytes, &pk_x);
+
+    sk_x.zeroize();
+    ss_x.zeroize();
+
+    Ok(shared_secret)
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha256::Sha256;
+
+    fn test_eseed() -> [u8; 64] {
+        let mut eseed = [0u8; 64];
+        for (i, byte) in eseed.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        eseed
+    }
+
+    #[test]
+    fn test_encapsulate_deterministic() {
+        // The shared secret has been verified by decapsulating the ciphertext with
+        // the Python `cryptography` package.
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let (ss, ct) = encapsulate_internal(kp.public(), &test_eseed()).unwrap();
+        assert_eq!(
+            ss.unprotected_as_bytes(),
+            &hex::decode("1464aacd3c53aaef9b938d954771af61e91aa8578e229ad05beee90112b7a199")
+                .unwrap()[..]
+        );
+        assert_eq!(
+            Sha256::digest(ct.as_ref()).unwrap().as_ref(),
+            &hex::decode("55ba2857fbe4a9c19a8e0e618777737ceab4a429e4cf0f8ace0c17275954040b")
+                .unwrap()[..]
+        );
+        assert_eq!(decapsulate(kp.private(), &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_ciphertext_contains_ephemeral_x25519_key() {
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let eseed = test_eseed();
+        let (_, ct) = encapsulate_internal(kp.public(), &eseed).unwrap();
+
+        let mut ek_x = [0u8; X25519_SIZE];
+        ek_x.copy_from_slice(&eseed[32..]);
+        assert_eq!(
+            ct.as_ref()[MLKEM768_CIPHERTEXTSIZE..],
+            scalarmult_base(&ek_x)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let ek_mlkem = mlkem768::EncapsulationKey::from_slice(
+            &kp.public().as_ref()[..MLKEM768_ENCAPSULATION_KEYSIZE],
+        )
+        .unwrap();
+        // The fingerprint covers the whole hybrid key, not only the ML-KEM-768 part.
+        assert_ne!(
+            kp.public().fingerprint().unwrap(),
+            ek_mlkem.fingerprint().unwrap()
+        );
+        assert_eq!(
+            ek_mlkem.fingerprint().unwrap(),
+            Fingerprint::of(ek_mlkem.as_ref()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_wrong_decapsulation_key() {
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let other = DecapsulationKey::from([4u8; HYBRID_DECAPSULATION_KEYSIZE]);
+        let (ss, ct) = encapsulate_internal(kp.public(), &test_eseed()).unwrap();
+        assert_ne!(decapsulate(&other, &ct).unwrap(), ss);
+    }
+
+    #[test]
+    fn test_encapsulation_key_from_slice() {
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let ek = kp.public().as_ref();
+        assert_eq!(&EncapsulationKey::from_slice(ek).unwrap(), kp.public());
+        assert!(EncapsulationKey::from_slice(&ek[..HYBRID_ENCAPSULATION_KEYSIZE - 1]).is_err());
+        assert!(EncapsulationKey::from_slice(&[ek, &[0u8]].concat()).is_err());
+        assert!(EncapsulationKey::from_slice(&[]).is_err());
+
+        // The ML-KEM-768 encapsulation key fails the modulus check.
+        let mut bad = ek.to_vec();
+        bad[0] = 0xff;
+        bad[1] |= 0x0f;
+        let err = EncapsulationKey::from_slice(&bad).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impls() {
+        let kp = KeyPair::from(DecapsulationKey::from([3u8; HYBRID_DECAPSULATION_KEYSIZE]));
+        let (ss, _) = encapsulate_internal(kp.public(), &test_eseed()).unwrap();
+        assert_eq!(format!("{:?}", ss), "SharedSecret {***OMITTED***}");
+        assert_eq!(
+            format!("{:?}", kp.private()),
+            "DecapsulationKey {***OMITTED***}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_encapsulate_decapsulate() {
+        let kp = KeyPair::generate();
+        let (ss1, ct1) = encapsulate(kp.public()).unwrap();
+        let (ss2, ct2) = encapsulate(kp.public()).unwrap();
+        assert_ne!(ss1, ss2);
+        assert_ne!(ct1, ct2);
+        assert_eq!(decapsulate(kp.private(), &ct1).unwrap(), ss1);
+        assert_eq!(decapsulate(kp.private(), &ct2).unwrap(), ss2);
+    }
+}
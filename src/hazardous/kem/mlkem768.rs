@@ -0,0 +1,1116 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! ML-KEM-768, the module-lattice-based key-encapsulation mechanism as specified
+//! in the [FIPS 203]. ML-KEM-768 targets NIST security category 3 and is believed
+//! to be secure against attackers with a quantum computer.
+//!
+//! A [`KeyPair`] holds an [`EncapsulationKey`], which is public, and a
+//! [`DecapsulationKey`], which is secret. [`encapsulate()`] uses the encapsulation key to
+//! produce a [`SharedSecret`] and a [`Ciphertext`]. The ciphertext is sent to the holder
+//! of the decapsulation key, who runs [`decapsulate()`] to recover the same shared secret.
+//!
+//! # Parameters:
+//! - `seed`: 64 bytes from which a [`KeyPair`] is derived deterministically (`d || z` in the FIPS 203).
+//! - `ek`: The encapsulation key of the recipient.
+//! - `dk`: The decapsulation key of the recipient.
+//! - `ciphertext`: The ciphertext produced by [`encapsulate()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of a key or ciphertext passed to `from_slice()` is incorrect.
+//! - An [`EncapsulationKey`] contains coefficients that are not reduced modulo q
+//!   (the modulus check in the FIPS 203, Section 7.2).
+//! - A [`DecapsulationKey`] contains a hash of its encapsulation key that doesn't match
+//!   (the hash check in the FIPS 203, Section 7.3), or an invalid encapsulation key.
+//! - Generating random bytes fails, when using [`encapsulate()`].
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`KeyPair::generate()`].
+//!
+//! # Security:
+//! - [`decapsulate()`] uses implicit rejection: For a ciphertext that was not produced by
+//!   [`encapsulate()`] with the matching encapsulation key, it returns a pseudorandom
+//!   shared secret instead of an error. Decapsulating with the wrong key, or a modified
+//!   ciphertext, is therefore only detected once the shared secret is used, e.g. when an
+//!   AEAD fails to authenticate.
+//! - The [`SharedSecret`] is uniformly random and may be used as a key directly. To derive
+//!   several keys from it, use a KDF such as [`hkdf`](crate::hazardous::kdf::hkdf).
+//! - ML-KEM only protects against passive attackers by itself. It does not authenticate either
+//!   party, so the encapsulation key must be obtained from a trusted source.
+//! - Until the security of lattice-based schemes has received more analysis, consider
+//!   combining ML-KEM with a classical key exchange, like X25519.
+//! - The [`Seed`] that a [`KeyPair`] was derived from is as sensitive as the decapsulation key.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::kem::mlkem768::{self, KeyPair};
+//!
+//! // The recipient generates a key pair and publishes the encapsulation key.
+//! let recipient = KeyPair::generate();
+//!
+//! // The sender makes a shared secret and a ciphertext for the recipient.
+//! let (sender_secret, ciphertext) = mlkem768::encapsulate(recipient.public())?;
+//!
+//! // The recipient recovers the shared secret from the ciphertext.
+//! let recipient_secret = mlkem768::decapsulate(recipient.private(), &ciphertext)?;
+//! assert_eq!(sender_secret, recipient_secret);
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [FIPS 203]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::sha3::{sha3_256, sha3_512, shake256, Shake128, Shake256};
+use crate::util::Fingerprint;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+use zeroize::Zeroize;
+
+/// The size of an ML-KEM-768 encapsulation key.
+pub const MLKEM768_ENCAPSULATION_KEYSIZE: usize = 384 * K + 32;
+
+/// The size of an ML-KEM-768 decapsulation key.
+pub const MLKEM768_DECAPSULATION_KEYSIZE: usize = 768 * K + 96;
+
+/// The size of an ML-KEM-768 ciphertext.
+pub const MLKEM768_CIPHERTEXTSIZE: usize = 32 * (DU * K + DV);
+
+/// The size of a shared secret produced by ML-KEM-768.
+pub const MLKEM768_SHAREDSECRETSIZE: usize = 32;
+
+/// The size of a seed that an ML-KEM-768 key pair is derived from.
+pub const MLKEM768_SEEDSIZE: usize = 64;
+
+/// The modulus q.
+const Q: u32 = 3329;
+
+/// The number of coefficients in a polynomial.
+const N: usize = 256;
+
+/// The ML-KEM-768 parameters, from the FIPS 203, Section 8.
+const K: usize = 3;
+const ETA1: usize = 2;
+const ETA2: usize = 2;
+const DU: usize = 10;
+const DV: usize = 4;
+
+/// The size of an encoded vector of K polynomials with 12-bit coefficients.
+const POLYVEC_BYTES: usize = 384 * K;
+
+/// zeta^BitRev7(i) mod q, for i in 0..128.
+const ZETAS: [u16; 128] = [
+    1, 1729, 2580, 3289, 2642, 630, 1897, 848, 1062, 1919, 193, 797, 2786, 3260, 569, 1746, 296,
+    2447, 1339, 1476, 3046, 56, 2240, 1333, 1426, 2094, 535, 2882, 2393, 2879, 1974, 821, 289, 331,
+    3253, 1756, 1197, 2304, 2277, 2055, 650, 1977, 2513, 632, 2865, 33, 1320, 1915, 2319, 1435,
+    807, 452, 1438, 2868, 1534, 2402, 2647, 2617, 1481, 648, 2474, 3110, 1227, 910, 17, 2761, 583,
+    2649, 1637, 723, 2288, 1100, 1409, 2662, 3281, 233, 756, 2156, 3015, 3050, 1703, 1651, 2789,
+    1789, 1847, 952, 1461, 2687, 939, 2308, 2437, 2388, 733, 2337, 268, 641, 1584, 2298, 2037,
+    3220, 375, 2549, 2090, 1645, 1063, 319, 2773, 757, 2099, 561, 2466, 2594, 2804, 1092, 403,
+    1026, 1143, 2150, 2775, 886, 1722, 1212, 1874, 1029, 2110, 2935, 885, 2154,
+];
+
+/// zeta^(2 * BitRev7(i) + 1) mod q, for i in 0..128.
+const GAMMAS: [u16; 128] = [
+    17, 3312, 2761, 568, 583, 2746, 2649, 680, 1637, 1692, 723, 2606, 2288, 1041, 1100, 2229, 1409,
+    1920, 2662, 667, 3281, 48, 233, 3096, 756, 2573, 2156, 1173, 3015, 314, 3050, 279, 1703, 1626,
+    1651, 1678, 2789, 540, 1789, 1540, 1847, 1482, 952, 2377, 1461, 1868, 2687, 642, 939, 2390,
+    2308, 1021, 2437, 892, 2388, 941, 733, 2596, 2337, 992, 268, 3061, 641, 2688, 1584, 1745, 2298,
+    1031, 2037, 1292, 3220, 109, 375, 2954, 2549, 780, 2090, 1239, 1645, 1684, 1063, 2266, 319,
+    3010, 2773, 556, 757, 2572, 2099, 1230, 561, 2768, 2466, 863, 2594, 735, 2804, 525, 1092, 2237,
+    403, 2926, 1026, 2303, 1143, 2186, 2150, 1179, 2775, 554, 886, 2443, 1722, 1607, 1212, 2117,
+    1874, 1455, 1029, 2300, 2110, 1219, 2935, 394, 885, 2444, 2154, 1175,
+];
+
+/// 128^-1 mod q
+const N_INV: u16 = 3303;
+
+/// Subtract q from `x` if `x >= q`, in constant time. `x` must be less than 2q.
+fn csubq(x: u32) -> u32 {
+    debug_assert!(x < 2 * Q);
+    let r = x.wrapping_sub(Q);
+    r.wrapping_add(Q & 0u32.wrapping_sub(r >> 31))
+}
+
+/// Compute floor(`t` / q), in constant time. `t` must be less than 2^26.
+///
+/// This avoids the division instruction, which is not constant time on all platforms.
+fn div_q(t: u32) -> u32 {
+    debug_assert!(t < (1 << 26));
+    // floor(2^32 / q)
+    let mut quotient = (((t as u64) * 1290167) >> 32) as u32;
+    // The estimate is at most one less than the actual quotient.
+    let remainder = t - quotient * Q;
+    quotient += (Q - 1).wrapping_sub(remainder) >> 31;
+
+    quotient
+}
+
+/// Reduce `t` modulo q. `t` must be less than 2^26.
+fn reduce(t: u32) -> u16 {
+    (t - div_q(t) * Q) as u16
+}
+
+fn fq_add(a: u16, b: u16) -> u16 {
+    csubq(a as u32 + b as u32) as u16
+}
+
+fn fq_sub(a: u16, b: u16) -> u16 {
+    csubq(a as u32 + Q - b as u32) as u16
+}
+
+fn fq_mul(a: u16, b: u16) -> u16 {
+    reduce(a as u32 * b as u32)
+}
+
+#[derive(Clone, Copy)]
+/// A polynomial in R_q, with each coefficient in [0, q).
+struct Poly([u16; N]);
+
+impl Zeroize for Poly {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Poly {
+    const ZERO: Self = Self([0u16; N]);
+
+    fn add(&self, other: &Self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, (a, b)) in ret.0.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            *r = fq_add(*a, *b);
+        }
+
+        ret
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, (a, b)) in ret.0.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            *r = fq_sub(*a, *b);
+        }
+
+        ret
+    }
+
+    /// The NTT, from the FIPS 203, Algorithm 9.
+    fn ntt(&self) -> Self {
+        let mut f = *self;
+        let mut i = 1;
+        let mut len = 128;
+        while len >= 2 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[i];
+                i += 1;
+                for j in start..start + len {
+                    let t = fq_mul(zeta, f.0[j + len]);
+                    f.0[j + len] = fq_sub(f.0[j], t);
+                    f.0[j] = fq_add(f.0[j], t);
+                }
+            }
+            len /= 2;
+        }
+
+        f
+    }
+
+    /// The inverse NTT, from the FIPS 203, Algorithm 10.
+    fn ntt_inverse(&self) -> Self {
+        let mut f = *self;
+        let mut i = 127;
+        let mut len = 2;
+        while len <= 128 {
+            for start in (0..N).step_by(2 * len) {
+                let zeta = ZETAS[i];
+                i -= 1;
+                for j in start..start + len {
+                    let t = f.0[j];
+                    f.0[j] = fq_add(t, f.0[j + len]);
+                    f.0[j + len] = fq_mul(zeta, fq_sub(f.0[j + len], t));
+                }
+            }
+            len *= 2;
+        }
+
+        for coeff in f.0.iter_mut() {
+            *coeff = fq_mul(*coeff, N_INV);
+        }
+
+        f
+    }
+
+    /// Multiply two polynomials in the NTT domain, from the FIPS 203, Algorithm 11,
+    /// and add the product to `self`.
+    fn add_ntt_product(&mut self, f: &Self, g: &Self) {
+        for (i, gamma) in GAMMAS.iter().enumerate() {
+            let (a0, a1) = (f.0[2 * i], f.0[2 * i + 1]);
+            let (b0, b1) = (g.0[2 * i], g.0[2 * i + 1]);
+
+            // BaseCaseMultiply, from the FIPS 203, Algorithm 12.
+            let c0 = fq_add(fq_mul(a0, b0), fq_mul(fq_mul(a1, b1), *gamma));
+            let c1 = fq_add(fq_mul(a0, b1), fq_mul(a1, b0));
+
+            self.0[2 * i] = fq_add(self.0[2 * i], c0);
+            self.0[2 * i + 1] = fq_add(self.0[2 * i + 1], c1);
+        }
+    }
+
+    /// Compute the inner product of two vectors in the NTT domain.
+    fn inner_product(f: &[Self; K], g: &[Self; K]) -> Self {
+        let mut ret = Self::ZERO;
+        for (a, b) in f.iter().zip(g.iter()) {
+            ret.add_ntt_product(a, b);
+        }
+
+        ret
+    }
+
+    /// ByteEncode_d, from the FIPS 203, Algorithm 5. Each coefficient must be less than 2^d.
+    fn byte_encode(&self, d: usize, dst_out: &mut [u8]) {
+        debug_assert_eq!(dst_out.len(), 32 * d);
+        let mut acc: u32 = 0;
+        let mut acc_bits = 0;
+        let mut idx = 0;
+        for coeff in self.0.iter() {
+            debug_assert!((*coeff as u32) < (1 << d));
+            acc |= (*coeff as u32) << acc_bits;
+            acc_bits += d;
+            while acc_bits >= 8 {
+                dst_out[idx] = acc as u8;
+                idx += 1;
+                acc >>= 8;
+                acc_bits -= 8;
+            }
+        }
+    }
+
+    /// ByteDecode_d, from the FIPS 203, Algorithm 6. For d = 12, the coefficients
+    /// are reduced modulo q.
+    fn byte_decode(src: &[u8], d: usize) -> Self {
+        debug_assert_eq!(src.len(), 32 * d);
+        let mask = (1u32 << d) - 1;
+        let mut ret = Self::ZERO;
+        let mut acc: u32 = 0;
+        let mut acc_bits = 0;
+        let mut coeffs = ret.0.iter_mut();
+        for byte in src.iter() {
+            acc |= (*byte as u32) << acc_bits;
+            acc_bits += 8;
+            while acc_bits >= d {
+                // The lengths match, so there is always a coefficient left.
+                if let Some(coeff) = coeffs.next() {
+                    let value = acc & mask;
+                    *coeff = if d == 12 { csubq(value) } else { value } as u16;
+                }
+                acc >>= d;
+                acc_bits -= d;
+            }
+        }
+
+        ret
+    }
+
+    /// Compress_d, from the FIPS 203, Section 4.2.1, applied to each coefficient.
+    fn compress(&self, d: usize) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, coeff) in ret.0.iter_mut().zip(self.0.iter()) {
+            // round(2^d / q * x) = floor((x * 2^d + floor(q / 2)) / q)
+            let rounded = div_q(((*coeff as u32) << d) + (Q / 2));
+            *r = (rounded & ((1 << d) - 1)) as u16;
+        }
+
+        ret
+    }
+
+    /// Decompress_d, from the FIPS 203, Section 4.2.1, applied to each coefficient.
+    fn decompress(&self, d: usize) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, coeff) in ret.0.iter_mut().zip(self.0.iter()) {
+            // round(q / 2^d * y) = floor((y * q + 2^(d - 1)) / 2^d)
+            *r = (((*coeff as u32) * Q + (1 << (d - 1))) >> d) as u16;
+        }
+
+        ret
+    }
+
+    /// SampleNTT, from the FIPS 203, Algorithm 7, using SHAKE128(rho || j || i).
+    ///
+    /// This runs in variable time, but only depends on public data.
+    fn sample_ntt(rho: &[u8], j: u8, i: u8) -> Self {
+        let mut xof = Shake128::new();
+        xof.absorb(rho);
+        xof.absorb(&[j, i]);
+
+        let mut ret = Self::ZERO;
+        let mut idx = 0;
+        let mut block = [0u8; 168];
+        while idx < N {
+            xof.squeeze(&mut block);
+            for c in block.chunks_exact(3) {
+                let d1 = (c[0] as u32) | (((c[1] & 0x0f) as u32) << 8);
+                let d2 = ((c[1] >> 4) as u32) | ((c[2] as u32) << 4);
+                if d1 < Q && idx < N {
+                    ret.0[idx] = d1 as u16;
+                    idx += 1;
+                }
+                if d2 < Q && idx < N {
+                    ret.0[idx] = d2 as u16;
+                    idx += 1;
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// SamplePolyCBD_eta, from the FIPS 203, Algorithm 8, using PRF_eta(s, b) = SHAKE256(s || b).
+    fn sample_cbd(s: &[u8], b: u8, eta: usize) -> Self {
+        debug_assert!(eta == 2 || eta == 3);
+        let mut prf_out = [0u8; 64 * 3];
+        let prf_out = &mut prf_out[..64 * eta];
+        let mut xof = Shake256::new();
+        xof.absorb(s);
+        xof.absorb(&[b]);
+        xof.squeeze(prf_out);
+
+        let bit = |idx: usize| -> u16 { ((prf_out[idx / 8] >> (idx % 8)) & 1) as u16 };
+        let mut ret = Self::ZERO;
+        for (i, coeff) in ret.0.iter_mut().enumerate() {
+            let mut x = 0u16;
+            let mut y = 0u16;
+            for j in 0..eta {
+                x += bit(2 * i * eta + j);
+                y += bit(2 * i * eta + eta + j);
+            }
+            *coeff = fq_sub(x, y);
+        }
+        prf_out.zeroize();
+
+        ret
+    }
+}
+
+/// Generate the matrix A (or its transpose) in the NTT domain, from the
+/// seed `rho`, as in the FIPS 203, Algorithm 13, step 3-7.
+fn sample_matrix(rho: &[u8], transpose: bool) -> [[Poly; K]; K] {
+    let mut a_hat = [[Poly::ZERO; K]; K];
+    for (i, row) in a_hat.iter_mut().enumerate() {
+        for (j, entry) in row.iter_mut().enumerate() {
+            *entry = if transpose {
+                Poly::sample_ntt(rho, i as u8, j as u8)
+            } else {
+                Poly::sample_ntt(rho, j as u8, i as u8)
+            };
+        }
+    }
+
+    a_hat
+}
+
+/// Decode an encoded vector of K polynomials with 12-bit coefficients.
+fn decode_polyvec(src: &[u8]) -> [Poly; K] {
+    debug_assert_eq!(src.len(), POLYVEC_BYTES);
+    let mut ret = [Poly::ZERO; K];
+    for (poly, chunk) in ret.iter_mut().zip(src.chunks_exact(384)) {
+        *poly = Poly::byte_decode(chunk, 12);
+    }
+
+    ret
+}
+
+/// Encode a vector of K polynomials with 12-bit coefficients.
+fn encode_polyvec(polys: &[Poly; K], dst_out: &mut [u8]) {
+    debug_assert_eq!(dst_out.len(), POLYVEC_BYTES);
+    for (poly, chunk) in polys.iter().zip(dst_out.chunks_exact_mut(384)) {
+        poly.byte_encode(12, chunk);
+    }
+}
+
+/// K-PKE.KeyGen, from the FIPS 203, Algorithm 13. Writes the encryption key
+/// to `ek` and the decryption key to `dk_pke`.
+fn pke_keygen(d: &[u8], ek: &mut [u8], dk_pke: &mut [u8]) {
+    let mut g = sha3_512(&[d, &[K as u8]]);
+    let (rho, sigma) = g.split_at(32);
+    let a_hat = sample_matrix(rho, false);
+
+    let mut s_hat = [Poly::ZERO; K];
+    let mut e_hat = [Poly::ZERO; K];
+    for (i, (s, e)) in s_hat.iter_mut().zip(e_hat.iter_mut()).enumerate() {
+        let mut s_poly = Poly::sample_cbd(sigma, i as u8, ETA1);
+        let mut e_poly = Poly::sample_cbd(sigma, (K + i) as u8, ETA1);
+        *s = s_poly.ntt();
+        *e = e_poly.ntt();
+        s_poly.zeroize();
+        e_poly.zeroize();
+    }
+
+    let mut t_hat = [Poly::ZERO; K];
+    for (t, (row, e)) in t_hat.iter_mut().zip(a_hat.iter().zip(e_hat.iter())) {
+        *t = Poly::inner_product(row, &s_hat).add(e);
+    }
+
+    encode_polyvec(&t_hat, &mut ek[..POLYVEC_BYTES]);
+    ek[POLYVEC_BYTES..].copy_from_slice(rho);
+    encode_polyvec(&s_hat, dk_pke);
+
+    g.zeroize();
+    s_hat.zeroize();
+    e_hat.zeroize();
+}
+
+/// K-PKE.Encrypt, from the FIPS 203, Algorithm 14.
+fn pke_encrypt(ek: &[u8], m: &[u8], r: &[u8], ciphertext: &mut [u8; MLKEM768_CIPHERTEXTSIZE]) {
+    let t_hat = decode_polyvec(&ek[..POLYVEC_BYTES]);
+    let a_hat_t = sample_matrix(&ek[POLYVEC_BYTES..], true);
+
+    let mut y_hat = [Poly::ZERO; K];
+    let mut e1 = [Poly::ZERO; K];
+    for (i, (y, e)) in y_hat.iter_mut().zip(e1.iter_mut()).enumerate() {
+        let mut y_poly = Poly::sample_cbd(r, i as u8, ETA1);
+        *y = y_poly.ntt();
+        *e = Poly::sample_cbd(r, (K + i) as u8, ETA2);
+        y_poly.zeroize();
+    }
+    let mut e2 = Poly::sample_cbd(r, (2 * K) as u8, ETA2);
+
+    let (c1, c2) = ciphertext.split_at_mut(32 * DU * K);
+    for ((row, e), chunk) in a_hat_t
+        .iter()
+        .zip(e1.iter())
+        .zip(c1.chunks_exact_mut(32 * DU))
+    {
+        let mut u = Poly::inner_product(row, &y_hat).ntt_inverse().add(e);
+        u.compress(DU).byte_encode(DU, chunk);
+        u.zeroize();
+    }
+
+    let mut mu = Poly::byte_decode(m, 1).decompress(1);
+    let mut v = Poly::inner_product(&t_hat, &y_hat)
+        .ntt_inverse()
+        .add(&e2)
+        .add(&mu);
+    v.compress(DV).byte_encode(DV, c2);
+
+    y_hat.zeroize();
+    e1.zeroize();
+    e2.zeroize();
+    mu.zeroize();
+    v.zeroize();
+}
+
+/// K-PKE.Decrypt, from the FIPS 203, Algorithm 15.
+fn pke_decrypt(dk_pke: &[u8], ciphertext: &[u8], m: &mut [u8; 32]) {
+    let (c1, c2) = ciphertext.split_at(32 * DU * K);
+    let mut u_hat = [Poly::ZERO; K];
+    for (u, chunk) in u_hat.iter_mut().zip(c1.chunks_exact(32 * DU)) {
+        *u = Poly::byte_decode(chunk, DU).decompress(DU).ntt();
+    }
+    let v = Poly::byte_decode(c2, DV).decompress(DV);
+
+    let mut s_hat = decode_polyvec(dk_pke);
+    let mut w = v.sub(&Poly::inner_product(&s_hat, &u_hat).ntt_inverse());
+    w.compress(1).byte_encode(1, m);
+
+    s_hat.zeroize();
+    w.zeroize();
+}
+
+/// ML-KEM.KeyGen_internal, from the FIPS 203, Algorithm 16.
+fn keygen_internal(
+    d: &[u8],
+    z: &[u8],
+    ek: &mut [u8; MLKEM768_ENCAPSULATION_KEYSIZE],
+    dk: &mut [u8; MLKEM768_DECAPSULATION_KEYSIZE],
+) {
+    pke_keygen(d, ek, &mut dk[..POLYVEC_BYTES]);
+    dk[POLYVEC_BYTES..2 * POLYVEC_BYTES + 32].copy_from_slice(ek);
+    dk[2 * POLYVEC_BYTES + 32..2 * POLYVEC_BYTES + 64].copy_from_slice(&sha3_256(&[ek]));
+    dk[2 * POLYVEC_BYTES + 64..].copy_from_slice(z);
+}
+
+/// ML-KEM.Encaps_internal, from the FIPS 203, Algorithm 17.
+#[cfg(any(feature = "safe_api", test))]
+pub(crate) fn encapsulate_internal(
+    ek: &EncapsulationKey,
+    m: &[u8; 32],
+) -> (SharedSecret, Ciphertext) {
+    let mut g = sha3_512(&[m, &sha3_256(&[ek.as_ref()])]);
+    let mut ciphertext = [0u8; MLKEM768_CIPHERTEXTSIZE];
+    pke_encrypt(ek.as_ref(), m, &g[32..], &mut ciphertext);
+
+    let mut value = [0u8; MLKEM768_SHAREDSECRETSIZE];
+    value.copy_from_slice(&g[..32]);
+    g.zeroize();
+
+    (SharedSecret { value }, Ciphertext::from(ciphertext))
+}
+
+/// ML-KEM.Decaps_internal, from the FIPS 203, Algorithm 18.
+fn decapsulate_internal(dk: &DecapsulationKey, ciphertext: &Ciphertext) -> SharedSecret {
+    let dk = &dk.value;
+    let dk_pke = &dk[..POLYVEC_BYTES];
+    let ek_pke = &dk[POLYVEC_BYTES..2 * POLYVEC_BYTES + 32];
+    let h = &dk[2 * POLYVEC_BYTES + 32..2 * POLYVEC_BYTES + 64];
+    let z = &dk[2 * POLYVEC_BYTES + 64..];
+
+    let mut m_prime = [0u8; 32];
+    pke_decrypt(dk_pke, ciphertext.as_ref(), &mut m_prime);
+    let mut g = sha3_512(&[&m_prime, h]);
+
+    let mut k_bar = [0u8; MLKEM768_SHAREDSECRETSIZE];
+    shake256(&[z, ciphertext.as_ref()], &mut k_bar);
+
+    let mut c_prime = [0u8; MLKEM768_CIPHERTEXTSIZE];
+    pke_encrypt(ek_pke, &m_prime, &g[32..], &mut c_prime);
+    let is_valid = c_prime.ct_eq(ciphertext.as_ref());
+
+    let mut value = [0u8; MLKEM768_SHAREDSECRETSIZE];
+    for (v, (k, k_rej)) in value.iter_mut().zip(g[..32].iter().zip(k_bar.iter())) {
+        *v = u8::conditional_select(k_rej, k, is_valid);
+    }
+
+    m_prime.zeroize();
+    g.zeroize();
+    k_bar.zeroize();
+    c_prime.zeroize();
+
+    SharedSecret { value }
+}
+
+construct_secret_key! {
+    /// A type to represent the 64-byte seed that an ML-KEM-768 [`KeyPair`] is derived from.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 64 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (Seed, test_seed, MLKEM768_SEEDSIZE, MLKEM768_SEEDSIZE, MLKEM768_SEEDSIZE)
+}
+
+impl_from_trait!(Seed, MLKEM768_SEEDSIZE);
+
+#[derive(Clone)]
+/// A type to represent the `EncapsulationKey` of ML-KEM-768, which is public.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `slice` is not 1184 bytes.
+/// - `slice` fails the modulus check of the FIPS 203, Section 7.2.
+///
+/// `PartialEq` and [`ConstantTimeEq`](crate::util::ConstantTimeEq) are
+/// implemented for this type in constant time.
+pub struct EncapsulationKey {
+    value: [u8; MLKEM768_ENCAPSULATION_KEYSIZE],
+    original_length: usize,
+}
+
+impl_ct_partialeq_trait!(EncapsulationKey, as_ref);
+impl_normal_debug_trait!(EncapsulationKey);
+impl_asref_trait!(EncapsulationKey);
+impl_serde_traits!(EncapsulationKey, as_ref);
+impl_try_from_traits!(EncapsulationKey);
+
+impl EncapsulationKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Construct from a given byte slice.
+    pub fn from_slice(slice: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if slice.len() != MLKEM768_ENCAPSULATION_KEYSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        // Modulus check: Decoding and re-encoding must give the same bytes,
+        // meaning every coefficient was already reduced modulo q.
+        let mut reencoded = [0u8; POLYVEC_BYTES];
+        encode_polyvec(&decode_polyvec(&slice[..POLYVEC_BYTES]), &mut reencoded);
+        if reencoded[..] != slice[..POLYVEC_BYTES] {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let mut value = [0u8; MLKEM768_ENCAPSULATION_KEYSIZE];
+        value.copy_from_slice(slice);
+
+        Ok(Self {
+            value,
+            original_length: MLKEM768_ENCAPSULATION_KEYSIZE,
+        })
+    }
+
+    func_len!();
+    func_is_empty!();
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the [`Fingerprint`] of this encapsulation key, for display and pinning.
+    pub fn fingerprint(&self) -> Result<Fingerprint, UnknownCryptoError> {
+        Fingerprint::of(self.as_ref())
+    }
+}
+
+/// A type to represent the `DecapsulationKey` of ML-KEM-768, which is secret.
+///
+/// This is the expanded 2400-byte decapsulation key of the FIPS 203. Storing the
+/// [`Seed`] instead, and deriving the [`KeyPair`] from it when needed, saves space.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `slice` is not 2400 bytes.
+/// - `slice` fails the hash check of the FIPS 203, Section 7.3.
+/// - The encapsulation key included in `slice` is not valid.
+///
+/// # Security:
+/// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
+///   that the type implements.
+///
+/// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+///   in custom verification logic that should not short-circuit.
+pub struct DecapsulationKey {
+    value: [u8; MLKEM768_DECAPSULATION_KEYSIZE],
+    original_length: usize,
+}
+
+impl_omitted_debug_trait!(DecapsulationKey);
+impl_drop_trait!(DecapsulationKey);
+impl_ct_partialeq_trait!(DecapsulationKey, unprotected_as_bytes);
+impl_try_from_traits!(secret DecapsulationKey);
+
+impl DecapsulationKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Construct from a given byte slice.
+    pub fn from_slice(slice: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if slice.len() != MLKEM768_DECAPSULATION_KEYSIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let ek = EncapsulationKey::from_slice(&slice[POLYVEC_BYTES..2 * POLYVEC_BYTES + 32])?;
+        let h = &slice[2 * POLYVEC_BYTES + 32..2 * POLYVEC_BYTES + 64];
+        if !bool::from(sha3_256(&[ek.as_ref()]).ct_eq(h)) {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let mut value = [0u8; MLKEM768_DECAPSULATION_KEYSIZE];
+        value.copy_from_slice(slice);
+
+        Ok(Self {
+            value,
+            original_length: MLKEM768_DECAPSULATION_KEYSIZE,
+        })
+    }
+
+    /// Return the [`EncapsulationKey`] that belongs to this decapsulation key.
+    pub fn public_key(&self) -> EncapsulationKey {
+        let mut value = [0u8; MLKEM768_ENCAPSULATION_KEYSIZE];
+        value.copy_from_slice(&self.value[POLYVEC_BYTES..2 * POLYVEC_BYTES + 32]);
+
+        // NOTE: The encapsulation key was checked when this was constructed.
+        EncapsulationKey {
+            value,
+            original_length: MLKEM768_ENCAPSULATION_KEYSIZE,
+        }
+    }
+
+    func_unprotected_as_bytes!();
+    func_len!();
+    func_is_empty!();
+}
+
+construct_public! {
+    /// A type to represent the `Ciphertext` that ML-KEM-768 encapsulation produces.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 1088 bytes.
+    (Ciphertext, test_ciphertext, MLKEM768_CIPHERTEXTSIZE, MLKEM768_CIPHERTEXTSIZE)
+}
+
+impl_from_trait!(Ciphertext, MLKEM768_CIPHERTEXTSIZE);
+
+/// A type to represent the `SharedSecret` that ML-KEM-768 produces.
+///
+/// # Security:
+/// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
+///   that the type implements.
+///
+/// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
+///   to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
+///   is implemented in such a way that the comparison happens in constant time.
+pub struct SharedSecret {
+    value: [u8; MLKEM768_SHAREDSECRETSIZE],
+}
+
+impl_omitted_debug_trait!(SharedSecret);
+impl_drop_trait!(SharedSecret);
+impl_ct_partialeq_trait!(SharedSecret, unprotected_as_bytes);
+
+impl SharedSecret {
+    #[inline]
+    /// Return the object as byte slice. __**Warning**__: Should not be used unless strictly
+    /// needed. This __**breaks protections**__ that the type implements.
+    pub fn unprotected_as_bytes(&self) -> &[u8] {
+        &self.value
+    }
+}
+
+/// A key pair of ML-KEM-768, containing an [`EncapsulationKey`] and a [`DecapsulationKey`].
+pub struct KeyPair {
+    public: EncapsulationKey,
+    private: DecapsulationKey,
+}
+
+impl KeyPair {
+    /// Deterministically derive a key pair from `seed`, using ML-KEM.KeyGen_internal
+    /// from the FIPS 203 with `seed = d || z`.
+    pub fn from_seed(seed: &Seed) -> Self {
+        let (d, z) = seed.unprotected_as_bytes().split_at(32);
+        let mut ek = [0u8; MLKEM768_ENCAPSULATION_KEYSIZE];
+        let mut dk = [0u8; MLKEM768_DECAPSULATION_KEYSIZE];
+        keygen_internal(d, z, &mut ek, &mut dk);
+
+        Self {
+            public: EncapsulationKey {
+                value: ek,
+                original_length: MLKEM768_ENCAPSULATION_KEYSIZE,
+            },
+            private: DecapsulationKey {
+                value: dk,
+                original_length: MLKEM768_DECAPSULATION_KEYSIZE,
+            },
+        }
+    }
+
+    #[cfg(feature = "safe_api")]
+    /// Randomly generate a key pair using a CSPRNG. Not available in `no_std` context.
+    pub fn generate() -> Self {
+        Self::from_seed(&Seed::generate())
+    }
+
+    /// Return the public [`EncapsulationKey`].
+    pub fn public(&self) -> &EncapsulationKey {
+        &self.public
+    }
+
+    /// Return the secret [`DecapsulationKey`].
+    pub fn private(&self) -> &DecapsulationKey {
+        &self.private
+    }
+}
+
+#[cfg(feature = "safe_api")]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Produce a [`SharedSecret`] and a [`Ciphertext`] that encapsulates it, for the holder
+/// of the decapsulation key that belongs to `ek`. Not available in `no_std` context.
+pub fn encapsulate(
+    ek: &EncapsulationKey,
+) -> Result<(SharedSecret, Ciphertext), UnknownCryptoError> {
+    let mut m = [0u8; 32];
+    crate::util::secure_rand_bytes(&mut m)?;
+    let ret = encapsulate_internal(ek, &m);
+    m.zeroize();
+
+    Ok(ret)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Recover the [`SharedSecret`] encapsulated in `ciphertext`, using `dk`.
+pub fn decapsulate(
+    dk: &DecapsulationKey,
+    ciphertext: &Ciphertext,
+) -> Result<SharedSecret, UnknownCryptoError> {
+    Ok(decapsulate_internal(dk, ciphertext))
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn test_keypair() -> KeyPair {
+        let mut seed = [0u8; MLKEM768_SEEDSIZE];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        KeyPair::from_seed(&Seed::from(seed))
+    }
+
+    mod test_keys {
+        use super::*;
+
+        #[test]
+        fn test_encapsulation_key_bad_length() {
+            let kp = test_keypair();
+            let ek = kp.public().as_ref();
+            assert!(
+                EncapsulationKey::from_slice(&ek[..MLKEM768_ENCAPSULATION_KEYSIZE - 1]).is_err()
+            );
+            assert!(EncapsulationKey::from_slice(&[ek, &[0u8]].concat()).is_err());
+            assert!(EncapsulationKey::from_slice(&[]).is_err());
+        }
+
+        #[test]
+        fn test_encapsulation_key_modulus_check() {
+            let kp = test_keypair();
+            let mut ek = kp.public().as_ref().to_vec();
+            // Coefficient 4095 is not reduced modulo q.
+            ek[0] = 0xff;
+            ek[1] |= 0x0f;
+            let err = EncapsulationKey::from_slice(&ek).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+            // Exactly q is not allowed either, but q - 1 is.
+            ek[0] = (Q & 0xff) as u8;
+            ek[1] = (ek[1] & 0xf0) | (Q >> 8) as u8;
+            assert!(EncapsulationKey::from_slice(&ek).is_err());
+            ek[0] -= 1;
+            assert!(EncapsulationKey::from_slice(&ek).is_ok());
+
+            // The seed rho is not checked.
+            let mut ek = kp.public().as_ref().to_vec();
+            ek[MLKEM768_ENCAPSULATION_KEYSIZE - 1] ^= 0xff;
+            assert!(EncapsulationKey::from_slice(&ek).is_ok());
+        }
+
+        #[test]
+        fn test_decapsulation_key_bad_length() {
+            let kp = test_keypair();
+            let dk = kp.private().unprotected_as_bytes();
+            assert!(
+                DecapsulationKey::from_slice(&dk[..MLKEM768_DECAPSULATION_KEYSIZE - 1]).is_err()
+            );
+            assert!(DecapsulationKey::from_slice(&[dk, &[0u8]].concat()).is_err());
+            assert!(DecapsulationKey::from_slice(&[]).is_err());
+        }
+
+        #[test]
+        fn test_decapsulation_key_hash_check() {
+            let kp = test_keypair();
+            assert!(DecapsulationKey::from_slice(kp.private().unprotected_as_bytes()).is_ok());
+
+            // Modifying the included encapsulation key or its hash must be detected.
+            for idx in [
+                POLYVEC_BYTES,
+                2 * POLYVEC_BYTES + 31,
+                2 * POLYVEC_BYTES + 32,
+                2 * POLYVEC_BYTES + 63,
+            ]
+            .iter()
+            {
+                let mut dk = kp.private().unprotected_as_bytes().to_vec();
+                dk[*idx] ^= 1;
+                let err = DecapsulationKey::from_slice(&dk).unwrap_err();
+                assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+            }
+
+            // The secret parts are not checked.
+            let mut dk = kp.private().unprotected_as_bytes().to_vec();
+            dk[0] ^= 1;
+            dk[MLKEM768_DECAPSULATION_KEYSIZE - 1] ^= 1;
+            assert!(DecapsulationKey::from_slice(&dk).is_ok());
+        }
+
+        #[test]
+        fn test_public_key() {
+            let kp = test_keypair();
+            assert_eq!(&kp.private().public_key(), kp.public());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_debug_impls() {
+            let kp = test_keypair();
+            let debug = format!("{:?}", kp.private());
+            assert_eq!(debug, "DecapsulationKey {***OMITTED***}");
+
+            let (ss, _) = encapsulate_internal(kp.public(), &[0u8; 32]);
+            let debug = format!("{:?}", ss);
+            assert_eq!(debug, "SharedSecret {***OMITTED***}");
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_generate_unique() {
+            let kp1 = KeyPair::generate();
+            let kp2 = KeyPair::generate();
+            assert_ne!(kp1.public(), kp2.public());
+            assert_ne!(kp1.private(), kp2.private());
+        }
+    }
+
+    mod test_kem {
+        use super::*;
+        use crate::hazardous::hash::sha2::sha256::Sha256;
+
+        #[test]
+        fn test_encapsulate_deterministic() {
+            // The shared secret has been verified by decapsulating the ciphertext
+            // with the Python `cryptography` package.
+            let kp = test_keypair();
+            let (ss, ct) = encapsulate_internal(kp.public(), &[0x42u8; 32]);
+            assert_eq!(
+                ss.unprotected_as_bytes(),
+                &hex::decode("b83e7f23b33f909715c7a50b0d4b1f6684d53e1f4b9056f803b29f058ccb5566")
+                    .unwrap()[..]
+            );
+            assert_eq!(
+                Sha256::digest(ct.as_ref()).unwrap().as_ref(),
+                &hex::decode("9c7b2f8d05c70575ec03ed8f93b7bb298e1506b97e54e5e885748965b1466f1c")
+                    .unwrap()[..]
+            );
+            assert_eq!(decapsulate(kp.private(), &ct).unwrap(), ss);
+        }
+
+        #[test]
+        fn test_implicit_rejection() {
+            let kp = test_keypair();
+            let other = KeyPair::from_seed(&Seed::from([1u8; MLKEM768_SEEDSIZE]));
+            let (ss, ct) = encapsulate_internal(kp.public(), &[0x42u8; 32]);
+
+            // The wrong key gives a different shared secret, not an error.
+            let rejected = decapsulate(other.private(), &ct).unwrap();
+            assert_ne!(rejected, ss);
+
+            // As does each modified ciphertext, and the result only depends on z and the ciphertext.
+            for idx in [0, 32 * DU * K - 1, 32 * DU * K, MLKEM768_CIPHERTEXTSIZE - 1].iter() {
+                let mut bad = [0u8; MLKEM768_CIPHERTEXTSIZE];
+                bad.copy_from_slice(ct.as_ref());
+                bad[*idx] ^= 1;
+                let bad = Ciphertext::from(bad);
+
+                let rejected = decapsulate(kp.private(), &bad).unwrap();
+                assert_ne!(rejected, ss);
+
+                let mut expected = [0u8; MLKEM768_SHAREDSECRETSIZE];
+                let z = &kp.private().unprotected_as_bytes()[MLKEM768_DECAPSULATION_KEYSIZE - 32..];
+                shake256(&[z, bad.as_ref()], &mut expected);
+                assert_eq!(rejected, &expected[..]);
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_encapsulate_decapsulate() {
+            let kp = KeyPair::generate();
+            let (ss1, ct1) = encapsulate(kp.public()).unwrap();
+            let (ss2, ct2) = encapsulate(kp.public()).unwrap();
+            assert_ne!(ss1, ss2);
+            assert_ne!(ct1, ct2);
+            assert_eq!(decapsulate(kp.private(), &ct1).unwrap(), ss1);
+            assert_eq!(decapsulate(kp.private(), &ct2).unwrap(), ss2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    #[test]
+    fn test_div_q_and_reduce() {
+        // Covers the largest input used, which is (q - 1)^2.
+        for t in (0..(Q - 1) * (Q - 1) + 1).step_by(7) {
+            assert_eq!(div_q(t), t / Q);
+            assert_eq!(reduce(t) as u32, t % Q);
+        }
+        let max = (1 << 26) - 1;
+        assert_eq!(div_q(max), max / Q);
+    }
+
+    #[test]
+    fn test_ntt_roundtrip() {
+        let mut f = Poly::ZERO;
+        for (i, coeff) in f.0.iter_mut().enumerate() {
+            *coeff = ((i * 1337) as u32 % Q) as u16;
+        }
+        assert_eq!(f.ntt().ntt_inverse().0[..], f.0[..]);
+    }
+
+    #[test]
+    fn test_ntt_multiplication() {
+        // (1 + X) * X^255 = X^255 - 1, in Z_q[X]/(X^256 + 1).
+        let mut f = Poly::ZERO;
+        f.0[0] = 1;
+        f.0[1] = 1;
+        let mut g = Poly::ZERO;
+        g.0[255] = 1;
+
+        let mut product = Poly::ZERO;
+        product.add_ntt_product(&f.ntt(), &g.ntt());
+        let product = product.ntt_inverse();
+
+        let mut expected = Poly::ZERO;
+        expected.0[0] = (Q - 1) as u16;
+        expected.0[255] = 1;
+        assert_eq!(product.0[..], expected.0[..]);
+    }
+
+    #[test]
+    fn test_compress_decompress() {
+        for d in [1, 4, 10].iter() {
+            let mut f = Poly::ZERO;
+            for (i, coeff) in f.0.iter_mut().enumerate() {
+                *coeff = ((i * 13) as u32 % Q) as u16;
+            }
+            let roundtrip = f.compress(*d).decompress(*d);
+            for (a, b) in f.0.iter().zip(roundtrip.0.iter()) {
+                // The error is at most round(q / 2^(d + 1)), modulo q.
+                let diff = fq_sub(*a, *b).min(fq_sub(*b, *a)) as u32;
+                assert!(diff <= (Q + (1 << *d)) >> (*d + 1));
+            }
+
+            let mut y = Poly::ZERO;
+            for (i, coeff) in y.0.iter_mut().enumerate() {
+                *coeff = (i % (1 << *d)) as u16;
+            }
+            assert_eq!(y.decompress(*d).compress(*d).0[..], y.0[..]);
+        }
+    }
+
+    #[test]
+    fn test_byte_encode_decode() {
+        for d in [1, 4, 10, 12].iter() {
+            let mut f = Poly::ZERO;
+            for (i, coeff) in f.0.iter_mut().enumerate() {
+                *coeff = ((i * 2749) as u32 % Q % (1 << *d)) as u16;
+            }
+            let mut encoded = [0u8; 384];
+            f.byte_encode(*d, &mut encoded[..32 * d]);
+            assert_eq!(Poly::byte_decode(&encoded[..32 * d], *d).0[..], f.0[..]);
+        }
+
+        // ByteDecode_12 reduces modulo q.
+        assert_eq!(
+            Poly::byte_decode(&[0xffu8; 384], 12).0[0],
+            (4095 - Q) as u16
+        );
+    }
+
+    #[test]
+    fn test_sample_cbd_range() {
+        let f = Poly::sample_cbd(&[7u8; 32], 0, ETA1);
+        for coeff in f.0.iter() {
+            assert!(*coeff <= ETA1 as u16 || *coeff >= (Q - ETA1 as u32) as u16);
+        }
+    }
+}
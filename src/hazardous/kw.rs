@@ -0,0 +1,598 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+// Based on the algorithm from https://github.com/floodyberry/poly1305-donna
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+
+//! # About:
+//! AES-256 Key Wrap (KW) as specified in the [RFC 3394], and AES-256 Key Wrap
+//! with Padding (KWP) as specified in the [RFC 5649]. Both are also specified in
+//! [NIST SP 800-38F].
+//!
+//! # Parameters:
+//! - `kek`: The key-encryption key.
+//! - `plaintext`: The key material to be wrapped.
+//! - `ciphertext`: The wrapped key material.
+//! - `dst_out`: Destination array that will hold the wrapped/unwrapped key material.
+//!
+//! [`wrap()`] produces `plaintext.len() + 8` bytes. [`wrap_with_padding()`] produces
+//! the length of `plaintext` rounded up to a multiple of 8, plus 8 bytes.
+//! [`unwrap_with_padding()`] returns the length of the unwrapped key material,
+//! which is written to the beginning of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `plaintext` is less than 16 bytes or not a multiple of 8 bytes, when using [`wrap()`].
+//! - `plaintext` is empty or longer than `2^32-1` bytes, when using [`wrap_with_padding()`].
+//! - `ciphertext` is less than 24 bytes or not a multiple of 8 bytes, when using [`unwrap()`].
+//! - `ciphertext` is less than 16 bytes or not a multiple of 8 bytes, when using
+//!   [`unwrap_with_padding()`].
+//! - The length of `dst_out` is less than what the wrapped/unwrapped output requires.
+//! - The integrity check fails when unwrapping. `dst_out` is zeroed when this happens.
+//!
+//! # Security:
+//! - Key wrapping is only intended for protecting key material. Use an AEAD
+//!   construction for general data. See the [`aead`](super::aead) module for this.
+//! - The output of wrapping is deterministic. Wrapping the same key material twice,
+//!   with the same `kek`, reveals that the key material is the same.
+//! - AES is implemented in software, without lookup tables, so that it runs in
+//!   constant time. Hardware AES instructions are not used.
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::kw;
+//!
+//! let kek = kw::SecretKey::generate();
+//! let key_material = [7u8; 32];
+//!
+//! let mut wrapped = [0u8; 40];
+//! kw::wrap(&kek, &key_material, &mut wrapped)?;
+//!
+//! let mut unwrapped = [0u8; 32];
+//! kw::unwrap(&kek, &wrapped, &mut unwrapped)?;
+//! assert_eq!(unwrapped, key_material);
+//!
+//! // Key material that is not a multiple of 8 bytes needs KWP.
+//! let mut wrapped = [0u8; 24];
+//! kw::wrap_with_padding(&kek, &key_material[..13], &mut wrapped)?;
+//!
+//! let mut unwrapped = [0u8; 16];
+//! let len = kw::unwrap_with_padding(&kek, &wrapped, &mut unwrapped)?;
+//! assert_eq!(&unwrapped[..len], &key_material[..13]);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`wrap()`]: kw::wrap
+//! [`unwrap()`]: kw::unwrap
+//! [`wrap_with_padding()`]: kw::wrap_with_padding
+//! [`unwrap_with_padding()`]: kw::unwrap_with_padding
+//! [`SecretKey::generate()`]: kw::SecretKey::generate
+//! [RFC 3394]: https://tools.ietf.org/html/rfc3394
+//! [RFC 5649]: https://tools.ietf.org/html/rfc5649
+//! [NIST SP 800-38F]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38F.pdf
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::util::aes::{Aes, AES_BLOCKSIZE};
+use subtle::{ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
+use zeroize::Zeroize;
+
+/// The key size of the key-encryption key.
+pub const KW_KEYSIZE: usize = 32;
+/// The size of the semiblocks that KW and KWP operate on.
+pub const KW_SEMIBLOCKSIZE: usize = 8;
+/// The amount of bytes that wrapping adds to the key material, excluding padding.
+pub const KW_OVERHEAD: usize = KW_SEMIBLOCKSIZE;
+
+/// The default initial value of KW.
+const KW_IV: [u8; KW_SEMIBLOCKSIZE] = [0xa6; KW_SEMIBLOCKSIZE];
+/// The constant part of the alternative initial value of KWP.
+const KWP_AIV_PREFIX: [u8; 4] = [0xa6, 0x59, 0x59, 0xa6];
+
+construct_secret_key! {
+    /// A type to represent the key-encryption key that AES-256 KW and KWP use.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, KW_KEYSIZE, KW_KEYSIZE, KW_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, KW_KEYSIZE);
+
+/// The wrapping function W, applied to the semiblocks in `r` in-place.
+/// Returns the final value of the integrity check register.
+fn w(cipher: &Aes, iv: [u8; KW_SEMIBLOCKSIZE], r: &mut [u8]) -> [u8; KW_SEMIBLOCKSIZE] {
+    debug_assert!(r.len() >= 2 * KW_SEMIBLOCKSIZE);
+    let n = (r.len() / KW_SEMIBLOCKSIZE) as u64;
+    let mut block = [0u8; AES_BLOCKSIZE];
+    block[..KW_SEMIBLOCKSIZE].copy_from_slice(&iv);
+
+    let mut t = 0u64;
+    for _ in 0..6 {
+        for semiblock in r.chunks_exact_mut(KW_SEMIBLOCKSIZE) {
+            t += 1;
+            block[KW_SEMIBLOCKSIZE..].copy_from_slice(semiblock);
+            cipher.encrypt_block(&mut block);
+            for (a, c) in block[..KW_SEMIBLOCKSIZE]
+                .iter_mut()
+                .zip(t.to_be_bytes().iter())
+            {
+                *a ^= c;
+            }
+            semiblock.copy_from_slice(&block[KW_SEMIBLOCKSIZE..]);
+        }
+    }
+    debug_assert_eq!(t, 6 * n);
+
+    let mut a = [0u8; KW_SEMIBLOCKSIZE];
+    a.copy_from_slice(&block[..KW_SEMIBLOCKSIZE]);
+    block.zeroize();
+
+    a
+}
+
+/// The unwrapping function W^-1, applied to the semiblocks in `r` in-place.
+/// Returns the recovered value of the integrity check register.
+fn w_inverse(cipher: &Aes, a: &[u8], r: &mut [u8]) -> [u8; KW_SEMIBLOCKSIZE] {
+    debug_assert!(r.len() >= 2 * KW_SEMIBLOCKSIZE);
+    let mut t = (r.len() / KW_SEMIBLOCKSIZE) as u64 * 6;
+    let mut block = [0u8; AES_BLOCKSIZE];
+    block[..KW_SEMIBLOCKSIZE].copy_from_slice(a);
+
+    for _ in 0..6 {
+        for semiblock in r.chunks_exact_mut(KW_SEMIBLOCKSIZE).rev() {
+            for (a, c) in block[..KW_SEMIBLOCKSIZE]
+                .iter_mut()
+                .zip(t.to_be_bytes().iter())
+            {
+                *a ^= c;
+            }
+            block[KW_SEMIBLOCKSIZE..].copy_from_slice(semiblock);
+            cipher.decrypt_block(&mut block);
+            semiblock.copy_from_slice(&block[KW_SEMIBLOCKSIZE..]);
+            t -= 1;
+        }
+    }
+
+    let mut a = [0u8; KW_SEMIBLOCKSIZE];
+    a.copy_from_slice(&block[..KW_SEMIBLOCKSIZE]);
+    block.zeroize();
+
+    a
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Wrap `plaintext` using AES-256 KW.
+pub fn wrap(
+    kek: &SecretKey,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if plaintext.len() < 2 * KW_SEMIBLOCKSIZE
+        || !plaintext
+            .chunks_exact(KW_SEMIBLOCKSIZE)
+            .remainder()
+            .is_empty()
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let out_len = plaintext.len() + KW_OVERHEAD;
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let cipher = Aes::new(kek.unprotected_as_bytes())?;
+    dst_out[KW_SEMIBLOCKSIZE..out_len].copy_from_slice(plaintext);
+    let a = w(&cipher, KW_IV, &mut dst_out[KW_SEMIBLOCKSIZE..out_len]);
+    dst_out[..KW_SEMIBLOCKSIZE].copy_from_slice(&a);
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Unwrap `ciphertext` using AES-256 KW.
+pub fn unwrap(
+    kek: &SecretKey,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if ciphertext.len() < 3 * KW_SEMIBLOCKSIZE
+        || !ciphertext
+            .chunks_exact(KW_SEMIBLOCKSIZE)
+            .remainder()
+            .is_empty()
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let out_len = ciphertext.len() - KW_OVERHEAD;
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let cipher = Aes::new(kek.unprotected_as_bytes())?;
+    dst_out[..out_len].copy_from_slice(&ciphertext[KW_SEMIBLOCKSIZE..]);
+    let a = w_inverse(
+        &cipher,
+        &ciphertext[..KW_SEMIBLOCKSIZE],
+        &mut dst_out[..out_len],
+    );
+
+    if bool::from(a.ct_eq(&KW_IV)) {
+        Ok(())
+    } else {
+        dst_out[..out_len].zeroize();
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Wrap `plaintext` using AES-256 KWP.
+pub fn wrap_with_padding(
+    kek: &SecretKey,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if plaintext.is_empty() || plaintext.len() as u64 > u64::from(u32::MAX) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let padded_len = plaintext.chunks(KW_SEMIBLOCKSIZE).len() * KW_SEMIBLOCKSIZE;
+    let out_len = padded_len + KW_OVERHEAD;
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut aiv = [0u8; KW_SEMIBLOCKSIZE];
+    aiv[..4].copy_from_slice(&KWP_AIV_PREFIX);
+    aiv[4..].copy_from_slice(&(plaintext.len() as u32).to_be_bytes());
+
+    let cipher = Aes::new(kek.unprotected_as_bytes())?;
+    let padded = &mut dst_out[KW_SEMIBLOCKSIZE..out_len];
+    padded[..plaintext.len()].copy_from_slice(plaintext);
+    padded[plaintext.len()..].iter_mut().for_each(|b| *b = 0u8);
+
+    if padded_len == KW_SEMIBLOCKSIZE {
+        let mut block = [0u8; AES_BLOCKSIZE];
+        block[..KW_SEMIBLOCKSIZE].copy_from_slice(&aiv);
+        block[KW_SEMIBLOCKSIZE..].copy_from_slice(padded);
+        cipher.encrypt_block(&mut block);
+        dst_out[..AES_BLOCKSIZE].copy_from_slice(&block);
+        block.zeroize();
+    } else {
+        let a = w(&cipher, aiv, padded);
+        dst_out[..KW_SEMIBLOCKSIZE].copy_from_slice(&a);
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Unwrap `ciphertext` using AES-256 KWP. Returns the length of the unwrapped key material.
+pub fn unwrap_with_padding(
+    kek: &SecretKey,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<usize, UnknownCryptoError> {
+    if ciphertext.len() < 2 * KW_SEMIBLOCKSIZE
+        || !ciphertext
+            .chunks_exact(KW_SEMIBLOCKSIZE)
+            .remainder()
+            .is_empty()
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let padded_len = ciphertext.len() - KW_OVERHEAD;
+    if dst_out.len() < padded_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let cipher = Aes::new(kek.unprotected_as_bytes())?;
+    let mut a = [0u8; KW_SEMIBLOCKSIZE];
+    if padded_len == KW_SEMIBLOCKSIZE {
+        let mut block = [0u8; AES_BLOCKSIZE];
+        block.copy_from_slice(ciphertext);
+        cipher.decrypt_block(&mut block);
+        a.copy_from_slice(&block[..KW_SEMIBLOCKSIZE]);
+        dst_out[..padded_len].copy_from_slice(&block[KW_SEMIBLOCKSIZE..]);
+        block.zeroize();
+    } else {
+        dst_out[..padded_len].copy_from_slice(&ciphertext[KW_SEMIBLOCKSIZE..]);
+        a = w_inverse(
+            &cipher,
+            &ciphertext[..KW_SEMIBLOCKSIZE],
+            &mut dst_out[..padded_len],
+        );
+    }
+
+    // The message length indicator must be within the last semiblock, and all
+    // padding bytes must be zero.
+    let mut mli_bytes = [0u8; 4];
+    mli_bytes.copy_from_slice(&a[4..]);
+    let mli = u32::from_be_bytes(mli_bytes) as u64;
+    let mut is_valid = a[..4].ct_eq(&KWP_AIV_PREFIX);
+    is_valid &= mli.ct_gt(&(padded_len as u64 - KW_SEMIBLOCKSIZE as u64));
+    is_valid &= !mli.ct_gt(&(padded_len as u64));
+
+    let last_start = padded_len - KW_SEMIBLOCKSIZE;
+    let mut padding = 0u8;
+    for (idx, b) in dst_out[last_start..padded_len].iter().enumerate() {
+        let is_padding = ((last_start + idx) as u64 + 1).ct_gt(&mli);
+        padding |= u8::conditional_select(&0u8, b, is_padding);
+    }
+    is_valid &= padding.ct_eq(&0u8);
+
+    if bool::from(is_valid) {
+        Ok(mli as usize)
+    } else {
+        dst_out[..padded_len].zeroize();
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const KEK: [u8; KW_KEYSIZE] = [9u8; KW_KEYSIZE];
+
+    mod test_wrap_unwrap {
+        use super::*;
+
+        #[test]
+        fn test_plaintext_length() {
+            let kek = SecretKey::from(KEK);
+            let mut dst_out = [0u8; 64];
+
+            assert!(wrap(&kek, &[0u8; 0], &mut dst_out).is_err());
+            assert!(wrap(&kek, &[0u8; 8], &mut dst_out).is_err());
+            assert!(wrap(&kek, &[0u8; 15], &mut dst_out).is_err());
+            assert!(wrap(&kek, &[0u8; 17], &mut dst_out).is_err());
+            assert!(wrap(&kek, &[0u8; 16], &mut dst_out).is_ok());
+            assert!(wrap(&kek, &[0u8; 24], &mut dst_out).is_ok());
+            assert!(wrap(&kek, &[0u8; 56], &mut dst_out).is_ok());
+        }
+
+        #[test]
+        fn test_ciphertext_length() {
+            let kek = SecretKey::from(KEK);
+            let mut dst_out = [0u8; 64];
+
+            assert!(unwrap(&kek, &[0u8; 0], &mut dst_out).is_err());
+            assert!(unwrap(&kek, &[0u8; 16], &mut dst_out).is_err());
+            assert!(unwrap(&kek, &[0u8; 25], &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_dst_out_length() {
+            let kek = SecretKey::from(KEK);
+            let mut wrapped = [0u8; 40];
+            assert!(wrap(&kek, &[1u8; 32], &mut wrapped[..39]).is_err());
+            assert!(wrap(&kek, &[1u8; 32], &mut wrapped).is_ok());
+
+            let mut unwrapped = [0u8; 33];
+            assert!(unwrap(&kek, &wrapped, &mut unwrapped[..31]).is_err());
+            assert!(unwrap(&kek, &wrapped, &mut unwrapped[..32]).is_ok());
+            assert!(unwrap(&kek, &wrapped, &mut unwrapped).is_ok());
+            assert_eq!(unwrapped[..32], [1u8; 32]);
+            assert_eq!(unwrapped[32], 0u8);
+        }
+
+        #[test]
+        fn test_modified_ciphertext_err_and_zeroed() {
+            let kek = SecretKey::from(KEK);
+            let mut wrapped = [0u8; 40];
+            wrap(&kek, &[1u8; 32], &mut wrapped).unwrap();
+
+            for idx in 0..wrapped.len() {
+                let mut modified = wrapped;
+                modified[idx] ^= 1;
+                let mut unwrapped = [0u8; 32];
+                assert!(unwrap(&kek, &modified, &mut unwrapped).is_err());
+                assert_eq!(unwrapped, [0u8; 32]);
+            }
+
+            let mut unwrapped = [0u8; 32];
+            let bad_kek = SecretKey::from([10u8; KW_KEYSIZE]);
+            assert!(unwrap(&bad_kek, &wrapped, &mut unwrapped).is_err());
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        fn prop_wrap_unwrap(input: Vec<u8>) -> bool {
+            let kek = SecretKey::generate();
+            let mut wrapped = vec![0u8; input.len() + KW_OVERHEAD];
+            let res = wrap(&kek, &input, &mut wrapped);
+
+            if input.len() < 16 || !input.chunks_exact(KW_SEMIBLOCKSIZE).remainder().is_empty() {
+                return res.is_err();
+            }
+
+            let mut unwrapped = vec![0u8; input.len()];
+            res.is_ok() && unwrap(&kek, &wrapped, &mut unwrapped).is_ok() && unwrapped == input
+        }
+    }
+
+    mod test_wrap_unwrap_with_padding {
+        use super::*;
+
+        #[test]
+        fn test_plaintext_length() {
+            let kek = SecretKey::from(KEK);
+            let mut dst_out = [0u8; 64];
+
+            assert!(wrap_with_padding(&kek, &[0u8; 0], &mut dst_out).is_err());
+            for len in 1..=56 {
+                assert!(wrap_with_padding(&kek, &[0u8; 56][..len], &mut dst_out).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_ciphertext_length() {
+            let kek = SecretKey::from(KEK);
+            let mut dst_out = [0u8; 64];
+
+            assert!(unwrap_with_padding(&kek, &[0u8; 0], &mut dst_out).is_err());
+            assert!(unwrap_with_padding(&kek, &[0u8; 8], &mut dst_out).is_err());
+            assert!(unwrap_with_padding(&kek, &[0u8; 17], &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_dst_out_length() {
+            let kek = SecretKey::from(KEK);
+            let mut wrapped = [0u8; 24];
+            assert!(wrap_with_padding(&kek, &[1u8; 9], &mut wrapped[..23]).is_err());
+            assert!(wrap_with_padding(&kek, &[1u8; 9], &mut wrapped).is_ok());
+
+            let mut unwrapped = [0u8; 16];
+            assert!(unwrap_with_padding(&kek, &wrapped, &mut unwrapped[..15]).is_err());
+            assert_eq!(
+                unwrap_with_padding(&kek, &wrapped, &mut unwrapped).unwrap(),
+                9
+            );
+            assert_eq!(unwrapped[..9], [1u8; 9]);
+        }
+
+        #[test]
+        fn test_single_block() {
+            let kek = SecretKey::from(KEK);
+            let mut wrapped = [0u8; 16];
+            wrap_with_padding(&kek, &[1u8; 8], &mut wrapped).unwrap();
+
+            let mut unwrapped = [0u8; 8];
+            assert_eq!(
+                unwrap_with_padding(&kek, &wrapped, &mut unwrapped).unwrap(),
+                8
+            );
+            assert_eq!(unwrapped, [1u8; 8]);
+        }
+
+        #[test]
+        fn test_modified_ciphertext_err_and_zeroed() {
+            let kek = SecretKey::from(KEK);
+            for (len, out_len) in [(5usize, 16usize), (8, 16), (13, 24), (32, 40)].iter() {
+                let (len, out_len) = (*len, *out_len);
+                let mut wrapped = [0u8; 40];
+                wrap_with_padding(&kek, &[1u8; 32][..len], &mut wrapped).unwrap();
+
+                for idx in 0..out_len {
+                    let mut modified = wrapped;
+                    modified[idx] ^= 1;
+                    let mut unwrapped = [0u8; 32];
+                    assert!(
+                        unwrap_with_padding(&kek, &modified[..out_len], &mut unwrapped).is_err()
+                    );
+                    assert_eq!(unwrapped, [0u8; 32]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_kw_and_kwp_are_not_interchangeable() {
+            let kek = SecretKey::from(KEK);
+            let mut wrapped = [0u8; 24];
+            wrap(&kek, &[1u8; 16], &mut wrapped).unwrap();
+            let mut unwrapped = [0u8; 16];
+            assert!(unwrap_with_padding(&kek, &wrapped, &mut unwrapped).is_err());
+
+            wrap_with_padding(&kek, &[1u8; 16], &mut wrapped).unwrap();
+            assert!(unwrap(&kek, &wrapped, &mut unwrapped).is_err());
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        fn prop_wrap_unwrap(input: Vec<u8>) -> bool {
+            let kek = SecretKey::generate();
+            let padded_len = input.chunks(KW_SEMIBLOCKSIZE).len() * KW_SEMIBLOCKSIZE;
+            let mut wrapped = vec![0u8; padded_len + KW_OVERHEAD];
+            let res = wrap_with_padding(&kek, &input, &mut wrapped);
+
+            if input.is_empty() {
+                return res.is_err();
+            }
+
+            let mut unwrapped = vec![0u8; padded_len];
+            res.is_ok()
+                && unwrap_with_padding(&kek, &wrapped, &mut unwrapped) == Ok(input.len())
+                && unwrapped[..input.len()] == input[..]
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    /// Wrap `padded` with KWP, using `aiv` as the alternative initial value.
+    fn wrap_with_aiv(aiv: [u8; KW_SEMIBLOCKSIZE], padded: &[u8], dst_out: &mut [u8]) {
+        let cipher = Aes::new(&[9u8; KW_KEYSIZE]).unwrap();
+        dst_out[KW_SEMIBLOCKSIZE..].copy_from_slice(padded);
+        let a = w(&cipher, aiv, &mut dst_out[KW_SEMIBLOCKSIZE..]);
+        dst_out[..KW_SEMIBLOCKSIZE].copy_from_slice(&a);
+    }
+
+    #[test]
+    fn test_kwp_message_length_indicator() {
+        let kek = SecretKey::from([9u8; KW_KEYSIZE]);
+        let mut unwrapped = [0u8; 16];
+
+        for mli in [0u32, 8, 9, 16, 17, u32::MAX].iter() {
+            let mut aiv = [0u8; KW_SEMIBLOCKSIZE];
+            aiv[..4].copy_from_slice(&KWP_AIV_PREFIX);
+            aiv[4..].copy_from_slice(&mli.to_be_bytes());
+
+            let mut wrapped = [0u8; 24];
+            wrap_with_aiv(aiv, &[0u8; 16], &mut wrapped);
+
+            let res = unwrap_with_padding(&kek, &wrapped, &mut unwrapped);
+            if *mli > 8 && *mli <= 16 {
+                assert_eq!(res.unwrap(), *mli as usize);
+            } else {
+                assert!(res.is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_kwp_non_zero_padding() {
+        let kek = SecretKey::from([9u8; KW_KEYSIZE]);
+        let mut aiv = [0u8; KW_SEMIBLOCKSIZE];
+        aiv[..4].copy_from_slice(&KWP_AIV_PREFIX);
+        aiv[4..].copy_from_slice(&10u32.to_be_bytes());
+
+        for idx in 10..16 {
+            let mut padded = [1u8; 16];
+            padded[10..].copy_from_slice(&[0u8; 6]);
+            let mut wrapped = [0u8; 24];
+            let mut unwrapped = [0u8; 16];
+
+            wrap_with_aiv(aiv, &padded, &mut wrapped);
+            assert_eq!(unwrap_with_padding(&kek, &wrapped, &mut unwrapped), Ok(10));
+
+            padded[idx] = 1;
+            wrap_with_aiv(aiv, &padded, &mut wrapped);
+            assert!(unwrap_with_padding(&kek, &wrapped, &mut unwrapped).is_err());
+            assert_eq!(unwrapped, [0u8; 16]);
+        }
+    }
+}
@@ -0,0 +1,406 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+// Based on the algorithm from https://github.com/floodyberry/poly1305-donna
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+
+//! # About:
+//! CMAC with AES-128, also known as OMAC1, as specified in the [RFC 4493]
+//! and [NIST SP 800-38B].
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `data`: Data to be authenticated.
+//! - `expected`: The expected tag that needs to be verified.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`finalize()`] is called twice without a [`reset()`] in between.
+//! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between.
+//! - The calculated tag does not match the expected when verifying.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::generate()`] can be used for this.
+//! - AES is implemented in software, without lookup tables, so that it runs in
+//!   constant time. This is considerably slower than [`hmac`](super::hmac) or
+//!   [`poly1305`](super::poly1305).
+//! - A single key should not be used to authenticate more than `2^48` messages,
+//!   as recommended by [NIST SP 800-38B].
+//!
+//! # Recommendation:
+//! - Only use this if you are required to use AES-CMAC. Otherwise, it is
+//!   recommended to use HMAC.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::mac::cmac::{Cmac, SecretKey};
+//!
+//! let secret_key = SecretKey::generate();
+//! let msg = "Some message.";
+//!
+//! let mut cmac_state = Cmac::new(&secret_key);
+//! cmac_state.update(msg.as_bytes())?;
+//! let tag = cmac_state.finalize()?;
+//!
+//! assert!(Cmac::verify(&tag, &secret_key, msg.as_bytes()).is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`update()`]: cmac::Cmac::update
+//! [`reset()`]: cmac::Cmac::reset
+//! [`finalize()`]: cmac::Cmac::finalize
+//! [`SecretKey::generate()`]: cmac::SecretKey::generate
+//! [RFC 4493]: https://tools.ietf.org/html/rfc4493
+//! [NIST SP 800-38B]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38b.pdf
+
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    util::aes::{Aes, AES_BLOCKSIZE},
+};
+use zeroize::Zeroize;
+
+/// The output size for CMAC-AES128.
+pub const CMAC_OUTSIZE: usize = 16;
+/// The key size for CMAC-AES128.
+pub const CMAC_KEYSIZE: usize = 16;
+
+construct_secret_key! {
+    /// A type to represent the `SecretKey` that CMAC-AES128 uses for authentication.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 16 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, CMAC_KEYSIZE, CMAC_KEYSIZE, CMAC_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, CMAC_KEYSIZE);
+
+construct_tag! {
+    /// A type to represent the `Tag` that CMAC-AES128 returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 16 bytes.
+    (Tag, test_tag, CMAC_OUTSIZE, CMAC_OUTSIZE)
+}
+
+impl_from_trait!(Tag, CMAC_OUTSIZE);
+
+/// Multiply `block` by `x` in `GF(2^128)`, using the polynomial of [NIST SP 800-38B].
+fn dbl(block: &[u8; AES_BLOCKSIZE]) -> [u8; AES_BLOCKSIZE] {
+    let mut out = [0u8; AES_BLOCKSIZE];
+    let msb = block[0] >> 7;
+    for i in 0..AES_BLOCKSIZE - 1 {
+        out[i] = (block[i] << 1) | (block[i + 1] >> 7);
+    }
+    out[AES_BLOCKSIZE - 1] = (block[AES_BLOCKSIZE - 1] << 1) ^ (msb * 0x87);
+
+    out
+}
+
+#[derive(Clone)]
+/// CMAC-AES128 streaming state.
+pub struct Cmac {
+    cipher: Aes,
+    k1: [u8; AES_BLOCKSIZE],
+    k2: [u8; AES_BLOCKSIZE],
+    state: [u8; AES_BLOCKSIZE],
+    buffer: [u8; AES_BLOCKSIZE],
+    leftover: usize,
+    is_finalized: bool,
+}
+
+impl Drop for Cmac {
+    fn drop(&mut self) {
+        self.k1.zeroize();
+        self.k2.zeroize();
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for Cmac {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Cmac {{ cipher: [***OMITTED***], k1: [***OMITTED***], k2: [***OMITTED***], state: [***OMITTED***], buffer: [***OMITTED***], leftover: [***OMITTED***], is_finalized: {:?} }}",
+            self.is_finalized
+        )
+    }
+}
+
+impl Cmac {
+    /// Process the block in `self.buffer`.
+    fn process_buffer(&mut self) {
+        for (s, b) in self.state.iter_mut().zip(self.buffer.iter()) {
+            *s ^= b;
+        }
+        self.cipher.encrypt_block(&mut self.state);
+    }
+
+    /// Initialize a `Cmac` struct with a given secret key.
+    #[allow(clippy::unwrap_used)]
+    pub fn new(secret_key: &SecretKey) -> Self {
+        // A 16-byte key is always a valid AES key.
+        let cipher = Aes::new(secret_key.unprotected_as_bytes()).unwrap();
+        let mut l = [0u8; AES_BLOCKSIZE];
+        cipher.encrypt_block(&mut l);
+        let k1 = dbl(&l);
+        let k2 = dbl(&k1);
+        l.zeroize();
+
+        Self {
+            cipher,
+            k1,
+            k2,
+            state: [0u8; AES_BLOCKSIZE],
+            buffer: [0u8; AES_BLOCKSIZE],
+            leftover: 0,
+            is_finalized: false,
+        }
+    }
+
+    /// Reset to `new()` state.
+    pub fn reset(&mut self) {
+        self.state = [0u8; AES_BLOCKSIZE];
+        self.buffer = [0u8; AES_BLOCKSIZE];
+        self.leftover = 0;
+        self.is_finalized = false;
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update state with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let mut bytes = data;
+        while !bytes.is_empty() {
+            // The last block is treated differently in finalize(), so a full
+            // buffer is only processed once more data is available.
+            if self.leftover == AES_BLOCKSIZE {
+                self.process_buffer();
+                self.leftover = 0;
+            }
+
+            let mut want = AES_BLOCKSIZE - self.leftover;
+            if want > bytes.len() {
+                want = bytes.len();
+            }
+            self.buffer[self.leftover..self.leftover + want].copy_from_slice(&bytes[..want]);
+            self.leftover += want;
+            bytes = &bytes[want..];
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return a CMAC-AES128 tag.
+    pub fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        self.is_finalized = true;
+
+        let subkey = if self.leftover == AES_BLOCKSIZE {
+            self.k1
+        } else {
+            self.buffer[self.leftover] = 0x80;
+            for b in self.buffer[self.leftover + 1..].iter_mut() {
+                *b = 0u8;
+            }
+            self.k2
+        };
+        for (b, k) in self.buffer.iter_mut().zip(subkey.iter()) {
+            *b ^= k;
+        }
+        self.process_buffer();
+
+        Ok(Tag::from(self.state))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating a CMAC-AES128 tag of `data`.
+    pub fn cmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+        let mut ctx = Self::new(secret_key);
+        ctx.update(data)?;
+        ctx.finalize()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Verify a CMAC-AES128 tag in constant time.
+    pub fn verify(
+        expected: &Tag,
+        secret_key: &SecretKey,
+        data: &[u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if &Self::cmac(secret_key, data)? == expected {
+            Ok(())
+        } else {
+            Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+        }
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug_impl() {
+        let secret_key = SecretKey::generate();
+        let initial_state = Cmac::new(&secret_key);
+        let debug = format!("{:?}", initial_state);
+        let expected = "Cmac { cipher: [***OMITTED***], k1: [***OMITTED***], k2: [***OMITTED***], state: [***OMITTED***], buffer: [***OMITTED***], leftover: [***OMITTED***], is_finalized: false }";
+        assert_eq!(debug, expected);
+    }
+
+    #[cfg(feature = "safe_api")]
+    mod test_verify {
+        use super::*;
+
+        #[quickcheck]
+        /// When using a different key, verify() should always yield an error.
+        /// NOTE: Using different and same input data is tested with TestableStreamingContext.
+        fn prop_verify_diff_key_false(data: Vec<u8>) -> bool {
+            let sk = SecretKey::generate();
+            let tag = Cmac::cmac(&sk, &data[..]).unwrap();
+            let bad_sk = SecretKey::generate();
+
+            Cmac::verify(&tag, &bad_sk, &data[..]).is_err()
+        }
+    }
+
+    mod test_streaming_interface {
+        use super::*;
+        use crate::test_framework::incremental_interface::{
+            StreamingContextConsistencyTester, TestableStreamingContext,
+        };
+
+        const KEY: [u8; CMAC_KEYSIZE] = [24u8; CMAC_KEYSIZE];
+
+        impl TestableStreamingContext<Tag> for Cmac {
+            fn reset(&mut self) -> Result<(), UnknownCryptoError> {
+                self.reset();
+                Ok(())
+            }
+
+            fn update(&mut self, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                self.update(input)
+            }
+
+            fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
+                self.finalize()
+            }
+
+            fn one_shot(input: &[u8]) -> Result<Tag, UnknownCryptoError> {
+                Cmac::cmac(&SecretKey::from(KEY), input)
+            }
+
+            fn verify_result(expected: &Tag, input: &[u8]) -> Result<(), UnknownCryptoError> {
+                // This will only run verification tests on differing input. They do not
+                // include tests for different secret keys.
+                Cmac::verify(expected, &SecretKey::from(KEY), input)
+            }
+
+            fn compare_states(state_1: &Cmac, state_2: &Cmac) {
+                assert_eq!(state_1.k1, state_2.k1);
+                assert_eq!(state_1.k2, state_2.k2);
+                assert_eq!(state_1.state, state_2.state);
+                assert_eq!(state_1.buffer[..], state_2.buffer[..]);
+                assert_eq!(state_1.leftover, state_2.leftover);
+                assert_eq!(state_1.is_finalized, state_2.is_finalized);
+            }
+        }
+
+        #[test]
+        fn default_consistency_tests() {
+            let initial_state = Cmac::new(&SecretKey::from(KEY));
+
+            let test_runner =
+                StreamingContextConsistencyTester::<Tag, Cmac>::new(initial_state, AES_BLOCKSIZE);
+            test_runner.run_all_tests();
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        /// Test different streaming state usage patterns.
+        fn prop_input_to_consistency(data: Vec<u8>) -> bool {
+            let initial_state = Cmac::new(&SecretKey::from(KEY));
+
+            let test_runner =
+                StreamingContextConsistencyTester::<Tag, Cmac>::new(initial_state, AES_BLOCKSIZE);
+            test_runner.run_all_tests_property(&data);
+            true
+        }
+    }
+}
+
+// Testing private functions in the module.
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    mod test_dbl {
+        use super::*;
+
+        #[test]
+        fn test_rfc4493_subkeys() {
+            let key = SecretKey::from([
+                0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+                0x4f, 0x3c,
+            ]);
+            let ctx = Cmac::new(&key);
+
+            assert_eq!(
+                ctx.k1,
+                [
+                    0xfb, 0xee, 0xd6, 0x18, 0x35, 0x71, 0x33, 0x66, 0x7c, 0x85, 0xe0, 0x8f, 0x72,
+                    0x36, 0xa8, 0xde
+                ]
+            );
+            assert_eq!(
+                ctx.k2,
+                [
+                    0xf7, 0xdd, 0xac, 0x30, 0x6a, 0xe2, 0x66, 0xcc, 0xf9, 0x0b, 0xc1, 0x1e, 0xe4,
+                    0x6d, 0x51, 0x3b
+                ]
+            );
+        }
+
+        #[test]
+        fn test_dbl_no_reduction() {
+            let mut block = [0u8; AES_BLOCKSIZE];
+            block[15] = 1;
+            let mut expected = [0u8; AES_BLOCKSIZE];
+            expected[15] = 2;
+            assert_eq!(dbl(&block), expected);
+        }
+    }
+}
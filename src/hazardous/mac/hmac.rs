@@ -30,12 +30,19 @@
 //! - [`finalize()`] is called twice without a [`reset()`] in between.
 //! - [`update()`] is called after [`finalize()`] without a [`reset()`] in
 //!   between.
+//! - [`export_state()`] is called after [`finalize()`] without a [`reset()`] in
+//!   between, or `dst_out` is not the size of an exported state.
+//! - The state given to [`import_state()`] is not the size of an exported state or is malformed.
 //! - The HMAC does not match the expected when verifying.
+//! - The length of a truncated tag is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater than
+//!   the output size of the hash function.
 //!
 //! # Security:
 //! - The secret key should always be generated using a CSPRNG.
 //!   [`SecretKey::generate()`] can be used for this.
 //! - The minimum recommended size for a secret key is 64 bytes.
+//! - Truncated tags provide less security against forgery than full-length tags. Only use them
+//!   when a protocol requires it.
 //!
 //! # Recommendation:
 //! - If you are unsure of whether to use HMAC or Poly1305, it is most often
@@ -52,17 +59,29 @@
 //! let tag = state.finalize()?;
 //!
 //! assert!(HmacSha512::verify(&tag, &key, b"Some message.").is_ok());
+//!
+//! // If the same key is used for many tags, its key schedule can be precomputed once.
+//! let precomputed = key.precompute();
+//! assert_eq!(precomputed.hmac(b"Some message.")?, tag);
+//! assert!(precomputed.verify(&tag, b"Some message.").is_ok());
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`update()`]: hmac::sha512::HmacSha512::update
 //! [`reset()`]: hmac::sha512::HmacSha512::reset
+//! [`export_state()`]: hmac::sha512::HmacSha512::export_state
+//! [`import_state()`]: hmac::sha512::HmacSha512::import_state
 //! [`finalize()`]: hmac::sha512::HmacSha512::finalize
 //! [`SecretKey::generate()`]: hmac::sha512::SecretKey::generate
 //! [Cryptographic Right Answers]: https://latacora.micro.blog/2018/04/03/cryptographic-right-answers.html
 
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
 use zeroize::Zeroize;
 
+/// The minimum length of a truncated tag, as used by
+/// [`finalize_truncated()`](sha512::HmacSha512::finalize_truncated) and
+/// [`verify_truncated()`](sha512::HmacSha512::verify_truncated).
+pub const MIN_TRUNCATED_TAG_SIZE: usize = 16;
+
 /// A trait used to define a cryptographic hash function used by HMAC.
 pub(crate) trait HmacHashFunction: Clone {
     /// The blocksize of the hash function.
@@ -83,6 +102,15 @@ pub(crate) trait HmacHashFunction: Clone {
     /// Compute a digest of `data` and copy it into `dest`.
     fn _digest(data: &[u8], dest: &mut [u8]) -> Result<(), UnknownCryptoError>;
 
+    /// The size of an exported state of the hash function.
+    const _STATESIZE: usize;
+
+    /// Export the state into `dest`.
+    fn _export_state(&self, dest: &mut [u8]) -> Result<(), UnknownCryptoError>;
+
+    /// Import a state previously exported with `_export_state()`.
+    fn _import_state(src: &[u8]) -> Result<Self, UnknownCryptoError>;
+
     #[cfg(test)]
     /// Compare two Sha2 state objects to check if their fields
     /// are the same.
@@ -187,7 +215,7 @@ impl<S: HmacHashFunction, const BLOCKSIZE: usize> Hmac<S, BLOCKSIZE> {
 
     fn _update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
         if self.is_finalized {
-            Err(UnknownCryptoError)
+            Err(UnknownCryptoError::new(ErrorKind::InvalidState))
         } else {
             self.working_hasher._update(data)
         }
@@ -196,7 +224,7 @@ impl<S: HmacHashFunction, const BLOCKSIZE: usize> Hmac<S, BLOCKSIZE> {
     fn _finalize(&mut self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
         debug_assert!(!dest.is_empty());
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
 
         self.is_finalized = true;
@@ -211,6 +239,39 @@ impl<S: HmacHashFunction, const BLOCKSIZE: usize> Hmac<S, BLOCKSIZE> {
         self.is_finalized = false;
     }
 
+    /// The size of an exported state: the working, outer and inner hash states.
+    const _STATESIZE: usize = S::_STATESIZE * 3;
+
+    fn _export_state(&self, dest: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dest.len() != Self::_STATESIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if self.is_finalized {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let (working, rest) = dest.split_at_mut(S::_STATESIZE);
+        let (opad, ipad) = rest.split_at_mut(S::_STATESIZE);
+        self.working_hasher._export_state(working)?;
+        self.opad_hasher._export_state(opad)?;
+        self.ipad_hasher._export_state(ipad)
+    }
+
+    fn _import_state(src: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if src.len() != Self::_STATESIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let (working, rest) = src.split_at(S::_STATESIZE);
+        let (opad, ipad) = rest.split_at(S::_STATESIZE);
+        Ok(Self {
+            working_hasher: S::_import_state(working)?,
+            opad_hasher: S::_import_state(opad)?,
+            ipad_hasher: S::_import_state(ipad)?,
+            is_finalized: false,
+        })
+    }
+
     #[cfg(test)]
     /// Compare two Hmac state objects to check if their fields
     /// are the same.
@@ -257,6 +318,9 @@ pub mod sha256 {
 
     use super::Hmac;
 
+    /// The size of an HMAC-SHA256 state exported with [`HmacSha256::export_state()`].
+    pub const HMAC_SHA256_STATESIZE: usize = sha256::SHA256_STATESIZE * 3;
+
     #[derive(Clone, Debug)]
     /// HMAC-SHA256 streaming state.
     pub struct HmacSha256 {
@@ -272,6 +336,7 @@ pub mod sha256 {
 
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Initialize `HmacSha256` struct with a given key.
+        #[allow(clippy::unwrap_used)]
         pub fn new(secret_key: &SecretKey) -> Self {
             // NOTE: `secret_key` has been pre-padded so .unwrap() is OK.
             Self::_new(secret_key.unprotected_as_bytes()).unwrap()
@@ -305,6 +370,65 @@ pub mod sha256 {
             Ok(Tag::from(dest))
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Write a HMAC-SHA256 tag, truncated to the length of `dst_out`, to `dst_out`.
+        ///
+        /// An error is returned if `dst_out` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 32 bytes.
+        pub fn finalize_truncated(&mut self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            if dst_out.len() < MIN_TRUNCATED_TAG_SIZE || dst_out.len() > sha256::SHA256_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut tag = [0u8; sha256::SHA256_OUTSIZE];
+            self._finalize_internal(&mut tag)?;
+            dst_out.copy_from_slice(&tag[..dst_out.len()]);
+
+            Ok(())
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a HMAC-SHA256 tag, truncated to the length of `expected`, in constant time.
+        ///
+        /// An error is returned if `expected` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 32 bytes.
+        pub fn verify_truncated(
+            expected: &[u8],
+            secret_key: &SecretKey,
+            data: &[u8],
+        ) -> Result<(), UnknownCryptoError> {
+            if expected.len() < MIN_TRUNCATED_TAG_SIZE || expected.len() > sha256::SHA256_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut ctx = Self::new(secret_key);
+            ctx.update(data)?;
+            let mut tag = [0u8; sha256::SHA256_OUTSIZE];
+            ctx.finalize_truncated(&mut tag[..expected.len()])?;
+
+            crate::util::secure_cmp(&tag[..expected.len()], expected)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Export the state into `dst_out`, so that authentication can be resumed later
+        /// with [`HmacSha256::import_state()`]. The state cannot be exported after
+        /// [`HmacSha256::finalize()`].
+        ///
+        /// # Security:
+        /// The exported state is equivalent to the secret key and must be protected as such.
+        pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            self._state._export_state(dst_out)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Resume authentication from a state exported with [`HmacSha256::export_state()`].
+        /// An error is returned if `state` is not [`HMAC_SHA256_STATESIZE`] bytes or is malformed.
+        pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Hmac::<Sha256, { sha256::SHA256_BLOCKSIZE }>::_import_state(state)?,
+            })
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// One-shot function for generating an HMAC-SHA256 tag of `data`.
         pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
@@ -313,6 +437,19 @@ pub mod sha256 {
             ctx.finalize()
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating an HMAC-SHA256 tag of the concatenation of the slices in `data`.
+        pub fn hmac_vectored(
+            secret_key: &SecretKey,
+            data: &[&[u8]],
+        ) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = Self::new(secret_key);
+            for slice in data.iter() {
+                ctx.update(slice)?;
+            }
+            ctx.finalize()
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Verify a HMAC-SHA256 tag in constant time.
         pub fn verify(
@@ -323,7 +460,7 @@ pub mod sha256 {
             if &Self::hmac(secret_key, data)? == expected {
                 Ok(())
             } else {
-                Err(UnknownCryptoError)
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
             }
         }
     }
@@ -357,10 +494,214 @@ pub mod sha256 {
         }
     }
 
+    impl SecretKey {
+        /// Precompute the HMAC key schedule for this key.
+        ///
+        /// The returned [`PrecomputedKey`] can produce any number of `HmacSha256`
+        /// states without processing the key again.
+        pub fn precompute(&self) -> PrecomputedKey {
+            PrecomputedKey {
+                _state: HmacSha256::new(self),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    /// A `SecretKey` for which the HMAC key schedule has already been computed.
+    ///
+    /// This is useful when many tags are computed with the same key, such as when
+    /// signing every request to an API.
+    pub struct PrecomputedKey {
+        _state: HmacSha256,
+    }
+
+    impl PrecomputedKey {
+        /// Initialize a `HmacSha256` state, equivalent to [`HmacSha256::new()`].
+        pub fn init(&self) -> HmacSha256 {
+            self._state.clone()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating a tag of `data`.
+        pub fn hmac(&self, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = self.init();
+            ctx.update(data)?;
+            ctx.finalize()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a tag in constant time.
+        pub fn verify(&self, expected: &Tag, data: &[u8]) -> Result<(), UnknownCryptoError> {
+            if &self.hmac(data)? == expected {
+                Ok(())
+            } else {
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+            }
+        }
+    }
+
     #[cfg(test)]
     mod public {
         use super::*;
 
+        mod test_precomputed_key {
+            use super::*;
+
+            #[test]
+            fn test_init_same_as_new() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+                precomputed
+                    .init()
+                    ._state
+                    .compare_state_to_other(&HmacSha256::new(&sk)._state);
+            }
+
+            #[test]
+            fn test_hmac_verify_same_as_unprecomputed() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+
+                for data in [&b""[..], &b"first"[..], &[0u8; 300][..]].iter() {
+                    let tag = HmacSha256::hmac(&sk, data).unwrap();
+                    assert_eq!(precomputed.hmac(data).unwrap(), tag);
+                    assert!(precomputed.verify(&tag, data).is_ok());
+                    assert!(precomputed.verify(&tag, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_verify_diff_key_err() {
+                let data = b"data";
+                let tag =
+                    HmacSha256::hmac(&SecretKey::from_slice(&[1u8; 32]).unwrap(), data).unwrap();
+                let precomputed = SecretKey::from_slice(&[2u8; 32]).unwrap().precompute();
+                assert!(precomputed.verify(&tag, data).is_err());
+            }
+        }
+
+        #[test]
+        fn test_hmac_vectored() {
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let data = [9u8; 300];
+            let expected = HmacSha256::hmac(&sk, &data).unwrap();
+
+            assert_eq!(
+                HmacSha256::hmac_vectored(&sk, &[&data[..5], &[], &data[5..200], &data[200..]])
+                    .unwrap(),
+                expected
+            );
+            assert_eq!(
+                HmacSha256::hmac_vectored(&sk, &[]).unwrap(),
+                HmacSha256::hmac(&sk, b"").unwrap()
+            );
+        }
+
+        mod test_export_import_state {
+            use super::*;
+
+            #[test]
+            fn test_resume_every_split() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let data = [7u8; 300];
+                let expected = HmacSha256::hmac(&sk, &data).unwrap();
+
+                for split in (0..=data.len()).step_by(7) {
+                    let mut ctx = HmacSha256::new(&sk);
+                    ctx.update(&data[..split]).unwrap();
+                    let mut state = [0u8; HMAC_SHA256_STATESIZE];
+                    ctx.export_state(&mut state).unwrap();
+
+                    let mut resumed = HmacSha256::import_state(&state).unwrap();
+                    resumed._state.compare_state_to_other(&ctx._state);
+                    resumed.update(&data[split..]).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+
+                    // The key schedule is part of the state, so reset() works as well.
+                    resumed.reset();
+                    resumed.update(&data).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+                }
+            }
+
+            #[test]
+            fn test_export_import_errors() {
+                let mut ctx = HmacSha256::new(&SecretKey::from_slice(&[1u8; 32]).unwrap());
+                let mut state = [0u8; HMAC_SHA256_STATESIZE];
+                assert!(ctx
+                    .export_state(&mut state[..HMAC_SHA256_STATESIZE - 1])
+                    .is_err());
+                ctx.export_state(&mut state).unwrap();
+                assert!(HmacSha256::import_state(&state[..HMAC_SHA256_STATESIZE - 1]).is_err());
+
+                let mut bad = state;
+                bad[HMAC_SHA256_STATESIZE - 1] = 1;
+                assert!(HmacSha256::import_state(&bad).is_err());
+
+                let _ = ctx.finalize().unwrap();
+                assert!(ctx.export_state(&mut state).is_err());
+            }
+        }
+
+        mod test_truncated {
+            use super::*;
+
+            #[test]
+            fn test_rfc4231_test_case_5() {
+                let sk = SecretKey::from_slice(&[0x0c; 20]).unwrap();
+                let data = b"Test With Truncation";
+                let expected = hex::decode("a3b6167473100ee06e0c796c2955552b").unwrap();
+
+                let mut ctx = HmacSha256::new(&sk);
+                ctx.update(data).unwrap();
+                let mut tag = [0u8; 16];
+                ctx.finalize_truncated(&mut tag).unwrap();
+                assert_eq!(&tag[..], &expected[..]);
+                assert!(HmacSha256::verify_truncated(&expected, &sk, data).is_ok());
+            }
+
+            #[test]
+            fn test_truncated_is_prefix_of_full() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha256::hmac(&sk, b"data").unwrap();
+
+                for len in MIN_TRUNCATED_TAG_SIZE..=32 {
+                    let mut ctx = HmacSha256::new(&sk);
+                    ctx.update(b"data").unwrap();
+                    let mut tag = [0u8; 32];
+                    ctx.finalize_truncated(&mut tag[..len]).unwrap();
+                    assert_eq!(&tag[..len], &full.unprotected_as_bytes()[..len]);
+                    assert!(HmacSha256::verify_truncated(&tag[..len], &sk, b"data").is_ok());
+                    assert!(HmacSha256::verify_truncated(&tag[..len], &sk, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_length_bounds_err() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha256::hmac(&sk, b"data").unwrap();
+                let full = full.unprotected_as_bytes();
+                let mut too_long = [0u8; 32 + 1];
+                too_long[..32].copy_from_slice(full);
+
+                let mut ctx = HmacSha256::new(&sk);
+                ctx.update(b"data").unwrap();
+                assert!(ctx
+                    .finalize_truncated(&mut [0u8; MIN_TRUNCATED_TAG_SIZE - 1])
+                    .is_err());
+                assert!(ctx.finalize_truncated(&mut too_long).is_err());
+
+                assert!(HmacSha256::verify_truncated(
+                    &full[..MIN_TRUNCATED_TAG_SIZE - 1],
+                    &sk,
+                    b"data"
+                )
+                .is_err());
+                assert!(HmacSha256::verify_truncated(&too_long, &sk, b"data").is_err());
+                assert!(HmacSha256::verify_truncated(&[], &sk, b"data").is_err());
+            }
+        }
+
         #[test]
         #[cfg(feature = "safe_api")]
         fn test_debug_impl() {
@@ -487,6 +828,9 @@ pub mod sha384 {
 
     use super::Hmac;
 
+    /// The size of an HMAC-SHA384 state exported with [`HmacSha384::export_state()`].
+    pub const HMAC_SHA384_STATESIZE: usize = sha384::SHA384_STATESIZE * 3;
+
     #[derive(Clone, Debug)]
     /// HMAC-SHA384 streaming state.
     pub struct HmacSha384 {
@@ -502,6 +846,7 @@ pub mod sha384 {
 
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Initialize `HmacSha384` struct with a given key.
+        #[allow(clippy::unwrap_used)]
         pub fn new(secret_key: &SecretKey) -> Self {
             // NOTE: `secret_key` has been pre-padded so .unwrap() is OK.
             Self::_new(secret_key.unprotected_as_bytes()).unwrap()
@@ -535,6 +880,65 @@ pub mod sha384 {
             Ok(Tag::from(dest))
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Write a HMAC-SHA384 tag, truncated to the length of `dst_out`, to `dst_out`.
+        ///
+        /// An error is returned if `dst_out` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 48 bytes.
+        pub fn finalize_truncated(&mut self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            if dst_out.len() < MIN_TRUNCATED_TAG_SIZE || dst_out.len() > sha384::SHA384_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut tag = [0u8; sha384::SHA384_OUTSIZE];
+            self._finalize_internal(&mut tag)?;
+            dst_out.copy_from_slice(&tag[..dst_out.len()]);
+
+            Ok(())
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a HMAC-SHA384 tag, truncated to the length of `expected`, in constant time.
+        ///
+        /// An error is returned if `expected` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 48 bytes.
+        pub fn verify_truncated(
+            expected: &[u8],
+            secret_key: &SecretKey,
+            data: &[u8],
+        ) -> Result<(), UnknownCryptoError> {
+            if expected.len() < MIN_TRUNCATED_TAG_SIZE || expected.len() > sha384::SHA384_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut ctx = Self::new(secret_key);
+            ctx.update(data)?;
+            let mut tag = [0u8; sha384::SHA384_OUTSIZE];
+            ctx.finalize_truncated(&mut tag[..expected.len()])?;
+
+            crate::util::secure_cmp(&tag[..expected.len()], expected)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Export the state into `dst_out`, so that authentication can be resumed later
+        /// with [`HmacSha384::import_state()`]. The state cannot be exported after
+        /// [`HmacSha384::finalize()`].
+        ///
+        /// # Security:
+        /// The exported state is equivalent to the secret key and must be protected as such.
+        pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            self._state._export_state(dst_out)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Resume authentication from a state exported with [`HmacSha384::export_state()`].
+        /// An error is returned if `state` is not [`HMAC_SHA384_STATESIZE`] bytes or is malformed.
+        pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Hmac::<Sha384, { sha384::SHA384_BLOCKSIZE }>::_import_state(state)?,
+            })
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// One-shot function for generating an HMAC-SHA384 tag of `data`.
         pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
@@ -543,6 +947,19 @@ pub mod sha384 {
             ctx.finalize()
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating an HMAC-SHA384 tag of the concatenation of the slices in `data`.
+        pub fn hmac_vectored(
+            secret_key: &SecretKey,
+            data: &[&[u8]],
+        ) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = Self::new(secret_key);
+            for slice in data.iter() {
+                ctx.update(slice)?;
+            }
+            ctx.finalize()
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Verify a HMAC-SHA384 tag in constant time.
         pub fn verify(
@@ -553,7 +970,7 @@ pub mod sha384 {
             if &Self::hmac(secret_key, data)? == expected {
                 Ok(())
             } else {
-                Err(UnknownCryptoError)
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
             }
         }
     }
@@ -587,10 +1004,214 @@ pub mod sha384 {
         }
     }
 
+    impl SecretKey {
+        /// Precompute the HMAC key schedule for this key.
+        ///
+        /// The returned [`PrecomputedKey`] can produce any number of `HmacSha384`
+        /// states without processing the key again.
+        pub fn precompute(&self) -> PrecomputedKey {
+            PrecomputedKey {
+                _state: HmacSha384::new(self),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    /// A `SecretKey` for which the HMAC key schedule has already been computed.
+    ///
+    /// This is useful when many tags are computed with the same key, such as when
+    /// signing every request to an API.
+    pub struct PrecomputedKey {
+        _state: HmacSha384,
+    }
+
+    impl PrecomputedKey {
+        /// Initialize a `HmacSha384` state, equivalent to [`HmacSha384::new()`].
+        pub fn init(&self) -> HmacSha384 {
+            self._state.clone()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating a tag of `data`.
+        pub fn hmac(&self, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = self.init();
+            ctx.update(data)?;
+            ctx.finalize()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a tag in constant time.
+        pub fn verify(&self, expected: &Tag, data: &[u8]) -> Result<(), UnknownCryptoError> {
+            if &self.hmac(data)? == expected {
+                Ok(())
+            } else {
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+            }
+        }
+    }
+
     #[cfg(test)]
     mod public {
         use super::*;
 
+        mod test_precomputed_key {
+            use super::*;
+
+            #[test]
+            fn test_init_same_as_new() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+                precomputed
+                    .init()
+                    ._state
+                    .compare_state_to_other(&HmacSha384::new(&sk)._state);
+            }
+
+            #[test]
+            fn test_hmac_verify_same_as_unprecomputed() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+
+                for data in [&b""[..], &b"first"[..], &[0u8; 300][..]].iter() {
+                    let tag = HmacSha384::hmac(&sk, data).unwrap();
+                    assert_eq!(precomputed.hmac(data).unwrap(), tag);
+                    assert!(precomputed.verify(&tag, data).is_ok());
+                    assert!(precomputed.verify(&tag, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_verify_diff_key_err() {
+                let data = b"data";
+                let tag =
+                    HmacSha384::hmac(&SecretKey::from_slice(&[1u8; 32]).unwrap(), data).unwrap();
+                let precomputed = SecretKey::from_slice(&[2u8; 32]).unwrap().precompute();
+                assert!(precomputed.verify(&tag, data).is_err());
+            }
+        }
+
+        #[test]
+        fn test_hmac_vectored() {
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let data = [9u8; 300];
+            let expected = HmacSha384::hmac(&sk, &data).unwrap();
+
+            assert_eq!(
+                HmacSha384::hmac_vectored(&sk, &[&data[..5], &[], &data[5..200], &data[200..]])
+                    .unwrap(),
+                expected
+            );
+            assert_eq!(
+                HmacSha384::hmac_vectored(&sk, &[]).unwrap(),
+                HmacSha384::hmac(&sk, b"").unwrap()
+            );
+        }
+
+        mod test_export_import_state {
+            use super::*;
+
+            #[test]
+            fn test_resume_every_split() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let data = [7u8; 300];
+                let expected = HmacSha384::hmac(&sk, &data).unwrap();
+
+                for split in (0..=data.len()).step_by(7) {
+                    let mut ctx = HmacSha384::new(&sk);
+                    ctx.update(&data[..split]).unwrap();
+                    let mut state = [0u8; HMAC_SHA384_STATESIZE];
+                    ctx.export_state(&mut state).unwrap();
+
+                    let mut resumed = HmacSha384::import_state(&state).unwrap();
+                    resumed._state.compare_state_to_other(&ctx._state);
+                    resumed.update(&data[split..]).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+
+                    // The key schedule is part of the state, so reset() works as well.
+                    resumed.reset();
+                    resumed.update(&data).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+                }
+            }
+
+            #[test]
+            fn test_export_import_errors() {
+                let mut ctx = HmacSha384::new(&SecretKey::from_slice(&[1u8; 32]).unwrap());
+                let mut state = [0u8; HMAC_SHA384_STATESIZE];
+                assert!(ctx
+                    .export_state(&mut state[..HMAC_SHA384_STATESIZE - 1])
+                    .is_err());
+                ctx.export_state(&mut state).unwrap();
+                assert!(HmacSha384::import_state(&state[..HMAC_SHA384_STATESIZE - 1]).is_err());
+
+                let mut bad = state;
+                bad[HMAC_SHA384_STATESIZE - 1] = 1;
+                assert!(HmacSha384::import_state(&bad).is_err());
+
+                let _ = ctx.finalize().unwrap();
+                assert!(ctx.export_state(&mut state).is_err());
+            }
+        }
+
+        mod test_truncated {
+            use super::*;
+
+            #[test]
+            fn test_rfc4231_test_case_5() {
+                let sk = SecretKey::from_slice(&[0x0c; 20]).unwrap();
+                let data = b"Test With Truncation";
+                let expected = hex::decode("3abf34c3503b2a23a46efc619baef897").unwrap();
+
+                let mut ctx = HmacSha384::new(&sk);
+                ctx.update(data).unwrap();
+                let mut tag = [0u8; 16];
+                ctx.finalize_truncated(&mut tag).unwrap();
+                assert_eq!(&tag[..], &expected[..]);
+                assert!(HmacSha384::verify_truncated(&expected, &sk, data).is_ok());
+            }
+
+            #[test]
+            fn test_truncated_is_prefix_of_full() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha384::hmac(&sk, b"data").unwrap();
+
+                for len in MIN_TRUNCATED_TAG_SIZE..=48 {
+                    let mut ctx = HmacSha384::new(&sk);
+                    ctx.update(b"data").unwrap();
+                    let mut tag = [0u8; 48];
+                    ctx.finalize_truncated(&mut tag[..len]).unwrap();
+                    assert_eq!(&tag[..len], &full.unprotected_as_bytes()[..len]);
+                    assert!(HmacSha384::verify_truncated(&tag[..len], &sk, b"data").is_ok());
+                    assert!(HmacSha384::verify_truncated(&tag[..len], &sk, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_length_bounds_err() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha384::hmac(&sk, b"data").unwrap();
+                let full = full.unprotected_as_bytes();
+                let mut too_long = [0u8; 48 + 1];
+                too_long[..48].copy_from_slice(full);
+
+                let mut ctx = HmacSha384::new(&sk);
+                ctx.update(b"data").unwrap();
+                assert!(ctx
+                    .finalize_truncated(&mut [0u8; MIN_TRUNCATED_TAG_SIZE - 1])
+                    .is_err());
+                assert!(ctx.finalize_truncated(&mut too_long).is_err());
+
+                assert!(HmacSha384::verify_truncated(
+                    &full[..MIN_TRUNCATED_TAG_SIZE - 1],
+                    &sk,
+                    b"data"
+                )
+                .is_err());
+                assert!(HmacSha384::verify_truncated(&too_long, &sk, b"data").is_err());
+                assert!(HmacSha384::verify_truncated(&[], &sk, b"data").is_err());
+            }
+        }
+
         #[test]
         #[cfg(feature = "safe_api")]
         fn test_debug_impl() {
@@ -717,6 +1338,9 @@ pub mod sha512 {
 
     use super::Hmac;
 
+    /// The size of an HMAC-SHA512 state exported with [`HmacSha512::export_state()`].
+    pub const HMAC_SHA512_STATESIZE: usize = sha512::SHA512_STATESIZE * 3;
+
     #[derive(Clone, Debug)]
     /// HMAC-SHA512 streaming state.
     pub struct HmacSha512 {
@@ -732,6 +1356,7 @@ pub mod sha512 {
 
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Initialize `HmacSha512` struct with a given key.
+        #[allow(clippy::unwrap_used)]
         pub fn new(secret_key: &SecretKey) -> Self {
             // NOTE: `secret_key` has been pre-padded so .unwrap() is OK.
             Self::_new(secret_key.unprotected_as_bytes()).unwrap()
@@ -765,6 +1390,65 @@ pub mod sha512 {
             Ok(Tag::from(dest))
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Write a HMAC-SHA512 tag, truncated to the length of `dst_out`, to `dst_out`.
+        ///
+        /// An error is returned if `dst_out` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 64 bytes.
+        pub fn finalize_truncated(&mut self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            if dst_out.len() < MIN_TRUNCATED_TAG_SIZE || dst_out.len() > sha512::SHA512_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut tag = [0u8; sha512::SHA512_OUTSIZE];
+            self._finalize_internal(&mut tag)?;
+            dst_out.copy_from_slice(&tag[..dst_out.len()]);
+
+            Ok(())
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a HMAC-SHA512 tag, truncated to the length of `expected`, in constant time.
+        ///
+        /// An error is returned if `expected` is less than [`MIN_TRUNCATED_TAG_SIZE`] or greater
+        /// than 64 bytes.
+        pub fn verify_truncated(
+            expected: &[u8],
+            secret_key: &SecretKey,
+            data: &[u8],
+        ) -> Result<(), UnknownCryptoError> {
+            if expected.len() < MIN_TRUNCATED_TAG_SIZE || expected.len() > sha512::SHA512_OUTSIZE {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+            }
+
+            let mut ctx = Self::new(secret_key);
+            ctx.update(data)?;
+            let mut tag = [0u8; sha512::SHA512_OUTSIZE];
+            ctx.finalize_truncated(&mut tag[..expected.len()])?;
+
+            crate::util::secure_cmp(&tag[..expected.len()], expected)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Export the state into `dst_out`, so that authentication can be resumed later
+        /// with [`HmacSha512::import_state()`]. The state cannot be exported after
+        /// [`HmacSha512::finalize()`].
+        ///
+        /// # Security:
+        /// The exported state is equivalent to the secret key and must be protected as such.
+        pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+            self._state._export_state(dst_out)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Resume authentication from a state exported with [`HmacSha512::export_state()`].
+        /// An error is returned if `state` is not [`HMAC_SHA512_STATESIZE`] bytes or is malformed.
+        pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Hmac::<Sha512, { sha512::SHA512_BLOCKSIZE }>::_import_state(state)?,
+            })
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// One-shot function for generating an HMAC-SHA512 tag of `data`.
         pub fn hmac(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
@@ -773,6 +1457,19 @@ pub mod sha512 {
             ctx.finalize()
         }
 
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating an HMAC-SHA512 tag of the concatenation of the slices in `data`.
+        pub fn hmac_vectored(
+            secret_key: &SecretKey,
+            data: &[&[u8]],
+        ) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = Self::new(secret_key);
+            for slice in data.iter() {
+                ctx.update(slice)?;
+            }
+            ctx.finalize()
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Verify a HMAC-SHA512 tag in constant time.
         pub fn verify(
@@ -783,7 +1480,7 @@ pub mod sha512 {
             if &Self::hmac(secret_key, data)? == expected {
                 Ok(())
             } else {
-                Err(UnknownCryptoError)
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
             }
         }
     }
@@ -817,10 +1514,214 @@ pub mod sha512 {
         }
     }
 
+    impl SecretKey {
+        /// Precompute the HMAC key schedule for this key.
+        ///
+        /// The returned [`PrecomputedKey`] can produce any number of `HmacSha512`
+        /// states without processing the key again.
+        pub fn precompute(&self) -> PrecomputedKey {
+            PrecomputedKey {
+                _state: HmacSha512::new(self),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    /// A `SecretKey` for which the HMAC key schedule has already been computed.
+    ///
+    /// This is useful when many tags are computed with the same key, such as when
+    /// signing every request to an API.
+    pub struct PrecomputedKey {
+        _state: HmacSha512,
+    }
+
+    impl PrecomputedKey {
+        /// Initialize a `HmacSha512` state, equivalent to [`HmacSha512::new()`].
+        pub fn init(&self) -> HmacSha512 {
+            self._state.clone()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// One-shot function for generating a tag of `data`.
+        pub fn hmac(&self, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
+            let mut ctx = self.init();
+            ctx.update(data)?;
+            ctx.finalize()
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Verify a tag in constant time.
+        pub fn verify(&self, expected: &Tag, data: &[u8]) -> Result<(), UnknownCryptoError> {
+            if &self.hmac(data)? == expected {
+                Ok(())
+            } else {
+                Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+            }
+        }
+    }
+
     #[cfg(test)]
     mod public {
         use super::*;
 
+        mod test_precomputed_key {
+            use super::*;
+
+            #[test]
+            fn test_init_same_as_new() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+                precomputed
+                    .init()
+                    ._state
+                    .compare_state_to_other(&HmacSha512::new(&sk)._state);
+            }
+
+            #[test]
+            fn test_hmac_verify_same_as_unprecomputed() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let precomputed = sk.precompute();
+
+                for data in [&b""[..], &b"first"[..], &[0u8; 300][..]].iter() {
+                    let tag = HmacSha512::hmac(&sk, data).unwrap();
+                    assert_eq!(precomputed.hmac(data).unwrap(), tag);
+                    assert!(precomputed.verify(&tag, data).is_ok());
+                    assert!(precomputed.verify(&tag, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_verify_diff_key_err() {
+                let data = b"data";
+                let tag =
+                    HmacSha512::hmac(&SecretKey::from_slice(&[1u8; 32]).unwrap(), data).unwrap();
+                let precomputed = SecretKey::from_slice(&[2u8; 32]).unwrap().precompute();
+                assert!(precomputed.verify(&tag, data).is_err());
+            }
+        }
+
+        #[test]
+        fn test_hmac_vectored() {
+            let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let data = [9u8; 300];
+            let expected = HmacSha512::hmac(&sk, &data).unwrap();
+
+            assert_eq!(
+                HmacSha512::hmac_vectored(&sk, &[&data[..5], &[], &data[5..200], &data[200..]])
+                    .unwrap(),
+                expected
+            );
+            assert_eq!(
+                HmacSha512::hmac_vectored(&sk, &[]).unwrap(),
+                HmacSha512::hmac(&sk, b"").unwrap()
+            );
+        }
+
+        mod test_export_import_state {
+            use super::*;
+
+            #[test]
+            fn test_resume_every_split() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let data = [7u8; 300];
+                let expected = HmacSha512::hmac(&sk, &data).unwrap();
+
+                for split in (0..=data.len()).step_by(7) {
+                    let mut ctx = HmacSha512::new(&sk);
+                    ctx.update(&data[..split]).unwrap();
+                    let mut state = [0u8; HMAC_SHA512_STATESIZE];
+                    ctx.export_state(&mut state).unwrap();
+
+                    let mut resumed = HmacSha512::import_state(&state).unwrap();
+                    resumed._state.compare_state_to_other(&ctx._state);
+                    resumed.update(&data[split..]).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+
+                    // The key schedule is part of the state, so reset() works as well.
+                    resumed.reset();
+                    resumed.update(&data).unwrap();
+                    assert_eq!(resumed.finalize().unwrap(), expected);
+                }
+            }
+
+            #[test]
+            fn test_export_import_errors() {
+                let mut ctx = HmacSha512::new(&SecretKey::from_slice(&[1u8; 32]).unwrap());
+                let mut state = [0u8; HMAC_SHA512_STATESIZE];
+                assert!(ctx
+                    .export_state(&mut state[..HMAC_SHA512_STATESIZE - 1])
+                    .is_err());
+                ctx.export_state(&mut state).unwrap();
+                assert!(HmacSha512::import_state(&state[..HMAC_SHA512_STATESIZE - 1]).is_err());
+
+                let mut bad = state;
+                bad[HMAC_SHA512_STATESIZE - 1] = 1;
+                assert!(HmacSha512::import_state(&bad).is_err());
+
+                let _ = ctx.finalize().unwrap();
+                assert!(ctx.export_state(&mut state).is_err());
+            }
+        }
+
+        mod test_truncated {
+            use super::*;
+
+            #[test]
+            fn test_rfc4231_test_case_5() {
+                let sk = SecretKey::from_slice(&[0x0c; 20]).unwrap();
+                let data = b"Test With Truncation";
+                let expected = hex::decode("415fad6271580a531d4179bc891d87a6").unwrap();
+
+                let mut ctx = HmacSha512::new(&sk);
+                ctx.update(data).unwrap();
+                let mut tag = [0u8; 16];
+                ctx.finalize_truncated(&mut tag).unwrap();
+                assert_eq!(&tag[..], &expected[..]);
+                assert!(HmacSha512::verify_truncated(&expected, &sk, data).is_ok());
+            }
+
+            #[test]
+            fn test_truncated_is_prefix_of_full() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha512::hmac(&sk, b"data").unwrap();
+
+                for len in MIN_TRUNCATED_TAG_SIZE..=64 {
+                    let mut ctx = HmacSha512::new(&sk);
+                    ctx.update(b"data").unwrap();
+                    let mut tag = [0u8; 64];
+                    ctx.finalize_truncated(&mut tag[..len]).unwrap();
+                    assert_eq!(&tag[..len], &full.unprotected_as_bytes()[..len]);
+                    assert!(HmacSha512::verify_truncated(&tag[..len], &sk, b"data").is_ok());
+                    assert!(HmacSha512::verify_truncated(&tag[..len], &sk, b"other").is_err());
+                }
+            }
+
+            #[test]
+            fn test_length_bounds_err() {
+                let sk = SecretKey::from_slice(&[1u8; 32]).unwrap();
+                let full = HmacSha512::hmac(&sk, b"data").unwrap();
+                let full = full.unprotected_as_bytes();
+                let mut too_long = [0u8; 64 + 1];
+                too_long[..64].copy_from_slice(full);
+
+                let mut ctx = HmacSha512::new(&sk);
+                ctx.update(b"data").unwrap();
+                assert!(ctx
+                    .finalize_truncated(&mut [0u8; MIN_TRUNCATED_TAG_SIZE - 1])
+                    .is_err());
+                assert!(ctx.finalize_truncated(&mut too_long).is_err());
+
+                assert!(HmacSha512::verify_truncated(
+                    &full[..MIN_TRUNCATED_TAG_SIZE - 1],
+                    &sk,
+                    b"data"
+                )
+                .is_err());
+                assert!(HmacSha512::verify_truncated(&too_long, &sk, b"data").is_err());
+                assert!(HmacSha512::verify_truncated(&[], &sk, b"data").is_err());
+            }
+        }
+
         #[test]
         #[cfg(feature = "safe_api")]
         fn test_debug_impl() {
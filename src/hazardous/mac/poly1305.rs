@@ -22,7 +22,7 @@
 // SOFTWARE.
 
 //! # About:
-//! This implementation is based on [poly1305-donna] by Andrew Moon.
+//! This implementation is based on the 64-bit variant of [poly1305-donna] by Andrew Moon.
 //!
 //! # Parameters:
 //! - `data`: Data to be authenticated.
@@ -70,8 +70,8 @@
 //! [Cryptographic Right Answers]: https://latacora.micro.blog/2018/04/03/cryptographic-right-answers.html
 
 use crate::{
-    errors::UnknownCryptoError,
-    util::endianness::{load_u32_le, store_u32_into_le},
+    errors::{ErrorKind, UnknownCryptoError},
+    util::endianness::{load_u64_into_le, store_u64_into_le},
 };
 
 /// The blocksize which Poly1305 operates on.
@@ -112,9 +112,9 @@ impl_from_trait!(Tag, POLY1305_OUTSIZE);
 #[derive(Clone)]
 /// Poly1305 streaming state.
 pub struct Poly1305 {
-    a: [u32; 5],
-    r: [u32; 5],
-    s: [u32; 4],
+    a: [u64; 3],
+    r: [u64; 3],
+    s: [u64; 2],
     leftover: usize,
     buffer: [u8; POLY1305_BLOCKSIZE],
     is_finalized: bool,
@@ -140,94 +140,78 @@ impl core::fmt::Debug for Poly1305 {
     }
 }
 
+/// Mask for a 44-bit limb.
+const MASK_44: u64 = 0xfffffffffff;
+/// Mask for a 42-bit limb.
+const MASK_42: u64 = 0x3ffffffffff;
+
 impl Poly1305 {
     #[rustfmt::skip]
     #[allow(clippy::cast_lossless)]
     #[allow(clippy::identity_op)]
     #[allow(clippy::unreadable_literal)]
     #[allow(clippy::assign_op_pattern)]
-    /// Process a datablock of `POLY1305_BLOCKSIZE` length.
-    fn process_block(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
-        if data.len() != POLY1305_BLOCKSIZE {
-            return Err(UnknownCryptoError);
+    /// Process one or more datablocks. `data` must be a non-zero multiple of
+    /// `POLY1305_BLOCKSIZE` in length.
+    ///
+    /// The accumulator is kept in registers for the whole of `data` and only
+    /// written back to the state once every block has been absorbed.
+    fn process_blocks(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        if data.is_empty() || !data.chunks_exact(POLY1305_BLOCKSIZE).remainder().is_empty() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
-        let hibit: u32 = if self.is_finalized {
+        let hibit: u64 = if self.is_finalized {
             0
         } else {
-            1 << 24
+            1 << 40
         };
 
-        let r0: u32 = self.r[0];
-        let r1: u32 = self.r[1];
-        let r2: u32 = self.r[2];
-        let r3: u32 = self.r[3];
-        let r4: u32 = self.r[4];
-
-        let s1: u32 = r1 * 5;
-        let s2: u32 = r2 * 5;
-        let s3: u32 = r3 * 5;
-        let s4: u32 = r4 * 5;
-
-        let mut h0: u32 = self.a[0];
-        let mut h1: u32 = self.a[1];
-        let mut h2: u32 = self.a[2];
-        let mut h3: u32 = self.a[3];
-        let mut h4: u32 = self.a[4];
-
-        // h += m[i]
-        h0 += (load_u32_le(&data[0..4])) & 0x3ffffff;
-        h1 += (load_u32_le(&data[3..7]) >> 2) & 0x3ffffff;
-        h2 += (load_u32_le(&data[6..10]) >> 4) & 0x3ffffff;
-        h3 += (load_u32_le(&data[9..13]) >> 6) & 0x3ffffff;
-        h4 += (load_u32_le(&data[12..16]) >> 8) | hibit;
-
-        // h *= r
-        let d0: u64 =
-            (h0 as u64 * r0 as u64) +
-            (h1 as u64 * s4 as u64) +
-            (h2 as u64 * s3 as u64) +
-            (h3 as u64 * s2 as u64) +
-            (h4 as u64 * s1 as u64);
-        let mut d1: u64 =
-            (h0 as u64 * r1 as u64) +
-            (h1 as u64 * r0 as u64) +
-            (h2 as u64 * s4 as u64) +
-            (h3 as u64 * s3 as u64) +
-            (h4 as u64 * s2 as u64);
-        let mut d2: u64 =
-            (h0 as u64 * r2 as u64) +
-            (h1 as u64 * r1 as u64) +
-            (h2 as u64 * r0 as u64) +
-            (h3 as u64 * s4 as u64) +
-            (h4 as u64 * s3 as u64);
-        let mut d3: u64 =
-            (h0 as u64 * r3 as u64) +
-            (h1 as u64 * r2 as u64) +
-            (h2 as u64 * r1 as u64) +
-            (h3 as u64 * r0 as u64) +
-            (h4 as u64 * s4 as u64);
-        let mut d4: u64 =
-            (h0 as u64 * r4 as u64) +
-            (h1 as u64 * r3 as u64) +
-            (h2 as u64 * r2 as u64) +
-            (h3 as u64 * r1 as u64) +
-            (h4 as u64 * r0 as u64);
-
-        // (partial) h %= p
-        let mut c: u32 = (d0 >> 26) as u32; h0 = (d0 & 0x3ffffff) as u32;
-        d1 += c as u64; c = (d1 >> 26) as u32; h1 = (d1 & 0x3ffffff) as u32;
-        d2 += c as u64; c = (d2 >> 26) as u32; h2 = (d2 & 0x3ffffff) as u32;
-        d3 += c as u64; c = (d3 >> 26) as u32; h3 = (d3 & 0x3ffffff) as u32;
-        d4 += c as u64; c = (d4 >> 26) as u32; h4 = (d4 & 0x3ffffff) as u32;
-        h0 += c * 5; c = h0 >> 26; h0 = h0 & 0x3ffffff;
-        h1 += c;
+        let r0: u64 = self.r[0];
+        let r1: u64 = self.r[1];
+        let r2: u64 = self.r[2];
+
+        let s1: u64 = r1 * (5 << 2);
+        let s2: u64 = r2 * (5 << 2);
+
+        let mut h0: u64 = self.a[0];
+        let mut h1: u64 = self.a[1];
+        let mut h2: u64 = self.a[2];
+
+        let mut t = [0u64; 2];
+        for block in data.chunks_exact(POLY1305_BLOCKSIZE) {
+            load_u64_into_le(block, &mut t);
+
+            // h += m[i]
+            h0 += t[0] & MASK_44;
+            h1 += ((t[0] >> 44) | (t[1] << 20)) & MASK_44;
+            h2 += ((t[1] >> 24) & MASK_42) | hibit;
+
+            // h *= r
+            let d0: u128 =
+                (h0 as u128 * r0 as u128) +
+                (h1 as u128 * s2 as u128) +
+                (h2 as u128 * s1 as u128);
+            let mut d1: u128 =
+                (h0 as u128 * r1 as u128) +
+                (h1 as u128 * r0 as u128) +
+                (h2 as u128 * s2 as u128);
+            let mut d2: u128 =
+                (h0 as u128 * r2 as u128) +
+                (h1 as u128 * r1 as u128) +
+                (h2 as u128 * r0 as u128);
+
+            // (partial) h %= p
+            let mut c: u64 = (d0 >> 44) as u64; h0 = (d0 as u64) & MASK_44;
+            d1 += c as u128; c = (d1 >> 44) as u64; h1 = (d1 as u64) & MASK_44;
+            d2 += c as u128; c = (d2 >> 42) as u64; h2 = (d2 as u64) & MASK_42;
+            h0 += c * 5; c = h0 >> 44; h0 = h0 & MASK_44;
+            h1 += c;
+        }
 
         self.a[0] = h0;
         self.a[1] = h1;
         self.a[2] = h2;
-        self.a[3] = h3;
-        self.a[4] = h4;
 
         Ok(())
     }
@@ -240,81 +224,71 @@ impl Poly1305 {
     /// Remaining processing after all data blocks have been processed.
     fn process_end_of_stream(&mut self) {
         // full carry h
-        let mut h0: u32 = self.a[0];
-        let mut h1: u32 = self.a[1];
-        let mut h2: u32 = self.a[2];
-        let mut h3: u32 = self.a[3];
-        let mut h4: u32 = self.a[4];
-
-        let mut c: u32 = h1 >> 26; h1 = h1 & 0x3ffffff;
-        h2 += c; c = h2 >> 26; h2 = h2 & 0x3ffffff;
-        h3 += c; c = h3 >> 26; h3 = h3 & 0x3ffffff;
-        h4 += c; c = h4 >> 26; h4 = h4 & 0x3ffffff;
-        h0 += c * 5; c = h0 >> 26; h0 = h0 & 0x3ffffff;
+        let mut h0: u64 = self.a[0];
+        let mut h1: u64 = self.a[1];
+        let mut h2: u64 = self.a[2];
+
+        let mut c: u64 = h1 >> 44; h1 = h1 & MASK_44;
+        h2 += c; c = h2 >> 42; h2 = h2 & MASK_42;
+        h0 += c * 5; c = h0 >> 44; h0 = h0 & MASK_44;
+        h1 += c; c = h1 >> 44; h1 = h1 & MASK_44;
+        h2 += c; c = h2 >> 42; h2 = h2 & MASK_42;
+        h0 += c * 5; c = h0 >> 44; h0 = h0 & MASK_44;
         h1 += c;
 
         // compute h + -p
-        let mut g0: u32 = h0.wrapping_add(5); c = g0 >> 26; g0 &= 0x3ffffff;
-        let mut g1: u32 = h1.wrapping_add(c); c = g1 >> 26; g1 &= 0x3ffffff;
-        let mut g2: u32 = h2.wrapping_add(c); c = g2 >> 26; g2 &= 0x3ffffff;
-        let mut g3: u32 = h3.wrapping_add(c); c = g3 >> 26; g3 &= 0x3ffffff;
-        let mut g4: u32 = h4.wrapping_add(c).wrapping_sub(1 << 26);
+        let mut g0: u64 = h0.wrapping_add(5); c = g0 >> 44; g0 &= MASK_44;
+        let mut g1: u64 = h1.wrapping_add(c); c = g1 >> 44; g1 &= MASK_44;
+        let mut g2: u64 = h2.wrapping_add(c).wrapping_sub(1 << 42);
 
         // select h if h < p, or h + -p if h >= p
-        let mut mask = (g4 >> (32 - 1)).wrapping_sub(1);
+        let mut mask = (g2 >> (64 - 1)).wrapping_sub(1);
         g0 &= mask;
         g1 &= mask;
         g2 &= mask;
-        g3 &= mask;
-        g4 &= mask;
         mask = !mask;
         h0 = (h0 & mask) | g0;
-    	h1 = (h1 & mask) | g1;
-    	h2 = (h2 & mask) | g2;
-    	h3 = (h3 & mask) | g3;
-    	h4 = (h4 & mask) | g4;
-
-        // h = h % (2^128)
-        h0 = ((h0) | (h1 << 26)) & 0xffffffff;
-        h1 = ((h1 >> 6) | (h2 << 20)) & 0xffffffff;
-        h2 = ((h2 >> 12) | (h3 << 14)) & 0xffffffff;
-        h3 = ((h3 >> 18) | (h4 << 8)) & 0xffffffff;
+        h1 = (h1 & mask) | g1;
+        h2 = (h2 & mask) | g2;
 
         // mac = (h + pad) % (2^128)
-        let mut f: u64 = (h0 as u64) + (self.s[0] as u64); h0 = f as u32;
-        f = (h1 as u64) + (self.s[1] as u64) + (f >> 32); h1 = f as u32;
-        f = (h2 as u64) + (self.s[2] as u64) + (f >> 32); h2 = f as u32;
-        f = (h3 as u64) + (self.s[3] as u64) + (f >> 32); h3 = f as u32;
+        let t0: u64 = self.s[0];
+        let t1: u64 = self.s[1];
+
+        h0 += t0 & MASK_44; c = h0 >> 44; h0 = h0 & MASK_44;
+        h1 += (((t0 >> 44) | (t1 << 20)) & MASK_44) + c; c = h1 >> 44; h1 = h1 & MASK_44;
+        h2 += ((t1 >> 24) & MASK_42) + c; h2 = h2 & MASK_42;
+
+        // h = h % (2^128)
+        h0 = h0 | (h1 << 44);
+        h1 = (h1 >> 20) | (h2 << 24);
 
         // Set self.a to MAC result
         self.a[0] = h0;
         self.a[1] = h1;
-        self.a[2] = h2;
-        self.a[3] = h3;
+        self.a[2] = 0;
     }
 
     #[allow(clippy::unreadable_literal)]
     /// Initialize a `Poly1305` struct with a given one-time key.
     pub fn new(one_time_key: &OneTimeKey) -> Self {
         let mut state = Self {
-            a: [0u32; 5],
-            r: [0u32; 5],
-            s: [0u32; 4],
+            a: [0u64; 3],
+            r: [0u64; 3],
+            s: [0u64; 2],
             leftover: 0,
             buffer: [0u8; POLY1305_BLOCKSIZE],
             is_finalized: false,
         };
 
-        state.r[0] = (load_u32_le(&one_time_key.unprotected_as_bytes()[0..4])) & 0x3ffffff;
-        state.r[1] = (load_u32_le(&one_time_key.unprotected_as_bytes()[3..7]) >> 2) & 0x3ffff03;
-        state.r[2] = (load_u32_le(&one_time_key.unprotected_as_bytes()[6..10]) >> 4) & 0x3ffc0ff;
-        state.r[3] = (load_u32_le(&one_time_key.unprotected_as_bytes()[9..13]) >> 6) & 0x3f03fff;
-        state.r[4] = (load_u32_le(&one_time_key.unprotected_as_bytes()[12..16]) >> 8) & 0x00fffff;
+        let mut t = [0u64; 2];
+        load_u64_into_le(&one_time_key.unprotected_as_bytes()[0..16], &mut t);
+
+        state.r[0] = t[0] & 0xffc0fffffff;
+        state.r[1] = ((t[0] >> 44) | (t[1] << 20)) & 0xfffffc0ffff;
+        state.r[2] = (t[1] >> 24) & 0x00ffffffc0f;
 
-        state.s[0] = load_u32_le(&one_time_key.unprotected_as_bytes()[16..20]);
-        state.s[1] = load_u32_le(&one_time_key.unprotected_as_bytes()[20..24]);
-        state.s[2] = load_u32_le(&one_time_key.unprotected_as_bytes()[24..28]);
-        state.s[3] = load_u32_le(&one_time_key.unprotected_as_bytes()[28..32]);
+        load_u64_into_le(&one_time_key.unprotected_as_bytes()[16..32], &mut state.s);
 
         state
     }
@@ -326,22 +300,22 @@ impl Poly1305 {
         data: &[u8],
     ) -> Result<(), UnknownCryptoError> {
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
         if data.is_empty() {
             return Ok(());
         }
 
-        let mut blocksize_iter = data.chunks_exact(POLY1305_BLOCKSIZE);
-        for block in &mut blocksize_iter {
-            self.process_block(block).unwrap();
+        let full_len = data.len() - (data.len() % POLY1305_BLOCKSIZE);
+        if full_len > 0 {
+            self.process_blocks(&data[..full_len])?;
         }
 
-        let remaining = blocksize_iter.remainder();
+        let remaining = &data[full_len..];
         if !remaining.is_empty() {
             let mut pad = [0u8; POLY1305_BLOCKSIZE];
             pad[..remaining.len()].copy_from_slice(remaining);
-            self.process_block(&pad).unwrap();
+            self.process_blocks(&pad)?;
         }
 
         Ok(())
@@ -349,7 +323,7 @@ impl Poly1305 {
 
     /// Reset to `new()` state.
     pub fn reset(&mut self) {
-        self.a = [0u32; 5];
+        self.a = [0u64; 3];
         self.leftover = 0;
         self.is_finalized = false;
         self.buffer = [0u8; POLY1305_BLOCKSIZE];
@@ -359,7 +333,7 @@ impl Poly1305 {
     /// Update state with `data`. This can be called multiple times.
     pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
         if data.is_empty() {
             return Ok(());
@@ -386,13 +360,14 @@ impl Poly1305 {
             }
 
             let tmp = self.buffer;
-            self.process_block(&tmp)?;
+            self.process_blocks(&tmp)?;
             self.leftover = 0;
         }
 
-        while bytes.len() >= POLY1305_BLOCKSIZE {
-            self.process_block(&bytes[0..POLY1305_BLOCKSIZE])?;
-            bytes = &bytes[POLY1305_BLOCKSIZE..];
+        let full_len = bytes.len() - (bytes.len() % POLY1305_BLOCKSIZE);
+        if full_len > 0 {
+            self.process_blocks(&bytes[..full_len])?;
+            bytes = &bytes[full_len..];
         }
 
         self.buffer[..bytes.len()].copy_from_slice(&bytes);
@@ -405,7 +380,7 @@ impl Poly1305 {
     /// Return a Poly1305 tag.
     pub fn finalize(&mut self) -> Result<Tag, UnknownCryptoError> {
         if self.is_finalized {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
         }
 
         self.is_finalized = true;
@@ -423,11 +398,11 @@ impl Poly1305 {
                 *buf_itm = 0u8;
             }
 
-            self.process_block(&local_buffer)?;
+            self.process_blocks(&local_buffer)?;
         }
 
         self.process_end_of_stream();
-        store_u32_into_le(&self.a[0..4], &mut local_buffer);
+        store_u64_into_le(&self.a[0..2], &mut local_buffer);
 
         Ok(Tag::from(local_buffer))
     }
@@ -440,6 +415,19 @@ impl Poly1305 {
         poly_1305_state.finalize()
     }
 
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// One-shot function for generating a Poly1305 tag of the concatenation of the slices in `data`.
+    pub fn poly1305_vectored(
+        one_time_key: &OneTimeKey,
+        data: &[&[u8]],
+    ) -> Result<Tag, UnknownCryptoError> {
+        let mut poly_1305_state = Self::new(one_time_key);
+        for slice in data.iter() {
+            poly_1305_state.update(slice)?;
+        }
+        poly_1305_state.finalize()
+    }
+
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
     /// Verify a Poly1305 tag in constant time.
     pub fn verify(
@@ -450,7 +438,7 @@ impl Poly1305 {
         if &Self::poly1305(one_time_key, data)? == expected {
             Ok(())
         } else {
-            Err(UnknownCryptoError)
+            Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
         }
     }
 }
@@ -489,6 +477,27 @@ mod public {
         }
     }
 
+    #[test]
+    fn test_poly1305_vectored() {
+        let sk = OneTimeKey::from_slice(&[3u8; 32]).unwrap();
+        let data = [7u8; 100];
+        let expected = Poly1305::poly1305(&sk, &data).unwrap();
+
+        assert_eq!(
+            Poly1305::poly1305_vectored(&sk, &[&data[..1], &[], &data[1..17], &data[17..]])
+                .unwrap(),
+            expected
+        );
+        assert_eq!(
+            Poly1305::poly1305_vectored(&sk, &[&data]).unwrap(),
+            expected
+        );
+        assert_eq!(
+            Poly1305::poly1305_vectored(&sk, &[]).unwrap(),
+            Poly1305::poly1305(&sk, b"").unwrap()
+        );
+    }
+
     mod test_streaming_interface {
         use super::*;
         use crate::test_framework::incremental_interface::{
@@ -595,23 +604,43 @@ mod private {
         }
     }
 
-    mod test_process_block {
+    mod test_process_blocks {
         use super::*;
 
         #[test]
-        fn test_process_block_len() {
+        fn test_process_blocks_len() {
             let block_0 = [0u8; 0];
             let block_1 = [0u8; 15];
             let block_2 = [0u8; 17];
             let block_3 = [0u8; 16];
+            let block_4 = [0u8; 33];
+            let block_5 = [0u8; 64];
 
             let sk = OneTimeKey::from_slice(&[0u8; 32]).unwrap();
             let mut state = Poly1305::new(&sk);
 
-            assert!(state.process_block(&block_0).is_err());
-            assert!(state.process_block(&block_1).is_err());
-            assert!(state.process_block(&block_2).is_err());
-            assert!(state.process_block(&block_3).is_ok());
+            assert!(state.process_blocks(&block_0).is_err());
+            assert!(state.process_blocks(&block_1).is_err());
+            assert!(state.process_blocks(&block_2).is_err());
+            assert!(state.process_blocks(&block_3).is_ok());
+            assert!(state.process_blocks(&block_4).is_err());
+            assert!(state.process_blocks(&block_5).is_ok());
+        }
+
+        #[test]
+        fn test_process_blocks_eq_one_by_one() {
+            let data = [7u8; 16 * 5];
+            let sk = OneTimeKey::from_slice(&[24u8; 32]).unwrap();
+
+            let mut state_multi = Poly1305::new(&sk);
+            state_multi.process_blocks(&data).unwrap();
+
+            let mut state_single = Poly1305::new(&sk);
+            for block in data.chunks_exact(POLY1305_BLOCKSIZE) {
+                state_single.process_blocks(block).unwrap();
+            }
+
+            assert_eq!(state_multi.a, state_single.a);
         }
     }
 
@@ -629,7 +658,7 @@ mod private {
             state.process_end_of_stream();
 
             let mut state = Poly1305::new(&sk);
-            state.process_block(&block).unwrap();
+            state.process_blocks(&block).unwrap();
             // Should not panic
             state.process_end_of_stream();
             state.reset();
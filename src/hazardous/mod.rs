@@ -41,3 +41,26 @@ pub mod kdf;
 
 /// Stream ciphers.
 pub mod stream;
+
+/// DRBGs (Deterministic Random Bit Generator).
+pub mod rng;
+
+/// Key wrapping.
+pub mod kw;
+
+/// Elliptic-curve cryptography.
+pub mod ecc;
+
+/// KEMs (Key Encapsulation Mechanism).
+pub mod kem;
+
+/// Digital signatures.
+pub mod sig;
+
+#[cfg(feature = "getrandom")]
+/// The Double Ratchet, for encrypted messaging sessions.
+pub mod ratchet;
+
+#[cfg(feature = "getrandom")]
+/// An X3DH-style key agreement, for starting a [`ratchet`] session.
+pub mod x3dh;
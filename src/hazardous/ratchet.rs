@@ -0,0 +1,837 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! A Double Ratchet, following the design of the [Signal specification], for sending encrypted
+//! messages between two parties that have agreed on a [`SharedSecret`], such as with
+//! [`x3dh`](super::x3dh).
+//!
+//! - The symmetric-key ratchet derives a new message key for every message, with a KDF chain
+//!   of HMAC-SHA256.
+//! - The DH ratchet replaces the sending and receiving chains with X25519 and HKDF-SHA256,
+//!   whenever a message with a new ratchet public key of the other party is received.
+//! - Messages are encrypted with ChaCha20Poly1305, using a key and nonce derived from the
+//!   message key with HKDF-SHA256. The [`HEADER_SIZE`] byte header of a message is sent in
+//!   plaintext, in front of the ciphertext, and is authenticated as additional data.
+//! - Up to [`MAX_SKIP`] message keys of messages that have not arrived yet are stored, so that
+//!   messages can be decrypted out of order. When more keys are stored, the oldest is discarded.
+//! - The whole state can be exported with [`Session::export_state()`], and imported again with
+//!   [`Session::import_state()`].
+//!
+//! The initiator, usually the party that sent the first message of the key agreement, creates
+//! its session with [`Session::initiate()`] and the ratchet public key of the responder. The
+//! responder creates its session with [`Session::respond()`] and that ratchet secret key.
+//! The responder can only send messages after it has received the first message.
+//!
+//! # Parameters:
+//! - `shared_secret`: The secret both parties have agreed on.
+//! - `remote_public_key`: The ratchet public key of the responder.
+//! - `secret_key`: The ratchet secret key of the responder.
+//! - `plaintext`: The message to encrypt.
+//! - `message`: The header, ciphertext and tag of a message, as output by [`Session::encrypt()`].
+//! - `ad`: Additional data to authenticate (this is not encrypted and can be empty), such as the
+//!   identity keys of both parties.
+//! - `dst_out`: Destination array that will hold the `message`/`plaintext` after
+//!   encryption/decryption.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` + [`OVERHEAD`] when encrypting.
+//! - The length of `dst_out` is less than `message` - [`OVERHEAD`] when decrypting.
+//! - The length of `message` is less than [`OVERHEAD`].
+//! - The responder encrypts before it has received a message.
+//! - `message` does not authenticate, or has already been decrypted.
+//! - More than [`MAX_SKIP`] messages of a single chain have been skipped.
+//! - An X25519 shared secret is all zero, because a ratchet public key is a low-order point.
+//! - `2^32 - 1` messages have been sent in a single chain.
+//! - An exported state is not [`STATEBYTES`] long, or is malformed.
+//! - Failure to generate random bytes securely.
+//!
+//! # Security:
+//! - The [`Session`] must be kept secret, and the `shared_secret` must not be used by any
+//!   other session.
+//! - When decryption fails, the [`Session`] is left as it was before.
+//! - An exported state must be protected like the secret keys it contains. Importing an
+//!   old state reuses message keys, and thereby breaks the security of the ratchet, so an
+//!   old state must never be used again once a state has been exported from it.
+//! - Headers are not encrypted, so the ratchet public keys and message numbers are visible.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::ratchet::{Session, SecretKey, SharedSecret, OVERHEAD};
+//!
+//! // Both parties agreed on a shared secret, and Alice knows Bob's ratchet public key.
+//! let shared_secret = SharedSecret::from_slice(&[1u8; 32])?;
+//! let bob_secret_key = SecretKey::generate();
+//!
+//! let mut alice = Session::initiate(&shared_secret, &bob_secret_key.public_key())?;
+//! let mut bob = Session::respond(&shared_secret, &bob_secret_key);
+//!
+//! let mut message = [0u8; 5 + OVERHEAD];
+//! alice.encrypt(b"Hello", b"", &mut message)?;
+//!
+//! let mut plaintext = [0u8; 5];
+//! bob.decrypt(&message, b"", &mut plaintext)?;
+//! assert_eq!(&plaintext, b"Hello");
+//!
+//! bob.encrypt(b"Hi!!!", b"", &mut message)?;
+//! alice.decrypt(&message, b"", &mut plaintext)?;
+//! assert_eq!(&plaintext, b"Hi!!!");
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [Signal specification]: https://signal.org/docs/specifications/doubleratchet/
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::aead::chacha20poly1305;
+use crate::hazardous::ecc::x25519::{scalarmult, scalarmult_base, X25519_SIZE};
+use crate::hazardous::kdf::hkdf;
+use crate::hazardous::mac::{hmac, poly1305::POLY1305_OUTSIZE};
+use crate::hazardous::stream::chacha20;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// The size of a [`SecretKey`], [`PublicKey`] and [`SharedSecret`].
+pub const RATCHET_KEYSIZE: usize = 32;
+/// The size of the header at the start of a message: the ratchet public key of the sender,
+/// the length of its previous sending chain and the number of the message, each as a
+/// big-endian `u32`.
+pub const HEADER_SIZE: usize = RATCHET_KEYSIZE + 8;
+/// The amount of bytes that [`Session::encrypt()`] adds to a plaintext.
+pub const OVERHEAD: usize = HEADER_SIZE + POLY1305_OUTSIZE;
+/// The maximum amount of skipped message keys that are stored.
+pub const MAX_SKIP: usize = 64;
+/// The size of a skipped message key in an exported state.
+const SKIPPED_KEYBYTES: usize = 1 + RATCHET_KEYSIZE + 4 + 32;
+/// The size of an exported [`Session`].
+pub const STATEBYTES: usize = 32 * 3 + 3 * (1 + 32) + 3 * 4 + 4 + MAX_SKIP * SKIPPED_KEYBYTES;
+
+const INFO_ROOT: &[u8] = b"orion ratchet root";
+const INFO_MESSAGE: &[u8] = b"orion ratchet message";
+
+construct_secret_key! {
+    /// A type to represent the X25519 ratchet `SecretKey` of a party.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, RATCHET_KEYSIZE, RATCHET_KEYSIZE, RATCHET_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, RATCHET_KEYSIZE);
+
+impl SecretKey {
+    /// Return the [`PublicKey`] that belongs to this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(scalarmult_base(&self.value))
+    }
+}
+
+construct_public! {
+    /// A type to represent the X25519 ratchet `PublicKey` of a party.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (PublicKey, test_public_key, RATCHET_KEYSIZE, RATCHET_KEYSIZE)
+}
+
+impl_from_trait!(PublicKey, RATCHET_KEYSIZE);
+
+construct_secret_key! {
+    /// A type to represent the `SharedSecret` that both parties have agreed on, and that
+    /// is the first root key of a [`Session`].
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SharedSecret, test_shared_secret, RATCHET_KEYSIZE, RATCHET_KEYSIZE, RATCHET_KEYSIZE)
+}
+
+impl_from_trait!(SharedSecret, RATCHET_KEYSIZE);
+
+/// X25519 that rejects an all-zero output.
+fn dh(
+    secret_key: &[u8; X25519_SIZE],
+    public_key: &[u8; X25519_SIZE],
+) -> Result<[u8; X25519_SIZE], UnknownCryptoError> {
+    let mut shared = scalarmult(secret_key, public_key);
+    if bool::from(shared.ct_eq(&[0u8; X25519_SIZE])) {
+        shared.zeroize();
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    Ok(shared)
+}
+
+/// Derive the next root key and a chain key from the root key and a DH output.
+fn kdf_rk(root_key: &mut [u8; 32], dh_out: &[u8; 32]) -> Result<[u8; 32], UnknownCryptoError> {
+    let mut okm = [0u8; 64];
+    hkdf::sha256::derive_key(root_key, dh_out, Some(INFO_ROOT), &mut okm)?;
+    let mut chain_key = [0u8; 32];
+    root_key.copy_from_slice(&okm[..32]);
+    chain_key.copy_from_slice(&okm[32..]);
+    okm.zeroize();
+
+    Ok(chain_key)
+}
+
+/// Advance the chain key and return the message key.
+fn kdf_ck(chain_key: &mut [u8; 32]) -> Result<[u8; 32], UnknownCryptoError> {
+    let sk = hmac::sha256::SecretKey::from_slice(chain_key)?;
+    let mut message_key = [0u8; 32];
+    message_key
+        .copy_from_slice(hmac::sha256::HmacSha256::hmac(&sk, &[0x01])?.unprotected_as_bytes());
+    chain_key.copy_from_slice(hmac::sha256::HmacSha256::hmac(&sk, &[0x02])?.unprotected_as_bytes());
+
+    Ok(message_key)
+}
+
+/// Derive the AEAD key and nonce of a message key.
+fn message_aead_key(
+    message_key: &[u8; 32],
+) -> Result<(chacha20::SecretKey, chacha20::Nonce), UnknownCryptoError> {
+    let mut okm = [0u8; 32 + 12];
+    hkdf::sha256::derive_key(&[0u8; 32], message_key, Some(INFO_MESSAGE), &mut okm)?;
+    let ret = (
+        chacha20::SecretKey::from_slice(&okm[..32])?,
+        chacha20::Nonce::from_slice(&okm[32..])?,
+    );
+    okm.zeroize();
+
+    Ok(ret)
+}
+
+/// The header of a message.
+struct Header {
+    public_key: [u8; RATCHET_KEYSIZE],
+    previous_len: u32,
+    n: u32,
+}
+
+impl Header {
+    fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut ret = [0u8; HEADER_SIZE];
+        ret[..RATCHET_KEYSIZE].copy_from_slice(&self.public_key);
+        ret[RATCHET_KEYSIZE..RATCHET_KEYSIZE + 4].copy_from_slice(&self.previous_len.to_be_bytes());
+        ret[RATCHET_KEYSIZE + 4..].copy_from_slice(&self.n.to_be_bytes());
+
+        ret
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut public_key = [0u8; RATCHET_KEYSIZE];
+        public_key.copy_from_slice(&bytes[..RATCHET_KEYSIZE]);
+
+        Self {
+            public_key,
+            previous_len: read_u32(&bytes[RATCHET_KEYSIZE..]),
+            n: read_u32(&bytes[RATCHET_KEYSIZE + 4..]),
+        }
+    }
+}
+
+/// Encode an optional key as a flag byte followed by the key, or all zeros if it is `None`.
+fn optional_bytes(value: &Option<[u8; 32]>) -> [u8; 33] {
+    let mut ret = [0u8; 33];
+    if let Some(value) = value {
+        ret[0] = 1;
+        ret[1..].copy_from_slice(value);
+    }
+
+    ret
+}
+
+/// Read a big-endian `u32` from the start of `bytes`.
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut ret = [0u8; 4];
+    ret.copy_from_slice(&bytes[..4]);
+    u32::from_be_bytes(ret)
+}
+
+#[derive(Clone, Copy, Default)]
+/// The message key of a message that has not arrived yet.
+struct SkippedKey {
+    used: bool,
+    public_key: [u8; RATCHET_KEYSIZE],
+    n: u32,
+    message_key: [u8; 32],
+}
+
+/// The state of one party of a Double Ratchet.
+pub struct Session {
+    /// The own ratchet key pair.
+    secret_key: [u8; RATCHET_KEYSIZE],
+    public_key: [u8; RATCHET_KEYSIZE],
+    /// The ratchet public key of the other party, if a message has been received.
+    remote_public_key: Option<[u8; RATCHET_KEYSIZE]>,
+    root_key: [u8; 32],
+    sending_chain: Option<[u8; 32]>,
+    receiving_chain: Option<[u8; 32]>,
+    sent: u32,
+    received: u32,
+    previous_sent: u32,
+    /// A ring buffer of the skipped message keys, of which `skipped_next` is the oldest.
+    skipped: [SkippedKey; MAX_SKIP],
+    skipped_next: usize,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.secret_key.zeroize();
+        self.root_key.zeroize();
+        if let Some(chain) = self.sending_chain.as_mut() {
+            chain.zeroize();
+        }
+        if let Some(chain) = self.receiving_chain.as_mut() {
+            chain.zeroize();
+        }
+        for skipped in self.skipped.iter_mut() {
+            skipped.message_key.zeroize();
+        }
+    }
+}
+
+impl core::fmt::Debug for Session {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Session {{ ***OMITTED*** }}")
+    }
+}
+
+impl Session {
+    fn new(
+        secret_key: [u8; RATCHET_KEYSIZE],
+        root_key: [u8; 32],
+        remote_public_key: Option<[u8; RATCHET_KEYSIZE]>,
+    ) -> Self {
+        Self {
+            public_key: scalarmult_base(&secret_key),
+            secret_key,
+            remote_public_key,
+            root_key,
+            sending_chain: None,
+            receiving_chain: None,
+            sent: 0,
+            received: 0,
+            previous_sent: 0,
+            skipped: [SkippedKey::default(); MAX_SKIP],
+            skipped_next: 0,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create the session of the initiator, who knows the ratchet public key of the responder.
+    pub fn initiate(
+        shared_secret: &SharedSecret,
+        remote_public_key: &PublicKey,
+    ) -> Result<Self, UnknownCryptoError> {
+        let mut secret_key = [0u8; RATCHET_KEYSIZE];
+        crate::util::secure_rand_bytes(&mut secret_key)?;
+        let mut session = Self::new(
+            secret_key,
+            shared_secret.value,
+            Some(remote_public_key.value),
+        );
+        secret_key.zeroize();
+
+        let mut dh_out = dh(&session.secret_key, &remote_public_key.value)?;
+        let chain = kdf_rk(&mut session.root_key, &dh_out);
+        dh_out.zeroize();
+        session.sending_chain = Some(chain?);
+
+        Ok(session)
+    }
+
+    /// Create the session of the responder, with the ratchet secret key whose public key
+    /// the initiator used.
+    pub fn respond(shared_secret: &SharedSecret, secret_key: &SecretKey) -> Self {
+        Self::new(secret_key.value, shared_secret.value, None)
+    }
+
+    /// Return the current ratchet public key of this session.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(self.public_key)
+    }
+
+    /// A copy of the session, which decryption works on until it has succeeded.
+    fn duplicate(&self) -> Self {
+        Self {
+            secret_key: self.secret_key,
+            public_key: self.public_key,
+            remote_public_key: self.remote_public_key,
+            root_key: self.root_key,
+            sending_chain: self.sending_chain,
+            receiving_chain: self.receiving_chain,
+            sent: self.sent,
+            received: self.received,
+            previous_sent: self.previous_sent,
+            skipped: self.skipped,
+            skipped_next: self.skipped_next,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt `plaintext`, writing the header, ciphertext and tag to `dst_out`. Returns the
+    /// amount of bytes written to `dst_out`, which is `plaintext.len()` + [`OVERHEAD`].
+    pub fn encrypt(
+        &mut self,
+        plaintext: &[u8],
+        ad: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<usize, UnknownCryptoError> {
+        let out_len = match plaintext.len().checked_add(OVERHEAD) {
+            Some(out_len) => out_len,
+            None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+        };
+        if dst_out.len() < out_len {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if self.sent == u32::MAX {
+            return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow));
+        }
+        let chain = self
+            .sending_chain
+            .as_mut()
+            .ok_or_else(|| UnknownCryptoError::new(ErrorKind::InvalidState))?;
+
+        let mut message_key = kdf_ck(chain)?;
+        let header = Header {
+            public_key: self.public_key,
+            previous_len: self.previous_sent,
+            n: self.sent,
+        }
+        .to_bytes();
+        self.sent += 1;
+
+        let (key, nonce) = message_aead_key(&message_key)?;
+        message_key.zeroize();
+        dst_out[..HEADER_SIZE].copy_from_slice(&header);
+        chacha20poly1305::seal_vectored(
+            &key,
+            &nonce,
+            &[plaintext],
+            &[ad, &header],
+            &mut dst_out[HEADER_SIZE..out_len],
+        )?;
+
+        Ok(out_len)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decrypt `message`, writing the plaintext to `dst_out`. Returns the amount of bytes written
+    /// to `dst_out`, which is `message.len()` - [`OVERHEAD`].
+    pub fn decrypt(
+        &mut self,
+        message: &[u8],
+        ad: &[u8],
+        dst_out: &mut [u8],
+    ) -> Result<usize, UnknownCryptoError> {
+        if message.len() < OVERHEAD {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        let out_len = message.len() - OVERHEAD;
+        if dst_out.len() < out_len {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let header = Header::from_bytes(&message[..HEADER_SIZE]);
+        let mut next = self.duplicate();
+        let (mut message_key, skipped) = next.message_key(&header)?;
+        if let Some(index) = skipped {
+            next.skipped[index].used = false;
+            next.skipped[index].message_key.zeroize();
+        }
+
+        let (key, nonce) = message_aead_key(&message_key)?;
+        message_key.zeroize();
+        chacha20poly1305::open_vectored(
+            &key,
+            &nonce,
+            &[&message[HEADER_SIZE..]],
+            &[ad, &message[..HEADER_SIZE]],
+            &mut dst_out[..out_len],
+        )?;
+
+        core::mem::swap(self, &mut next);
+
+        Ok(out_len)
+    }
+
+    /// Find the message key of `header`, ratcheting the state forward as needed. If the key
+    /// was a skipped key, its index is also returned.
+    fn message_key(
+        &mut self,
+        header: &Header,
+    ) -> Result<([u8; 32], Option<usize>), UnknownCryptoError> {
+        if let Some(index) = self.skipped.iter().position(|skipped| {
+            skipped.used && skipped.public_key == header.public_key && skipped.n == header.n
+        }) {
+            return Ok((self.skipped[index].message_key, Some(index)));
+        }
+
+        if self.remote_public_key != Some(header.public_key) {
+            self.skip_message_keys(header.previous_len)?;
+            self.dh_ratchet(&header.public_key)?;
+        }
+
+        self.skip_message_keys(header.n)?;
+        let chain = self
+            .receiving_chain
+            .as_mut()
+            .ok_or_else(|| UnknownCryptoError::new(ErrorKind::InvalidState))?;
+        if header.n < self.received {
+            // The message was already decrypted, or its key has been discarded.
+            return Err(UnknownCryptoError::new(ErrorKind::MacMismatch));
+        }
+        let message_key = kdf_ck(chain)?;
+        self.received += 1;
+
+        Ok((message_key, None))
+    }
+
+    /// Store the message keys of the receiving chain up to message number `until`.
+    fn skip_message_keys(&mut self, until: u32) -> Result<(), UnknownCryptoError> {
+        let (chain, remote_public_key) =
+            match (self.receiving_chain.as_mut(), self.remote_public_key) {
+                (Some(chain), Some(remote_public_key)) => (chain, remote_public_key),
+                _ => return Ok(()),
+            };
+        if until <= self.received {
+            return Ok(());
+        }
+        if (until - self.received) as usize > MAX_SKIP {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+        }
+
+        while self.received < until {
+            let oldest = &mut self.skipped[self.skipped_next];
+            oldest.message_key.zeroize();
+            *oldest = SkippedKey {
+                used: true,
+                public_key: remote_public_key,
+                n: self.received,
+                message_key: kdf_ck(chain)?,
+            };
+            self.skipped_next = (self.skipped_next + 1) % MAX_SKIP;
+            self.received += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Replace the receiving and sending chains, after receiving `remote_public_key`.
+    fn dh_ratchet(
+        &mut self,
+        remote_public_key: &[u8; RATCHET_KEYSIZE],
+    ) -> Result<(), UnknownCryptoError> {
+        self.previous_sent = self.sent;
+        self.sent = 0;
+        self.received = 0;
+        self.remote_public_key = Some(*remote_public_key);
+
+        let mut dh_out = dh(&self.secret_key, remote_public_key)?;
+        let chain = kdf_rk(&mut self.root_key, &dh_out);
+        dh_out.zeroize();
+        self.receiving_chain = Some(chain?);
+
+        crate::util::secure_rand_bytes(&mut self.secret_key)?;
+        self.public_key = scalarmult_base(&self.secret_key);
+        let mut dh_out = dh(&self.secret_key, remote_public_key)?;
+        let chain = kdf_rk(&mut self.root_key, &dh_out);
+        dh_out.zeroize();
+        self.sending_chain = Some(chain?);
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Export the state into `dst_out`, which must be [`STATEBYTES`] long, so that the session
+    /// can be resumed with [`Session::import_state()`].
+    ///
+    /// # Security:
+    /// The exported state contains the secret keys of the session and must be protected as such.
+    pub fn export_state(&self, dst_out: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dst_out.len() != STATEBYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let mut pos = 0;
+        let mut put = |bytes: &[u8]| {
+            dst_out[pos..pos + bytes.len()].copy_from_slice(bytes);
+            pos += bytes.len();
+        };
+        put(&self.secret_key);
+        put(&self.public_key);
+        put(&self.root_key);
+        put(&optional_bytes(&self.remote_public_key));
+        put(&optional_bytes(&self.sending_chain));
+        put(&optional_bytes(&self.receiving_chain));
+        put(&self.sent.to_be_bytes());
+        put(&self.received.to_be_bytes());
+        put(&self.previous_sent.to_be_bytes());
+        // `skipped_next` is less than MAX_SKIP.
+        put(&(self.skipped_next as u32).to_be_bytes());
+        for skipped in self.skipped.iter() {
+            put(&[skipped.used as u8]);
+            put(&skipped.public_key);
+            put(&skipped.n.to_be_bytes());
+            put(&skipped.message_key);
+        }
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Import a state that was exported with [`Session::export_state()`].
+    pub fn import_state(state: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if state.len() != STATEBYTES {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let invalid = || UnknownCryptoError::new(ErrorKind::InvalidEncoding);
+        let mut pos = 0;
+        let mut take = |len: usize| {
+            pos += len;
+            &state[pos - len..pos]
+        };
+        let read_key = |bytes: &[u8]| {
+            let mut key = [0u8; 32];
+            key.copy_from_slice(bytes);
+            key
+        };
+        let read_flag = |byte: u8| match byte {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid()),
+        };
+
+        let mut session = Self::new(read_key(take(32)), [0u8; 32], None);
+        if session.public_key != read_key(take(32)) {
+            return Err(invalid());
+        }
+        session.root_key = read_key(take(32));
+        let mut optionals = [None; 3];
+        for optional in optionals.iter_mut() {
+            let bytes = take(33);
+            *optional = if read_flag(bytes[0])? {
+                Some(read_key(&bytes[1..]))
+            } else if bytes[1..].iter().any(|b| *b != 0) {
+                return Err(invalid());
+            } else {
+                None
+            };
+        }
+        session.remote_public_key = optionals[0];
+        session.sending_chain = optionals[1];
+        session.receiving_chain = optionals[2];
+        session.sent = read_u32(take(4));
+        session.received = read_u32(take(4));
+        session.previous_sent = read_u32(take(4));
+        session.skipped_next = read_u32(take(4)) as usize;
+        if session.skipped_next >= MAX_SKIP {
+            return Err(invalid());
+        }
+        for skipped in session.skipped.iter_mut() {
+            let bytes = take(SKIPPED_KEYBYTES);
+            *skipped = SkippedKey {
+                used: read_flag(bytes[0])?,
+                public_key: read_key(&bytes[1..33]),
+                n: read_u32(&bytes[33..37]),
+                message_key: read_key(&bytes[37..]),
+            };
+        }
+
+        Ok(session)
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    /// A message or plaintext of at most 64 bytes.
+    #[derive(Clone, Copy, Debug)]
+    struct Buf([u8; 64], usize);
+
+    impl Buf {
+        fn as_slice(&self) -> &[u8] {
+            &self.0[..self.1]
+        }
+    }
+
+    fn sessions() -> (Session, Session) {
+        let shared_secret = SharedSecret::generate();
+        let bob_secret_key = SecretKey::generate();
+        (
+            Session::initiate(&shared_secret, &bob_secret_key.public_key()).unwrap(),
+            Session::respond(&shared_secret, &bob_secret_key),
+        )
+    }
+
+    fn encrypt(session: &mut Session, plaintext: &[u8]) -> Buf {
+        let mut out = Buf([0u8; 64], plaintext.len() + OVERHEAD);
+        assert_eq!(
+            session.encrypt(plaintext, b"ad", &mut out.0).unwrap(),
+            out.1
+        );
+        out
+    }
+
+    fn decrypt(session: &mut Session, message: &Buf) -> Result<Buf, UnknownCryptoError> {
+        let mut out = Buf([0u8; 64], message.1 - OVERHEAD);
+        session.decrypt(message.as_slice(), b"ad", &mut out.0)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_conversation() {
+        let (mut alice, mut bob) = sessions();
+
+        for round in 0..5u8 {
+            for i in 0..3u8 {
+                let message = encrypt(&mut alice, &[round, i]);
+                assert_eq!(decrypt(&mut bob, &message).unwrap().as_slice(), [round, i]);
+            }
+            let alice_key = alice.public_key();
+            let message = encrypt(&mut bob, &[round]);
+            assert_eq!(decrypt(&mut alice, &message).unwrap().as_slice(), [round]);
+            // Every reply ratchets the DH keys forward.
+            assert_ne!(alice.public_key(), alice_key);
+        }
+    }
+
+    #[test]
+    fn test_responder_can_not_send_first() {
+        let (_, mut bob) = sessions();
+        let mut out = [0u8; OVERHEAD];
+        assert_eq!(
+            bob.encrypt(b"", b"", &mut out).unwrap_err().kind(),
+            ErrorKind::InvalidState
+        );
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let (mut alice, mut bob) = sessions();
+        let first = encrypt(&mut alice, b"first");
+        let second = encrypt(&mut alice, b"second");
+        let third = encrypt(&mut alice, b"third");
+
+        assert_eq!(decrypt(&mut bob, &third).unwrap().as_slice(), b"third");
+        let reply = encrypt(&mut bob, b"reply");
+        assert_eq!(decrypt(&mut alice, &reply).unwrap().as_slice(), b"reply");
+        let fourth = encrypt(&mut alice, b"fourth");
+        assert_eq!(decrypt(&mut bob, &fourth).unwrap().as_slice(), b"fourth");
+
+        // Skipped messages of an older chain can still be decrypted, but only once.
+        assert_eq!(decrypt(&mut bob, &first).unwrap().as_slice(), b"first");
+        assert_eq!(decrypt(&mut bob, &second).unwrap().as_slice(), b"second");
+        assert!(decrypt(&mut bob, &first).is_err());
+        assert!(decrypt(&mut bob, &fourth).is_err());
+        assert!(decrypt(&mut bob, &third).is_err());
+    }
+
+    #[test]
+    fn test_max_skip() {
+        let (mut alice, mut bob) = sessions();
+        for _ in 0..MAX_SKIP {
+            encrypt(&mut alice, b"skipped");
+        }
+        assert!(decrypt(&mut bob, &encrypt(&mut alice, b"ok")).is_ok());
+
+        for _ in 0..=MAX_SKIP {
+            encrypt(&mut alice, b"skipped");
+        }
+        let err = decrypt(&mut bob, &encrypt(&mut alice, b"too far")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+    }
+
+    #[test]
+    fn test_failed_decryption_keeps_state() {
+        let (mut alice, mut bob) = sessions();
+        let message = encrypt(&mut alice, b"message");
+
+        for i in 0..message.1 {
+            let mut modified = message;
+            modified.0[i] ^= 1;
+            assert!(decrypt(&mut bob, &modified).is_err());
+        }
+        let mut out = [0u8; 7];
+        assert!(bob
+            .decrypt(message.as_slice(), b"other ad", &mut out)
+            .is_err());
+        assert!(bob
+            .decrypt(&message.as_slice()[..OVERHEAD - 1], b"ad", &mut out)
+            .is_err());
+        assert!(bob
+            .decrypt(message.as_slice(), b"ad", &mut out[..6])
+            .is_err());
+
+        assert_eq!(decrypt(&mut bob, &message).unwrap().as_slice(), b"message");
+    }
+
+    #[test]
+    fn test_low_order_public_key() {
+        let shared_secret = SharedSecret::generate();
+        assert!(Session::initiate(&shared_secret, &PublicKey::from([0u8; 32])).is_err());
+    }
+
+    #[test]
+    fn test_export_import_state() {
+        let (mut alice, mut bob) = sessions();
+        let skipped = encrypt(&mut alice, b"skipped");
+        let message = encrypt(&mut alice, b"message");
+        assert!(decrypt(&mut bob, &message).is_ok());
+
+        let mut state = [0u8; STATEBYTES];
+        bob.export_state(&mut state).unwrap();
+        let mut bob = Session::import_state(&state).unwrap();
+        let mut exported_again = [0u8; STATEBYTES];
+        bob.export_state(&mut exported_again).unwrap();
+        assert_eq!(state[..], exported_again[..]);
+
+        assert_eq!(decrypt(&mut bob, &skipped).unwrap().as_slice(), b"skipped");
+        let reply = encrypt(&mut bob, b"reply");
+        assert_eq!(decrypt(&mut alice, &reply).unwrap().as_slice(), b"reply");
+
+        assert!(bob.export_state(&mut state[1..]).is_err());
+        assert!(Session::import_state(&state[1..]).is_err());
+        // A public key that does not belong to the secret key.
+        let mut modified = state;
+        modified[32] ^= 1;
+        assert!(Session::import_state(&modified).is_err());
+        // An invalid flag.
+        let mut modified = state;
+        modified[96] = 2;
+        assert!(Session::import_state(&modified).is_err());
+    }
+}
@@ -0,0 +1,571 @@
+// MIT License
+
+//! # Parameters:
+//! - `entropy_input`: Secret input from a source of entropy.
+//! - `nonce`: A value that is used, together with `entropy_input`, at instantiation.
+//!   It does not need to be secret, but should not repeat.
+//! - `personalization_string`: Optional data that makes the instantiation unique, such
+//!   as a device or application identifier. If [`None`] then it's an empty string.
+//! - `additional_input`: Optional data that is mixed into the state. If [`None`] then
+//!   it's an empty string.
+//! - `dst_out`: Destination buffer for the generated bytes. The amount of bytes to be
+//!   generated is implied by the length of `dst_out`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `entropy_input` is less than 32.
+//! - The length of `dst_out` is less than 1.
+//! - The length of `dst_out` is greater than 65536.
+//! - More than 2^48 requests have been made since the last reseed. When this
+//!   happens, [`reseed()`] must be called before more output can be generated.
+//!
+//! # Security:
+//! - The output is fully determined by the inputs. Anyone who knows `entropy_input`,
+//!   `nonce`, `personalization_string` and all `additional_input` can reproduce it.
+//! - `entropy_input` must come from a source of entropy, such as a CSPRNG, when the
+//!   output is used as secret keys or nonces. [`secure_rand_bytes()`] can be used for this.
+//! - When generating nonces from a secret key and a message, such as in
+//!   [RFC 6979](https://tools.ietf.org/html/rfc6979), the secret key should be used as
+//!   `entropy_input` and the message digest as `nonce`.
+//! - The DRBG does not protect against its state being copied. Output that is generated
+//!   before a reseed cannot be recovered from the state.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{hazardous::rng::hmac_drbg::sha512::HmacDrbg, util};
+//!
+//! let mut entropy_input = [0u8; 32];
+//! util::secure_rand_bytes(&mut entropy_input)?;
+//! let mut nonce = [0u8; 16];
+//! util::secure_rand_bytes(&mut nonce)?;
+//!
+//! let mut drbg = HmacDrbg::instantiate(&entropy_input, &nonce, Some(b"my app"))?;
+//! let mut key = [0u8; 32];
+//! drbg.generate(None, &mut key)?;
+//!
+//! // Fresh entropy can be mixed in at any time.
+//! util::secure_rand_bytes(&mut entropy_input)?;
+//! drbg.reseed(&entropy_input, None)?;
+//! drbg.generate(Some(b"another key"), &mut key)?;
+//!
+//! // The output is deterministic.
+//! let mut first = HmacDrbg::instantiate(&[1u8; 32], b"nonce", None)?;
+//! let mut second = HmacDrbg::instantiate(&[1u8; 32], b"nonce", None)?;
+//! let mut out_first = [0u8; 64];
+//! let mut out_second = [0u8; 64];
+//! first.generate(None, &mut out_first)?;
+//! second.generate(None, &mut out_second)?;
+//! assert_eq!(out_first, out_second);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`reseed()`]: sha512::HmacDrbg::reseed
+//! [`secure_rand_bytes()`]: crate::util::secure_rand_bytes
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::mac::hmac;
+use core::marker::PhantomData;
+use zeroize::Zeroize;
+
+/// The minimum length of `entropy_input` (bytes). This is the highest security
+/// strength supported by any of the hash functions.
+pub const MIN_ENTROPY_SIZE: usize = 32;
+/// The maximum amount of bytes that can be generated in a single request.
+pub const MAX_REQUEST_SIZE: usize = 65536;
+/// The maximum amount of requests between reseeds.
+const RESEED_INTERVAL: u64 = 1 << 48;
+
+/// The HMAC_DRBG state, with `OUTSIZE` being the output size of the hash function.
+///
+/// NOTE: See the comment about the const param in HKDF.
+struct Drbg<Hmac, const OUTSIZE: usize> {
+    k: [u8; OUTSIZE],
+    v: [u8; OUTSIZE],
+    reseed_counter: u64,
+    _hmac: PhantomData<Hmac>,
+}
+
+impl<Hmac, const OUTSIZE: usize> Drop for Drbg<Hmac, OUTSIZE> {
+    fn drop(&mut self) {
+        self.k.iter_mut().zeroize();
+        self.v.iter_mut().zeroize();
+    }
+}
+
+impl<Hmac, const OUTSIZE: usize> Drbg<Hmac, OUTSIZE>
+where
+    Hmac: hmac::HmacFunction,
+{
+    /// The HMAC_DRBG_Update process. `provided_data` is the concatenation of all
+    /// the slices and is considered Null if it's empty.
+    fn update(&mut self, provided_data: &[&[u8]]) -> Result<(), UnknownCryptoError> {
+        debug_assert!(OUTSIZE == Hmac::HASH_FUNC_OUTSIZE);
+        let is_null = provided_data.iter().all(|data| data.is_empty());
+
+        for separator in [0x00u8, 0x01u8].iter() {
+            let mut ctx = Hmac::_new(&self.k)?;
+            ctx._update(&self.v)?;
+            ctx._update(&[*separator])?;
+            for data in provided_data.iter() {
+                ctx._update(data)?;
+            }
+            ctx._finalize(&mut self.k)?;
+
+            let mut ctx = Hmac::_new(&self.k)?;
+            ctx._update(&self.v)?;
+            ctx._finalize(&mut self.v)?;
+
+            if is_null {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The HMAC_DRBG_Instantiate process.
+    fn instantiate(
+        entropy_input: &[u8],
+        nonce: &[u8],
+        personalization_string: Option<&[u8]>,
+    ) -> Result<Self, UnknownCryptoError> {
+        if entropy_input.len() < MIN_ENTROPY_SIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let mut state = Self {
+            k: [0x00u8; OUTSIZE],
+            v: [0x01u8; OUTSIZE],
+            reseed_counter: 1,
+            _hmac: PhantomData,
+        };
+        state.update(&[
+            entropy_input,
+            nonce,
+            personalization_string.unwrap_or(&[0u8; 0]),
+        ])?;
+
+        Ok(state)
+    }
+
+    /// The HMAC_DRBG_Reseed process.
+    fn reseed(
+        &mut self,
+        entropy_input: &[u8],
+        additional_input: Option<&[u8]>,
+    ) -> Result<(), UnknownCryptoError> {
+        if entropy_input.len() < MIN_ENTROPY_SIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        self.update(&[entropy_input, additional_input.unwrap_or(&[0u8; 0])])?;
+        self.reseed_counter = 1;
+
+        Ok(())
+    }
+
+    /// The HMAC_DRBG_Generate process.
+    fn generate(
+        &mut self,
+        additional_input: Option<&[u8]>,
+        dest: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        if dest.is_empty() || dest.len() > MAX_REQUEST_SIZE {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if self.reseed_counter > RESEED_INTERVAL {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let additional_input = additional_input.unwrap_or(&[0u8; 0]);
+        if !additional_input.is_empty() {
+            self.update(&[additional_input])?;
+        }
+
+        let mut ctx = Hmac::_new(&self.k)?;
+        for block in dest.chunks_mut(OUTSIZE) {
+            ctx._update(&self.v)?;
+            ctx._finalize(&mut self.v)?;
+            ctx._reset();
+            block.copy_from_slice(&self.v[..block.len()]);
+        }
+
+        self.update(&[additional_input])?;
+        self.reseed_counter += 1;
+
+        Ok(())
+    }
+}
+
+/// HMAC_DRBG with HMAC-SHA256.
+pub mod sha256 {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha256::SHA256_OUTSIZE;
+
+    /// HMAC_DRBG with HMAC-SHA256.
+    pub struct HmacDrbg {
+        pub(super) _state: Drbg<hmac::sha256::HmacSha256, { SHA256_OUTSIZE }>,
+    }
+
+    impl core::fmt::Debug for HmacDrbg {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(
+                f,
+                "HmacDrbg {{ k: [***OMITTED***], v: [***OMITTED***], reseed_counter: {:?} }}",
+                self._state.reseed_counter
+            )
+        }
+    }
+
+    impl HmacDrbg {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Instantiate a new HMAC_DRBG.
+        pub fn instantiate(
+            entropy_input: &[u8],
+            nonce: &[u8],
+            personalization_string: Option<&[u8]>,
+        ) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Drbg::instantiate(entropy_input, nonce, personalization_string)?,
+            })
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Reseed with fresh `entropy_input`.
+        pub fn reseed(
+            &mut self,
+            entropy_input: &[u8],
+            additional_input: Option<&[u8]>,
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.reseed(entropy_input, additional_input)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Generate `dst_out.len()` bytes.
+        pub fn generate(
+            &mut self,
+            additional_input: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.generate(additional_input, dst_out)
+        }
+    }
+}
+
+/// HMAC_DRBG with HMAC-SHA384.
+pub mod sha384 {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha384::SHA384_OUTSIZE;
+
+    /// HMAC_DRBG with HMAC-SHA384.
+    pub struct HmacDrbg {
+        pub(super) _state: Drbg<hmac::sha384::HmacSha384, { SHA384_OUTSIZE }>,
+    }
+
+    impl core::fmt::Debug for HmacDrbg {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(
+                f,
+                "HmacDrbg {{ k: [***OMITTED***], v: [***OMITTED***], reseed_counter: {:?} }}",
+                self._state.reseed_counter
+            )
+        }
+    }
+
+    impl HmacDrbg {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Instantiate a new HMAC_DRBG.
+        pub fn instantiate(
+            entropy_input: &[u8],
+            nonce: &[u8],
+            personalization_string: Option<&[u8]>,
+        ) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Drbg::instantiate(entropy_input, nonce, personalization_string)?,
+            })
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Reseed with fresh `entropy_input`.
+        pub fn reseed(
+            &mut self,
+            entropy_input: &[u8],
+            additional_input: Option<&[u8]>,
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.reseed(entropy_input, additional_input)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Generate `dst_out.len()` bytes.
+        pub fn generate(
+            &mut self,
+            additional_input: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.generate(additional_input, dst_out)
+        }
+    }
+}
+
+/// HMAC_DRBG with HMAC-SHA512.
+pub mod sha512 {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha512::SHA512_OUTSIZE;
+
+    /// HMAC_DRBG with HMAC-SHA512.
+    pub struct HmacDrbg {
+        pub(super) _state: Drbg<hmac::sha512::HmacSha512, { SHA512_OUTSIZE }>,
+    }
+
+    impl core::fmt::Debug for HmacDrbg {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(
+                f,
+                "HmacDrbg {{ k: [***OMITTED***], v: [***OMITTED***], reseed_counter: {:?} }}",
+                self._state.reseed_counter
+            )
+        }
+    }
+
+    impl HmacDrbg {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Instantiate a new HMAC_DRBG.
+        pub fn instantiate(
+            entropy_input: &[u8],
+            nonce: &[u8],
+            personalization_string: Option<&[u8]>,
+        ) -> Result<Self, UnknownCryptoError> {
+            Ok(Self {
+                _state: Drbg::instantiate(entropy_input, nonce, personalization_string)?,
+            })
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Reseed with fresh `entropy_input`.
+        pub fn reseed(
+            &mut self,
+            entropy_input: &[u8],
+            additional_input: Option<&[u8]>,
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.reseed(entropy_input, additional_input)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Generate `dst_out.len()` bytes.
+        pub fn generate(
+            &mut self,
+            additional_input: Option<&[u8]>,
+            dst_out: &mut [u8],
+        ) -> Result<(), UnknownCryptoError> {
+            self._state.generate(additional_input, dst_out)
+        }
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const ENTROPY: [u8; 32] = [
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31,
+    ];
+    const NONCE: [u8; 16] = [
+        32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47,
+    ];
+    const RESEED_ENTROPY: [u8; 32] = [
+        48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70,
+        71, 72, 73, 74, 75, 76, 77, 78, 79,
+    ];
+
+    macro_rules! test_drbg (($name:ident, $drbg:ty, $outsize:expr, $expected:expr) => (
+        mod $name {
+            use super::*;
+            type HmacDrbg = $drbg;
+
+            #[test]
+            fn test_instantiate_reseed_generate() {
+                let expected = $expected;
+                let mut drbg = HmacDrbg::instantiate(
+                    &ENTROPY,
+                    &NONCE,
+                    Some(b"orion personalization"),
+                )
+                .unwrap();
+
+                let mut out = [0u8; 64];
+                drbg.generate(None, &mut out).unwrap();
+                assert_eq!(out[..], hex::decode(expected[0]).unwrap()[..]);
+
+                drbg.reseed(&RESEED_ENTROPY, Some(b"additional input")).unwrap();
+                let mut out = [0u8; 100];
+                drbg.generate(Some(b"additional input"), &mut out).unwrap();
+                assert_eq!(out[..], hex::decode(expected[1]).unwrap()[..]);
+
+                let mut out = [0u8; 1];
+                drbg.generate(None, &mut out).unwrap();
+                assert_eq!(out[..], hex::decode(expected[2]).unwrap()[..]);
+
+                // Without personalization string and with a partial last block.
+                let mut drbg = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                let mut out = [0u8; $outsize + 1];
+                drbg.generate(None, &mut out).unwrap();
+                assert_eq!(out[..], hex::decode(expected[3]).unwrap()[..]);
+            }
+
+            #[test]
+            fn test_none_same_as_empty() {
+                let mut first = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                let mut second = HmacDrbg::instantiate(&ENTROPY, &NONCE, Some(&[])).unwrap();
+                let mut out_first = [0u8; 64];
+                let mut out_second = [0u8; 64];
+
+                first.generate(None, &mut out_first).unwrap();
+                second.generate(Some(&[]), &mut out_second).unwrap();
+                assert_eq!(out_first, out_second);
+
+                first.reseed(&RESEED_ENTROPY, None).unwrap();
+                second.reseed(&RESEED_ENTROPY, Some(&[])).unwrap();
+                first.generate(None, &mut out_first).unwrap();
+                second.generate(None, &mut out_second).unwrap();
+                assert_eq!(out_first, out_second);
+            }
+
+            #[test]
+            fn test_inputs_change_output() {
+                let mut out = [0u8; 32];
+                let mut out_other = [0u8; 32];
+                HmacDrbg::instantiate(&ENTROPY, &NONCE, None)
+                    .unwrap()
+                    .generate(None, &mut out)
+                    .unwrap();
+
+                HmacDrbg::instantiate(&RESEED_ENTROPY, &NONCE, None)
+                    .unwrap()
+                    .generate(None, &mut out_other)
+                    .unwrap();
+                assert_ne!(out, out_other);
+
+                HmacDrbg::instantiate(&ENTROPY, &NONCE[..15], None)
+                    .unwrap()
+                    .generate(None, &mut out_other)
+                    .unwrap();
+                assert_ne!(out, out_other);
+
+                HmacDrbg::instantiate(&ENTROPY, &NONCE, Some(b"personal"))
+                    .unwrap()
+                    .generate(None, &mut out_other)
+                    .unwrap();
+                assert_ne!(out, out_other);
+
+                HmacDrbg::instantiate(&ENTROPY, &NONCE, None)
+                    .unwrap()
+                    .generate(Some(b"additional"), &mut out_other)
+                    .unwrap();
+                assert_ne!(out, out_other);
+            }
+
+            #[test]
+            fn test_entropy_length() {
+                assert!(HmacDrbg::instantiate(&ENTROPY[..31], &NONCE, None).is_err());
+                assert!(HmacDrbg::instantiate(&[0u8; 0], &NONCE, None).is_err());
+                assert!(HmacDrbg::instantiate(&ENTROPY, &[0u8; 0], None).is_ok());
+
+                let mut drbg = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                assert!(drbg.reseed(&RESEED_ENTROPY[..31], None).is_err());
+                assert!(drbg.reseed(&RESEED_ENTROPY, None).is_ok());
+            }
+
+            #[test]
+            fn test_request_length() {
+                let mut drbg = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                assert!(drbg.generate(None, &mut [0u8; 0]).is_err());
+                assert!(drbg.generate(None, &mut [0u8; 1]).is_ok());
+                assert!(drbg.generate(None, &mut [0u8; MAX_REQUEST_SIZE]).is_ok());
+                assert!(drbg.generate(None, &mut [0u8; MAX_REQUEST_SIZE + 1]).is_err());
+            }
+
+            #[test]
+            fn test_reseed_required() {
+                let mut drbg = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                let mut out = [0u8; 32];
+
+                drbg._state.reseed_counter = RESEED_INTERVAL;
+                assert!(drbg.generate(None, &mut out).is_ok());
+                assert!(drbg.generate(None, &mut out).is_err());
+                assert!(drbg.generate(None, &mut out).is_err());
+
+                drbg.reseed(&RESEED_ENTROPY, None).unwrap();
+                assert!(drbg.generate(None, &mut out).is_ok());
+            }
+
+            #[test]
+            #[cfg(feature = "safe_api")]
+            fn test_debug_impl() {
+                let drbg = HmacDrbg::instantiate(&ENTROPY, &NONCE, None).unwrap();
+                let debug = format!("{:?}", drbg);
+                let expected = "HmacDrbg { k: [***OMITTED***], v: [***OMITTED***], reseed_counter: 1 }";
+                assert_eq!(debug, expected);
+            }
+        }
+    ));
+
+    test_drbg!(
+        test_sha256,
+        sha256::HmacDrbg,
+        32,
+        [
+            "d3303bb8b01e407f460af1be2d88a4f7828597bf80a6cdb1fd53b40e9506c094b27f296ba08cf4bc2c9b0f0b7aada35b630d47529a5885ce4d4d23902bee0051",
+            "12ae9f62f3dde6761ab6edc2052fefadd536f1f8b2a5e5798c1d9242b5b43c0a2312766b9ec9e636a60dba5e165667a8c1c2e1f6ce9d74f2516e6cfade7246e51a498c440cd61b7b98e570c29fd7379abc30abbab92f3fecdce3eed765e9045b8797c415",
+            "2c",
+            "0ffb80875a3e9022a4941a3fa1b0d3611df14e1cf651a73ce9229b9f3ad5688768",
+        ]
+    );
+
+    test_drbg!(
+        test_sha384,
+        sha384::HmacDrbg,
+        48,
+        [
+            "b1ad3ab2da82bf4fa1c60ce29b13b77483dc882bf1b352b68b3cf0adc9b7772504f00d41e47cec29d98295e97f722f141cb363bca97cf05e510b47f125ddb6c3",
+            "5a76ff73be39e5a50b51571dafe98ef66367ec98639fd9bf98c3f162bf7ad520a4fbc2ad2196c1b2ecc9b700973e688efd9d5e91983f802efa23b924da0483b5ae098bdfafdf2d6996e00bcbdd3c82a06641387ae63e29d3da689eba7e7ea4ccd04efa3d",
+            "2f",
+            "290e3c0f81e1dd8e99e839fb98383fafc42d52d2cec7424d02ee75b628f981fe5e2375daa2b2315ca692ca75843f29ce69",
+        ]
+    );
+
+    test_drbg!(
+        test_sha512,
+        sha512::HmacDrbg,
+        64,
+        [
+            "2cf1e783edbbd861cb73a76b9ae3f3a95b37ebe945bf8e47f795db5d7c4cd8059fd4fb69389cabde774e95f458ec8eb0b05a02bf2bc73f489f174d177441bd42",
+            "985a15ab11fc98dcb8395633472ea9893c1c588d1bf3531c9c81fab9b008b57dac6a67671b19d2c4c1787692f4397b25294b8ed78786d8fee26428ac6d2b5b3d01f08c48323edc6cb1ef1f17582914f63dc33aea805a23fc589b3f723b00bb8828587bf6",
+            "ab",
+            "5a947e2ec811344b506f321e3f1fbde3fde96845301a7c1793e72b2071e1d984846eda8ee0e97301da2e6d07c4937b7a50c729a1ad16e594ab3dd965617092707e",
+        ]
+    );
+
+    #[test]
+    fn test_nist_cavp_sha256() {
+        // HMAC_DRBG.rsp: [SHA-256], [PredictionResistance = False],
+        // [EntropyInputLen = 256], [NonceLen = 128], [PersonalizationStringLen = 0],
+        // [AdditionalInputLen = 0], COUNT = 0.
+        let entropy_input =
+            hex::decode("ca851911349384bffe89de1cbdc46e6831e44d34a4fb935ee285dd14b71a7488")
+                .unwrap();
+        let nonce = hex::decode("659ba96c601dc69fc902940805ec0ca8").unwrap();
+        let expected = hex::decode(
+            "e528e9abf2dece54d47c7e75e5fe302149f817ea9fb4bee6f4199697d04d5b89\
+             d54fbb978a15b5c443c9ec21036d2460b6f73ebad0dc2aba6e624abf07745bc1\
+             07694bb7547bb0995f70de25d6b29e2d3011bb19d27676c07162c8b5ccde0668\
+             961df86803482cb37ed6d5c0bb8d50cf1f50d476aa0458bdaba806f48be9dcb8",
+        )
+        .unwrap();
+
+        let mut drbg = sha256::HmacDrbg::instantiate(&entropy_input, &nonce, None).unwrap();
+        let mut returned_bits = [0u8; 128];
+        drbg.generate(None, &mut returned_bits).unwrap();
+        drbg.generate(None, &mut returned_bits).unwrap();
+        assert_eq!(returned_bits[..], expected[..]);
+    }
+}
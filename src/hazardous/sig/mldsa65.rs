@@ -0,0 +1,1163 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! ML-DSA-65, the module-lattice-based digital signature algorithm as specified
+//! in the [FIPS 204]. ML-DSA-65 targets NIST security category 3 and is believed
+//! to be secure against attackers with a quantum computer.
+//!
+//! The API mirrors that of Ed25519: A 32-byte [`SecretKey`] is used to [`sign()`]
+//! a message, and the resulting [`Signature`] is checked with [`verify()`] against
+//! the [`PublicKey`] that belongs to the secret key.
+//!
+//! Signing uses the deterministic variant of ML-DSA with an empty context string,
+//! so the same message and secret key always produce the same signature.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key, which is the seed `ξ` of the FIPS 204 key generation.
+//! - `public_key`: The public key that belongs to `secret_key`.
+//! - `message`: The message to sign or verify.
+//! - `signature`: The signature of `message`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of a key or signature passed to `from_slice()` is incorrect.
+//! - The `signature` is not valid for `message` and `public_key`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`SecretKey::generate()`]
+//!   or [`KeyPair::generate()`].
+//!
+//! # Security:
+//! - Signatures are bound to the public key and the message, but the same message signed
+//!   twice gives the same signature. If signatures must not reveal that two messages were
+//!   equal, include a random value in the message.
+//! - ML-DSA signatures are 3309 bytes and public keys are 1952 bytes, which is
+//!   considerably larger than Ed25519.
+//! - Signing takes a variable number of iterations, which does not depend on the secret key
+//!   in a way that is useful to an attacker.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::sig::mldsa65::{self, KeyPair};
+//!
+//! let kp = KeyPair::generate();
+//! let firmware = b"firmware image";
+//!
+//! let signature = mldsa65::sign(kp.private(), firmware)?;
+//! assert!(mldsa65::verify(&signature, kp.public(), firmware).is_ok());
+//! assert!(mldsa65::verify(&signature, kp.public(), b"other image").is_err());
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [FIPS 204]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.204.pdf
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::sha3::{shake256, Shake128, Shake256};
+use crate::util::Fingerprint;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// The size of an ML-DSA-65 secret key (the seed of the key generation).
+pub const MLDSA65_SECRETKEYSIZE: usize = 32;
+
+/// The size of an ML-DSA-65 public key.
+pub const MLDSA65_PUBLICKEYSIZE: usize = 32 + 320 * K;
+
+/// The size of an ML-DSA-65 signature.
+pub const MLDSA65_SIGNATURESIZE: usize = C_TILDE_BYTES + 640 * L + OMEGA + K;
+
+/// The modulus q.
+const Q: u32 = 8380417;
+
+/// The number of coefficients in a polynomial.
+const N: usize = 256;
+
+/// The number of dropped bits from t.
+const D: usize = 13;
+
+/// The ML-DSA-65 parameters, from the FIPS 204, Section 4.
+const TAU: usize = 49;
+const C_TILDE_BYTES: usize = 48;
+const GAMMA1: u32 = 1 << 19;
+const GAMMA2: u32 = (Q - 1) / 32;
+const K: usize = 6;
+const L: usize = 5;
+const ETA: u32 = 4;
+const BETA: u32 = 196;
+const OMEGA: usize = 55;
+
+/// -q^-1 mod 2^32
+const QINV_NEG: u32 = 4236238847;
+
+/// 2^64 mod q
+const R2: u32 = 2365951;
+
+/// 256^-1 * 2^32 mod q
+const N_INV_MONT: u32 = 16382;
+
+/// zeta^BitRev8(i) * 2^32 mod q, for i in 0..256.
+const ZETAS_MONT: [u32; N] = [
+    4193792, 25847, 5771523, 7861508, 237124, 7602457, 7504169, 466468, 1826347, 2353451, 8021166,
+    6288512, 3119733, 5495562, 3111497, 2680103, 2725464, 1024112, 7300517, 3585928, 7830929,
+    7260833, 2619752, 6271868, 6262231, 4520680, 6980856, 5102745, 1757237, 8360995, 4010497,
+    280005, 2706023, 95776, 3077325, 3530437, 6718724, 4788269, 5842901, 3915439, 4519302, 5336701,
+    3574422, 5512770, 3539968, 8079950, 2348700, 7841118, 6681150, 6736599, 3505694, 4558682,
+    3507263, 6239768, 6779997, 3699596, 811944, 531354, 954230, 3881043, 3900724, 5823537, 2071892,
+    5582638, 4450022, 6851714, 4702672, 5339162, 6927966, 3475950, 2176455, 6795196, 7122806,
+    1939314, 4296819, 7380215, 5190273, 5223087, 4747489, 126922, 3412210, 7396998, 2147896,
+    2715295, 5412772, 4686924, 7969390, 5903370, 7709315, 7151892, 8357436, 7072248, 7998430,
+    1349076, 1852771, 6949987, 5037034, 264944, 508951, 3097992, 44288, 7280319, 904516, 3958618,
+    4656075, 8371839, 1653064, 5130689, 2389356, 8169440, 759969, 7063561, 189548, 4827145,
+    3159746, 6529015, 5971092, 8202977, 1315589, 1341330, 1285669, 6795489, 7567685, 6940675,
+    5361315, 4499357, 4751448, 3839961, 2091667, 3407706, 2316500, 3817976, 5037939, 2244091,
+    5933984, 4817955, 266997, 2434439, 7144689, 3513181, 4860065, 4621053, 7183191, 5187039,
+    900702, 1859098, 909542, 819034, 495491, 6767243, 8337157, 7857917, 7725090, 5257975, 2031748,
+    3207046, 4823422, 7855319, 7611795, 4784579, 342297, 286988, 5942594, 4108315, 3437287,
+    5038140, 1735879, 203044, 2842341, 2691481, 5790267, 1265009, 4055324, 1247620, 2486353,
+    1595974, 4613401, 1250494, 2635921, 4832145, 5386378, 1869119, 1903435, 7329447, 7047359,
+    1237275, 5062207, 6950192, 7929317, 1312455, 3306115, 6417775, 7100756, 1917081, 5834105,
+    7005614, 1500165, 777191, 2235880, 3406031, 7838005, 5548557, 6709241, 6533464, 5796124,
+    4656147, 594136, 4603424, 6366809, 2432395, 2454455, 8215696, 1957272, 3369112, 185531,
+    7173032, 5196991, 162844, 1616392, 3014001, 810149, 1652634, 4686184, 6581310, 5341501,
+    3523897, 3866901, 269760, 2213111, 7404533, 1717735, 472078, 7953734, 1723600, 6577327,
+    1910376, 6712985, 7276084, 8119771, 4546524, 5441381, 6144432, 7959518, 6094090, 183443,
+    7403526, 1612842, 4834730, 7826001, 3919660, 8332111, 7018208, 3937738, 1400424, 7534263,
+    1976782,
+];
+
+/// Subtract q from `x` if `x >= q`, in constant time. `x` must be less than 2q.
+fn csubq(x: u32) -> u32 {
+    debug_assert!(x < 2 * Q);
+    let r = x.wrapping_sub(Q);
+    r.wrapping_add(Q & 0u32.wrapping_sub(r >> 31))
+}
+
+/// Compute `x` * 2^-32 mod q, in constant time. `x` must be less than q * 2^32.
+fn mont_reduce(x: u64) -> u32 {
+    let m = (x as u32).wrapping_mul(QINV_NEG);
+    csubq(((x + (m as u64) * (Q as u64)) >> 32) as u32)
+}
+
+fn fq_add(a: u32, b: u32) -> u32 {
+    csubq(a + b)
+}
+
+fn fq_sub(a: u32, b: u32) -> u32 {
+    csubq(a + Q - b)
+}
+
+/// Multiply `a` by `b_mont`, which is in the Montgomery domain.
+fn fq_mul_mont(a: u32, b_mont: u32) -> u32 {
+    mont_reduce((a as u64) * (b_mont as u64))
+}
+
+fn fq_mul(a: u32, b: u32) -> u32 {
+    fq_mul_mont(mont_reduce((a as u64) * (b as u64)), R2)
+}
+
+/// Map `x`, with `-q < x < q`, to [0, q).
+fn fq_from_signed(x: i32) -> u32 {
+    (x as u32).wrapping_add(Q & ((x >> 31) as u32))
+}
+
+/// The absolute value of the centered representative of `x`, in constant time.
+fn centered_abs(x: u32) -> u32 {
+    let mask = 0u32.wrapping_sub(((Q - 1) / 2).wrapping_sub(x) >> 31);
+    (x & !mask) | (Q.wrapping_sub(x) & mask)
+}
+
+/// Decompose, from the FIPS 204, Algorithm 36, in constant time.
+/// Returns (r1, r0) with r0 in [-gamma2, gamma2].
+fn decompose(r: u32) -> (u32, i32) {
+    let mut r1 = (r as i32 + 127) >> 7;
+    r1 = (r1 * 1025 + (1 << 21)) >> 22;
+    r1 &= 15;
+
+    let mut r0 = r as i32 - r1 * 2 * GAMMA2 as i32;
+    r0 -= (((Q as i32 - 1) / 2 - r0) >> 31) & Q as i32;
+
+    (r1 as u32, r0)
+}
+
+/// UseHint, from the FIPS 204, Algorithm 40.
+fn use_hint(hint: bool, r: u32) -> u32 {
+    let (r1, r0) = decompose(r);
+    match (hint, r0 > 0) {
+        (false, _) => r1,
+        (true, true) => (r1 + 1) & 15,
+        (true, false) => (r1 + 15) & 15,
+    }
+}
+
+#[derive(Clone, Copy)]
+/// A polynomial in R_q, with each coefficient in [0, q).
+struct Poly([u32; N]);
+
+impl Zeroize for Poly {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Poly {
+    const ZERO: Self = Self([0u32; N]);
+
+    fn add(&self, other: &Self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, (a, b)) in ret.0.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            *r = fq_add(*a, *b);
+        }
+
+        ret
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, (a, b)) in ret.0.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            *r = fq_sub(*a, *b);
+        }
+
+        ret
+    }
+
+    /// The NTT, from the FIPS 204, Algorithm 41.
+    fn ntt(&self) -> Self {
+        let mut w = *self;
+        let mut m = 0;
+        let mut len = 128;
+        while len >= 1 {
+            for start in (0..N).step_by(2 * len) {
+                m += 1;
+                let zeta = ZETAS_MONT[m];
+                for j in start..start + len {
+                    let t = fq_mul_mont(w.0[j + len], zeta);
+                    w.0[j + len] = fq_sub(w.0[j], t);
+                    w.0[j] = fq_add(w.0[j], t);
+                }
+            }
+            len /= 2;
+        }
+
+        w
+    }
+
+    /// The inverse NTT, from the FIPS 204, Algorithm 42.
+    fn ntt_inverse(&self) -> Self {
+        let mut w = *self;
+        let mut m = N;
+        let mut len = 1;
+        while len < N {
+            for start in (0..N).step_by(2 * len) {
+                m -= 1;
+                let zeta = Q - ZETAS_MONT[m];
+                for j in start..start + len {
+                    let t = w.0[j];
+                    w.0[j] = fq_add(t, w.0[j + len]);
+                    w.0[j + len] = fq_mul_mont(fq_sub(t, w.0[j + len]), zeta);
+                }
+            }
+            len *= 2;
+        }
+
+        for coeff in w.0.iter_mut() {
+            *coeff = fq_mul_mont(*coeff, N_INV_MONT);
+        }
+
+        w
+    }
+
+    /// Multiply two polynomials in the NTT domain.
+    fn pointwise(&self, other: &Self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, (a, b)) in ret.0.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+            *r = fq_mul(*a, *b);
+        }
+
+        ret
+    }
+
+    /// Compute the inner product of two vectors in the NTT domain.
+    fn inner_product(f: &[Self], g: &[Self]) -> Self {
+        debug_assert_eq!(f.len(), g.len());
+        let mut ret = Self::ZERO;
+        for (a, b) in f.iter().zip(g.iter()) {
+            ret = ret.add(&a.pointwise(b));
+        }
+
+        ret
+    }
+
+    /// Return `true` if the infinity norm of `self` is at least `bound`.
+    fn exceeds(&self, bound: u32) -> bool {
+        let mut ret = 0u32;
+        for coeff in self.0.iter() {
+            ret |= (bound - 1).wrapping_sub(centered_abs(*coeff)) >> 31;
+        }
+
+        ret == 1
+    }
+
+    /// SimpleBitPack, from the FIPS 204, Algorithm 16. Each coefficient must be less than 2^bits.
+    fn bit_pack(&self, bits: usize, dst_out: &mut [u8]) {
+        debug_assert_eq!(dst_out.len(), 32 * bits);
+        let mut acc: u64 = 0;
+        let mut acc_bits = 0;
+        let mut idx = 0;
+        for coeff in self.0.iter() {
+            debug_assert!((*coeff as u64) < (1 << bits));
+            acc |= (*coeff as u64) << acc_bits;
+            acc_bits += bits;
+            while acc_bits >= 8 {
+                dst_out[idx] = acc as u8;
+                idx += 1;
+                acc >>= 8;
+                acc_bits -= 8;
+            }
+        }
+    }
+
+    /// The inverse of [`Self::bit_pack()`]. The coefficients must be reduced by the caller.
+    fn bit_unpack(src: &[u8], bits: usize) -> [u32; N] {
+        debug_assert_eq!(src.len(), 32 * bits);
+        let mask = (1u64 << bits) - 1;
+        let mut ret = [0u32; N];
+        let mut acc: u64 = 0;
+        let mut acc_bits = 0;
+        let mut coeffs = ret.iter_mut();
+        for byte in src.iter() {
+            acc |= (*byte as u64) << acc_bits;
+            acc_bits += 8;
+            while acc_bits >= bits {
+                // The lengths match, so there is always a coefficient left.
+                if let Some(coeff) = coeffs.next() {
+                    *coeff = (acc & mask) as u32;
+                }
+                acc >>= bits;
+                acc_bits -= bits;
+            }
+        }
+
+        ret
+    }
+
+    /// Encode z with BitPack(z, gamma1 - 1, gamma1), from the FIPS 204, Algorithm 17.
+    fn pack_z(&self, dst_out: &mut [u8]) {
+        let mut packed = Self::ZERO;
+        for (p, coeff) in packed.0.iter_mut().zip(self.0.iter()) {
+            *p = fq_sub(GAMMA1, *coeff);
+        }
+        packed.bit_pack(20, dst_out);
+    }
+
+    /// Decode z with BitUnpack(z, gamma1 - 1, gamma1), from the FIPS 204, Algorithm 19.
+    fn unpack_z(src: &[u8]) -> Self {
+        let mut ret = Self(Self::bit_unpack(src, 20));
+        for coeff in ret.0.iter_mut() {
+            *coeff = fq_sub(GAMMA1, *coeff);
+        }
+
+        ret
+    }
+
+    /// Return HighBits(r) of each coefficient.
+    fn high_bits(&self) -> Self {
+        let mut ret = Self::ZERO;
+        for (r, coeff) in ret.0.iter_mut().zip(self.0.iter()) {
+            *r = decompose(*coeff).0;
+        }
+
+        ret
+    }
+
+    /// RejNTTPoly, from the FIPS 204, Algorithm 30, using SHAKE128(rho || s || r).
+    ///
+    /// This runs in variable time, but only depends on public data.
+    fn rej_ntt_poly(rho: &[u8], s: u8, r: u8) -> Self {
+        let mut xof = Shake128::new();
+        xof.absorb(rho);
+        xof.absorb(&[s, r]);
+
+        let mut ret = Self::ZERO;
+        let mut idx = 0;
+        let mut block = [0u8; 168];
+        while idx < N {
+            xof.squeeze(&mut block);
+            for c in block.chunks_exact(3) {
+                let coeff = (c[0] as u32) | ((c[1] as u32) << 8) | (((c[2] & 0x7f) as u32) << 16);
+                if coeff < Q && idx < N {
+                    ret.0[idx] = coeff;
+                    idx += 1;
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// RejBoundedPoly, from the FIPS 204, Algorithm 31, using SHAKE256(rho || nonce).
+    fn rej_bounded_poly(rho: &[u8], nonce: u16) -> Self {
+        let mut xof = Shake256::new();
+        xof.absorb(rho);
+        xof.absorb(&nonce.to_le_bytes());
+
+        let mut ret = Self::ZERO;
+        let mut idx = 0;
+        let mut block = [0u8; 136];
+        while idx < N {
+            xof.squeeze(&mut block);
+            for byte in block.iter() {
+                for z in [byte & 0x0f, byte >> 4].iter() {
+                    // CoeffFromHalfByte, from the FIPS 204, Algorithm 15, for eta = 4.
+                    if (*z as u32) < 2 * ETA + 1 && idx < N {
+                        ret.0[idx] = fq_sub(ETA, *z as u32);
+                        idx += 1;
+                    }
+                }
+            }
+        }
+        block.zeroize();
+
+        ret
+    }
+
+    /// SampleInBall, from the FIPS 204, Algorithm 29.
+    fn sample_in_ball(seed: &[u8]) -> Self {
+        let mut xof = Shake256::new();
+        xof.absorb(seed);
+        let mut signs = [0u8; 8];
+        xof.squeeze(&mut signs);
+        let mut signs = u64::from_le_bytes(signs);
+
+        let mut ret = Self::ZERO;
+        let mut j = [0u8; 1];
+        for i in (N - TAU)..N {
+            loop {
+                xof.squeeze(&mut j);
+                if (j[0] as usize) <= i {
+                    break;
+                }
+            }
+            let j = j[0] as usize;
+            ret.0[i] = ret.0[j];
+            ret.0[j] = if signs & 1 == 1 { Q - 1 } else { 1 };
+            signs >>= 1;
+        }
+
+        ret
+    }
+}
+
+/// ExpandA, from the FIPS 204, Algorithm 32.
+fn expand_a(rho: &[u8]) -> [[Poly; L]; K] {
+    let mut a_hat = [[Poly::ZERO; L]; K];
+    for (r, row) in a_hat.iter_mut().enumerate() {
+        for (s, entry) in row.iter_mut().enumerate() {
+            *entry = Poly::rej_ntt_poly(rho, s as u8, r as u8);
+        }
+    }
+
+    a_hat
+}
+
+/// ExpandMask, from the FIPS 204, Algorithm 34.
+fn expand_mask(rho: &[u8], kappa: u16) -> [Poly; L] {
+    let mut y = [Poly::ZERO; L];
+    let mut buf = [0u8; 640];
+    for (r, poly) in y.iter_mut().enumerate() {
+        shake256(&[rho, &(kappa + r as u16).to_le_bytes()], &mut buf);
+        *poly = Poly::unpack_z(&buf);
+    }
+    buf.zeroize();
+
+    y
+}
+
+/// Compute mu = H(tr || 0 || 0 || message), with an empty context string as in the
+/// FIPS 204, Algorithm 2 and 3.
+fn message_representative(tr: &[u8], message: &[u8]) -> [u8; 64] {
+    let mut mu = [0u8; 64];
+    shake256(&[tr, &[0u8, 0u8], message], &mut mu);
+
+    mu
+}
+
+/// Compute the commitment hash c~ = H(mu || w1Encode(w1)), from the FIPS 204, Algorithm 28.
+fn commitment_hash(mu: &[u8], w1: &[Poly; K]) -> [u8; C_TILDE_BYTES] {
+    let mut encoded = [0u8; 128 * K];
+    for (poly, chunk) in w1.iter().zip(encoded.chunks_exact_mut(128)) {
+        poly.bit_pack(4, chunk);
+    }
+    let mut c_tilde = [0u8; C_TILDE_BYTES];
+    shake256(&[mu, &encoded], &mut c_tilde);
+
+    c_tilde
+}
+
+/// The key material that ML-DSA.KeyGen_internal, from the FIPS 204, Algorithm 6,
+/// derives from the seed xi. The vectors are kept in the NTT domain.
+struct ExpandedSecretKey {
+    a_hat: [[Poly; L]; K],
+    s1_hat: [Poly; L],
+    s2_hat: [Poly; K],
+    t0_hat: [Poly; K],
+    key: [u8; 32],
+    tr: [u8; 64],
+    public: [u8; MLDSA65_PUBLICKEYSIZE],
+}
+
+impl Drop for ExpandedSecretKey {
+    fn drop(&mut self) {
+        self.s1_hat.zeroize();
+        self.s2_hat.zeroize();
+        self.t0_hat.zeroize();
+        self.key.zeroize();
+    }
+}
+
+impl ExpandedSecretKey {
+    fn from_seed(xi: &[u8]) -> Self {
+        let mut expanded = [0u8; 128];
+        shake256(&[xi, &[K as u8, L as u8]], &mut expanded);
+        let (rho, rest) = expanded.split_at(32);
+        let (rho_prime, key) = rest.split_at(64);
+
+        let mut ret = Self {
+            a_hat: expand_a(rho),
+            s1_hat: [Poly::ZERO; L],
+            s2_hat: [Poly::ZERO; K],
+            t0_hat: [Poly::ZERO; K],
+            key: [0u8; 32],
+            tr: [0u8; 64],
+            public: [0u8; MLDSA65_PUBLICKEYSIZE],
+        };
+        ret.key.copy_from_slice(key);
+
+        let mut s1 = [Poly::ZERO; L];
+        for (r, (s, s_hat)) in s1.iter_mut().zip(ret.s1_hat.iter_mut()).enumerate() {
+            *s = Poly::rej_bounded_poly(rho_prime, r as u16);
+            *s_hat = s.ntt();
+        }
+
+        let (pk_rho, pk_t1) = ret.public.split_at_mut(32);
+        pk_rho.copy_from_slice(rho);
+        for (i, chunk) in pk_t1.chunks_exact_mut(320).enumerate() {
+            let mut s2 = Poly::rej_bounded_poly(rho_prime, (L + i) as u16);
+            let mut t = Poly::inner_product(&ret.a_hat[i], &ret.s1_hat)
+                .ntt_inverse()
+                .add(&s2);
+
+            // Power2Round, from the FIPS 204, Algorithm 35.
+            let mut t1 = Poly::ZERO;
+            let mut t0 = Poly::ZERO;
+            for (coeff, (hi, lo)) in t.0.iter().zip(t1.0.iter_mut().zip(t0.0.iter_mut())) {
+                *hi = (coeff + (1 << (D - 1)) - 1) >> D;
+                *lo = fq_from_signed(*coeff as i32 - (*hi << D) as i32);
+            }
+            t1.bit_pack(10, chunk);
+
+            ret.s2_hat[i] = s2.ntt();
+            ret.t0_hat[i] = t0.ntt();
+            s2.zeroize();
+            t.zeroize();
+            t0.zeroize();
+        }
+        shake256(&[&ret.public], &mut ret.tr);
+
+        s1.zeroize();
+        expanded.zeroize();
+
+        ret
+    }
+
+    /// ML-DSA.Sign_internal, from the FIPS 204, Algorithm 7, with rnd = {0}^32.
+    fn sign(&self, message: &[u8], signature: &mut [u8; MLDSA65_SIGNATURESIZE]) {
+        let mut mu = message_representative(&self.tr, message);
+        let mut rho_prime = [0u8; 64];
+        shake256(&[&self.key, &[0u8; 32], &mu], &mut rho_prime);
+
+        let mut kappa: u16 = 0;
+        loop {
+            let mut y = expand_mask(&rho_prime, kappa);
+            // NOTE: Each iteration succeeds with a probability of about 1/5, so kappa
+            // overflowing is not a concern in practice.
+            kappa = kappa.wrapping_add(L as u16);
+
+            let mut y_hat = [Poly::ZERO; L];
+            for (p_hat, p) in y_hat.iter_mut().zip(y.iter()) {
+                *p_hat = p.ntt();
+            }
+            let mut w = [Poly::ZERO; K];
+            let mut w1 = [Poly::ZERO; K];
+            for ((w_i, w1_i), row) in w.iter_mut().zip(w1.iter_mut()).zip(self.a_hat.iter()) {
+                *w_i = Poly::inner_product(row, &y_hat).ntt_inverse();
+                *w1_i = w_i.high_bits();
+            }
+
+            let c_tilde = commitment_hash(&mu, &w1);
+            let c_hat = Poly::sample_in_ball(&c_tilde).ntt();
+
+            let mut z = [Poly::ZERO; L];
+            let mut is_rejected = false;
+            for ((z_j, y_j), s1_hat) in z.iter_mut().zip(y.iter()).zip(self.s1_hat.iter()) {
+                *z_j = y_j.add(&c_hat.pointwise(s1_hat).ntt_inverse());
+                is_rejected |= z_j.exceeds(GAMMA1 - BETA);
+            }
+
+            let mut hint = [[false; N]; K];
+            let mut hint_count = 0;
+            for (i, w_i) in w.iter_mut().enumerate() {
+                // w - cs2
+                *w_i = w_i.sub(&c_hat.pointwise(&self.s2_hat[i]).ntt_inverse());
+                let ct0 = c_hat.pointwise(&self.t0_hat[i]).ntt_inverse();
+                is_rejected |= ct0.exceeds(GAMMA2);
+
+                for (j, (r, ct0_j)) in w_i.0.iter().zip(ct0.0.iter()).enumerate() {
+                    let (r1, r0) = decompose(*r);
+                    is_rejected |= r0.unsigned_abs() >= GAMMA2 - BETA;
+                    // MakeHint(-ct0, w - cs2 + ct0), from the FIPS 204, Algorithm 39.
+                    let h = decompose(fq_add(*r, *ct0_j)).0 != r1;
+                    hint[i][j] = h;
+                    hint_count += h as usize;
+                }
+            }
+            is_rejected |= hint_count > OMEGA;
+
+            y.zeroize();
+            y_hat.zeroize();
+            w.zeroize();
+
+            if is_rejected {
+                z.zeroize();
+                continue;
+            }
+
+            // sigEncode, from the FIPS 204, Algorithm 26.
+            let (sig_c, rest) = signature.split_at_mut(C_TILDE_BYTES);
+            let (sig_z, sig_h) = rest.split_at_mut(640 * L);
+            sig_c.copy_from_slice(&c_tilde);
+            for (z_j, chunk) in z.iter().zip(sig_z.chunks_exact_mut(640)) {
+                z_j.pack_z(chunk);
+            }
+            hint_bit_pack(&hint, sig_h);
+
+            z.zeroize();
+            break;
+        }
+
+        mu.zeroize();
+        rho_prime.zeroize();
+    }
+}
+
+/// HintBitPack, from the FIPS 204, Algorithm 20.
+fn hint_bit_pack(hint: &[[bool; N]; K], dst_out: &mut [u8]) {
+    debug_assert_eq!(dst_out.len(), OMEGA + K);
+    let mut idx = 0;
+    for (i, h_i) in hint.iter().enumerate() {
+        for (j, h) in h_i.iter().enumerate() {
+            if *h {
+                dst_out[idx] = j as u8;
+                idx += 1;
+            }
+        }
+        dst_out[OMEGA + i] = idx as u8;
+    }
+}
+
+/// HintBitUnpack, from the FIPS 204, Algorithm 21. Returns `None` if the encoding is malformed.
+fn hint_bit_unpack(src: &[u8]) -> Option<[[bool; N]; K]> {
+    debug_assert_eq!(src.len(), OMEGA + K);
+    let mut hint = [[false; N]; K];
+    let mut idx = 0;
+    for (i, h_i) in hint.iter_mut().enumerate() {
+        let end = src[OMEGA + i] as usize;
+        if end < idx || end > OMEGA {
+            return None;
+        }
+        let first = idx;
+        while idx < end {
+            if idx > first && src[idx - 1] >= src[idx] {
+                return None;
+            }
+            h_i[src[idx] as usize] = true;
+            idx += 1;
+        }
+    }
+
+    if src[idx..OMEGA].iter().any(|b| *b != 0) {
+        return None;
+    }
+
+    Some(hint)
+}
+
+/// ML-DSA.Verify_internal, from the FIPS 204, Algorithm 8.
+fn verify_internal(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    let (c_tilde, rest) = signature.split_at(C_TILDE_BYTES);
+    let (sig_z, sig_h) = rest.split_at(640 * L);
+
+    let hint = match hint_bit_unpack(sig_h) {
+        Some(hint) => hint,
+        None => return false,
+    };
+    let mut z_hat = [Poly::ZERO; L];
+    for (z_j, chunk) in z_hat.iter_mut().zip(sig_z.chunks_exact(640)) {
+        let z = Poly::unpack_z(chunk);
+        if z.exceeds(GAMMA1 - BETA) {
+            return false;
+        }
+        *z_j = z.ntt();
+    }
+
+    let (rho, pk_t1) = public_key.split_at(32);
+    let a_hat = expand_a(rho);
+    let mut tr = [0u8; 64];
+    shake256(&[public_key], &mut tr);
+    let mu = message_representative(&tr, message);
+    let c_hat = Poly::sample_in_ball(c_tilde).ntt();
+
+    let mut w1 = [Poly::ZERO; K];
+    for (i, (w1_i, chunk)) in w1.iter_mut().zip(pk_t1.chunks_exact(320)).enumerate() {
+        let mut t1 = Poly(Poly::bit_unpack(chunk, 10));
+        for coeff in t1.0.iter_mut() {
+            *coeff <<= D;
+        }
+        let w_approx = Poly::inner_product(&a_hat[i], &z_hat)
+            .sub(&c_hat.pointwise(&t1.ntt()))
+            .ntt_inverse();
+        for (w1_ij, (h, r)) in w1_i.0.iter_mut().zip(hint[i].iter().zip(w_approx.0.iter())) {
+            *w1_ij = use_hint(*h, *r);
+        }
+    }
+
+    bool::from(commitment_hash(&mu, &w1).ct_eq(c_tilde))
+}
+
+construct_secret_key! {
+    /// A type to represent the secret key of ML-DSA-65. This is the 32-byte seed
+    /// that the FIPS 204 key generation expands into the signing key.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, MLDSA65_SECRETKEYSIZE, MLDSA65_SECRETKEYSIZE, MLDSA65_SECRETKEYSIZE)
+}
+
+impl_from_trait!(SecretKey, MLDSA65_SECRETKEYSIZE);
+
+construct_public! {
+    /// A type to represent the public key of ML-DSA-65.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 1952 bytes.
+    (PublicKey, test_public_key, MLDSA65_PUBLICKEYSIZE, MLDSA65_PUBLICKEYSIZE)
+}
+
+impl From<&SecretKey> for PublicKey {
+    fn from(secret_key: &SecretKey) -> Self {
+        let expanded = ExpandedSecretKey::from_seed(secret_key.unprotected_as_bytes());
+
+        Self::from(expanded.public)
+    }
+}
+
+impl_from_trait!(PublicKey, MLDSA65_PUBLICKEYSIZE);
+
+impl PublicKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the [`Fingerprint`] of this public key, for display and pinning.
+    pub fn fingerprint(&self) -> Result<Fingerprint, UnknownCryptoError> {
+        Fingerprint::of(self.as_ref())
+    }
+}
+
+construct_public! {
+    /// A type to represent a signature of ML-DSA-65.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 3309 bytes.
+    (Signature, test_signature, MLDSA65_SIGNATURESIZE, MLDSA65_SIGNATURESIZE)
+}
+
+impl_from_trait!(Signature, MLDSA65_SIGNATURESIZE);
+
+/// A key pair of ML-DSA-65, containing a [`PublicKey`] and a [`SecretKey`].
+pub struct KeyPair {
+    public: PublicKey,
+    private: SecretKey,
+}
+
+impl KeyPair {
+    #[cfg(feature = "safe_api")]
+    /// Randomly generate a key pair using a CSPRNG. Not available in `no_std` context.
+    pub fn generate() -> Self {
+        Self::from(SecretKey::generate())
+    }
+
+    /// Return the [`PublicKey`].
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// Return the [`SecretKey`].
+    pub fn private(&self) -> &SecretKey {
+        &self.private
+    }
+}
+
+impl From<SecretKey> for KeyPair {
+    fn from(secret_key: SecretKey) -> Self {
+        Self {
+            public: PublicKey::from(&secret_key),
+            private: secret_key,
+        }
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Sign `message` with `secret_key`.
+pub fn sign(secret_key: &SecretKey, message: &[u8]) -> Result<Signature, UnknownCryptoError> {
+    let expanded = ExpandedSecretKey::from_seed(secret_key.unprotected_as_bytes());
+    let mut signature = [0u8; MLDSA65_SIGNATURESIZE];
+    expanded.sign(message, &mut signature);
+
+    Ok(Signature::from(signature))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify that `signature` is a valid signature of `message` by `public_key`.
+pub fn verify(
+    signature: &Signature,
+    public_key: &PublicKey,
+    message: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    if verify_internal(public_key.as_ref(), message, signature.as_ref()) {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+impl super::Signer<Signature> for SecretKey {
+    fn sign(&self, message: &[u8]) -> Result<Signature, UnknownCryptoError> {
+        sign(self, message)
+    }
+}
+
+impl super::Signer<Signature> for KeyPair {
+    fn sign(&self, message: &[u8]) -> Result<Signature, UnknownCryptoError> {
+        sign(&self.private, message)
+    }
+}
+
+impl super::Verifier<Signature> for PublicKey {
+    fn verify(&self, signature: &Signature, message: &[u8]) -> Result<(), UnknownCryptoError> {
+        verify(signature, self, message)
+    }
+}
+
+impl super::Verifier<Signature> for KeyPair {
+    fn verify(&self, signature: &Signature, message: &[u8]) -> Result<(), UnknownCryptoError> {
+        verify(signature, &self.public, message)
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::hash::sha2::sha256::Sha256;
+
+    #[test]
+    fn test_sign_deterministic() {
+        // The public key and signature have been verified with the Python `cryptography` package.
+        let sk = SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]);
+        let pk = PublicKey::from(&sk);
+        let sig = sign(&sk, b"abc").unwrap();
+        assert_eq!(
+            Sha256::digest(pk.as_ref()).unwrap().as_ref(),
+            &hex::decode("d3a1e51ecf491b79ca7691bd269271f8d8e8d94313a6abcc6c8ae8bc34b5f9aa")
+                .unwrap()[..]
+        );
+        assert_eq!(
+            Sha256::digest(sig.as_ref()).unwrap().as_ref(),
+            &hex::decode("c08280d756fa903ecb4c2b495a65a81674e71e1c2a3c1a377fd9bd4963e5689f")
+                .unwrap()[..]
+        );
+        assert!(verify(&sig, &pk, b"abc").is_ok());
+    }
+
+    #[test]
+    fn test_verify_wrong_key_or_message() {
+        let sk = SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]);
+        let other_pk = PublicKey::from(&SecretKey::from([8u8; MLDSA65_SECRETKEYSIZE]));
+        let sig = sign(&sk, b"abc").unwrap();
+
+        let err = verify(&sig, &other_pk, b"abc").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MacMismatch);
+        assert!(verify(&sig, &PublicKey::from(&sk), b"abd").is_err());
+        assert!(verify(&sig, &PublicKey::from(&sk), b"").is_err());
+    }
+
+    #[test]
+    fn test_verify_z_out_of_bounds() {
+        let sk = SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]);
+        let pk = PublicKey::from(&sk);
+        let sig = sign(&sk, b"abc").unwrap();
+
+        // The first coefficient of z is encoded as gamma1 - z, so all-zero bytes mean
+        // z = gamma1, which is above the bound.
+        let mut bad = [0u8; MLDSA65_SIGNATURESIZE];
+        bad.copy_from_slice(sig.as_ref());
+        bad[C_TILDE_BYTES..C_TILDE_BYTES + 3].copy_from_slice(&[0u8; 3]);
+        assert!(verify(&Signature::from(bad), &pk, b"abc").is_err());
+    }
+
+    #[test]
+    fn test_signer_verifier_traits() {
+        use crate::hazardous::sig::{Signer, Verifier};
+
+        fn sign_generic<S, K: Signer<S>>(key: &K) -> S {
+            key.sign(b"abc").unwrap()
+        }
+
+        fn verify_generic<S, K: Verifier<S>>(key: &K, signature: &S, message: &[u8]) -> bool {
+            key.verify(signature, message).is_ok()
+        }
+
+        let kp = KeyPair::from(SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]));
+        let expected = sign(kp.private(), b"abc").unwrap();
+        assert_eq!(sign_generic(kp.private()), expected);
+        assert_eq!(sign_generic(&kp), expected);
+
+        assert!(verify_generic(kp.public(), &expected, b"abc"));
+        assert!(verify_generic(&kp, &expected, b"abc"));
+        assert!(!verify_generic(kp.public(), &expected, b"abd"));
+        assert!(!verify_generic(&kp, &expected, b"abd"));
+
+        // Usable as trait objects, to pick the scheme at runtime.
+        let verifiers: [&dyn Verifier<Signature>; 2] = [kp.public(), &kp];
+        assert!(verifiers
+            .iter()
+            .all(|v| v.verify(&expected, b"abc").is_ok()));
+    }
+
+    #[test]
+    fn test_keypair() {
+        let kp = KeyPair::from(SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]));
+        assert_eq!(
+            kp.public(),
+            &PublicKey::from(&SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]))
+        );
+        let sig = sign(kp.private(), b"").unwrap();
+        assert!(verify(&sig, kp.public(), b"").is_ok());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let pk = PublicKey::from(&SecretKey::from([7u8; MLDSA65_SECRETKEYSIZE]));
+        let other = PublicKey::from(&SecretKey::from([8u8; MLDSA65_SECRETKEYSIZE]));
+        assert_eq!(
+            pk.fingerprint().unwrap(),
+            Fingerprint::of(pk.as_ref()).unwrap()
+        );
+        assert_ne!(pk.fingerprint().unwrap(), other.fingerprint().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_generate_unique() {
+        let kp1 = KeyPair::generate();
+        let kp2 = KeyPair::generate();
+        assert_ne!(kp1.public(), kp2.public());
+        assert_ne!(kp1.private(), kp2.private());
+
+        let sig = sign(kp1.private(), b"firmware").unwrap();
+        assert!(verify(&sig, kp1.public(), b"firmware").is_ok());
+        assert!(verify(&sig, kp2.public(), b"firmware").is_err());
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_sign_verify(message: Vec<u8>) -> bool {
+        let kp = KeyPair::generate();
+        let sig = sign(kp.private(), &message).unwrap();
+
+        verify(&sig, kp.public(), &message).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod private {
+    use super::*;
+
+    #[test]
+    fn test_field_arithmetic() {
+        for a in (0..Q).step_by(99991) {
+            for b in (0..Q).step_by(77773) {
+                assert_eq!(fq_mul(a, b) as u64, (a as u64 * b as u64) % Q as u64);
+                assert_eq!(fq_add(a, b), (a + b) % Q);
+                assert_eq!(fq_sub(a, b), (a + Q - b) % Q);
+            }
+        }
+        assert_eq!(fq_mul(Q - 1, Q - 1), 1);
+        assert_eq!(fq_from_signed(-1), Q - 1);
+        assert_eq!(fq_from_signed(1), 1);
+        assert_eq!(centered_abs(Q - 5), 5);
+        assert_eq!(centered_abs((Q - 1) / 2), (Q - 1) / 2);
+        assert_eq!(centered_abs(Q / 2 + 1), (Q - 1) / 2);
+    }
+
+    #[test]
+    fn test_ntt_roundtrip() {
+        let mut f = Poly::ZERO;
+        for (i, coeff) in f.0.iter_mut().enumerate() {
+            *coeff = ((i as u64 * 1_000_003) % Q as u64) as u32;
+        }
+        assert_eq!(f.ntt().ntt_inverse().0[..], f.0[..]);
+    }
+
+    #[test]
+    fn test_ntt_multiplication() {
+        // (1 + X) * X^255 = X^255 - 1, in Z_q[X]/(X^256 + 1).
+        let mut f = Poly::ZERO;
+        f.0[0] = 1;
+        f.0[1] = 1;
+        let mut g = Poly::ZERO;
+        g.0[255] = 1;
+
+        let product = f.ntt().pointwise(&g.ntt()).ntt_inverse();
+        let mut expected = Poly::ZERO;
+        expected.0[0] = Q - 1;
+        expected.0[255] = 1;
+        assert_eq!(product.0[..], expected.0[..]);
+    }
+
+    #[test]
+    fn test_decompose() {
+        for r in (0..Q)
+            .step_by(997)
+            .chain([0, GAMMA2, Q - GAMMA2, Q - 1].iter().copied())
+        {
+            let (r1, r0) = decompose(r);
+            assert!(r1 < 16);
+            assert!(r0 >= -(GAMMA2 as i32) && r0 <= GAMMA2 as i32);
+            assert_eq!(fq_add(r1 * 2 * GAMMA2, fq_from_signed(r0)), r);
+            // UseHint without a hint gives HighBits, with a hint an adjacent value.
+            assert_eq!(use_hint(false, r), r1);
+            let hinted = use_hint(true, r);
+            assert!(hinted == (r1 + 1) & 15 || hinted == (r1 + 15) & 15);
+        }
+    }
+
+    #[test]
+    fn test_exceeds() {
+        let mut f = Poly::ZERO;
+        assert!(!f.exceeds(1));
+        f.0[7] = Q - 10;
+        assert!(!f.exceeds(11));
+        assert!(f.exceeds(10));
+        f.0[7] = 10;
+        assert!(!f.exceeds(11));
+        assert!(f.exceeds(10));
+    }
+
+    #[test]
+    fn test_sample_in_ball() {
+        let c = Poly::sample_in_ball(&[1u8; C_TILDE_BYTES]);
+        let nonzero = c.0.iter().filter(|coeff| **coeff != 0).count();
+        assert_eq!(nonzero, TAU);
+        assert!(c.0.iter().all(|coeff| [0, 1, Q - 1].contains(coeff)));
+    }
+
+    #[test]
+    fn test_z_pack_unpack() {
+        let mut z = Poly::ZERO;
+        for (i, coeff) in z.0.iter_mut().enumerate() {
+            *coeff = fq_from_signed((i as i32 * 4099) - (GAMMA1 as i32 - 1));
+        }
+        let mut encoded = [0u8; 640];
+        z.pack_z(&mut encoded);
+        assert_eq!(Poly::unpack_z(&encoded).0[..], z.0[..]);
+    }
+
+    mod test_hint_encoding {
+        use super::*;
+
+        #[test]
+        fn test_roundtrip() {
+            let mut hint = [[false; N]; K];
+            hint[0][3] = true;
+            hint[0][200] = true;
+            hint[4][0] = true;
+            hint[5][255] = true;
+            let mut encoded = [0u8; OMEGA + K];
+            hint_bit_pack(&hint, &mut encoded);
+            assert_eq!(encoded[OMEGA..], [2, 2, 2, 2, 3, 4]);
+            assert_eq!(hint_bit_unpack(&encoded).unwrap()[..], hint[..]);
+        }
+
+        #[test]
+        fn test_too_many_hints() {
+            let mut encoded = [0u8; OMEGA + K];
+            encoded[OMEGA..].copy_from_slice(&[OMEGA as u8 + 1; K]);
+            assert!(hint_bit_unpack(&encoded).is_none());
+        }
+
+        #[test]
+        fn test_decreasing_count() {
+            let mut encoded = [0u8; OMEGA + K];
+            encoded[0] = 1;
+            encoded[1] = 2;
+            encoded[OMEGA..].copy_from_slice(&[2, 1, 2, 2, 2, 2]);
+            assert!(hint_bit_unpack(&encoded).is_none());
+        }
+
+        #[test]
+        fn test_unordered_indices() {
+            let mut encoded = [0u8; OMEGA + K];
+            encoded[0] = 2;
+            encoded[1] = 2;
+            encoded[OMEGA..].copy_from_slice(&[2; K]);
+            assert!(hint_bit_unpack(&encoded).is_none());
+
+            // The same index in different polynomials is fine.
+            encoded[OMEGA..].copy_from_slice(&[1, 2, 2, 2, 2, 2]);
+            assert!(hint_bit_unpack(&encoded).is_some());
+        }
+
+        #[test]
+        fn test_nonzero_padding() {
+            let mut encoded = [0u8; OMEGA + K];
+            encoded[OMEGA - 1] = 1;
+            assert!(hint_bit_unpack(&encoded).is_none());
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+/// ML-DSA-65 as specified in the [FIPS 204](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.204.pdf).
+pub mod mldsa65;
+
+/// WOTS+, a hash-based one-time signature scheme built on BLAKE2b.
+pub mod wots;
+
+use crate::errors::UnknownCryptoError;
+
+/// A secret key, or key pair, that produces signatures of type `S`.
+///
+/// Together with [`Verifier`], this allows applications to be generic over
+/// the signature scheme.
+///
+/// # Example:
+/// ```rust
+/// use orion::hazardous::sig::{mldsa65, Signer, Verifier};
+///
+/// fn sign_and_check<S, K: Signer<S> + Verifier<S>>(key: &K, message: &[u8]) -> bool {
+///     match key.sign(message) {
+///         Ok(signature) => key.verify(&signature, message).is_ok(),
+///         Err(_) => false,
+///     }
+/// }
+///
+/// let kp = mldsa65::KeyPair::from(mldsa65::SecretKey::from_slice(&[1u8; 32])?);
+/// assert!(sign_and_check(&kp, b"firmware image"));
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub trait Signer<S> {
+    /// Sign `message`.
+    fn sign(&self, message: &[u8]) -> Result<S, UnknownCryptoError>;
+}
+
+/// A public key, or key pair, that verifies signatures of type `S`.
+pub trait Verifier<S> {
+    /// Verify that `signature` is a valid signature of `message`. An error is returned
+    /// if it is not.
+    fn verify(&self, signature: &S, message: &[u8]) -> Result<(), UnknownCryptoError>;
+}
@@ -0,0 +1,479 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! A Winternitz one-time signature scheme (WOTS+) with `n = 32` and `w = 16`, using
+//! BLAKE2b-256. Its security only relies on the hash function, so it is believed to be
+//! secure against attackers with a quantum computer. It is a building block for
+//! structures such as chains of firmware updates, where each update carries the public
+//! key that the next one is signed with.
+//!
+//! A 32-byte [`SecretKey`] seed is expanded into 67 hash chains of length 16. The
+//! message digest and its checksum, as 67 base-16 digits, select how far along each
+//! chain the [`Signature`] reveals. The [`PublicKey`] is a public seed and the hash
+//! of the ends of all chains.
+//!
+//! Using the keyed BLAKE2b-256 as `PRF(seed, m)` and BLAKE2b-256 as `H`:
+//! - `pk_seed = PRF(seed, 0x00)` and the start of chain `i` is `PRF(seed, 0x01 || i)`.
+//! - One step `j` along chain `i` is `F(x) = H(0x02 || pk_seed || i || j || x)`.
+//! - The root is `H(0x03 || pk_seed || end_0 || .. || end_66)`.
+//! - The message digest is `H(0x04 || pk_seed || root || message)`. The checksum is
+//!   computed as in the [RFC 8391], Section 3.1.5.
+//!
+//! # Parameters:
+//! - `secret_key`: The one-time secret key, which is consumed when signing.
+//! - `public_key`: The public key that belongs to `secret_key`.
+//! - `message`: The message to sign or verify.
+//! - `signature`: The signature of `message`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of a key or signature passed to `from_slice()` is incorrect.
+//! - The `signature` is not valid for `message` and `public_key`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`SecretKey::generate()`]
+//!   or [`KeyPair::generate()`].
+//!
+//! # Security:
+//! - __**A secret key must only ever sign one message**__. Each signature reveals part
+//!   of the hash chains, and two signatures of different messages with the same key
+//!   allow forging signatures of further messages. [`sign()`] takes the [`SecretKey`] by
+//!   value so that it cannot accidentally be used again, but a key that is stored and
+//!   loaded again must be tracked by the application.
+//! - Signatures are 2144 bytes. Public keys are 64 bytes.
+//! - This construction is specific to orion and is not interoperable with other WOTS+
+//!   implementations, such as the one inside XMSS.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::sig::wots::{self, KeyPair};
+//!
+//! let kp = KeyPair::generate()?;
+//! let public_key = *kp.public();
+//! let firmware = b"firmware image";
+//!
+//! let signature = wots::sign(kp.into_private(), firmware)?;
+//! assert!(wots::verify(&signature, &public_key, firmware).is_ok());
+//! assert!(wots::verify(&signature, &public_key, b"other image").is_err());
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [RFC 8391]: https://www.rfc-editor.org/rfc/rfc8391.html
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::blake2b::{self, Blake2b};
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// The size of a WOTS+ secret key (the seed that the chains are derived from).
+pub const WOTS_SECRETKEYSIZE: usize = 32;
+
+/// The size of a WOTS+ public key.
+pub const WOTS_PUBLICKEYSIZE: usize = 2 * N;
+
+/// The size of a WOTS+ signature.
+pub const WOTS_SIGNATURESIZE: usize = LEN * N;
+
+/// The size of a hash output.
+const N: usize = 32;
+
+/// The Winternitz parameter, which is the length of each chain.
+const W: u8 = 16;
+
+/// The number of base-16 digits of the message digest.
+const LEN1: usize = 2 * N;
+
+/// The number of base-16 digits of the checksum.
+const LEN2: usize = 3;
+
+/// The number of chains.
+const LEN: usize = LEN1 + LEN2;
+
+/// The domain separation prefixes of the hash function calls.
+const PREFIX_PK_SEED: u8 = 0x00;
+const PREFIX_CHAIN_START: u8 = 0x01;
+const PREFIX_CHAIN_STEP: u8 = 0x02;
+const PREFIX_ROOT: u8 = 0x03;
+const PREFIX_MESSAGE: u8 = 0x04;
+
+/// Compute BLAKE2b-256 over `parts`, keyed with `key` if given.
+fn hash(key: Option<&blake2b::SecretKey>, parts: &[&[u8]]) -> Result<[u8; N], UnknownCryptoError> {
+    let mut ctx = Blake2b::new(key, N)?;
+    for part in parts.iter() {
+        ctx.update(part)?;
+    }
+
+    let mut out = [0u8; N];
+    out.copy_from_slice(ctx.finalize()?.as_ref());
+
+    Ok(out)
+}
+
+/// Apply `steps` steps of chain `chain` to `x`, starting at step `start`.
+fn chain(
+    pk_seed: &[u8; N],
+    chain: usize,
+    x: &mut [u8; N],
+    start: u8,
+    steps: u8,
+) -> Result<(), UnknownCryptoError> {
+    debug_assert!(chain < LEN);
+    debug_assert!(start + steps < W);
+
+    for step in start..(start + steps) {
+        *x = hash(
+            None,
+            &[&[PREFIX_CHAIN_STEP], pk_seed, &[chain as u8, step], &x[..]],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Compute the root from the ends of all chains.
+fn root(pk_seed: &[u8; N], ends: &[u8; WOTS_SIGNATURESIZE]) -> Result<[u8; N], UnknownCryptoError> {
+    hash(None, &[&[PREFIX_ROOT], pk_seed, &ends[..]])
+}
+
+/// Compute the 67 base-16 digits of the digest of `message` and its checksum.
+fn digits(public_key: &PublicKey, message: &[u8]) -> Result<[u8; LEN], UnknownCryptoError> {
+    let digest = hash(None, &[&[PREFIX_MESSAGE], public_key.as_ref(), message])?;
+
+    Ok(digits_of_digest(&digest))
+}
+
+/// Split `digest` into base-16 digits, most significant first, and append the checksum
+/// as in the RFC 8391, Algorithm 5.
+fn digits_of_digest(digest: &[u8; N]) -> [u8; LEN] {
+    let mut ret = [0u8; LEN];
+    for (pair, byte) in ret[..LEN1].chunks_exact_mut(2).zip(digest.iter()) {
+        pair[0] = byte >> 4;
+        pair[1] = byte & 0x0f;
+    }
+
+    let checksum: u16 = ret[..LEN1].iter().map(|d| u16::from(W - 1 - d)).sum();
+    // The checksum is at most 64 * 15, so it fits in 12 bits. It is shifted into the
+    // most significant bits of two bytes, of which only the first 3 digits are used.
+    let checksum = (checksum << 4).to_be_bytes();
+    ret[LEN1] = checksum[0] >> 4;
+    ret[LEN1 + 1] = checksum[0] & 0x0f;
+    ret[LEN1 + 2] = checksum[1] >> 4;
+
+    ret
+}
+
+/// The chains derived from a [`SecretKey`]. The starts of the chains are zeroed on drop.
+struct ExpandedSecretKey {
+    pk_seed: [u8; N],
+    starts: [u8; WOTS_SIGNATURESIZE],
+}
+
+impl Drop for ExpandedSecretKey {
+    fn drop(&mut self) {
+        self.starts.zeroize();
+    }
+}
+
+impl ExpandedSecretKey {
+    fn from_seed(seed: &SecretKey) -> Result<Self, UnknownCryptoError> {
+        let key = blake2b::SecretKey::from_slice(seed.unprotected_as_bytes())?;
+        let mut expanded = Self {
+            pk_seed: hash(Some(&key), &[&[PREFIX_PK_SEED]])?,
+            starts: [0u8; WOTS_SIGNATURESIZE],
+        };
+        for (i, start) in expanded.starts.chunks_exact_mut(N).enumerate() {
+            let mut value = hash(Some(&key), &[&[PREFIX_CHAIN_START, i as u8]])?;
+            start.copy_from_slice(&value);
+            value.zeroize();
+        }
+
+        Ok(expanded)
+    }
+
+    /// Walk each chain from its start, to step `steps[i]` of chain `i`.
+    fn walk(&self, steps: &[u8; LEN]) -> Result<[u8; WOTS_SIGNATURESIZE], UnknownCryptoError> {
+        let mut out = [0u8; WOTS_SIGNATURESIZE];
+        for (i, (start, out)) in self
+            .starts
+            .chunks_exact(N)
+            .zip(out.chunks_exact_mut(N))
+            .enumerate()
+        {
+            let mut x = [0u8; N];
+            x.copy_from_slice(start);
+            chain(&self.pk_seed, i, &mut x, 0, steps[i])?;
+            out.copy_from_slice(&x);
+            x.zeroize();
+        }
+
+        Ok(out)
+    }
+
+    fn public_key(&self) -> Result<PublicKey, UnknownCryptoError> {
+        let ends = self.walk(&[W - 1; LEN])?;
+        let mut value = [0u8; WOTS_PUBLICKEYSIZE];
+        value[..N].copy_from_slice(&self.pk_seed);
+        value[N..].copy_from_slice(&root(&self.pk_seed, &ends)?);
+
+        Ok(PublicKey::from(value))
+    }
+}
+
+construct_secret_key! {
+    /// A type to represent the one-time secret key of WOTS+. This is the 32-byte seed
+    /// that the hash chains are derived from.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, WOTS_SECRETKEYSIZE, WOTS_SECRETKEYSIZE, WOTS_SECRETKEYSIZE)
+}
+
+impl_from_trait!(SecretKey, WOTS_SECRETKEYSIZE);
+
+construct_public! {
+    /// A type to represent the public key of WOTS+. This is the public seed followed
+    /// by the root of the hash chains.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 64 bytes.
+    (PublicKey, test_public_key, WOTS_PUBLICKEYSIZE, WOTS_PUBLICKEYSIZE)
+}
+
+impl_from_trait!(PublicKey, WOTS_PUBLICKEYSIZE);
+
+impl PublicKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the public key that belongs to `secret_key`.
+    pub fn from_secret_key(secret_key: &SecretKey) -> Result<Self, UnknownCryptoError> {
+        ExpandedSecretKey::from_seed(secret_key)?.public_key()
+    }
+}
+
+construct_public! {
+    /// A type to represent a one-time signature of WOTS+.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 2144 bytes.
+    (Signature, test_signature, WOTS_SIGNATURESIZE, WOTS_SIGNATURESIZE)
+}
+
+impl_from_trait!(Signature, WOTS_SIGNATURESIZE);
+
+/// A key pair of WOTS+, containing a [`PublicKey`] and a one-time [`SecretKey`].
+pub struct KeyPair {
+    public: PublicKey,
+    private: SecretKey,
+}
+
+impl KeyPair {
+    #[cfg(feature = "safe_api")]
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Randomly generate a key pair using a CSPRNG. Not available in `no_std` context.
+    pub fn generate() -> Result<Self, UnknownCryptoError> {
+        Self::from_secret_key(SecretKey::generate())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Make a key pair from `secret_key`, computing its [`PublicKey`].
+    pub fn from_secret_key(secret_key: SecretKey) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            public: PublicKey::from_secret_key(&secret_key)?,
+            private: secret_key,
+        })
+    }
+
+    /// Return the [`PublicKey`].
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// Consume the key pair and return the [`SecretKey`], to [`sign()`] with.
+    pub fn into_private(self) -> SecretKey {
+        self.private
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Sign `message` with the one-time `secret_key`, which is consumed.
+pub fn sign(secret_key: SecretKey, message: &[u8]) -> Result<Signature, UnknownCryptoError> {
+    let expanded = ExpandedSecretKey::from_seed(&secret_key)?;
+    drop(secret_key);
+    let public_key = expanded.public_key()?;
+    let signature = expanded.walk(&digits(&public_key, message)?)?;
+
+    Ok(Signature::from(signature))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify that `signature` is a valid signature of `message` by `public_key`.
+pub fn verify(
+    signature: &Signature,
+    public_key: &PublicKey,
+    message: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    let mut pk_seed = [0u8; N];
+    pk_seed.copy_from_slice(&public_key.as_ref()[..N]);
+    let digits = digits(public_key, message)?;
+
+    let mut ends = [0u8; WOTS_SIGNATURESIZE];
+    for (i, (sig, end)) in signature
+        .as_ref()
+        .chunks_exact(N)
+        .zip(ends.chunks_exact_mut(N))
+        .enumerate()
+    {
+        let mut x = [0u8; N];
+        x.copy_from_slice(sig);
+        chain(&pk_seed, i, &mut x, digits[i], W - 1 - digits[i])?;
+        end.copy_from_slice(&x);
+    }
+
+    if bool::from(root(&pk_seed, &ends)?.ct_eq(&public_key.as_ref()[N..])) {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+impl super::Verifier<Signature> for PublicKey {
+    fn verify(&self, signature: &Signature, message: &[u8]) -> Result<(), UnknownCryptoError> {
+        verify(signature, self, message)
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    fn test_sign_deterministic() {
+        // Computed with an independent Python implementation of the construction
+        // described in the module documentation, using `hashlib.blake2b`.
+        let sk = SecretKey::from([7u8; WOTS_SECRETKEYSIZE]);
+        let pk = PublicKey::from_secret_key(&sk).unwrap();
+        assert_eq!(
+            hex::encode(pk),
+            "ec7d61cd94e3602ef1c8a15db2a41b6f620fc60036c7e56d7756c9ae5c34e9c4e0568448af6c9f77313e64b61c3fc637e374b7d48db17f85478c778cca0513c3"
+        );
+        let sig = sign(sk, b"abc").unwrap();
+        assert_eq!(
+            hex::encode(&sig.as_ref()[..N]),
+            "d008f67c160d2df69faf7c889f6c9124afb1f384477cd5bea2aada4fcddb88a4"
+        );
+        assert_eq!(
+            hex::encode(&sig.as_ref()[WOTS_SIGNATURESIZE - N..]),
+            "44a2240c2704603db137503acc1d4beafeec6ce46e5d6d81d106e8296bbf77ea"
+        );
+        assert!(verify(&sig, &pk, b"abc").is_ok());
+    }
+
+    #[test]
+    fn test_verify_wrong_key_or_message() {
+        let sk = SecretKey::from([7u8; WOTS_SECRETKEYSIZE]);
+        let pk = PublicKey::from_secret_key(&sk).unwrap();
+        let other_pk =
+            PublicKey::from_secret_key(&SecretKey::from([8u8; WOTS_SECRETKEYSIZE])).unwrap();
+        let sig = sign(sk, b"abc").unwrap();
+
+        let err = verify(&sig, &other_pk, b"abc").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MacMismatch);
+        assert!(verify(&sig, &pk, b"abd").is_err());
+        assert!(verify(&sig, &pk, b"").is_err());
+    }
+
+    #[test]
+    fn test_verify_modified_signature() {
+        let sk = SecretKey::from([7u8; WOTS_SECRETKEYSIZE]);
+        let pk = PublicKey::from_secret_key(&sk).unwrap();
+        let sig = sign(sk, b"abc").unwrap();
+
+        for i in [0, N, LEN1 * N, WOTS_SIGNATURESIZE - 1].iter() {
+            let mut bad = [0u8; WOTS_SIGNATURESIZE];
+            bad.copy_from_slice(sig.as_ref());
+            bad[*i] ^= 1;
+            assert!(verify(&Signature::from(bad), &pk, b"abc").is_err());
+        }
+    }
+
+    #[test]
+    fn test_digits_and_checksum() {
+        // All digits at their maximum give a checksum of 0.
+        let digits = digits_of_digest(&[0xffu8; N]);
+        assert!(digits[..LEN1].iter().all(|d| *d == 15));
+        assert_eq!(digits[LEN1..], [0, 0, 0]);
+
+        // All digits at 0 give the maximum checksum, 64 * 15 = 960 = 0x3c0.
+        let digits = digits_of_digest(&[0u8; N]);
+        assert!(digits[..LEN1].iter().all(|d| *d == 0));
+        assert_eq!(digits[LEN1..], [0x3, 0xc, 0x0]);
+
+        let mut digest = [0u8; N];
+        digest[0] = 0xa5;
+        let digits = digits_of_digest(&digest);
+        assert_eq!(digits[..2], [0xa, 0x5]);
+        assert_eq!(digits[LEN1..], [0x3, 0xb, 0x1]);
+    }
+
+    #[test]
+    fn test_keypair() {
+        let kp = KeyPair::from_secret_key(SecretKey::from([7u8; WOTS_SECRETKEYSIZE])).unwrap();
+        let pk = *kp.public();
+        assert_eq!(
+            pk,
+            PublicKey::from_secret_key(&SecretKey::from([7u8; WOTS_SECRETKEYSIZE])).unwrap()
+        );
+
+        let sig = sign(kp.into_private(), b"abc").unwrap();
+        assert!(verify(&sig, &pk, b"abc").is_ok());
+    }
+
+    #[test]
+    fn test_verifier_trait() {
+        use crate::hazardous::sig::Verifier;
+
+        let sk = SecretKey::from([7u8; WOTS_SECRETKEYSIZE]);
+        let pk = PublicKey::from_secret_key(&sk).unwrap();
+        let sig = sign(sk, b"abc").unwrap();
+        assert!(Verifier::verify(&pk, &sig, b"abc").is_ok());
+        assert!(Verifier::verify(&pk, &sig, b"abd").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_generate() {
+        let kp = KeyPair::generate().unwrap();
+        let pk = *kp.public();
+        let sig = sign(kp.into_private(), b"firmware").unwrap();
+        assert!(verify(&sig, &pk, b"firmware").is_ok());
+        assert_ne!(pk, *KeyPair::generate().unwrap().public());
+    }
+}
@@ -0,0 +1,317 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `nonce`: The nonce value.
+//! - `initial_counter`: The initial counter value. In most cases, this is `0`.
+//! - `ciphertext`: The encrypted data.
+//! - `plaintext`: The data to be encrypted.
+//! - `dst_out`: Destination array that will hold the ciphertext/plaintext after
+//!   encryption/decryption.
+//!
+//! The 16-byte counter block is the 12-byte `nonce` followed by the 32-bit
+//! big-endian block counter, which starts at `initial_counter`. This is the
+//! same layout as the counter blocks used by AES-GCM, and compatible with
+//! [NIST SP 800-38A] CTR mode, as long as the counter does not wrap.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `dst_out` is less than `plaintext` or `ciphertext`.
+//! - `plaintext` or `ciphertext` is empty.
+//! - The `initial_counter` is high enough to cause a potential overflow.
+//! - More than `2^32 * 16` bytes of data would be processed with one key and nonce.
+//!
+//! Even though `dst_out` is allowed to be of greater length than `plaintext`,
+//! the `ciphertext` produced by `aes256ctr` will always be of the
+//! same length as the `plaintext`.
+//!
+//! # Security:
+//! - It is critical for security that a given nonce is not re-used with a given
+//!   key. Should this happen, the security of all data that has been encrypted
+//!   with that given key is compromised.
+//! - Functions herein do not provide any data integrity. If you need
+//!   data integrity, which is nearly ***always the case***, you should use an
+//!   AEAD construction instead. See the [`aead`](super::super::aead) module for this.
+//! - The nonce is not big enough to be randomly generated for a large amount of
+//!   messages with the same key. Using a counter is recommended.
+//! - AES is implemented in software, without lookup tables, so that it runs in
+//!   constant time. Hardware AES instructions are not used, because `orion` does
+//!   not use `unsafe` code. As a result, this is considerably slower than
+//!   [`chacha20`](super::chacha20).
+//! - To securely generate a strong key, use [`SecretKey::generate()`].
+//!
+//! # Recommendation:
+//! - Only use this if you are required to use AES. Otherwise, it is recommended
+//!   to use [`XChaCha20Poly1305`] when possible.
+//!
+//! # Example:
+//! ```rust
+//! use orion::hazardous::stream::aes256ctr;
+//!
+//! let secret_key = aes256ctr::SecretKey::generate();
+//!
+//! // WARNING: This nonce is only meant for demonstration and should not
+//! // be repeated. Please read the security section.
+//! let nonce = aes256ctr::Nonce::from([0u8; 12]);
+//! let message = "Data to protect".as_bytes();
+//!
+//! // The length of this message is 15.
+//!
+//! let mut dst_out_pt = [0u8; 15];
+//! let mut dst_out_ct = [0u8; 15];
+//!
+//! aes256ctr::encrypt(&secret_key, &nonce, 0, message, &mut dst_out_ct)?;
+//!
+//! aes256ctr::decrypt(&secret_key, &nonce, 0, &dst_out_ct, &mut dst_out_pt)?;
+//!
+//! assert_eq!(dst_out_pt, message);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`SecretKey::generate()`]: aes256ctr::SecretKey::generate()
+//! [`XChaCha20Poly1305`]: super::super::aead::xchacha20poly1305
+//! [NIST SP 800-38A]: https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38a.pdf
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::util::aes::Aes;
+use zeroize::Zeroize;
+
+/// The key size for AES-256.
+pub const AES256_KEYSIZE: usize = 32;
+/// The nonce size for AES-256-CTR.
+pub const AES_CTR_NONCESIZE: usize = 12;
+/// The blocksize which AES operates on.
+pub const AES_BLOCKSIZE: usize = crate::util::aes::AES_BLOCKSIZE;
+
+construct_secret_key! {
+    /// A type to represent the `SecretKey` that AES-256-CTR uses.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, AES256_KEYSIZE, AES256_KEYSIZE, AES256_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, AES256_KEYSIZE);
+
+construct_public! {
+    /// A type that represents a `Nonce` that AES-256-CTR uses.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 12 bytes.
+    (Nonce, test_nonce, AES_CTR_NONCESIZE, AES_CTR_NONCESIZE)
+}
+
+impl_from_trait!(Nonce, AES_CTR_NONCESIZE);
+
+/// In-place AES-256-CTR encryption.
+pub(crate) fn encrypt_in_place(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    bytes: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if bytes.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let total_blocks = bytes.chunks(AES_BLOCKSIZE).len() as u64;
+    if u64::from(initial_counter) + total_blocks > 1u64 << 32 {
+        return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow));
+    }
+
+    let aes = Aes::new(secret_key.unprotected_as_bytes())?;
+    let mut keystream = [0u8; AES_BLOCKSIZE];
+    let mut counter = initial_counter;
+
+    for chunk in bytes.chunks_mut(AES_BLOCKSIZE) {
+        keystream[..AES_CTR_NONCESIZE].copy_from_slice(nonce.as_ref());
+        keystream[AES_CTR_NONCESIZE..].copy_from_slice(&counter.to_be_bytes());
+        aes.encrypt_block(&mut keystream);
+
+        for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+            *b ^= k;
+        }
+        // This can only wrap after the last block has been processed.
+        counter = counter.wrapping_add(1);
+    }
+
+    keystream.zeroize();
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// AES-256-CTR encryption.
+pub fn encrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if dst_out.len() < plaintext.len() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if plaintext.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    dst_out[..plaintext.len()].copy_from_slice(plaintext);
+    encrypt_in_place(
+        secret_key,
+        nonce,
+        initial_counter,
+        &mut dst_out[..plaintext.len()],
+    )
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// AES-256-CTR decryption.
+pub fn decrypt(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    initial_counter: u32,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[cfg(feature = "safe_api")]
+    mod test_encrypt_decrypt {
+        use super::*;
+        use crate::test_framework::streamcipher_interface::*;
+
+        impl TestingRandom for SecretKey {
+            fn gen() -> Self {
+                Self::generate()
+            }
+        }
+
+        impl TestingRandom for Nonce {
+            fn gen() -> Self {
+                let mut n = [0u8; AES_CTR_NONCESIZE];
+                crate::util::secure_rand_bytes(&mut n).unwrap();
+                Self::from_slice(&n).unwrap()
+            }
+        }
+
+        #[test]
+        fn test_diff_params() {
+            test_diff_params_diff_output(&encrypt, &decrypt);
+        }
+
+        #[quickcheck]
+        fn prop_encrypt_decrypt_same_input(input: Vec<u8>, counter: u32) -> bool {
+            let secret_key = SecretKey::generate();
+            let nonce = Nonce::from([0u8; AES_CTR_NONCESIZE]);
+            let needed = input.chunks(AES_BLOCKSIZE).len() as u64;
+
+            let mut ct = vec![0u8; input.len()];
+            let mut pt = vec![0u8; input.len()];
+            let res = encrypt(&secret_key, &nonce, counter, &input, &mut ct);
+            if input.is_empty() || u64::from(counter) + needed > 1 << 32 {
+                return res.is_err();
+            }
+
+            res.is_ok()
+                && decrypt(&secret_key, &nonce, counter, &ct, &mut pt).is_ok()
+                && pt == input
+        }
+
+        #[quickcheck]
+        // Encrypting from a later counter is the same as skipping blocks of the keystream.
+        fn prop_counter_is_block_offset(input: Vec<u8>, skip: u8) -> bool {
+            if input.is_empty() {
+                return true;
+            }
+            let secret_key = SecretKey::generate();
+            let nonce = Nonce::from([1u8; AES_CTR_NONCESIZE]);
+            let offset = usize::from(skip) * AES_BLOCKSIZE;
+
+            let mut padded = vec![0u8; offset];
+            padded.extend_from_slice(&input);
+            let mut ct_full = vec![0u8; padded.len()];
+            let mut ct_part = vec![0u8; input.len()];
+            encrypt(&secret_key, &nonce, 0, &padded, &mut ct_full).unwrap();
+            encrypt(&secret_key, &nonce, u32::from(skip), &input, &mut ct_part).unwrap();
+
+            ct_full[offset..] == ct_part[..]
+        }
+    }
+
+    mod test_errors {
+        use super::*;
+
+        #[test]
+        fn test_empty_input_err() {
+            let sk = SecretKey::from([0u8; AES256_KEYSIZE]);
+            let n = Nonce::from([0u8; AES_CTR_NONCESIZE]);
+            let mut dst_out = [0u8; 16];
+            assert!(encrypt(&sk, &n, 0, &[0u8; 0], &mut dst_out).is_err());
+            assert!(decrypt(&sk, &n, 0, &[0u8; 0], &mut dst_out).is_err());
+        }
+
+        #[test]
+        fn test_dst_out_length() {
+            let sk = SecretKey::from([0u8; AES256_KEYSIZE]);
+            let n = Nonce::from([0u8; AES_CTR_NONCESIZE]);
+            let input = [0u8; 17];
+
+            let mut dst_out_less = [0u8; 16];
+            assert!(encrypt(&sk, &n, 0, &input, &mut dst_out_less).is_err());
+            assert!(decrypt(&sk, &n, 0, &input, &mut dst_out_less).is_err());
+
+            let mut dst_out_exact = [0u8; 17];
+            assert!(encrypt(&sk, &n, 0, &input, &mut dst_out_exact).is_ok());
+            assert!(decrypt(&sk, &n, 0, &input, &mut dst_out_exact).is_ok());
+
+            let mut dst_out_greater = [0u8; 18];
+            assert!(encrypt(&sk, &n, 0, &input, &mut dst_out_greater).is_ok());
+            assert!(decrypt(&sk, &n, 0, &input, &mut dst_out_greater).is_ok());
+            assert_eq!(dst_out_greater[17], 0);
+        }
+
+        #[test]
+        fn test_initial_counter_overflow_err() {
+            let sk = SecretKey::from([0u8; AES256_KEYSIZE]);
+            let n = Nonce::from([0u8; AES_CTR_NONCESIZE]);
+            let mut dst_out = [0u8; 32];
+
+            assert!(encrypt(&sk, &n, u32::MAX, &[0u8; 16], &mut dst_out).is_ok());
+            assert!(encrypt(&sk, &n, u32::MAX - 1, &[0u8; 32], &mut dst_out).is_ok());
+            assert!(encrypt(&sk, &n, u32::MAX, &[0u8; 17], &mut dst_out).is_err());
+            assert!(decrypt(&sk, &n, u32::MAX, &[0u8; 17], &mut dst_out).is_err());
+        }
+    }
+}
@@ -24,10 +24,15 @@
 //! - `secret_key`: The secret key.
 //! - `nonce`: The nonce value.
 //! - `initial_counter`: The initial counter value. In most cases, this is `0`.
+//! - `counter`: The counter value of a single keystream block.
+//! - `offset`: The byte offset into the keystream at which to start encryption/decryption.
+//!   Encrypting at `offset` gives the same result as encrypting from `initial_counter` `0`
+//!   and skipping the first `offset` bytes.
 //! - `ciphertext`: The encrypted data.
 //! - `plaintext`: The data to be encrypted.
 //! - `dst_out`: Destination array that will hold the ciphertext/plaintext after
 //!   encryption/decryption.
+//! - `dst`: Destination buffer for the keystream read from a [`KeystreamReader`].
 //!
 //! `nonce`: "Counters and LFSRs are both acceptable ways of generating unique
 //! nonces, as is encrypting a counter using a block cipher with a 64-bit block
@@ -41,15 +46,23 @@
 //! - The length of `dst_out` is less than `plaintext` or `ciphertext`.
 //! - `plaintext` or `ciphertext` is empty.
 //! - The `initial_counter` is high enough to cause a potential overflow.
+//! - More than `2^32-1` keystream blocks, or more than `2^32-1 * 64` bytes of data,
+//!   would be processed with one key and nonce.
+//! - `offset` plus the length of `plaintext` or `ciphertext` is greater than
+//!   the length of the keystream, `2^32 * 64` bytes.
+//! - [`KeystreamReader::fill_bytes()`] is called with an empty `dst`, or with a
+//!   `dst` that is longer than the remaining keystream. No keystream is consumed
+//!   when this happens.
+//!
+//! [`KeystreamReader`] also implements [`std::io::Read`] when the `safe_api` feature
+//! is enabled. Reading returns fewer bytes and then `Ok(0)` once the keystream
+//! is exhausted, instead of an error.
 //!
 //! Even though `dst_out` is allowed to be of greater length than `plaintext`,
 //! the `ciphertext` produced by `chacha20`/`xchacha20` will always be of the
 //! same length as the `plaintext`.
 //!
-//! # Panics:
-//! A panic will occur if:
-//! - More than `2^32-1` keystream blocks are processed or more than `2^32-1 * 64`
-//!   bytes of data are processed.
+//! These limits are checked before any keystream is generated.
 //!
 //! # Security:
 //! - It is critical for security that a given nonce is not re-used with a given
@@ -85,14 +98,28 @@
 //! chacha20::decrypt(&secret_key, &nonce, 0, &dst_out_ct, &mut dst_out_pt)?;
 //!
 //! assert_eq!(dst_out_pt, message);
+//!
+//! // Random access: decrypt only bytes 5 to 10 of the ciphertext.
+//! let mut part = [0u8; 5];
+//! chacha20::decrypt_at_offset(&secret_key, &nonce, 5, &dst_out_ct[5..10], &mut part)?;
+//! assert_eq!(part, message[5..10]);
+//!
+//! // The keystream itself can be read, without needing a plaintext.
+//! let mut reader = chacha20::KeystreamReader::new(&secret_key, &nonce, 0);
+//! let mut keystream = [0u8; 15];
+//! reader.fill_bytes(&mut keystream)?;
+//! for ((ct, pt), ks) in dst_out_ct.iter().zip(message.iter()).zip(keystream.iter()) {
+//!     assert_eq!(*ct, pt ^ ks);
+//! }
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`SecretKey::generate()`]: chacha20::SecretKey::generate()
 //! [`XChaCha20Poly1305`]: super::aead::xchacha20poly1305
 //! [RFC]: https://tools.ietf.org/html/rfc8439
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
 use crate::util::endianness::load_u32_le;
 use crate::util::u32x4::U32x4;
+use core::convert::TryFrom;
 use zeroize::{Zeroize, Zeroizing};
 
 /// The key size for ChaCha20.
@@ -100,11 +127,15 @@ pub const CHACHA_KEYSIZE: usize = 32;
 /// The nonce size for IETF ChaCha20.
 pub const IETF_CHACHA_NONCESIZE: usize = 12;
 /// The blocksize which ChaCha20 operates on.
-pub(crate) const CHACHA_BLOCKSIZE: usize = 64;
+pub const CHACHA_BLOCKSIZE: usize = 64;
 /// The size of the subkey that HChaCha20 returns.
-const HCHACHA_OUTSIZE: usize = 32;
+pub const HCHACHA_OUTSIZE: usize = 32;
 /// The nonce size for HChaCha20.
-pub(crate) const HCHACHA_NONCESIZE: usize = 16;
+pub const HCHACHA_NONCESIZE: usize = 16;
+/// The amount of keystream blocks that are computed in parallel.
+const PAR_BLOCKS: usize = 4;
+/// The size of the temporary keystream buffer used by `xor_keystream()`.
+pub(crate) const KEYSTREAM_BUFSIZE: usize = PAR_BLOCKS * CHACHA_BLOCKSIZE;
 
 construct_secret_key! {
     /// A type to represent the `SecretKey` that Chacha20, XChaCha20, ChaCha20-Poly1305 and
@@ -122,6 +153,21 @@ construct_secret_key! {
 
 impl_from_trait!(SecretKey, CHACHA_KEYSIZE);
 
+construct_secret_key! {
+    /// A type to represent the `SubKey` that HChaCha20 returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SubKey, test_subkey, HCHACHA_OUTSIZE, HCHACHA_OUTSIZE, HCHACHA_OUTSIZE)
+}
+
+impl_from_trait!(SubKey, HCHACHA_OUTSIZE);
+
 construct_public! {
     /// A type that represents a `Nonce` that ChaCha20 and ChaCha20-Poly1305 use.
     ///
@@ -166,6 +212,25 @@ macro_rules! DOUBLE_ROUND {
         $r3 = $r3.shl_1();
     };
 }
+
+/// A quarter round on the words `$a`, `$b`, `$c` and `$d` of the state `$x`,
+/// where each word holds the same position of `PAR_BLOCKS` blocks.
+macro_rules! QUARTER_ROUND_WIDE {
+    ($x:expr, $a:expr, $b:expr, $c:expr, $d:expr) => {
+        $x[$a] = $x[$a].wrapping_add($x[$b]);
+        $x[$d] = ($x[$d] ^ $x[$a]).rotate_left(16);
+
+        $x[$c] = $x[$c].wrapping_add($x[$d]);
+        $x[$b] = ($x[$b] ^ $x[$c]).rotate_left(12);
+
+        $x[$a] = $x[$a].wrapping_add($x[$b]);
+        $x[$d] = ($x[$d] ^ $x[$a]).rotate_left(8);
+
+        $x[$c] = $x[$c].wrapping_add($x[$d]);
+        $x[$b] = ($x[$b] ^ $x[$c]).rotate_left(7);
+    };
+}
+
 pub(crate) struct ChaCha20 {
     state: [U32x4; 4],
     internal_counter: u32,
@@ -185,10 +250,10 @@ impl ChaCha20 {
     pub(crate) fn new(sk: &[u8], n: &[u8], is_ietf: bool) -> Result<Self, UnknownCryptoError> {
         debug_assert!(sk.len() == CHACHA_KEYSIZE);
         if (n.len() != IETF_CHACHA_NONCESIZE) && is_ietf {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
         if (n.len() != HCHACHA_NONCESIZE) && !is_ietf {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
         // Row 0 with constants.
@@ -235,7 +300,11 @@ impl ChaCha20 {
     }
 
     /// Process the next keystream and copy into destination array.
-    pub(crate) fn keystream_block(&mut self, block_counter: u32, inplace: &mut [u8]) {
+    pub(crate) fn keystream_block(
+        &mut self,
+        block_counter: u32,
+        inplace: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
         debug_assert!(if self.is_ietf {
             inplace.len() == CHACHA_BLOCKSIZE
         } else {
@@ -246,9 +315,12 @@ impl ChaCha20 {
             self.state[3].0 = block_counter;
         }
 
-        // If this panics, max amount of keystream blocks
+        // If this fails, max amount of keystream blocks
         // have been retrieved.
-        self.internal_counter = self.internal_counter.checked_add(1).unwrap();
+        self.internal_counter = match self.internal_counter.checked_add(1) {
+            Some(next) => next,
+            None => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+        };
 
         let mut wr0 = self.state[0];
         let mut wr1 = self.state[1];
@@ -266,7 +338,7 @@ impl ChaCha20 {
         DOUBLE_ROUND!(wr0, wr1, wr2, wr3);
         DOUBLE_ROUND!(wr0, wr1, wr2, wr3);
 
-        let mut iter = inplace.chunks_exact_mut(16);
+        let rows = inplace.chunks_exact_mut(16);
 
         if self.is_ietf {
             wr0 = wr0.wrapping_add(self.state[0]);
@@ -274,37 +346,142 @@ impl ChaCha20 {
             wr2 = wr2.wrapping_add(self.state[2]);
             wr3 = wr3.wrapping_add(self.state[3]);
 
-            wr0.store_into_le(iter.next().unwrap());
-            wr1.store_into_le(iter.next().unwrap());
-            wr2.store_into_le(iter.next().unwrap());
-            wr3.store_into_le(iter.next().unwrap());
+            for (row, dst) in [wr0, wr1, wr2, wr3].iter().zip(rows) {
+                row.store_into_le(dst);
+            }
         } else {
-            wr0.store_into_le(iter.next().unwrap());
-            wr3.store_into_le(iter.next().unwrap());
+            for (row, dst) in [wr0, wr3].iter().zip(rows) {
+                row.store_into_le(dst);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Process the next `PAR_BLOCKS` keystream blocks, starting at `initial_counter`,
+    /// and copy them into destination array.
+    ///
+    /// Each `U32x4` of the working state holds the same word of four consecutive blocks,
+    /// instead of a row of one block. This needs no shuffling between rounds and lets
+    /// the compiler process all four blocks with the same SIMD instructions, without
+    /// any `unsafe`.
+    pub(crate) fn keystream_blocks_wide(
+        &mut self,
+        initial_counter: u32,
+        inplace: &mut [u8],
+    ) -> Result<(), UnknownCryptoError> {
+        debug_assert!(self.is_ietf);
+        debug_assert!(inplace.len() == PAR_BLOCKS * CHACHA_BLOCKSIZE);
+        debug_assert!(initial_counter.checked_add(PAR_BLOCKS as u32 - 1).is_some());
+
+        // If this fails, max amount of keystream blocks
+        // have been retrieved.
+        self.internal_counter = match self.internal_counter.checked_add(PAR_BLOCKS as u32) {
+            Some(next) => next,
+            None => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+        };
+
+        let mut initial = [U32x4(0, 0, 0, 0); 16];
+        for (row_idx, row) in self.state.iter().enumerate() {
+            initial[row_idx * 4] = U32x4(row.0, row.0, row.0, row.0);
+            initial[row_idx * 4 + 1] = U32x4(row.1, row.1, row.1, row.1);
+            initial[row_idx * 4 + 2] = U32x4(row.2, row.2, row.2, row.2);
+            initial[row_idx * 4 + 3] = U32x4(row.3, row.3, row.3, row.3);
+        }
+        initial[12] = U32x4(
+            initial_counter,
+            initial_counter.wrapping_add(1),
+            initial_counter.wrapping_add(2),
+            initial_counter.wrapping_add(3),
+        );
+
+        let mut x = initial;
+        for _ in 0..10 {
+            // Column rounds
+            QUARTER_ROUND_WIDE!(x, 0, 4, 8, 12);
+            QUARTER_ROUND_WIDE!(x, 1, 5, 9, 13);
+            QUARTER_ROUND_WIDE!(x, 2, 6, 10, 14);
+            QUARTER_ROUND_WIDE!(x, 3, 7, 11, 15);
+            // Diagonal rounds
+            QUARTER_ROUND_WIDE!(x, 0, 5, 10, 15);
+            QUARTER_ROUND_WIDE!(x, 1, 6, 11, 12);
+            QUARTER_ROUND_WIDE!(x, 2, 7, 8, 13);
+            QUARTER_ROUND_WIDE!(x, 3, 4, 9, 14);
+        }
+
+        // Transpose back, so that lane n of word i ends up at word i of block n.
+        for (word_idx, (word, initial_word)) in x.iter().zip(initial.iter()).enumerate() {
+            let word = word.wrapping_add(*initial_word);
+            let word_pos = word_idx * 4..(word_idx * 4) + 4;
+            for (lane, block) in [word.0, word.1, word.2, word.3]
+                .iter()
+                .zip(inplace.chunks_exact_mut(CHACHA_BLOCKSIZE))
+            {
+                block[word_pos.clone()].copy_from_slice(&lane.to_le_bytes());
+            }
         }
+
+        x.iter_mut().zeroize();
+        initial.iter_mut().zeroize();
+
+        Ok(())
     }
 }
 
-/// XOR keystream into destination array using a temporary buffer for each keystream block.
+/// XOR keystream into destination array, using a temporary buffer that holds
+/// the keystream of up to `PAR_BLOCKS` blocks at a time.
 pub(crate) fn xor_keystream(
     ctx: &mut ChaCha20,
     initial_counter: u32,
-    tmp_block: &mut [u8],
+    tmp_buffer: &mut [u8],
     bytes: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
-    debug_assert!(tmp_block.len() == CHACHA_BLOCKSIZE);
+    debug_assert!(tmp_buffer.len() == KEYSTREAM_BUFSIZE);
     if bytes.is_empty() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    // Check the limits up front, so that `bytes` is not partially processed
+    // when they are exceeded.
+    let total_blocks = bytes.chunks(CHACHA_BLOCKSIZE).len() as u64;
+    if u64::from(initial_counter) + total_blocks > (1u64 << 32)
+        || u64::from(ctx.internal_counter) + total_blocks > u64::from(u32::MAX)
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow));
     }
 
-    for (ctr, out_block) in bytes.chunks_mut(CHACHA_BLOCKSIZE).enumerate() {
-        match initial_counter.checked_add(ctr as u32) {
-            Some(counter) => {
-                ctx.keystream_block(counter, tmp_block);
-                xor_slices!(tmp_block, out_block);
+    let use_wide_blocks = crate::util::cpu::use_wide_vectors();
+    for (batch, out_blocks) in bytes.chunks_mut(KEYSTREAM_BUFSIZE).enumerate() {
+        let batch_counter = match u32::try_from(batch * PAR_BLOCKS)
+            .ok()
+            .and_then(|offset| initial_counter.checked_add(offset))
+        {
+            Some(counter) => counter,
+            None => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+        };
+        let block_count = out_blocks.chunks(CHACHA_BLOCKSIZE).len();
+
+        // With only the baseline SSE2 registers, the wide state doesn't fit in registers and is
+        // slower than processing one block at a time, so this is only used when the
+        // target supports wider vectors. See `util::cpu` for how this is selected.
+        if use_wide_blocks
+            && block_count == PAR_BLOCKS
+            && batch_counter.checked_add(PAR_BLOCKS as u32 - 1).is_some()
+        {
+            ctx.keystream_blocks_wide(batch_counter, tmp_buffer)?;
+        } else {
+            for (idx, keystream_block) in tmp_buffer
+                .chunks_exact_mut(CHACHA_BLOCKSIZE)
+                .take(block_count)
+                .enumerate()
+            {
+                match batch_counter.checked_add(idx as u32) {
+                    Some(counter) => ctx.keystream_block(counter, keystream_block)?,
+                    None => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+                }
             }
-            None => return Err(UnknownCryptoError),
         }
+
+        xor_slices!(tmp_buffer, out_blocks);
     }
 
     Ok(())
@@ -318,12 +495,12 @@ pub(crate) fn encrypt_in_place(
     bytes: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
     if bytes.is_empty() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
     let mut ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
-    let mut keystream_block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
-    xor_keystream(&mut ctx, initial_counter, keystream_block.as_mut(), bytes)
+    let mut keystream = Zeroizing::new([0u8; KEYSTREAM_BUFSIZE]);
+    xor_keystream(&mut ctx, initial_counter, keystream.as_mut(), bytes)
 }
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -336,10 +513,10 @@ pub fn encrypt(
     dst_out: &mut [u8],
 ) -> Result<(), UnknownCryptoError> {
     if dst_out.len() < plaintext.len() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
     if plaintext.is_empty() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
     dst_out[..plaintext.len()].copy_from_slice(plaintext);
@@ -363,16 +540,255 @@ pub fn decrypt(
     encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
 }
 
-/// HChaCha20 as specified in the [draft-RFC](https://github.com/bikeshedders/xchacha-rfc/blob/master).
-pub(super) fn hchacha20(
+/// In-place IETF ChaCha20 encryption, starting at the byte `offset` of the keystream.
+pub(crate) fn encrypt_in_place_at_offset(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    offset: u64,
+    bytes: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if bytes.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    match offset.checked_add(bytes.len() as u64) {
+        Some(end) if end <= (1u64 << 32) * CHACHA_BLOCKSIZE as u64 => (),
+        _ => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+    }
+
+    // The end of the data is within the keystream, so the block counter fits in a u32.
+    let mut counter = match u32::try_from(offset / CHACHA_BLOCKSIZE as u64) {
+        Ok(counter) => counter,
+        Err(_) => return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow)),
+    };
+    let skip = (offset % CHACHA_BLOCKSIZE as u64) as usize;
+    let mut ctx = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)?;
+
+    let mut bytes = bytes;
+    if skip != 0 {
+        // The offset is within a block, so only the end of its keystream is used.
+        let mut block = Zeroizing::new([0u8; CHACHA_BLOCKSIZE]);
+        ctx.keystream_block(counter, block.as_mut())?;
+        let take = core::cmp::min(CHACHA_BLOCKSIZE - skip, bytes.len());
+        let (partial, rest) = bytes.split_at_mut(take);
+        xor_slices!(block[skip..], partial);
+
+        if rest.is_empty() {
+            return Ok(());
+        }
+        counter += 1;
+        bytes = rest;
+    }
+
+    let mut keystream = Zeroizing::new([0u8; KEYSTREAM_BUFSIZE]);
+    xor_keystream(&mut ctx, counter, keystream.as_mut(), bytes)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// IETF ChaCha20 encryption, starting at the byte `offset` of the keystream.
+pub fn encrypt_at_offset(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    offset: u64,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if dst_out.len() < plaintext.len() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if plaintext.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    dst_out[..plaintext.len()].copy_from_slice(plaintext);
+    encrypt_in_place_at_offset(secret_key, nonce, offset, &mut dst_out[..plaintext.len()])
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// IETF ChaCha20 decryption, starting at the byte `offset` of the keystream.
+pub fn decrypt_at_offset(
     secret_key: &SecretKey,
-    nonce: &[u8],
-) -> Result<[u8; HCHACHA_OUTSIZE], UnknownCryptoError> {
+    nonce: &Nonce,
+    offset: u64,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    encrypt_at_offset(secret_key, nonce, offset, ciphertext, dst_out)
+}
+
+/// A reader over the IETF ChaCha20 keystream of a `secret_key` and `nonce`, starting at
+/// the block `initial_counter`. The counter is incremented automatically.
+pub struct KeystreamReader {
+    ctx: ChaCha20,
+    /// The counter of the next block that has not been generated yet.
+    next_counter: u64,
+    /// The counter after the last block that can be generated.
+    end_counter: u64,
+    buffer: [u8; CHACHA_BLOCKSIZE],
+    /// The position of the first unread byte in `buffer`.
+    position: usize,
+}
+
+impl Drop for KeystreamReader {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for KeystreamReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "KeystreamReader {{ ctx: [***OMITTED***], next_counter: {:?}, end_counter: {:?}, \
+            buffer: [***OMITTED***], position: {:?} }}",
+            self.next_counter, self.end_counter, self.position
+        )
+    }
+}
+
+impl KeystreamReader {
+    /// Initialize a reader over the keystream, starting at the block `initial_counter`.
+    ///
+    /// At most `2^32-1` blocks can be read, and never past the block with counter `2^32-1`.
+    #[allow(clippy::unwrap_used)]
+    pub fn new(secret_key: &SecretKey, nonce: &Nonce, initial_counter: u32) -> Self {
+        let next_counter = u64::from(initial_counter);
+        let end_counter = core::cmp::min(1u64 << 32, next_counter + u64::from(u32::MAX));
+
+        Self {
+            // The nonce is always the correct length, so this cannot fail.
+            ctx: ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true).unwrap(),
+            next_counter,
+            end_counter,
+            buffer: [0u8; CHACHA_BLOCKSIZE],
+            position: CHACHA_BLOCKSIZE,
+        }
+    }
+
+    /// The next block counter, which `remaining()` checks to be within a `u32`.
+    fn next_counter_u32(&self) -> Result<u32, UnknownCryptoError> {
+        u32::try_from(self.next_counter)
+            .map_err(|_| UnknownCryptoError::new(ErrorKind::NonceOverflow))
+    }
+
+    /// The amount of keystream bytes that can still be read.
+    pub fn remaining(&self) -> u64 {
+        (self.end_counter - self.next_counter) * CHACHA_BLOCKSIZE as u64
+            + (CHACHA_BLOCKSIZE - self.position) as u64
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dst` with the next `dst.len()` bytes of keystream.
+    pub fn fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dst.is_empty() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        if dst.len() as u64 > self.remaining() {
+            return Err(UnknownCryptoError::new(ErrorKind::NonceOverflow));
+        }
+
+        // Use the buffered keystream first.
+        let buffered = CHACHA_BLOCKSIZE - self.position;
+        let take = if buffered < dst.len() {
+            buffered
+        } else {
+            dst.len()
+        };
+        let (from_buffer, rest) = dst.split_at_mut(take);
+        from_buffer.copy_from_slice(&self.buffer[self.position..self.position + take]);
+        self.buffer[self.position..self.position + take].zeroize();
+        self.position += take;
+
+        // Complete blocks are written directly into `dst`.
+        let full_len = rest.len() - (rest.len() % CHACHA_BLOCKSIZE);
+        let (full_blocks, partial_block) = rest.split_at_mut(full_len);
+        if !full_blocks.is_empty() {
+            // `remaining()` was checked, so the counter is valid.
+            let counter = self.next_counter_u32()?;
+            let mut tmp = Zeroizing::new([0u8; KEYSTREAM_BUFSIZE]);
+            full_blocks.iter_mut().for_each(|b| *b = 0u8);
+            xor_keystream(&mut self.ctx, counter, tmp.as_mut(), full_blocks)?;
+            self.next_counter += (full_len / CHACHA_BLOCKSIZE) as u64;
+        }
+
+        if !partial_block.is_empty() {
+            let counter = self.next_counter_u32()?;
+            self.ctx.keystream_block(counter, &mut self.buffer)?;
+            self.next_counter += 1;
+            partial_block.copy_from_slice(&self.buffer[..partial_block.len()]);
+            self.buffer[..partial_block.len()].zeroize();
+            self.position = partial_block.len();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safe_api")]
+impl std::io::Read for KeystreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let remaining = self.remaining();
+        let len = if (buf.len() as u64) < remaining {
+            buf.len()
+        } else {
+            remaining as usize
+        };
+        if len == 0 {
+            return Ok(0);
+        }
+
+        self.fill_bytes(&mut buf[..len])?;
+
+        Ok(len)
+    }
+}
+
+/// IETF ChaCha20 keystream block with the block counter `counter`.
+#[allow(clippy::unwrap_used)]
+pub fn keystream_block(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    counter: u32,
+    dst_out: &mut [u8; CHACHA_BLOCKSIZE],
+) {
+    // The nonce is always the correct length and only a single block is processed,
+    // so this cannot fail.
+    ChaCha20::new(secret_key.unprotected_as_bytes(), nonce.as_ref(), true)
+        .unwrap()
+        .keystream_block(counter, dst_out)
+        .unwrap();
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// HChaCha20 as specified in the [draft-RFC](https://github.com/bikeshedders/xchacha-rfc/blob/master).
+///
+/// HChaCha20 derives a [`SubKey`] from a `secret_key` and a 16-byte `nonce`. This is
+/// how XChaCha20 extends the nonce of ChaCha20, and is only needed when building
+/// other constructions in the same way.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - The length of `nonce` is not [`HCHACHA_NONCESIZE`].
+///
+/// # Security:
+/// - The subkey is as secret as `secret_key` and must be treated as such.
+/// - When used as a ChaCha20 key, the same rules for nonce reuse apply to the
+///   subkey as to any other key.
+///
+/// # Example:
+/// ```rust
+/// use orion::hazardous::stream::chacha20;
+///
+/// let secret_key = chacha20::SecretKey::generate();
+/// let subkey = chacha20::hchacha20(&secret_key, &[0u8; chacha20::HCHACHA_NONCESIZE])?;
+/// let derived_key = chacha20::SecretKey::from_slice(subkey.unprotected_as_bytes())?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn hchacha20(secret_key: &SecretKey, nonce: &[u8]) -> Result<SubKey, UnknownCryptoError> {
     let mut chacha_state = ChaCha20::new(secret_key.unprotected_as_bytes(), nonce, false)?;
-    let mut keystream_block = [0u8; HCHACHA_OUTSIZE];
-    chacha_state.keystream_block(0, &mut keystream_block);
+    let mut keystream_block = Zeroizing::new([0u8; HCHACHA_OUTSIZE]);
+    chacha_state.keystream_block(0, keystream_block.as_mut())?;
 
-    Ok(keystream_block)
+    Ok(SubKey::from(*keystream_block))
 }
 
 // Testing public functions in the module.
@@ -410,6 +826,211 @@ mod public {
         }
     }
 
+    mod test_keystream_reader {
+        use super::*;
+
+        fn keystream_by_encrypt(initial_counter: u32, dst: &mut [u8]) {
+            let secret_key = SecretKey::from_slice(&[1u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from([2u8; IETF_CHACHA_NONCESIZE]);
+            dst.iter_mut().for_each(|b| *b = 0u8);
+            encrypt_in_place(&secret_key, &nonce, initial_counter, dst).unwrap();
+        }
+
+        fn reader(initial_counter: u32) -> KeystreamReader {
+            let secret_key = SecretKey::from_slice(&[1u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from([2u8; IETF_CHACHA_NONCESIZE]);
+            KeystreamReader::new(&secret_key, &nonce, initial_counter)
+        }
+
+        #[test]
+        fn test_same_as_encrypt() {
+            let mut expected = [0u8; 1000];
+            keystream_by_encrypt(5, &mut expected);
+
+            for split in [1, 10, 63, 64, 65, 128, 300, 999, 1000].iter() {
+                let mut actual = [0u8; 1000];
+                let mut reader = reader(5);
+                for chunk in actual.chunks_mut(*split) {
+                    reader.fill_bytes(chunk).unwrap();
+                }
+                assert_eq!(actual[..], expected[..]);
+            }
+
+            // Uneven splits, crossing block boundaries at different offsets.
+            let mut actual = [0u8; 1000];
+            let mut reader = reader(5);
+            reader.fill_bytes(&mut actual[..3]).unwrap();
+            reader.fill_bytes(&mut actual[3..200]).unwrap();
+            reader.fill_bytes(&mut actual[200..201]).unwrap();
+            reader.fill_bytes(&mut actual[201..]).unwrap();
+            assert_eq!(actual[..], expected[..]);
+        }
+
+        #[test]
+        fn test_at_offset_same_as_encrypt() {
+            let secret_key = SecretKey::from_slice(&[1u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from([2u8; IETF_CHACHA_NONCESIZE]);
+            let mut plaintext = [0u8; 1000];
+            plaintext
+                .iter_mut()
+                .enumerate()
+                .for_each(|(idx, b)| *b = idx as u8);
+            let mut expected = [0u8; 1000];
+            encrypt(&secret_key, &nonce, 0, &plaintext, &mut expected).unwrap();
+
+            for offset in [0usize, 1, 5, 63, 64, 65, 127, 128, 300, 999].iter() {
+                for len in [1usize, 2, 59, 64, 65, 200].iter() {
+                    let end = core::cmp::min(offset + len, 1000);
+                    let mut actual = [0u8; 200];
+                    encrypt_at_offset(
+                        &secret_key,
+                        &nonce,
+                        *offset as u64,
+                        &plaintext[*offset..end],
+                        &mut actual,
+                    )
+                    .unwrap();
+                    assert_eq!(actual[..end - offset], expected[*offset..end]);
+
+                    let mut decrypted = [0u8; 200];
+                    decrypt_at_offset(
+                        &secret_key,
+                        &nonce,
+                        *offset as u64,
+                        &expected[*offset..end],
+                        &mut decrypted,
+                    )
+                    .unwrap();
+                    assert_eq!(decrypted[..end - offset], plaintext[*offset..end]);
+                }
+            }
+
+            // A block-aligned offset is the same as the initial counter.
+            let mut with_counter = [0u8; 100];
+            let mut with_offset = [0u8; 100];
+            encrypt(&secret_key, &nonce, 7, &plaintext[..100], &mut with_counter).unwrap();
+            encrypt_at_offset(
+                &secret_key,
+                &nonce,
+                7 * 64,
+                &plaintext[..100],
+                &mut with_offset,
+            )
+            .unwrap();
+            assert_eq!(with_counter[..], with_offset[..]);
+        }
+
+        #[test]
+        fn test_at_offset_errors() {
+            let secret_key = SecretKey::from_slice(&[1u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from([2u8; IETF_CHACHA_NONCESIZE]);
+            let end = (1u64 << 32) * CHACHA_BLOCKSIZE as u64;
+            let mut dst_out = [0u8; 64];
+
+            assert!(encrypt_at_offset(&secret_key, &nonce, 0, &[0u8; 0], &mut dst_out).is_err());
+            assert!(encrypt_at_offset(&secret_key, &nonce, 0, &[0u8; 65], &mut dst_out).is_err());
+
+            assert!(
+                encrypt_at_offset(&secret_key, &nonce, end - 64, &[0u8; 64], &mut dst_out).is_ok()
+            );
+            assert!(
+                encrypt_at_offset(&secret_key, &nonce, end - 10, &[0u8; 10], &mut dst_out).is_ok()
+            );
+            assert!(
+                encrypt_at_offset(&secret_key, &nonce, end - 10, &[0u8; 11], &mut dst_out).is_err()
+            );
+            assert!(encrypt_at_offset(&secret_key, &nonce, end, &[0u8; 1], &mut dst_out).is_err());
+            assert!(
+                encrypt_at_offset(&secret_key, &nonce, u64::MAX, &[0u8; 1], &mut dst_out).is_err()
+            );
+
+            // The last block is the same as when using the initial counter.
+            let mut expected = [0u8; 64];
+            encrypt(&secret_key, &nonce, u32::MAX, &[0u8; 64], &mut expected).unwrap();
+            encrypt_at_offset(&secret_key, &nonce, end - 64, &[0u8; 64], &mut dst_out).unwrap();
+            assert_eq!(dst_out, expected);
+        }
+
+        #[test]
+        fn test_keystream_block_same_as_reader() {
+            let secret_key = SecretKey::from_slice(&[1u8; CHACHA_KEYSIZE]).unwrap();
+            let nonce = Nonce::from([2u8; IETF_CHACHA_NONCESIZE]);
+
+            for counter in [0u32, 1, 7, u32::MAX].iter() {
+                let mut expected = [0u8; CHACHA_BLOCKSIZE];
+                keystream_by_encrypt(*counter, &mut expected);
+                let mut actual = [0u8; CHACHA_BLOCKSIZE];
+                keystream_block(&secret_key, &nonce, *counter, &mut actual);
+                assert_eq!(actual[..], expected[..]);
+            }
+        }
+
+        #[test]
+        fn test_empty_err() {
+            let mut reader = reader(0);
+            assert!(reader.fill_bytes(&mut [0u8; 0]).is_err());
+        }
+
+        #[test]
+        fn test_remaining() {
+            let max = u64::from(u32::MAX) * CHACHA_BLOCKSIZE as u64;
+            let mut ks = reader(0);
+            assert_eq!(ks.remaining(), max);
+            ks.fill_bytes(&mut [0u8; 10]).unwrap();
+            assert_eq!(ks.remaining(), max - 10);
+
+            assert_eq!(reader(1).remaining(), max);
+            assert_eq!(reader(u32::MAX).remaining(), CHACHA_BLOCKSIZE as u64);
+        }
+
+        #[test]
+        fn test_counter_overflow_err() {
+            let mut expected = [0u8; 2 * CHACHA_BLOCKSIZE];
+            keystream_by_encrypt(u32::MAX - 1, &mut expected);
+
+            let mut reader = reader(u32::MAX - 1);
+            let mut actual = [0u8; 2 * CHACHA_BLOCKSIZE + 1];
+            assert!(reader.fill_bytes(&mut actual).is_err());
+            // No keystream was consumed by the failed call.
+            assert_eq!(reader.remaining(), 2 * CHACHA_BLOCKSIZE as u64);
+
+            reader.fill_bytes(&mut actual[..10]).unwrap();
+            reader
+                .fill_bytes(&mut actual[10..2 * CHACHA_BLOCKSIZE])
+                .unwrap();
+            assert_eq!(actual[..2 * CHACHA_BLOCKSIZE], expected[..]);
+            assert_eq!(reader.remaining(), 0);
+            assert!(reader.fill_bytes(&mut actual[..1]).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_io_read() {
+            use std::io::Read;
+
+            let mut expected = [0u8; 2 * CHACHA_BLOCKSIZE];
+            keystream_by_encrypt(u32::MAX - 1, &mut expected);
+
+            let mut actual = Vec::new();
+            reader(u32::MAX - 1).read_to_end(&mut actual).unwrap();
+            assert_eq!(actual[..], expected[..]);
+
+            let mut reader = reader(u32::MAX);
+            let mut buf = [0u8; 100];
+            assert_eq!(reader.read(&mut buf).unwrap(), CHACHA_BLOCKSIZE);
+            assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_debug_impl() {
+            let debug = format!("{:?}", reader(0));
+            let expected = "KeystreamReader { ctx: [***OMITTED***], next_counter: 0, \
+            end_counter: 4294967295, buffer: [***OMITTED***], position: 64 }";
+            assert_eq!(debug, expected);
+        }
+    }
+
     // hex crate uses Vec<u8>, so we need std.
     mod test_hchacha20 {
         use super::*;
@@ -447,13 +1068,16 @@ mod public {
         }
 
         pub fn hchacha_test_runner(key: &str, nonce: &str, output_expected: &str) {
-            let actual: [u8; 32] = hchacha20(
+            let actual = hchacha20(
                 &SecretKey::from_slice(&decode(key).unwrap()).unwrap(),
                 &decode(nonce).unwrap(),
             )
             .unwrap();
 
-            assert_eq!(&actual, &decode(output_expected).unwrap()[..]);
+            assert_eq!(
+                actual.unprotected_as_bytes(),
+                &decode(output_expected).unwrap()[..]
+            );
         }
 
         // Testing against Monocypher-generated test vectors
@@ -918,7 +1542,7 @@ mod private {
         fn test_xor_keystream_err_bad_tmp() {
             let mut ctx =
                 ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
-            let mut tmp = [0u8; CHACHA_BLOCKSIZE - 1];
+            let mut tmp = [0u8; KEYSTREAM_BUFSIZE - 1];
             let mut out = [0u8; CHACHA_BLOCKSIZE];
             xor_keystream(&mut ctx, 0, &mut tmp, &mut out).unwrap();
         }
@@ -927,7 +1551,7 @@ mod private {
         fn test_xor_keystream_err_empty_input() {
             let mut ctx =
                 ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
-            let mut tmp = [0u8; CHACHA_BLOCKSIZE];
+            let mut tmp = [0u8; KEYSTREAM_BUFSIZE];
             let mut out = [0u8; 0];
             assert!(xor_keystream(&mut ctx, 0, &mut tmp, &mut out).is_err());
         }
@@ -939,6 +1563,114 @@ mod private {
             let mut out = [0u8; 0];
             assert!(encrypt_in_place(&sk, &n, 0, &mut out).is_err());
         }
+
+        #[test]
+        fn test_enc_in_place_err_counter_overflow_leaves_input() {
+            let n = Nonce::from([0u8; IETF_CHACHA_NONCESIZE]);
+            let sk = SecretKey::from([0u8; CHACHA_KEYSIZE]);
+
+            let mut bytes = [0u8; CHACHA_BLOCKSIZE];
+            assert!(encrypt_in_place(&sk, &n, u32::MAX, &mut bytes).is_ok());
+            assert_ne!(bytes, [0u8; CHACHA_BLOCKSIZE]);
+
+            // The last block of the keystream is u32::MAX, so a second block
+            // cannot be produced and nothing should be processed.
+            let mut bytes = [0u8; CHACHA_BLOCKSIZE + 1];
+            assert!(encrypt_in_place(&sk, &n, u32::MAX, &mut bytes).is_err());
+            assert_eq!(bytes, [0u8; CHACHA_BLOCKSIZE + 1]);
+        }
+    }
+
+    mod test_keystream_blocks_wide {
+        use super::*;
+
+        fn wide_equals_sequential(key: &[u8], nonce: &[u8], initial_counter: u32) -> bool {
+            let mut ctx_wide = ChaCha20::new(key, nonce, true).unwrap();
+            let mut ctx_seq = ChaCha20::new(key, nonce, true).unwrap();
+
+            let mut wide = [0u8; PAR_BLOCKS * CHACHA_BLOCKSIZE];
+            let mut seq = [0u8; PAR_BLOCKS * CHACHA_BLOCKSIZE];
+            ctx_wide
+                .keystream_blocks_wide(initial_counter, &mut wide)
+                .unwrap();
+            for (idx, block) in seq.chunks_exact_mut(CHACHA_BLOCKSIZE).enumerate() {
+                ctx_seq
+                    .keystream_block(initial_counter + idx as u32, block)
+                    .unwrap();
+            }
+
+            wide[..] == seq[..] && ctx_wide.internal_counter == ctx_seq.internal_counter
+        }
+
+        #[test]
+        fn test_wide_equals_sequential() {
+            assert!(wide_equals_sequential(
+                &[0u8; CHACHA_KEYSIZE],
+                &[0u8; IETF_CHACHA_NONCESIZE],
+                0
+            ));
+            assert!(wide_equals_sequential(
+                &[255u8; CHACHA_KEYSIZE],
+                &[255u8; IETF_CHACHA_NONCESIZE],
+                u32::MAX - 3
+            ));
+        }
+
+        #[test]
+        fn test_xor_keystream_counter_overflow_in_batch() {
+            // Five blocks, starting 3 blocks before the counter overflows.
+            let mut ctx =
+                ChaCha20::new(&[0u8; CHACHA_KEYSIZE], &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+            let mut tmp = [0u8; KEYSTREAM_BUFSIZE];
+            let mut out = [0u8; CHACHA_BLOCKSIZE * 5];
+            assert_eq!(
+                xor_keystream(&mut ctx, u32::MAX - 2, &mut tmp, &mut out)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::NonceOverflow
+            );
+
+            let mut out = [0u8; CHACHA_BLOCKSIZE * 4];
+            let mut expected = [0u8; CHACHA_BLOCKSIZE * 4];
+            assert!(xor_keystream(&mut ctx, u32::MAX - 3, &mut tmp, &mut out).is_ok());
+            for (idx, block) in expected.chunks_exact_mut(CHACHA_BLOCKSIZE).enumerate() {
+                ctx.keystream_block(u32::MAX - 3 + idx as u32, block)
+                    .unwrap();
+            }
+            assert_eq!(out[..], expected[..]);
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_xor_keystream_lengths_around_batch() {
+            let mut ctx =
+                ChaCha20::new(&[1u8; CHACHA_KEYSIZE], &[2u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+            let mut tmp = [0u8; KEYSTREAM_BUFSIZE];
+            let mut keystream_block = [0u8; CHACHA_BLOCKSIZE];
+
+            for len in 1..(CHACHA_BLOCKSIZE * 9) {
+                let mut out = vec![0u8; len];
+                let mut expected = vec![0u8; len];
+                xor_keystream(&mut ctx, 7, &mut tmp, &mut out).unwrap();
+                for (idx, block) in expected.chunks_mut(CHACHA_BLOCKSIZE).enumerate() {
+                    ctx.keystream_block(7 + idx as u32, &mut keystream_block)
+                        .unwrap();
+                    block.copy_from_slice(&keystream_block[..block.len()]);
+                }
+                assert_eq!(out, expected);
+            }
+        }
+
+        #[quickcheck]
+        #[cfg(feature = "safe_api")]
+        fn prop_wide_equals_sequential(key: Vec<u8>, nonce: Vec<u8>, counter: u32) -> bool {
+            let mut key = key;
+            let mut nonce = nonce;
+            key.resize(CHACHA_KEYSIZE, 0);
+            nonce.resize(IETF_CHACHA_NONCESIZE, 0);
+
+            wide_equals_sequential(&key, &nonce, counter.min(u32::MAX - 3))
+        }
     }
 
     mod test_keystream_block {
@@ -952,8 +1684,12 @@ mod private {
             let mut hchacha_keystream_block_zero = [0u8; HCHACHA_OUTSIZE];
             let mut hchacha_keystream_block_max = [0u8; HCHACHA_OUTSIZE];
 
-            chacha_state_hchacha.keystream_block(0, &mut hchacha_keystream_block_zero);
-            chacha_state_hchacha.keystream_block(u32::MAX, &mut hchacha_keystream_block_max);
+            chacha_state_hchacha
+                .keystream_block(0, &mut hchacha_keystream_block_zero)
+                .unwrap();
+            chacha_state_hchacha
+                .keystream_block(u32::MAX, &mut hchacha_keystream_block_max)
+                .unwrap();
 
             assert_eq!(hchacha_keystream_block_zero, hchacha_keystream_block_max);
         }
@@ -968,8 +1704,12 @@ mod private {
             let mut ietf_keystream_block = [0u8; CHACHA_BLOCKSIZE];
             let mut hchacha_keystream_block = [0u8; HCHACHA_OUTSIZE];
 
-            chacha_state_ietf.keystream_block(0, &mut ietf_keystream_block);
-            chacha_state_ietf.keystream_block(0, &mut hchacha_keystream_block);
+            chacha_state_ietf
+                .keystream_block(0, &mut ietf_keystream_block)
+                .unwrap();
+            chacha_state_ietf
+                .keystream_block(0, &mut hchacha_keystream_block)
+                .unwrap();
         }
 
         #[cfg(debug_assertions)]
@@ -982,13 +1722,16 @@ mod private {
             let mut ietf_keystream_block = [0u8; CHACHA_BLOCKSIZE];
             let mut hchacha_keystream_block = [0u8; HCHACHA_OUTSIZE];
 
-            chacha_state_hchacha.keystream_block(0, &mut hchacha_keystream_block);
-            chacha_state_hchacha.keystream_block(0, &mut ietf_keystream_block);
+            chacha_state_hchacha
+                .keystream_block(0, &mut hchacha_keystream_block)
+                .unwrap();
+            chacha_state_hchacha
+                .keystream_block(0, &mut ietf_keystream_block)
+                .unwrap();
         }
 
         #[test]
-        #[should_panic]
-        fn test_xor_keystream_panic_on_too_much_keystream_data_ietf() {
+        fn test_xor_keystream_err_on_too_much_keystream_data_ietf() {
             let mut chacha_state_ietf = ChaCha20 {
                 state: [
                     U32x4(0, 0, 0, 0),
@@ -1002,14 +1745,18 @@ mod private {
 
             let mut keystream_block = [0u8; CHACHA_BLOCKSIZE];
 
-            for amount in 0..(128 + 1) {
-                chacha_state_ietf.keystream_block(amount, &mut keystream_block);
+            for amount in 0..128 {
+                assert!(chacha_state_ietf
+                    .keystream_block(amount, &mut keystream_block)
+                    .is_ok());
             }
+            assert!(chacha_state_ietf
+                .keystream_block(128, &mut keystream_block)
+                .is_err());
         }
 
         #[test]
-        #[should_panic]
-        fn test_xor_keystream_panic_on_too_much_keystream_data_hchacha() {
+        fn test_xor_keystream_err_on_too_much_keystream_data_hchacha() {
             let mut chacha_state_ietf = ChaCha20 {
                 state: [
                     U32x4(0, 0, 0, 0),
@@ -1023,9 +1770,14 @@ mod private {
 
             let mut keystream_block = [0u8; HCHACHA_OUTSIZE];
 
-            for _ in 0..(128 + 1) {
-                chacha_state_ietf.keystream_block(0, &mut keystream_block);
+            for _ in 0..128 {
+                assert!(chacha_state_ietf
+                    .keystream_block(0, &mut keystream_block)
+                    .is_ok());
             }
+            assert!(chacha_state_ietf
+                .keystream_block(0, &mut keystream_block)
+                .is_err());
         }
     }
 }
@@ -1078,7 +1830,7 @@ mod test_vectors {
         assert!(state.state[..] == expected_init[..]);
 
         let mut kb = [0u8; 64];
-        state.keystream_block(1, &mut kb);
+        state.keystream_block(1, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1103,7 +1855,7 @@ mod test_vectors {
 
         let mut state = init(&key, &nonce).unwrap();
         let mut kb = [0u8; 64];
-        state.keystream_block(0, &mut kb);
+        state.keystream_block(0, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1128,7 +1880,7 @@ mod test_vectors {
 
         let mut state = init(&key, &nonce).unwrap();
         let mut kb = [0u8; 64];
-        state.keystream_block(1, &mut kb);
+        state.keystream_block(1, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1153,7 +1905,7 @@ mod test_vectors {
 
         let mut state = init(&key, &nonce).unwrap();
         let mut kb = [0u8; 64];
-        state.keystream_block(1, &mut kb);
+        state.keystream_block(1, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1178,7 +1930,7 @@ mod test_vectors {
 
         let mut state = init(&key, &nonce).unwrap();
         let mut kb = [0u8; 64];
-        state.keystream_block(2, &mut kb);
+        state.keystream_block(2, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1203,7 +1955,7 @@ mod test_vectors {
 
         let mut state = init(&key, &nonce).unwrap();
         let mut kb = [0u8; 64];
-        state.keystream_block(0, &mut kb);
+        state.keystream_block(0, &mut kb).unwrap();
 
         assert_eq!(kb[..], expected[..]);
     }
@@ -1249,10 +2001,14 @@ mod test_vectors {
         let mut state = init(&key, &nonce).unwrap();
         let mut actual_keystream = [0u8; 128];
 
-        state.keystream_block(1, &mut actual_keystream[..64]);
+        state
+            .keystream_block(1, &mut actual_keystream[..64])
+            .unwrap();
         assert!(first_state == state.state);
 
-        state.keystream_block(2, &mut actual_keystream[64..]);
+        state
+            .keystream_block(2, &mut actual_keystream[64..])
+            .unwrap();
         assert!(second_state == state.state);
 
         assert_eq!(
@@ -25,3 +25,6 @@ pub mod chacha20;
 
 /// XChaCha20 as specified in the [draft-irtf-cfrg-xchacha-03](https://tools.ietf.org/html/draft-irtf-cfrg-xchacha-03).
 pub mod xchacha20;
+
+/// AES-256 in CTR mode as specified in the [NIST SP 800-38A](https://nvlpubs.nist.gov/nistpubs/Legacy/SP/nistspecialpublication800-38a.pdf).
+pub mod aes256ctr;
@@ -24,6 +24,8 @@
 //! - `secret_key`: The secret key.
 //! - `nonce`: The nonce value.
 //! - `initial_counter`: The initial counter value. In most cases, this is `0`.
+//! - `counter`: The counter value of a single keystream block.
+//! - `offset`: The byte offset into the keystream at which to start encryption/decryption.
 //! - `ciphertext`: The encrypted data.
 //! - `plaintext`: The data to be encrypted.
 //! - `dst_out`: Destination array that will hold the ciphertext/plaintext after
@@ -34,15 +36,14 @@
 //! - The length of `dst_out` is less than `plaintext` or `ciphertext`.
 //! - `plaintext` or `ciphertext` is empty.
 //! - The `initial_counter` is high enough to cause a potential overflow.
+//! - `offset` plus the length of `plaintext` or `ciphertext` is greater than
+//!   the length of the keystream, `2^32 * 64` bytes.
+//! - More than `2^32-1 * 64` bytes of data would be processed.
 //!
 //! Even though `dst_out` is allowed to be of greater length than `plaintext`,
 //! the `ciphertext` produced by `chacha20`/`xchacha20` will always be of the
 //! same length as the `plaintext`.
 //!
-//! # Panics:
-//! A panic will occur if:
-//! - More than `2^32-1 * 64` bytes of data are processed.
-//!
 //! # Security:
 //! - It is critical for security that a given nonce is not re-used with a given
 //!   key. Should this happen, the security of all data that has been encrypted
@@ -75,6 +76,15 @@
 //! xchacha20::decrypt(&secret_key, &nonce, 0, &dst_out_ct, &mut dst_out_pt)?;
 //!
 //! assert_eq!(dst_out_pt, message);
+//!
+//! // Random access: decrypt only bytes 5 to 10 of the ciphertext.
+//! let mut part = [0u8; 5];
+//! xchacha20::decrypt_at_offset(&secret_key, &nonce, 5, &dst_out_ct[5..10], &mut part)?;
+//! assert_eq!(part, message[5..10]);
+//!
+//! // A single block of keystream can be computed directly.
+//! let mut keystream = [0u8; 64];
+//! xchacha20::keystream_block(&secret_key, &nonce, 0, &mut keystream);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 //! [`SecretKey::generate()`]: xchacha20::SecretKey::generate()
@@ -83,7 +93,9 @@
 pub use crate::hazardous::stream::chacha20::SecretKey;
 use crate::{
     errors::UnknownCryptoError,
-    hazardous::stream::chacha20::{self, Nonce as IETFNonce, IETF_CHACHA_NONCESIZE},
+    hazardous::stream::chacha20::{
+        self, Nonce as IETFNonce, CHACHA_BLOCKSIZE, IETF_CHACHA_NONCESIZE,
+    },
 };
 
 /// The nonce size for XChaCha20.
@@ -105,10 +117,12 @@ construct_public! {
 impl_from_trait!(Nonce, XCHACHA_NONCESIZE);
 
 /// Generate a subkey using HChaCha20 for XChaCha20 and corresponding nonce.
+#[allow(clippy::unwrap_used)]
 pub(crate) fn subkey_and_nonce(secret_key: &SecretKey, nonce: &Nonce) -> (SecretKey, IETFNonce) {
-    // .unwrap() should not be able to panic because we pass a 16-byte nonce.
-    let subkey: SecretKey =
-        SecretKey::from(chacha20::hchacha20(secret_key, &nonce.as_ref()[0..16]).unwrap());
+    // .unwrap() cannot panic because we pass a 16-byte nonce, and the subkey
+    // is always the size of a SecretKey.
+    let subkey = chacha20::hchacha20(secret_key, &nonce.as_ref()[0..16]).unwrap();
+    let subkey = SecretKey::from_slice(subkey.unprotected_as_bytes()).unwrap();
     let mut prefixed_nonce = [0u8; IETF_CHACHA_NONCESIZE];
     prefixed_nonce[4..IETF_CHACHA_NONCESIZE].copy_from_slice(&nonce.as_ref()[16..24]);
 
@@ -141,6 +155,43 @@ pub fn decrypt(
     encrypt(secret_key, nonce, initial_counter, ciphertext, dst_out)
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// XChaCha20 encryption, starting at the byte `offset` of the keystream.
+pub fn encrypt_at_offset(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    offset: u64,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+
+    chacha20::encrypt_at_offset(&subkey, &ietf_nonce, offset, plaintext, dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// XChaCha20 decryption, starting at the byte `offset` of the keystream.
+pub fn decrypt_at_offset(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    offset: u64,
+    ciphertext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    encrypt_at_offset(secret_key, nonce, offset, ciphertext, dst_out)
+}
+
+/// XChaCha20 keystream block with the block counter `counter`.
+pub fn keystream_block(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    counter: u32,
+    dst_out: &mut [u8; CHACHA_BLOCKSIZE],
+) {
+    let (subkey, ietf_nonce) = subkey_and_nonce(secret_key, nonce);
+    chacha20::keystream_block(&subkey, &ietf_nonce, counter, dst_out);
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -168,4 +219,66 @@ mod public {
             true
         }
     }
+
+    mod test_at_offset {
+        use super::*;
+
+        #[test]
+        fn test_same_as_encrypt() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let nonce = Nonce::from([2u8; XCHACHA_NONCESIZE]);
+            let plaintext = [7u8; 300];
+            let mut expected = [0u8; 300];
+            encrypt(&secret_key, &nonce, 0, &plaintext, &mut expected).unwrap();
+
+            for offset in [0usize, 1, 63, 64, 100].iter() {
+                let mut actual = [0u8; 300];
+                encrypt_at_offset(
+                    &secret_key,
+                    &nonce,
+                    *offset as u64,
+                    &plaintext[*offset..],
+                    &mut actual,
+                )
+                .unwrap();
+                assert_eq!(actual[..300 - offset], expected[*offset..]);
+
+                let mut decrypted = [0u8; 300];
+                decrypt_at_offset(
+                    &secret_key,
+                    &nonce,
+                    *offset as u64,
+                    &expected[*offset..],
+                    &mut decrypted,
+                )
+                .unwrap();
+                assert_eq!(decrypted[..300 - offset], plaintext[*offset..]);
+            }
+        }
+    }
+
+    mod test_keystream_block {
+        use super::*;
+
+        #[test]
+        fn test_same_as_encrypt() {
+            let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+            let nonce = Nonce::from([2u8; XCHACHA_NONCESIZE]);
+
+            for counter in [0u32, 1, 7, u32::MAX].iter() {
+                let mut expected = [0u8; CHACHA_BLOCKSIZE];
+                encrypt(
+                    &secret_key,
+                    &nonce,
+                    *counter,
+                    &[0u8; CHACHA_BLOCKSIZE],
+                    &mut expected,
+                )
+                .unwrap();
+                let mut actual = [0u8; CHACHA_BLOCKSIZE];
+                keystream_block(&secret_key, &nonce, *counter, &mut actual);
+                assert_eq!(actual[..], expected[..]);
+            }
+        }
+    }
 }
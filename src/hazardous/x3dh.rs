@@ -0,0 +1,610 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # About:
+//! An asynchronous key agreement based on prekeys, following the design of [X3DH], for starting
+//! a [`ratchet`](super::ratchet) session with a party that may be offline.
+//!
+//! The responder publishes a prekey bundle: its [`IdentityPublicKey`], a signed prekey with the
+//! [`Signature`] from [`sign_prekey()`], and optionally one-time prekeys, which are each used
+//! only once. The initiator uses a bundle with [`initiate()`], and sends its own
+//! [`IdentityPublicKey`], the returned ephemeral [`PublicKey`] and the one-time prekey it used
+//! together with its first message. The responder then computes the same [`Agreement`] with
+//! [`respond()`].
+//!
+//! - The shared secret is HKDF-SHA256 of the X25519 shared secrets between the identity key
+//!   and signed prekey, the ephemeral key and identity key, the ephemeral key and signed
+//!   prekey, and the ephemeral key and one-time prekey, if one is used.
+//! - Signal's X3DH signs prekeys with XEdDSA. Since orion does not implement Ed25519, an
+//!   [`IdentitySecretKey`] is instead a seed from which both an X25519 key and an ML-DSA-65
+//!   signing key are derived, and prekeys are signed with ML-DSA-65. This is not compatible
+//!   with X3DH of Signal.
+//! - The [`Agreement::associated_data()`] is SHA-256 of the identity public keys of the
+//!   initiator and the responder, and should be passed as the `ad` of the ratchet.
+//!
+//! # Parameters:
+//! - `identity_secret_key`: The own identity secret key.
+//! - `remote_identity_key`: The identity public key of the other party.
+//! - `signed_prekey`: The signed prekey of the responder.
+//! - `signature`: The signature of the signed prekey, from [`sign_prekey()`].
+//! - `one_time_prekey`: A one-time prekey of the responder, if any was used.
+//! - `ephemeral_key`: The ephemeral public key that [`initiate()`] returned.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `signature` is not a valid signature of `signed_prekey` by `remote_identity_key`.
+//! - The length of a key or signature passed to `from_slice()` is incorrect.
+//! - An X25519 shared secret is all zero, because a public key is a low-order point.
+//! - Failure to generate random bytes securely.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using `generate()`.
+//!
+//! # Security:
+//! - The identity public key of the other party must be obtained from a trusted source, or
+//!   verified by the users, such as by comparing
+//!   [`IdentityPublicKey::fingerprint()`].
+//! - The responder must delete the secret key of a one-time prekey once it has been used. A
+//!   one-time prekey protects against replays of the first message; without one, the same
+//!   first message gives the same [`Agreement`] each time it is received.
+//! - Signed prekeys should be replaced regularly, and their secret keys deleted some time after,
+//!   so that old sessions remain secure if a signed prekey is compromised later.
+//!
+//! # Example:
+//! ```rust
+//! # #[cfg(feature = "safe_api")] {
+//! use orion::hazardous::{ratchet, x3dh};
+//!
+//! // Bob publishes his prekey bundle.
+//! let bob_identity = x3dh::IdentitySecretKey::generate();
+//! let bob_signed_prekey = ratchet::SecretKey::generate();
+//! let bob_one_time_prekey = ratchet::SecretKey::generate();
+//! let signature = x3dh::sign_prekey(&bob_identity, &bob_signed_prekey.public_key())?;
+//!
+//! // Alice uses the bundle, while Bob is offline.
+//! let alice_identity = x3dh::IdentitySecretKey::generate();
+//! let (alice_agreement, ephemeral_key) = x3dh::initiate(
+//!     &alice_identity,
+//!     &bob_identity.public_key()?,
+//!     &bob_signed_prekey.public_key(),
+//!     &signature,
+//!     Some(&bob_one_time_prekey.public_key()),
+//! )?;
+//! let mut alice = ratchet::Session::initiate(
+//!     alice_agreement.shared_secret(),
+//!     &bob_signed_prekey.public_key(),
+//! )?;
+//! let mut message = [0u8; 5 + ratchet::OVERHEAD];
+//! alice.encrypt(b"Hello", alice_agreement.associated_data(), &mut message)?;
+//!
+//! // Bob receives Alice's identity key, the ephemeral key and the first message.
+//! let bob_agreement = x3dh::respond(
+//!     &bob_identity,
+//!     &alice_identity.public_key()?,
+//!     &bob_signed_prekey,
+//!     Some(&bob_one_time_prekey),
+//!     &ephemeral_key,
+//! )?;
+//! let mut bob = ratchet::Session::respond(bob_agreement.shared_secret(), &bob_signed_prekey);
+//! let mut plaintext = [0u8; 5];
+//! bob.decrypt(&message, bob_agreement.associated_data(), &mut plaintext)?;
+//! assert_eq!(&plaintext, b"Hello");
+//! # }
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [X3DH]: https://signal.org/docs/specifications/x3dh/
+
+pub use super::ratchet::{PublicKey, SecretKey, SharedSecret};
+pub use super::sig::mldsa65::Signature;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::ecc::x25519::{scalarmult, scalarmult_base, X25519_SIZE};
+use crate::hazardous::hash::sha2::sha256::Sha256;
+use crate::hazardous::kdf::hkdf;
+use crate::hazardous::sig::mldsa65::{self, MLDSA65_PUBLICKEYSIZE};
+use crate::util::Fingerprint;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// The size of an [`IdentitySecretKey`].
+pub const IDENTITY_SECRETKEYSIZE: usize = 32;
+/// The size of an [`IdentityPublicKey`]: an X25519 public key followed by an ML-DSA-65
+/// public key.
+pub const IDENTITY_PUBLICKEYSIZE: usize = X25519_SIZE + MLDSA65_PUBLICKEYSIZE;
+/// The size of [`Agreement::associated_data()`].
+pub const ASSOCIATED_DATASIZE: usize = 32;
+
+const INFO_IDENTITY: &[u8] = b"orion x3dh identity key";
+const INFO_SHARED_SECRET: &[u8] = b"orion x3dh shared secret";
+const SIGNED_PREKEY_PREFIX: &[u8] = b"orion x3dh signed prekey";
+
+construct_secret_key! {
+    /// A type to represent the `IdentitySecretKey` of a party. This is a seed, from which the
+    /// X25519 identity key and the ML-DSA-65 key that signs prekeys are derived.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (IdentitySecretKey, test_identity_secret_key, IDENTITY_SECRETKEYSIZE, IDENTITY_SECRETKEYSIZE, IDENTITY_SECRETKEYSIZE)
+}
+
+impl_from_trait!(IdentitySecretKey, IDENTITY_SECRETKEYSIZE);
+
+impl IdentitySecretKey {
+    /// Derive the X25519 secret key and the ML-DSA-65 secret key.
+    fn expand(&self) -> Result<([u8; X25519_SIZE], mldsa65::SecretKey), UnknownCryptoError> {
+        let mut okm = [0u8; X25519_SIZE + 32];
+        hkdf::sha256::derive_key(&[0u8; 32], &self.value, Some(INFO_IDENTITY), &mut okm)?;
+        let mut dh = [0u8; X25519_SIZE];
+        dh.copy_from_slice(&okm[..X25519_SIZE]);
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&okm[X25519_SIZE..]);
+        let signing = mldsa65::SecretKey::from(seed);
+        okm.zeroize();
+        seed.zeroize();
+
+        Ok((dh, signing))
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return the [`IdentityPublicKey`] that belongs to this secret key.
+    pub fn public_key(&self) -> Result<IdentityPublicKey, UnknownCryptoError> {
+        let (mut dh, signing) = self.expand()?;
+        let mut value = [0u8; IDENTITY_PUBLICKEYSIZE];
+        value[..X25519_SIZE].copy_from_slice(&scalarmult_base(&dh));
+        value[X25519_SIZE..].copy_from_slice(mldsa65::PublicKey::from(&signing).as_ref());
+        dh.zeroize();
+
+        Ok(IdentityPublicKey::from(value))
+    }
+}
+
+construct_public! {
+    /// A type to represent the `IdentityPublicKey` of a party.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 1984 bytes.
+    (IdentityPublicKey, test_identity_public_key, IDENTITY_PUBLICKEYSIZE, IDENTITY_PUBLICKEYSIZE)
+}
+
+impl_from_trait!(IdentityPublicKey, IDENTITY_PUBLICKEYSIZE);
+
+impl IdentityPublicKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the [`Fingerprint`] of this public key, for users to compare.
+    pub fn fingerprint(&self) -> Result<Fingerprint, UnknownCryptoError> {
+        Fingerprint::of(self.as_ref())
+    }
+
+    fn dh(&self) -> [u8; X25519_SIZE] {
+        let mut ret = [0u8; X25519_SIZE];
+        ret.copy_from_slice(&self.value[..X25519_SIZE]);
+        ret
+    }
+
+    fn signing(&self) -> Result<mldsa65::PublicKey, UnknownCryptoError> {
+        mldsa65::PublicKey::from_slice(&self.value[X25519_SIZE..])
+    }
+}
+
+/// The result of the key agreement.
+pub struct Agreement {
+    shared_secret: SharedSecret,
+    associated_data: [u8; ASSOCIATED_DATASIZE],
+}
+
+impl core::fmt::Debug for Agreement {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Agreement {{ shared_secret: ***OMITTED***, associated_data: {:?} }}",
+            self.associated_data
+        )
+    }
+}
+
+impl Agreement {
+    /// Return the shared secret, which is the first root key of a
+    /// [`ratchet::Session`](super::ratchet::Session).
+    pub fn shared_secret(&self) -> &SharedSecret {
+        &self.shared_secret
+    }
+
+    /// Return the associated data, which binds the identity keys of both parties.
+    pub fn associated_data(&self) -> &[u8; ASSOCIATED_DATASIZE] {
+        &self.associated_data
+    }
+}
+
+/// Build the message that the signature of `signed_prekey` covers.
+fn signed_prekey_message(
+    signed_prekey: &PublicKey,
+) -> [u8; SIGNED_PREKEY_PREFIX.len() + X25519_SIZE] {
+    let mut message = [0u8; SIGNED_PREKEY_PREFIX.len() + X25519_SIZE];
+    message[..SIGNED_PREKEY_PREFIX.len()].copy_from_slice(SIGNED_PREKEY_PREFIX);
+    message[SIGNED_PREKEY_PREFIX.len()..].copy_from_slice(signed_prekey.as_ref());
+
+    message
+}
+
+/// X25519 that rejects an all-zero output.
+fn dh(
+    secret_key: &[u8; X25519_SIZE],
+    public_key: &[u8; X25519_SIZE],
+) -> Result<[u8; X25519_SIZE], UnknownCryptoError> {
+    let mut shared = scalarmult(secret_key, public_key);
+    if bool::from(shared.ct_eq(&[0u8; X25519_SIZE])) {
+        shared.zeroize();
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    Ok(shared)
+}
+
+/// Derive the [`Agreement`] from the shared secrets, in the order of the X3DH specification.
+fn agreement(
+    dh_outputs: &[Result<[u8; X25519_SIZE], UnknownCryptoError>],
+    initiator: &IdentityPublicKey,
+    responder: &IdentityPublicKey,
+) -> Result<Agreement, UnknownCryptoError> {
+    // 32 0xFF bytes are prepended as in the X3DH specification, followed by at most four
+    // shared secrets.
+    let mut ikm = [0xFFu8; X25519_SIZE * 5];
+    let mut ikm_len = X25519_SIZE;
+    let mut failed = None;
+    for dh_output in dh_outputs.iter() {
+        match dh_output {
+            Ok(shared) => ikm[ikm_len..ikm_len + X25519_SIZE].copy_from_slice(shared),
+            Err(err) => failed = Some(*err),
+        }
+        ikm_len += X25519_SIZE;
+    }
+
+    let mut okm = [0u8; 32];
+    let derived = match failed {
+        Some(err) => Err(err),
+        None => hkdf::sha256::derive_key(
+            &[0u8; 32],
+            &ikm[..ikm_len],
+            Some(INFO_SHARED_SECRET),
+            &mut okm,
+        ),
+    };
+    ikm.zeroize();
+    derived?;
+    let shared_secret = SharedSecret::from(okm);
+    okm.zeroize();
+
+    let mut ctx = Sha256::new();
+    ctx.update(initiator.as_ref())?;
+    ctx.update(responder.as_ref())?;
+    let mut associated_data = [0u8; ASSOCIATED_DATASIZE];
+    associated_data.copy_from_slice(ctx.finalize()?.as_ref());
+
+    Ok(Agreement {
+        shared_secret,
+        associated_data,
+    })
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Sign the public key of `signed_prekey` with the identity key, for the prekey bundle.
+pub fn sign_prekey(
+    identity_secret_key: &IdentitySecretKey,
+    signed_prekey: &PublicKey,
+) -> Result<Signature, UnknownCryptoError> {
+    let (mut dh, signing) = identity_secret_key.expand()?;
+    dh.zeroize();
+
+    mldsa65::sign(&signing, &signed_prekey_message(signed_prekey))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compute the [`Agreement`] of the initiator from the prekey bundle of the responder. Returns
+/// the agreement and the ephemeral public key, which must be sent to the responder.
+pub fn initiate(
+    identity_secret_key: &IdentitySecretKey,
+    remote_identity_key: &IdentityPublicKey,
+    signed_prekey: &PublicKey,
+    signature: &Signature,
+    one_time_prekey: Option<&PublicKey>,
+) -> Result<(Agreement, PublicKey), UnknownCryptoError> {
+    let own_identity_key = identity_secret_key.public_key()?;
+    mldsa65::verify(
+        signature,
+        &remote_identity_key.signing()?,
+        &signed_prekey_message(signed_prekey),
+    )?;
+
+    let mut ephemeral = [0u8; X25519_SIZE];
+    crate::util::secure_rand_bytes(&mut ephemeral)?;
+    let (mut identity, _) = identity_secret_key.expand()?;
+    let mut signed_prekey_bytes = [0u8; X25519_SIZE];
+    signed_prekey_bytes.copy_from_slice(signed_prekey.as_ref());
+
+    let mut dh_outputs = [
+        dh(&identity, &signed_prekey_bytes),
+        dh(&ephemeral, &remote_identity_key.dh()),
+        dh(&ephemeral, &signed_prekey_bytes),
+        Err(UnknownCryptoError::new(ErrorKind::InvalidState)),
+    ];
+    let mut used = 3;
+    if let Some(one_time_prekey) = one_time_prekey {
+        let mut one_time_prekey_bytes = [0u8; X25519_SIZE];
+        one_time_prekey_bytes.copy_from_slice(one_time_prekey.as_ref());
+        dh_outputs[3] = dh(&ephemeral, &one_time_prekey_bytes);
+        used = 4;
+    }
+
+    let ret = agreement(&dh_outputs[..used], &own_identity_key, remote_identity_key);
+    let ephemeral_key = PublicKey::from(scalarmult_base(&ephemeral));
+    identity.zeroize();
+    ephemeral.zeroize();
+    for dh_output in dh_outputs.iter_mut().flatten() {
+        dh_output.zeroize();
+    }
+
+    Ok((ret?, ephemeral_key))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compute the [`Agreement`] of the responder, from the identity key and ephemeral key of the
+/// initiator.
+pub fn respond(
+    identity_secret_key: &IdentitySecretKey,
+    remote_identity_key: &IdentityPublicKey,
+    signed_prekey: &SecretKey,
+    one_time_prekey: Option<&SecretKey>,
+    ephemeral_key: &PublicKey,
+) -> Result<Agreement, UnknownCryptoError> {
+    let own_identity_key = identity_secret_key.public_key()?;
+    let (mut identity, _) = identity_secret_key.expand()?;
+    let mut signed_prekey_bytes = [0u8; X25519_SIZE];
+    signed_prekey_bytes.copy_from_slice(signed_prekey.unprotected_as_bytes());
+    let mut ephemeral_bytes = [0u8; X25519_SIZE];
+    ephemeral_bytes.copy_from_slice(ephemeral_key.as_ref());
+
+    let mut dh_outputs = [
+        dh(&signed_prekey_bytes, &remote_identity_key.dh()),
+        dh(&identity, &ephemeral_bytes),
+        dh(&signed_prekey_bytes, &ephemeral_bytes),
+        Err(UnknownCryptoError::new(ErrorKind::InvalidState)),
+    ];
+    let mut used = 3;
+    if let Some(one_time_prekey) = one_time_prekey {
+        let mut one_time_prekey_bytes = [0u8; X25519_SIZE];
+        one_time_prekey_bytes.copy_from_slice(one_time_prekey.unprotected_as_bytes());
+        dh_outputs[3] = dh(&one_time_prekey_bytes, &ephemeral_bytes);
+        one_time_prekey_bytes.zeroize();
+        used = 4;
+    }
+
+    let ret = agreement(&dh_outputs[..used], remote_identity_key, &own_identity_key);
+    identity.zeroize();
+    signed_prekey_bytes.zeroize();
+    for dh_output in dh_outputs.iter_mut().flatten() {
+        dh_output.zeroize();
+    }
+
+    ret
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::ratchet::{Session, OVERHEAD};
+
+    struct Bob {
+        identity: IdentitySecretKey,
+        signed_prekey: SecretKey,
+        one_time_prekey: SecretKey,
+        signature: Signature,
+    }
+
+    fn bob() -> Bob {
+        let identity = IdentitySecretKey::generate();
+        let signed_prekey = SecretKey::generate();
+        let signature = sign_prekey(&identity, &signed_prekey.public_key()).unwrap();
+
+        Bob {
+            identity,
+            signed_prekey,
+            one_time_prekey: SecretKey::generate(),
+            signature,
+        }
+    }
+
+    #[test]
+    fn test_agreement_matches() {
+        let bob = bob();
+        let alice = IdentitySecretKey::generate();
+
+        for use_one_time_prekey in [false, true].iter() {
+            let one_time_public = bob.one_time_prekey.public_key();
+            let (alice_agreement, ephemeral_key) = initiate(
+                &alice,
+                &bob.identity.public_key().unwrap(),
+                &bob.signed_prekey.public_key(),
+                &bob.signature,
+                if *use_one_time_prekey {
+                    Some(&one_time_public)
+                } else {
+                    None
+                },
+            )
+            .unwrap();
+            let bob_agreement = respond(
+                &bob.identity,
+                &alice.public_key().unwrap(),
+                &bob.signed_prekey,
+                if *use_one_time_prekey {
+                    Some(&bob.one_time_prekey)
+                } else {
+                    None
+                },
+                &ephemeral_key,
+            )
+            .unwrap();
+
+            assert_eq!(
+                alice_agreement.shared_secret(),
+                bob_agreement.shared_secret()
+            );
+            assert_eq!(
+                alice_agreement.associated_data(),
+                bob_agreement.associated_data()
+            );
+
+            // The responder must use the same one-time prekey.
+            let without = respond(
+                &bob.identity,
+                &alice.public_key().unwrap(),
+                &bob.signed_prekey,
+                if *use_one_time_prekey {
+                    None
+                } else {
+                    Some(&bob.one_time_prekey)
+                },
+                &ephemeral_key,
+            )
+            .unwrap();
+            assert_ne!(alice_agreement.shared_secret(), without.shared_secret());
+        }
+    }
+
+    #[test]
+    fn test_ratchet_session() {
+        let bob = bob();
+        let alice = IdentitySecretKey::generate();
+        let (alice_agreement, ephemeral_key) = initiate(
+            &alice,
+            &bob.identity.public_key().unwrap(),
+            &bob.signed_prekey.public_key(),
+            &bob.signature,
+            None,
+        )
+        .unwrap();
+        let bob_agreement = respond(
+            &bob.identity,
+            &alice.public_key().unwrap(),
+            &bob.signed_prekey,
+            None,
+            &ephemeral_key,
+        )
+        .unwrap();
+
+        let mut alice_session = Session::initiate(
+            alice_agreement.shared_secret(),
+            &bob.signed_prekey.public_key(),
+        )
+        .unwrap();
+        let mut bob_session = Session::respond(bob_agreement.shared_secret(), &bob.signed_prekey);
+
+        let mut message = [0u8; 5 + OVERHEAD];
+        let mut plaintext = [0u8; 5];
+        alice_session
+            .encrypt(b"Hello", alice_agreement.associated_data(), &mut message)
+            .unwrap();
+        // The associated data binds the identity keys.
+        assert!(bob_session
+            .decrypt(&message, &[0u8; ASSOCIATED_DATASIZE], &mut plaintext)
+            .is_err());
+        bob_session
+            .decrypt(&message, bob_agreement.associated_data(), &mut plaintext)
+            .unwrap();
+        assert_eq!(&plaintext, b"Hello");
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let bob = bob();
+        let alice = IdentitySecretKey::generate();
+        let other_prekey = SecretKey::generate().public_key();
+
+        // The signature is of another prekey.
+        assert!(initiate(
+            &alice,
+            &bob.identity.public_key().unwrap(),
+            &other_prekey,
+            &bob.signature,
+            None
+        )
+        .is_err());
+        // The signature is by another identity.
+        assert!(initiate(
+            &alice,
+            &alice.public_key().unwrap(),
+            &bob.signed_prekey.public_key(),
+            &bob.signature,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_low_order_keys() {
+        let bob = bob();
+        let alice = IdentitySecretKey::generate();
+        let zero = PublicKey::from([0u8; X25519_SIZE]);
+        assert!(initiate(
+            &alice,
+            &bob.identity.public_key().unwrap(),
+            &bob.signed_prekey.public_key(),
+            &bob.signature,
+            Some(&zero),
+        )
+        .is_err());
+        assert!(respond(
+            &bob.identity,
+            &alice.public_key().unwrap(),
+            &bob.signed_prekey,
+            None,
+            &zero
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_identity_public_key() {
+        let identity = IdentitySecretKey::from_slice(&[7u8; 32]).unwrap();
+        let public_key = identity.public_key().unwrap();
+        assert_eq!(public_key, identity.public_key().unwrap());
+        assert_eq!(
+            IdentityPublicKey::from_slice(public_key.as_ref()).unwrap(),
+            public_key
+        );
+        assert_ne!(
+            IdentitySecretKey::from_slice(&[8u8; 32])
+                .unwrap()
+                .public_key()
+                .unwrap(),
+            public_key
+        );
+        assert!(public_key.fingerprint().is_ok());
+    }
+}
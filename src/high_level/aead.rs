@@ -31,31 +31,51 @@
 //!
 //! # About:
 //! - Both one-shot functions and a [`streaming`] API are provided.
-//! - The nonce is automatically generated.
+//! - The nonce is automatically generated, except for [`seal_with_nonce`] which is meant
+//!   for expert use only.
 //! - Returns a vector where the first 24 bytes are the nonce and the rest is
 //!   the authenticated ciphertext with the last 16 bytes being the corresponding Poly1305 tag.
-//! - Uses XChaCha20Poly1305 with no additional data.
+//! - Uses XChaCha20Poly1305 with no additional data, except for [`seal_with_context`] and
+//!   [`open_with_context`] which authenticate a `context` as additional data.
 //! - When using [`seal`] and [`open`] then the separation of tags, nonces and
 //!   ciphertext are automatically handled.
+//! - [`open_secret`] returns the plaintext as a [`SecretBytes`], which is zeroized
+//!   when dropped, instead of a plain `Vec<u8>`.
+//! - [`seal_into`] and [`open_into`] write to a caller-provided buffer instead of
+//!   allocating, and return the amount of bytes written. [`SEAL_OVERHEAD`] is the amount
+//!   of bytes sealing adds to a plaintext, so that such buffers can be sized at compile time.
+//! - [`open_with_keyring`] attempts decryption with several keys, such as during key rotation.
+//! - [`seal_multi`] encrypts a message once for multiple recipients, each of which can
+//!   decrypt it with [`open_multi`] and their own key.
+//! - With the `serde` feature, `seal_value` and `open_value` serialize any `Serialize` type
+//!   and encrypt it, such as for caches, cookies or configuration secrets.
 //!
 //! # Parameters:
 //! - `plaintext`:  The data to be encrypted.
 //! - `secret_key`: The secret key used to encrypt the `plaintext`.
 //! - `ciphertext_with_tag_and_nonce`:  The data to be decrypted with the first
 //!   24 bytes being the nonce and the last 16 bytes being the corresponding Poly1305 tag.
+//! - `dst_out`: The buffer that [`seal_into`]/[`open_into`] write the output to.
+//! - `nonce`: The nonce used by [`seal_with_nonce`]/[`open_with_nonce`].
+//! - `context`: A string that identifies the purpose of a ciphertext, such as `"db-backup"`.
+//! - `ciphertext_with_tag`: The data to be decrypted by [`open_with_nonce`], with the last
+//!   16 bytes being the corresponding Poly1305 tag.
 //!
 //! # Errors:
 //! An error will be returned if:
 //! - `secret_key` is not 32 bytes.
 //! - The `plaintext` is empty.
-//! - `ciphertext_with_tag_and_nonce` is less than 41 bytes
-//!   ([`XCHACHA_NONCESIZE`] + [`POLY1305_OUTSIZE`] + 1).
+//! - `ciphertext_with_tag_and_nonce` is less than 41 bytes ([`SEAL_OVERHEAD`] + 1).
+//! - `ciphertext_with_tag` is less than 17 bytes ([`POLY1305_OUTSIZE`] + 1).
 //! - The received tag does not match the calculated tag when calling [`open`].
-//! - `plaintext.len()` + [`XCHACHA_NONCESIZE`] + [`POLY1305_OUTSIZE`] overflows when calling [`seal`].
+//! - `plaintext.len()` + [`SEAL_OVERHEAD`] overflows when calling [`seal`].
+//! - `dst_out` is shorter than the output of [`seal_into`]/[`open_into`].
+//! - The received tag does not match the calculated tag when calling [`open_with_nonce`].
+//! - The `context` given to [`open_with_context`] is not the one used when sealing.
+//! - More than 2^32-2 * 64 bytes of data are processed.
 //!
 //! # Panics:
 //! A panic will occur if:
-//! - More than 2^32-1 * 64 bytes of data are processed.
 //! - Failure to generate random bytes securely.
 //!
 //! # Security:
@@ -64,6 +84,11 @@
 //! the security of all data that has been encrypted with that given key is
 //! compromised.
 //! - To securely generate a strong key, use [`SecretKey::default()`].
+//! - When using [`seal_with_nonce`], the caller is responsible for never re-using a nonce
+//!   with the same key.
+//! - When the same key is used for several purposes, give each its own `context` with
+//!   [`seal_with_context`], so that a ciphertext created for one purpose cannot be
+//!   presented as belonging to another.
 //! - The length of the `plaintext` is not hidden, only its contents.
 //!
 //! # Example:
@@ -73,38 +98,84 @@
 //! let secret_key = aead::SecretKey::default();
 //! let ciphertext = aead::seal(&secret_key, "Secret message".as_bytes())?;
 //! let decrypted_data = aead::open(&secret_key, &ciphertext)?;
+//!
+//! // Encrypting a message once for multiple recipients.
+//! let recipient_keys = [aead::SecretKey::default(), aead::SecretKey::default()];
+//! let sealed = aead::seal_multi(&recipient_keys, "Secret message".as_bytes())?;
+//! let decrypted_data = aead::open_multi(&recipient_keys[1], &sealed)?;
+//!
+//! // Encrypting into fixed-size buffers, without allocating.
+//! const MSG_LEN: usize = 14;
+//! let mut sealed = [0u8; MSG_LEN + aead::SEAL_OVERHEAD];
+//! aead::seal_into(&secret_key, b"Secret message", &mut sealed)?;
+//! let mut decrypted = [0u8; MSG_LEN];
+//! aead::open_into(&secret_key, &sealed, &mut decrypted)?;
+//! assert_eq!(&decrypted, b"Secret message");
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 
-pub use super::hltypes::SecretKey;
+pub use super::hltypes::{SecretBytes, SecretKey};
+pub use crate::hazardous::stream::xchacha20::Nonce;
 use crate::{
-    errors::UnknownCryptoError,
+    errors::{ErrorKind, UnknownCryptoError},
     hazardous::{
         aead,
         mac::poly1305::POLY1305_OUTSIZE,
-        stream::{
-            chacha20,
-            xchacha20::{Nonce, XCHACHA_NONCESIZE},
-        },
+        stream::{chacha20, xchacha20::XCHACHA_NONCESIZE},
     },
 };
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+/// The amount of bytes that [`seal`] adds to a plaintext: a [`XCHACHA_NONCESIZE`] byte
+/// nonce and a [`POLY1305_OUTSIZE`] byte tag.
+pub const SEAL_OVERHEAD: usize = XCHACHA_NONCESIZE + POLY1305_OUTSIZE;
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Authenticated encryption using XChaCha20Poly1305.
 pub fn seal(secret_key: &SecretKey, plaintext: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    let out_len = match plaintext.len().checked_add(SEAL_OVERHEAD) {
+        Some(min_out_len) => min_out_len,
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let mut dst_out = vec![0u8; out_len];
+    seal_into(secret_key, plaintext, &mut dst_out)?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated encryption using XChaCha20Poly1305, writing the nonce, ciphertext and tag to
+/// `dst_out` instead of allocating. Returns the amount of bytes written to `dst_out`, which is
+/// `plaintext.len()` + [`SEAL_OVERHEAD`].
+pub fn seal_into(
+    secret_key: &SecretKey,
+    plaintext: &[u8],
+    dst_out: &mut [u8],
+) -> Result<usize, UnknownCryptoError> {
+    seal_into_with_ad(secret_key, plaintext, None, dst_out)
+}
+
+/// Shared implementation of [`seal_into`] and [`seal_with_context`].
+pub(super) fn seal_into_with_ad(
+    secret_key: &SecretKey,
+    plaintext: &[u8],
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<usize, UnknownCryptoError> {
     if plaintext.is_empty() {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
-    let out_len = match plaintext
-        .len()
-        .checked_add(XCHACHA_NONCESIZE + POLY1305_OUTSIZE)
-    {
+    let out_len = match plaintext.len().checked_add(SEAL_OVERHEAD) {
         Some(min_out_len) => min_out_len,
-        None => return Err(UnknownCryptoError),
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
     };
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
 
-    let mut dst_out = vec![0u8; out_len];
     let nonce = Nonce::generate();
     dst_out[..XCHACHA_NONCESIZE].copy_from_slice(nonce.as_ref());
 
@@ -112,11 +183,11 @@ pub fn seal(secret_key: &SecretKey, plaintext: &[u8]) -> Result<Vec<u8>, Unknown
         &chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?,
         &nonce,
         plaintext,
-        None,
-        &mut dst_out[XCHACHA_NONCESIZE..],
+        ad,
+        &mut dst_out[XCHACHA_NONCESIZE..out_len],
     )?;
 
-    Ok(dst_out)
+    Ok(out_len)
 }
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -126,17 +197,159 @@ pub fn open(
     ciphertext_with_tag_and_nonce: &[u8],
 ) -> Result<Vec<u8>, UnknownCryptoError> {
     // Avoid empty ciphertexts
-    if ciphertext_with_tag_and_nonce.len() <= (XCHACHA_NONCESIZE + POLY1305_OUTSIZE) {
-        return Err(UnknownCryptoError);
+    if ciphertext_with_tag_and_nonce.len() <= SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut dst_out = vec![0u8; ciphertext_with_tag_and_nonce.len() - SEAL_OVERHEAD];
+    open_into(secret_key, ciphertext_with_tag_and_nonce, &mut dst_out)?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption using XChaCha20Poly1305, writing the plaintext to `dst_out` instead
+/// of allocating. Returns the amount of bytes written to `dst_out`, which is
+/// `ciphertext_with_tag_and_nonce.len()` - [`SEAL_OVERHEAD`].
+pub fn open_into(
+    secret_key: &SecretKey,
+    ciphertext_with_tag_and_nonce: &[u8],
+    dst_out: &mut [u8],
+) -> Result<usize, UnknownCryptoError> {
+    open_into_with_ad(secret_key, ciphertext_with_tag_and_nonce, None, dst_out)
+}
+
+/// Shared implementation of [`open_into`] and [`open_with_context`].
+pub(super) fn open_into_with_ad(
+    secret_key: &SecretKey,
+    ciphertext_with_tag_and_nonce: &[u8],
+    ad: Option<&[u8]>,
+    dst_out: &mut [u8],
+) -> Result<usize, UnknownCryptoError> {
+    // Avoid empty ciphertexts
+    if ciphertext_with_tag_and_nonce.len() <= SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
 
-    let mut dst_out =
-        vec![0u8; ciphertext_with_tag_and_nonce.len() - (XCHACHA_NONCESIZE + POLY1305_OUTSIZE)];
+    let out_len = ciphertext_with_tag_and_nonce.len() - SEAL_OVERHEAD;
+    if dst_out.len() < out_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
 
     aead::xchacha20poly1305::open(
         &chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?,
         &Nonce::from_slice(&ciphertext_with_tag_and_nonce[..XCHACHA_NONCESIZE])?,
         &ciphertext_with_tag_and_nonce[XCHACHA_NONCESIZE..],
+        ad,
+        &mut dst_out[..out_len],
+    )?;
+
+    Ok(out_len)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated encryption using XChaCha20Poly1305, bound to `context`.
+///
+/// The `context` is authenticated as additional data, but is not part of the output. The
+/// ciphertext can only be opened by [`open_with_context`] with the same `context`. An empty
+/// `context` produces the same result as [`seal`].
+pub fn seal_with_context(
+    secret_key: &SecretKey,
+    context: &str,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    let out_len = match plaintext.len().checked_add(SEAL_OVERHEAD) {
+        Some(min_out_len) => min_out_len,
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let mut dst_out = vec![0u8; out_len];
+    seal_into_with_ad(
+        secret_key,
+        plaintext,
+        Some(context.as_bytes()),
+        &mut dst_out,
+    )?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption using XChaCha20Poly1305, of a ciphertext produced by
+/// [`seal_with_context`] with the same `context`.
+pub fn open_with_context(
+    secret_key: &SecretKey,
+    context: &str,
+    ciphertext_with_tag_and_nonce: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    // Avoid empty ciphertexts
+    if ciphertext_with_tag_and_nonce.len() <= SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut dst_out = vec![0u8; ciphertext_with_tag_and_nonce.len() - SEAL_OVERHEAD];
+    open_into_with_ad(
+        secret_key,
+        ciphertext_with_tag_and_nonce,
+        Some(context.as_bytes()),
+        &mut dst_out,
+    )?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated encryption using XChaCha20Poly1305 with a caller-provided nonce. The nonce is
+/// not part of the output, which is the ciphertext followed by the Poly1305 tag.
+///
+/// # Security:
+/// __**This is meant for expert use only**__, such as reproducing test vectors or protocols
+/// that transmit the nonce separately. The caller is responsible for never re-using a nonce
+/// with the same key. Prefer [`seal`], which generates the nonce automatically.
+pub fn seal_with_nonce(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    if plaintext.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let out_len = match plaintext.len().checked_add(POLY1305_OUTSIZE) {
+        Some(min_out_len) => min_out_len,
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let mut dst_out = vec![0u8; out_len];
+    aead::xchacha20poly1305::seal(
+        &chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?,
+        nonce,
+        plaintext,
+        None,
+        &mut dst_out,
+    )?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption using XChaCha20Poly1305 with a detached nonce, of a ciphertext
+/// produced by [`seal_with_nonce`].
+pub fn open_with_nonce(
+    secret_key: &SecretKey,
+    nonce: &Nonce,
+    ciphertext_with_tag: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    // Avoid empty ciphertexts
+    if ciphertext_with_tag.len() <= POLY1305_OUTSIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut dst_out = vec![0u8; ciphertext_with_tag.len() - POLY1305_OUTSIZE];
+    aead::xchacha20poly1305::open(
+        &chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?,
+        nonce,
+        ciphertext_with_tag,
         None,
         &mut dst_out,
     )?;
@@ -144,6 +357,233 @@ pub fn open(
     Ok(dst_out)
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption using XChaCha20Poly1305, returning the plaintext as a
+/// [`SecretBytes`] which is zeroized when dropped.
+pub fn open_secret(
+    secret_key: &SecretKey,
+    ciphertext_with_tag_and_nonce: &[u8],
+) -> Result<SecretBytes, UnknownCryptoError> {
+    open(secret_key, ciphertext_with_tag_and_nonce).map(SecretBytes::from)
+}
+
+#[cfg(feature = "serde")]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Serialize `value` and encrypt it using XChaCha20Poly1305, as with [`seal`].
+///
+/// The value is serialized in the format of the default configuration of the
+/// [`bincode`](https://crates.io/crates/bincode) 1.x crate. Intermediate buffers holding the
+/// serialized value are zeroized.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `secret_key` is not 32 bytes.
+/// - `value` cannot be serialized, e.g. when it is a sequence of unknown length.
+/// - `value` serializes to zero bytes, e.g. when it is `()`.
+pub fn seal_value<T: serde::Serialize + ?Sized>(
+    secret_key: &SecretKey,
+    value: &T,
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    let mut plaintext = crate::serde_bincode::to_vec(value)?;
+    let sealed = seal(secret_key, &plaintext);
+    plaintext.zeroize();
+
+    sealed
+}
+
+#[cfg(feature = "serde")]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decrypt a ciphertext produced by [`seal_value`] and deserialize the value.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `secret_key` is not 32 bytes.
+/// - `ciphertext_with_tag_and_nonce` is less than 41 bytes.
+/// - The received tag does not match the calculated tag, in which case the
+///   [`ErrorKind`](crate::errors::ErrorKind) is `MacMismatch`.
+/// - The decrypted data is not a serialized `T`, in which case the
+///   [`ErrorKind`](crate::errors::ErrorKind) is `InvalidEncoding`.
+pub fn open_value<T: serde::de::DeserializeOwned>(
+    secret_key: &SecretKey,
+    ciphertext_with_tag_and_nonce: &[u8],
+) -> Result<T, UnknownCryptoError> {
+    let mut plaintext = open(secret_key, ciphertext_with_tag_and_nonce)?;
+    let value = crate::serde_bincode::from_slice(&plaintext).map_err(UnknownCryptoError::from);
+    plaintext.zeroize();
+
+    value
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption using XChaCha20Poly1305, with each key in `keyring` as a candidate.
+/// Returns the index of the first key in `keyring` that could open `ciphertext_with_tag_and_nonce`,
+/// and the plaintext.
+///
+/// This allows key rotation, where ciphertexts produced with older keys should remain readable.
+/// Every key is attempted, in order, regardless of whether an earlier one succeeded.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `keyring` is empty.
+/// - Any key in `keyring` is not 32 bytes.
+/// - `ciphertext_with_tag_and_nonce` is less than 41 bytes.
+/// - None of the keys in `keyring` can open `ciphertext_with_tag_and_nonce`.
+///
+/// # Security:
+/// - Decryption time grows with the length of `keyring`, but not with which key succeeded.
+/// - Keys that should no longer be accepted must be removed from `keyring`.
+pub fn open_with_keyring(
+    keyring: &[SecretKey],
+    ciphertext_with_tag_and_nonce: &[u8],
+) -> Result<(usize, Vec<u8>), UnknownCryptoError> {
+    if keyring.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+    for key in keyring.iter() {
+        chacha20::SecretKey::from_slice(key.unprotected_as_bytes())?;
+    }
+    // Avoid empty ciphertexts
+    if ciphertext_with_tag_and_nonce.len() <= SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut dst_out = vec![0u8; ciphertext_with_tag_and_nonce.len() - SEAL_OVERHEAD];
+    let mut opened_with: Option<usize> = None;
+    for (idx, key) in keyring.iter().enumerate() {
+        // The tag is verified before anything is written to dst_out, so
+        // failed attempts leave it untouched.
+        if open_into(key, ciphertext_with_tag_and_nonce, &mut dst_out).is_ok()
+            && opened_with.is_none()
+        {
+            opened_with = Some(idx);
+        }
+    }
+
+    match opened_with {
+        Some(idx) => Ok((idx, dst_out)),
+        None => Err(UnknownCryptoError::new(ErrorKind::MacMismatch)),
+    }
+}
+
+/// The size of each recipient's wrapped content key in the output of [`seal_multi`].
+const WRAPPED_KEY_SIZE: usize = XCHACHA_NONCESIZE + chacha20::CHACHA_KEYSIZE + POLY1305_OUTSIZE;
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated encryption of `plaintext` for multiple recipients using XChaCha20Poly1305.
+///
+/// The `plaintext` is encrypted once, under a random content key. The content key is
+/// encrypted under each of `recipient_keys`, so that any one of them can be used with
+/// [`open_multi`].
+///
+/// The output is the number of recipients (`u16`, little-endian), followed by one wrapped
+/// content key of 72 bytes per recipient, in the order of `recipient_keys`, followed by the
+/// nonce, ciphertext and tag of the `plaintext`. Everything before the encrypted `plaintext`
+/// is authenticated as its additional data.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `recipient_keys` is empty or holds more than `u16::MAX` keys.
+/// - Any of `recipient_keys` is not 32 bytes.
+/// - The `plaintext` is empty.
+///
+/// # Security:
+/// - Every recipient can decrypt the content key, and can therefore create a new message that
+///   the other recipients will accept. This does not authenticate which recipient sent a message.
+/// - The number of recipients is not hidden.
+pub fn seal_multi(
+    recipient_keys: &[SecretKey],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    if recipient_keys.is_empty() || recipient_keys.len() > u16::MAX as usize {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+    if plaintext.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let header_len = 2 + recipient_keys.len() * WRAPPED_KEY_SIZE;
+    let out_len = match plaintext.len().checked_add(header_len + SEAL_OVERHEAD) {
+        Some(min_out_len) => min_out_len,
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let content_key = chacha20::SecretKey::generate();
+
+    let mut dst_out = vec![0u8; out_len];
+    dst_out[..2].copy_from_slice(&(recipient_keys.len() as u16).to_le_bytes());
+    for (recipient_key, wrapped) in recipient_keys
+        .iter()
+        .zip(dst_out[2..header_len].chunks_exact_mut(WRAPPED_KEY_SIZE))
+    {
+        seal_into(recipient_key, content_key.unprotected_as_bytes(), wrapped)?;
+    }
+
+    let (header, payload) = dst_out.split_at_mut(header_len);
+    let nonce = Nonce::generate();
+    payload[..XCHACHA_NONCESIZE].copy_from_slice(nonce.as_ref());
+    aead::xchacha20poly1305::seal(
+        &content_key,
+        &nonce,
+        plaintext,
+        Some(header),
+        &mut payload[XCHACHA_NONCESIZE..],
+    )?;
+
+    Ok(dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticated decryption of the output of [`seal_multi`], using the key of one of its
+/// recipients.
+///
+/// Every wrapped content key is attempted with `secret_key`, in order, regardless of whether
+/// an earlier one was successfully opened.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `secret_key` is not 32 bytes.
+/// - `sealed` is not a well-formed output of [`seal_multi`].
+/// - `secret_key` is not the key of any recipient.
+/// - The received tag does not match the calculated tag.
+pub fn open_multi(secret_key: &SecretKey, sealed: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
+    if sealed.len() < 2 {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let recipients = u16::from_le_bytes([sealed[0], sealed[1]]) as usize;
+    let header_len = 2 + recipients * WRAPPED_KEY_SIZE;
+    if recipients == 0 || sealed.len() <= header_len + SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let (header, payload) = sealed.split_at(header_len);
+    let mut content_key: Option<chacha20::SecretKey> = None;
+    let mut buf = [0u8; chacha20::CHACHA_KEYSIZE];
+    for wrapped in header[2..].chunks_exact(WRAPPED_KEY_SIZE) {
+        if open_into(secret_key, wrapped, &mut buf).is_ok() && content_key.is_none() {
+            content_key = Some(chacha20::SecretKey::from(buf));
+        }
+    }
+    buf.zeroize();
+
+    let content_key = match content_key {
+        Some(key) => key,
+        None => return Err(UnknownCryptoError::new(ErrorKind::MacMismatch)),
+    };
+
+    let mut dst_out = vec![0u8; payload.len() - SEAL_OVERHEAD];
+    aead::xchacha20poly1305::open(
+        &content_key,
+        &Nonce::from_slice(&payload[..XCHACHA_NONCESIZE])?,
+        &payload[XCHACHA_NONCESIZE..],
+        Some(header),
+        &mut dst_out,
+    )?;
+
+    Ok(dst_out)
+}
+
 pub mod streaming {
     //! Streaming AEAD based on XChaCha20Poly1305.
     //!
@@ -158,8 +598,11 @@ pub mod streaming {
     //!
     //! # Parameters:
     //! - `secret_key`: The secret key.
-    //! - `nonce`: The nonce value.
+    //! - `header`: The [`StreamHeader`] generated for a stream by `StreamSealer::new()`. It is not
+    //!   secret, but must be given to `StreamOpener::new()` to open the stream.
     //! - `plaintext`: The data to be encrypted.
+    //! - `ad`: Additional data to authenticate with a chunk. It is not encrypted and not part of
+    //!   `ciphertext`, so it must be given again when opening.
     //! - `ciphertext`: The encrypted data with a Poly1305 tag and a [`StreamTag`] indicating its function.
     //! - `tag`: Indicates the type of message. The `tag` is a part of the output when encrypting. It
     //! is encrypted and authenticated.
@@ -179,12 +622,18 @@ pub mod streaming {
     //! - Failure to generate random bytes securely.
     //!
     //! # Security:
-    //! - It is critical for security that a given nonce is not re-used with a given
-    //!   key.
+    //! - It is critical for security that a given header is not re-used with a given
+    //!   key. `StreamSealer::new()` always generates a new, random header.
     //! - To securely generate a strong key, use [`SecretKey::generate()`].
     //! - The length of the messages is leaked.
     //! - It is recommended to use `StreamTag::Finish` as tag for the last message. This allows the
     //!   decrypting side to detect if messages at the end of the stream are lost.
+    //! - Chunks are already bound to their position within a stream. Per-chunk `ad` can be used to
+    //!   also bind them to application context, such as a file ID, so that a stream of chunks
+    //!   cannot be presented as belonging to another file.
+    //! - A [`RekeyPolicy`] can be set to automatically rekey after a number of messages or bytes.
+    //!   Both sides must use the same policy, otherwise decryption fails once the sides rekey at
+    //!   different points.
     //!
     //! # Example:
     //! ```rust
@@ -192,8 +641,8 @@ pub mod streaming {
     //! use orion::aead::SecretKey;
     //!
     //! let key = SecretKey::default();
-    //! let (mut sealer, nonce) = StreamSealer::new(&key)?;
-    //! let mut opener = StreamOpener::new(&key, &nonce)?;
+    //! let (mut sealer, header) = StreamSealer::new(&key)?;
+    //! let mut opener = StreamOpener::new(&key, &header)?;
     //!
     //! // Message 1
     //! let plaintext1 = b"Secret message 1";
@@ -216,15 +665,38 @@ pub mod streaming {
     //! assert_eq!(plaintext3, &dec3.as_ref());
     //! assert_eq!(tag3, StreamTag::Finish);
     //!
+    //! // Binding chunks to application context with additional data.
+    //! let (mut sealer, header) = StreamSealer::new(&key)?;
+    //! let mut opener = StreamOpener::new(&key, &header)?;
+    //!
+    //! let cipher = sealer.seal_chunk_with_ad(b"Chunk 0", b"file-id: 42", StreamTag::Finish)?;
+    //! let (dec, tag) = opener.open_chunk_with_ad(&cipher, b"file-id: 42")?;
+    //! assert_eq!(&dec, b"Chunk 0");
+    //! assert_eq!(tag, StreamTag::Finish);
+    //!
+    //! // Automatically rekeying after every 1000 messages.
+    //! let policy = RekeyPolicy::never().after_messages(1000)?;
+    //! let (mut sealer, header) = StreamSealer::new(&key)?;
+    //! let mut opener = StreamOpener::new(&key, &header)?;
+    //! sealer.set_rekey_policy(policy);
+    //! opener.set_rekey_policy(policy);
+    //!
+    //! let cipher = sealer.seal_chunk(b"Chunk 0", StreamTag::Finish)?;
+    //! let (dec, _) = opener.open_chunk(&cipher)?;
+    //! assert_eq!(&dec, b"Chunk 0");
+    //!
     //! # Ok::<(), orion::errors::UnknownCryptoError>(())
     //! ```
     //! [`ABYTES`]: crate::hazardous::aead::streaming::ABYTES
+    //! [`RekeyPolicy`]: crate::hazardous::aead::streaming::RekeyPolicy
     //! [`StreamTag`]: crate::hazardous::aead::streaming::StreamTag
+    //! [`StreamHeader`]: crate::hazardous::aead::streaming::StreamHeader
     //! [`SecretKey::generate()`]: super::SecretKey::generate
     //! ["secretstream" API]: https://download.libsodium.org/doc/secret-key_cryptography/secretstream
 
     use super::*;
-    pub use crate::hazardous::aead::streaming::Nonce;
+    pub use crate::hazardous::aead::streaming::RekeyPolicy;
+    pub use crate::hazardous::aead::streaming::StreamHeader;
     pub use crate::hazardous::aead::streaming::StreamTag;
 
     #[derive(Debug)]
@@ -235,15 +707,22 @@ pub mod streaming {
 
     impl StreamSealer {
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-        /// Initialize a `StreamSealer` struct with a given key.
-        pub fn new(secret_key: &SecretKey) -> Result<(Self, Nonce), UnknownCryptoError> {
-            let nonce = Nonce::generate();
+        /// Initialize a `StreamSealer` struct with a given key. Returns the sealer and a randomly
+        /// generated [`StreamHeader`], which the `StreamOpener` needs.
+        pub fn new(secret_key: &SecretKey) -> Result<(Self, StreamHeader), UnknownCryptoError> {
+            let header = StreamHeader::generate();
             let sk = &aead::streaming::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
 
             let sealer = Self {
-                internal_sealer: aead::streaming::StreamXChaCha20Poly1305::new(sk, &nonce),
+                internal_sealer: aead::streaming::StreamXChaCha20Poly1305::from_header(sk, &header),
             };
-            Ok((sealer, nonce))
+            Ok((sealer, header))
+        }
+
+        /// Set the [`RekeyPolicy`] used to automatically rekey the stream. The `StreamOpener`
+        /// must use the same policy.
+        pub fn set_rekey_policy(&mut self, policy: RekeyPolicy) {
+            self.internal_sealer.set_rekey_policy(policy);
         }
 
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -253,14 +732,26 @@ pub mod streaming {
             plaintext: &[u8],
             tag: StreamTag,
         ) -> Result<Vec<u8>, UnknownCryptoError> {
-            let sealed_chunk_len = plaintext.len().checked_add(aead::streaming::ABYTES);
-            if sealed_chunk_len.is_none() {
-                return Err(UnknownCryptoError);
-            }
+            self.seal_chunk_with_ad(plaintext, &[], tag)
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Encrypts `plaintext` and authenticates it together with `ad`, which is not encrypted
+        /// and not part of the output. The `StreamTag` indicates the type of message.
+        pub fn seal_chunk_with_ad(
+            &mut self,
+            plaintext: &[u8],
+            ad: &[u8],
+            tag: StreamTag,
+        ) -> Result<Vec<u8>, UnknownCryptoError> {
+            let sealed_chunk_len = match plaintext.len().checked_add(aead::streaming::ABYTES) {
+                Some(len) => len,
+                None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+            };
 
-            let mut sealed_chunk = vec![0u8; sealed_chunk_len.unwrap()];
+            let mut sealed_chunk = vec![0u8; sealed_chunk_len];
             self.internal_sealer
-                .seal_chunk(plaintext, None, &mut sealed_chunk, tag)?;
+                .seal_chunk(plaintext, Some(ad), &mut sealed_chunk, tag)?;
 
             Ok(sealed_chunk)
         }
@@ -274,31 +765,65 @@ pub mod streaming {
 
     impl StreamOpener {
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-        /// Initialize a `StreamOpener` struct with a given key and nonce.
-        pub fn new(secret_key: &SecretKey, nonce: &Nonce) -> Result<Self, UnknownCryptoError> {
+        /// Initialize a `StreamOpener` struct with a given key and the [`StreamHeader`] of the
+        /// stream.
+        pub fn new(
+            secret_key: &SecretKey,
+            header: &StreamHeader,
+        ) -> Result<Self, UnknownCryptoError> {
             let sk = &chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
 
             Ok(Self {
-                internal_sealer: aead::streaming::StreamXChaCha20Poly1305::new(sk, nonce),
+                internal_sealer: aead::streaming::StreamXChaCha20Poly1305::from_header(sk, header),
             })
         }
+
+        /// Set the [`RekeyPolicy`] used to automatically rekey the stream. This must be the
+        /// same policy that the `StreamSealer` used.
+        pub fn set_rekey_policy(&mut self, policy: RekeyPolicy) {
+            self.internal_sealer.set_rekey_policy(policy);
+        }
+
         #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
         /// Decrypts `ciphertext`. Returns the decrypted data and the `StreamTag` indicating the type of message.
         pub fn open_chunk(
             &mut self,
             ciphertext: &[u8],
+        ) -> Result<(Vec<u8>, StreamTag), UnknownCryptoError> {
+            self.open_chunk_with_ad(ciphertext, &[])
+        }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Decrypts `ciphertext`, which must have been sealed with the same `ad`. Returns the
+        /// decrypted data and the `StreamTag` indicating the type of message.
+        pub fn open_chunk_with_ad(
+            &mut self,
+            ciphertext: &[u8],
+            ad: &[u8],
         ) -> Result<(Vec<u8>, StreamTag), UnknownCryptoError> {
             if ciphertext.len() < aead::streaming::ABYTES {
-                return Err(UnknownCryptoError);
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
             }
 
             let mut opened_chunk = vec![0u8; ciphertext.len() - aead::streaming::ABYTES];
             let tag = self
                 .internal_sealer
-                .open_chunk(ciphertext, None, &mut opened_chunk)?;
+                .open_chunk(ciphertext, Some(ad), &mut opened_chunk)?;
 
             Ok((opened_chunk, tag))
         }
+
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Decrypts `ciphertext`. Returns the decrypted data as a [`SecretBytes`], which is zeroized
+        /// when dropped, and the `StreamTag` indicating the type of message.
+        pub fn open_chunk_secret(
+            &mut self,
+            ciphertext: &[u8],
+        ) -> Result<(SecretBytes, StreamTag), UnknownCryptoError> {
+            let (opened_chunk, tag) = self.open_chunk(ciphertext)?;
+
+            Ok((SecretBytes::from(opened_chunk), tag))
+        }
     }
 }
 
@@ -388,6 +913,351 @@ mod public {
 
             assert!(seal(&key, plaintext).is_err());
             assert!(open(&key, plaintext).is_err());
+            assert!(open_secret(&key, plaintext).is_err());
+        }
+
+        #[test]
+        fn test_open_secret() {
+            let key = SecretKey::default();
+            let plaintext = "Secret message".as_bytes();
+
+            let mut dst_ciphertext = seal(&key, plaintext).unwrap();
+            let dst_plaintext = open_secret(&key, &dst_ciphertext).unwrap();
+            assert!(dst_plaintext == plaintext);
+            assert_eq!(
+                dst_plaintext.unprotected_as_bytes(),
+                &open(&key, &dst_ciphertext).unwrap()[..]
+            );
+
+            dst_ciphertext[25] ^= 1;
+            assert!(open_secret(&key, &dst_ciphertext).is_err());
+        }
+    }
+
+    mod test_seal_open_into {
+        use super::*;
+
+        #[test]
+        fn test_roundtrip_and_lengths() {
+            let key = SecretKey::default();
+            let plaintext = "Secret message".as_bytes();
+            let mut ciphertext = [0u8; 14 + XCHACHA_NONCESIZE + POLY1305_OUTSIZE + 10];
+            let mut decrypted = [0u8; 14 + 10];
+
+            assert!(seal_into(&key, plaintext, &mut ciphertext[..14 + 39]).is_err());
+            let ct_len = seal_into(&key, plaintext, &mut ciphertext).unwrap();
+            assert_eq!(ct_len, 14 + 40);
+            // Bytes after the output are not touched.
+            assert_eq!(&ciphertext[ct_len..], &[0u8; 10]);
+
+            assert!(open_into(&key, &ciphertext[..ct_len], &mut decrypted[..13]).is_err());
+            let pt_len = open_into(&key, &ciphertext[..ct_len], &mut decrypted).unwrap();
+            assert_eq!(&decrypted[..pt_len], plaintext);
+            assert_eq!(&decrypted[pt_len..], &[0u8; 10]);
+
+            assert_eq!(open(&key, &ciphertext[..ct_len]).unwrap(), plaintext);
+        }
+
+        #[test]
+        fn test_same_errors_as_seal_open() {
+            let key = SecretKey::default();
+            let mut buf = [0u8; 64];
+
+            assert!(seal_into(&key, b"", &mut buf).is_err());
+            assert!(open_into(&key, &[0u8; 40], &mut buf).is_err());
+            assert!(seal_into(&SecretKey::generate(31).unwrap(), b"a", &mut buf).is_err());
+
+            let mut ciphertext = seal(&key, b"Secret message").unwrap();
+            ciphertext[25] ^= 1;
+            assert!(open_into(&key, &ciphertext, &mut buf).is_err());
+        }
+
+        #[test]
+        fn test_seal_overhead() {
+            let key = SecretKey::default();
+            assert_eq!(SEAL_OVERHEAD, 40);
+            assert_eq!(seal(&key, b"a").unwrap().len(), 1 + SEAL_OVERHEAD);
+
+            let mut exact = [0u8; 14 + SEAL_OVERHEAD];
+            assert_eq!(
+                seal_into(&key, b"Secret message", &mut exact).unwrap(),
+                exact.len()
+            );
+        }
+    }
+
+    mod test_seal_open_with_nonce {
+        use super::*;
+
+        #[test]
+        fn test_roundtrip() {
+            let key = SecretKey::default();
+            let nonce = Nonce::generate();
+            let plaintext = "Secret message".as_bytes();
+
+            let ciphertext = seal_with_nonce(&key, &nonce, plaintext).unwrap();
+            assert_eq!(ciphertext.len(), plaintext.len() + POLY1305_OUTSIZE);
+            assert_eq!(
+                seal_with_nonce(&key, &nonce, plaintext).unwrap(),
+                ciphertext
+            );
+            assert_eq!(
+                open_with_nonce(&key, &nonce, &ciphertext).unwrap(),
+                plaintext
+            );
+
+            // Same as seal(), without the nonce prefix.
+            let mut with_nonce = nonce.as_ref().to_vec();
+            with_nonce.extend_from_slice(&ciphertext);
+            assert_eq!(open(&key, &with_nonce).unwrap(), plaintext);
+        }
+
+        #[test]
+        fn test_errors() {
+            let key = SecretKey::default();
+            let nonce = Nonce::generate();
+
+            assert!(seal_with_nonce(&key, &nonce, b"").is_err());
+            assert!(open_with_nonce(&key, &nonce, &[0u8; POLY1305_OUTSIZE]).is_err());
+            assert!(seal_with_nonce(&SecretKey::generate(31).unwrap(), &nonce, b"a").is_err());
+
+            let mut ciphertext = seal_with_nonce(&key, &nonce, b"Secret message").unwrap();
+            assert!(open_with_nonce(&key, &Nonce::generate(), &ciphertext).is_err());
+            assert!(open_with_nonce(&SecretKey::default(), &nonce, &ciphertext).is_err());
+            ciphertext[0] ^= 1;
+            assert!(open_with_nonce(&key, &nonce, &ciphertext).is_err());
+        }
+    }
+
+    mod test_seal_open_with_context {
+        use super::*;
+
+        #[test]
+        fn test_roundtrip() {
+            let key = SecretKey::default();
+            let plaintext = "Secret message".as_bytes();
+
+            let ciphertext = seal_with_context(&key, "db-backup", plaintext).unwrap();
+            assert_eq!(ciphertext.len(), plaintext.len() + 24 + 16);
+            assert_eq!(
+                open_with_context(&key, "db-backup", &ciphertext).unwrap(),
+                plaintext
+            );
+        }
+
+        #[test]
+        fn test_diff_context_err() {
+            let key = SecretKey::default();
+            let ciphertext = seal_with_context(&key, "db-backup", b"Secret message").unwrap();
+
+            assert!(open_with_context(&key, "session-cookie", &ciphertext).is_err());
+            assert!(open_with_context(&key, "", &ciphertext).is_err());
+            assert!(open(&key, &ciphertext).is_err());
+        }
+
+        #[test]
+        fn test_empty_context_same_as_seal() {
+            let key = SecretKey::default();
+            let ciphertext = seal_with_context(&key, "", b"Secret message").unwrap();
+            assert_eq!(open(&key, &ciphertext).unwrap(), b"Secret message");
+
+            let ciphertext = seal(&key, b"Secret message").unwrap();
+            assert_eq!(
+                open_with_context(&key, "", &ciphertext).unwrap(),
+                b"Secret message"
+            );
+        }
+
+        #[test]
+        fn test_errors() {
+            let key = SecretKey::default();
+            assert!(seal_with_context(&key, "ctx", b"").is_err());
+            assert!(open_with_context(&key, "ctx", &[0u8; 40]).is_err());
+            assert!(seal_with_context(&SecretKey::generate(31).unwrap(), "ctx", b"a").is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod test_seal_open_value {
+        use super::*;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Session {
+            user_id: u64,
+            name: String,
+            roles: Vec<String>,
+        }
+
+        fn session() -> Session {
+            Session {
+                user_id: 1337,
+                name: "orion".to_string(),
+                roles: vec!["admin".to_string()],
+            }
+        }
+
+        #[test]
+        fn test_seal_open_value() {
+            let sk = SecretKey::default();
+            let sealed = seal_value(&sk, &session()).unwrap();
+            assert_eq!(open_value::<Session>(&sk, &sealed).unwrap(), session());
+
+            // The plaintext is the bincode serialization.
+            let plaintext = open(&sk, &sealed).unwrap();
+            assert_eq!(plaintext, bincode::serialize(&session()).unwrap());
+            let sealed = seal(&sk, &plaintext).unwrap();
+            assert_eq!(open_value::<Session>(&sk, &sealed).unwrap(), session());
+        }
+
+        #[test]
+        fn test_tampered_and_wrong_type() {
+            let sk = SecretKey::default();
+            let mut sealed = seal_value(&sk, &session()).unwrap();
+
+            let err = open_value::<u64>(&sk, &sealed).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+            let err = open_value::<Session>(&SecretKey::default(), &sealed).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::MacMismatch);
+
+            let last = sealed.len() - 1;
+            sealed[last] ^= 1;
+            let err = open_value::<Session>(&sk, &sealed).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::MacMismatch);
+        }
+
+        #[test]
+        fn test_invalid_values() {
+            let sk = SecretKey::default();
+            assert!(seal_value(&sk, &()).is_err());
+            assert!(seal_value(&SecretKey::from_slice(&[0u8; 16]).unwrap(), &1u8).is_err());
+            assert!(seal_value(&sk, &1u8).is_ok());
+            assert!(seal_value(&sk, "str").is_ok());
+        }
+    }
+
+    mod test_open_with_keyring {
+        use super::*;
+
+        #[test]
+        fn test_returns_index_of_key() {
+            let keyring = [
+                SecretKey::default(),
+                SecretKey::default(),
+                SecretKey::default(),
+            ];
+            let plaintext = "Secret message".as_bytes();
+
+            for (idx, key) in keyring.iter().enumerate() {
+                let ciphertext = seal(key, plaintext).unwrap();
+                let (opened_with, decrypted) = open_with_keyring(&keyring, &ciphertext).unwrap();
+                assert_eq!(opened_with, idx);
+                assert_eq!(decrypted, plaintext);
+            }
+
+            let ciphertext = seal(&SecretKey::default(), plaintext).unwrap();
+            assert!(open_with_keyring(&keyring, &ciphertext).is_err());
+        }
+
+        #[test]
+        fn test_first_matching_key() {
+            let keyring = [
+                SecretKey::from_slice(&[1u8; 32]).unwrap(),
+                SecretKey::from_slice(&[0u8; 32]).unwrap(),
+                SecretKey::from_slice(&[0u8; 32]).unwrap(),
+            ];
+            let ciphertext = seal(&keyring[2], b"Secret message").unwrap();
+            assert_eq!(open_with_keyring(&keyring, &ciphertext).unwrap().0, 1);
+        }
+
+        #[test]
+        fn test_errors() {
+            let key = SecretKey::default();
+            let mut ciphertext = seal(&key, b"Secret message").unwrap();
+
+            assert!(open_with_keyring(&[], &ciphertext).is_err());
+            assert!(open_with_keyring(
+                &[SecretKey::default(), SecretKey::generate(31).unwrap()],
+                &ciphertext
+            )
+            .is_err());
+            assert!(open_with_keyring(&[SecretKey::default()], &ciphertext[..40]).is_err());
+
+            ciphertext[25] ^= 1;
+            assert!(open_with_keyring(&[key], &ciphertext).is_err());
+        }
+    }
+
+    mod test_seal_open_multi {
+        use super::*;
+
+        #[test]
+        fn test_every_recipient_can_open() {
+            let keys = [
+                SecretKey::default(),
+                SecretKey::default(),
+                SecretKey::default(),
+            ];
+            let plaintext = "Secret message".as_bytes();
+
+            let sealed = seal_multi(&keys, plaintext).unwrap();
+            assert_eq!(
+                sealed.len(),
+                2 + keys.len() * WRAPPED_KEY_SIZE + plaintext.len() + 24 + 16
+            );
+            for key in keys.iter() {
+                assert_eq!(open_multi(key, &sealed).unwrap(), plaintext);
+            }
+            assert!(open_multi(&SecretKey::default(), &sealed).is_err());
+        }
+
+        #[test]
+        fn test_errors() {
+            let key = SecretKey::default();
+            assert!(seal_multi(&[], b"a").is_err());
+            assert!(seal_multi(&[SecretKey::default()], b"").is_err());
+            assert!(seal_multi(
+                &[SecretKey::default(), SecretKey::generate(31).unwrap()],
+                b"a"
+            )
+            .is_err());
+
+            let sealed = seal_multi(&[key], b"a").unwrap();
+            let key = SecretKey::from_slice(&[0u8; 32]).unwrap();
+            assert!(open_multi(&key, &sealed).is_err());
+            let sealed = seal_multi(&[SecretKey::from_slice(&[0u8; 32]).unwrap()], b"a").unwrap();
+            assert!(open_multi(&key, &sealed).is_ok());
+            assert!(open_multi(&SecretKey::generate(31).unwrap(), &sealed).is_err());
+            assert!(open_multi(&key, &sealed[..1]).is_err());
+            assert!(open_multi(&key, &sealed[..sealed.len() - 1]).is_err());
+
+            let mut zero_recipients = sealed.clone();
+            zero_recipients[..2].copy_from_slice(&[0u8; 2]);
+            assert!(open_multi(&key, &zero_recipients).is_err());
+        }
+
+        #[test]
+        fn test_modified_err() {
+            let keys = [SecretKey::default(), SecretKey::default()];
+            let sealed = seal_multi(&keys, b"Secret message").unwrap();
+
+            for idx in 0..sealed.len() {
+                let mut modified = sealed.clone();
+                modified[idx] ^= 1;
+                assert!(open_multi(&keys[0], &modified).is_err());
+            }
+        }
+
+        #[test]
+        fn test_removed_recipient_err() {
+            let keys = [SecretKey::default(), SecretKey::default()];
+            let sealed = seal_multi(&keys, b"Secret message").unwrap();
+
+            // Dropping the second recipient is detected by the first one.
+            let mut modified = 1u16.to_le_bytes().to_vec();
+            modified.extend_from_slice(&sealed[2..2 + WRAPPED_KEY_SIZE]);
+            modified.extend_from_slice(&sealed[2 + 2 * WRAPPED_KEY_SIZE..]);
+            assert!(open_multi(&keys[0], &modified).is_err());
         }
     }
 
@@ -398,8 +1268,8 @@ mod public {
         #[test]
         fn test_auth_enc_encryption_decryption() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let plaintext = "Secret message".as_bytes();
 
             let dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
@@ -409,6 +1279,75 @@ mod public {
             assert_eq!(tag, StreamTag::Message);
         }
 
+        #[test]
+        fn test_open_chunk_secret() {
+            let key = SecretKey::default();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
+            let plaintext = "Secret message".as_bytes();
+
+            let dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Finish).unwrap();
+            let (dst_plaintext, tag) = opener.open_chunk_secret(&dst_ciphertext).unwrap();
+            assert!(dst_plaintext == plaintext);
+            assert_eq!(tag, StreamTag::Finish);
+            assert!(opener.open_chunk_secret(&dst_ciphertext).is_err());
+        }
+
+        #[test]
+        fn test_chunk_with_ad() {
+            let key = SecretKey::default();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
+
+            let ct0 = sealer
+                .seal_chunk_with_ad(b"Chunk 0", b"file 1", StreamTag::Message)
+                .unwrap();
+            let ct1 = sealer
+                .seal_chunk_with_ad(b"Chunk 1", b"file 1", StreamTag::Finish)
+                .unwrap();
+
+            // Wrong AD fails and leaves the state untouched.
+            assert!(opener.open_chunk_with_ad(&ct0, b"file 2").is_err());
+            assert!(opener.open_chunk(&ct0).is_err());
+
+            let (pt0, tag0) = opener.open_chunk_with_ad(&ct0, b"file 1").unwrap();
+            assert_eq!(&pt0[..], b"Chunk 0");
+            assert_eq!(tag0, StreamTag::Message);
+            let (pt1, tag1) = opener.open_chunk_with_ad(&ct1, b"file 1").unwrap();
+            assert_eq!(&pt1[..], b"Chunk 1");
+            assert_eq!(tag1, StreamTag::Finish);
+        }
+
+        #[test]
+        fn test_empty_ad_same_as_no_ad() {
+            let key = SecretKey::default();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
+
+            let ct = sealer
+                .seal_chunk_with_ad(b"Chunk", b"", StreamTag::Message)
+                .unwrap();
+            assert!(opener.open_chunk(&ct).is_ok());
+        }
+
+        #[test]
+        fn test_rekey_policy() {
+            let key = SecretKey::default();
+            let policy = RekeyPolicy::never().after_messages(2).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
+            let mut opener_no_policy = StreamOpener::new(&key, &header).unwrap();
+            sealer.set_rekey_policy(policy);
+            opener.set_rekey_policy(policy);
+
+            for idx in 0..5 {
+                let ct = sealer.seal_chunk(b"Chunk", StreamTag::Message).unwrap();
+                let (pt, _) = opener.open_chunk(&ct).unwrap();
+                assert_eq!(&pt, b"Chunk");
+                assert_eq!(opener_no_policy.open_chunk(&ct).is_ok(), idx < 2);
+            }
+        }
+
         #[test]
         fn test_seal_chunk_plaintext_empty_ok() {
             let key = SecretKey::default();
@@ -422,8 +1361,8 @@ mod public {
         fn test_open_chunk_less_than_abytes_err() {
             let key = SecretKey::default();
             let ciphertext = [0u8; aead::streaming::ABYTES - 1];
-            let (_, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (_, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
 
             assert!(opener.open_chunk(&ciphertext).is_err());
         }
@@ -431,8 +1370,8 @@ mod public {
         #[test]
         fn test_open_chunk_abytes_exact_ok() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let ciphertext = sealer
                 .seal_chunk("".as_bytes(), StreamTag::Message)
                 .unwrap();
@@ -445,8 +1384,8 @@ mod public {
         #[test]
         fn test_modified_tag_err() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let plaintext = "Secret message".as_bytes();
 
             let mut dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
@@ -458,8 +1397,8 @@ mod public {
         #[test]
         fn test_modified_ciphertext_err() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let plaintext = "Secret message".as_bytes();
 
             let mut dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
@@ -471,8 +1410,8 @@ mod public {
         #[test]
         fn test_modified_mac_err() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let plaintext = "Secret message".as_bytes();
 
             let mut dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
@@ -486,9 +1425,9 @@ mod public {
         fn test_diff_secret_key_err() {
             let key = SecretKey::default();
             let plaintext = "Secret message".as_bytes();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
             let bad_key = SecretKey::default();
-            let mut opener = StreamOpener::new(&bad_key, &nonce).unwrap();
+            let mut opener = StreamOpener::new(&bad_key, &header).unwrap();
 
             let dst_ciphertext = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
 
@@ -499,19 +1438,19 @@ mod public {
         fn test_secret_length_err() {
             let key = SecretKey::generate(31).unwrap();
             assert!(StreamSealer::new(&key).is_err());
-            assert!(StreamOpener::new(&key, &Nonce::generate()).is_err());
+            assert!(StreamOpener::new(&key, &StreamHeader::generate()).is_err());
         }
 
         #[test]
         fn same_input_generates_different_ciphertext() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
             let plaintext = "Secret message 1".as_bytes();
             let cipher1 = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
             let cipher2 = sealer.seal_chunk(plaintext, StreamTag::Message).unwrap();
             assert!(cipher1 != cipher2);
 
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let (dec1, tag1) = opener.open_chunk(&cipher1).unwrap();
             let (dec2, tag2) = opener.open_chunk(&cipher2).unwrap();
             assert_eq!(plaintext, &dec1[..]);
@@ -523,7 +1462,7 @@ mod public {
         #[test]
         fn same_input_on_same_init_different_ct() {
             // Two sealers initialized that encrypt the same plaintext
-            // should produce different ciphertexts because the nonce
+            // should produce different ciphertexts because the header
             // is randomly generated.
             let key = SecretKey::default();
             let (mut sealer_first, _) = StreamSealer::new(&key).unwrap();
@@ -542,7 +1481,7 @@ mod public {
         #[test]
         fn test_stream_seal_and_open() {
             let key = SecretKey::default();
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
             let plaintext1 = "Secret message 1".as_bytes();
             let plaintext2 = "Secret message 2".as_bytes();
             let plaintext3 = "Secret message 3".as_bytes();
@@ -550,7 +1489,7 @@ mod public {
             let cipher2 = sealer.seal_chunk(plaintext2, StreamTag::Finish).unwrap();
             let cipher3 = sealer.seal_chunk(plaintext3, StreamTag::Message).unwrap();
 
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let (dec1, tag1) = opener.open_chunk(&cipher1).unwrap();
             let (dec2, tag2) = opener.open_chunk(&cipher2).unwrap();
             let (dec3, tag3) = opener.open_chunk(&cipher3).unwrap();
@@ -567,10 +1506,10 @@ mod public {
         fn prop_stream_seal_open_same_input(input: Vec<u8>) -> bool {
             let key = SecretKey::default();
 
-            let (mut sealer, nonce) = StreamSealer::new(&key).unwrap();
+            let (mut sealer, header) = StreamSealer::new(&key).unwrap();
             let ct = sealer.seal_chunk(&input[..], StreamTag::Message).unwrap();
 
-            let mut opener = StreamOpener::new(&key, &nonce).unwrap();
+            let mut opener = StreamOpener::new(&key, &header).unwrap();
             let (pt_decrypted, tag) = opener.open_chunk(&ct).unwrap();
 
             input == pt_decrypted && tag == StreamTag::Message
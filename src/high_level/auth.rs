@@ -31,17 +31,26 @@
 //! requests.
 //!
 //! # About:
-//! - Uses BLAKE2b-256 in keyed mode.
+//! - Uses BLAKE2b-256 in keyed mode. Keyed BLAKE2b is a MAC by itself and does not need the
+//!   HMAC construction, so it is faster than HMAC-SHA512, especially for short messages.
+//! - [`authenticate_reader()`] and [`authenticate_verify_reader()`] process data from a reader,
+//!   without loading it all into memory.
+//! - [`Tag`] implements `Display` and `FromStr` using hexadecimal, such as for storing
+//!   tags in configuration files.
 //!
 //! # Parameters:
 //! - `secret_key`: Secret key used to authenticate `data`.
 //! - `data`: Data to be authenticated.
 //! - `expected`: The expected authentication [`Tag`].
+//! - `reader`: A reader whose contents, until EOF, should be authenticated.
+//! - `length`: The length of the [`SecretKey`] to generate with [`generate_key()`].
 //!
 //! # Errors:
 //! An error will be returned if:
 //! - The calculated [`Tag`] does not match the expected.
 //! - The [`SecretKey`] supplied is less than 32 bytes or greater than 64 bytes.
+//! - `length` is less than 32 or greater than 64.
+//! - Reading from `reader` fails.
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -51,6 +60,8 @@
 //! - The secret key should always be generated using a CSPRNG.
 //!   [`SecretKey::default()`] can be used for
 //! this; it will generate a [`SecretKey`] of 32 bytes.
+//! - [`generate_key()`] generates a key of any length that is accepted here, such as
+//!   64 bytes for interoperability.
 //! - The required minimum length for a [`SecretKey`] is 32 bytes.
 //!
 //! # Example:
@@ -66,25 +77,41 @@
 //!
 //! // API server verifies the authenticity of the message with the tag
 //! assert!(auth::authenticate_verify(&expected_tag, &key, &msg).is_ok());
+//!
+//! // Large data, such as files, can be authenticated from a reader.
+//! let reader: &[u8] = msg;
+//! assert!(auth::authenticate_verify_reader(&expected_tag, &key, reader).is_ok());
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
 
 pub use super::hltypes::{SecretKey, Tag};
 use crate::{
-    errors::UnknownCryptoError,
-    hazardous::hash::blake2b::{self, Blake2b, Digest},
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::hash::blake2b::{self, Blake2b, Digest, BLAKE2B_KEYSIZE},
 };
 
 /// The Tag size (bytes) to be output by BLAKE2b in keyed mode.
 const BLAKE2B_TAG_SIZE: usize = 32;
 /// The minimum `SecretKey` size (bytes) to be used by BLAKE2b in keyed mode.
 const BLAKE2B_MIN_KEY_SIZE: usize = 32;
+/// The size of the buffer used when authenticating from a reader.
+const READER_BUFSIZE: usize = 8192;
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Randomly generate a [`SecretKey`] of `length` bytes, where `length` is between 32 and 64.
+pub fn generate_key(length: usize) -> Result<SecretKey, UnknownCryptoError> {
+    if !(BLAKE2B_MIN_KEY_SIZE..=BLAKE2B_KEYSIZE).contains(&length) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    SecretKey::generate(length)
+}
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Authenticate a message using BLAKE2b-256 in keyed mode.
 pub fn authenticate(secret_key: &SecretKey, data: &[u8]) -> Result<Tag, UnknownCryptoError> {
     if secret_key.len() < BLAKE2B_MIN_KEY_SIZE {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
     let blake2b_secret_key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
     let mut state = Blake2b::new(Some(&blake2b_secret_key), BLAKE2B_TAG_SIZE)?;
@@ -101,18 +128,73 @@ pub fn authenticate_verify(
     data: &[u8],
 ) -> Result<(), UnknownCryptoError> {
     if secret_key.len() < BLAKE2B_MIN_KEY_SIZE {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
     }
     let key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
     let expected_digest = Digest::from_slice(expected.unprotected_as_bytes())?;
     Blake2b::verify(&expected_digest, &key, BLAKE2B_TAG_SIZE, data)
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate everything read from `reader` until EOF, using BLAKE2b-256 in keyed mode.
+pub fn authenticate_reader(
+    secret_key: &SecretKey,
+    mut reader: impl std::io::Read,
+) -> Result<Tag, UnknownCryptoError> {
+    if secret_key.len() < BLAKE2B_MIN_KEY_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let blake2b_secret_key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
+    let mut state = Blake2b::new(Some(&blake2b_secret_key), BLAKE2B_TAG_SIZE)?;
+    let mut buf = [0u8; READER_BUFSIZE];
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => state.update(&buf[..n])?,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let blake2b_digest = state.finalize()?;
+    Tag::from_slice(blake2b_digest.as_ref())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate and verify everything read from `reader` until EOF, using BLAKE2b-256 in
+/// keyed mode.
+pub fn authenticate_verify_reader(
+    expected: &Tag,
+    secret_key: &SecretKey,
+    reader: impl std::io::Read,
+) -> Result<(), UnknownCryptoError> {
+    if &authenticate_reader(secret_key, reader)? == expected {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 mod public {
     use super::*;
 
+    #[test]
+    fn test_generate_key() {
+        assert!(generate_key(31).is_err());
+        assert!(generate_key(65).is_err());
+        assert!(generate_key(0).is_err());
+
+        for length in [32, 48, 64].iter() {
+            let key = generate_key(*length).unwrap();
+            assert_eq!(key.len(), *length);
+            let tag = authenticate(&key, b"msg").unwrap();
+            assert!(authenticate_verify(&tag, &key, b"msg").is_ok());
+        }
+    }
+
     mod test_auth_and_verify {
         use super::*;
         #[test]
@@ -154,6 +236,105 @@ mod public {
         }
     }
 
+    mod test_tag_display_fromstr {
+        use super::*;
+        use core::str::FromStr;
+
+        #[test]
+        fn test_roundtrip() {
+            let tag = authenticate(&SecretKey::default(), b"msg").unwrap();
+            let encoded = tag.to_string();
+            assert_eq!(encoded, tag.to_hex());
+            assert_eq!(Tag::from_str(&encoded).unwrap(), tag);
+        }
+
+        #[test]
+        fn test_fromstr_err() {
+            assert!(Tag::from_str(&"00".repeat(31)).is_err());
+            assert!(Tag::from_str(&"00".repeat(33)).is_err());
+            assert!(Tag::from_str(&"zz".repeat(32)).is_err());
+            assert!(Tag::from_str(&"00".repeat(32)).is_ok());
+        }
+    }
+
+    mod test_reader {
+        use super::*;
+        use std::io;
+
+        /// A reader that returns `Interrupted` and short reads.
+        struct FlakyReader<'a> {
+            data: &'a [u8],
+            interrupt: bool,
+        }
+
+        impl io::Read for FlakyReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.interrupt = !self.interrupt;
+                if self.interrupt {
+                    return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+                }
+                let n = core::cmp::min(core::cmp::min(buf.len(), 7), self.data.len());
+                buf[..n].copy_from_slice(&self.data[..n]);
+                self.data = &self.data[n..];
+                Ok(n)
+            }
+        }
+
+        struct FailingReader;
+
+        impl io::Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "failed"))
+            }
+        }
+
+        #[test]
+        fn test_same_as_authenticate() {
+            let sk = SecretKey::default();
+            for len in [
+                0,
+                1,
+                64,
+                READER_BUFSIZE,
+                READER_BUFSIZE + 1,
+                3 * READER_BUFSIZE,
+            ]
+            .iter()
+            {
+                let data = vec![*len as u8; *len];
+                let tag = authenticate(&sk, &data).unwrap();
+                assert_eq!(authenticate_reader(&sk, &data[..]).unwrap(), tag);
+
+                let flaky = FlakyReader {
+                    data: &data,
+                    interrupt: false,
+                };
+                assert_eq!(authenticate_reader(&sk, flaky).unwrap(), tag);
+                assert!(authenticate_verify_reader(&tag, &sk, &data[..]).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_verify_reader_err() {
+            let sk = SecretKey::default();
+            let tag = authenticate(&sk, b"data").unwrap();
+
+            assert!(authenticate_verify_reader(&tag, &sk, &b"bad data"[..]).is_err());
+            assert!(authenticate_verify_reader(&tag, &SecretKey::default(), &b"data"[..]).is_err());
+            assert!(authenticate_verify_reader(&tag, &sk, FailingReader).is_err());
+        }
+
+        #[test]
+        fn test_reader_errors() {
+            let sk = SecretKey::default();
+            assert_eq!(
+                authenticate_reader(&sk, FailingReader).unwrap_err(),
+                UnknownCryptoError::new(ErrorKind::Io)
+            );
+            assert!(authenticate_reader(&SecretKey::generate(31).unwrap(), &b""[..]).is_err());
+        }
+    }
+
     #[quickcheck]
     #[cfg(feature = "safe_api")]
     /// Authentication and verifying that tag with the same parameters
@@ -199,7 +380,7 @@ mod public {
         let sec_key = sec_key_res.unwrap();
         let msg = "what do ya want for nothing?".as_bytes().to_vec();
         let auth_res = authenticate(&sec_key, &msg);
-        if input.len() >= BLAKE2B_MIN_KEY_SIZE && input.len() <= blake2b::BLAKE2B_KEYSIZE {
+        if input.len() >= BLAKE2B_MIN_KEY_SIZE && input.len() <= BLAKE2B_KEYSIZE {
             auth_res.is_ok()
         } else {
             auth_res.is_err()
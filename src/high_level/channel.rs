@@ -0,0 +1,514 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Encrypted channels over a transport.
+//!
+//! # Use case:
+//! `orion::channel` can be used by a client and a server to exchange encrypted messages
+//! over a transport such as a `TcpStream`, after they have derived [`SessionKeys`] with
+//! [`orion::kex`](crate::kex).
+//!
+//! # About:
+//! - Each direction of a [`Channel`] is a stream of the streaming AEAD of
+//!   [`hazardous::aead::streaming`](crate::hazardous::aead::streaming)
+//!   (XChaCha20Poly1305), which starts with a random [`StreamHeader`]. The header is sent
+//!   before the first frame.
+//! - Every message is sent as a frame: its length as a big-endian `u32`, followed by the
+//!   encrypted message. A frame also authenticates all the frames sent before it, so
+//!   frames that are replayed, reordered or dropped are detected.
+//! - [`Channel::close()`] sends a final frame, after which [`Channel::recv()`] of the other
+//!   side returns `None`. A transport that ends without this frame, such as because the
+//!   connection was cut by an attacker, is an error.
+//! - Messages longer than the maximum frame length, which is [`DEFAULT_MAX_FRAME_LEN`] unless
+//!   set with [`Channel::with_max_frame_len()`], are rejected when sending and receiving,
+//!   before any memory is allocated for them.
+//! - Only blocking transports that implement [`Read`] and [`Write`] are supported.
+//!
+//! # Parameters:
+//! - `transport`: The transport to send and receive frames over.
+//! - `session_keys`: The session keys derived with [`orion::kex`](crate::kex).
+//! - `max_frame_len`: The maximum length of a message.
+//! - `message`: The message to send.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `max_frame_len` is greater than [`MAX_FRAME_LEN`].
+//! - `message` or a received frame is longer than the maximum frame length.
+//! - A received frame does not authenticate, or has been replayed, reordered or dropped.
+//! - The transport ends before the other side has closed the channel.
+//! - Reading from or writing to the transport fails.
+//! - [`Channel::send()`] is called after [`Channel::close()`].
+//! - A method is called after [`Channel::send()`] or [`Channel::recv()`] has returned an
+//!   error, since the channel can not recover from a partially sent or received frame.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely.
+//!
+//! # Security:
+//! - The channel is only as authenticated as the key exchange. The public key of the other
+//!   party must be obtained from a trusted source.
+//! - The lengths of the messages are not hidden, only their contents.
+//! - A session that is cut short by an error must be started again with new [`SessionKeys`],
+//!   or at least on a new [`Channel`], which sends new [`StreamHeader`]s.
+//!
+//! # Example:
+//! ```rust
+//! use orion::channel::Channel;
+//! use orion::kex::{KeyPair, SessionKeys};
+//!
+//! let client = KeyPair::generate();
+//! let server = KeyPair::generate();
+//! let client_keys = SessionKeys::derive(&client, server.public())?;
+//! let server_keys = SessionKeys::derive_server(&server, client.public())?;
+//!
+//! // The client writes to a buffer, which would usually be a `TcpStream`.
+//! let mut client_channel = Channel::new(std::io::Cursor::new(Vec::new()), &client_keys)?;
+//! client_channel.send(b"Hello server")?;
+//! client_channel.close()?;
+//! let sent = client_channel.into_inner().into_inner();
+//!
+//! let mut server_channel = Channel::new(std::io::Cursor::new(sent), &server_keys)?;
+//! assert_eq!(server_channel.recv()?, Some(b"Hello server".to_vec()));
+//! assert_eq!(server_channel.recv()?, None);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`SessionKeys`]: crate::kex::SessionKeys
+//! [`StreamHeader`]: crate::hazardous::aead::streaming::StreamHeader
+
+use super::kex::SessionKeys;
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::{
+        aead::streaming::{StreamHeader, StreamTag, StreamXChaCha20Poly1305, ABYTES, HEADERBYTES},
+        stream::chacha20,
+    },
+};
+use std::io::{Read, Write};
+
+/// The maximum message length used by [`Channel::new()`].
+pub const DEFAULT_MAX_FRAME_LEN: usize = 1_048_576;
+/// The largest maximum message length that can be set with [`Channel::with_max_frame_len()`].
+pub const MAX_FRAME_LEN: usize = 16_777_216;
+/// The size of the length that starts every frame.
+const LENGTH_SIZE: usize = 4;
+
+/// A channel that sends and receives encrypted messages over `transport`.
+pub struct Channel<T: Read + Write> {
+    transport: T,
+    sender: StreamXChaCha20Poly1305,
+    /// The header of the sending stream, until it has been sent.
+    sender_header: Option<StreamHeader>,
+    /// The key of the receiving stream, until its header has been received.
+    receiving_key: Option<chacha20::SecretKey>,
+    receiver: Option<StreamXChaCha20Poly1305>,
+    max_frame_len: usize,
+    closed: bool,
+    peer_closed: bool,
+    failed: bool,
+}
+
+impl<T: Read + Write> core::fmt::Debug for Channel<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Channel {{ max_frame_len: {:?}, closed: {:?}, peer_closed: {:?}, failed: {:?} }}",
+            self.max_frame_len, self.closed, self.peer_closed, self.failed
+        )
+    }
+}
+
+impl<T: Read + Write> Channel<T> {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a channel over `transport`, using [`DEFAULT_MAX_FRAME_LEN`].
+    pub fn new(transport: T, session_keys: &SessionKeys) -> Result<Self, UnknownCryptoError> {
+        Self::with_max_frame_len(transport, session_keys, DEFAULT_MAX_FRAME_LEN)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a channel over `transport`, which accepts messages of up to `max_frame_len` bytes.
+    /// Both sides should use the same `max_frame_len`.
+    pub fn with_max_frame_len(
+        transport: T,
+        session_keys: &SessionKeys,
+        max_frame_len: usize,
+    ) -> Result<Self, UnknownCryptoError> {
+        if max_frame_len > MAX_FRAME_LEN {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+        }
+
+        let sender_header = StreamHeader::generate();
+        let sender = StreamXChaCha20Poly1305::from_header(
+            &chacha20::SecretKey::from_slice(session_keys.transmitting().unprotected_as_bytes())?,
+            &sender_header,
+        );
+
+        Ok(Self {
+            transport,
+            sender,
+            sender_header: Some(sender_header),
+            receiving_key: Some(chacha20::SecretKey::from_slice(
+                session_keys.receiving().unprotected_as_bytes(),
+            )?),
+            receiver: None,
+            max_frame_len,
+            closed: false,
+            peer_closed: false,
+            failed: false,
+        })
+    }
+
+    /// Return a reference to the transport.
+    pub fn get_ref(&self) -> &T {
+        &self.transport
+    }
+
+    /// Return the transport, consuming the channel.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt `message` and send it as one frame.
+    pub fn send(&mut self, message: &[u8]) -> Result<(), UnknownCryptoError> {
+        if self.closed {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+        if message.len() > self.max_frame_len {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        self.send_frame(message, StreamTag::Message)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Send the final frame, after which no more messages can be sent. Messages can still
+    /// be received until the other side closes the channel as well.
+    pub fn close(&mut self) -> Result<(), UnknownCryptoError> {
+        if self.closed {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        self.send_frame(&[], StreamTag::Finish)?;
+        self.closed = true;
+
+        Ok(())
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Receive and decrypt the next frame. Returns `None` once the other side has closed the
+    /// channel.
+    pub fn recv(&mut self) -> Result<Option<Vec<u8>>, UnknownCryptoError> {
+        if self.failed {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+        if self.peer_closed {
+            return Ok(None);
+        }
+
+        let received = self.recv_frame();
+        if received.is_err() {
+            self.failed = true;
+        }
+
+        received
+    }
+
+    fn send_frame(&mut self, message: &[u8], tag: StreamTag) -> Result<(), UnknownCryptoError> {
+        if self.failed {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidState));
+        }
+
+        let header_len = if self.sender_header.is_some() {
+            HEADERBYTES
+        } else {
+            0
+        };
+        let frame_len = message.len() + ABYTES;
+        let mut frame = vec![0u8; header_len + LENGTH_SIZE + frame_len];
+        if let Some(header) = self.sender_header.as_ref() {
+            frame[..HEADERBYTES].copy_from_slice(header.as_ref());
+        }
+        // `frame_len` is at most MAX_FRAME_LEN + ABYTES, so it always fits a `u32`.
+        frame[header_len..header_len + LENGTH_SIZE]
+            .copy_from_slice(&(frame_len as u32).to_be_bytes());
+
+        let sent = self
+            .sender
+            .seal_chunk(message, None, &mut frame[header_len + LENGTH_SIZE..], tag)
+            .and_then(|_| {
+                self.transport.write_all(&frame)?;
+                self.transport.flush()?;
+                Ok(())
+            });
+        match sent {
+            Ok(()) => self.sender_header = None,
+            Err(_) => self.failed = true,
+        }
+
+        sent
+    }
+
+    fn recv_frame(&mut self) -> Result<Option<Vec<u8>>, UnknownCryptoError> {
+        if let Some(key) = self.receiving_key.take() {
+            let mut header = [0u8; HEADERBYTES];
+            self.transport.read_exact(&mut header)?;
+            self.receiver = Some(StreamXChaCha20Poly1305::from_header(
+                &key,
+                &StreamHeader::from(header),
+            ));
+        }
+
+        let mut length = [0u8; LENGTH_SIZE];
+        self.transport.read_exact(&mut length)?;
+        let frame_len = u32::from_be_bytes(length) as usize;
+        if frame_len < ABYTES || frame_len - ABYTES > self.max_frame_len {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let mut frame = vec![0u8; frame_len];
+        self.transport.read_exact(&mut frame)?;
+        let mut message = vec![0u8; frame_len - ABYTES];
+        let receiver = self
+            .receiver
+            .as_mut()
+            .ok_or_else(|| UnknownCryptoError::new(ErrorKind::InvalidState))?;
+
+        match receiver.open_chunk(&frame, None, &mut message)? {
+            StreamTag::Message => Ok(Some(message)),
+            StreamTag::Finish if message.is_empty() => {
+                self.peer_closed = true;
+                Ok(None)
+            }
+            _ => Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+        }
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::kex::KeyPair;
+    use std::io::Cursor;
+
+    /// A transport that reads from a fixed buffer and records what is written to it.
+    struct Transport {
+        incoming: Cursor<Vec<u8>>,
+        outgoing: Vec<u8>,
+    }
+
+    impl Transport {
+        fn new(incoming: Vec<u8>) -> Self {
+            Self {
+                incoming: Cursor::new(incoming),
+                outgoing: Vec::new(),
+            }
+        }
+    }
+
+    impl Read for Transport {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.incoming.read(buf)
+        }
+    }
+
+    impl Write for Transport {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.outgoing.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn session_keys() -> (SessionKeys, SessionKeys) {
+        let client = KeyPair::generate();
+        let server = KeyPair::generate();
+
+        (
+            SessionKeys::derive(&client, server.public()).unwrap(),
+            SessionKeys::derive_server(&server, client.public()).unwrap(),
+        )
+    }
+
+    /// Send `messages` from the client, and return what was written to the transport.
+    fn client_sends(client_keys: &SessionKeys, messages: &[&[u8]], close: bool) -> Vec<u8> {
+        let mut channel = Channel::new(Transport::new(vec![]), client_keys).unwrap();
+        for message in messages {
+            channel.send(message).unwrap();
+        }
+        if close {
+            channel.close().unwrap();
+        }
+
+        channel.into_inner().outgoing
+    }
+
+    /// The frames that follow the stream header, as (start, end) offsets.
+    fn frames(sent: &[u8]) -> Vec<(usize, usize)> {
+        let mut ret = vec![];
+        let mut pos = HEADERBYTES;
+        while pos < sent.len() {
+            let mut length = [0u8; LENGTH_SIZE];
+            length.copy_from_slice(&sent[pos..pos + LENGTH_SIZE]);
+            let end = pos + LENGTH_SIZE + u32::from_be_bytes(length) as usize;
+            ret.push((pos, end));
+            pos = end;
+        }
+
+        ret
+    }
+
+    #[test]
+    fn test_send_recv_both_directions() {
+        let (client_keys, server_keys) = session_keys();
+        let sent = client_sends(&client_keys, &[b"first", b"", b"third"], true);
+
+        let mut server = Channel::new(Transport::new(sent), &server_keys).unwrap();
+        assert_eq!(server.recv().unwrap(), Some(b"first".to_vec()));
+        assert_eq!(server.recv().unwrap(), Some(vec![]));
+        assert_eq!(server.recv().unwrap(), Some(b"third".to_vec()));
+        assert_eq!(server.recv().unwrap(), None);
+        assert_eq!(server.recv().unwrap(), None);
+
+        server.send(b"reply").unwrap();
+        let reply = server.into_inner().outgoing;
+        assert_eq!(reply.len(), HEADERBYTES + LENGTH_SIZE + 5 + ABYTES);
+
+        let mut client = Channel::new(Transport::new(reply), &client_keys).unwrap();
+        assert_eq!(client.recv().unwrap(), Some(b"reply".to_vec()));
+    }
+
+    #[test]
+    fn test_wrong_direction_fails() {
+        let (client_keys, _) = session_keys();
+        let sent = client_sends(&client_keys, &[b"message"], true);
+
+        // The client can not receive with the key it transmits with.
+        let mut client = Channel::new(Transport::new(sent), &client_keys).unwrap();
+        assert_eq!(client.recv().unwrap_err().kind(), ErrorKind::MacMismatch);
+    }
+
+    #[test]
+    fn test_replay_reorder_drop() {
+        let (client_keys, server_keys) = session_keys();
+        let sent = client_sends(&client_keys, &[b"first", b"second", b"third"], true);
+        let f = frames(&sent);
+        let header = &sent[..HEADERBYTES];
+        let frame = |i: usize| &sent[f[i].0..f[i].1];
+
+        for order in [[0, 0, 1], [1, 0, 2], [0, 2, 3]].iter() {
+            let mut modified = header.to_vec();
+            for i in order.iter() {
+                modified.extend_from_slice(frame(*i));
+            }
+            let mut server = Channel::new(Transport::new(modified), &server_keys).unwrap();
+            let received: Vec<_> = (0..3).map(|_| server.recv()).collect();
+            assert!(received.iter().any(|r| r.is_err()));
+        }
+    }
+
+    #[test]
+    fn test_truncated_transport() {
+        let (client_keys, server_keys) = session_keys();
+        let sent = client_sends(&client_keys, &[b"message"], false);
+
+        let mut server = Channel::new(Transport::new(sent.clone()), &server_keys).unwrap();
+        assert!(server.recv().unwrap().is_some());
+        assert_eq!(server.recv().unwrap_err().kind(), ErrorKind::Io);
+        // The channel can not be used after an error.
+        assert_eq!(server.recv().unwrap_err().kind(), ErrorKind::InvalidState);
+        assert_eq!(
+            server.send(b"x").unwrap_err().kind(),
+            ErrorKind::InvalidState
+        );
+
+        let mut server = Channel::new(
+            Transport::new(sent[..sent.len() - 1].to_vec()),
+            &server_keys,
+        )
+        .unwrap();
+        assert!(server.recv().is_err());
+        let mut server = Channel::new(Transport::new(vec![]), &server_keys).unwrap();
+        assert!(server.recv().is_err());
+    }
+
+    #[test]
+    fn test_tampered_frame() {
+        let (client_keys, server_keys) = session_keys();
+        let sent = client_sends(&client_keys, &[b"message"], true);
+
+        for i in 0..sent.len() {
+            let mut modified = sent.clone();
+            modified[i] ^= 1;
+            let mut server = Channel::new(Transport::new(modified), &server_keys).unwrap();
+            assert!(server.recv().is_err() || server.recv().is_err());
+        }
+    }
+
+    #[test]
+    fn test_max_frame_len() {
+        let (client_keys, server_keys) = session_keys();
+        assert!(
+            Channel::with_max_frame_len(Transport::new(vec![]), &client_keys, MAX_FRAME_LEN)
+                .is_ok()
+        );
+        assert!(Channel::with_max_frame_len(
+            Transport::new(vec![]),
+            &client_keys,
+            MAX_FRAME_LEN + 1
+        )
+        .is_err());
+
+        let mut client =
+            Channel::with_max_frame_len(Transport::new(vec![]), &client_keys, 16).unwrap();
+        client.send(&[0u8; 16]).unwrap();
+        assert_eq!(
+            client.send(&[0u8; 17]).unwrap_err().kind(),
+            ErrorKind::InvalidLength
+        );
+        // A message that is too long is rejected before anything is sent.
+        client.send(b"still usable").unwrap();
+        client.close().unwrap();
+        assert_eq!(client.close().unwrap_err().kind(), ErrorKind::InvalidState);
+        assert_eq!(
+            client.send(b"x").unwrap_err().kind(),
+            ErrorKind::InvalidState
+        );
+        let sent = client.into_inner().outgoing;
+
+        let mut server =
+            Channel::with_max_frame_len(Transport::new(sent.clone()), &server_keys, 15).unwrap();
+        assert_eq!(server.recv().unwrap_err().kind(), ErrorKind::InvalidLength);
+        let mut server =
+            Channel::with_max_frame_len(Transport::new(sent), &server_keys, 16).unwrap();
+        assert_eq!(server.recv().unwrap(), Some(vec![0u8; 16]));
+
+        // A huge length prefix is rejected without reading further.
+        let mut huge = vec![0u8; HEADERBYTES];
+        huge.extend_from_slice(&u32::MAX.to_be_bytes());
+        let mut server = Channel::new(Transport::new(huge), &server_keys).unwrap();
+        assert_eq!(server.recv().unwrap_err().kind(), ErrorKind::InvalidLength);
+    }
+}
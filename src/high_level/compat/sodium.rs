@@ -0,0 +1,267 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! The `crypto_auth` and `crypto_shorthash` functions of libsodium.
+//!
+//! # Use case:
+//! `orion::compat::sodium` can be used to verify and create MACs and short hashes that
+//! are byte-compatible with those of libsodium, such as when migrating a system whose
+//! stored MACs were produced by libsodium.
+//!
+//! # About:
+//! - [`auth()`] and [`auth_verify()`] are `crypto_auth` and `crypto_auth_verify`, which use
+//!   HMAC-SHA512-256: HMAC-SHA512 with the output truncated to 32 bytes.
+//! - [`shorthash()`] is `crypto_shorthash`, which uses SipHash-2-4 and returns its output as
+//!   8 little-endian bytes.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `data`: The data to authenticate or hash.
+//! - `expected`: The expected authentication [`AuthTag`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The calculated [`AuthTag`] does not match the expected.
+//!
+//! # Security:
+//! - The secret keys should always be generated using a CSPRNG. [`AuthKey::generate()`]
+//!   and [`ShortHashKey::generate()`] can be used for this.
+//! - [`shorthash()`] is only meant for short-lived values, such as hash table keys that
+//!   must not be predictable by an attacker. Its 64-bit output is too short for a MAC.
+//! - For new designs, use [`orion::auth`](crate::auth) instead of [`auth()`].
+//!
+//! # Example:
+//! ```rust
+//! use orion::compat::sodium;
+//!
+//! let key = sodium::AuthKey::generate();
+//! let tag = sodium::auth(&key, b"Some message.")?;
+//! assert!(sodium::auth_verify(&tag, &key, b"Some message.").is_ok());
+//!
+//! let key = sodium::ShortHashKey::generate();
+//! let bucket = u64::from_le_bytes(sodium::shorthash(&key, b"user-1"));
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+
+use crate::errors::UnknownCryptoError;
+use crate::hazardous::mac::hmac::sha512::{self, HmacSha512};
+
+/// The size of an [`AuthKey`], `crypto_auth_KEYBYTES`.
+pub const AUTH_KEYBYTES: usize = 32;
+
+/// The size of an [`AuthTag`], `crypto_auth_BYTES`.
+pub const AUTH_BYTES: usize = 32;
+
+/// The size of a [`ShortHashKey`], `crypto_shorthash_KEYBYTES`.
+pub const SHORTHASH_KEYBYTES: usize = 16;
+
+/// The size of the output of [`shorthash()`], `crypto_shorthash_BYTES`.
+pub const SHORTHASH_BYTES: usize = 8;
+
+construct_secret_key! {
+    /// A type to represent the secret key of [`auth()`].
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (AuthKey, test_auth_key, AUTH_KEYBYTES, AUTH_KEYBYTES, AUTH_KEYBYTES)
+}
+
+impl_from_trait!(AuthKey, AUTH_KEYBYTES);
+
+construct_tag! {
+    /// A type to represent the [`AuthTag`] that [`auth()`] returns.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (AuthTag, test_auth_tag, AUTH_BYTES, AUTH_BYTES)
+}
+
+impl_from_trait!(AuthTag, AUTH_BYTES);
+
+construct_secret_key! {
+    /// A type to represent the secret key of [`shorthash()`].
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 16 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (ShortHashKey, test_shorthash_key, SHORTHASH_KEYBYTES, SHORTHASH_KEYBYTES, SHORTHASH_KEYBYTES)
+}
+
+impl_from_trait!(ShortHashKey, SHORTHASH_KEYBYTES);
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate `data` using HMAC-SHA512-256, as `crypto_auth` does.
+pub fn auth(secret_key: &AuthKey, data: &[u8]) -> Result<AuthTag, UnknownCryptoError> {
+    let mut ctx = HmacSha512::new(&sha512::SecretKey::from_slice(
+        secret_key.unprotected_as_bytes(),
+    )?);
+    ctx.update(data)?;
+    let mut tag = [0u8; AUTH_BYTES];
+    ctx.finalize_truncated(&mut tag)?;
+
+    Ok(AuthTag::from(tag))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate and verify `data` in constant time, as `crypto_auth_verify` does.
+pub fn auth_verify(
+    expected: &AuthTag,
+    secret_key: &AuthKey,
+    data: &[u8],
+) -> Result<(), UnknownCryptoError> {
+    HmacSha512::verify_truncated(
+        expected.unprotected_as_bytes(),
+        &sha512::SecretKey::from_slice(secret_key.unprotected_as_bytes())?,
+        data,
+    )
+}
+
+/// The SipRound of SipHash.
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// Hash `data` using SipHash-2-4, as `crypto_shorthash` does.
+pub fn shorthash(secret_key: &ShortHashKey, data: &[u8]) -> [u8; SHORTHASH_BYTES] {
+    let mut k0 = [0u8; 8];
+    let mut k1 = [0u8; 8];
+    k0.copy_from_slice(&secret_key.unprotected_as_bytes()[..8]);
+    k1.copy_from_slice(&secret_key.unprotected_as_bytes()[8..]);
+    let (k0, k1) = (u64::from_le_bytes(k0), u64::from_le_bytes(k1));
+
+    let mut v = [
+        k0 ^ 0x736f6d6570736575,
+        k1 ^ 0x646f72616e646f6d,
+        k0 ^ 0x6c7967656e657261,
+        k1 ^ 0x7465646279746573,
+    ];
+
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let mut m = [0u8; 8];
+        m.copy_from_slice(chunk);
+        let m = u64::from_le_bytes(m);
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+
+    // The last block holds the remaining bytes and the length of `data`, modulo 256.
+    let mut last = [0u8; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    last[7] = data.len() as u8;
+    let m = u64::from_le_bytes(last);
+    v[3] ^= m;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= m;
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+
+    (v[0] ^ v[1] ^ v[2] ^ v[3]).to_le_bytes()
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    // Computed with libsodium's crypto_auth() and crypto_shorthash().
+    #[test]
+    fn test_auth_libsodium() {
+        let key = AuthKey::from_slice(&(0u8..32).collect::<Vec<u8>>()).unwrap();
+        let vectors: [(&[u8], &str); 3] = [
+            (
+                b"",
+                "b04a70f45e9529968060f0026344d5f4da59f1c3ce228245f6bb088d7b8aa9fc",
+            ),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                "0623d51f882717efa360aa2217d0b554b57ea018eb518178b23045941a6ae244",
+            ),
+            (
+                &(0u8..200).collect::<Vec<u8>>(),
+                "c0051d021d3d1b61041a6d01c75c6118ebb92bf6bfae9c2dc7026a51a16078b6",
+            ),
+        ];
+
+        for (data, expected) in vectors.iter() {
+            let tag = auth(&key, data).unwrap();
+            assert_eq!(hex::encode(tag.unprotected_as_bytes()), *expected);
+            assert!(auth_verify(&tag, &key, data).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_auth_verify_fails() {
+        let key = AuthKey::generate();
+        let tag = auth(&key, b"data").unwrap();
+        assert!(auth_verify(&tag, &key, b"Data").is_err());
+        assert!(auth_verify(&tag, &AuthKey::generate(), b"data").is_err());
+
+        let mut modified = [0u8; AUTH_BYTES];
+        modified.copy_from_slice(tag.unprotected_as_bytes());
+        modified[0] ^= 1;
+        assert!(auth_verify(&AuthTag::from(modified), &key, b"data").is_err());
+    }
+
+    #[test]
+    fn test_shorthash_libsodium() {
+        let key = ShortHashKey::from_slice(&(0u8..16).collect::<Vec<u8>>()).unwrap();
+        let vectors: [(Vec<u8>, &str); 4] = [
+            (vec![], "310e0edd47db6f72"),
+            ((0u8..15).collect(), "e545be4961ca29a1"),
+            ((0u8..63).collect(), "724506eb4c328a95"),
+            (
+                b"The quick brown fox jumps over the lazy dog".to_vec(),
+                "e46f1fdc05612752",
+            ),
+        ];
+
+        for (data, expected) in vectors.iter() {
+            assert_eq!(hex::encode(shorthash(&key, data)), *expected);
+        }
+    }
+}
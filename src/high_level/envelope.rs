@@ -0,0 +1,347 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Envelope encryption with data and key encryption keys.
+//!
+//! # Use case:
+//! `orion::envelope` can be used to encrypt data at rest under a data encryption key
+//! (DEK), which is itself encrypted under a long-lived key encryption key (KEK).
+//!
+//! An example of this could be a service that stores files in a database, with a new
+//! DEK for each file and the KEK kept in a key management service. When the KEK is
+//! rotated, only the small wrapped DEKs have to be encrypted again, not the files.
+//!
+//! # About:
+//! - Uses XChaCha20Poly1305, as in [`aead`](crate::aead), for both the DEK and the data.
+//!   The DEK and the data are encrypted with [`aead::seal_with_context()`], under the
+//!   contexts `"orion-envelope-dek"` and `"orion-envelope-data"` respectively, so that
+//!   one can never be opened as the other.
+//! - [`seal()`] generates a new DEK, encrypts the data with it, encrypts the DEK under the
+//!   KEK and returns both in a single blob. [`open()`] reverses this.
+//! - [`rewrap()`] encrypts the DEK of a blob under a new KEK, leaving the encrypted data
+//!   as it is.
+//! - [`generate_dek()`], [`seal_dek()`], [`open_dek()`], [`seal_data()`] and [`open_data()`]
+//!   give access to the individual steps, such as when the DEK is wrapped by an external
+//!   key management service. [`pack()`] and [`unpack()`] combine and split the blob.
+//! - The blob is a version byte (`1`), the length of the wrapped DEK (`u16`,
+//!   little-endian), the wrapped DEK and the encrypted data.
+//!
+//! # Parameters:
+//! - `kek`: The key encryption key.
+//! - `dek`: The data encryption key.
+//! - `data`: The data to be encrypted.
+//! - `wrapped_dek`: The DEK encrypted under a KEK.
+//! - `ciphertext`: The encrypted data.
+//! - `blob`: The output of [`seal()`] or [`pack()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `kek` or `dek` is not 32 bytes.
+//! - `data` is empty.
+//! - `wrapped_dek` is empty or longer than `u16::MAX` bytes, when calling [`pack()`].
+//! - `blob` is not well-formed, or its version is not `1`.
+//! - The received tag of `wrapped_dek`, `ciphertext` or `blob` does not match the
+//!   calculated tag.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely.
+//!
+//! # Security:
+//! - Generate a new DEK for each piece of data, e.g. with [`seal()`] or [`generate_dek()`].
+//! - Anyone with the KEK can decrypt every DEK under it, and therefore all the data.
+//! - The version byte and length in a blob are not authenticated, but changing them only
+//!   makes opening fail.
+//! - The length of the data is not hidden, only its contents.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{aead::SecretKey, envelope};
+//!
+//! let kek = SecretKey::default();
+//! let blob = envelope::seal(&kek, b"Secret file")?;
+//! assert_eq!(envelope::open(&kek, &blob)?, b"Secret file");
+//!
+//! // Rotating the KEK.
+//! let new_kek = SecretKey::default();
+//! let blob = envelope::rewrap(&kek, &new_kek, &blob)?;
+//! assert_eq!(envelope::open(&new_kek, &blob)?, b"Secret file");
+//! assert!(envelope::open(&kek, &blob).is_err());
+//!
+//! // The individual steps.
+//! let dek = envelope::generate_dek();
+//! let ciphertext = envelope::seal_data(&dek, b"Secret file")?;
+//! let wrapped_dek = envelope::seal_dek(&new_kek, &dek)?;
+//! let blob = envelope::pack(&wrapped_dek, &ciphertext)?;
+//!
+//! let (wrapped_dek, ciphertext) = envelope::unpack(&blob)?;
+//! let dek = envelope::open_dek(&new_kek, wrapped_dek)?;
+//! assert_eq!(envelope::open_data(&dek, ciphertext)?, b"Secret file");
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`aead::seal_with_context()`]: crate::aead::seal_with_context
+
+pub use super::hltypes::SecretKey;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::high_level::aead;
+use zeroize::Zeroize;
+
+/// The size of a DEK.
+const DEK_SIZE: usize = 32;
+/// The version of the blob format.
+const VERSION: u8 = 1;
+/// The size of the version and length of the wrapped DEK, at the start of a blob.
+const HEADER_SIZE: usize = 3;
+/// The context that a DEK is encrypted under.
+const DEK_CONTEXT: &str = "orion-envelope-dek";
+/// The context that data is encrypted under.
+const DATA_CONTEXT: &str = "orion-envelope-data";
+
+/// Return an error if `key` is not 32 bytes.
+fn check_key(key: &SecretKey) -> Result<(), UnknownCryptoError> {
+    if key.len() != DEK_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    Ok(())
+}
+
+/// Generate a new random DEK of 32 bytes.
+pub fn generate_dek() -> SecretKey {
+    SecretKey::default()
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt `dek` under `kek`.
+pub fn seal_dek(kek: &SecretKey, dek: &SecretKey) -> Result<Vec<u8>, UnknownCryptoError> {
+    check_key(dek)?;
+
+    aead::seal_with_context(kek, DEK_CONTEXT, dek.unprotected_as_bytes())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decrypt a `wrapped_dek` produced by [`seal_dek()`] under `kek`.
+pub fn open_dek(kek: &SecretKey, wrapped_dek: &[u8]) -> Result<SecretKey, UnknownCryptoError> {
+    let mut bytes = aead::open_with_context(kek, DEK_CONTEXT, wrapped_dek)?;
+    let dek = if bytes.len() == DEK_SIZE {
+        SecretKey::from_slice(&bytes)
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::InvalidLength))
+    };
+    bytes.zeroize();
+
+    dek
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt `data` under `dek`.
+pub fn seal_data(dek: &SecretKey, data: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    check_key(dek)?;
+    if data.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    aead::seal_with_context(dek, DATA_CONTEXT, data)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decrypt a `ciphertext` produced by [`seal_data()`] under `dek`.
+pub fn open_data(dek: &SecretKey, ciphertext: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    aead::open_with_context(dek, DATA_CONTEXT, ciphertext)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Combine a `wrapped_dek` and the `ciphertext` of the data into a blob.
+pub fn pack(wrapped_dek: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    if wrapped_dek.is_empty() || wrapped_dek.len() > u16::MAX as usize || ciphertext.is_empty() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut blob = Vec::with_capacity(HEADER_SIZE + wrapped_dek.len() + ciphertext.len());
+    blob.push(VERSION);
+    blob.extend_from_slice(&(wrapped_dek.len() as u16).to_le_bytes());
+    blob.extend_from_slice(wrapped_dek);
+    blob.extend_from_slice(ciphertext);
+
+    Ok(blob)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Split a `blob` into the wrapped DEK and the ciphertext of the data.
+pub fn unpack(blob: &[u8]) -> Result<(&[u8], &[u8]), UnknownCryptoError> {
+    if blob.len() < HEADER_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if blob[0] != VERSION {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+    }
+
+    let wrapped_len = u16::from_le_bytes([blob[1], blob[2]]) as usize;
+    if wrapped_len == 0 || blob.len() <= HEADER_SIZE + wrapped_len {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    Ok(blob[HEADER_SIZE..].split_at(wrapped_len))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt `data` under a new DEK, which is encrypted under `kek`, and return both as a blob.
+pub fn seal(kek: &SecretKey, data: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    check_key(kek)?;
+    let dek = generate_dek();
+    let ciphertext = seal_data(&dek, data)?;
+    let wrapped_dek = seal_dek(kek, &dek)?;
+
+    pack(&wrapped_dek, &ciphertext)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decrypt a `blob` produced by [`seal()`] under `kek`.
+pub fn open(kek: &SecretKey, blob: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+    let (wrapped_dek, ciphertext) = unpack(blob)?;
+    let dek = open_dek(kek, wrapped_dek)?;
+
+    open_data(&dek, ciphertext)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decrypt the DEK of `blob` under `old_kek` and encrypt it under `new_kek`. The
+/// encrypted data is copied as it is.
+pub fn rewrap(
+    old_kek: &SecretKey,
+    new_kek: &SecretKey,
+    blob: &[u8],
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    let (wrapped_dek, ciphertext) = unpack(blob)?;
+    let dek = open_dek(old_kek, wrapped_dek)?;
+    let wrapped_dek = seal_dek(new_kek, &dek)?;
+
+    pack(&wrapped_dek, ciphertext)
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    #[test]
+    fn test_seal_open() {
+        let kek = SecretKey::default();
+        let blob = seal(&kek, b"Secret data").unwrap();
+        assert_eq!(open(&kek, &blob).unwrap(), b"Secret data");
+        assert!(open(&SecretKey::default(), &blob).is_err());
+
+        // A new DEK is used every time.
+        let other = seal(&kek, b"Secret data").unwrap();
+        assert_ne!(unpack(&blob).unwrap().1, unpack(&other).unwrap().1);
+    }
+
+    #[test]
+    fn test_blob_format() {
+        let kek = SecretKey::default();
+        let blob = seal(&kek, b"Secret data").unwrap();
+        // Wrapped DEK: nonce, DEK and tag.
+        let wrapped_len = 24 + DEK_SIZE + 16;
+        assert_eq!(blob[0], VERSION);
+        assert_eq!(&blob[1..3], &(wrapped_len as u16).to_le_bytes());
+        assert_eq!(blob.len(), HEADER_SIZE + wrapped_len + 24 + 11 + 16);
+
+        let (wrapped_dek, ciphertext) = unpack(&blob).unwrap();
+        assert_eq!(wrapped_dek, &blob[HEADER_SIZE..HEADER_SIZE + wrapped_len]);
+        assert_eq!(ciphertext, &blob[HEADER_SIZE + wrapped_len..]);
+        assert_eq!(pack(wrapped_dek, ciphertext).unwrap(), blob);
+    }
+
+    #[test]
+    fn test_rewrap() {
+        let old_kek = SecretKey::default();
+        let new_kek = SecretKey::default();
+        let blob = seal(&old_kek, b"Secret data").unwrap();
+        let rewrapped = rewrap(&old_kek, &new_kek, &blob).unwrap();
+
+        assert_eq!(unpack(&blob).unwrap().1, unpack(&rewrapped).unwrap().1);
+        assert_eq!(open(&new_kek, &rewrapped).unwrap(), b"Secret data");
+        assert!(open(&old_kek, &rewrapped).is_err());
+        assert!(rewrap(&new_kek, &old_kek, &blob).is_err());
+    }
+
+    #[test]
+    fn test_contexts_are_separated() {
+        let kek = SecretKey::default();
+        let dek = generate_dek();
+        let wrapped_dek = seal_dek(&kek, &dek).unwrap();
+        assert!(open_data(&kek, &wrapped_dek).is_err());
+        assert_eq!(open_dek(&kek, &wrapped_dek).unwrap(), dek);
+
+        // Data of 32 bytes cannot be opened as a DEK.
+        let ciphertext = seal_data(&dek, &[0u8; DEK_SIZE]).unwrap();
+        assert!(open_dek(&dek, &ciphertext).is_err());
+        assert!(aead::open(&kek, &wrapped_dek).is_err());
+    }
+
+    #[test]
+    fn test_invalid_keys_and_data() {
+        let kek = SecretKey::default();
+        let short = SecretKey::from_slice(&[0u8; 16]).unwrap();
+        assert!(seal(&short, b"data").is_err());
+        assert!(seal_dek(&kek, &short).is_err());
+        assert!(seal_dek(&short, &generate_dek()).is_err());
+        assert!(seal_data(&short, b"data").is_err());
+        assert!(seal(&kek, b"").is_err());
+        assert!(seal_data(&generate_dek(), b"").is_err());
+
+        // A wrapped key that is not 32 bytes.
+        let wrapped = aead::seal_with_context(&kek, DEK_CONTEXT, &[0u8; 16]).unwrap();
+        let err = open_dek(&kek, &wrapped).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    }
+
+    #[test]
+    fn test_malformed_blobs() {
+        let kek = SecretKey::default();
+        let blob = seal(&kek, b"Secret data").unwrap();
+
+        assert!(unpack(&[]).is_err());
+        assert!(unpack(&blob[..HEADER_SIZE]).is_err());
+        assert!(unpack(&blob[..HEADER_SIZE + 72]).is_err());
+        assert!(unpack(&[VERSION, 0, 0, 1]).is_err());
+
+        let mut wrong_version = blob.clone();
+        wrong_version[0] = 2;
+        let err = unpack(&wrong_version).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+        let mut wrong_length = blob.clone();
+        wrong_length[1] ^= 1;
+        assert!(open(&kek, &wrong_length).is_err());
+
+        for i in HEADER_SIZE..blob.len() {
+            let mut modified = blob.clone();
+            modified[i] ^= 1;
+            assert!(open(&kek, &modified).is_err());
+        }
+
+        assert!(pack(&[], b"ct").is_err());
+        assert!(pack(b"wrapped", &[]).is_err());
+        assert!(pack(&vec![0u8; u16::MAX as usize + 1], b"ct").is_err());
+        assert!(pack(&vec![0u8; u16::MAX as usize], b"ct").is_ok());
+    }
+}
@@ -0,0 +1,408 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Chunked file encryption.
+//!
+//! # Use case:
+//! `orion::file` can be used to encrypt and authenticate data that is too large to fit into
+//! memory, such as files, using a fixed container format.
+//!
+//! # About:
+//! - The data is split into chunks, which are encrypted with the streaming AEAD of
+//!   [`orion::aead::streaming`](super::aead::streaming) (XChaCha20Poly1305).
+//! - The container starts with a header of [`HEADER_SIZE`] bytes: the magic bytes `orion`,
+//!   a version byte (currently `1`), the plaintext chunk size (`u32`, little-endian) and the
+//!   24-byte [`StreamHeader`].
+//! - The header is followed by the encrypted chunks. Every chunk except the last holds exactly
+//!   `chunk_size` bytes of plaintext. The last chunk may hold less, possibly none, and is sealed
+//!   with `StreamTag::Finish`.
+//! - The header is authenticated as additional data for every chunk.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `chunk_size`: The amount of plaintext bytes in each chunk.
+//! - `reader`: The data to be encrypted/decrypted, read until EOF.
+//! - `writer`: Where the encrypted/decrypted data is written to.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `secret_key` is not 32 bytes.
+//! - `chunk_size` is 0 or greater than [`MAX_CHUNK_SIZE`].
+//! - Reading from `reader` or writing to `writer` fails.
+//! - The header is not valid, is for another version or specifies an invalid chunk size.
+//! - The received mac does not match the calculated mac when decrypting a chunk. This can
+//!   indicate a modified, dropped or reordered chunk.
+//! - The data ends before the last chunk, which is sealed with `StreamTag::Finish` (truncation).
+//! - A chunk is sealed with another tag than expected for its position.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely.
+//!
+//! # Security:
+//! - A new, random [`StreamHeader`] is generated every time data is encrypted, so the same
+//!   `secret_key` can be used to encrypt many files.
+//! - To securely generate a strong key, use [`SecretKey::default()`].
+//! - [`open_file()`] writes decrypted chunks to `writer` as soon as they have been
+//!   authenticated. If an error is returned, everything that has been written to `writer` must
+//!   be discarded, as it may be incomplete.
+//! - The length of the data is leaked, up to the padding of the last chunk.
+//!
+//! # Example:
+//! ```rust
+//! use orion::file;
+//!
+//! let key = file::SecretKey::default();
+//! let data = vec![1u8; 100_000];
+//!
+//! let mut sealed = Vec::new();
+//! file::seal_file(&key, &data[..], &mut sealed)?;
+//!
+//! let mut opened = Vec::new();
+//! file::open_file(&key, &sealed[..], &mut opened)?;
+//! assert_eq!(opened, data);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`StreamHeader`]: crate::hazardous::aead::streaming::StreamHeader
+
+pub use super::hltypes::SecretKey;
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::{
+        aead::streaming::{
+            StreamHeader, StreamTag, StreamXChaCha20Poly1305, ABYTES, HEADERBYTES, TAG_SIZE,
+        },
+        stream::chacha20,
+    },
+};
+use std::io::{self, Read, Write};
+use zeroize::Zeroizing;
+
+/// The magic bytes at the start of the container.
+const MAGIC: [u8; 5] = *b"orion";
+/// The current version of the container format.
+const VERSION: u8 = 1;
+/// The size of the container header.
+pub const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + HEADERBYTES;
+/// The chunk size used by [`seal_file()`].
+pub const DEFAULT_CHUNK_SIZE: usize = 65536;
+/// The maximum chunk size accepted when encrypting and decrypting.
+pub const MAX_CHUNK_SIZE: usize = 16_777_216;
+
+/// Read from `reader` until `buf` is full or EOF is reached. Returns the amount of bytes read.
+fn read_until_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, UnknownCryptoError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(filled)
+}
+
+fn new_stream(
+    secret_key: &SecretKey,
+    header: &StreamHeader,
+) -> Result<StreamXChaCha20Poly1305, UnknownCryptoError> {
+    let sk = chacha20::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
+    Ok(StreamXChaCha20Poly1305::from_header(&sk, header))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt and authenticate everything read from `reader`, writing the container to `writer`.
+/// Uses chunks of [`DEFAULT_CHUNK_SIZE`] bytes.
+pub fn seal_file(
+    secret_key: &SecretKey,
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<(), UnknownCryptoError> {
+    seal_file_with_chunk_size(secret_key, DEFAULT_CHUNK_SIZE, reader, writer)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encrypt and authenticate everything read from `reader`, writing the container to `writer`.
+/// Uses chunks of `chunk_size` bytes.
+pub fn seal_file_with_chunk_size(
+    secret_key: &SecretKey,
+    chunk_size: usize,
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<(), UnknownCryptoError> {
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    let stream_header = StreamHeader::generate();
+    let mut ctx = new_stream(secret_key, &stream_header)?;
+
+    let mut header = [0u8; HEADER_SIZE];
+    header[..MAGIC.len()].copy_from_slice(&MAGIC);
+    header[MAGIC.len()] = VERSION;
+    header[MAGIC.len() + 1..MAGIC.len() + 5].copy_from_slice(&(chunk_size as u32).to_le_bytes());
+    header[MAGIC.len() + 5..].copy_from_slice(stream_header.as_ref());
+    writer.write_all(&header)?;
+
+    let mut buf = Zeroizing::new(vec![0u8; chunk_size + ABYTES]);
+    loop {
+        let read = read_until_full(&mut reader, &mut buf[TAG_SIZE..TAG_SIZE + chunk_size])?;
+        let tag = if read < chunk_size {
+            StreamTag::Finish
+        } else {
+            StreamTag::Message
+        };
+
+        let chunk = &mut buf[..read + ABYTES];
+        ctx.seal_chunk_in_place(chunk, Some(&header), tag)?;
+        writer.write_all(chunk)?;
+
+        if tag.is_final() {
+            break;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Authenticate and decrypt a container read from `reader`, writing the plaintext to `writer`.
+pub fn open_file(
+    secret_key: &SecretKey,
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<(), UnknownCryptoError> {
+    let mut header = [0u8; HEADER_SIZE];
+    if read_until_full(&mut reader, &mut header)? != HEADER_SIZE
+        || header[..MAGIC.len()] != MAGIC
+        || header[MAGIC.len()] != VERSION
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+    }
+
+    let mut chunk_size = [0u8; 4];
+    chunk_size.copy_from_slice(&header[MAGIC.len() + 1..MAGIC.len() + 5]);
+    let chunk_size = u32::from_le_bytes(chunk_size) as usize;
+    if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+    }
+
+    let stream_header = StreamHeader::from_slice(&header[MAGIC.len() + 5..])?;
+    let mut ctx = new_stream(secret_key, &stream_header)?;
+
+    let mut buf = Zeroizing::new(vec![0u8; chunk_size + ABYTES]);
+    loop {
+        let read = read_until_full(&mut reader, &mut buf)?;
+        if read < ABYTES {
+            // Includes a stream that ends without a chunk sealed with StreamTag::Finish.
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        // Only the last chunk is shorter than a full chunk, and it must be the only
+        // one sealed with StreamTag::Finish.
+        let is_last = read < buf.len();
+        let chunk = &mut buf[..read];
+        let tag = ctx.open_chunk_in_place(chunk, Some(&header))?;
+        let expected_tag = if is_last {
+            StreamTag::Finish
+        } else {
+            StreamTag::Message
+        };
+        if tag != expected_tag {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        writer.write_all(&chunk[TAG_SIZE..read - (ABYTES - TAG_SIZE)])?;
+
+        if is_last {
+            break;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const CHUNK: usize = 64;
+
+    fn seal(key: &SecretKey, data: &[u8]) -> Vec<u8> {
+        let mut sealed = Vec::new();
+        seal_file_with_chunk_size(key, CHUNK, data, &mut sealed).unwrap();
+        sealed
+    }
+
+    fn open(key: &SecretKey, sealed: &[u8]) -> Result<Vec<u8>, UnknownCryptoError> {
+        let mut opened = Vec::new();
+        open_file(key, sealed, &mut opened).map(|_| opened)
+    }
+
+    #[test]
+    fn test_roundtrip_lengths() {
+        let key = SecretKey::default();
+        for len in [0, 1, CHUNK - 1, CHUNK, CHUNK + 1, 3 * CHUNK, 3 * CHUNK + 7].iter() {
+            let data = vec![*len as u8; *len];
+            let sealed = seal(&key, &data);
+            // Every full chunk is followed by one more (possibly empty) final chunk.
+            let chunks = len / CHUNK + 1;
+            assert_eq!(sealed.len(), HEADER_SIZE + len + chunks * ABYTES);
+            assert_eq!(open(&key, &sealed).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_default_chunk_size() {
+        let key = SecretKey::default();
+        let data = vec![0u8; DEFAULT_CHUNK_SIZE + 1];
+        let mut sealed = Vec::new();
+        seal_file(&key, &data[..], &mut sealed).unwrap();
+        assert_eq!(sealed.len(), HEADER_SIZE + data.len() + 2 * ABYTES);
+        assert_eq!(open(&key, &sealed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_header() {
+        let key = SecretKey::default();
+        let sealed = seal(&key, b"");
+        assert_eq!(&sealed[..5], b"orion");
+        assert_eq!(sealed[5], VERSION);
+        assert_eq!(&sealed[6..10], &(CHUNK as u32).to_le_bytes());
+
+        // The stream header is random.
+        assert_ne!(sealed, seal(&key, b""));
+    }
+
+    #[test]
+    fn test_invalid_chunk_size_err() {
+        let key = SecretKey::default();
+        let mut sealed = Vec::new();
+        assert!(seal_file_with_chunk_size(&key, 0, &b""[..], &mut sealed).is_err());
+        assert!(
+            seal_file_with_chunk_size(&key, MAX_CHUNK_SIZE + 1, &b""[..], &mut sealed).is_err()
+        );
+        assert!(sealed.is_empty());
+        assert!(seal_file_with_chunk_size(&key, 1, &b""[..], &mut sealed).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_key_err() {
+        let mut sealed = Vec::new();
+        let key = SecretKey::generate(31).unwrap();
+        assert!(seal_file(&key, &b""[..], &mut sealed).is_err());
+
+        let sealed = seal(&SecretKey::default(), b"data");
+        assert!(open(&key, &sealed).is_err());
+        assert!(open(&SecretKey::default(), &sealed).is_err());
+    }
+
+    #[test]
+    fn test_modified_header_err() {
+        let key = SecretKey::default();
+        let sealed = seal(&key, &[0u8; 2 * CHUNK]);
+
+        for idx in 0..HEADER_SIZE {
+            let mut modified = sealed.clone();
+            modified[idx] ^= 1;
+            assert!(open(&key, &modified).is_err());
+        }
+
+        assert!(open(&key, &sealed[..HEADER_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn test_modified_chunk_err() {
+        let key = SecretKey::default();
+        let sealed = seal(&key, &[0u8; 2 * CHUNK]);
+
+        for idx in HEADER_SIZE..sealed.len() {
+            let mut modified = sealed.clone();
+            modified[idx] ^= 1;
+            assert_eq!(
+                open(&key, &modified).unwrap_err(),
+                UnknownCryptoError::new(ErrorKind::MacMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncation_err() {
+        let key = SecretKey::default();
+        let data = [0u8; 2 * CHUNK];
+        let sealed = seal(&key, &data);
+        let full = CHUNK + ABYTES;
+
+        // Dropping the final chunk, or cutting a chunk short, must be detected.
+        for len in [
+            HEADER_SIZE,
+            HEADER_SIZE + full,
+            HEADER_SIZE + 2 * full,
+            HEADER_SIZE + 2 * full - 1,
+            sealed.len() - 1,
+        ]
+        .iter()
+        {
+            assert!(open(&key, &sealed[..*len]).is_err());
+        }
+
+        let mut extended = sealed.clone();
+        extended.push(0u8);
+        assert!(open(&key, &extended).is_err());
+        assert_eq!(open(&key, &sealed).unwrap(), &data[..]);
+    }
+
+    #[test]
+    fn test_reordered_chunks_err() {
+        let key = SecretKey::default();
+        let sealed = seal(&key, &[1u8; 3 * CHUNK]);
+        let full = CHUNK + ABYTES;
+
+        let mut reordered = sealed[..HEADER_SIZE].to_vec();
+        reordered.extend_from_slice(&sealed[HEADER_SIZE + full..HEADER_SIZE + 2 * full]);
+        reordered.extend_from_slice(&sealed[HEADER_SIZE..HEADER_SIZE + full]);
+        reordered.extend_from_slice(&sealed[HEADER_SIZE + 2 * full..]);
+        assert!(open(&key, &reordered).is_err());
+    }
+
+    #[test]
+    fn test_chunks_from_other_file_err() {
+        let key = SecretKey::default();
+        let first = seal(&key, &[1u8; CHUNK]);
+        let second = seal(&key, &[1u8; CHUNK]);
+
+        let mut mixed = first[..HEADER_SIZE].to_vec();
+        mixed.extend_from_slice(&second[HEADER_SIZE..]);
+        assert!(open(&key, &mixed).is_err());
+    }
+
+    #[quickcheck]
+    /// Opening a sealed container should always produce the original input.
+    fn prop_seal_open_same_input(input: Vec<u8>) -> bool {
+        let key = SecretKey::default();
+        open(&key, &seal(&key, &input[..])).unwrap() == input
+    }
+}
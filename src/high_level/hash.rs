@@ -31,31 +31,184 @@
 //!
 //! # About:
 //! - Uses BLAKE2b with an output size of 32 bytes (i.e BLAKE2b-256).
+//! - [`digest_keyed()`] uses BLAKE2b-256 in keyed mode.
+//! - [`Algorithm`] selects a hash function at runtime, for applications that store
+//!   an algorithm identifier alongside the hashed data. [`Algorithm::as_byte()`] and
+//!   `Algorithm::try_from()` convert to and from such an identifier. Digests of all
+//!   algorithms are returned as a [`Digest`].
+//! - [`Digest`] implements `Display` and `FromStr` using hexadecimal, encoded and decoded in
+//!   constant time.
 //!
 //! # Parameters:
+//! - `secret_key`: The secret key used by [`digest_keyed()`].
 //! - `data`:  The data to be hashed.
+//! - `reader`: A reader whose contents, until EOF, should be hashed.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - [`Hasher::update()`] is called after [`Hasher::finalize()`].
+//! - The identifier given to `Algorithm::try_from()` is unknown.
+//! - Reading from `reader` fails.
+//! - The [`SecretKey`] supplied to [`digest_keyed()`] is less than 32 bytes or greater than 64 bytes.
 //!
 //! # Panics:
 //! A panic will occur if:
 //! - More than 2*(2^64-1) bytes of data are hashed.
 //!
+//! # Note:
+//! This module is available without the `alloc` feature, for `no_std` targets with no heap.
+//! [`digest_keyed()`] then additionally requires the `alloc` and `getrandom` features, and
+//! [`digest_from_reader()`] requires `safe_api`.
+//!
 //! # Security:
-//! - This interface does not support supplying BLAKE2b with a secret key, and
-//!   the hashes retrieved
-//! from using `orion::hash` are therefore not suitable as MACs.
+//! - Only the hashes retrieved from [`digest_keyed()`] depend on a secret key. The
+//!   others are not suitable as MACs.
+//! - [`digest_keyed()`] is meant for use as a keyed hash or PRF. Its output is the same as
+//!   that of [`orion::auth::authenticate()`](super::auth::authenticate), which should be preferred,
+//!   along with [`orion::auth::authenticate_verify()`](super::auth::authenticate_verify),
+//!   when the intention is to authenticate messages.
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::default()`] can be used for this; it will generate a [`SecretKey`] of 32 bytes.
 //! - BLAKE2b is not suitable for password hashing. See [`orion::pwhash`](super::pwhash)
 //!   instead.
+//! - The SHA-2 algorithms of [`Algorithm`] are vulnerable to length extension attacks.
+//!   BLAKE2b should be preferred unless SHA-2 is required for interoperability.
 //!
 //! # Example:
 //! ```rust
 //! use orion::hash::{digest, Digest};
 //!
 //! let hash: Digest = digest(b"Some data")?;
+//!
+//! // Data that isn't available all at once can be hashed incrementally.
+//! use orion::hash::Hasher;
+//!
+//! let mut hasher = Hasher::new();
+//! hasher.update(b"Some ")?;
+//! // Cloning the hasher forks it, so a common prefix is only hashed once.
+//! let mut other = hasher.clone();
+//! hasher.update(b"data")?;
+//! assert_eq!(hasher.finalize()?, hash);
+//! other.update(b"other data")?;
+//! assert_eq!(other.finalize()?, digest(b"Some other data")?);
+//!
+//! // Keyed hashing.
+//! use orion::hash::{digest_keyed, SecretKey};
+//!
+//! let key = SecretKey::default();
+//! let keyed_hash: Digest = digest_keyed(&key, b"Some data")?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//!
+//! Selecting the algorithm at runtime:
+//! ```rust
+//! use core::convert::TryFrom;
+//! use orion::hash::{Algorithm, Hasher};
+//!
+//! // The identifier stored alongside the data, e.g. in a file header.
+//! let stored_id = Algorithm::Sha512.as_byte();
+//!
+//! let algorithm = Algorithm::try_from(stored_id)?;
+//! let mut hasher = Hasher::with_algorithm(algorithm);
+//! hasher.update(b"Some data")?;
+//! assert_eq!(hasher.finalize()?, algorithm.digest(b"Some data")?);
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
+//!
+//! Hashing a file, or anything else that implements [`std::io::Read`]:
+//! ```rust
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use orion::hash::{digest, digest_from_reader};
+//!
+//! let file_contents = std::io::Cursor::new(b"Some data");
+//! assert_eq!(digest_from_reader(file_contents)?, digest(b"Some data")?);
+//! # Ok(())
+//! # }
+//! ```
 
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+pub use super::hltypes::SecretKey;
 pub use crate::hazardous::hash::blake2b::Digest;
-use crate::{errors::UnknownCryptoError, hazardous::hash::blake2b};
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::hash::{
+        blake2b,
+        sha2::{sha256::Sha256, sha384::Sha384, sha512::Sha512},
+    },
+};
+use core::convert::TryFrom;
+
+/// The minimum `SecretKey` size (bytes) to be used by BLAKE2b in keyed mode.
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+const BLAKE2B_MIN_KEY_SIZE: usize = 32;
+
+/// The size of the buffer used when hashing from a reader.
+#[cfg(feature = "safe_api")]
+const READER_BUFSIZE: usize = 8192;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Hash functions that can be selected at runtime.
+pub enum Algorithm {
+    /// BLAKE2b with an output size of 32 bytes.
+    Blake2b256,
+    /// BLAKE2b with an output size of 48 bytes.
+    Blake2b384,
+    /// BLAKE2b with an output size of 64 bytes.
+    Blake2b512,
+    /// SHA-256.
+    Sha256,
+    /// SHA-384.
+    Sha384,
+    /// SHA-512.
+    Sha512,
+}
+
+impl Algorithm {
+    /// Return the identifier of the algorithm.
+    pub fn as_byte(&self) -> u8 {
+        match *self {
+            Algorithm::Blake2b256 => 0x01,
+            Algorithm::Blake2b384 => 0x02,
+            Algorithm::Blake2b512 => 0x03,
+            Algorithm::Sha256 => 0x04,
+            Algorithm::Sha384 => 0x05,
+            Algorithm::Sha512 => 0x06,
+        }
+    }
+
+    /// Return the size (bytes) of digests produced by the algorithm.
+    pub fn output_size(&self) -> usize {
+        match *self {
+            Algorithm::Blake2b256 | Algorithm::Sha256 => 32,
+            Algorithm::Blake2b384 | Algorithm::Sha384 => 48,
+            Algorithm::Blake2b512 | Algorithm::Sha512 => 64,
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Hash `data` using the algorithm.
+    pub fn digest(&self, data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+        let mut hasher = Hasher::with_algorithm(*self);
+        hasher.update(data)?;
+        hasher.finalize()
+    }
+}
+
+impl TryFrom<u8> for Algorithm {
+    type Error = UnknownCryptoError;
+
+    fn try_from(byte: u8) -> Result<Self, Self::Error> {
+        match byte {
+            0x01 => Ok(Algorithm::Blake2b256),
+            0x02 => Ok(Algorithm::Blake2b384),
+            0x03 => Ok(Algorithm::Blake2b512),
+            0x04 => Ok(Algorithm::Sha256),
+            0x05 => Ok(Algorithm::Sha384),
+            0x06 => Ok(Algorithm::Sha512),
+            _ => Err(UnknownCryptoError::new(ErrorKind::InvalidParameter)),
+        }
+    }
+}
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Hashing using BLAKE2b-256.
@@ -63,6 +216,116 @@ pub fn digest(data: &[u8]) -> Result<Digest, UnknownCryptoError> {
     blake2b::Hasher::Blake2b256.digest(data)
 }
 
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Keyed hashing using BLAKE2b-256 in keyed mode.
+pub fn digest_keyed(secret_key: &SecretKey, data: &[u8]) -> Result<Digest, UnknownCryptoError> {
+    if secret_key.len() < BLAKE2B_MIN_KEY_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    let blake2b_secret_key = blake2b::SecretKey::from_slice(secret_key.unprotected_as_bytes())?;
+    let mut state = blake2b::Blake2b::new(Some(&blake2b_secret_key), 32)?;
+    state.update(data)?;
+    state.finalize()
+}
+
+#[cfg(feature = "safe_api")]
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hashing using BLAKE2b-256, of everything read from `reader` until EOF.
+pub fn digest_from_reader(mut reader: impl std::io::Read) -> Result<Digest, UnknownCryptoError> {
+    let mut hasher = Hasher::new();
+    let mut buf = [0u8; READER_BUFSIZE];
+
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n])?,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    hasher.finalize()
+}
+
+#[derive(Clone, Debug)]
+enum HasherState {
+    Blake2b(blake2b::Blake2b),
+    Sha256(Sha256),
+    Sha384(Sha384),
+    Sha512(Sha512),
+}
+
+#[derive(Clone, Debug)]
+/// Incremental hashing using BLAKE2b-256, or the [`Algorithm`] given to
+/// [`Hasher::with_algorithm()`].
+pub struct Hasher {
+    algorithm: Algorithm,
+    state: HasherState,
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher {
+    /// Initialize a new `Hasher`, using BLAKE2b-256.
+    pub fn new() -> Self {
+        Self::with_algorithm(Algorithm::Blake2b256)
+    }
+
+    /// Initialize a new `Hasher`, using `algorithm`.
+    #[allow(clippy::unwrap_used)]
+    pub fn with_algorithm(algorithm: Algorithm) -> Self {
+        let state = match algorithm {
+            // NOTE: These are valid BLAKE2b sizes so .unwrap() is OK.
+            Algorithm::Blake2b256 => {
+                HasherState::Blake2b(blake2b::Hasher::Blake2b256.init().unwrap())
+            }
+            Algorithm::Blake2b384 => {
+                HasherState::Blake2b(blake2b::Hasher::Blake2b384.init().unwrap())
+            }
+            Algorithm::Blake2b512 => {
+                HasherState::Blake2b(blake2b::Hasher::Blake2b512.init().unwrap())
+            }
+            Algorithm::Sha256 => HasherState::Sha256(Sha256::new()),
+            Algorithm::Sha384 => HasherState::Sha384(Sha384::new()),
+            Algorithm::Sha512 => HasherState::Sha512(Sha512::new()),
+        };
+
+        Self { algorithm, state }
+    }
+
+    /// Return the [`Algorithm`] used by this `Hasher`.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Update the `Hasher` with `data`. This can be called multiple times.
+    pub fn update(&mut self, data: &[u8]) -> Result<(), UnknownCryptoError> {
+        match self.state {
+            HasherState::Blake2b(ref mut state) => state.update(data),
+            HasherState::Sha256(ref mut state) => state.update(data),
+            HasherState::Sha384(ref mut state) => state.update(data),
+            HasherState::Sha512(ref mut state) => state.update(data),
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Return the digest of all data given to [`Hasher::update()`].
+    pub fn finalize(mut self) -> Result<Digest, UnknownCryptoError> {
+        match self.state {
+            HasherState::Blake2b(ref mut state) => state.finalize(),
+            HasherState::Sha256(ref mut state) => Digest::from_slice(state.finalize()?.as_ref()),
+            HasherState::Sha384(ref mut state) => Digest::from_slice(state.finalize()?.as_ref()),
+            HasherState::Sha512(ref mut state) => Digest::from_slice(state.finalize()?.as_ref()),
+        }
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(feature = "safe_api")]
 #[cfg(test)]
@@ -80,4 +343,247 @@ mod public {
     fn prop_digest_diff_result(input: Vec<u8>) -> bool {
         digest(&input[..]).unwrap() != digest(b"Completely wrong input").unwrap()
     }
+
+    mod test_display_fromstr {
+        use super::*;
+        use core::str::FromStr;
+
+        const EMPTY_DIGEST: &str =
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8";
+
+        #[test]
+        fn test_display_is_hex() {
+            let hash = digest(b"").unwrap();
+            assert_eq!(hash.to_string(), EMPTY_DIGEST);
+            assert_eq!(hash.to_string(), hash.to_hex());
+        }
+
+        #[test]
+        fn test_fromstr_roundtrip() {
+            let hash = digest(b"").unwrap();
+            assert_eq!(Digest::from_str(EMPTY_DIGEST).unwrap(), hash);
+            assert_eq!(EMPTY_DIGEST.to_uppercase().parse::<Digest>().unwrap(), hash);
+
+            let long = Algorithm::Blake2b512.digest(b"").unwrap();
+            assert_eq!(long.to_string().parse::<Digest>().unwrap(), long);
+        }
+
+        #[test]
+        fn test_fromstr_err() {
+            assert!(Digest::from_str("").is_err());
+            assert!(Digest::from_str("0").is_err());
+            assert!(Digest::from_str("0g").is_err());
+            assert!(Digest::from_str(&"00".repeat(65)).is_err());
+            assert!(Digest::from_str(&"00".repeat(64)).is_ok());
+        }
+    }
+
+    mod test_digest_keyed {
+        use super::*;
+
+        #[test]
+        fn test_digest_keyed_key_length() {
+            assert!(digest_keyed(&SecretKey::generate(31).unwrap(), b"").is_err());
+            assert!(digest_keyed(&SecretKey::generate(32).unwrap(), b"").is_ok());
+            assert!(digest_keyed(&SecretKey::generate(64).unwrap(), b"").is_ok());
+            assert!(digest_keyed(&SecretKey::generate(65).unwrap(), b"").is_err());
+        }
+
+        #[test]
+        fn test_digest_keyed_same_as_auth() {
+            let key = SecretKey::default();
+            let keyed = digest_keyed(&key, b"data").unwrap();
+            let tag = crate::auth::authenticate(&key, b"data").unwrap();
+            assert_eq!(keyed.as_ref(), tag.unprotected_as_bytes());
+        }
+
+        #[quickcheck]
+        /// Keyed hashing should differ from unkeyed hashing and between keys.
+        fn prop_digest_keyed_diff_result(input: Vec<u8>) -> bool {
+            let keyed = digest_keyed(&SecretKey::default(), &input[..]).unwrap();
+            keyed != digest(&input[..]).unwrap()
+                && keyed != digest_keyed(&SecretKey::default(), &input[..]).unwrap()
+        }
+    }
+
+    mod test_hasher {
+        use super::*;
+
+        #[quickcheck]
+        /// Hashing incrementally should produce the same output as one-shot hashing.
+        fn prop_hasher_same_as_digest(input: Vec<u8>, split: usize) -> bool {
+            let split = if input.is_empty() {
+                0
+            } else {
+                split % input.len()
+            };
+            let mut hasher = Hasher::new();
+            hasher.update(&input[..split]).unwrap();
+            hasher.update(&input[split..]).unwrap();
+
+            hasher.finalize().unwrap() == digest(&input[..]).unwrap()
+        }
+
+        #[test]
+        fn test_default_same_as_new() {
+            assert_eq!(
+                Hasher::default().finalize().unwrap(),
+                Hasher::new().finalize().unwrap()
+            );
+        }
+    }
+
+    mod test_algorithm {
+        use super::*;
+
+        const ALL: [Algorithm; 6] = [
+            Algorithm::Blake2b256,
+            Algorithm::Blake2b384,
+            Algorithm::Blake2b512,
+            Algorithm::Sha256,
+            Algorithm::Sha384,
+            Algorithm::Sha512,
+        ];
+
+        #[test]
+        fn test_identifier_roundtrip() {
+            for algorithm in ALL.iter() {
+                assert_eq!(
+                    Algorithm::try_from(algorithm.as_byte()).unwrap(),
+                    *algorithm
+                );
+            }
+            assert!(Algorithm::try_from(0x00).is_err());
+            assert!(Algorithm::try_from(0x07).is_err());
+        }
+
+        #[test]
+        fn test_same_as_hazardous() {
+            let data = b"Some data";
+            assert_eq!(
+                Algorithm::Blake2b256.digest(data).unwrap(),
+                digest(data).unwrap()
+            );
+            assert_eq!(
+                Algorithm::Blake2b384.digest(data).unwrap(),
+                blake2b::Hasher::Blake2b384.digest(data).unwrap()
+            );
+            assert_eq!(
+                Algorithm::Blake2b512.digest(data).unwrap(),
+                blake2b::Hasher::Blake2b512.digest(data).unwrap()
+            );
+            assert_eq!(
+                Algorithm::Sha256.digest(data).unwrap().as_ref(),
+                Sha256::digest(data).unwrap().as_ref()
+            );
+            assert_eq!(
+                Algorithm::Sha384.digest(data).unwrap().as_ref(),
+                Sha384::digest(data).unwrap().as_ref()
+            );
+            assert_eq!(
+                Algorithm::Sha512.digest(data).unwrap().as_ref(),
+                Sha512::digest(data).unwrap().as_ref()
+            );
+        }
+
+        #[test]
+        fn test_output_size() {
+            for algorithm in ALL.iter() {
+                assert_eq!(
+                    algorithm.digest(b"").unwrap().len(),
+                    algorithm.output_size()
+                );
+                assert_eq!(Hasher::with_algorithm(*algorithm).algorithm(), *algorithm);
+            }
+        }
+
+        #[quickcheck]
+        /// Hashing incrementally should produce the same output as one-shot hashing.
+        fn prop_hasher_same_as_digest(input: Vec<u8>, split: usize, idx: usize) -> bool {
+            let algorithm = ALL[idx % ALL.len()];
+            let split = if input.is_empty() {
+                0
+            } else {
+                split % input.len()
+            };
+            let mut hasher = Hasher::with_algorithm(algorithm);
+            hasher.update(&input[..split]).unwrap();
+            hasher.update(&input[split..]).unwrap();
+
+            hasher.finalize().unwrap() == algorithm.digest(&input[..]).unwrap()
+        }
+    }
+
+    mod test_digest_from_reader {
+        use super::*;
+        use std::io::{self, Read};
+
+        #[quickcheck]
+        /// Hashing from a reader should produce the same output as one-shot hashing.
+        fn prop_reader_same_as_digest(input: Vec<u8>) -> bool {
+            digest_from_reader(&input[..]).unwrap() == digest(&input[..]).unwrap()
+        }
+
+        #[test]
+        fn test_reader_larger_than_buffer() {
+            let input = vec![1u8; READER_BUFSIZE * 3 + 1];
+            assert_eq!(
+                digest_from_reader(io::Cursor::new(&input)).unwrap(),
+                digest(&input).unwrap()
+            );
+        }
+
+        /// Reader that is interrupted once before every read, and fails after `fail_after` bytes.
+        struct FlakyReader {
+            data: Vec<u8>,
+            pos: usize,
+            interrupt: bool,
+            fail_after: Option<usize>,
+        }
+
+        impl Read for FlakyReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.interrupt = !self.interrupt;
+                if self.interrupt {
+                    return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+                }
+                if let Some(limit) = self.fail_after {
+                    if self.pos >= limit {
+                        return Err(io::Error::new(io::ErrorKind::BrokenPipe, "failed"));
+                    }
+                }
+
+                let n = core::cmp::min(buf.len(), self.data.len() - self.pos);
+                buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+                self.pos += n;
+                Ok(n)
+            }
+        }
+
+        #[test]
+        fn test_reader_interrupted_is_retried() {
+            let reader = FlakyReader {
+                data: vec![2u8; READER_BUFSIZE + 10],
+                pos: 0,
+                interrupt: false,
+                fail_after: None,
+            };
+            assert_eq!(
+                digest_from_reader(reader).unwrap(),
+                digest(&[2u8; READER_BUFSIZE + 10]).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_reader_error() {
+            let reader = FlakyReader {
+                data: vec![2u8; READER_BUFSIZE + 10],
+                pos: 0,
+                interrupt: false,
+                fail_after: Some(READER_BUFSIZE),
+            };
+            let err = digest_from_reader(reader).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::Io);
+        }
+    }
 }
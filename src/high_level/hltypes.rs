@@ -22,7 +22,11 @@
 
 /// These are the different types used by the high-level interface. They are not
 /// used in `hazardous`.
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::blake2b::Blake2b;
+use alloc::vec::Vec;
+#[cfg(feature = "safe_api")]
+use ct_codecs::{Base64NoPadding, Decoder, Encoder};
 
 construct_secret_key_variable_size! {
     /// A type to represent a secret key.
@@ -34,30 +38,98 @@ construct_secret_key_variable_size! {
     /// - `slice` is empty.
     /// - `length` is 0.
     /// - `length` is not less than [`isize::MAX`].
+    /// - `generate()` fails to generate random bytes securely.
     ///
     /// # Panics:
     /// A panic will occur if:
-    /// - Failure to generate random bytes securely.
+    /// - `default()` fails to generate random bytes securely.
     (SecretKey, test_secret_key, 32)
 }
 
 construct_salt_variable_size! {
     /// A type to represent the `Salt` that Argon2i uses during key derivation.
     ///
-    /// As default it will randomly generate a `Salt` of 16 bytes.
+    /// As default it will randomly generate a `Salt` of 16 bytes. Salts of other
+    /// lengths, such as those used by existing deployments, can be created with
+    /// `from_slice()`.
     ///
     /// # Errors:
     /// An error will be returned if:
-    /// - `slice` is empty.
-    /// - `length` is 0.
+    /// - `slice` is less than 8 bytes.
+    /// - `length` is less than 8.
     /// - `length` is not less than [`isize::MAX`].
+    /// - `generate()` fails to generate random bytes securely.
     ///
     /// # Panics:
     /// A panic will occur if:
-    /// - Failure to generate random bytes securely.
-    (Salt, test_salt, 16)
+    /// - `default()` fails to generate random bytes securely.
+    (Salt, test_salt, 8, 16)
+}
+
+/// The domain separation prefix for [`Salt::derive_from_identifier()`].
+const SALT_FROM_IDENTIFIER_CONTEXT: &[u8] = b"orion-salt";
+
+impl Salt {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a 16-byte `Salt` deterministically from `identifier`, such as the ID of
+    /// the record that a key is derived for.
+    ///
+    /// The `Salt` is computed as BLAKE2b-128("orion-salt" || `identifier`).
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `identifier` is empty.
+    ///
+    /// # Security:
+    /// - A salt is only unique if `identifier` is. Two deployments that use the same
+    ///   identifiers, such as incrementing user IDs, derive the same salts. Include a
+    ///   value that is unique to the deployment in `identifier` to avoid this.
+    /// - Randomly generated salts from [`Salt::default()`] should be preferred, whenever
+    ///   they can be stored.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use orion::kdf;
+    ///
+    /// let salt = kdf::Salt::derive_from_identifier(b"example.com/users/1337")?;
+    /// assert_eq!(salt, kdf::Salt::derive_from_identifier(b"example.com/users/1337")?);
+    /// # Ok::<(), orion::errors::UnknownCryptoError>(())
+    /// ```
+    pub fn derive_from_identifier(identifier: &[u8]) -> Result<Self, UnknownCryptoError> {
+        if identifier.is_empty() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        let mut ctx = Blake2b::new(None, 16)?;
+        ctx.update(SALT_FROM_IDENTIFIER_CONTEXT)?;
+        ctx.update(identifier)?;
+
+        Self::from_slice(ctx.finalize()?.as_ref())
+    }
+}
+
+#[cfg(feature = "safe_api")]
+/// Formatted as base64, using the standard alphabet without padding, as salts are in
+/// encoded password hashes.
+impl core::fmt::Display for Salt {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let encoded =
+            Base64NoPadding::encode_to_string(self.as_ref()).map_err(|_| core::fmt::Error)?;
+        f.write_str(&encoded)
+    }
 }
 
+#[cfg(feature = "safe_api")]
+/// Parsed from base64, using the standard alphabet without padding, with `from_slice()`.
+impl core::str::FromStr for Salt {
+    type Err = UnknownCryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_slice(&Base64NoPadding::decode_to_vec(s, None)?)
+    }
+}
+
+#[cfg(feature = "safe_api")]
 construct_tag! {
     /// A type to represent the `Tag` output by BLAKE2b-256 in keyed mode.
     ///
@@ -67,8 +139,12 @@ construct_tag! {
     (Tag, test_tag, 32, 32)
 }
 
+#[cfg(feature = "safe_api")]
 impl_from_trait!(Tag, 32);
 
+#[cfg(feature = "safe_api")]
+impl_hex_display_fromstr_traits!(Tag, unprotected_as_bytes, 32);
+
 construct_secret_key_variable_size! {
     /// A type to represent the `Password` that Argon2i hashes and uses for key derivation.
     ///
@@ -79,9 +155,210 @@ construct_secret_key_variable_size! {
     /// - `slice` is empty.
     /// - `length` is 0.
     /// - `length` is not less than [`isize::MAX`].
+    /// - `generate()` fails to generate random bytes securely.
     ///
     /// # Panics:
     /// A panic will occur if:
-    /// - Failure to generate random bytes securely.
+    /// - `default()` fails to generate random bytes securely.
     (Password, test_password, 32)
 }
+
+/// A type to represent sensitive data of any length, which is zeroized when dropped.
+///
+/// This is meant for secrets that orion has no dedicated type for, such as decrypted
+/// plaintext, API tokens, passphrases or shared secrets from other libraries. It is
+/// available as `orion::util::SecretBytes`.
+///
+/// # Security:
+/// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
+///   that the type implements. Copies made from the returned slice are not zeroized.
+///
+/// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
+///   to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
+///   is implemented in such a way that the comparison happens in constant time.
+///
+/// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+///   in custom verification logic that should not short-circuit.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `length` is 0.
+/// - `length` is not less than [`isize::MAX`].
+/// - `generate()` fails to generate random bytes securely.
+///
+/// # Example:
+/// ```rust
+/// use orion::util::SecretBytes;
+///
+/// let secret = SecretBytes::from(vec![1u8; 32]);
+/// assert_eq!(secret.len(), 32);
+/// assert!(secret == &[1u8; 32][..]);
+///
+/// let api_token = SecretBytes::from(&b"some api token"[..]);
+/// assert!(api_token != secret);
+///
+/// let random = SecretBytes::generate(48)?;
+/// assert_eq!(random.len(), 48);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub struct SecretBytes {
+    value: Vec<u8>,
+    original_length: usize,
+}
+
+impl_omitted_debug_trait!(SecretBytes);
+impl_drop_trait!(SecretBytes);
+impl_ct_partialeq_trait!(SecretBytes, unprotected_as_bytes);
+
+impl SecretBytes {
+    func_unprotected_as_bytes!();
+    func_len!();
+    func_generate_variable_size!(SecretBytes);
+
+    #[inline]
+    /// Return `true` if this object does not hold any data, `false` otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.original_length == 0
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    /// Take ownership of `value`. The allocation of `value` is reused and zeroized when
+    /// the `SecretBytes` is dropped.
+    fn from(value: Vec<u8>) -> Self {
+        let original_length = value.len();
+
+        Self {
+            value,
+            original_length,
+        }
+    }
+}
+
+impl From<&[u8]> for SecretBytes {
+    /// Copy `slice` into a new `SecretBytes`.
+    fn from(slice: &[u8]) -> Self {
+        Self::from(Vec::from(slice))
+    }
+}
+
+#[cfg(test)]
+mod test_secret_bytes {
+    use super::*;
+
+    #[test]
+    fn test_from_vec() {
+        let secret = SecretBytes::from(vec![1u8, 2, 3]);
+        assert_eq!(secret.len(), 3);
+        assert!(!secret.is_empty());
+        assert_eq!(secret.unprotected_as_bytes(), &[1u8, 2, 3]);
+
+        let empty = SecretBytes::from(Vec::new());
+        assert_eq!(empty.len(), 0);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let secret = SecretBytes::from(&[1u8, 2, 3][..]);
+        assert!(secret == SecretBytes::from(vec![1u8, 2, 3]));
+        assert!(SecretBytes::from(&[][..]).is_empty());
+    }
+
+    #[test]
+    fn test_generate() {
+        assert!(SecretBytes::generate(0).is_err());
+        assert!(SecretBytes::generate((isize::MAX as usize) + 1).is_err());
+
+        let secret = SecretBytes::generate(128).unwrap();
+        assert_eq!(secret.len(), 128);
+        assert!(secret != SecretBytes::from(vec![0u8; 128]));
+        assert!(secret != SecretBytes::generate(128).unwrap());
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        let zero = SecretBytes::from(vec![0u8; 16]);
+        let one = SecretBytes::from(vec![1u8; 16]);
+        let short = SecretBytes::from(vec![0u8; 15]);
+
+        assert!(zero == SecretBytes::from(vec![0u8; 16]));
+        assert!(zero != one);
+        assert!(zero != short);
+        assert!(zero == [0u8; 16].as_ref());
+        assert!(zero != [1u8; 16].as_ref());
+    }
+
+    #[test]
+    fn test_omitted_debug() {
+        let secret = format!("{:?}", [7u8; 16].as_ref());
+        let test_debug_contents = format!("{:?}", SecretBytes::from(vec![7u8; 16]));
+        assert!(!test_debug_contents.contains(&secret));
+        assert_eq!(test_debug_contents, "SecretBytes {***OMITTED***}");
+    }
+}
+
+#[cfg(test)]
+mod test_salt_derive_from_identifier {
+    use super::*;
+
+    #[test]
+    fn test_derive_from_identifier() {
+        let salt = Salt::derive_from_identifier(b"example.com/users/1337").unwrap();
+        assert_eq!(salt.len(), 16);
+        assert_eq!(
+            salt.as_ref(),
+            &[
+                0x3f, 0x2a, 0x6d, 0xad, 0x08, 0x94, 0xf3, 0x7c, 0x20, 0xc9, 0x3f, 0xf6, 0x41, 0x87,
+                0x29, 0x7d
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_identifier_diff_salt() {
+        assert_ne!(
+            Salt::derive_from_identifier(b"user-1").unwrap(),
+            Salt::derive_from_identifier(b"user-2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_empty_identifier_err() {
+        assert!(Salt::derive_from_identifier(b"").is_err());
+        assert!(Salt::derive_from_identifier(b"1").is_ok());
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+mod test_salt_display_fromstr {
+    use super::*;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_display_is_base64() {
+        let salt = Salt::from_slice(b"somesaltsomesalt").unwrap();
+        assert_eq!(salt.to_string(), "c29tZXNhbHRzb21lc2FsdA");
+    }
+
+    #[test]
+    fn test_fromstr_roundtrip() {
+        let salt = Salt::default();
+        assert_eq!(Salt::from_str(&salt.to_string()).unwrap(), salt);
+        assert_eq!(
+            Salt::from_str("c29tZXNhbHRzb21lc2FsdA").unwrap().as_ref(),
+            b"somesaltsomesalt"
+        );
+    }
+
+    #[test]
+    fn test_fromstr_err() {
+        assert!(Salt::from_str("").is_err());
+        // Padding is not accepted.
+        assert!(Salt::from_str("c29tZXNhbHRzb21lc2FsdA==").is_err());
+        // Decodes to less than 8 bytes.
+        assert!(Salt::from_str("c29tZXNhbA").is_err());
+        assert!(Salt::from_str("c29tZXNhbHQ").is_ok());
+    }
+}
@@ -31,6 +31,21 @@
 //!
 //! # About:
 //! - Uses Argon2i.
+//! - [`Algorithm`] selects the KDF and its cost parameters at runtime, for applications
+//!   that store them alongside the derived data. PBKDF2 is available for
+//!   interoperability only.
+//! - [`SecretKey::from_password()`] derives a key that can be used directly with
+//!   [`aead`](crate::aead) and [`auth`](crate::auth).
+//! - [`expand()`] derives keys from a key that already has high entropy, such as a
+//!   randomly generated [`SecretKey`], using HKDF-SHA512. It is not memory-hard and must
+//!   not be used with passwords. [`generate_key()`] generates such a master key of a
+//!   chosen length.
+//! - [`seed`] derives a deterministic hierarchy of keys from a single master seed.
+//! - [`Salt`]s of any length from 8 bytes can be used, so that keys derived by existing
+//!   deployments can be derived again. [`Salt::derive_from_identifier()`] deterministically
+//!   derives a salt from an identifier, for when a random salt cannot be stored.
+//! - [`Salt`] implements `Display` and `FromStr` using base64 without padding, as in
+//!   encoded password hashes.
 //!
 //! # Note:
 //! This implementation only supports a single thread/lane.
@@ -41,10 +56,13 @@
 //! - `iterations`: Iterations cost parameter for Argon2i.
 //! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2i.
 //! - `length`: The desired length of the derived key.
+//! - `iterations` of [`Algorithm::Pbkdf2Sha256`], [`Algorithm::Pbkdf2Sha384`] and
+//!   [`Algorithm::Pbkdf2Sha512`]: Iteration count for PBKDF2.
 //!
 //! # Errors:
 //! An error will be returned if:
-//! - `iterations` is less than 3.
+//! - `iterations` is less than 3 when using Argon2i.
+//! - `iterations` is less than 1 when using PBKDF2.
 //! - `length` is less than 4.
 //! - `memory` is less than 8.
 //! - The length of the `password` is greater than [`isize::MAX`].
@@ -56,7 +74,13 @@
 //! - The salt should always be generated using a CSPRNG. [`Salt::default()`]
 //!   can be used for this, it will generate a [`Salt`] of 16 bytes.
 //! - The recommended minimum size for a salt is 16 bytes.
+//! - Salts from [`Salt::derive_from_identifier()`] are only unique across records and
+//!   deployments if the identifiers are.
 //! - The recommended minimum size for a derived key is 16 bytes.
+//! - Only use [`expand()`] with keys that have been randomly generated or derived by a
+//!   KDF. Use [`derive_key()`] for anything that might be guessed, such as a password.
+//! - Argon2i should be preferred over PBKDF2. When PBKDF2 is needed, the iteration
+//!   count should be set as high as feasible. The recommended minimum is 100000.
 //!
 //! # Example:
 //! ```rust
@@ -69,10 +93,117 @@
 //!
 //! # Ok::<(), orion::errors::UnknownCryptoError>(())
 //! ```
+//!
+//! Selecting the algorithm at runtime:
+//! ```rust
+//! use orion::kdf;
+//!
+//! let user_password = kdf::Password::from_slice(b"User password")?;
+//! let salt = kdf::Salt::default();
+//!
+//! // The algorithm and parameters stored alongside the data.
+//! let algorithm = kdf::Algorithm::Argon2i { iterations: 3, memory: 1<<16 };
+//!
+//! let derived_key = algorithm.derive_key(&user_password, &salt, 32)?;
+//! assert_eq!(derived_key, kdf::derive_key(&user_password, &salt, 3, 1<<16, 32)?);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
 //! [libsodium's docs]: https://download.libsodium.org/doc/password_hashing/default_phf#guidelines-for-choosing-the-parameters
 
 pub use super::hltypes::{Password, Salt, SecretKey};
-use crate::{errors::UnknownCryptoError, hazardous::kdf::argon2i, pwhash::MIN_ITERATIONS};
+
+pub mod seed;
+use super::MIN_ITERATIONS;
+#[cfg(feature = "serde")]
+pub use crate::serde_impls::secret_key as serde_secret_key;
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::kdf::{argon2i, hkdf, pbkdf2},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Key derivation functions and their cost parameters, that can be selected at runtime.
+pub enum Algorithm {
+    /// Argon2i, as used by [`derive_key()`].
+    Argon2i {
+        /// Iterations cost parameter.
+        iterations: u32,
+        /// Memory (in kibibytes (KiB)) cost parameter.
+        memory: u32,
+    },
+    /// PBKDF2 using HMAC-SHA256.
+    Pbkdf2Sha256 {
+        /// Iteration count.
+        iterations: u32,
+    },
+    /// PBKDF2 using HMAC-SHA384.
+    Pbkdf2Sha384 {
+        /// Iteration count.
+        iterations: u32,
+    },
+    /// PBKDF2 using HMAC-SHA512.
+    Pbkdf2Sha512 {
+        /// Iteration count.
+        iterations: u32,
+    },
+}
+
+impl Default for Algorithm {
+    /// Argon2i with 3 iterations and 64 MiB of memory.
+    fn default() -> Self {
+        Algorithm::Argon2i {
+            iterations: 3,
+            memory: 1 << 16,
+        }
+    }
+}
+
+impl Algorithm {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a key of `length` bytes using the algorithm.
+    pub fn derive_key(
+        &self,
+        password: &Password,
+        salt: &Salt,
+        length: u32,
+    ) -> Result<SecretKey, UnknownCryptoError> {
+        match *self {
+            Algorithm::Argon2i { iterations, memory } => {
+                derive_key(password, salt, iterations, memory, length)
+            }
+            Algorithm::Pbkdf2Sha256 { iterations } => {
+                let mut dk = SecretKey::from_slice(&vec![0u8; length as usize])?;
+                pbkdf2::sha256::derive_key(
+                    &pbkdf2::sha256::Password::from_slice(password.unprotected_as_bytes())?,
+                    salt.as_ref(),
+                    iterations as usize,
+                    &mut dk.value,
+                )?;
+                Ok(dk)
+            }
+            Algorithm::Pbkdf2Sha384 { iterations } => {
+                let mut dk = SecretKey::from_slice(&vec![0u8; length as usize])?;
+                pbkdf2::sha384::derive_key(
+                    &pbkdf2::sha384::Password::from_slice(password.unprotected_as_bytes())?,
+                    salt.as_ref(),
+                    iterations as usize,
+                    &mut dk.value,
+                )?;
+                Ok(dk)
+            }
+            Algorithm::Pbkdf2Sha512 { iterations } => {
+                let mut dk = SecretKey::from_slice(&vec![0u8; length as usize])?;
+                pbkdf2::sha512::derive_key(
+                    &pbkdf2::sha512::Password::from_slice(password.unprotected_as_bytes())?,
+                    salt.as_ref(),
+                    iterations as usize,
+                    &mut dk.value,
+                )?;
+                Ok(dk)
+            }
+        }
+    }
+}
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Derive a key using Argon2i.
@@ -84,7 +215,7 @@ pub fn derive_key(
     length: u32,
 ) -> Result<SecretKey, UnknownCryptoError> {
     if iterations < MIN_ITERATIONS {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
     }
 
     let mut dk = SecretKey::from_slice(&vec![0u8; length as usize])?;
@@ -102,11 +233,331 @@ pub fn derive_key(
     Ok(dk)
 }
 
+/// The maximum length of a key derived with [`expand()`].
+const MAX_EXPAND_LENGTH: u32 = 255 * 64;
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Derive a key of `length` bytes from a high-entropy `master_key`, bound to `context`,
+/// using HKDF-SHA512.
+///
+/// Unlike [`derive_key()`], this is fast. Deriving session keys or separate keys for
+/// encryption and authentication from a single master key are typical uses. Different
+/// `context`s give independent keys.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `length` is 0.
+/// - `length` is greater than `16320` (`255 * 64`).
+///
+/// # Security:
+/// - `master_key` must have high entropy. Passwords must instead be used with
+///   [`derive_key()`], which makes guessing them expensive.
+///
+/// # Example:
+/// ```rust
+/// use orion::{aead, kdf};
+///
+/// let master_key = kdf::SecretKey::default();
+///
+/// let encryption_key = kdf::expand(&master_key, b"encryption", 32)?;
+/// let ciphertext = aead::seal(&encryption_key, b"Secret message")?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn expand(
+    master_key: &SecretKey,
+    context: &[u8],
+    length: u32,
+) -> Result<SecretKey, UnknownCryptoError> {
+    if length == 0 || length > MAX_EXPAND_LENGTH {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut dk = SecretKey::from_slice(&vec![0u8; length as usize])?;
+    hkdf::sha512::derive_key(
+        &[],
+        master_key.unprotected_as_bytes(),
+        Some(context),
+        &mut dk.value,
+    )?;
+
+    Ok(dk)
+}
+
+/// The minimum length of a key generated with [`generate_key()`].
+const MIN_GENERATE_LENGTH: usize = 16;
+
+/// The maximum length of a key generated with [`generate_key()`], which is the output size
+/// of SHA-512. Longer master keys do not make [`expand()`] any stronger.
+const MAX_GENERATE_LENGTH: usize = 64;
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Randomly generate a [`SecretKey`] of `length` bytes, for use as the `master_key`
+/// of [`expand()`].
+///
+/// [`SecretKey::default()`] generates a key of 32 bytes. This is for protocols and
+/// interoperability cases that need master keys of other lengths.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `length` is less than `16` or greater than `64`.
+/// - Failure to generate random bytes securely.
+///
+/// # Example:
+/// ```rust
+/// use orion::kdf;
+///
+/// let master_key = kdf::generate_key(64)?;
+/// let session_key = kdf::expand(&master_key, b"session", 32)?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn generate_key(length: usize) -> Result<SecretKey, UnknownCryptoError> {
+    if !(MIN_GENERATE_LENGTH..=MAX_GENERATE_LENGTH).contains(&length) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    SecretKey::generate(length)
+}
+
+/// The length of a key derived with [`SecretKey::from_password()`].
+const PASSWORD_KEY_LENGTH: u32 = 32;
+
+impl SecretKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive a 32-byte `SecretKey` from `password`, using `params`.
+    ///
+    /// The key can be used with both [`aead`](crate::aead) and [`auth`](crate::auth),
+    /// which have the same `SecretKey` type. Use [`Algorithm::default()`], unless the
+    /// cost parameters have been chosen for the hardware the key is derived on.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `params` are invalid. See [`Algorithm::derive_key()`].
+    /// - The length of the `salt` is less than `8`.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use orion::{aead, kdf};
+    ///
+    /// let password = kdf::Password::from_slice(b"User password")?;
+    /// let salt = kdf::Salt::default();
+    ///
+    /// let secret_key =
+    ///     aead::SecretKey::from_password(&password, &salt, &kdf::Algorithm::default())?;
+    /// let ciphertext = aead::seal(&secret_key, b"Secret message")?;
+    /// # Ok::<(), orion::errors::UnknownCryptoError>(())
+    /// ```
+    pub fn from_password(
+        password: &Password,
+        salt: &Salt,
+        params: &Algorithm,
+    ) -> Result<Self, UnknownCryptoError> {
+        params.derive_key(password, salt, PASSWORD_KEY_LENGTH)
+    }
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 mod public {
     use super::*;
 
+    mod test_from_password {
+        use super::*;
+
+        #[test]
+        fn test_same_as_derive_key() {
+            let password = Password::from_slice(b"password").unwrap();
+            let salt = Salt::from_slice(&[1u8; 16]).unwrap();
+            let params = Algorithm::Argon2i {
+                iterations: 3,
+                memory: 1024,
+            };
+
+            let sk = SecretKey::from_password(&password, &salt, &params).unwrap();
+            assert_eq!(sk.len(), 32);
+            assert_eq!(sk, derive_key(&password, &salt, 3, 1024, 32).unwrap());
+
+            let params = Algorithm::Pbkdf2Sha512 { iterations: 1 };
+            let sk = SecretKey::from_password(&password, &salt, &params).unwrap();
+            assert_eq!(sk, params.derive_key(&password, &salt, 32).unwrap());
+        }
+
+        #[test]
+        fn test_bad_params() {
+            let password = Password::from_slice(b"password").unwrap();
+            let salt = Salt::from_slice(&[1u8; 16]).unwrap();
+            let params = Algorithm::Argon2i {
+                iterations: 2,
+                memory: 1024,
+            };
+            assert!(SecretKey::from_password(&password, &salt, &params).is_err());
+        }
+
+        #[test]
+        #[cfg(feature = "safe_api")]
+        fn test_key_usable_with_aead_and_auth() {
+            let password = Password::from_slice(b"password").unwrap();
+            let salt = Salt::from_slice(&[1u8; 16]).unwrap();
+            let params = Algorithm::Argon2i {
+                iterations: 3,
+                memory: 1024,
+            };
+            let sk = SecretKey::from_password(&password, &salt, &params).unwrap();
+
+            let ct = crate::aead::seal(&sk, b"data").unwrap();
+            assert_eq!(crate::aead::open(&sk, &ct).unwrap(), &b"data"[..]);
+            let tag = crate::auth::authenticate(&sk, b"data").unwrap();
+            assert!(crate::auth::authenticate_verify(&tag, &sk, b"data").is_ok());
+        }
+
+        #[test]
+        fn test_default_params() {
+            assert_eq!(
+                Algorithm::default(),
+                Algorithm::Argon2i {
+                    iterations: 3,
+                    memory: 1 << 16
+                }
+            );
+        }
+    }
+
+    mod test_expand {
+        use super::*;
+        use alloc::vec::Vec;
+
+        #[test]
+        fn test_expand() {
+            let master_key = SecretKey::from_slice(&(0u8..32).collect::<Vec<u8>>()).unwrap();
+            let dk = expand(&master_key, b"session key", 32).unwrap();
+
+            // Generated with the `cryptography` Python package.
+            let expected =
+                hex::decode("28a39d5e962ffafd220b31383d80744aba9007a1248ed9c37ad2f497b8ac18e0")
+                    .unwrap();
+            assert_eq!(dk.unprotected_as_bytes(), &expected[..]);
+        }
+
+        #[test]
+        fn test_expand_diff_context() {
+            let master_key = SecretKey::default();
+            assert_ne!(
+                expand(&master_key, b"encryption", 32).unwrap(),
+                expand(&master_key, b"authentication", 32).unwrap()
+            );
+            assert_eq!(
+                expand(&master_key, b"encryption", 32).unwrap(),
+                expand(&master_key, b"encryption", 32).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_expand_length() {
+            let master_key = SecretKey::default();
+            assert!(expand(&master_key, b"", 0).is_err());
+            assert!(expand(&master_key, b"", 1).is_ok());
+            assert!(expand(&master_key, b"", MAX_EXPAND_LENGTH).is_ok());
+            assert!(expand(&master_key, b"", MAX_EXPAND_LENGTH + 1).is_err());
+            assert_eq!(expand(&master_key, b"", 64).unwrap().len(), 64);
+        }
+
+        #[test]
+        fn test_generate_key() {
+            assert!(generate_key(0).is_err());
+            assert!(generate_key(MIN_GENERATE_LENGTH - 1).is_err());
+            assert!(generate_key(MAX_GENERATE_LENGTH + 1).is_err());
+
+            let short = generate_key(MIN_GENERATE_LENGTH).unwrap();
+            let long = generate_key(MAX_GENERATE_LENGTH).unwrap();
+            assert_eq!(short.len(), MIN_GENERATE_LENGTH);
+            assert_eq!(long.len(), MAX_GENERATE_LENGTH);
+            assert_ne!(
+                expand(&short, b"", 32).unwrap(),
+                expand(&long, b"", 32).unwrap()
+            );
+        }
+    }
+
+    mod test_algorithm {
+        use super::*;
+
+        #[test]
+        fn test_argon2i_same_as_derive_key() {
+            let password = Password::from_slice(&[0u8; 64]).unwrap();
+            let salt = Salt::from_slice(&[0u8; 16]).unwrap();
+            let algorithm = Algorithm::Argon2i {
+                iterations: 3,
+                memory: 1024,
+            };
+
+            assert_eq!(
+                algorithm.derive_key(&password, &salt, 32).unwrap(),
+                derive_key(&password, &salt, 3, 1024, 32).unwrap()
+            );
+            assert!(Algorithm::Argon2i {
+                iterations: 2,
+                memory: 1024
+            }
+            .derive_key(&password, &salt, 32)
+            .is_err());
+        }
+
+        #[test]
+        fn test_pbkdf2_same_as_hazardous() {
+            let password = Password::from_slice(b"password").unwrap();
+            let salt = Salt::from_slice(b"saltsalt").unwrap();
+            let mut expected = [0u8; 40];
+
+            pbkdf2::sha256::derive_key(
+                &pbkdf2::sha256::Password::from_slice(b"password").unwrap(),
+                b"saltsalt",
+                10,
+                &mut expected,
+            )
+            .unwrap();
+            let dk = Algorithm::Pbkdf2Sha256 { iterations: 10 }
+                .derive_key(&password, &salt, 40)
+                .unwrap();
+            assert_eq!(dk.unprotected_as_bytes(), &expected[..]);
+
+            pbkdf2::sha384::derive_key(
+                &pbkdf2::sha384::Password::from_slice(b"password").unwrap(),
+                b"saltsalt",
+                10,
+                &mut expected,
+            )
+            .unwrap();
+            let dk = Algorithm::Pbkdf2Sha384 { iterations: 10 }
+                .derive_key(&password, &salt, 40)
+                .unwrap();
+            assert_eq!(dk.unprotected_as_bytes(), &expected[..]);
+
+            pbkdf2::sha512::derive_key(
+                &pbkdf2::sha512::Password::from_slice(b"password").unwrap(),
+                b"saltsalt",
+                10,
+                &mut expected,
+            )
+            .unwrap();
+            let dk = Algorithm::Pbkdf2Sha512 { iterations: 10 }
+                .derive_key(&password, &salt, 40)
+                .unwrap();
+            assert_eq!(dk.unprotected_as_bytes(), &expected[..]);
+        }
+
+        #[test]
+        fn test_pbkdf2_bad_params() {
+            let password = Password::from_slice(b"password").unwrap();
+            let salt = Salt::from_slice(b"saltsalt").unwrap();
+
+            assert!(Algorithm::Pbkdf2Sha512 { iterations: 0 }
+                .derive_key(&password, &salt, 32)
+                .is_err());
+            assert!(Algorithm::Pbkdf2Sha512 { iterations: 1 }
+                .derive_key(&password, &salt, 0)
+                .is_err());
+        }
+    }
+
     mod test_derive_key_and_verify {
         use super::*;
 
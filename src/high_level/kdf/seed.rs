@@ -0,0 +1,289 @@
+// MIT License
+
+// Copyright (c) 2018-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Deterministic key hierarchy from a master seed.
+//!
+//! # Use case:
+//! `orion::kdf::seed` can be used to derive all the keys of a device or user from a
+//! single 32-byte master [`Seed`]. Only the seed has to be backed up, and every key
+//! can be derived again from it and its path.
+//!
+//! An example of this could be a device that keeps separate keys for encrypting
+//! backups and signing firmware updates, and regenerates them after a reset.
+//!
+//! # About:
+//! - Uses HKDF-SHA512 with the seed as input key material.
+//! - Each key is identified by a path of labels separated by `/`, such as
+//!   `"backup/2024/key1"`.
+//! - The kind of key is bound to the derivation too, so the same path gives independent
+//!   keys with [`derive()`], [`derive_mldsa65_keypair()`], [`derive_mlkem768_keypair()`]
+//!   and [`derive_hybrid_kem_keypair()`].
+//!
+//! # Parameters:
+//! - `seed`: The master seed.
+//! - `path`: The path of the key to derive.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `path` is empty.
+//! - `path` contains an empty label, e.g. when it starts or ends with `/` or contains `//`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`Seed::generate()`].
+//!
+//! # Security:
+//! - The seed must be generated with a CSPRNG, e.g. with [`Seed::generate()`]. It must
+//!   never be derived from a password.
+//! - Anyone with the seed can derive every key of the hierarchy. Store backups of it
+//!   with at least the care of the most valuable key.
+//! - Paths are not secret and give no protection by themselves.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{aead, kdf::seed};
+//!
+//! let master = seed::Seed::generate();
+//!
+//! let backup_key = seed::derive(&master, "backup/2024/key1")?;
+//! let ciphertext = aead::seal(&backup_key, b"Secret message")?;
+//!
+//! // After restoring the seed, the same key is derived again.
+//! let restored = seed::derive(&master, "backup/2024/key1")?;
+//! assert_eq!(aead::open(&restored, &ciphertext)?, b"Secret message");
+//!
+//! let signing_key = seed::derive_mldsa65_keypair(&master, "signing/firmware")?;
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+
+use super::SecretKey;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::kdf::hkdf;
+use crate::hazardous::kem::{hybrid, mlkem768};
+use crate::hazardous::sig::mldsa65;
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+/// The size of a master seed.
+pub const SEED_SIZE: usize = 32;
+
+/// The domain separation prefix of all keys derived from a seed.
+const SEED_CONTEXT: &[u8] = b"orion-seed";
+
+construct_secret_key! {
+    /// A type to represent the 32-byte master seed of a key hierarchy.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (Seed, test_seed, SEED_SIZE, SEED_SIZE, SEED_SIZE)
+}
+
+impl_from_trait!(Seed, SEED_SIZE);
+
+/// Derive `dst_out.len()` bytes for the key of `kind` at `path`.
+fn derive_bytes(
+    seed: &Seed,
+    kind: &[u8],
+    path: &str,
+    dst_out: &mut [u8],
+) -> Result<(), UnknownCryptoError> {
+    if path.is_empty() || path.split('/').any(|label| label.is_empty()) {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    let mut info = Vec::with_capacity(SEED_CONTEXT.len() + kind.len() + path.len() + 2);
+    info.extend_from_slice(SEED_CONTEXT);
+    info.push(0u8);
+    info.extend_from_slice(kind);
+    info.push(0u8);
+    info.extend_from_slice(path.as_bytes());
+
+    hkdf::sha512::derive_key(&[], seed.unprotected_as_bytes(), Some(&info), dst_out)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Derive the 32-byte symmetric key at `path`, which can be used with
+/// [`aead`](crate::aead) and [`auth`](crate::auth).
+pub fn derive(seed: &Seed, path: &str) -> Result<SecretKey, UnknownCryptoError> {
+    let mut key = SecretKey::from_slice(&[0u8; 32])?;
+    derive_bytes(seed, b"secret-key", path, &mut key.value)?;
+
+    Ok(key)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Derive the ML-DSA-65 signing key pair at `path`.
+pub fn derive_mldsa65_keypair(
+    seed: &Seed,
+    path: &str,
+) -> Result<mldsa65::KeyPair, UnknownCryptoError> {
+    let mut bytes = [0u8; mldsa65::MLDSA65_SECRETKEYSIZE];
+    derive_bytes(seed, b"mldsa65", path, &mut bytes)?;
+    let secret_key = mldsa65::SecretKey::from(bytes);
+    bytes.zeroize();
+
+    Ok(mldsa65::KeyPair::from(secret_key))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Derive the ML-KEM-768 key pair at `path`.
+pub fn derive_mlkem768_keypair(
+    seed: &Seed,
+    path: &str,
+) -> Result<mlkem768::KeyPair, UnknownCryptoError> {
+    let mut bytes = [0u8; mlkem768::MLKEM768_SEEDSIZE];
+    derive_bytes(seed, b"mlkem768", path, &mut bytes)?;
+    let kem_seed = mlkem768::Seed::from(bytes);
+    bytes.zeroize();
+
+    Ok(mlkem768::KeyPair::from_seed(&kem_seed))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Derive the hybrid X25519 and ML-KEM-768 key pair at `path`.
+pub fn derive_hybrid_kem_keypair(
+    seed: &Seed,
+    path: &str,
+) -> Result<hybrid::KeyPair, UnknownCryptoError> {
+    let mut bytes = [0u8; hybrid::HYBRID_DECAPSULATION_KEYSIZE];
+    derive_bytes(seed, b"hybrid-kem", path, &mut bytes)?;
+    let dk = hybrid::DecapsulationKey::from(bytes);
+    bytes.zeroize();
+
+    Ok(hybrid::KeyPair::from(dk))
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn test_seed() -> Seed {
+        let mut bytes = [0u8; SEED_SIZE];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        Seed::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_derive_known_answer() {
+        // Generated with the HKDF of the Python `cryptography` package.
+        let key = derive(&test_seed(), "backup/2024/key1").unwrap();
+        assert_eq!(
+            key.unprotected_as_bytes(),
+            &hex::decode("d7325239a6bbf5f935655aaa4c43b5c2c895fef0b4755f1c221a892939deb579")
+                .unwrap()[..]
+        );
+
+        let kp = derive_mldsa65_keypair(&test_seed(), "signing/firmware").unwrap();
+        let expected = mldsa65::SecretKey::from_slice(
+            &hex::decode("97d568199c815a90c1a43fc5c3eb89a27c37387f8fea533569c56c1f87659b24")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(kp.private(), &expected);
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let seed = test_seed();
+        assert_eq!(derive(&seed, "a/b").unwrap(), derive(&seed, "a/b").unwrap());
+        assert_eq!(
+            derive_mldsa65_keypair(&seed, "a/b").unwrap().public(),
+            derive_mldsa65_keypair(&seed, "a/b").unwrap().public()
+        );
+        assert_eq!(
+            derive_mlkem768_keypair(&seed, "a/b").unwrap().public(),
+            derive_mlkem768_keypair(&seed, "a/b").unwrap().public()
+        );
+        assert_eq!(
+            derive_hybrid_kem_keypair(&seed, "a/b").unwrap().public(),
+            derive_hybrid_kem_keypair(&seed, "a/b").unwrap().public()
+        );
+    }
+
+    #[test]
+    fn test_paths_are_independent() {
+        let seed = test_seed();
+        let key = derive(&seed, "a/b").unwrap();
+        assert_ne!(key, derive(&seed, "a/c").unwrap());
+        assert_ne!(key, derive(&seed, "a").unwrap());
+        assert_ne!(key, derive(&seed, "a/b/c").unwrap());
+        assert_ne!(key, derive(&seed, "ab").unwrap());
+
+        let other_seed = Seed::from_slice(&[1u8; SEED_SIZE]).unwrap();
+        assert_ne!(key, derive(&other_seed, "a/b").unwrap());
+    }
+
+    #[test]
+    fn test_key_kinds_are_independent() {
+        let seed = test_seed();
+        let key = derive(&seed, "a").unwrap();
+        let mldsa = derive_mldsa65_keypair(&seed, "a").unwrap();
+        assert_ne!(
+            key.unprotected_as_bytes(),
+            mldsa.private().unprotected_as_bytes()
+        );
+
+        let mut hybrid_dk = [0u8; 32];
+        derive_bytes(&seed, b"hybrid-kem", "a", &mut hybrid_dk).unwrap();
+        assert_ne!(key.unprotected_as_bytes(), &hybrid_dk[..]);
+        assert_ne!(mldsa.private().unprotected_as_bytes(), &hybrid_dk[..]);
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        let seed = test_seed();
+        for path in ["", "/", "/a", "a/", "a//b"].iter() {
+            let err = derive(&seed, path).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+            assert!(derive_mldsa65_keypair(&seed, path).is_err());
+            assert!(derive_mlkem768_keypair(&seed, path).is_err());
+            assert!(derive_hybrid_kem_keypair(&seed, path).is_err());
+        }
+        assert!(derive(&seed, "a").is_ok());
+        assert!(derive(&seed, "a b/c.d").is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_keys_are_usable() {
+        let seed = test_seed();
+        let kp = derive_mldsa65_keypair(&seed, "signing").unwrap();
+        let sig = mldsa65::sign(kp.private(), b"msg").unwrap();
+        assert!(mldsa65::verify(&sig, kp.public(), b"msg").is_ok());
+
+        let kp = derive_hybrid_kem_keypair(&seed, "kem").unwrap();
+        let (ss, ct) = hybrid::encapsulate(kp.public()).unwrap();
+        assert_eq!(hybrid::decapsulate(kp.private(), &ct).unwrap(), ss);
+
+        let kp = derive_mlkem768_keypair(&seed, "kem").unwrap();
+        let (ss, ct) = mlkem768::encapsulate(kp.public()).unwrap();
+        assert_eq!(mlkem768::decapsulate(kp.private(), &ct).unwrap(), ss);
+    }
+}
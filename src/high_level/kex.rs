@@ -0,0 +1,351 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Key exchange.
+//!
+//! # Use case:
+//! `orion::kex` can be used by a client and a server, that each have an X25519 key pair,
+//! to derive a pair of session keys from the other party's public key. The session keys
+//! can be used directly with [`orion::aead`](crate::aead).
+//!
+//! # About:
+//! - The key exchange is compatible with [`crypto_kx`] of libsodium, so one side may use
+//!   libsodium and the other orion.
+//! - The shared secret `q` is computed with X25519 and both session keys with
+//!   BLAKE2b-512(`q` || `client_pk` || `server_pk`). The first 32 bytes are the key the
+//!   client receives with and the server transmits with, the last 32 bytes the key the
+//!   client transmits with and the server receives with.
+//!
+//! # Parameters:
+//! - `client_keypair`: The client's key pair.
+//! - `server_keypair`: The server's key pair.
+//! - `client_pk`: The client's public key.
+//! - `server_pk`: The server's public key.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of a key passed to `from_slice()` is not 32 bytes.
+//! - The public key of the other party is a low-order point, so that the shared
+//!   secret is all zero.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`KeyPair::generate()`]
+//!   or [`SecretKey::generate()`].
+//!
+//! # Security:
+//! - The key exchange does not authenticate either party. The public key of the other
+//!   party must be obtained from a trusted source.
+//! - The session keys are the same for every key exchange between the same two key pairs.
+//!   Since [`orion::aead`](crate::aead) uses random nonces, they may still be used for many
+//!   messages, but ephemeral key pairs should be used where forward secrecy is required.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{aead, kex::{KeyPair, SessionKeys}};
+//!
+//! let client = KeyPair::generate();
+//! let server = KeyPair::generate();
+//!
+//! let client_keys = SessionKeys::derive(&client, server.public())?;
+//! let server_keys = SessionKeys::derive_server(&server, client.public())?;
+//!
+//! let ciphertext = aead::seal(client_keys.transmitting(), b"Hello server")?;
+//! let plaintext = aead::open(server_keys.receiving(), &ciphertext)?;
+//! assert_eq!(plaintext, b"Hello server");
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`crypto_kx`]: https://doc.libsodium.org/key_exchange
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::ecc::x25519::{scalarmult, scalarmult_base, X25519_SIZE};
+use crate::hazardous::hash::blake2b::Blake2b;
+use crate::high_level::hltypes;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// The size of a public or secret key.
+pub const KEX_KEYSIZE: usize = X25519_SIZE;
+
+/// The size of each of the session keys.
+pub const KEX_SESSIONKEYSIZE: usize = 32;
+
+construct_secret_key! {
+    /// A type to represent the X25519 `SecretKey` of a key pair.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (SecretKey, test_secret_key, KEX_KEYSIZE, KEX_KEYSIZE, KEX_KEYSIZE)
+}
+
+impl_from_trait!(SecretKey, KEX_KEYSIZE);
+
+impl SecretKey {
+    /// Return the [`PublicKey`] that belongs to this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from(scalarmult_base(&self.value))
+    }
+}
+
+construct_public! {
+    /// A type to represent the X25519 `PublicKey` of a key pair.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (PublicKey, test_public_key, KEX_KEYSIZE, KEX_KEYSIZE)
+}
+
+impl_from_trait!(PublicKey, KEX_KEYSIZE);
+
+/// A key pair, containing a [`PublicKey`] and a [`SecretKey`].
+pub struct KeyPair {
+    public: PublicKey,
+    private: SecretKey,
+}
+
+impl KeyPair {
+    /// Randomly generate a key pair using a CSPRNG.
+    pub fn generate() -> Self {
+        Self::from(SecretKey::generate())
+    }
+
+    /// Return the [`PublicKey`].
+    pub fn public(&self) -> &PublicKey {
+        &self.public
+    }
+
+    /// Return the [`SecretKey`].
+    pub fn private(&self) -> &SecretKey {
+        &self.private
+    }
+}
+
+impl From<SecretKey> for KeyPair {
+    fn from(sk: SecretKey) -> Self {
+        Self {
+            public: sk.public_key(),
+            private: sk,
+        }
+    }
+}
+
+/// The pair of session keys that one side derives, one for receiving and one for
+/// transmitting data. The keys of the client and the server are swapped, so the key
+/// one side transmits with is the key the other side receives with.
+pub struct SessionKeys {
+    receiving: hltypes::SecretKey,
+    transmitting: hltypes::SecretKey,
+}
+
+impl SessionKeys {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive the session keys of the client, as with `crypto_kx_client_session_keys()`.
+    pub fn derive(
+        client_keypair: &KeyPair,
+        server_pk: &PublicKey,
+    ) -> Result<Self, UnknownCryptoError> {
+        let mut keys = derive_keys(
+            client_keypair.private(),
+            client_keypair.public(),
+            server_pk,
+            server_pk,
+        )?;
+        let ret = Self::from_halves(&keys[..KEX_SESSIONKEYSIZE], &keys[KEX_SESSIONKEYSIZE..]);
+        keys.zeroize();
+
+        ret
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Derive the session keys of the server, as with `crypto_kx_server_session_keys()`.
+    pub fn derive_server(
+        server_keypair: &KeyPair,
+        client_pk: &PublicKey,
+    ) -> Result<Self, UnknownCryptoError> {
+        let mut keys = derive_keys(
+            server_keypair.private(),
+            client_pk,
+            server_keypair.public(),
+            client_pk,
+        )?;
+        let ret = Self::from_halves(&keys[KEX_SESSIONKEYSIZE..], &keys[..KEX_SESSIONKEYSIZE]);
+        keys.zeroize();
+
+        ret
+    }
+
+    /// Return the [`aead::SecretKey`](crate::aead::SecretKey) to decrypt data received
+    /// from the other side with.
+    pub fn receiving(&self) -> &hltypes::SecretKey {
+        &self.receiving
+    }
+
+    /// Return the [`aead::SecretKey`](crate::aead::SecretKey) to encrypt data transmitted
+    /// to the other side with.
+    pub fn transmitting(&self) -> &hltypes::SecretKey {
+        &self.transmitting
+    }
+
+    fn from_halves(receiving: &[u8], transmitting: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Ok(Self {
+            receiving: hltypes::SecretKey::from_slice(receiving)?,
+            transmitting: hltypes::SecretKey::from_slice(transmitting)?,
+        })
+    }
+}
+
+impl core::fmt::Debug for SessionKeys {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SessionKeys {{***OMITTED***}}")
+    }
+}
+
+/// Compute BLAKE2b-512(X25519(`sk`, `other_pk`) || `client_pk` || `server_pk`).
+fn derive_keys(
+    sk: &SecretKey,
+    client_pk: &PublicKey,
+    server_pk: &PublicKey,
+    other_pk: &PublicKey,
+) -> Result<[u8; 2 * KEX_SESSIONKEYSIZE], UnknownCryptoError> {
+    let mut q = scalarmult(&sk.value, &other_pk.value);
+    if bool::from(q.ct_eq(&[0u8; X25519_SIZE])) {
+        q.zeroize();
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    let mut ctx = Blake2b::new(None, 2 * KEX_SESSIONKEYSIZE)?;
+    ctx.update(&q)?;
+    q.zeroize();
+    ctx.update(client_pk.as_ref())?;
+    ctx.update(server_pk.as_ref())?;
+
+    let mut keys = [0u8; 2 * KEX_SESSIONKEYSIZE];
+    keys.copy_from_slice(ctx.finalize()?.as_ref());
+
+    Ok(keys)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn test_keypairs() -> (KeyPair, KeyPair) {
+        let mut client_sk = [0u8; KEX_KEYSIZE];
+        let mut server_sk = [0u8; KEX_KEYSIZE];
+        for i in 0..KEX_KEYSIZE {
+            client_sk[i] = i as u8;
+            server_sk[i] = (i + 32) as u8;
+        }
+
+        (
+            KeyPair::from(SecretKey::from(client_sk)),
+            KeyPair::from(SecretKey::from(server_sk)),
+        )
+    }
+
+    #[test]
+    // Generated with libsodium 1.0.18 `crypto_kx_client_session_keys()`.
+    fn test_libsodium_compatibility() {
+        let (client, server) = test_keypairs();
+        assert_eq!(
+            hex::encode(client.public()),
+            "8f40c5adb68f25624ae5b214ea767a6ec94d829d3d7b5e1ad1ba6f3e2138285f"
+        );
+        assert_eq!(
+            hex::encode(server.public()),
+            "358072d6365880d1aeea329adf9121383851ed21a28e3b75e965d0d2cd166254"
+        );
+
+        let keys = SessionKeys::derive(&client, server.public()).unwrap();
+        assert_eq!(
+            keys.receiving().unprotected_as_bytes(),
+            &hex::decode("09fcadb630f490a255a9461619a3a32586c5ec11be9a584832de0aad99099e02")
+                .unwrap()[..]
+        );
+        assert_eq!(
+            keys.transmitting().unprotected_as_bytes(),
+            &hex::decode("a1c994d365e824f318de66626a225b70a6f3be5b0febe8638882fd8a20d0bf0c")
+                .unwrap()[..]
+        );
+    }
+
+    #[test]
+    fn test_client_and_server_agree() {
+        let (client, server) = test_keypairs();
+        let client_keys = SessionKeys::derive(&client, server.public()).unwrap();
+        let server_keys = SessionKeys::derive_server(&server, client.public()).unwrap();
+
+        assert_eq!(client_keys.receiving(), server_keys.transmitting());
+        assert_eq!(client_keys.transmitting(), server_keys.receiving());
+        assert_ne!(client_keys.receiving(), client_keys.transmitting());
+    }
+
+    #[test]
+    fn test_generated_keypairs_agree() {
+        let client = KeyPair::generate();
+        let server = KeyPair::generate();
+        let client_keys = SessionKeys::derive(&client, server.public()).unwrap();
+        let server_keys = SessionKeys::derive_server(&server, client.public()).unwrap();
+
+        let ciphertext = crate::aead::seal(client_keys.transmitting(), b"msg").unwrap();
+        assert_eq!(
+            crate::aead::open(server_keys.receiving(), &ciphertext).unwrap(),
+            b"msg"
+        );
+    }
+
+    #[test]
+    fn test_low_order_public_key() {
+        let (client, server) = test_keypairs();
+        let zero = PublicKey::from([0u8; KEX_KEYSIZE]);
+
+        assert_eq!(
+            SessionKeys::derive(&client, &zero).unwrap_err().kind(),
+            ErrorKind::InvalidParameter
+        );
+        assert!(SessionKeys::derive_server(&server, &zero).is_err());
+    }
+
+    #[test]
+    fn test_other_keypair_disagrees() {
+        let (client, server) = test_keypairs();
+        let other = KeyPair::from(SecretKey::from([7u8; KEX_KEYSIZE]));
+        let client_keys = SessionKeys::derive(&client, server.public()).unwrap();
+        let other_keys = SessionKeys::derive_server(&other, client.public()).unwrap();
+
+        assert_ne!(client_keys.transmitting(), other_keys.receiving());
+    }
+
+    #[test]
+    fn test_debug_omits_keys() {
+        let (client, server) = test_keypairs();
+        let keys = SessionKeys::derive(&client, server.public()).unwrap();
+        assert_eq!(format!("{:?}", keys), "SessionKeys {***OMITTED***}");
+    }
+}
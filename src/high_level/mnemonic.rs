@@ -0,0 +1,423 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Word phrases for backing up keys.
+//!
+//! # Use case:
+//! `orion::mnemonic` can be used to write down a 32-byte [`SecretKey`] or [`Seed`] as
+//! 24 words, so that it can be backed up on paper and typed in again later.
+//!
+//! An example of this could be printing a recovery sheet for the master seed of a
+//! device, from which all its keys are derived with [`kdf::seed`](crate::kdf::seed).
+//!
+//! # About:
+//! - Uses the BIP39 English wordlist of 2048 words. Each word encodes 11 bits.
+//! - The 256 bits of the key are followed by an 8-bit checksum, which is the first byte
+//!   of the SHA-256 digest of the key. This gives 264 bits, or 24 words.
+//! - The phrase of a key is the same as the BIP39 mnemonic of 256 bits of entropy, so
+//!   it can be checked with other BIP39 tools. The BIP39 seed derivation with PBKDF2
+//!   and a passphrase is __not__ applied: decoding the phrase gives the key itself.
+//! - When decoding, words may be separated by any ASCII whitespace and are
+//!   case-insensitive.
+//!
+//! # Parameters:
+//! - `secret_key`: The key to encode.
+//! - `seed`: The seed to encode.
+//! - `phrase`: The phrase to decode.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - The length of `secret_key` is not 32 bytes.
+//! - `phrase` does not consist of exactly 24 words.
+//! - `phrase` contains a word which is not in the wordlist.
+//! - The checksum of `phrase` is invalid.
+//!
+//! # Security:
+//! - Anyone who sees the phrase has the key. Store it with the same care as the key.
+//! - The checksum only detects mistakes made when writing down or typing in a phrase.
+//!   It does not protect the phrase from being modified on purpose.
+//! - The [`Phrase`] returned when encoding and all intermediate values are zeroized
+//!   when no longer needed. Copies made from [`Phrase::unprotected_as_str()`], and the
+//!   string that a phrase is decoded from, are not. Words are looked up in
+//!   constant time, apart from their length.
+//!
+//! # Example:
+//! ```rust
+//! use orion::{kdf::seed::Seed, mnemonic};
+//!
+//! let master = Seed::generate();
+//! let phrase = mnemonic::encode_seed(&master)?;
+//! assert_eq!(phrase.unprotected_as_str().split(' ').count(), 24);
+//!
+//! let restored = mnemonic::decode_seed(phrase.unprotected_as_str())?;
+//! assert_eq!(restored, master);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+
+mod wordlist;
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::sha2::sha256::Sha256;
+use crate::high_level::kdf::seed::{Seed, SEED_SIZE};
+use crate::high_level::kdf::SecretKey;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+use wordlist::WORDLIST;
+use zeroize::Zeroize;
+
+/// The amount of bytes encoded by a phrase.
+const ENTROPY_SIZE: usize = 32;
+/// The amount of words in a phrase.
+const WORD_COUNT: usize = 24;
+/// The maximum length of a word in the wordlist.
+pub(crate) const MAX_WORD_LEN: usize = 8;
+/// The maximum length of a phrase, with words separated by a single space.
+const MAX_PHRASE_LEN: usize = WORD_COUNT * (MAX_WORD_LEN + 1) - 1;
+
+/// A phrase of 24 words, which is zeroized when dropped.
+///
+/// # Security:
+/// - __**Avoid using**__ `unprotected_as_str()` whenever possible, as it breaks all
+///   protections that the type implements. Copies made from the returned string are
+///   not zeroized.
+pub struct Phrase {
+    value: String,
+}
+
+impl_omitted_debug_trait!(Phrase);
+
+impl Drop for Phrase {
+    fn drop(&mut self) {
+        self.value.zeroize();
+    }
+}
+
+impl Phrase {
+    #[inline]
+    /// Return the phrase, with words separated by a single space.
+    /// __**Warning**__: Should not be used unless strictly needed. This __**breaks protections**__
+    /// that the type implements.
+    pub fn unprotected_as_str(&self) -> &str {
+        self.value.as_str()
+    }
+}
+
+/// Compute the checksum of `entropy`.
+fn checksum(entropy: &[u8]) -> Result<u8, UnknownCryptoError> {
+    Ok(Sha256::digest(entropy)?.as_ref()[0])
+}
+
+/// Read the 11-bit index of the word at `position` from `buf`.
+fn read_index(buf: &[u8; ENTROPY_SIZE + 2], position: usize) -> u16 {
+    let bit = position * 11;
+    let byte = bit / 8;
+    let bits =
+        (u32::from(buf[byte]) << 16) | (u32::from(buf[byte + 1]) << 8) | u32::from(buf[byte + 2]);
+
+    ((bits >> (13 - (bit % 8))) & 0x7ff) as u16
+}
+
+/// Write the 11-bit `index` of the word at `position` to `buf`.
+fn write_index(buf: &mut [u8; ENTROPY_SIZE + 2], position: usize, index: u16) {
+    let bit = position * 11;
+    let byte = bit / 8;
+    let bits = (u32::from(index) & 0x7ff) << (13 - (bit % 8));
+
+    buf[byte] |= (bits >> 16) as u8;
+    buf[byte + 1] |= (bits >> 8) as u8;
+    buf[byte + 2] |= bits as u8;
+}
+
+/// Pad `word` with zeroes, and append its length. Words longer than [`MAX_WORD_LEN`]
+/// are truncated, and must be rejected by the caller.
+fn pad_word(word: &[u8]) -> [u8; MAX_WORD_LEN + 1] {
+    let mut padded = [0u8; MAX_WORD_LEN + 1];
+    for (dst, src) in padded.iter_mut().zip(word.iter()) {
+        *dst = src.to_ascii_lowercase();
+    }
+    padded[MAX_WORD_LEN] = word.len() as u8;
+
+    padded
+}
+
+/// Get the padded word of `index`, without the position of the word in the wordlist
+/// depending on `index` in the memory access pattern.
+pub(crate) fn word_of(index: u16) -> [u8; MAX_WORD_LEN + 1] {
+    let mut word = [0u8; MAX_WORD_LEN + 1];
+    for (i, candidate) in WORDLIST.iter().enumerate() {
+        let candidate = pad_word(candidate.as_bytes());
+        let is_index = (i as u16).ct_eq(&index);
+        for (dst, src) in word.iter_mut().zip(candidate.iter()) {
+            dst.conditional_assign(src, is_index);
+        }
+    }
+
+    word
+}
+
+/// Get the index of `word`, comparing it with every word in the wordlist.
+fn index_of(word: &str) -> (u16, Choice) {
+    if word.len() > MAX_WORD_LEN {
+        return (0, Choice::from(0));
+    }
+
+    let mut padded = pad_word(word.as_bytes());
+    let mut index = 0u16;
+    let mut found = Choice::from(0);
+    for (i, candidate) in WORDLIST.iter().enumerate() {
+        let is_word = pad_word(candidate.as_bytes()).ct_eq(&padded);
+        index.conditional_assign(&(i as u16), is_word);
+        found |= is_word;
+    }
+    padded.zeroize();
+
+    (index, found)
+}
+
+/// Encode `entropy` as a phrase.
+fn encode(entropy: &[u8]) -> Result<Phrase, UnknownCryptoError> {
+    if entropy.len() != ENTROPY_SIZE {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+
+    let mut buf = [0u8; ENTROPY_SIZE + 2];
+    buf[..ENTROPY_SIZE].copy_from_slice(entropy);
+    buf[ENTROPY_SIZE] = checksum(entropy)?;
+
+    // Allocated once, so that no copies of the phrase are left behind by reallocations.
+    let mut phrase = Phrase {
+        value: String::with_capacity(MAX_PHRASE_LEN),
+    };
+    for position in 0..WORD_COUNT {
+        if position > 0 {
+            phrase.value.push(' ');
+        }
+        let mut word = word_of(read_index(&buf, position));
+        for byte in word[..usize::from(word[MAX_WORD_LEN])].iter() {
+            phrase.value.push(char::from(*byte));
+        }
+        word.zeroize();
+    }
+    buf.zeroize();
+
+    Ok(phrase)
+}
+
+/// Decode `phrase` into `dst_out`.
+fn decode(phrase: &str, dst_out: &mut [u8; ENTROPY_SIZE]) -> Result<(), UnknownCryptoError> {
+    let mut buf = [0u8; ENTROPY_SIZE + 2];
+    let mut count = 0usize;
+    let mut is_valid = Choice::from(1);
+
+    for word in phrase.split_ascii_whitespace() {
+        if count == WORD_COUNT {
+            buf.zeroize();
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+        let (index, found) = index_of(word);
+        write_index(&mut buf, count, index);
+        is_valid &= found;
+        count += 1;
+    }
+
+    is_valid &= Choice::from((count == WORD_COUNT) as u8);
+    is_valid &= checksum(&buf[..ENTROPY_SIZE])?.ct_eq(&buf[ENTROPY_SIZE]);
+
+    if is_valid.into() {
+        dst_out.copy_from_slice(&buf[..ENTROPY_SIZE]);
+        buf.zeroize();
+        Ok(())
+    } else {
+        buf.zeroize();
+        Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding))
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encode a 32-byte `secret_key` as a phrase.
+pub fn encode_secret_key(secret_key: &SecretKey) -> Result<Phrase, UnknownCryptoError> {
+    encode(secret_key.unprotected_as_bytes())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decode a `phrase` into a 32-byte [`SecretKey`].
+pub fn decode_secret_key(phrase: &str) -> Result<SecretKey, UnknownCryptoError> {
+    let mut bytes = [0u8; ENTROPY_SIZE];
+    decode(phrase, &mut bytes)?;
+    let secret_key = SecretKey::from_slice(&bytes);
+    bytes.zeroize();
+
+    secret_key
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encode `seed` as a phrase.
+pub fn encode_seed(seed: &Seed) -> Result<Phrase, UnknownCryptoError> {
+    encode(seed.unprotected_as_bytes())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decode a `phrase` into a [`Seed`].
+pub fn decode_seed(phrase: &str) -> Result<Seed, UnknownCryptoError> {
+    let mut bytes = [0u8; SEED_SIZE];
+    decode(phrase, &mut bytes)?;
+    let seed = Seed::from_slice(&bytes);
+    bytes.zeroize();
+
+    seed
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    // Test vectors for 256 bits of entropy from
+    // https://github.com/trezor/python-mnemonic/blob/master/vectors.json.
+    const VECTORS: [(&str, &str); 5] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+             abandon abandon abandon art",
+        ),
+        (
+            "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+            "legal winner thank year wave sausage worth useful legal winner thank year wave \
+             sausage worth useful legal winner thank year wave sausage worth title",
+        ),
+        (
+            "8080808080808080808080808080808080808080808080808080808080808080",
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd \
+             amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+        ),
+        (
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo \
+             zoo zoo vote",
+        ),
+        (
+            "68a79eaca2324873eacc50cb9c6eca8cc68ea5d936f98787c60c7ebc74e6ce7c",
+            "hamster diagram private dutch cause delay private meat slide toddler razor book \
+             happy fancy gospel tennis maple dilemma loan word shrug inflict delay length",
+        ),
+    ];
+
+    #[test]
+    fn test_vectors() {
+        for (entropy, expected) in VECTORS.iter() {
+            let secret_key = SecretKey::from_slice(&hex::decode(entropy).unwrap()).unwrap();
+            let phrase = encode_secret_key(&secret_key).unwrap();
+            assert_eq!(phrase.unprotected_as_str(), *expected);
+            assert_eq!(decode_secret_key(expected).unwrap(), secret_key);
+
+            let seed = Seed::from_slice(&hex::decode(entropy).unwrap()).unwrap();
+            assert_eq!(encode_seed(&seed).unwrap().unprotected_as_str(), *expected);
+            assert_eq!(decode_seed(expected).unwrap(), seed);
+        }
+    }
+
+    #[test]
+    fn test_wordlist() {
+        assert!(WORDLIST.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(WORDLIST
+            .iter()
+            .all(|word| word.len() >= 3 && word.len() <= MAX_WORD_LEN));
+        assert_eq!(WORDLIST[0], "abandon");
+        assert_eq!(WORDLIST[2047], "zoo");
+    }
+
+    #[test]
+    fn test_lookup() {
+        for (i, word) in WORDLIST.iter().enumerate() {
+            let (index, found) = index_of(word);
+            assert!(bool::from(found));
+            assert_eq!(usize::from(index), i);
+            assert_eq!(&pad_word(word.as_bytes()), &word_of(index));
+        }
+        assert!(!bool::from(index_of("abando").1));
+        assert!(!bool::from(index_of("abandons").1));
+        assert!(!bool::from(index_of("abandon\0").1));
+        assert!(!bool::from(index_of("zoozoozoo").1));
+        assert!(!bool::from(index_of("").1));
+    }
+
+    #[test]
+    fn test_secret_key_length() {
+        let err = encode_secret_key(&SecretKey::from_slice(&[0u8; 31]).unwrap()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidLength);
+        assert!(encode_secret_key(&SecretKey::from_slice(&[0u8; 64]).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_whitespace_and_case() {
+        let (_, expected) = VECTORS[4];
+        let secret_key = decode_secret_key(expected).unwrap();
+
+        let uppercase = expected.to_ascii_uppercase();
+        assert_eq!(decode_secret_key(&uppercase).unwrap(), secret_key);
+        let spaced = format!("  {}\n", expected.replace(' ', " \t\n "));
+        assert_eq!(decode_secret_key(&spaced).unwrap(), secret_key);
+    }
+
+    #[test]
+    fn test_invalid_phrases() {
+        let (_, expected) = VECTORS[4];
+        let words: Vec<&str> = expected.split(' ').collect();
+
+        let mut invalid = vec![
+            String::new(),
+            words[..23].join(" "),
+            format!("{} zoo", expected),
+            // A word that is not in the wordlist.
+            expected.replace("hamster", "hamstr"),
+            // Two words swapped, or one word replaced, changes the checksum.
+            expected.replacen("diagram private", "private diagram", 1),
+            expected.replace("length", "legal"),
+        ];
+        // A single word written as two.
+        invalid.push(expected.replace("hamster", "ham ster"));
+
+        for phrase in invalid.iter() {
+            let err = decode_secret_key(phrase).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+            assert!(decode_seed(phrase).is_err());
+        }
+    }
+
+    #[test]
+    fn test_phrase_debug() {
+        let phrase = encode_seed(&Seed::generate()).unwrap();
+        assert!(!format!("{:?}", phrase).contains(phrase.unprotected_as_str()));
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_encode_decode(bytes: Vec<u8>) -> bool {
+        let mut entropy = [0u8; ENTROPY_SIZE];
+        for (dst, src) in entropy.iter_mut().zip(bytes.iter()) {
+            *dst = *src;
+        }
+        let seed = Seed::from_slice(&entropy).unwrap();
+        let phrase = encode_seed(&seed).unwrap();
+
+        decode_seed(phrase.unprotected_as_str()).unwrap() == seed
+    }
+}
@@ -20,9 +20,49 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 pub mod aead;
+#[cfg(feature = "safe_api")]
 pub mod auth;
+#[cfg(feature = "safe_api")]
+pub mod channel;
+#[cfg(feature = "safe_api")]
+pub mod compat;
+#[cfg(feature = "safe_api")]
+pub mod envelope;
+#[cfg(feature = "safe_api")]
+pub mod file;
 pub mod hash;
-mod hltypes;
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+pub(crate) mod hltypes;
+#[cfg(feature = "safe_api")]
+pub mod jwt;
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 pub mod kdf;
+#[cfg(feature = "safe_api")]
+pub mod kex;
+#[cfg(feature = "safe_api")]
+pub mod mnemonic;
+#[cfg(feature = "safe_api")]
+mod protected;
+#[cfg(feature = "safe_api")]
 pub mod pwhash;
+#[cfg(feature = "safe_api")]
+pub mod rng;
+#[cfg(feature = "safe_api")]
+pub mod token;
+#[cfg(feature = "safe_api")]
+pub mod vault;
+
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+/// Minimum amount of Argon2i iterations.
+pub(crate) const MIN_ITERATIONS: u32 = 3;
+
+#[cfg(feature = "safe_api")]
+/// The current system time, in seconds since the Unix epoch.
+pub(crate) fn unix_time() -> Result<u64, crate::errors::UnknownCryptoError> {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .map_err(|_| crate::errors::UnknownCryptoError::new(crate::errors::ErrorKind::InvalidState))
+}
@@ -0,0 +1,245 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Password-protected export and import of [`SecretKey`].
+//!
+//! The container consists of:
+//! - The magic bytes `orionkey`.
+//! - A version byte, which is currently 1.
+//! - The Argon2i iterations and memory as `u32` little-endian.
+//! - A salt of 16 bytes.
+//! - The secret key encrypted with XChaCha20Poly1305 under a key derived with Argon2i, with
+//!   all the preceding fields as additional data.
+
+use super::aead::{open_into_with_ad, seal_into_with_ad};
+use super::hltypes::{Password, Salt, SecretKey};
+use super::kdf;
+use super::vault::{check_params, DEFAULT_ITERATIONS, DEFAULT_MEMORY};
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::mac::poly1305::POLY1305_OUTSIZE;
+use crate::hazardous::stream::xchacha20::XCHACHA_NONCESIZE;
+use zeroize::Zeroize;
+
+/// The magic bytes at the start of an exported key.
+const MAGIC: &[u8; 8] = b"orionkey";
+/// The current version of the container.
+const VERSION: u8 = 1;
+/// The length of the salt.
+const SALT_SIZE: usize = 16;
+/// The length of the header preceding the encrypted key.
+const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + 4 + SALT_SIZE;
+
+impl SecretKey {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Encrypt this key with a key derived from `password`, for writing it to disk or
+    /// moving it to another machine. The key can be recovered with [`import_protected()`].
+    ///
+    /// The key is derived with Argon2i, using 3 iterations and 64 MiB of memory.
+    ///
+    /// # Security:
+    /// - The protection is only as strong as `password`. Use a long, randomly generated
+    ///   password whenever possible.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use orion::aead::SecretKey;
+    /// use orion::kdf::Password;
+    ///
+    /// let secret_key = SecretKey::default();
+    /// let password = Password::from_slice(b"Secret password")?;
+    ///
+    /// let exported = secret_key.export_protected(&password)?;
+    /// assert_eq!(SecretKey::import_protected(&password, &exported)?, secret_key);
+    /// # Ok::<(), orion::errors::UnknownCryptoError>(())
+    /// ```
+    /// [`import_protected()`]: SecretKey::import_protected
+    pub fn export_protected(&self, password: &Password) -> Result<Vec<u8>, UnknownCryptoError> {
+        self.export_protected_with_params(password, DEFAULT_ITERATIONS, DEFAULT_MEMORY)
+    }
+
+    /// Implementation of [`SecretKey::export_protected()`] with the Argon2i parameters
+    /// specified, so that tests need not use the default memory cost.
+    fn export_protected_with_params(
+        &self,
+        password: &Password,
+        iterations: u32,
+        memory: u32,
+    ) -> Result<Vec<u8>, UnknownCryptoError> {
+        check_params(iterations, memory)?;
+        let salt = Salt::generate(SALT_SIZE)?;
+        let wrapping_key = kdf::derive_key(password, &salt, iterations, memory, 32)?;
+
+        let mut exported =
+            vec![0u8; HEADER_SIZE + XCHACHA_NONCESIZE + self.len() + POLY1305_OUTSIZE];
+        exported[..MAGIC.len()].copy_from_slice(MAGIC);
+        exported[MAGIC.len()] = VERSION;
+        let params = &mut exported[MAGIC.len() + 1..HEADER_SIZE];
+        params[..4].copy_from_slice(&iterations.to_le_bytes());
+        params[4..8].copy_from_slice(&memory.to_le_bytes());
+        params[8..].copy_from_slice(salt.as_ref());
+
+        let (header, sealed) = exported.split_at_mut(HEADER_SIZE);
+        seal_into_with_ad(
+            &wrapping_key,
+            self.unprotected_as_bytes(),
+            Some(header),
+            sealed,
+        )?;
+
+        Ok(exported)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Decrypt a key exported with [`export_protected()`], using `password`.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `exported` is not a key exported with [`export_protected()`], or its Argon2i
+    ///   parameters are outside of the range accepted by [`orion::vault`]. The
+    ///   [`ErrorKind`] is then `InvalidEncoding`.
+    /// - `password` is not the one used to export the key, or `exported` has been
+    ///   modified. The [`ErrorKind`] is then `MacMismatch`.
+    ///
+    /// # Security:
+    /// - The Argon2i parameters are read from `exported` before they can be authenticated,
+    ///   so importing a modified key can take up to the time and memory that
+    ///   [`orion::vault`] allows.
+    ///
+    /// [`export_protected()`]: SecretKey::export_protected
+    /// [`orion::vault`]: crate::vault
+    pub fn import_protected(
+        password: &Password,
+        exported: &[u8],
+    ) -> Result<Self, UnknownCryptoError> {
+        if exported.len() <= HEADER_SIZE + XCHACHA_NONCESIZE + POLY1305_OUTSIZE
+            || &exported[..MAGIC.len()] != MAGIC
+            || exported[MAGIC.len()] != VERSION
+        {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let (header, sealed) = exported.split_at(HEADER_SIZE);
+        let params = &header[MAGIC.len() + 1..];
+        let mut iterations = [0u8; 4];
+        iterations.copy_from_slice(&params[..4]);
+        let iterations = u32::from_le_bytes(iterations);
+        let mut memory = [0u8; 4];
+        memory.copy_from_slice(&params[4..8]);
+        let memory = u32::from_le_bytes(memory);
+        if check_params(iterations, memory).is_err() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let salt = Salt::from_slice(&params[8..])?;
+        let wrapping_key = kdf::derive_key(password, &salt, iterations, memory, 32)?;
+        let mut key = vec![0u8; sealed.len() - (XCHACHA_NONCESIZE + POLY1305_OUTSIZE)];
+        let imported = open_into_with_ad(&wrapping_key, sealed, Some(header), &mut key)
+            .and_then(|_| Self::from_slice(&key));
+        key.zeroize();
+
+        imported
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn password() -> Password {
+        Password::from_slice(b"Secret password").unwrap()
+    }
+
+    fn export(secret_key: &SecretKey) -> Vec<u8> {
+        secret_key
+            .export_protected_with_params(&password(), 3, 8)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for len in [1, 32, 64, 100].iter() {
+            let secret_key = SecretKey::generate(*len).unwrap();
+            let exported = export(&secret_key);
+            assert_eq!(
+                exported.len(),
+                HEADER_SIZE + XCHACHA_NONCESIZE + len + POLY1305_OUTSIZE
+            );
+            let imported = SecretKey::import_protected(&password(), &exported).unwrap();
+            assert_eq!(imported, secret_key);
+        }
+    }
+
+    #[test]
+    fn test_export_is_randomized() {
+        let secret_key = SecretKey::default();
+        assert_ne!(export(&secret_key), export(&secret_key));
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let exported = export(&SecretKey::default());
+        let wrong = Password::from_slice(b"Wrong password").unwrap();
+        let err = SecretKey::import_protected(&wrong, &exported).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MacMismatch);
+    }
+
+    #[test]
+    fn test_modified() {
+        let exported = export(&SecretKey::default());
+        for i in 0..exported.len() {
+            let mut modified = exported.clone();
+            modified[i] ^= 1;
+            assert!(SecretKey::import_protected(&password(), &modified).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_container() {
+        let exported = export(&SecretKey::default());
+        for len in 0..=HEADER_SIZE + XCHACHA_NONCESIZE + POLY1305_OUTSIZE {
+            let err = SecretKey::import_protected(&password(), &exported[..len]).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+        }
+
+        let mut wrong_version = exported.clone();
+        wrong_version[MAGIC.len()] = VERSION + 1;
+        let err = SecretKey::import_protected(&password(), &wrong_version).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+        let mut huge_memory = exported;
+        huge_memory[MAGIC.len() + 5..MAGIC.len() + 9].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = SecretKey::import_protected(&password(), &huge_memory).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let secret_key = SecretKey::default();
+        assert!(secret_key
+            .export_protected_with_params(&password(), 2, 8)
+            .is_err());
+        assert!(secret_key
+            .export_protected_with_params(&password(), 3, 7)
+            .is_err());
+    }
+}
@@ -40,6 +40,16 @@
 //! The encoding specifies the settings used to hash the password.
 //! - [`PasswordHash::unprotected_as_bytes()`] returns only the hashed password in raw bytes.
 //!
+//! `Display` and `FromStr` of [`PasswordHash`] use the same encoded form, so that password
+//! hashes can be written to and read from e.g. configuration files directly.
+//!
+//! The parameters a [`PasswordHash`] was produced with are available through
+//! [`PasswordHash::iterations()`], [`PasswordHash::memory_kib()`], [`PasswordHash::lanes()`],
+//! [`PasswordHash::variant()`] and [`PasswordHash::salt()`]. These can be used to audit
+//! stored password hashes and find the ones that should be rehashed with stronger parameters.
+//! [`verify_and_maybe_rehash()`] does this when a user logs in, since the password is
+//! available then.
+//!
 //! The following is an example of how the encoded password hash might look:
 //! ```text
 //! $argon2i$v=19$m=8192,t=3,p=1$c21hbGxzYWx0$lmO1aPPy3x0CcvrKpFLi1TL/uSVJ/eO5hPHiWZFaWvY
@@ -48,13 +58,18 @@
 //! See a more detailed description of the [encoding format here].
 //!
 //! # Note:
-//! This implementation only supports a single thread/lane.
+//! Password hashes are only ever created using a single thread/lane. Password hashes
+//! produced by other Argon2 implementations may use any variant (Argon2d, Argon2i or
+//! Argon2id), version 0x10 or 0x13 and any number of lanes. These can be parsed with
+//! [`PasswordHash::from_encoded()`] and verified with [`hash_password_verify()`].
 //!
 //! # Parameters:
 //! - `password`: The password to be hashed.
 //! - `expected`: The expected password hash.
 //! - `iterations`: Iterations cost parameter for Argon2i.
 //! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2i.
+//! - `min_entropy_bits`: The least estimated entropy, in bits, that [`hash_password_checked()`]
+//!   accepts for `password`.
 //!
 //! # Errors:
 //! An error will be returned if:
@@ -62,6 +77,8 @@
 //! - `iterations` is less than 3.
 //! - The length of the `password` is greater than [`isize::MAX`].
 //! - The password hash does not match `expected`.
+//! - The estimated entropy of `password` is less than `min_entropy_bits`. The [`ErrorKind`] is
+//!   then [`ErrorKind::WeakPassword`].
 //!
 //! # Panics:
 //! A panic will occur if:
@@ -73,6 +90,12 @@
 //! Either use [`hash_password_verify()`] or compare two [`PasswordHash`]es.
 //! - Choosing the correct cost parameters is important for security. Please refer to [libsodium's docs]
 //! for a description of how to do this.
+//! - Use [`verify_or_dummy()`] when looking up users, so that the time it takes to
+//! reject a login does not reveal whether the user exists.
+//! - [`relief`] lets clients compute Argon2i, for servers that cannot afford to.
+//! - [`estimate_entropy_bits()`] is only a rough heuristic. It catches short, repetitive or
+//!   very common passwords, but a password it rates highly can still be easy to guess, for
+//!   example if it is a known phrase or has been leaked before.
 //!
 //! # Example:
 //! ```rust
@@ -88,10 +111,16 @@
 //! [libsodium's docs]: https://download.libsodium.org/doc/password_hashing/default_phf#guidelines-for-choosing-the-parameters
 
 pub use super::hltypes::Password;
+
+pub mod relief;
 use super::hltypes::Salt;
+pub(crate) use super::MIN_ITERATIONS;
 use crate::{
-    errors::UnknownCryptoError,
-    hazardous::kdf::argon2i::{self, LANES, MIN_MEMORY},
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::kdf::argon2i::{
+        self, ARGON2D_VARIANT, ARGON2ID_VARIANT, ARGON2_VARIANT, ARGON2_VERSION, ARGON2_VERSION_10,
+        LANES, MAX_LANES, MIN_MEMORY,
+    },
 };
 use ct_codecs::{Base64NoPadding, Decoder, Encoder};
 use zeroize::Zeroizing;
@@ -102,30 +131,90 @@ pub const SALT_LENGTH: usize = 16;
 /// The length of the hashed password.
 pub const PWHASH_LENGTH: usize = 32;
 
-/// Minimum amount of iterations.
-pub(crate) const MIN_ITERATIONS: u32 = 3;
+/// The maximum length of the optional `keyid` field, as defined for Argon2 in the PHC string format.
+const MAX_KEYID_LENGTH: usize = 8;
+
+/// The maximum length of the optional `data` field, as defined for Argon2 in the PHC string format.
+const MAX_DATA_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The Argon2 variant a password hash was produced with.
+///
+/// [`hash_password()`] always uses Argon2i. The other variants are only found in
+/// password hashes produced by other Argon2 implementations.
+pub enum Variant {
+    /// Argon2d, which uses data-dependent memory access.
+    Argon2d,
+    /// Argon2i, which uses data-independent memory access.
+    Argon2i,
+    /// Argon2id, which uses data-independent memory access for the first half of the first pass.
+    Argon2id,
+}
+
+impl Variant {
+    /// Parse the algorithm identifier of an encoded password hash.
+    fn from_name(name: &str) -> Result<Self, UnknownCryptoError> {
+        match name {
+            "argon2d" => Ok(Self::Argon2d),
+            "argon2i" => Ok(Self::Argon2i),
+            "argon2id" => Ok(Self::Argon2id),
+            _ => Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+        }
+    }
+
+    /// The algorithm identifier used in an encoded password hash.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Argon2d => "argon2d",
+            Self::Argon2i => "argon2i",
+            Self::Argon2id => "argon2id",
+        }
+    }
+
+    /// The type value that Argon2 uses internally.
+    fn id(&self) -> u32 {
+        match self {
+            Self::Argon2d => ARGON2D_VARIANT,
+            Self::Argon2i => ARGON2_VARIANT,
+            Self::Argon2id => ARGON2ID_VARIANT,
+        }
+    }
+}
 
 /// A type to represent the `PasswordHash` that Argon2i returns when used for password hashing.
 ///
 ///  
+/// # About:
+/// [`PasswordHash::from_encoded()`] accepts password hashes in the form of:
+/// `$<argon2d|argon2i|argon2id>[$v=<16|19>]$<parameters>$<salt>$<hash>`
+///
+/// The parameters are `m`, `t` and `p`, optionally followed by `keyid` and `data`,
+/// and may be given in any order. If the version is omitted, it is 0x10 (16). The
+/// password hash is re-encoded in the canonical order, so
+/// [`PasswordHash::unprotected_as_encoded()`] may differ from the input:
+/// `$<variant>$v=<version>$m=<value>,t=<value>,p=<value>[,keyid=<value>][,data=<value>]$<salt>$<hash>`
+///
 /// # Errors:
 /// An error will be returned if:
 /// - The encoded password hash contains whitespace.
-/// - The encoded password hash has a parallelism count other than 1.
-/// - The encoded password contains any other fields than: The algorithm name,
-/// version, m, t, p and the salt and password hash.
+/// - The algorithm name is not one of `argon2d`, `argon2i` or `argon2id`.
+/// - The version is not 16 or 19.
+/// - The encoded password hash contains any other fields than: The algorithm name,
+/// version, parameters and the salt and password hash.
+/// - Any of m, t or p is missing, or any parameter is given more than once or is unknown.
 /// - The encoded password hash contains invalid Base64 encoding.
 /// - Any decimal parameter value, such as m, contains leading zeroes and is longer
 /// than a single character.
-/// - `iterations` is less than 3.
-/// - `memory` is less than 8.
+/// - `iterations` is less than 1.
+/// - `memory` is less than 8 times the parallelism count.
+/// - The parallelism count is less than 1 or greater than `2^24-1`.
+/// - `keyid` is empty or longer than 8 bytes.
+/// - `data` is empty or longer than 32 bytes.
 /// - `password` is not 32 bytes.
 /// - `salt` is not 16 bytes.
 /// - The encoded password hash contains numerical values that cannot
 /// be represented as a `u32`.
 /// - The encoded password hash length is less than [`PasswordHash::MIN_ENCODED_LEN`] or greater than [`PasswordHash::MAX_ENCODED_LEN`].
-/// - The parameters in the encoded password hash are not correctly ordered. The ordering must be:
-/// `$argon2i$v=19$m=<value>,t=<value>,p=<value>$<salt>$<hash>`
 /// # Panics:
 /// A panic will occur if:
 /// - Overflowing calculations happen on `usize` when decoding the password and salt from Base64.
@@ -135,6 +224,9 @@ pub(crate) const MIN_ITERATIONS: u32 = 3;
 /// that the type implements.
 /// - Never use `unprotected_as_bytes()` or `unprotected_as_encoded()` to compare password hashes,
 /// as that will not run in constant-time. Compare `PasswordHash`es directly using `==` instead.
+/// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+///   in custom verification logic that should not short-circuit.
+///
 /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
 /// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
 /// is implemented in such a way that the comparison happens in constant time. Thus, users should
@@ -162,24 +254,30 @@ pub struct PasswordHash {
     salt: Salt,
     iterations: u32,
     memory: u32,
+    variant: Variant,
+    version: u32,
+    lanes: u32,
+    keyid: Option<Vec<u8>>,
+    data: Option<Vec<u8>>,
 }
 
 #[allow(clippy::len_without_is_empty)]
 impl PasswordHash {
     /// Given a 16-byte salt (22 characters encoded) and 32-byte password hash (43 characters encoded),
-    /// and parameters (m, t) in decimal representation of 1..10 in length, 92 is the minimum length for an encoded password hash.
-    pub const MIN_ENCODED_LEN: usize = 92;
+    /// `$argon2d$m=8,t=1,p=1$<salt>$<hash>` is the shortest encoded password hash, at 87 characters.
+    pub const MIN_ENCODED_LEN: usize = 87;
 
     /// Given a 16-byte salt (22 characters encoded) and 32-byte password hash (43 characters encoded),
-    /// and parameters (m, t) in decimal representation of 1..10 in length, 110 is the maximum length for an encoded password hash.
-    pub const MAX_ENCODED_LEN: usize = 110;
+    /// the `argon2id` variant, parameters (m, t, p) at their maximum values, an 8-byte `keyid` and 32-byte
+    /// `data`, 185 is the maximum length for an encoded password hash.
+    pub const MAX_ENCODED_LEN: usize = 185;
 
     /// Parse a decimal parameter value to a u32. Returns an error on overflow
     /// and if the value has leading zeroes.
     fn parse_decimal_value(value: &str) -> Result<u32, UnknownCryptoError> {
         // See: https://github.com/P-H-C/phc-string-format/blob/master/phc-sf-spec.md#decimal-encoding
         if value.len() > 1 && value.starts_with('0') {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
         // .parse::<T>() detects overflows (in debug and release builds)
         // and rejects empty strings. If the value contains spaces, parsing
@@ -187,19 +285,38 @@ impl PasswordHash {
         Ok(value.parse::<u32>()?)
     }
 
+    /// Parse an optional Base64-encoded parameter value, that is at most `max_len` bytes.
+    fn parse_binary_value(value: &str, max_len: usize) -> Result<Vec<u8>, UnknownCryptoError> {
+        let decoded = Base64NoPadding::decode_to_vec(value, None)?;
+        if decoded.is_empty() || decoded.len() > max_len {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        Ok(decoded)
+    }
+
     /// Encode password hash, salt and parameters for storage.
-    fn encode(
-        password_hash: &[u8],
-        salt: &[u8],
-        iterations: u32,
-        memory: u32,
-    ) -> Result<String, UnknownCryptoError> {
+    fn encode(&self) -> Result<String, UnknownCryptoError> {
+        let mut optional_params = String::new();
+        if let Some(keyid) = &self.keyid {
+            optional_params.push_str(",keyid=");
+            optional_params.push_str(&Base64NoPadding::encode_to_string(keyid)?);
+        }
+        if let Some(data) = &self.data {
+            optional_params.push_str(",data=");
+            optional_params.push_str(&Base64NoPadding::encode_to_string(data)?);
+        }
+
         Ok(format!(
-            "$argon2i$v=19$m={},t={},p=1${}${}",
-            memory,
-            iterations,
-            Base64NoPadding::encode_to_string(salt)?,
-            Base64NoPadding::encode_to_string(password_hash)?,
+            "${}$v={}$m={},t={},p={}{}${}${}",
+            self.variant.name(),
+            self.version,
+            self.memory,
+            self.iterations,
+            self.lanes,
+            optional_params,
+            Base64NoPadding::encode_to_string(&self.salt)?,
+            Base64NoPadding::encode_to_string(&self.password_hash)?,
         ))
     }
 
@@ -212,27 +329,33 @@ impl PasswordHash {
         memory: u32,
     ) -> Result<Self, UnknownCryptoError> {
         if password_hash.len() != PWHASH_LENGTH {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
         if salt.len() != SALT_LENGTH {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
         if iterations < MIN_ITERATIONS {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
         if memory < MIN_MEMORY {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
 
-        let encoded_password_hash = Self::encode(password_hash, salt, iterations, memory)?;
-
-        Ok(Self {
-            encoded_password_hash,
+        let mut ret = Self {
+            encoded_password_hash: String::new(),
             password_hash: password_hash.into(),
             salt: Salt::from_slice(salt)?,
             iterations,
             memory,
-        })
+            variant: Variant::Argon2i,
+            version: ARGON2_VERSION,
+            lanes: LANES,
+            keyid: None,
+            data: None,
+        };
+        ret.encoded_password_hash = ret.encode()?;
+
+        Ok(ret)
     }
 
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -241,80 +364,115 @@ impl PasswordHash {
         if password_hash.len() > Self::MAX_ENCODED_LEN
             || password_hash.len() < Self::MIN_ENCODED_LEN
         {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
 
         if password_hash.contains(' ') {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
 
         let parts_split = password_hash.split('$').collect::<Vec<&str>>();
-        if parts_split.len() != 6 {
-            return Err(UnknownCryptoError);
-        }
-        let mut parts = parts_split.into_iter();
-        if parts.next() != Some("") {
-            return Err(UnknownCryptoError);
-        }
-        if parts.next() != Some("argon2i") {
-            return Err(UnknownCryptoError);
-        }
-        if parts.next() != Some("v=19") {
-            return Err(UnknownCryptoError);
+        // The version field is optional.
+        if parts_split.len() != 6 && parts_split.len() != 5 {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
-
-        // Splits as ["m", "X", "t", "Y", "p", "Z"] where m=X, t=Y and p=Z.
-        let param_parts_split = parts
-            .next()
-            .unwrap()
-            .split(|v| v == '=' || v == ',')
-            .collect::<Vec<&str>>();
-        if param_parts_split.len() != 6 {
-            return Err(UnknownCryptoError);
+        let mut parts = parts_split.iter().copied();
+        let mut next_part = || {
+            parts
+                .next()
+                .ok_or(UnknownCryptoError::new(ErrorKind::InvalidEncoding))
+        };
+        if !next_part()?.is_empty() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
-        let mut param_parts = param_parts_split.into_iter();
+        let variant = Variant::from_name(next_part()?)?;
 
-        if param_parts.next() != Some("m") {
-            return Err(UnknownCryptoError);
+        let version = if parts_split.len() == 6 {
+            let version_part = next_part()?;
+            if !version_part.starts_with("v=") {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+            }
+            Self::parse_decimal_value(&version_part[2..])?
+        } else {
+            ARGON2_VERSION_10
+        };
+        if version != ARGON2_VERSION && version != ARGON2_VERSION_10 {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
         }
 
-        let memory = Self::parse_decimal_value(&param_parts.next().unwrap())?;
-        if memory < MIN_MEMORY {
-            return Err(UnknownCryptoError);
+        let mut memory: Option<u32> = None;
+        let mut iterations: Option<u32> = None;
+        let mut lanes: Option<u32> = None;
+        let mut keyid: Option<Vec<u8>> = None;
+        let mut data: Option<Vec<u8>> = None;
+
+        for param in next_part()?.split(',') {
+            let mut param_parts = param.splitn(2, '=');
+            let name = param_parts.next().unwrap_or("");
+            let value = match param_parts.next() {
+                Some(value) => value,
+                None => return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+            };
+
+            let is_duplicate = match name {
+                "m" => memory.replace(Self::parse_decimal_value(value)?).is_some(),
+                "t" => iterations
+                    .replace(Self::parse_decimal_value(value)?)
+                    .is_some(),
+                "p" => lanes.replace(Self::parse_decimal_value(value)?).is_some(),
+                "keyid" => keyid
+                    .replace(Self::parse_binary_value(value, MAX_KEYID_LENGTH)?)
+                    .is_some(),
+                "data" => data
+                    .replace(Self::parse_binary_value(value, MAX_DATA_LENGTH)?)
+                    .is_some(),
+                _ => return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+            };
+            if is_duplicate {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+            }
         }
 
-        if param_parts.next() != Some("t") {
-            return Err(UnknownCryptoError);
-        }
-        let iterations = Self::parse_decimal_value(&param_parts.next().unwrap())?;
-        if iterations < MIN_ITERATIONS {
-            return Err(UnknownCryptoError);
+        let (memory, iterations, lanes) = match (memory, iterations, lanes) {
+            (Some(m), Some(t), Some(p)) => (m, t, p),
+            _ => return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+        };
+        if !(1..=MAX_LANES).contains(&lanes) {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
-
-        if param_parts.next() != Some("p") {
-            return Err(UnknownCryptoError);
+        if memory < MIN_MEMORY * lanes {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
-        let lanes = Self::parse_decimal_value(&param_parts.next().unwrap())?;
-        if lanes != LANES {
-            return Err(UnknownCryptoError);
+        // Only new password hashes are restricted to at least 3 iterations. Password hashes
+        // produced elsewhere may use as few as the specification allows.
+        if iterations < argon2i::MIN_ITERATIONS {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
         }
 
-        let salt = Base64NoPadding::decode_to_vec(parts.next().unwrap(), None)?;
+        let salt = Base64NoPadding::decode_to_vec(next_part()?, None)?;
         if salt.len() != SALT_LENGTH {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
-        let password_hash_raw = Base64NoPadding::decode_to_vec(&parts.next().unwrap(), None)?;
+        let password_hash_raw = Base64NoPadding::decode_to_vec(next_part()?, None)?;
         if password_hash_raw.len() != PWHASH_LENGTH {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
         }
 
-        Ok(Self {
-            encoded_password_hash: password_hash.into(),
+        let mut ret = Self {
+            encoded_password_hash: String::new(),
             password_hash: password_hash_raw,
             salt: Salt::from_slice(&salt)?,
             iterations,
             memory,
-        })
+            variant,
+            version,
+            lanes,
+            keyid,
+            data,
+        };
+        ret.encoded_password_hash = ret.encode()?;
+
+        Ok(ret)
     }
 
     #[inline]
@@ -337,6 +495,36 @@ impl PasswordHash {
         self.password_hash.len()
     }
 
+    #[inline]
+    /// Return the iterations (`t`) the password hash was produced with.
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    #[inline]
+    /// Return the memory (`m`), in kibibytes (KiB), the password hash was produced with.
+    pub fn memory_kib(&self) -> u32 {
+        self.memory
+    }
+
+    #[inline]
+    /// Return the parallelism count (`p`) the password hash was produced with.
+    pub fn lanes(&self) -> u32 {
+        self.lanes
+    }
+
+    #[inline]
+    /// Return the Argon2 variant the password hash was produced with.
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    #[inline]
+    /// Return the salt the password hash was produced with.
+    pub fn salt(&self) -> &Salt {
+        &self.salt
+    }
+
     #[inline]
     /// Return `true` if the password hash is empty, `false` otherwise.
     ///
@@ -349,6 +537,31 @@ impl PasswordHash {
     }
 }
 
+#[cfg(feature = "serde")]
+/// Serialized as the encoded password hash, in both human-readable and binary formats.
+/// Password hashes are usually serialized for storage, so this is the same format
+/// that [`PasswordHash::unprotected_as_encoded()`] returns.
+impl serde::Serialize for PasswordHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.unprotected_as_encoded())
+    }
+}
+
+#[cfg(feature = "serde")]
+/// Deserialized from an encoded password hash, using [`PasswordHash::from_encoded()`].
+impl<'de> serde::Deserialize<'de> for PasswordHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let encoded = String::deserialize(deserializer)?;
+        PasswordHash::from_encoded(&encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 impl core::fmt::Debug for PasswordHash {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
@@ -360,6 +573,24 @@ impl core::fmt::Debug for PasswordHash {
     }
 }
 
+/// Formatted as the encoded password hash, which is the same as
+/// [`PasswordHash::unprotected_as_encoded()`]. Unlike `Debug`, this does not omit
+/// the password hash.
+impl core::fmt::Display for PasswordHash {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.unprotected_as_encoded())
+    }
+}
+
+/// Parsed from an encoded password hash, using [`PasswordHash::from_encoded()`].
+impl core::str::FromStr for PasswordHash {
+    type Err = UnknownCryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PasswordHash::from_encoded(s)
+    }
+}
+
 impl_ct_partialeq_trait!(PasswordHash, unprotected_as_bytes);
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -370,11 +601,10 @@ pub fn hash_password(
     memory: u32,
 ) -> Result<PasswordHash, UnknownCryptoError> {
     if iterations < MIN_ITERATIONS {
-        return Err(UnknownCryptoError);
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
     }
 
-    // Cannot panic as this is a valid size.
-    let salt = Salt::generate(SALT_LENGTH).unwrap();
+    let salt = Salt::generate(SALT_LENGTH)?;
     let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
 
     argon2i::derive_key(
@@ -390,12 +620,140 @@ pub fn hash_password(
     PasswordHash::from_slice(buffer.as_ref(), salt.as_ref(), iterations, memory)
 }
 
+/// Passwords that are common enough that they are rejected regardless of their length
+/// and character classes.
+const COMMON_PASSWORDS: [&str; 24] = [
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "123456",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "asdfghjkl",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "iloveyou",
+    "letmein",
+    "welcome",
+    "admin",
+    "monkey",
+    "dragon",
+    "football",
+    "baseball",
+    "sunshine",
+    "trustno1",
+];
+
+/// Estimate the entropy, in bits, of `password`.
+///
+/// The estimate is based on the length of the password and the classes of characters
+/// it contains (lowercase and uppercase ASCII letters, digits, other ASCII characters
+/// and non-ASCII characters). A character that repeats the previous one, or is next to
+/// it in the ASCII table (as in `abc` or `321`), only counts as a single bit. A password
+/// that is one of a small list of very common passwords, ignoring case, has an estimated
+/// entropy of 0.
+///
+/// A `password` that is not valid UTF-8 is estimated per byte.
+pub fn estimate_entropy_bits(password: &Password) -> u32 {
+    let bytes = password.unprotected_as_bytes();
+    let chars: Zeroizing<Vec<char>> = Zeroizing::new(match core::str::from_utf8(bytes) {
+        Ok(s) => {
+            let lower = Zeroizing::new(s.to_ascii_lowercase());
+            if COMMON_PASSWORDS.contains(&lower.as_str()) {
+                return 0;
+            }
+            s.chars().collect()
+        }
+        Err(_) => bytes.iter().map(|b| char::from(*b)).collect(),
+    });
+
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) = (0, 0, 0, 0, 0);
+    for c in chars.iter() {
+        match c {
+            'a'..='z' => lower = 26,
+            'A'..='Z' => upper = 26,
+            '0'..='9' => digit = 10,
+            ' '..='~' => symbol = 33,
+            _ => other = 100,
+        }
+    }
+    let pool = f64::from(lower + upper + digit + symbol + other);
+    if pool == 0.0 {
+        return 0;
+    }
+    let bits_per_char = pool.log2();
+
+    let mut bits = 0f64;
+    let mut previous: Option<u32> = None;
+    for c in chars.iter() {
+        let c = u32::from(*c);
+        bits += match previous {
+            Some(p) if c == p || c == p.wrapping_add(1) || c == p.wrapping_sub(1) => 1.0,
+            _ => bits_per_char,
+        };
+        previous = Some(c);
+    }
+
+    if bits >= f64::from(u32::MAX) {
+        u32::MAX
+    } else {
+        bits as u32
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Hash a password using Argon2i, if its estimated entropy is at least `min_entropy_bits`.
+///
+/// The entropy of `password` is estimated with [`estimate_entropy_bits()`], before any
+/// time is spent on Argon2i. If it is too low, an error with [`ErrorKind::WeakPassword`] is
+/// returned, which can be used to ask the user for a better password.
+///
+/// # Example:
+/// ```rust
+/// use orion::errors::ErrorKind;
+/// use orion::pwhash;
+///
+/// let weak = pwhash::Password::from_slice(b"aaaaaaaaaaaa")?;
+/// let err = pwhash::hash_password_checked(&weak, 3, 1<<16, 50).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::WeakPassword);
+///
+/// let password = pwhash::Password::from_slice(b"correct horse battery staple")?;
+/// let hash = pwhash::hash_password_checked(&password, 3, 1<<16, 50)?;
+/// assert!(pwhash::hash_password_verify(&hash, &password).is_ok());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn hash_password_checked(
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+    min_entropy_bits: u32,
+) -> Result<PasswordHash, UnknownCryptoError> {
+    if estimate_entropy_bits(password) < min_entropy_bits {
+        return Err(UnknownCryptoError::new(ErrorKind::WeakPassword));
+    }
+
+    hash_password(password, iterations, memory)
+}
+
 /// Hash and verify a password using Argon2i. The Argon2i parameters `iterations`
 /// and `memory` will be pulled from the `expected: &PasswordHash` argument. If
 /// you want to manually specify the iterations and memory for Argon2i to use in
 /// hashing the `password` argument, see the
 /// [`hazardous::kdf`](crate::hazardous::kdf::argon2i) module.
 ///
+/// If `expected` was produced by another Argon2 implementation, its variant,
+/// version, parallelism count and `data` are used as well.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `expected` contains a `keyid`. The secret key it identifies is not available to orion.
+/// - The password hash does not match `expected`.
+///
 /// # Example:
 /// ```rust
 /// use orion::pwhash;
@@ -421,25 +779,301 @@ pub fn hash_password_verify(
     expected: &PasswordHash,
     password: &Password,
 ) -> Result<(), UnknownCryptoError> {
+    if expected.keyid.is_some() {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
     let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
 
-    argon2i::verify(
+    argon2i::verify_with(
+        expected.variant.id(),
+        expected.version,
+        expected.lanes,
         expected.unprotected_as_bytes(),
         password.unprotected_as_bytes(),
         expected.salt.as_ref(),
         expected.iterations,
         expected.memory,
         None,
-        None,
+        expected.data.as_deref(),
         buffer.as_mut(),
     )
 }
 
+/// The salt that [`verify_or_dummy()`] hashes with, when there is no password hash for a user.
+const DUMMY_SALT: [u8; SALT_LENGTH] = *b"orion-dummy-salt";
+
+/// Verify a password like [`hash_password_verify()`], or spend the same amount of
+/// time failing to verify it when `expected` is `None`.
+///
+/// Returning early when a user does not exist reveals, through timing, which users
+/// do exist. When `expected` is `None`, `password` is instead hashed with `iterations`
+/// and `memory`, and an error is always returned.
+///
+/// # Parameters:
+/// - `iterations`, `memory`: The Argon2i parameters used when `expected` is `None`.
+///   These should be the parameters that the password hashes of existing users are
+///   created with. They are not used when `expected` is `Some`.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `expected` is `Some` and [`hash_password_verify()`] returns an error.
+/// - `expected` is `None`. If `iterations` is less than 3 or `memory` is less than 8,
+///   the [`ErrorKind`] is `InvalidParameter`, otherwise it is `MacMismatch`.
+///
+/// # Security:
+/// - The timing only matches that of existing users if their password hashes use
+///   `iterations` and `memory`. Password hashes with other parameters, e.g. from before
+///   the parameters were increased, take a different amount of time to verify.
+///
+/// # Example:
+/// ```rust
+/// use orion::pwhash;
+///
+/// let password = pwhash::Password::from_slice(b"Secret password")?;
+/// let hash = pwhash::hash_password(&password, 3, 1<<16)?;
+///
+/// // A user that exists.
+/// assert!(pwhash::verify_or_dummy(Some(&hash), &password, 3, 1<<16).is_ok());
+/// // A user that does not exist takes as long to reject.
+/// assert!(pwhash::verify_or_dummy(None, &password, 3, 1<<16).is_err());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+pub fn verify_or_dummy(
+    expected: Option<&PasswordHash>,
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+) -> Result<(), UnknownCryptoError> {
+    match expected {
+        Some(expected) => hash_password_verify(expected, password),
+        None => {
+            if iterations < MIN_ITERATIONS {
+                return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+            }
+
+            // The hash does not matter, there was no password hash to verify against.
+            let mut buffer = Zeroizing::new([0u8; PWHASH_LENGTH]);
+            argon2i::derive_key(
+                password.unprotected_as_bytes(),
+                &DUMMY_SALT,
+                iterations,
+                memory,
+                None,
+                None,
+                buffer.as_mut(),
+            )?;
+
+            Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+        }
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a password like [`hash_password_verify()`], and rehash it if `expected` was
+/// produced with weaker cost parameters than `iterations` and `memory`, or with other
+/// settings than [`hash_password()`] uses.
+///
+/// Returns `Ok(Some(hash))` with the new password hash, which should replace `expected`
+/// wherever it is stored, when `expected`:
+/// - has fewer iterations or less memory than desired.
+/// - is not Argon2i, is not version 0x13, uses more than one lane or has `data`. Such
+///   password hashes are produced by other Argon2 implementations.
+///
+/// Returns `Ok(None)` otherwise.
+///
+/// # Parameters:
+/// - `iterations`, `memory`: The cost parameters that password hashes should have.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - [`hash_password_verify()`] returns an error. No new password hash is created then.
+/// - A new password hash is needed, and [`hash_password()`] returns an error for
+///   `iterations` and `memory`.
+///
+/// # Panics:
+/// A panic will occur if:
+/// - Failure to generate random bytes securely.
+///
+/// # Example:
+/// ```rust
+/// use orion::pwhash;
+///
+/// let password = pwhash::Password::from_slice(b"Secret password")?;
+/// let mut stored = pwhash::hash_password(&password, 3, 1<<15)?;
+///
+/// // The parameters have since been increased.
+/// if let Some(rehashed) = pwhash::verify_and_maybe_rehash(&stored, &password, 3, 1<<16)? {
+///     stored = rehashed;
+/// }
+/// assert_eq!(stored.memory_kib(), 1<<16);
+/// assert!(pwhash::verify_and_maybe_rehash(&stored, &password, 3, 1<<16)?.is_none());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn verify_and_maybe_rehash(
+    expected: &PasswordHash,
+    password: &Password,
+    iterations: u32,
+    memory: u32,
+) -> Result<Option<PasswordHash>, UnknownCryptoError> {
+    hash_password_verify(expected, password)?;
+
+    let is_current = expected.variant == Variant::Argon2i
+        && expected.version == ARGON2_VERSION
+        && expected.lanes == LANES
+        && expected.data.is_none();
+    if is_current && expected.iterations >= iterations && expected.memory >= memory {
+        return Ok(None);
+    }
+
+    hash_password(password, iterations, memory).map(Some)
+}
+
 // Testing public functions in the module.
 #[cfg(test)]
 mod public {
     use super::*;
 
+    mod test_verify_and_maybe_rehash {
+        use super::*;
+
+        #[test]
+        fn test_rehash_when_weaker() {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = hash_password(&password, 3, 64).unwrap();
+
+            for (iterations, memory) in [(4, 64), (3, 128), (4, 128)].iter() {
+                let rehashed = verify_and_maybe_rehash(&hash, &password, *iterations, *memory)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(rehashed.iterations(), *iterations);
+                assert_eq!(rehashed.memory_kib(), *memory);
+                assert!(hash_password_verify(&rehashed, &password).is_ok());
+            }
+        }
+
+        #[test]
+        fn test_no_rehash_when_as_strong() {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = hash_password(&password, 4, 128).unwrap();
+
+            for (iterations, memory) in [(4, 128), (3, 128), (4, 64), (3, 8)].iter() {
+                assert!(
+                    verify_and_maybe_rehash(&hash, &password, *iterations, *memory)
+                        .unwrap()
+                        .is_none()
+                );
+            }
+        }
+
+        /// Verify `encoded`, which has the desired cost parameters, and check that it is
+        /// rehashed into an Argon2i password hash like [`hash_password()`] creates.
+        fn assert_rehashed(encoded: &str) {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = PasswordHash::from_encoded(encoded).unwrap();
+
+            let rehashed = verify_and_maybe_rehash(&hash, &password, 3, 64)
+                .unwrap()
+                .unwrap();
+            assert_eq!(rehashed.variant(), Variant::Argon2i);
+            assert_eq!(rehashed.version, ARGON2_VERSION);
+            assert_eq!(rehashed.lanes(), LANES);
+            assert!(rehashed.data.is_none());
+            assert!(hash_password_verify(&rehashed, &password).is_ok());
+            assert!(verify_and_maybe_rehash(&rehashed, &password, 3, 64)
+                .unwrap()
+                .is_none());
+        }
+
+        #[test]
+        fn test_rehash_argon2d() {
+            assert_rehashed("$argon2d$v=19$m=64,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$JzBAklQCB0Kb7ikYQJ3e/meWcwxSo+zzsZWLgd+YOYM");
+        }
+
+        #[test]
+        fn test_rehash_argon2id() {
+            assert_rehashed("$argon2id$v=19$m=64,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$YWTHOaBOF940qAvuZcalj0uKJAy/VlL0p541rgK3xhY");
+        }
+
+        #[test]
+        fn test_rehash_version_10() {
+            assert_rehashed("$argon2i$v=16$m=64,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$X5QixmQmUdz6sEU16/O8t5WL9y2NosJ/AeYE53DJLhQ");
+        }
+
+        #[test]
+        fn test_rehash_lanes() {
+            assert_rehashed("$argon2i$v=19$m=64,t=3,p=2$c29tZXNhbHRzb21lc2FsdA$6rgOjbHsRPbG8fIy8hDGjfmmCY8g/fNvBN99GBM8x7I");
+        }
+
+        #[test]
+        fn test_rehash_data() {
+            assert_rehashed("$argon2i$v=19$m=64,t=3,p=1,data=YXNzb2NpYXRlZCBkYXRh$c29tZXNhbHRzb21lc2FsdA$kUBOEDyyXl4nAaavYMNDnQkiK6IqXH50illkSSRSgfE");
+        }
+
+        #[test]
+        fn test_wrong_password_not_rehashed() {
+            let password = Password::from_slice(b"password").unwrap();
+            let wrong = Password::from_slice(b"pass").unwrap();
+            let hash = hash_password(&password, 3, 64).unwrap();
+
+            assert!(verify_and_maybe_rehash(&hash, &wrong, 4, 128).is_err());
+            assert!(verify_and_maybe_rehash(&hash, &wrong, 3, 64).is_err());
+        }
+
+        #[test]
+        fn test_invalid_desired_params() {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = hash_password(&password, 3, 64).unwrap();
+
+            assert!(verify_and_maybe_rehash(&hash, &password, 3, 1).is_ok());
+            let err = verify_and_maybe_rehash(&hash, &password, 4, 7).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+        }
+    }
+
+    mod test_verify_or_dummy {
+        use super::*;
+
+        #[test]
+        fn test_some_same_as_hash_password_verify() {
+            let password = Password::from_slice(b"password").unwrap();
+            let hash = hash_password(&password, 3, 64).unwrap();
+
+            let wrong = Password::from_slice(b"pass").unwrap();
+            assert!(verify_or_dummy(Some(&hash), &password, 3, 64).is_ok());
+            assert!(verify_or_dummy(Some(&hash), &wrong, 3, 64).is_err());
+            // The parameters are only used without a password hash.
+            assert!(verify_or_dummy(Some(&hash), &password, 0, 0).is_ok());
+        }
+
+        #[test]
+        fn test_none_always_err() {
+            let password = Password::from_slice(b"password").unwrap();
+            assert_eq!(
+                verify_or_dummy(None, &password, 3, 64).unwrap_err().kind(),
+                ErrorKind::MacMismatch
+            );
+            assert_eq!(
+                verify_or_dummy(None, &password, 4, 128).unwrap_err().kind(),
+                ErrorKind::MacMismatch
+            );
+        }
+
+        #[test]
+        fn test_none_invalid_params() {
+            let password = Password::from_slice(b"password").unwrap();
+            for (iterations, memory) in [(2, 64), (3, 7)].iter() {
+                assert_eq!(
+                    verify_or_dummy(None, &password, *iterations, *memory)
+                        .unwrap_err()
+                        .kind(),
+                    ErrorKind::InvalidParameter
+                );
+            }
+        }
+    }
+
     #[test]
     #[cfg(feature = "safe_api")]
     fn test_debug_impl() {
@@ -523,6 +1157,63 @@ mod public {
         }
     }
 
+    /// Password hashes produced by other Argon2 implementations. The Argon2id hashes were
+    /// generated with the `cryptography` Python package, where the first uses the default
+    /// parameters of the `argon2` crate.
+    mod test_encoding_interop {
+        use super::*;
+
+        fn verify(encoded_hash: &str) -> Result<(), UnknownCryptoError> {
+            let password = Password::from_slice(b"password").unwrap();
+            let expected = PasswordHash::from_encoded(encoded_hash).unwrap();
+            assert_eq!(expected.unprotected_as_encoded(), encoded_hash);
+
+            hash_password_verify(&expected, &password)
+        }
+
+        #[test]
+        fn test_argon2id_default_params() {
+            assert!(verify("$argon2id$v=19$m=19456,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$K13EBUiG7JV+9ZxztmHFTdb7J0WQsnj2V8bZaqyPptE").is_ok());
+        }
+
+        #[test]
+        fn test_argon2id_lanes() {
+            assert!(verify("$argon2id$v=19$m=100,t=2,p=3$c29tZXNhbHRzb21lc2FsdA$suCb5f3kXp455yDxcVjIY1PDRV91oB88wpDxT23aUac").is_ok());
+            assert!(verify("$argon2id$v=19$m=100,t=2,p=2$c29tZXNhbHRzb21lc2FsdA$suCb5f3kXp455yDxcVjIY1PDRV91oB88wpDxT23aUac").is_err());
+        }
+
+        #[test]
+        fn test_argon2id_data() {
+            assert!(verify("$argon2id$v=19$m=64,t=1,p=4,data=YXNzb2NpYXRlZCBkYXRh$c29tZXNhbHRzb21lc2FsdA$GAi417IV0Ue0OaJfuMiOB0vtesJnA9AcF2DQRx47KWE").is_ok());
+            assert!(verify("$argon2id$v=19$m=64,t=1,p=4$c29tZXNhbHRzb21lc2FsdA$GAi417IV0Ue0OaJfuMiOB0vtesJnA9AcF2DQRx47KWE").is_err());
+        }
+
+        #[test]
+        fn test_argon2d() {
+            assert!(verify("$argon2d$v=19$m=64,t=2,p=2$c29tZXNhbHRzb21lc2FsdA$WNlIX5xzlOejo3Wa59ERwn/ME4SL/Hj6bx1mGOKQGnk").is_ok());
+            assert!(verify("$argon2id$v=19$m=64,t=2,p=2$c29tZXNhbHRzb21lc2FsdA$WNlIX5xzlOejo3Wa59ERwn/ME4SL/Hj6bx1mGOKQGnk").is_err());
+        }
+
+        #[test]
+        fn test_argon2i_few_iterations() {
+            assert!(verify("$argon2i$v=19$m=64,t=2,p=1$c29tZXNhbHRzb21lc2FsdA$pbigQcuZHII9wPBZI5URmc0Lp86D/tE0caICkLhLFTQ").is_ok());
+            assert!(verify("$argon2i$v=19$m=64,t=1,p=1$c29tZXNhbHRzb21lc2FsdA$77X1OtRH9olx3G9mXHJJzKWqXxbc1sXyAxQFftkk7VM").is_ok());
+            assert!(verify("$argon2i$v=19$m=64,t=1,p=1$c29tZXNhbHRzb21lc2FsdA$pbigQcuZHII9wPBZI5URmc0Lp86D/tE0caICkLhLFTQ").is_err());
+        }
+
+        #[test]
+        fn test_argon2i_version_10() {
+            assert!(verify("$argon2i$v=16$m=64,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$X5QixmQmUdz6sEU16/O8t5WL9y2NosJ/AeYE53DJLhQ").is_ok());
+            assert!(verify("$argon2i$v=19$m=64,t=3,p=1$c29tZXNhbHRzb21lc2FsdA$X5QixmQmUdz6sEU16/O8t5WL9y2NosJ/AeYE53DJLhQ").is_err());
+        }
+
+        #[test]
+        fn test_keyid_err() {
+            let err = verify("$argon2id$v=19$m=19456,t=2,p=1,keyid=aWQ$c29tZXNhbHRzb21lc2FsdA$K13EBUiG7JV+9ZxztmHFTdb7J0WQsnj2V8bZaqyPptE").unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidParameter);
+        }
+    }
+
     mod test_password_hash {
         use super::*;
 
@@ -538,6 +1229,36 @@ mod public {
             assert_eq!(password_hash, password_hash_again);
         }
 
+        #[test]
+        fn test_display_fromstr() {
+            let password_hash =
+                PasswordHash::from_slice(&[0u8; 32], &[0u8; 16], 3, 1 << 16).unwrap();
+            let encoded = password_hash.to_string();
+            assert_eq!(encoded, password_hash.unprotected_as_encoded());
+            assert_eq!(encoded.parse::<PasswordHash>().unwrap(), password_hash);
+            assert!("$argon2i$v=19$m=65536,t=3,p=1"
+                .parse::<PasswordHash>()
+                .is_err());
+        }
+
+        #[test]
+        fn test_accessors() {
+            let password_hash =
+                PasswordHash::from_slice(&[0u8; 32], &[1u8; 16], 3, 1 << 16).unwrap();
+            assert_eq!(password_hash.iterations(), 3);
+            assert_eq!(password_hash.memory_kib(), 1 << 16);
+            assert_eq!(password_hash.lanes(), 1);
+            assert_eq!(password_hash.variant(), Variant::Argon2i);
+            assert_eq!(password_hash.salt().as_ref(), &[1u8; 16]);
+
+            let external = PasswordHash::from_encoded("$argon2id$v=19$m=100,t=2,p=3$c29tZXNhbHRzb21lc2FsdA$suCb5f3kXp455yDxcVjIY1PDRV91oB88wpDxT23aUac").unwrap();
+            assert_eq!(external.iterations(), 2);
+            assert_eq!(external.memory_kib(), 100);
+            assert_eq!(external.lanes(), 3);
+            assert_eq!(external.variant(), Variant::Argon2id);
+            assert_eq!(external.salt().as_ref(), b"somesaltsomesalt");
+        }
+
         #[test]
         fn test_password_hash_ne() {
             let password_hash =
@@ -611,9 +1332,14 @@ mod public {
             let zero = "$argon2i$v=19$m=65536,t=3,p=0$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let two = "$argon2i$v=19$m=65536,t=3,p=2$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
 
+            let max = "$argon2i$v=19$m=134217720,t=3,p=16777215$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let above_max = "$argon2i$v=19$m=134217728,t=3,p=16777216$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
             assert!(PasswordHash::from_encoded(one).is_ok());
             assert!(PasswordHash::from_encoded(zero).is_err());
-            assert!(PasswordHash::from_encoded(two).is_err());
+            assert!(PasswordHash::from_encoded(two).is_ok());
+            assert!(PasswordHash::from_encoded(max).is_ok());
+            assert!(PasswordHash::from_encoded(above_max).is_err());
         }
 
         #[test]
@@ -623,31 +1349,48 @@ mod public {
             // Throws error during parsing as u32
             let u32_overflow = format!("$argon2i$v=19$m={},t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA", u64::MAX);
 
+            // The minimum memory scales with the parallelism count.
+            let exact_min_lanes = "$argon2i$v=19$m=16,t=3,p=2$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let less_lanes = "$argon2i$v=19$m=15,t=3,p=2$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
             assert!(PasswordHash::from_encoded(exact_min).is_ok());
             assert!(PasswordHash::from_encoded(less).is_err());
             assert!(PasswordHash::from_encoded(&u32_overflow).is_err());
+            assert!(PasswordHash::from_encoded(exact_min_lanes).is_ok());
+            assert!(PasswordHash::from_encoded(less_lanes).is_err());
         }
 
         #[test]
         fn test_bad_encoding_invalid_iterations() {
-            let exact_min = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
-            let less = "$argon2i$v=19$m=65536,t=2,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let exact_min = "$argon2i$v=19$m=65536,t=1,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let less = "$argon2i$v=19$m=65536,t=0,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             // Throws error during parsing as u32
             let u32_overflow = format!("$argon2i$v=19$m=65536,t={},p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA", u64::MAX);
 
+            let argon2id_min = "$argon2id$v=19$m=65536,t=1,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let argon2id_less = "$argon2id$v=19$m=65536,t=0,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
             assert!(PasswordHash::from_encoded(exact_min).is_ok());
             assert!(PasswordHash::from_encoded(less).is_err());
             assert!(PasswordHash::from_encoded(&u32_overflow).is_err());
+            assert!(PasswordHash::from_encoded(argon2id_min).is_ok());
+            assert!(PasswordHash::from_encoded(argon2id_less).is_err());
         }
 
         #[test]
         fn test_bad_encoding_invalid_algo() {
             let argon2id = "$argon2id$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let argon2d = "$argon2d$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let argon2 = "$argon2$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let argon2x = "$argon2x$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let upper = "$ARGON2I$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let nothing = "$$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
 
-            assert!(PasswordHash::from_encoded(argon2d).is_err());
-            assert!(PasswordHash::from_encoded(argon2id).is_err());
+            assert!(PasswordHash::from_encoded(argon2d).is_ok());
+            assert!(PasswordHash::from_encoded(argon2id).is_ok());
+            assert!(PasswordHash::from_encoded(argon2).is_err());
+            assert!(PasswordHash::from_encoded(argon2x).is_err());
+            assert!(PasswordHash::from_encoded(upper).is_err());
             assert!(PasswordHash::from_encoded(nothing).is_err());
         }
 
@@ -657,25 +1400,86 @@ mod public {
             let v0 = "$argon2i$v=0$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let nothing = "$argon2i$v=$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
 
+            let v16 = "$argon2i$v=16$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let omitted = "$argon2i$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let no_v = "$argon2i$19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
             assert!(PasswordHash::from_encoded(v13).is_err());
             assert!(PasswordHash::from_encoded(v0).is_err());
             assert!(PasswordHash::from_encoded(nothing).is_err());
+            assert!(PasswordHash::from_encoded(no_v).is_err());
+            assert!(PasswordHash::from_encoded(v16).is_ok());
+            // Omitting the version means version 0x10, which is always re-encoded.
+            assert_eq!(
+                PasswordHash::from_encoded(omitted)
+                    .unwrap()
+                    .unprotected_as_encoded(),
+                v16
+            );
         }
 
         #[test]
-        fn test_bad_encoding_invalid_order() {
-            let version_first = "$v=19$argon2i$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+        fn test_encoding_any_param_order() {
+            let canonical = "$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let t_before_m = "$argon2i$v=19$t=3,m=65536,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let p_before_t = "$argon2i$v=19$m=65536,p=1,t=3$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let p_before_m = "$argon2i$v=19$p=1,m=65536,t=3$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
+            for encoded in [canonical, t_before_m, p_before_t, p_before_m].iter() {
+                let password_hash = PasswordHash::from_encoded(encoded).unwrap();
+                assert_eq!(password_hash.unprotected_as_encoded(), canonical);
+            }
+
+            let optional_first = "$argon2id$v=19$data=ZGF0YQ,keyid=aWQ,p=2,t=1,m=64$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            assert_eq!(
+                PasswordHash::from_encoded(optional_first)
+                    .unwrap()
+                    .unprotected_as_encoded(),
+                "$argon2id$v=19$m=64,t=1,p=2,keyid=aWQ,data=ZGF0YQ$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA"
+            );
+        }
+
+        #[test]
+        fn test_bad_encoding_params() {
+            let duplicate_m = "$argon2i$v=19$m=65536,t=3,p=1,m=65536$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let duplicate_keyid = "$argon2i$v=19$m=65536,t=3,p=1,keyid=aWQ,keyid=aWQ$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let missing_m = "$argon2i$v=19$t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let missing_t = "$argon2i$v=19$m=65536,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let missing_p = "$argon2i$v=19$m=65536,t=3$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let unknown = "$argon2i$v=19$m=65536,t=3,p=1,x=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let trailing_comma = "$argon2i$v=19$m=65536,t=3,p=1,$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let empty_keyid = "$argon2i$v=19$m=65536,t=3,p=1,keyid=$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let long_keyid = "$argon2i$v=19$m=65536,t=3,p=1,keyid=MDAwMDAwMDAw$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let max_keyid = "$argon2i$v=19$m=65536,t=3,p=1,keyid=MDAwMDAwMDA$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let long_data = "$argon2i$v=19$m=65536,t=3,p=1,data=MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAw$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let max_data = "$argon2i$v=19$m=65536,t=3,p=1,data=MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let bad_data = "$argon2i$v=19$m=65536,t=3,p=1,data=!!!!$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+
+            assert!(PasswordHash::from_encoded(duplicate_m).is_err());
+            assert!(PasswordHash::from_encoded(duplicate_keyid).is_err());
+            assert!(PasswordHash::from_encoded(missing_m).is_err());
+            assert!(PasswordHash::from_encoded(missing_t).is_err());
+            assert!(PasswordHash::from_encoded(missing_p).is_err());
+            assert!(PasswordHash::from_encoded(unknown).is_err());
+            assert!(PasswordHash::from_encoded(trailing_comma).is_err());
+            assert!(PasswordHash::from_encoded(empty_keyid).is_err());
+            assert!(PasswordHash::from_encoded(long_keyid).is_err());
+            assert!(PasswordHash::from_encoded(max_keyid).is_ok());
+            assert!(PasswordHash::from_encoded(long_data).is_err());
+            assert!(PasswordHash::from_encoded(max_data).is_ok());
+            assert!(PasswordHash::from_encoded(bad_data).is_err());
+        }
+
+        #[test]
+        fn test_bad_encoding_invalid_order() {
+            let version_first = "$v=19$argon2i$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let params_before_version = "$argon2i$m=65536,t=3,p=1$v=19$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let pass_before_salt = "$argon2i$v=19$m=65536,t=3,p=1$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA$cHBwcHBwcHBwcHBwcHBwcA";
             let salt_first = "$cHBwcHBwcHBwcHBwcHBwcA$argon2i$v=19$m=65536,t=3,p=1$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             let pass_first = "$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA$argon2i$v=19$m=65536,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA";
 
             assert!(PasswordHash::from_encoded(version_first).is_err());
-            assert!(PasswordHash::from_encoded(t_before_m).is_err());
-            assert!(PasswordHash::from_encoded(p_before_t).is_err());
-            assert!(PasswordHash::from_encoded(p_before_m).is_err());
+            assert!(PasswordHash::from_encoded(params_before_version).is_err());
             assert!(PasswordHash::from_encoded(pass_before_salt).is_err());
             assert!(PasswordHash::from_encoded(salt_first).is_err());
             assert!(PasswordHash::from_encoded(pass_first).is_err());
@@ -749,16 +1553,16 @@ mod public {
 
         #[test]
         fn test_bounds_max_min_encoded_len() {
-            let minimum = "$argon2i$v=19$m=8,t=3,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let minimum = "$argon2d$m=8,t=1,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             assert_eq!(minimum.len(), PasswordHash::MIN_ENCODED_LEN);
-            let maximum = "$argon2i$v=19$m=1111111111,t=1111111111,p=1$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let maximum = "$argon2id$v=19$m=4294967295,t=4294967295,p=16777215,keyid=MDAwMDAwMDA,data=MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA$cHBwcHBwcHBwcHBwcHBwcA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             assert_eq!(maximum.len(), PasswordHash::MAX_ENCODED_LEN);
 
             // salt removed one char
-            let less = "$argon2i$v=19$m=8,t=3,p=1$cHBwcHBwcHBwcHBwcHBwc$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let less = "$argon2d$m=8,t=1,p=1$cHBwcHBwcHBwcHBwcHBwc$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             assert_eq!(less.len(), PasswordHash::MIN_ENCODED_LEN - 1);
             // salt added one char
-            let more = "$argon2i$v=19$m=1111111111,t=1111111111,p=1$cHBwcHBwcHBwcHBwcHBwcAA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
+            let more = "$argon2id$v=19$m=4294967295,t=4294967295,p=16777215,keyid=MDAwMDAwMDA,data=MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA$cHBwcHBwcHBwcHBwcHBwcAA$MDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDAwMDA";
             assert_eq!(more.len(), PasswordHash::MAX_ENCODED_LEN + 1);
 
             assert!(PasswordHash::from_encoded(minimum).is_ok());
@@ -813,6 +1617,57 @@ mod public {
         }
     }
 
+    mod test_hash_password_checked {
+        use super::*;
+
+        fn entropy(pw: &[u8]) -> u32 {
+            estimate_entropy_bits(&Password::from_slice(pw).unwrap())
+        }
+
+        #[test]
+        fn test_estimate_entropy_bits() {
+            assert_eq!(entropy(b"password"), 0);
+            assert_eq!(entropy(b"PassWord"), 0);
+            assert_eq!(entropy(b"letmein"), 0);
+            assert_eq!(entropy(b"aaaaaaaaaaaaaaaa"), 4 + 15);
+            assert_eq!(entropy(b"abcdefghijklmnop"), 4 + 15);
+            assert_eq!(entropy(b"9876543210"), 3 + 9);
+            // 8 lowercase characters, with no repeats or sequences.
+            assert_eq!(entropy(b"xkqdmzpt"), 37);
+            // Adding more classes grows the pool.
+            assert!(entropy(b"xK3!mZpT") > entropy(b"xkqdmzpt"));
+            assert!(entropy("xkqdmzpé".as_bytes()) > entropy(b"xK3!mZpT"));
+            assert!(entropy(b"correct horse battery staple") > 100);
+            // Not valid UTF-8.
+            assert!(entropy(&[0xff, 0x00, 0x80, 0x7f]) > 0);
+        }
+
+        #[test]
+        fn test_weak_password_rejected() {
+            let password = Password::from_slice(b"123456").unwrap();
+            let err = hash_password_checked(&password, 3, 64, 1).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::WeakPassword);
+
+            let password = Password::from_slice(b"xkqdmzpt").unwrap();
+            assert_eq!(
+                hash_password_checked(&password, 3, 64, 38)
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::WeakPassword
+            );
+            let hash = hash_password_checked(&password, 3, 64, 37).unwrap();
+            assert!(hash_password_verify(&hash, &password).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_parameters_still_rejected() {
+            let password = Password::from_slice(b"correct horse battery staple").unwrap();
+            assert!(hash_password_checked(&password, 2, 64, 0).is_err());
+            assert!(hash_password_checked(&password, 3, 7, 0).is_err());
+            assert!(hash_password_checked(&password, 3, 64, 0).is_ok());
+        }
+    }
+
     mod test_pwhash_and_verify {
         use super::*;
 
@@ -0,0 +1,276 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Server relief, where the client computes the memory-hard part of password hashing.
+//!
+//! # Use case:
+//! `orion::pwhash::relief` can be used by login systems with many users, where computing
+//! Argon2i for every login would cost the server too much. The client computes Argon2i
+//! over the password and sends the resulting [`PreHash`] to the server, which only has
+//! to compute a keyed BLAKE2b hash of it.
+//!
+//! # About:
+//! - The client uses Argon2i with a salt that the server provides, resulting in a
+//!   32-byte [`PreHash`].
+//! - The server stores BLAKE2b-256 keyed with a [`ServerKey`] over the [`PreHash`]. An
+//!   attacker that obtains the stored [`StoredHash`]es must also obtain the [`ServerKey`]
+//!   before an offline attack on the passwords is possible, and must then compute Argon2i
+//!   for every guess.
+//!
+//! # Parameters:
+//! - `password`: The password of the user.
+//! - `salt`: The salt of the user, provided by the server.
+//! - `iterations`: Iterations cost parameter for Argon2i.
+//! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2i.
+//! - `server_key`: The secret key of the server.
+//! - `pre_hash`: The output of [`client_pre_hash()`], received from the client.
+//! - `expected`: The [`StoredHash`] of the user, from [`server_finish()`].
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `iterations` is less than 3.
+//! - `memory` is less than 8.
+//! - The length of the `password` is greater than [`isize::MAX`].
+//! - The [`StoredHash`] computed from `pre_hash` does not match `expected`.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely, when using [`ServerKey::generate()`].
+//!
+//! # Security:
+//! - The [`PreHash`] is equivalent to the password towards the server. It must only be
+//!   sent over an encrypted and authenticated channel, and never be stored or logged.
+//! - The server must hand out the same salt and cost parameters for a user on every
+//!   login. To not reveal which users exist, it can derive salts for unknown users with
+//!   [`Salt::derive_from_identifier()`], from the user name and a value unique to the
+//!   deployment.
+//! - The server should enforce minimum cost parameters itself, since a client decides
+//!   how to compute the [`PreHash`].
+//! - The [`ServerKey`] should be kept apart from the database of [`StoredHash`]es, e.g.
+//!   in a hardware security module or a secrets manager.
+//!
+//! # Example:
+//! ```rust
+//! use orion::pwhash::relief;
+//!
+//! let server_key = relief::ServerKey::generate();
+//! let salt = relief::Salt::derive_from_identifier(b"example.com/users/alice")?;
+//!
+//! // At sign-up, the client pre-hashes the password, and the server stores the result.
+//! let password = relief::Password::from_slice(b"Secret password")?;
+//! let pre_hash = relief::client_pre_hash(&password, &salt, 3, 1<<16)?;
+//! let stored = relief::server_finish(&server_key, &pre_hash)?;
+//!
+//! // At login, the client pre-hashes the password again, and the server verifies it.
+//! let pre_hash = relief::client_pre_hash(&password, &salt, 3, 1<<16)?;
+//! assert!(relief::server_verify(&stored, &server_key, &pre_hash).is_ok());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::blake2b::{self, Blake2b};
+use crate::hazardous::kdf::argon2i;
+pub use crate::high_level::hltypes::{Password, Salt};
+use crate::pwhash::MIN_ITERATIONS;
+
+/// The size of a [`PreHash`].
+pub const PRE_HASH_SIZE: usize = 32;
+/// The size of a [`StoredHash`].
+const STORED_HASH_SIZE: usize = 32;
+
+construct_secret_key! {
+    /// A type to represent the `PreHash` that the client computes with Argon2i.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (PreHash, test_pre_hash, PRE_HASH_SIZE, PRE_HASH_SIZE, PRE_HASH_SIZE)
+}
+
+impl_from_trait!(PreHash, PRE_HASH_SIZE);
+
+construct_secret_key! {
+    /// A type to represent the secret `ServerKey` that a [`PreHash`] is hashed with.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    ///
+    /// # Panics:
+    /// A panic will occur if:
+    /// - Failure to generate random bytes securely.
+    (ServerKey, test_server_key, 32, 32, 32)
+}
+
+impl_from_trait!(ServerKey, 32);
+
+construct_tag! {
+    /// A type to represent the `StoredHash` that the server stores for a user.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    (StoredHash, test_stored_hash, STORED_HASH_SIZE, STORED_HASH_SIZE)
+}
+
+impl_from_trait!(StoredHash, STORED_HASH_SIZE);
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compute the [`PreHash`] of `password` on the client, using Argon2i.
+pub fn client_pre_hash(
+    password: &Password,
+    salt: &Salt,
+    iterations: u32,
+    memory: u32,
+) -> Result<PreHash, UnknownCryptoError> {
+    if iterations < MIN_ITERATIONS {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    let mut pre_hash = PreHash::from_slice(&[0u8; PRE_HASH_SIZE])?;
+    argon2i::derive_key(
+        password.unprotected_as_bytes(),
+        salt.as_ref(),
+        iterations,
+        memory,
+        None,
+        None,
+        &mut pre_hash.value,
+    )?;
+
+    Ok(pre_hash)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compute the [`StoredHash`] of a [`PreHash`] on the server, using BLAKE2b-256 keyed
+/// with `server_key`.
+pub fn server_finish(
+    server_key: &ServerKey,
+    pre_hash: &PreHash,
+) -> Result<StoredHash, UnknownCryptoError> {
+    let key = blake2b::SecretKey::from_slice(server_key.unprotected_as_bytes())?;
+    let mut ctx = Blake2b::new(Some(&key), STORED_HASH_SIZE)?;
+    ctx.update(pre_hash.unprotected_as_bytes())?;
+
+    StoredHash::from_slice(ctx.finalize()?.as_ref())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Verify a [`PreHash`] received from the client against the [`StoredHash`] of the
+/// user, in constant time.
+pub fn server_verify(
+    expected: &StoredHash,
+    server_key: &ServerKey,
+    pre_hash: &PreHash,
+) -> Result<(), UnknownCryptoError> {
+    if &server_finish(server_key, pre_hash)? == expected {
+        Ok(())
+    } else {
+        Err(UnknownCryptoError::new(ErrorKind::MacMismatch))
+    }
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn password() -> Password {
+        Password::from_slice(b"password").unwrap()
+    }
+
+    fn salt() -> Salt {
+        Salt::from_slice(b"somesaltsomesalt").unwrap()
+    }
+
+    #[test]
+    fn test_pre_hash_is_argon2i() {
+        let pre_hash = client_pre_hash(&password(), &salt(), 3, 64).unwrap();
+        let mut expected = [0u8; PRE_HASH_SIZE];
+        argon2i::derive_key(
+            b"password",
+            b"somesaltsomesalt",
+            3,
+            64,
+            None,
+            None,
+            &mut expected,
+        )
+        .unwrap();
+        assert!(pre_hash == &expected[..]);
+    }
+
+    #[test]
+    fn test_stored_hash_is_keyed_blake2b() {
+        let server_key = ServerKey::from_slice(&[7u8; 32]).unwrap();
+        let pre_hash = PreHash::from_slice(&[1u8; 32]).unwrap();
+        let stored = server_finish(&server_key, &pre_hash).unwrap();
+
+        let key = blake2b::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let mut ctx = Blake2b::new(Some(&key), 32).unwrap();
+        ctx.update(&[1u8; 32]).unwrap();
+        assert!(stored == ctx.finalize().unwrap().as_ref());
+    }
+
+    #[test]
+    fn test_verify() {
+        let server_key = ServerKey::generate();
+        let pre_hash = client_pre_hash(&password(), &salt(), 3, 64).unwrap();
+        let stored = server_finish(&server_key, &pre_hash).unwrap();
+
+        let login = client_pre_hash(&password(), &salt(), 3, 64).unwrap();
+        assert!(server_verify(&stored, &server_key, &login).is_ok());
+    }
+
+    #[test]
+    fn test_verify_fails() {
+        let server_key = ServerKey::generate();
+        let pre_hash = client_pre_hash(&password(), &salt(), 3, 64).unwrap();
+        let stored = server_finish(&server_key, &pre_hash).unwrap();
+
+        let wrong_password = Password::from_slice(b"pass").unwrap();
+        let other_salt = Salt::from_slice(b"othersaltothersa").unwrap();
+        let wrong = [
+            client_pre_hash(&wrong_password, &salt(), 3, 64).unwrap(),
+            client_pre_hash(&password(), &other_salt, 3, 64).unwrap(),
+            client_pre_hash(&password(), &salt(), 4, 64).unwrap(),
+            client_pre_hash(&password(), &salt(), 3, 128).unwrap(),
+        ];
+        for pre_hash in wrong.iter() {
+            let err = server_verify(&stored, &server_key, pre_hash).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::MacMismatch);
+        }
+
+        let other_key = ServerKey::generate();
+        assert!(server_verify(&stored, &other_key, &pre_hash).is_err());
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(client_pre_hash(&password(), &salt(), 2, 64).is_err());
+        assert!(client_pre_hash(&password(), &salt(), 3, 7).is_err());
+    }
+}
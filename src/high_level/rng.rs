@@ -0,0 +1,464 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Fast userspace CSPRNG.
+//!
+//! # Use case:
+//! `orion::rng` can be used when large amounts of random data are needed, such
+//! as generating many nonces, filling buffers with random test data or as a
+//! source of randomness for other crates through [`rand_core`].
+//!
+//! [`util::secure_rand_bytes()`] makes a call to the OS for every request, which is
+//! slow when it is done many times. [`OrionRng`] is seeded from the OS once and then
+//! generates random data in userspace.
+//!
+//! # About:
+//! - The state is a single 256-bit ChaCha20 key. Each time random data is needed,
+//!   ChaCha20 keystream is generated with this key and an all-zero nonce. The first
+//!   32 bytes of the keystream replace the key, and the rest is output. Once
+//!   output, bytes are erased from the state, so a compromised state does not
+//!   reveal earlier output.
+//! - [`OrionRng::new()`] seeds the generator with [`getrandom`] and reseeds it
+//!   automatically after every 16 MiB of output. [`OrionRng::reseed()`] can be used
+//!   to mix in fresh randomness from the OS at any time.
+//! - [`OrionRng::from_seed()`] creates a deterministic generator, which produces
+//!   the same output every time it is given the same seed and the same sequence of
+//!   requests. It is never reseeded automatically.
+//! - With the `rand_core` feature enabled, [`OrionRng`] implements
+//!   `rand_core::TryRng` and `rand_core::TryCryptoRng` (and therefore `Rng` and
+//!   `CryptoRng`), as well as `rand_core::SeedableRng`.
+//!
+//! # Parameters:
+//! - `seed`: The seed for a deterministic generator.
+//! - `dst`: Destination buffer for the random bytes. The amount of bytes to be
+//!   generated is implied by the length of `dst`.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `dst` is empty.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely from the OS.
+//! - The platform is not supported by [`getrandom`].
+//!
+//! # Security:
+//! - A generator created with [`OrionRng::from_seed()`] is only as unpredictable as
+//!   its seed. It should be used for reproducible tests and must not be used with a
+//!   low-entropy or hard-coded seed for anything that needs to be secret.
+//! - After a process is forked, both processes hold the same state and will produce
+//!   the same output. Call [`OrionRng::reseed()`] in the child, or create a new
+//!   [`OrionRng`] after forking.
+//! - Prefer the `generate()` functions of orion's key and nonce types when these
+//!   are needed.
+//!
+//! # Example:
+//! ```rust
+//! use orion::rng::OrionRng;
+//!
+//! let mut rng = OrionRng::new()?;
+//! let mut nonces = [[0u8; 24]; 100];
+//! for nonce in nonces.iter_mut() {
+//!     rng.fill_bytes(nonce)?;
+//! }
+//!
+//! // A deterministic generator for reproducible tests.
+//! let mut first = OrionRng::from_seed([7u8; 32]);
+//! let mut second = OrionRng::from_seed([7u8; 32]);
+//! let mut out_first = [0u8; 1024];
+//! let mut out_second = [0u8; 1024];
+//! first.fill_bytes(&mut out_first)?;
+//! second.fill_bytes(&mut out_second)?;
+//! assert_eq!(out_first, out_second);
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [`util::secure_rand_bytes()`]: crate::util::secure_rand_bytes
+//! [`getrandom`]: https://github.com/rust-random/getrandom
+//! [`rand_core`]: https://crates.io/crates/rand_core
+
+use crate::{
+    errors::{ErrorKind, UnknownCryptoError},
+    hazardous::stream::chacha20::{
+        xor_keystream, ChaCha20, CHACHA_BLOCKSIZE, CHACHA_KEYSIZE, IETF_CHACHA_NONCESIZE,
+        KEYSTREAM_BUFSIZE,
+    },
+};
+use zeroize::Zeroize;
+
+/// The size (bytes) of a seed for [`OrionRng::from_seed()`].
+pub const SEED_SIZE: usize = CHACHA_KEYSIZE;
+/// The amount of output (bytes) after which [`OrionRng`] is reseeded from the OS.
+const RESEED_INTERVAL: u64 = 16 * 1024 * 1024;
+/// The maximum amount of bytes generated with a single key when filling large
+/// destination buffers directly.
+const MAX_BULK_SIZE: usize = 1024 * 1024;
+
+/// A ChaCha20-based userspace CSPRNG. See the [module-level](self) documentation.
+pub struct OrionRng {
+    key: [u8; CHACHA_KEYSIZE],
+    buffer: [u8; KEYSTREAM_BUFSIZE],
+    position: usize,
+    bytes_since_reseed: u64,
+    is_deterministic: bool,
+}
+
+impl Drop for OrionRng {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl core::fmt::Debug for OrionRng {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "OrionRng {{ key: [***OMITTED***], buffer: [***OMITTED***], position: {:?}, \
+            bytes_since_reseed: {:?}, is_deterministic: {:?} }}",
+            self.position, self.bytes_since_reseed, self.is_deterministic
+        )
+    }
+}
+
+/// Generate keystream with `key` into `dst`, after replacing `key` with the first
+/// 32 bytes of the keystream. `dst` must be zeroed and at most `MAX_BULK_SIZE` bytes.
+///
+/// The key and nonce are always valid and `dst` is within the keystream of a single
+/// nonce, so none of the `.unwrap()` calls can panic.
+#[allow(clippy::unwrap_used)]
+fn generate_and_rekey(key: &mut [u8; CHACHA_KEYSIZE], dst: &mut [u8]) {
+    debug_assert!(!dst.is_empty());
+    debug_assert!(dst.len() <= MAX_BULK_SIZE);
+    let mut ctx = ChaCha20::new(key, &[0u8; IETF_CHACHA_NONCESIZE], true).unwrap();
+    let mut tmp = [0u8; KEYSTREAM_BUFSIZE];
+
+    let mut first_block = [0u8; CHACHA_BLOCKSIZE];
+    ctx.keystream_block(0, &mut first_block).unwrap();
+    key.copy_from_slice(&first_block[..CHACHA_KEYSIZE]);
+    // The first block only provides the new key, so that the rest of the
+    // keystream can be written directly into `dst`.
+    xor_keystream(&mut ctx, 1, &mut tmp, dst).unwrap();
+
+    first_block.zeroize();
+    tmp.zeroize();
+}
+
+impl OrionRng {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create a new generator, seeded from the OS.
+    pub fn new() -> Result<Self, UnknownCryptoError> {
+        let mut seed = [0u8; SEED_SIZE];
+        crate::util::secure_rand_bytes(&mut seed)?;
+        let mut rng = Self::from_seed(seed);
+        rng.is_deterministic = false;
+        seed.zeroize();
+
+        Ok(rng)
+    }
+
+    /// Create a new deterministic generator from `seed`.
+    pub fn from_seed(seed: [u8; SEED_SIZE]) -> Self {
+        Self {
+            key: seed,
+            buffer: [0u8; KEYSTREAM_BUFSIZE],
+            position: KEYSTREAM_BUFSIZE,
+            bytes_since_reseed: 0,
+            is_deterministic: true,
+        }
+    }
+
+    /// Mix fresh randomness from the OS into the state and discard any buffered
+    /// output. A generator created with [`OrionRng::from_seed()`] is no longer
+    /// deterministic after this and will be reseeded automatically from then on.
+    pub fn reseed(&mut self) {
+        let mut fresh = [0u8; SEED_SIZE];
+        crate::util::secure_rand_bytes_or_panic(&mut fresh);
+        xor_slices!(fresh, self.key);
+        fresh.zeroize();
+
+        self.buffer.zeroize();
+        self.position = KEYSTREAM_BUFSIZE;
+        self.bytes_since_reseed = 0;
+        self.is_deterministic = false;
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Fill `dst` with random bytes.
+    pub fn fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), UnknownCryptoError> {
+        if dst.is_empty() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+
+        self.fill(dst);
+
+        Ok(())
+    }
+
+    /// Fill `dst` with random bytes, which may be empty.
+    fn fill(&mut self, dst: &mut [u8]) {
+        if !self.is_deterministic && self.bytes_since_reseed >= RESEED_INTERVAL {
+            self.reseed();
+        }
+
+        let dst_len = dst.len() as u64;
+        let mut remaining = dst;
+        while !remaining.is_empty() {
+            if self.position == KEYSTREAM_BUFSIZE {
+                if remaining.len() >= KEYSTREAM_BUFSIZE {
+                    // Large requests are generated directly into the destination.
+                    let bulk_len = if remaining.len() > MAX_BULK_SIZE {
+                        MAX_BULK_SIZE
+                    } else {
+                        remaining.len()
+                    };
+                    let (bulk, rest) = remaining.split_at_mut(bulk_len);
+                    bulk.iter_mut().for_each(|b| *b = 0u8);
+                    generate_and_rekey(&mut self.key, bulk);
+                    remaining = rest;
+                    continue;
+                }
+
+                // Buffered bytes are always zeroed once output.
+                generate_and_rekey(&mut self.key, &mut self.buffer);
+                self.position = 0;
+            }
+
+            let available = &mut self.buffer[self.position..];
+            let take = if available.len() < remaining.len() {
+                available.len()
+            } else {
+                remaining.len()
+            };
+            let (out, rest) = remaining.split_at_mut(take);
+            out.copy_from_slice(&available[..take]);
+            // Erase output from the state.
+            available[..take].zeroize();
+            self.position += take;
+            remaining = rest;
+        }
+
+        self.bytes_since_reseed = self.bytes_since_reseed.saturating_add(dst_len);
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::TryRng for OrionRng {
+    type Error = core::convert::Infallible;
+
+    fn try_next_u32(&mut self) -> Result<u32, Self::Error> {
+        let mut bytes = [0u8; 4];
+        self.fill(&mut bytes);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Self::Error> {
+        let mut bytes = [0u8; 8];
+        self.fill(&mut bytes);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn try_fill_bytes(&mut self, dst: &mut [u8]) -> Result<(), Self::Error> {
+        self.fill(dst);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::TryCryptoRng for OrionRng {}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for OrionRng {
+    type Seed = [u8; SEED_SIZE];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        OrionRng::from_seed(seed)
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+    use crate::hazardous::stream::chacha20;
+
+    #[test]
+    fn test_fill_bytes_empty_err() {
+        let mut rng = OrionRng::new().unwrap();
+        assert!(rng.fill_bytes(&mut [0u8; 0]).is_err());
+        assert!(rng.fill_bytes(&mut [0u8; 1]).is_ok());
+    }
+
+    #[test]
+    fn test_new_not_deterministic() {
+        let mut first = OrionRng::new().unwrap();
+        let mut second = OrionRng::new().unwrap();
+        let mut out_first = [0u8; 64];
+        let mut out_second = [0u8; 64];
+        first.fill_bytes(&mut out_first).unwrap();
+        second.fill_bytes(&mut out_second).unwrap();
+
+        assert_ne!(out_first[..], out_second[..]);
+        assert_ne!(out_first[..], [0u8; 64][..]);
+    }
+
+    #[test]
+    fn test_from_seed_deterministic() {
+        let mut first = OrionRng::from_seed([1u8; SEED_SIZE]);
+        let mut second = OrionRng::from_seed([1u8; SEED_SIZE]);
+        let mut other = OrionRng::from_seed([2u8; SEED_SIZE]);
+
+        for len in [1, 31, 64, 255, 256, 257, 1000, MAX_BULK_SIZE + 1].iter() {
+            let mut out_first = vec![0u8; *len];
+            let mut out_second = vec![0u8; *len];
+            let mut out_other = vec![0u8; *len];
+            first.fill_bytes(&mut out_first).unwrap();
+            second.fill_bytes(&mut out_second).unwrap();
+            other.fill_bytes(&mut out_other).unwrap();
+
+            assert_eq!(out_first, out_second);
+            assert_ne!(out_first, out_other);
+        }
+    }
+
+    #[test]
+    fn test_from_seed_output_is_chacha20_keystream() {
+        let seed = [3u8; SEED_SIZE];
+        let key = chacha20::SecretKey::from_slice(&seed).unwrap();
+        let nonce = chacha20::Nonce::from([0u8; IETF_CHACHA_NONCESIZE]);
+        let mut keystream = [0u8; 5 * CHACHA_BLOCKSIZE];
+        chacha20::encrypt(
+            &key,
+            &nonce,
+            0,
+            &[0u8; 5 * CHACHA_BLOCKSIZE],
+            &mut keystream,
+        )
+        .unwrap();
+
+        // The first block becomes the next key and the rest is output.
+        let mut rng = OrionRng::from_seed(seed);
+        let mut out = [0u8; 4 * CHACHA_BLOCKSIZE];
+        rng.fill_bytes(&mut out[..10]).unwrap();
+        rng.fill_bytes(&mut out[10..]).unwrap();
+        assert_eq!(out[..], keystream[CHACHA_BLOCKSIZE..]);
+        assert_eq!(rng.key[..], keystream[..CHACHA_KEYSIZE]);
+
+        let mut rng = OrionRng::from_seed(seed);
+        let mut out = [0u8; 4 * CHACHA_BLOCKSIZE];
+        rng.fill_bytes(&mut out).unwrap();
+        assert_eq!(out[..], keystream[CHACHA_BLOCKSIZE..]);
+    }
+
+    #[test]
+    fn test_large_output_does_not_repeat() {
+        let mut rng = OrionRng::from_seed([4u8; SEED_SIZE]);
+        let mut out = vec![0u8; MAX_BULK_SIZE * 3];
+        rng.fill_bytes(&mut out).unwrap();
+
+        let mut chunks = out.chunks(MAX_BULK_SIZE);
+        let first = chunks.next().unwrap();
+        for chunk in chunks {
+            assert_ne!(first, chunk);
+            assert_ne!(&first[..64], &chunk[..64]);
+        }
+    }
+
+    #[test]
+    fn test_reseed() {
+        let mut rng = OrionRng::from_seed([5u8; SEED_SIZE]);
+        let mut twin = OrionRng::from_seed([5u8; SEED_SIZE]);
+        let mut out = [0u8; 32];
+        let mut out_twin = [0u8; 32];
+        rng.fill_bytes(&mut out).unwrap();
+        twin.fill_bytes(&mut out_twin).unwrap();
+        assert_eq!(out, out_twin);
+
+        rng.reseed();
+        assert!(!rng.is_deterministic);
+        rng.fill_bytes(&mut out).unwrap();
+        twin.fill_bytes(&mut out_twin).unwrap();
+        assert_ne!(out, out_twin);
+    }
+
+    #[test]
+    fn test_automatic_reseed() {
+        // A deterministic generator is never reseeded.
+        let mut rng = OrionRng::from_seed([6u8; SEED_SIZE]);
+        let mut twin = OrionRng::from_seed([6u8; SEED_SIZE]);
+        rng.bytes_since_reseed = RESEED_INTERVAL;
+        let mut out = [0u8; 32];
+        let mut out_twin = [0u8; 32];
+        rng.fill_bytes(&mut out).unwrap();
+        twin.fill_bytes(&mut out_twin).unwrap();
+        assert_eq!(out, out_twin);
+
+        let mut rng = OrionRng::from_seed([6u8; SEED_SIZE]);
+        let twin_key = rng.key;
+        rng.is_deterministic = false;
+        rng.bytes_since_reseed = RESEED_INTERVAL;
+        rng.fill_bytes(&mut out).unwrap();
+        assert_eq!(rng.bytes_since_reseed, 32);
+        assert_ne!(rng.key, twin_key);
+        rng.fill_bytes(&mut out).unwrap();
+        assert_ne!(out, out_twin);
+    }
+
+    #[test]
+    fn test_output_is_erased_from_state() {
+        let mut rng = OrionRng::from_seed([7u8; SEED_SIZE]);
+        let mut out = [0u8; 100];
+        rng.fill_bytes(&mut out).unwrap();
+        assert_eq!(rng.position, 100);
+        assert_eq!(rng.buffer[..100], [0u8; 100][..]);
+        assert_ne!(rng.buffer[100..], [0u8; KEYSTREAM_BUFSIZE - 100][..]);
+    }
+
+    #[test]
+    fn test_debug_impl() {
+        let rng = OrionRng::from_seed([0u8; SEED_SIZE]);
+        let debug = format!("{:?}", rng);
+        let expected = "OrionRng { key: [***OMITTED***], buffer: [***OMITTED***], position: 256, \
+        bytes_since_reseed: 0, is_deterministic: true }";
+        assert_eq!(debug, expected);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn test_rand_core() {
+        use rand_core::{Rng, SeedableRng};
+
+        let mut rng = <OrionRng as SeedableRng>::from_seed([8u8; SEED_SIZE]);
+        let mut twin = OrionRng::from_seed([8u8; SEED_SIZE]);
+
+        let mut expected = [0u8; 12];
+        twin.fill_bytes(&mut expected).unwrap();
+        assert_eq!(rng.next_u32().to_le_bytes()[..], expected[..4]);
+        assert_eq!(rng.next_u64().to_le_bytes()[..], expected[4..]);
+
+        // Empty destinations are allowed by rand_core.
+        Rng::fill_bytes(&mut rng, &mut []);
+        let mut expected = [0u8; 300];
+        let mut actual = [0u8; 300];
+        twin.fill_bytes(&mut expected).unwrap();
+        Rng::fill_bytes(&mut rng, &mut actual);
+        assert_eq!(actual[..], expected[..]);
+    }
+}
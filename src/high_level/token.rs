@@ -0,0 +1,272 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Encrypted tokens with an expiry time.
+//!
+//! # Use case:
+//! `orion::token` can be used to create tokens that are only handed out and read by the
+//! same service, such as session tokens or the links for resetting a password.
+//! The payload of a token is encrypted and authenticated, and a token is only
+//! accepted for a limited time after it was created.
+//!
+//! # About:
+//! - This is similar to [Fernet], but is not compatible with it.
+//! - A token is the URL-safe base64 encoding, without padding, of a version byte, the
+//!   creation time in seconds since the Unix epoch as a big-endian `u64`, and the
+//!   payload sealed with XChaCha20Poly1305. The version and creation time are
+//!   authenticated as additional data.
+//! - [`open()`] only accepts a token that was created at most `ttl` seconds ago.
+//!   Tokens that were created more than [`MAX_CLOCK_SKEW`] seconds in the future are
+//!   also rejected.
+//! - [`seal_at()`] and [`open_at()`] take the current time as a parameter, instead of
+//!   reading the system clock.
+//!
+//! # Parameters:
+//! - `secret_key`: The secret key.
+//! - `payload`: The data to be encrypted into the token.
+//! - `token`: The token to open.
+//! - `ttl`: For how many seconds after its creation a token is valid.
+//! - `now`: The current time, in seconds since the Unix epoch.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `secret_key` is not 32 bytes.
+//! - `payload` is empty.
+//! - `token` is not valid URL-safe base64, or has an unknown version.
+//! - `token` was not created with `secret_key`, or has been modified.
+//! - `token` is older than `ttl`, or is from the future. The [`ErrorKind`] is then
+//!   [`ErrorKind::Expired`].
+//! - The system clock is set to before the Unix epoch.
+//!
+//! # Security:
+//! - The secret key should always be generated using a CSPRNG.
+//!   [`SecretKey::default()`] can be used for this; it will generate a [`SecretKey`] of
+//!   32 bytes.
+//! - A token can be opened any number of times until it expires. If it must only be used
+//!   once, such as for a password reset, the service has to record that it was used.
+//! - The creation time is not encrypted, so anyone with the token can see when it was
+//!   created.
+//!
+//! # Example:
+//! ```rust
+//! use orion::token;
+//!
+//! let secret_key = token::SecretKey::default();
+//! let reset_token = token::seal(&secret_key, b"reset-password:user-1")?;
+//!
+//! // The token is valid for one hour.
+//! let payload = token::open(&secret_key, &reset_token, 3600)?;
+//! assert_eq!(payload, b"reset-password:user-1");
+//!
+//! // A token that was created two hours ago has expired.
+//! let now = 1_600_000_000;
+//! let old_token = token::seal_at(&secret_key, b"session:user-1", now - 7200)?;
+//! assert!(token::open_at(&secret_key, &old_token, 3600, now).is_err());
+//! # Ok::<(), orion::errors::UnknownCryptoError>(())
+//! ```
+//! [Fernet]: https://github.com/fernet/spec/blob/master/Spec.md
+
+use super::aead::{open_into_with_ad, seal_into_with_ad, SEAL_OVERHEAD};
+pub use super::hltypes::SecretKey;
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use ct_codecs::{Base64UrlSafeNoPadding, Decoder, Encoder};
+
+/// The version byte of tokens.
+const VERSION: u8 = 0x01;
+
+/// The length of the version byte and the `u64` creation time that start a token.
+const HEADER_LEN: usize = 9;
+
+/// How many seconds in the future the creation time of a token may be.
+pub const MAX_CLOCK_SKEW: u64 = 60;
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Create a token that holds `payload`, using the system time as its creation time.
+pub fn seal(secret_key: &SecretKey, payload: &[u8]) -> Result<String, UnknownCryptoError> {
+    seal_at(secret_key, payload, super::unix_time()?)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Create a token that holds `payload`, with `now` as its creation time.
+pub fn seal_at(
+    secret_key: &SecretKey,
+    payload: &[u8],
+    now: u64,
+) -> Result<String, UnknownCryptoError> {
+    let out_len = match payload.len().checked_add(HEADER_LEN + SEAL_OVERHEAD) {
+        Some(out_len) => out_len,
+        None => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+    };
+
+    let mut raw = vec![0u8; out_len];
+    raw[0] = VERSION;
+    raw[1..HEADER_LEN].copy_from_slice(&now.to_be_bytes());
+    let (header, sealed) = raw.split_at_mut(HEADER_LEN);
+    seal_into_with_ad(secret_key, payload, Some(header), sealed)?;
+
+    Ok(Base64UrlSafeNoPadding::encode_to_string(raw)?)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Open `token` and return its payload, if it was created at most `ttl` seconds ago
+/// according to the system time.
+pub fn open(secret_key: &SecretKey, token: &str, ttl: u64) -> Result<Vec<u8>, UnknownCryptoError> {
+    open_at(secret_key, token, ttl, super::unix_time()?)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Open `token` and return its payload, if it was created at most `ttl` seconds before `now`.
+pub fn open_at(
+    secret_key: &SecretKey,
+    token: &str,
+    ttl: u64,
+    now: u64,
+) -> Result<Vec<u8>, UnknownCryptoError> {
+    let raw = Base64UrlSafeNoPadding::decode_to_vec(token, None)?;
+    // Avoid empty payloads
+    if raw.len() <= HEADER_LEN + SEAL_OVERHEAD {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+    }
+    if raw[0] != VERSION {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+    }
+
+    let (header, sealed) = raw.split_at(HEADER_LEN);
+    let mut payload = vec![0u8; sealed.len() - SEAL_OVERHEAD];
+    open_into_with_ad(secret_key, sealed, Some(header), &mut payload)?;
+
+    // The creation time is only trusted once the token has been authenticated.
+    let mut created = [0u8; 8];
+    created.copy_from_slice(&header[1..]);
+    let created = u64::from_be_bytes(created);
+    if now.saturating_add(MAX_CLOCK_SKEW) < created || now > created.saturating_add(ttl) {
+        return Err(UnknownCryptoError::new(ErrorKind::Expired));
+    }
+
+    Ok(payload)
+}
+
+// Testing public functions in the module.
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    const NOW: u64 = 1_600_000_000;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let sk = SecretKey::default();
+        let token = seal_at(&sk, b"payload", NOW).unwrap();
+        assert_eq!(open_at(&sk, &token, 10, NOW).unwrap(), b"payload");
+        assert_eq!(
+            token.len(),
+            Base64UrlSafeNoPadding::encoded_len(7 + HEADER_LEN + SEAL_OVERHEAD).unwrap()
+        );
+
+        let token = seal(&sk, b"payload").unwrap();
+        assert_eq!(open(&sk, &token, 10).unwrap(), b"payload");
+        assert!(token
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+    }
+
+    #[test]
+    fn test_ttl() {
+        let sk = SecretKey::default();
+        let token = seal_at(&sk, b"payload", NOW).unwrap();
+
+        assert!(open_at(&sk, &token, 10, NOW + 10).is_ok());
+        let err = open_at(&sk, &token, 10, NOW + 11).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Expired);
+        assert!(open_at(&sk, &token, u64::MAX, u64::MAX).is_ok());
+
+        // Accepted up to MAX_CLOCK_SKEW seconds before its creation.
+        assert!(open_at(&sk, &token, 10, NOW - MAX_CLOCK_SKEW).is_ok());
+        let err = open_at(&sk, &token, 10, NOW - MAX_CLOCK_SKEW - 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Expired);
+    }
+
+    #[test]
+    fn test_timestamp_is_authenticated() {
+        let sk = SecretKey::default();
+        let token = seal_at(&sk, b"payload", NOW - 100).unwrap();
+        let mut raw = Base64UrlSafeNoPadding::decode_to_vec(&token, None).unwrap();
+        raw[1..HEADER_LEN].copy_from_slice(&NOW.to_be_bytes());
+        let modified = Base64UrlSafeNoPadding::encode_to_string(&raw).unwrap();
+
+        let err = open_at(&sk, &modified, 10, NOW).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MacMismatch);
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        let sk = SecretKey::default();
+        let token = seal_at(&sk, b"payload", NOW).unwrap();
+
+        assert!(open_at(&SecretKey::default(), &token, 10, NOW).is_err());
+        assert!(open_at(&sk, &token[1..], 10, NOW).is_err());
+        assert!(open_at(&sk, &token[..token.len() - 1], 10, NOW).is_err());
+        assert!(open_at(&sk, "", 10, NOW).is_err());
+        assert!(open_at(&sk, &format!("{}=", token), 10, NOW).is_err());
+
+        let mut raw = Base64UrlSafeNoPadding::decode_to_vec(&token, None).unwrap();
+        raw[0] = 0x02;
+        let err = open_at(
+            &sk,
+            &Base64UrlSafeNoPadding::encode_to_string(&raw).unwrap(),
+            10,
+            NOW,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+        let mut raw = Base64UrlSafeNoPadding::decode_to_vec(&token, None).unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 1;
+        assert!(open_at(
+            &sk,
+            &Base64UrlSafeNoPadding::encode_to_string(&raw).unwrap(),
+            10,
+            NOW
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let sk = SecretKey::default();
+        assert!(seal_at(&sk, b"", NOW).is_err());
+        let short = SecretKey::from_slice(&[0u8; 31]).unwrap();
+        assert!(seal_at(&short, b"payload", NOW).is_err());
+
+        // A token with an empty payload can not be opened either.
+        let raw = [VERSION; HEADER_LEN + SEAL_OVERHEAD];
+        let err = open_at(
+            &sk,
+            &Base64UrlSafeNoPadding::encode_to_string(raw).unwrap(),
+            10,
+            NOW,
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidLength);
+    }
+}
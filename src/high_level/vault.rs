@@ -0,0 +1,605 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Password-protected key-value store.
+//!
+//! # Use case:
+//! `orion::vault` can be used to store a small amount of secrets, such as API tokens or
+//! keys, in a single file that is encrypted under a password.
+//!
+//! An example of this could be a command-line tool that keeps the credentials of its
+//! users in a file in their home directory.
+//!
+//! # About:
+//! - A [`Vault`] maps string keys to secret values, with [`Vault::get()`],
+//!   [`Vault::put()`], [`Vault::delete()`] and [`Vault::list()`]. Values are held as
+//!   [`SecretBytes`]s, which are zeroized when dropped.
+//! - The key of the vault is derived from the password with Argon2i, as in
+//!   [`kdf::derive_key()`](crate::kdf::derive_key). The password is only needed when
+//!   creating and opening a vault, not when saving it.
+//! - The entries are encrypted with [`orion::file`](crate::file), using the streaming AEAD
+//!   (XChaCha20Poly1305).
+//! - [`Vault::save()`] writes to a temporary file in the same directory, syncs it to disk
+//!   and renames it to the target path, so an existing vault is never left half-written.
+//!   On Unix, the file is only readable and writable by its owner.
+//! - The file starts with a header of [`HEADER_SIZE`] bytes: the magic bytes `orionvault`,
+//!   a version byte (currently `1`), the Argon2i iterations and memory (`u32`,
+//!   little-endian) and the 16-byte salt. It is followed by the container of
+//!   [`orion::file`](crate::file). The entries are encoded as their count (`u32`,
+//!   little-endian), followed by the length (`u32`, little-endian) and bytes of each key
+//!   and value, ordered by key.
+//!
+//! # Parameters:
+//! - `password`: The password of the vault.
+//! - `iterations`: Iterations cost parameter for Argon2i.
+//! - `memory`: Memory (in kibibytes (KiB)) cost parameter for Argon2i.
+//! - `path`: The file that the vault is read from or saved to.
+//! - `reader`, `writer`: Where the vault is read from or written to.
+//! - `key`: The name of an entry.
+//! - `value`: The secret of an entry.
+//!
+//! # Errors:
+//! An error will be returned if:
+//! - `iterations` is less than 3 or greater than [`MAX_ITERATIONS`].
+//! - `memory` is less than 8 or greater than [`MAX_MEMORY`].
+//! - Reading or writing the vault fails.
+//! - The vault is not valid or is for another version.
+//! - The password is not the one of the vault, or the vault has been modified.
+//! - `key` is empty, or `key` or `value` is longer than `u32::MAX` bytes.
+//!
+//! # Panics:
+//! A panic will occur if:
+//! - Failure to generate random bytes securely.
+//!
+//! # Security:
+//! - The Argon2i cost parameters are read from the file when it is opened, before they can
+//!   be authenticated. Vaults with parameters above [`MAX_ITERATIONS`] and [`MAX_MEMORY`] are
+//!   rejected, but a modified file can still make opening take up to the time and memory that
+//!   these allow.
+//! - Choosing the correct cost parameters is important for security. [`Vault::create()`]
+//!   uses [`DEFAULT_ITERATIONS`] and [`DEFAULT_MEMORY`].
+//! - The keys, the amount of entries and their total size are not hidden by the
+//!   encryption, apart from the contents.
+//! - The keys are not secret, and are returned as plain strings by [`Vault::list()`].
+//! - A vault can be replaced by an older version of itself, saved with the same password.
+//!
+//! # Example:
+//! ```rust
+//! use orion::vault::{Password, Vault};
+//!
+//! let password = Password::from_slice(b"Secret password")?;
+//! # let password_copy = Password::from_slice(b"Secret password")?;
+//! # let path = std::env::temp_dir().join(format!("orion-vault-doc-{}", std::process::id()));
+//! let mut vault = Vault::create(&password)?;
+//! # let mut vault = Vault::create_with_params(&password, 3, 8)?;
+//! vault.put("github-token", b"ghp_xxxxxxxx")?;
+//! vault.save(&path)?;
+//!
+//! let vault = Vault::open(&path, &password)?;
+//! assert_eq!(vault.list(), ["github-token"]);
+//! assert!(*vault.get("github-token").unwrap() == &b"ghp_xxxxxxxx"[..]);
+//! # std::fs::remove_file(&path)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub use super::hltypes::{Password, SecretBytes};
+use super::hltypes::{Salt, SecretKey};
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::high_level::{file, kdf};
+use crate::pwhash::MIN_ITERATIONS;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use zeroize::Zeroize;
+
+/// A writer that appends to a `Vec` with [`extend_zeroizing()`](crate::util::extend_zeroizing),
+/// so that the decrypted entries are not left behind in freed memory as the `Vec` grows.
+struct ZeroizingWriter<'a>(&'a mut Vec<u8>);
+
+impl Write for ZeroizingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        crate::util::extend_zeroizing(self.0, buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The magic bytes at the start of a vault.
+const MAGIC: [u8; 10] = *b"orionvault";
+/// The current version of the vault format.
+const VERSION: u8 = 1;
+/// The size of the salt of a vault.
+const SALT_SIZE: usize = 16;
+/// The size of the vault header.
+pub const HEADER_SIZE: usize = MAGIC.len() + 1 + 4 + 4 + SALT_SIZE;
+/// The Argon2i iterations used by [`Vault::create()`].
+pub const DEFAULT_ITERATIONS: u32 = 3;
+/// The Argon2i memory (KiB) used by [`Vault::create()`].
+pub const DEFAULT_MEMORY: u32 = 1 << 16;
+/// The maximum Argon2i iterations of a vault.
+pub const MAX_ITERATIONS: u32 = 256;
+/// The maximum Argon2i memory (KiB) of a vault, which is 2 GiB.
+pub const MAX_MEMORY: u32 = 1 << 21;
+/// The minimum Argon2i memory (KiB) of a vault.
+const MIN_MEMORY: u32 = 8;
+
+/// A password-protected key-value store. See the [module-level](self) documentation.
+pub struct Vault {
+    key: SecretKey,
+    iterations: u32,
+    memory: u32,
+    salt: Salt,
+    entries: BTreeMap<String, SecretBytes>,
+}
+
+impl core::fmt::Debug for Vault {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Vault {{ key: [***OMITTED***], iterations: {:?}, memory: {:?}, entries: {:?} }}",
+            self.iterations,
+            self.memory,
+            self.list()
+        )
+    }
+}
+
+/// Return an error if `iterations` or `memory` is outside of the range accepted for a vault,
+/// or a key exported with `SecretKey::export_protected()`.
+pub(super) fn check_params(iterations: u32, memory: u32) -> Result<(), UnknownCryptoError> {
+    if !(MIN_ITERATIONS..=MAX_ITERATIONS).contains(&iterations)
+        || !(MIN_MEMORY..=MAX_MEMORY).contains(&memory)
+    {
+        return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter));
+    }
+
+    Ok(())
+}
+
+/// Read a `u32` length and that many bytes from `src`, starting at `*pos`.
+fn read_field<'a>(src: &'a [u8], pos: &mut usize) -> Result<&'a [u8], UnknownCryptoError> {
+    let invalid = || UnknownCryptoError::new(ErrorKind::InvalidEncoding);
+
+    let len_bytes = src.get(*pos..*pos + 4).ok_or_else(invalid)?;
+    let len = u32::from_le_bytes([len_bytes[0], len_bytes[1], len_bytes[2], len_bytes[3]]);
+    let start = *pos + 4;
+    let end = start.checked_add(len as usize).ok_or_else(invalid)?;
+    let field = src.get(start..end).ok_or_else(invalid)?;
+    *pos = end;
+
+    Ok(field)
+}
+
+impl Vault {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create an empty vault protected by `password`, using [`DEFAULT_ITERATIONS`] and
+    /// [`DEFAULT_MEMORY`].
+    pub fn create(password: &Password) -> Result<Self, UnknownCryptoError> {
+        Self::create_with_params(password, DEFAULT_ITERATIONS, DEFAULT_MEMORY)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Create an empty vault protected by `password`, using the given Argon2i cost parameters.
+    pub fn create_with_params(
+        password: &Password,
+        iterations: u32,
+        memory: u32,
+    ) -> Result<Self, UnknownCryptoError> {
+        check_params(iterations, memory)?;
+        let salt = Salt::default();
+        let key = kdf::derive_key(password, &salt, iterations, memory, 32)?;
+
+        Ok(Self {
+            key,
+            iterations,
+            memory,
+            salt,
+            entries: BTreeMap::new(),
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Open the vault at `path` with `password`.
+    pub fn open<P: AsRef<Path>>(path: P, password: &Password) -> Result<Self, UnknownCryptoError> {
+        Self::from_reader(fs::File::open(path)?, password)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Read a vault from `reader` with `password`.
+    pub fn from_reader<R: Read>(
+        mut reader: R,
+        password: &Password,
+    ) -> Result<Self, UnknownCryptoError> {
+        let mut header = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut header)?;
+        if header[..MAGIC.len()] != MAGIC || header[MAGIC.len()] != VERSION {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        let params = &header[MAGIC.len() + 1..];
+        let iterations = u32::from_le_bytes([params[0], params[1], params[2], params[3]]);
+        let memory = u32::from_le_bytes([params[4], params[5], params[6], params[7]]);
+        // The parameters are checked before deriving the key, since the header is not
+        // authenticated until the entries are opened.
+        if check_params(iterations, memory).is_err() {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+        let salt = Salt::from_slice(&params[8..])?;
+        let key = kdf::derive_key(password, &salt, iterations, memory, 32)?;
+
+        let mut plaintext = Vec::new();
+        let opened = file::open_file(&key, reader, ZeroizingWriter(&mut plaintext));
+        let entries = opened.and_then(|_| Self::decode_entries(&plaintext));
+        plaintext.zeroize();
+
+        Ok(Self {
+            key,
+            iterations,
+            memory,
+            salt,
+            entries: entries?,
+        })
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Save the vault to `path`, replacing the file atomically if it exists.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), UnknownCryptoError> {
+        let path = path.as_ref();
+        let file_name = match path.file_name() {
+            Some(file_name) => file_name.to_string_lossy(),
+            None => return Err(UnknownCryptoError::new(ErrorKind::InvalidParameter)),
+        };
+        let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let written = options.open(&tmp_path).and_then(|mut tmp_file| {
+            self.write_to(&mut tmp_file).map_err(std::io::Error::from)?;
+            tmp_file.sync_all()
+        });
+        match written.and_then(|_| fs::rename(&tmp_path, path)) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                let _ = fs::remove_file(&tmp_path);
+                Err(err.into())
+            }
+        }
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Write the vault to `writer`.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), UnknownCryptoError> {
+        let mut header = [0u8; HEADER_SIZE];
+        header[..MAGIC.len()].copy_from_slice(&MAGIC);
+        header[MAGIC.len()] = VERSION;
+        header[MAGIC.len() + 1..MAGIC.len() + 5].copy_from_slice(&self.iterations.to_le_bytes());
+        header[MAGIC.len() + 5..MAGIC.len() + 9].copy_from_slice(&self.memory.to_le_bytes());
+        header[MAGIC.len() + 9..].copy_from_slice(self.salt.as_ref());
+        writer.write_all(&header)?;
+
+        let mut plaintext = self.encode_entries();
+        let sealed = file::seal_file(&self.key, &plaintext[..], writer);
+        plaintext.zeroize();
+
+        sealed
+    }
+
+    /// Return the value of `key`, if it exists.
+    pub fn get(&self, key: &str) -> Option<&SecretBytes> {
+        self.entries.get(key)
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Set the value of `key` to `value`, replacing any existing value.
+    pub fn put(&mut self, key: &str, value: &[u8]) -> Result<(), UnknownCryptoError> {
+        if key.is_empty() || key.len() > u32::MAX as usize || value.len() > u32::MAX as usize {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidLength));
+        }
+        self.entries
+            .insert(key.to_string(), SecretBytes::from(value.to_vec()));
+
+        Ok(())
+    }
+
+    /// Remove `key` and its value. Returns `true` if `key` existed.
+    pub fn delete(&mut self, key: &str) -> bool {
+        self.entries.remove(key).is_some()
+    }
+
+    /// Return all keys, in order.
+    pub fn list(&self) -> Vec<&str> {
+        self.entries.keys().map(|key| key.as_str()).collect()
+    }
+
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Protect the vault with `new_password` from now on, with a new salt. The entries are
+    /// kept. The vault must be saved for this to take effect.
+    pub fn change_password(&mut self, new_password: &Password) -> Result<(), UnknownCryptoError> {
+        let salt = Salt::default();
+        self.key = kdf::derive_key(new_password, &salt, self.iterations, self.memory, 32)?;
+        self.salt = salt;
+
+        Ok(())
+    }
+
+    /// Encode the entries. The caller is responsible for zeroing the returned `Vec`.
+    fn encode_entries(&self) -> Vec<u8> {
+        let len = self
+            .entries
+            .iter()
+            .fold(4, |len, (key, value)| len + 8 + key.len() + value.len());
+        let mut out = Vec::with_capacity(len);
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (key, value) in self.entries.iter() {
+            out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            out.extend_from_slice(key.as_bytes());
+            out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            out.extend_from_slice(value.unprotected_as_bytes());
+        }
+
+        out
+    }
+
+    /// Decode entries encoded by [`Vault::encode_entries()`].
+    fn decode_entries(src: &[u8]) -> Result<BTreeMap<String, SecretBytes>, UnknownCryptoError> {
+        let invalid = || UnknownCryptoError::new(ErrorKind::InvalidEncoding);
+
+        if src.len() < 4 {
+            return Err(invalid());
+        }
+        let count = u32::from_le_bytes([src[0], src[1], src[2], src[3]]);
+        let mut pos = 4;
+        let mut entries = BTreeMap::new();
+        for _ in 0..count {
+            let key = core::str::from_utf8(read_field(src, &mut pos)?).map_err(|_| invalid())?;
+            let value = read_field(src, &mut pos)?;
+            if key.is_empty()
+                || entries
+                    .insert(key.to_string(), SecretBytes::from(value.to_vec()))
+                    .is_some()
+            {
+                return Err(invalid());
+            }
+        }
+        if pos != src.len() {
+            return Err(invalid());
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+
+    fn password() -> Password {
+        Password::from_slice(b"Secret password").unwrap()
+    }
+
+    fn test_vault() -> Vault {
+        let mut vault = Vault::create_with_params(&password(), 3, 8).unwrap();
+        vault.put("token", b"abc").unwrap();
+        vault.put("empty", b"").unwrap();
+        vault.put("binary", &[0u8, 255, 1]).unwrap();
+
+        vault
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let mut random = [0u8; 8];
+        crate::util::secure_rand_bytes(&mut random).unwrap();
+        std::env::temp_dir().join(format!("orion-vault-{}-{}", name, hex::encode(random)))
+    }
+
+    #[test]
+    fn test_get_put_delete_list() {
+        let mut vault = test_vault();
+        assert_eq!(vault.list(), ["binary", "empty", "token"]);
+        assert!(*vault.get("token").unwrap() == &b"abc"[..]);
+        assert!(vault.get("empty").unwrap().is_empty());
+        assert!(vault.get("missing").is_none());
+
+        vault.put("token", b"def").unwrap();
+        assert!(*vault.get("token").unwrap() == &b"def"[..]);
+
+        assert!(vault.delete("token"));
+        assert!(!vault.delete("token"));
+        assert_eq!(vault.list(), ["binary", "empty"]);
+
+        assert!(vault.put("", b"value").is_err());
+    }
+
+    #[test]
+    fn test_write_read() {
+        let vault = test_vault();
+        let mut written = Vec::new();
+        vault.write_to(&mut written).unwrap();
+        assert_eq!(&written[..MAGIC.len()], &MAGIC);
+        assert_eq!(written[MAGIC.len()], VERSION);
+        assert_eq!(
+            &written[MAGIC.len() + 1..MAGIC.len() + 5],
+            &3u32.to_le_bytes()
+        );
+        assert_eq!(
+            &written[MAGIC.len() + 5..MAGIC.len() + 9],
+            &8u32.to_le_bytes()
+        );
+
+        let read = Vault::from_reader(&written[..], &password()).unwrap();
+        assert_eq!(read.list(), vault.list());
+        for key in vault.list() {
+            assert!(*read.get(key).unwrap() == vault.get(key).unwrap().unprotected_as_bytes());
+        }
+
+        let wrong = Password::from_slice(b"Wrong password").unwrap();
+        let err = Vault::from_reader(&written[..], &wrong).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MacMismatch);
+    }
+
+    #[test]
+    fn test_empty_vault() {
+        let vault = Vault::create_with_params(&password(), 3, 8).unwrap();
+        let mut written = Vec::new();
+        vault.write_to(&mut written).unwrap();
+        let read = Vault::from_reader(&written[..], &password()).unwrap();
+        assert!(read.list().is_empty());
+    }
+
+    #[test]
+    fn test_modified_vault() {
+        let mut written = Vec::new();
+        test_vault().write_to(&mut written).unwrap();
+
+        for i in 0..written.len() {
+            let mut modified = written.clone();
+            modified[i] ^= 1;
+            assert!(Vault::from_reader(&modified[..], &password()).is_err());
+        }
+        assert!(Vault::from_reader(&written[..written.len() - 1], &password()).is_err());
+        assert!(Vault::from_reader(&written[..HEADER_SIZE - 1], &password()).is_err());
+
+        let mut wrong_version = written.clone();
+        wrong_version[MAGIC.len()] = 2;
+        let err = Vault::from_reader(&wrong_version[..], &password()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+    }
+
+    #[test]
+    fn test_header_params_out_of_range() {
+        let mut written = Vec::new();
+        test_vault().write_to(&mut written).unwrap();
+        let iterations = MAGIC.len() + 1;
+        let memory = MAGIC.len() + 5;
+
+        for (offset, value) in [
+            (iterations, MIN_ITERATIONS - 1),
+            (iterations, MAX_ITERATIONS + 1),
+            (iterations, u32::MAX),
+            (memory, MIN_MEMORY - 1),
+            (memory, MAX_MEMORY + 1),
+            (memory, u32::MAX),
+        ]
+        .iter()
+        {
+            let mut modified = written.clone();
+            modified[*offset..*offset + 4].copy_from_slice(&value.to_le_bytes());
+            let err = Vault::from_reader(&modified[..], &password()).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+        }
+    }
+
+    #[test]
+    fn test_decode_entries() {
+        let vault = test_vault();
+        let encoded = vault.encode_entries();
+        assert_eq!(Vault::decode_entries(&encoded).unwrap().len(), 3);
+
+        for len in 0..encoded.len() {
+            assert!(Vault::decode_entries(&encoded[..len]).is_err());
+        }
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(Vault::decode_entries(&trailing).is_err());
+
+        // The same key twice.
+        let mut duplicate = 2u32.to_le_bytes().to_vec();
+        for _ in 0..2 {
+            duplicate.extend_from_slice(&[1, 0, 0, 0, b'a', 0, 0, 0, 0]);
+        }
+        assert!(Vault::decode_entries(&duplicate).is_err());
+        // An empty key and a key that is not UTF-8.
+        assert!(Vault::decode_entries(&[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(Vault::decode_entries(&[1, 0, 0, 0, 1, 0, 0, 0, 0xff, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_save_open() {
+        let path = temp_path("save");
+        let mut vault = test_vault();
+        vault.save(&path).unwrap();
+
+        vault.put("new", b"entry").unwrap();
+        vault.save(&path).unwrap();
+        let opened = Vault::open(&path, &password()).unwrap();
+        assert_eq!(opened.list(), ["binary", "empty", "new", "token"]);
+
+        // No temporary file is left behind.
+        let tmp_name = format!(".{}.tmp", path.file_name().unwrap().to_string_lossy());
+        assert!(!path.with_file_name(tmp_name).exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_file(&path).unwrap();
+        assert!(Vault::open(&path, &password()).is_err());
+    }
+
+    #[test]
+    fn test_save_invalid_path() {
+        let vault = test_vault();
+        assert!(vault.save("/").is_err());
+        assert!(vault.save(temp_path("dir").join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_change_password() {
+        let mut vault = test_vault();
+        let new_password = Password::from_slice(b"New password").unwrap();
+        vault.change_password(&new_password).unwrap();
+
+        let mut written = Vec::new();
+        vault.write_to(&mut written).unwrap();
+        assert!(Vault::from_reader(&written[..], &password()).is_err());
+        let read = Vault::from_reader(&written[..], &new_password).unwrap();
+        assert_eq!(read.list(), vault.list());
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        assert!(Vault::create_with_params(&password(), 2, 8).is_err());
+        assert!(Vault::create_with_params(&password(), 3, 7).is_err());
+        assert!(Vault::create_with_params(&password(), MAX_ITERATIONS + 1, 8).is_err());
+        assert!(Vault::create_with_params(&password(), 3, MAX_MEMORY + 1).is_err());
+    }
+
+    #[test]
+    fn test_debug_omits_values() {
+        let debug = format!("{:?}", test_vault());
+        assert!(debug.contains("token"));
+        assert!(!debug.contains("abc"));
+    }
+}
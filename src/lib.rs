@@ -26,33 +26,109 @@
 //! [`orion::aead`] offers authenticated secret-key encryption using
 //! XChaCha20Poly1305.
 //!
+//! ## Envelope encryption
+//! [`orion::envelope`] offers encryption of data at rest under data encryption keys, which are
+//! in turn encrypted under a key encryption key.
+//!
 //! ## Password hashing and verification
 //! [`orion::pwhash`] offers password hashing and verification using Argon2i.
 //!
 //! ## Key derivation
 //! [`orion::kdf`] offers key derivation using Argon2i.
 //!
+//! ## Key exchange
+//! [`orion::kex`] offers session key derivation from X25519 key pairs, compatible
+//! with libsodium's `crypto_kx`. [`orion::channel`] uses these session keys to exchange
+//! encrypted messages over a transport, such as a `TcpStream`.
+//!
 //! ## Message authentication
 //! [`orion::auth`] offers message authentication and verification using BLAKE2b.
 //!
+//! ## JSON Web Tokens
+//! [`orion::jwt`] offers signing and verification of HS256 and HS512 JSON Web Tokens.
+//!
+//! ## Expiring tokens
+//! [`orion::token`] offers encrypted tokens that are only valid for a limited time, such as
+//! session tokens and password-reset links.
+//!
 //! ## Hashing
 //! [`orion::hash`] offers hashing using BLAKE2b.
 //!
+//! ## File encryption
+//! [`orion::file`] offers chunked encryption of large data, such as files, using the streaming
+//! XChaCha20Poly1305 AEAD.
+//!
+//! ## Password-protected storage
+//! [`orion::vault`] offers a small key-value store, encrypted under a password.
+//!
+//! ## Random number generation
+//! [`orion::rng`] offers a fast ChaCha20-based userspace CSPRNG.
+//!
+//! ## Compatibility
+//! [`orion::compat`] offers functions that are byte-compatible with other libraries, such as
+//! `crypto_auth` and `crypto_shorthash` of libsodium.
+//!
+//! ## Key backup
+//! [`orion::mnemonic`] offers encoding of keys as phrases of words from the BIP39 wordlist.
+//! `SecretKey::export_protected()` encrypts a key with a password, for storing it on disk or
+//! moving it to another machine.
+//!
 //! ### A note on `no_std`:
 //! When orion is used in a `no_std` context, the high-level API is not available, since it relies on access to the systems random number generator.
+//! The exceptions are [`orion::aead`] and [`orion::kdf`], which are available when both the
+//! `alloc` and `getrandom` features are enabled, for targets that have a heap and are supported
+//! by `getrandom`. [`orion::hash`] needs neither, and is always available.
+//!
+//! ### A note on panics:
+//! Invalid input, such as a malformed ciphertext or password hash, always results in an
+//! [`UnknownCryptoError`](errors::UnknownCryptoError) and never in a panic. The only panics
+//! left are on failure of the systems random number generator, in the `Default`
+//! implementations and `generate()` functions that cannot return an error, each of which
+//! documents this under `# Panics:`. Internal use of `unwrap()`, `expect()` and `panic!()`
+//! is denied through clippy, except where it is documented why it cannot panic.
+//!
+//! ### A note on `serde`:
+//! The `serde` feature implements `Serialize` and `Deserialize` for public values, such as
+//! nonces, digests, salts, ristretto255 points and password hashes. Human-readable formats
+//! use hex (and the PHC string format for password hashes), while binary formats use the raw
+//! bytes. Secret keys are not
+//! serialized, unless this is explicitly requested through [`orion::kdf::serde_secret_key`].
+//! It also enables `orion::aead::seal_value` and `orion::aead::open_value`, which encrypt any
+//! serializable value. This feature requires `safe_api`.
+//!
+//! ### A note on `rand_core`:
+//! The `rand_core` feature implements the `rand_core` traits for [`orion::rng::OrionRng`].
+//! This feature requires `safe_api` and Rust 1.85 or newer.
 //!
 //! More information about orion is available in the [wiki].
 //!
 //! [`orion::aead`]: crate::aead
+//! [`orion::envelope`]: crate::envelope
 //! [`orion::pwhash`]: crate::pwhash
 //! [`orion::kdf`]: crate::kdf
+//! [`orion::kex`]: crate::kex
+//! [`orion::channel`]: crate::channel
 //! [`orion::auth`]: crate::auth
+//! [`orion::jwt`]: crate::jwt
+//! [`orion::token`]: crate::token
 //! [`orion::hash`]: crate::hash
+//! [`orion::file`]: crate::file
+//! [`orion::vault`]: crate::vault
+//! [`orion::rng`]: crate::rng
+//! [`orion::mnemonic`]: crate::mnemonic
+//! [`orion::compat`]: crate::compat
+//! [`orion::rng::OrionRng`]: crate::rng::OrionRng
+//! [`orion::kdf::serde_secret_key`]: crate::kdf::serde_secret_key
 //! [wiki]: https://github.com/orion-rs/orion/wiki
 
 #![cfg_attr(not(feature = "safe_api"), no_std)]
+#![cfg_attr(feature = "portable_simd", feature(portable_simd))]
 #![forbid(unsafe_code)]
 #![deny(clippy::mem_forget)]
+#![cfg_attr(
+    not(test),
+    deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)
+)]
 #![warn(
     missing_docs,
     rust_2018_idioms,
@@ -60,7 +136,7 @@
     unused_qualifications,
     overflowing_literals
 )]
-#![doc(html_root_url = "https://docs.rs/orion/0.16.0")]
+#![doc(html_root_url = "https://docs.rs/orion/0.17.0")]
 
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
@@ -77,6 +153,10 @@ extern crate alloc;
 #[macro_use]
 mod typedefs;
 
+mod newtypes;
+#[doc(hidden)]
+pub use newtypes::__private;
+
 #[macro_use]
 /// Utilities such as constant-time comparison.
 pub mod util;
@@ -87,24 +167,59 @@ pub mod errors;
 /// \[__**Caution**__\] Low-level API.
 pub mod hazardous;
 
-#[cfg(feature = "safe_api")]
 mod high_level;
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "serde", feature = "safe_api"))]
+mod serde_impls;
+
+#[cfg(all(feature = "serde", feature = "safe_api"))]
+mod serde_bincode;
+
 pub use high_level::hash;
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 pub use high_level::aead;
 
 #[cfg(feature = "safe_api")]
 pub use high_level::auth;
 
 #[cfg(feature = "safe_api")]
-pub use high_level::pwhash;
+pub use high_level::envelope;
 
 #[cfg(feature = "safe_api")]
+pub use high_level::pwhash;
+
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 pub use high_level::kdf;
 
+#[cfg(feature = "safe_api")]
+pub use high_level::kex;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::channel;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::jwt;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::token;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::file;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::rng;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::mnemonic;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::compat;
+
+#[cfg(feature = "safe_api")]
+pub use high_level::vault;
+
 #[doc(hidden)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
 /// Testing framework.
 pub mod test_framework;
@@ -0,0 +1,428 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Exported macros that let other crates define fixed-size newtypes with the same
+//! protections as orion's own.
+//!
+//! The macros are expanded in the crate that uses them, so a `#[cfg(feature = ..)]` in
+//! their output would check that crate's features instead of orion's. Items that depend
+//! on orion's features are therefore generated by the hidden `__newtype_*` macros below,
+//! which are defined once for each side of the `cfg`.
+
+/// Items used by the exported newtype macros. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use zeroize::Zeroize;
+
+    #[cfg(feature = "getrandom")]
+    /// Calls `crate::util::secure_rand_bytes_or_panic()`, which is not public.
+    pub fn secure_rand_bytes_or_panic(dst: &mut [u8]) {
+        crate::util::secure_rand_bytes_or_panic(dst)
+    }
+
+    #[cfg(all(feature = "serde", feature = "safe_api"))]
+    pub use crate::serde_impls::{deserialize_bytes, serialize_bytes};
+    #[cfg(all(feature = "serde", feature = "safe_api"))]
+    pub use serde;
+}
+
+#[macro_export]
+/// Define a fixed-size newtype for secret data, such as an application-specific token
+/// or key, with the same protections as orion's secret keys.
+///
+/// # About:
+/// The macro takes a struct declaration with the size of the type in bytes. The type gets:
+/// - `from_slice()`, which only accepts slices of exactly the given size.
+/// - `const fn from_array()`, `From<[u8; N]>` and `TryFrom<&[u8]>`.
+/// - `unprotected_as_bytes()`, `len()` and `is_empty()`.
+/// - `generate()` with the `getrandom` feature, which panics if the CSPRNG fails.
+///
+/// # Security:
+/// - The data is zeroed when the value is dropped.
+/// - `PartialEq`, `PartialEq<&[u8]>` and [`ConstantTimeEq`](crate::util::ConstantTimeEq)
+///   are implemented in constant time.
+/// - `Debug` omits the data, so it is not written to logs.
+/// - `Clone`, `Copy` and `serde` traits are not implemented.
+/// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all
+///   protections that the type implements.
+///
+/// # Example:
+/// ```rust
+/// orion::secret_newtype! {
+///     /// A token for authenticating to the database.
+///     pub struct DatabaseToken(32);
+/// }
+///
+/// let token = DatabaseToken::generate();
+/// assert_eq!(token.len(), 32);
+/// assert_eq!(format!("{:?}", token), "DatabaseToken {***OMITTED***}");
+///
+/// let copy = DatabaseToken::from_slice(token.unprotected_as_bytes())?;
+/// assert!(token == copy);
+/// assert!(DatabaseToken::from_slice(&[0u8; 31]).is_err());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+macro_rules! secret_newtype {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($size:expr);) => {
+        $(#[$meta])*
+        $vis struct $name {
+            value: [u8; $size],
+        }
+
+        impl $name {
+            $crate::__newtype_common_functions!($name, $size);
+            $crate::__newtype_generate!($name, $size);
+
+            #[inline]
+            /// Return the object as byte slice. __**Warning**__: Should not be used unless strictly
+            /// needed. This __**breaks protections**__ that the type implements.
+            pub fn unprotected_as_bytes(&self) -> &[u8] {
+                &self.value[..]
+            }
+        }
+
+        $crate::__newtype_common_traits!($name, $size, unprotected_as_bytes);
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {{***OMITTED***}}", stringify!($name))
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                use $crate::__private::Zeroize;
+                self.value.zeroize();
+            }
+        }
+    };
+}
+
+#[macro_export]
+/// Define a fixed-size newtype for data that is not secret, such as an application-specific
+/// identifier or public key.
+///
+/// # About:
+/// The macro takes a struct declaration with the size of the type in bytes. The type gets:
+/// - `from_slice()`, which only accepts slices of exactly the given size.
+/// - `const fn from_array()`, `From<[u8; N]>` and `TryFrom<&[u8]>`.
+/// - `AsRef<[u8]>`, `len()` and `is_empty()`.
+/// - `Clone`, `Copy` and a `Debug` that shows the data.
+/// - `serde::{Serialize, Deserialize}` with the `serde` feature, as hex for
+///   human-readable formats and as raw bytes otherwise.
+///
+/// `PartialEq`, `PartialEq<&[u8]>` and [`ConstantTimeEq`](crate::util::ConstantTimeEq)
+/// are implemented in constant time.
+///
+/// # Example:
+/// ```rust
+/// orion::public_newtype! {
+///     /// The identifier of a device.
+///     pub struct DeviceId(16);
+/// }
+///
+/// const DEVICE: DeviceId = DeviceId::from_array([7u8; 16]);
+///
+/// assert_eq!(DEVICE.as_ref(), &[7u8; 16]);
+/// assert!(DEVICE == DeviceId::from_slice(&[7u8; 16])?);
+/// assert!(DeviceId::from_slice(&[7u8; 17]).is_err());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+macro_rules! public_newtype {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($size:expr);) => {
+        #[derive(Clone, Copy)]
+        $(#[$meta])*
+        $vis struct $name {
+            value: [u8; $size],
+        }
+
+        impl $name {
+            $crate::__newtype_common_functions!($name, $size);
+        }
+
+        $crate::__newtype_common_traits!($name, $size, as_ref);
+        $crate::__newtype_serde!($name);
+
+        impl AsRef<[u8]> for $name {
+            #[inline]
+            fn as_ref(&self) -> &[u8] {
+                &self.value[..]
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{} {:?}", stringify!($name), &self.value[..])
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+/// Functions shared by the types of [`secret_newtype!`] and [`public_newtype!`].
+macro_rules! __newtype_common_functions {
+    ($name:ident, $size:expr) => {
+        #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+        /// Construct from a given byte slice.
+        pub fn from_slice(slice: &[u8]) -> Result<$name, $crate::errors::UnknownCryptoError> {
+            if slice.len() != $size {
+                return Err($crate::errors::UnknownCryptoError::new(
+                    $crate::errors::ErrorKind::InvalidLength,
+                ));
+            }
+
+            let mut value = [0u8; $size];
+            value.copy_from_slice(slice);
+
+            Ok($name { value })
+        }
+
+        #[inline]
+        /// Make an object from a byte array. Unlike `From`, this can be used
+        /// in a `const` context.
+        pub const fn from_array(bytes: [u8; $size]) -> $name {
+            $name { value: bytes }
+        }
+
+        #[inline]
+        /// Return the length of the object.
+        pub const fn len(&self) -> usize {
+            $size
+        }
+
+        #[inline]
+        /// Return `true` if this object does not hold any data, `false` otherwise.
+        pub const fn is_empty(&self) -> bool {
+            $size == 0
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+/// Traits shared by the types of [`secret_newtype!`] and [`public_newtype!`]. Equality
+/// is implemented in constant time over `$bytes_function`.
+macro_rules! __newtype_common_traits {
+    ($name:ident, $size:expr, $bytes_function:ident) => {
+        impl PartialEq<$name> for $name {
+            fn eq(&self, other: &$name) -> bool {
+                use $crate::util::ConstantTimeEq;
+
+                (self.$bytes_function().ct_eq(other.$bytes_function())).into()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialEq<&[u8]> for $name {
+            fn eq(&self, other: &&[u8]) -> bool {
+                use $crate::util::ConstantTimeEq;
+
+                (self.$bytes_function().ct_eq(*other)).into()
+            }
+        }
+
+        impl $crate::util::ConstantTimeEq for $name {
+            fn ct_eq(&self, other: &$name) -> $crate::util::Choice {
+                self.$bytes_function().ct_eq(other.$bytes_function())
+            }
+        }
+
+        impl From<[u8; $size]> for $name {
+            #[inline]
+            /// Make an object from a byte array.
+            fn from(bytes: [u8; $size]) -> $name {
+                $name::from_array(bytes)
+            }
+        }
+
+        impl core::convert::TryFrom<&[u8]> for $name {
+            type Error = $crate::errors::UnknownCryptoError;
+
+            #[inline]
+            /// Make an object from a byte slice, using `from_slice()`.
+            fn try_from(slice: &[u8]) -> Result<$name, Self::Error> {
+                $name::from_slice(slice)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "getrandom")]
+#[doc(hidden)]
+#[macro_export]
+/// `generate()` for the types of [`secret_newtype!`].
+macro_rules! __newtype_generate {
+    ($name:ident, $size:expr) => {
+        /// Randomly generate using a CSPRNG.
+        ///
+        /// # Panics:
+        /// A panic will occur if:
+        /// - Failure to generate random bytes securely.
+        pub fn generate() -> $name {
+            let mut value = [0u8; $size];
+            $crate::__private::secure_rand_bytes_or_panic(&mut value);
+
+            $name { value }
+        }
+    };
+}
+
+#[cfg(not(feature = "getrandom"))]
+#[doc(hidden)]
+#[macro_export]
+/// `generate()` requires the `getrandom` feature.
+macro_rules! __newtype_generate {
+    ($name:ident, $size:expr) => {};
+}
+
+#[cfg(all(feature = "serde", feature = "safe_api"))]
+#[doc(hidden)]
+#[macro_export]
+/// `serde::{Serialize, Deserialize}` for the types of [`public_newtype!`].
+macro_rules! __newtype_serde {
+    ($name:ident) => {
+        /// Serialized as a hex string for human-readable formats and as raw bytes otherwise.
+        impl $crate::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::__private::serde::Serializer,
+            {
+                $crate::__private::serialize_bytes(self.as_ref(), serializer)
+            }
+        }
+
+        /// Deserialized from a hex string for human-readable formats and from raw bytes otherwise.
+        /// The result is built using `from_slice()`.
+        impl<'de> $crate::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::__private::serde::Deserializer<'de>,
+            {
+                let bytes = $crate::__private::deserialize_bytes(deserializer)?;
+                Self::from_slice(&bytes).map_err($crate::__private::serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[cfg(not(all(feature = "serde", feature = "safe_api")))]
+#[doc(hidden)]
+#[macro_export]
+/// `serde` support requires the `serde` and `safe_api` features.
+macro_rules! __newtype_serde {
+    ($name:ident) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    secret_newtype! {
+        /// Test secret.
+        pub struct TestSecret(24);
+    }
+
+    public_newtype! {
+        /// Test public.
+        pub(crate) struct TestPublic(12);
+    }
+
+    #[test]
+    fn test_from_slice() {
+        assert!(TestSecret::from_slice(&[1u8; 24]).is_ok());
+        assert!(TestSecret::from_slice(&[1u8; 23]).is_err());
+        assert!(TestSecret::from_slice(&[1u8; 25]).is_err());
+        assert!(TestSecret::from_slice(&[0u8; 0]).is_err());
+
+        assert!(TestPublic::from_slice(&[1u8; 12]).is_ok());
+        assert!(TestPublic::from_slice(&[1u8; 11]).is_err());
+        assert!(TestPublic::from_slice(&[1u8; 13]).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        use core::convert::TryFrom;
+
+        const SECRET: TestSecret = TestSecret::from_array([2u8; 24]);
+        assert_eq!(SECRET.unprotected_as_bytes(), &[2u8; 24]);
+        assert_eq!(SECRET.len(), 24);
+        assert!(!SECRET.is_empty());
+        assert!(TestSecret::from([2u8; 24]) == SECRET);
+        assert!(TestSecret::try_from(&[2u8; 24][..]).unwrap() == SECRET);
+        assert!(TestSecret::try_from(&[2u8; 23][..]).is_err());
+
+        let public = TestPublic::from([3u8; 12]);
+        assert_eq!(public.as_ref(), &[3u8; 12]);
+        assert!(TestPublic::try_from(&[3u8; 12][..]).unwrap() == public);
+        assert_eq!(public.len(), 12);
+        assert!(!public.is_empty());
+    }
+
+    #[test]
+    fn test_partial_eq() {
+        use crate::util::ConstantTimeEq;
+
+        let secret = TestSecret::from_slice(&[0u8; 24]).unwrap();
+        assert!(secret == TestSecret::from_slice(&[0u8; 24]).unwrap());
+        assert!(secret != TestSecret::from_slice(&[1u8; 24]).unwrap());
+        assert!(secret == [0u8; 24].as_ref());
+        assert!(secret != [1u8; 24].as_ref());
+        assert!(bool::from(secret.ct_eq(&TestSecret::from([0u8; 24]))));
+        assert!(!bool::from(secret.ct_eq(&TestSecret::from([1u8; 24]))));
+
+        let public = TestPublic::from_slice(&[0u8; 12]).unwrap();
+        assert!(public == TestPublic::from_slice(&[0u8; 12]).unwrap());
+        assert!(public != TestPublic::from_slice(&[1u8; 12]).unwrap());
+        assert!(public == [0u8; 12].as_ref());
+        assert!(!bool::from(public.ct_eq(&TestPublic::from([1u8; 12]))));
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_debug() {
+        let secret = format!("{:?}", [5u8; 24].as_ref());
+        let debug = format!("{:?}", TestSecret::from([5u8; 24]));
+        assert_eq!(debug, "TestSecret {***OMITTED***}");
+        assert!(!debug.contains(&secret));
+
+        let public = format!("{:?}", [5u8; 12].as_ref());
+        assert!(format!("{:?}", TestPublic::from([5u8; 12])).contains(&public));
+    }
+
+    #[test]
+    #[cfg(feature = "getrandom")]
+    fn test_generate() {
+        let first = TestSecret::generate();
+        assert_eq!(first.len(), 24);
+        assert!(first != TestSecret::generate());
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "safe_api"))]
+    fn test_serde_roundtrip() {
+        let public = TestPublic::from([9u8; 12]);
+        let encoded = serde_json::to_string(&public).unwrap();
+        assert_eq!(encoded, "\"090909090909090909090909\"");
+        assert!(serde_json::from_str::<TestPublic>(&encoded).unwrap() == public);
+        assert!(serde_json::from_str::<TestPublic>("\"0909\"").is_err());
+    }
+}
@@ -0,0 +1,692 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! A serde data format for encrypting values with [`aead::seal_value()`].
+//!
+//! The format is the default of the [`bincode`] 1.x crate: integers and floats are
+//! fixed-size little-endian, `bool` and the `Option` tag are a `u8`, enum variants are a
+//! `u32`, and strings, bytes, sequences and maps are prefixed with their length as a `u64`.
+//! The format is not self-describing, so types that need `deserialize_any()` are not
+//! supported.
+//!
+//! [`aead::seal_value()`]: crate::aead::seal_value
+//! [`bincode`]: https://crates.io/crates/bincode
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use core::fmt;
+use serde::{de, ser, Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// An error while serializing or deserializing a value. It is only used within the
+/// serializers, and becomes [`ErrorKind::InvalidEncoding`] towards the caller.
+#[derive(Debug)]
+pub(crate) struct Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid encoding of a value")
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        // The message may include parts of the value, so it is not kept.
+        Self
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        Self
+    }
+}
+
+impl From<Error> for UnknownCryptoError {
+    fn from(_: Error) -> Self {
+        UnknownCryptoError::new(ErrorKind::InvalidEncoding)
+    }
+}
+
+/// Serialize `value`.
+///
+/// The caller is responsible for zeroing the returned `Vec` if it holds secret data.
+pub(crate) fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut serializer = Serializer { out: Vec::new() };
+    if let Err(err) = value.serialize(&mut serializer) {
+        serializer.out.zeroize();
+        return Err(err);
+    }
+
+    Ok(serializer.out)
+}
+
+/// Deserialize a `T` from all of `bytes`.
+pub(crate) fn from_slice<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, Error> {
+    let mut deserializer = Deserializer { input: bytes };
+    let value = T::deserialize(&mut deserializer)?;
+    if !deserializer.input.is_empty() {
+        return Err(Error);
+    }
+
+    Ok(value)
+}
+
+struct Serializer {
+    out: Vec<u8>,
+}
+
+impl Serializer {
+    fn write(&mut self, bytes: &[u8]) {
+        crate::util::extend_zeroizing(&mut self.out, bytes);
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<(), Error> {
+        let len = len.ok_or(Error)?;
+        self.write(&(len as u64).to_le_bytes());
+
+        Ok(())
+    }
+}
+
+macro_rules! serialize_le (($method:ident, $ty:ty) => (
+    fn $method(self, v: $ty) -> Result<(), Error> {
+        self.write(&v.to_le_bytes());
+        Ok(())
+    }
+));
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    serialize_le!(serialize_i8, i8);
+    serialize_le!(serialize_i16, i16);
+    serialize_le!(serialize_i32, i32);
+    serialize_le!(serialize_i64, i64);
+    serialize_le!(serialize_i128, i128);
+    serialize_le!(serialize_u8, u8);
+    serialize_le!(serialize_u16, u16);
+    serialize_le!(serialize_u32, u32);
+    serialize_le!(serialize_u64, u64);
+    serialize_le!(serialize_u128, u128);
+    serialize_le!(serialize_f32, f32);
+    serialize_le!(serialize_f64, f64);
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.serialize_u8(v as u8)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write(v.encode_utf8(&mut [0u8; 4]).as_bytes());
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.write_len(Some(v.len()))?;
+        self.write(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_u8(0)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+        self.serialize_u8(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.serialize_u32(index)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.serialize_u32(index)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, Error> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, Error> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.serialize_u32(index)?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.serialize_u32(index)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Implement a compound serializer, where all elements are written one after the other.
+macro_rules! impl_serialize_compound (($trait:ident, $($method:ident($($key:ty),*)),+) => (
+    impl ser::$trait for &mut Serializer {
+        type Ok = ();
+        type Error = Error;
+
+        $(fn $method<T: ?Sized + Serialize>(&mut self, $(_: $key,)* value: &T) -> Result<(), Error> {
+            value.serialize(&mut **self)
+        })+
+
+        fn end(self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+));
+
+impl_serialize_compound!(SerializeSeq, serialize_element());
+impl_serialize_compound!(SerializeTuple, serialize_element());
+impl_serialize_compound!(SerializeTupleStruct, serialize_field());
+impl_serialize_compound!(SerializeTupleVariant, serialize_field());
+impl_serialize_compound!(SerializeMap, serialize_key(), serialize_value());
+impl_serialize_compound!(SerializeStruct, serialize_field(&'static str));
+impl_serialize_compound!(SerializeStructVariant, serialize_field(&'static str));
+
+struct Deserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> Deserializer<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if len > self.input.len() {
+            return Err(Error);
+        }
+        let (taken, rest) = self.input.split_at(len);
+        self.input = rest;
+
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+
+        Ok(array)
+    }
+
+    fn take_tag(&mut self) -> Result<bool, Error> {
+        match self.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(Error),
+        }
+    }
+
+    fn take_len(&mut self) -> Result<usize, Error> {
+        let len = u64::from_le_bytes(self.take_array()?);
+        if len > usize::MAX as u64 {
+            return Err(Error);
+        }
+
+        Ok(len as usize)
+    }
+
+    fn take_bytes(&mut self) -> Result<&'de [u8], Error> {
+        let len = self.take_len()?;
+        self.take(len)
+    }
+
+    fn take_str(&mut self) -> Result<&'de str, Error> {
+        core::str::from_utf8(self.take_bytes()?).map_err(|_| Error)
+    }
+
+    fn access(&mut self, remaining: usize) -> Access<'_, 'de> {
+        Access {
+            deserializer: self,
+            remaining,
+        }
+    }
+}
+
+macro_rules! deserialize_le (($method:ident, $visit:ident, $ty:ty) => (
+    fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.$visit(<$ty>::from_le_bytes(self.take_array()?))
+    }
+));
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        // The format is not self-describing.
+        Err(Error)
+    }
+
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error)
+    }
+
+    deserialize_le!(deserialize_i8, visit_i8, i8);
+    deserialize_le!(deserialize_i16, visit_i16, i16);
+    deserialize_le!(deserialize_i32, visit_i32, i32);
+    deserialize_le!(deserialize_i64, visit_i64, i64);
+    deserialize_le!(deserialize_i128, visit_i128, i128);
+    deserialize_le!(deserialize_u8, visit_u8, u8);
+    deserialize_le!(deserialize_u16, visit_u16, u16);
+    deserialize_le!(deserialize_u32, visit_u32, u32);
+    deserialize_le!(deserialize_u64, visit_u64, u64);
+    deserialize_le!(deserialize_u128, visit_u128, u128);
+    deserialize_le!(deserialize_f32, visit_f32, f32);
+    deserialize_le!(deserialize_f64, visit_f64, f64);
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.take_tag()?)
+    }
+
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // The length of a UTF-8 encoding follows from the leading ones of its first byte.
+        let width = match self.input.first().map(|first| first.leading_ones()) {
+            Some(0) => 1,
+            Some(ones @ 2..=4) => ones as usize,
+            _ => return Err(Error),
+        };
+        let encoded = core::str::from_utf8(self.take(width)?).map_err(|_| Error)?;
+        visitor.visit_char(encoded.chars().next().ok_or(Error)?)
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.take_str()?)
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_bytes(self.take_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.take_tag()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.take_len()?;
+        visitor.visit_seq(self.access(len))
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let len = self.take_len()?;
+        visitor.visit_map(self.access(len))
+    }
+
+    fn deserialize_tuple<V: de::Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self.access(len))
+    }
+
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self.access(len))
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self.access(fields.len()))
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// Access to the elements of a sequence, tuple, struct or map.
+struct Access<'a, 'de> {
+    deserializer: &'a mut Deserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        de::SeqAccess::next_element_seed(self, seed)
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        use de::IntoDeserializer;
+
+        let index = u32::from_le_bytes(self.take_array()?);
+        let value = seed.deserialize(index.into_deserializer())?;
+
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_seq(self.access(len))
+    }
+
+    fn struct_variant<V: de::Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_seq(self.access(fields.len()))
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Kind {
+        Unit,
+        Newtype(u16),
+        Tuple(i8, char),
+        Struct { flag: bool, name: String },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Unit;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Wrapper(u64);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Value {
+        id: u128,
+        negative: i64,
+        ratio: f32,
+        precise: f64,
+        chars: Vec<char>,
+        bytes: Vec<u8>,
+        optional: Option<String>,
+        missing: Option<u32>,
+        wrapper: Wrapper,
+        unit: Unit,
+        tuple: (u8, i16, ()),
+        kinds: Vec<Kind>,
+        map: BTreeMap<String, i32>,
+        array: [u8; 3],
+    }
+
+    fn value() -> Value {
+        let mut map = BTreeMap::new();
+        map.insert("one".to_string(), 1);
+        map.insert("minus two".to_string(), -2);
+
+        Value {
+            id: u128::MAX - 7,
+            negative: i64::MIN,
+            ratio: 1.5,
+            precise: -0.1,
+            chars: vec!['a', 'ø', '€', '🦀'],
+            bytes: vec![0, 1, 2, 255],
+            optional: Some("orion".to_string()),
+            missing: None,
+            wrapper: Wrapper(7),
+            unit: Unit,
+            tuple: (1, -300, ()),
+            kinds: vec![
+                Kind::Unit,
+                Kind::Newtype(65535),
+                Kind::Tuple(-1, 'x'),
+                Kind::Struct {
+                    flag: true,
+                    name: "name".to_string(),
+                },
+            ],
+            map,
+            array: [7, 8, 9],
+        }
+    }
+
+    #[test]
+    fn test_same_as_bincode() {
+        let encoded = to_vec(&value()).unwrap();
+        assert_eq!(encoded, bincode::serialize(&value()).unwrap());
+        assert_eq!(from_slice::<Value>(&encoded).unwrap(), value());
+
+        assert_eq!(to_vec("str").unwrap(), bincode::serialize("str").unwrap());
+        assert_eq!(to_vec(&()).unwrap(), Vec::<u8>::new());
+
+        // The buffer grows many times.
+        let long: Vec<u16> = (0..5000).collect();
+        assert_eq!(to_vec(&long).unwrap(), bincode::serialize(&long).unwrap());
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let encoded = to_vec(&("borrowed", &b"bytes"[..])).unwrap();
+        let (s, b): (&str, &[u8]) = from_slice(&encoded).unwrap();
+        assert_eq!(s, "borrowed");
+        assert_eq!(b, b"bytes");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let encoded = to_vec(&value()).unwrap();
+        for len in 0..encoded.len() {
+            assert!(from_slice::<Value>(&encoded[..len]).is_err());
+        }
+        let mut trailing = encoded.clone();
+        trailing.push(0);
+        assert!(from_slice::<Value>(&trailing).is_err());
+
+        assert!(from_slice::<bool>(&[2]).is_err());
+        assert!(from_slice::<Option<u8>>(&[2, 0]).is_err());
+        assert!(from_slice::<char>(&[0xff]).is_err());
+        assert!(from_slice::<char>(&[0xc3]).is_err());
+        assert!(from_slice::<char>(&[0xed, 0xa0, 0x80]).is_err());
+        assert!(from_slice::<String>(&[1, 0, 0, 0, 0, 0, 0, 0, 0xff]).is_err());
+        assert!(from_slice::<Kind>(&[4, 0, 0, 0]).is_err());
+        // A length that is larger than the input.
+        assert!(from_slice::<Vec<u8>>(&[0xff; 8]).is_err());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        struct UnknownLength;
+
+        impl Serialize for UnknownLength {
+            fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use ser::SerializeSeq;
+                serializer.serialize_seq(None)?.end()
+            }
+        }
+
+        assert!(to_vec(&UnknownLength).is_err());
+        assert!(from_slice::<serde_json::Value>(&[0]).is_err());
+
+        let err: UnknownCryptoError = to_vec(&UnknownLength).unwrap_err().into();
+        assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+    }
+}
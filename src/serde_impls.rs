@@ -0,0 +1,341 @@
+// MIT License
+
+// Copyright (c) 2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Helpers for the `Serialize` and `Deserialize` implementations of orion's types.
+//!
+//! Human-readable formats, such as JSON, encode bytes as lowercase hex. Binary formats
+//! encode the raw bytes. All intermediate buffers are zeroed before being dropped, so
+//! that the same helpers can be used when the user opts in to serializing secret keys.
+
+use crate::util;
+use core::fmt;
+use serde::{de, ser, Deserializer, Serializer};
+
+/// Serialize `bytes` as hex if the format is human-readable and as raw bytes otherwise.
+pub fn serialize_bytes<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if !serializer.is_human_readable() {
+        return serializer.serialize_bytes(bytes);
+    }
+
+    let encoded_len = bytes
+        .len()
+        .checked_mul(2)
+        .ok_or_else(|| ser::Error::custom("input too long to hex encode"))?;
+    let mut encoded = vec![0u8; encoded_len];
+    util::hex_encode(bytes, &mut encoded).map_err(ser::Error::custom)?;
+    // `hex_encode()` only ever outputs ASCII, so this never fails.
+    let res = match core::str::from_utf8(&encoded) {
+        Ok(encoded_str) => serializer.serialize_str(encoded_str),
+        Err(err) => Err(ser::Error::custom(err)),
+    };
+    encoded.iter_mut().for_each(|b| *b = 0);
+
+    res
+}
+
+/// Deserialize bytes that were serialized with [`serialize_bytes()`].
+///
+/// The caller is responsible for zeroing the returned `Vec` if it holds secret data.
+pub fn deserialize_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(HexVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+struct HexVisitor;
+
+impl<'de> de::Visitor<'de> for HexVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a hex-encoded string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if v.len() & 1 != 0 {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+
+        let mut decoded = vec![0u8; v.len() / 2];
+        util::hex_decode(v.as_bytes(), &mut decoded)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str("<omitted>"), &self))?;
+
+        Ok(decoded)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> de::Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        // Cap the pre-allocation, so that an untrusted length doesn't cause a huge allocation.
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+
+        Ok(bytes)
+    }
+}
+
+#[allow(clippy::module_name_repetitions)]
+/// Opt-in (de)serialization of a [`SecretKey`], for use with `#[serde(with = "...")]`.
+///
+/// [`SecretKey`] does not implement `Serialize` and `Deserialize`, so that it is not
+/// accidentally written to logs, APIs or other places where secrets don't belong.
+///
+/// # Security:
+/// - The serialized key is __not__ protected in any way. It is only zeroed in the
+///   intermediate buffers orion itself uses. The output of the serializer, and the input
+///   of the deserializer, must be handled with the same care as the key itself.
+///
+/// # Example:
+/// ```rust
+/// use orion::kdf::{self, SecretKey};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct KeyFile {
+///     #[serde(with = "kdf::serde_secret_key")]
+///     key: SecretKey,
+/// }
+///
+/// let key_file = KeyFile { key: SecretKey::default() };
+/// let serialized = serde_json::to_string(&key_file).unwrap();
+/// let deserialized: KeyFile = serde_json::from_str(&serialized).unwrap();
+///
+/// assert_eq!(deserialized.key, key_file.key);
+/// ```
+///
+/// [`SecretKey`]: crate::kdf::SecretKey
+pub mod secret_key {
+    use crate::kdf::SecretKey;
+    use serde::{de, Deserializer, Serializer};
+
+    /// Serialize the `SecretKey`.
+    pub fn serialize<S>(secret_key: &SecretKey, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        super::serialize_bytes(secret_key.unprotected_as_bytes(), serializer)
+    }
+
+    /// Deserialize a `SecretKey`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<SecretKey, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut bytes = super::deserialize_bytes(deserializer)?;
+        let res = SecretKey::from_slice(&bytes).map_err(de::Error::custom);
+        bytes.iter_mut().for_each(|b| *b = 0);
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod public {
+    use crate::hazardous::{
+        aead::streaming::StreamHeader,
+        ecc::ristretto255::{Point, RISTRETTO255_POINTSIZE},
+        hash::{blake2b, sha2::sha512},
+        stream::{chacha20, xchacha20},
+    };
+    use crate::kdf::{self, Salt, SecretKey};
+    use crate::pwhash::{self, Password, PasswordHash};
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn test_nonce_roundtrip() {
+        let nonce = xchacha20::Nonce::generate();
+        let json = serde_json::to_string(&nonce).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(nonce.as_ref())));
+        assert_eq!(
+            serde_json::from_str::<xchacha20::Nonce>(&json).unwrap(),
+            nonce
+        );
+
+        let binary = bincode::serialize(&nonce).unwrap();
+        assert_eq!(
+            bincode::deserialize::<xchacha20::Nonce>(&binary).unwrap(),
+            nonce
+        );
+
+        let nonce = chacha20::Nonce::from([7u8; 12]);
+        let json = serde_json::to_string(&nonce).unwrap();
+        assert_eq!(json, "\"070707070707070707070707\"");
+        assert_eq!(
+            serde_json::from_str::<chacha20::Nonce>(&json).unwrap(),
+            nonce
+        );
+    }
+
+    #[test]
+    fn test_stream_header_roundtrip() {
+        let header = StreamHeader::generate();
+        let json = serde_json::to_string(&header).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(header.as_ref())));
+        assert_eq!(serde_json::from_str::<StreamHeader>(&json).unwrap(), header);
+
+        let binary = bincode::serialize(&header).unwrap();
+        assert_eq!(
+            bincode::deserialize::<StreamHeader>(&binary).unwrap(),
+            header
+        );
+
+        assert!(serde_json::from_str::<StreamHeader>("\"0707\"").is_err());
+    }
+
+    #[test]
+    fn test_digest_roundtrip() {
+        let digest = blake2b::Blake2b::new(None, 20)
+            .and_then(|mut ctx| {
+                ctx.update(b"orion")?;
+                ctx.finalize()
+            })
+            .unwrap();
+        let json = serde_json::to_string(&digest).unwrap();
+        let deserialized: blake2b::Digest = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, digest);
+        assert_eq!(deserialized.len(), 20);
+
+        let binary = bincode::serialize(&digest).unwrap();
+        assert_eq!(
+            bincode::deserialize::<blake2b::Digest>(&binary).unwrap(),
+            digest
+        );
+
+        let digest = sha512::Sha512::digest(b"orion").unwrap();
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(
+            serde_json::from_str::<sha512::Digest>(&json).unwrap(),
+            digest
+        );
+    }
+
+    #[test]
+    fn test_binary_is_raw_bytes() {
+        let nonce = chacha20::Nonce::from([1u8; 12]);
+        let binary = bincode::serialize(&nonce).unwrap();
+        // bincode prefixes a byte array with its length as a u64.
+        assert_eq!(&binary[..8], &12u64.to_le_bytes());
+        assert_eq!(&binary[8..], &[1u8; 12]);
+    }
+
+    #[test]
+    fn test_point_roundtrip() {
+        let point = Point::generator();
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(
+            json,
+            "\"e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76\""
+        );
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), point);
+
+        let binary = bincode::serialize(&point).unwrap();
+        assert_eq!(&binary[8..], &point.to_bytes()[..]);
+        assert_eq!(bincode::deserialize::<Point>(&binary).unwrap(), point);
+
+        // Non-canonical encodings are rejected, as with `Point::from_slice()`.
+        let non_canonical = [0xffu8; RISTRETTO255_POINTSIZE];
+        let json = serde_json::to_string(&hex::encode(non_canonical)).unwrap();
+        assert!(serde_json::from_str::<Point>(&json).is_err());
+    }
+
+    #[test]
+    fn test_salt_roundtrip() {
+        let salt = Salt::default();
+        let json = serde_json::to_string(&salt).unwrap();
+        assert_eq!(serde_json::from_str::<Salt>(&json).unwrap(), salt);
+        let binary = bincode::serialize(&salt).unwrap();
+        assert_eq!(bincode::deserialize::<Salt>(&binary).unwrap(), salt);
+    }
+
+    #[test]
+    fn test_password_hash_roundtrip() {
+        let password = Password::from_slice(b"password").unwrap();
+        let hash = pwhash::hash_password(&password, 3, 1 << 4).unwrap();
+
+        let json = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", hash.unprotected_as_encoded()));
+        assert_eq!(serde_json::from_str::<PasswordHash>(&json).unwrap(), hash);
+
+        let binary = bincode::serialize(&hash).unwrap();
+        assert_eq!(bincode::deserialize::<PasswordHash>(&binary).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_secret_key_opt_in_roundtrip() {
+        #[derive(Serialize, Deserialize)]
+        struct KeyFile {
+            #[serde(with = "kdf::serde_secret_key")]
+            key: SecretKey,
+        }
+
+        let key_file = KeyFile {
+            key: SecretKey::generate(64).unwrap(),
+        };
+        let json = serde_json::to_string(&key_file).unwrap();
+        let deserialized: KeyFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.key, key_file.key);
+
+        let binary = bincode::serialize(&key_file).unwrap();
+        let deserialized: KeyFile = bincode::deserialize(&binary).unwrap();
+        assert_eq!(deserialized.key, key_file.key);
+    }
+
+    #[test]
+    fn test_invalid_input_err() {
+        // Odd length, invalid characters and invalid lengths for the type.
+        assert!(serde_json::from_str::<chacha20::Nonce>("\"0707070707070707070707070\"").is_err());
+        assert!(serde_json::from_str::<chacha20::Nonce>("\"07070707070707070707070g\"").is_err());
+        assert!(serde_json::from_str::<chacha20::Nonce>("\"0707\"").is_err());
+        assert!(serde_json::from_str::<Salt>("\"\"").is_err());
+        assert!(serde_json::from_str::<PasswordHash>("\"$argon2i$v=19$\"").is_err());
+        assert!(bincode::deserialize::<xchacha20::Nonce>(&[0u8; 8]).is_err());
+    }
+}
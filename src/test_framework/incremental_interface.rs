@@ -20,7 +20,7 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::errors::UnknownCryptoError;
+use crate::errors::{ErrorKind, UnknownCryptoError};
 use core::marker::PhantomData;
 
 /// Trait to define default streaming contexts that can be tested.
@@ -79,6 +79,7 @@ where
         self.consistency(data);
         self.consistency(&[0u8; 0]);
         self.produces_same_state(data);
+        self.clone_forks_state(data);
 
         // Following test requires std.
         self.incremental_and_one_shot(data);
@@ -101,6 +102,7 @@ where
         self.consistency(&Self::DEFAULT_INPUT);
         self.consistency(&[0u8; 0]);
         self.produces_same_state(&Self::DEFAULT_INPUT);
+        self.clone_forks_state(&Self::DEFAULT_INPUT);
 
         // Following test requires std.
         self.incremental_processing_with_leftover();
@@ -124,6 +126,7 @@ where
         self.consistency(&Self::DEFAULT_INPUT);
         self.consistency(&[0u8; 0]);
         self.produces_same_state(&Self::DEFAULT_INPUT);
+        self.clone_forks_state(&Self::DEFAULT_INPUT);
         self.incremental_and_one_shot(&Self::DEFAULT_INPUT);
         self.double_finalize_with_reset_no_update_ok(&Self::DEFAULT_INPUT);
         self.double_finalize_with_reset_ok(&Self::DEFAULT_INPUT);
@@ -226,6 +229,34 @@ where
         T::compare_states(&state_3, &state_4);
     }
 
+    /// Testing that a state cloned after processing a common prefix can be
+    /// updated independently of the state it was cloned from.
+    fn clone_forks_state(&self, data: &[u8]) {
+        let suffix_a = [1u8; 3];
+        let suffix_b = [2u8; 130];
+
+        let mut expected_a = self._initial_context.clone();
+        expected_a.update(data).unwrap();
+        expected_a.update(&suffix_a).unwrap();
+        let mut expected_b = self._initial_context.clone();
+        expected_b.update(data).unwrap();
+        expected_b.update(&suffix_b).unwrap();
+
+        let mut prefix = self._initial_context.clone();
+        prefix.update(data).unwrap();
+        let mut fork_a = prefix.clone();
+        let mut fork_b = prefix.clone();
+        fork_a.update(&suffix_a).unwrap();
+        // Finalizing one fork must not affect the others.
+        assert_eq!(fork_a.finalize().unwrap(), expected_a.finalize().unwrap());
+        fork_b.update(&suffix_b).unwrap();
+        assert_eq!(fork_b.finalize().unwrap(), expected_b.finalize().unwrap());
+
+        let mut unchanged = self._initial_context.clone();
+        unchanged.update(data).unwrap();
+        T::compare_states(&prefix, &unchanged);
+    }
+
     #[cfg(feature = "safe_api")]
     /// Test for issues when incrementally processing data
     /// with leftover in the internal buffer. It should produce
@@ -293,7 +324,10 @@ where
         let mut state = self._initial_context.clone();
         state.update(data).unwrap();
         let _ = state.finalize().unwrap();
-        assert!(state.finalize().is_err());
+        assert_eq!(
+            state.finalize().unwrap_err().kind(),
+            ErrorKind::InvalidState
+        );
     }
 
     /// new(), update(), finalize(), reset(), update(): OK
@@ -23,15 +23,14 @@
 ///
 /// Trait implementation macros
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 /// Macro that implements the `Default` trait using a CSPRNG.
 macro_rules! impl_default_trait (($name:ident, $size:expr) => (
     impl Default for $name {
-        #[cfg(feature = "safe_api")]
-        /// Randomly generate using a CSPRNG with recommended size. Not available in `no_std` context.
+        /// Randomly generate using a CSPRNG with recommended size.
         fn default() -> $name {
             let mut value = vec![0u8; $size];
-            crate::util::secure_rand_bytes(&mut value).unwrap();
+            crate::util::secure_rand_bytes_or_panic(&mut value);
 
             $name { value, original_length: $size }
         }
@@ -42,7 +41,8 @@ macro_rules! impl_default_trait (($name:ident, $size:expr) => (
 /// provides a given $bytes_function to return a slice. This `PartialEq` will
 /// execute in constant-time.
 ///
-/// This also provides an empty `Eq` implementation.
+/// This also provides an empty `Eq` implementation and an implementation
+/// of `subtle::ConstantTimeEq`.
 macro_rules! impl_ct_partialeq_trait (($name:ident, $bytes_function:ident) => (
     impl PartialEq<$name> for $name {
         fn eq(&self, other: &$name) -> bool {
@@ -63,6 +63,12 @@ macro_rules! impl_ct_partialeq_trait (($name:ident, $bytes_function:ident) => (
                 .ct_eq(*other)).into()
         }
     }
+
+    impl subtle::ConstantTimeEq for $name {
+        fn ct_eq(&self, other: &$name) -> subtle::Choice {
+            self.$bytes_function().ct_eq(other.$bytes_function())
+        }
+    }
 ));
 
 /// Macro that implements the `Debug` trait on a object called `$name`.
@@ -87,12 +93,13 @@ macro_rules! impl_normal_debug_trait (($name:ident) => (
 
 /// Macro that implements the `Drop` trait on a object called `$name` which has
 /// a field `value`. This `Drop` will zero out the field `value` when the
-/// objects destructor is called.
+/// objects destructor is called. If `value` is a `Vec`, its spare capacity is
+/// zeroed as well.
 macro_rules! impl_drop_trait (($name:ident) => (
     impl Drop for $name {
         fn drop(&mut self) {
             use zeroize::Zeroize;
-            self.value.iter_mut().zeroize();
+            self.value.zeroize();
         }
     }
 ));
@@ -110,23 +117,141 @@ macro_rules! impl_asref_trait (($name:ident) => (
     }
 ));
 
+/// Macro that implements the `serde::{Serialize, Deserialize}` traits on a object
+/// called `$name`, using `$bytes_function` to serialize and `from_slice()` to
+/// deserialize. This should only be implemented on public types.
+macro_rules! impl_serde_traits (($name:ident, $bytes_function:ident) => (
+    #[cfg(all(feature = "serde", feature = "safe_api"))]
+    /// Serialized as a hex string for human-readable formats and as raw bytes otherwise.
+    impl serde::Serialize for $name {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            $crate::serde_impls::serialize_bytes(&self.$bytes_function()[..], serializer)
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "safe_api"))]
+    /// Deserialized from a hex string for human-readable formats and from raw bytes otherwise.
+    /// The result is built using `from_slice()`.
+    impl<'de> serde::Deserialize<'de> for $name {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            let bytes = $crate::serde_impls::deserialize_bytes(deserializer)?;
+            Self::from_slice(&bytes).map_err(serde::de::Error::custom)
+        }
+    }
+));
+
+/// Macro that implements the `Display` and `FromStr` traits on a object called `$name`,
+/// using lowercase hexadecimal of `$bytes_function`, encoded and decoded in constant time.
+/// `FromStr` accepts either case and builds the result using `from_slice()`. This should only
+/// be implemented on types that are not secret.
+macro_rules! impl_hex_display_fromstr_traits (($name:ident, $bytes_function:ident, $upper_bound:expr) => (
+    impl core::fmt::Display for $name {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            $crate::util::hex_fmt(self.$bytes_function(), f)
+        }
+    }
+
+    impl core::str::FromStr for $name {
+        type Err = UnknownCryptoError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            if s.len() % 2 != 0 || s.len() / 2 > $upper_bound {
+                return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
+            }
+
+            let mut bytes = [0u8; $upper_bound];
+            $crate::util::hex_decode(s.as_bytes(), &mut bytes[..s.len() / 2])?;
+
+            Self::from_slice(&bytes[..s.len() / 2])
+        }
+    }
+));
+
 /// Macro that implements the `From<[T]>` trait on a object called `$name`
 /// which has fields `value` and `original_length`. It implements From
 /// based on `$size` and this macro should, in most cases, only be used for
-/// types which have a fixed-length.
+/// types which have a fixed-length. It also adds a `const fn from_array()`,
+/// which does the same but can be used to define constants.
 macro_rules! impl_from_trait (($name:ident, $size:expr) => (
-    impl From<[u8; $size]> for $name {
+    impl $name {
         #[inline]
-        /// Make an object from a byte array.
-        fn from(bytes: [u8; $size]) -> $name {
+        /// Make an object from a byte array. Unlike `From`, this can be used
+        /// in a `const` context, such as with [`secretkey!`](crate::secretkey).
+        pub const fn from_array(bytes: [u8; $size]) -> $name {
             $name {
                 value: bytes,
                 original_length: $size
             }
         }
     }
+
+    impl From<[u8; $size]> for $name {
+        #[inline]
+        /// Make an object from a byte array.
+        fn from(bytes: [u8; $size]) -> $name {
+            $name::from_array(bytes)
+        }
+    }
 ));
 
+/// Macro that implements `TryFrom<&[u8]>` and, with the `alloc` feature, `TryFrom<Vec<u8>>`
+/// on a object called `$name`, using `from_slice()`. The `secret` variant should be used for
+/// types containing sensitive data, which zeroes the consumed `Vec` before it is dropped.
+macro_rules! impl_try_from_traits {
+    ($name:ident) => (
+        impl_try_from_traits!(@slice $name);
+
+        #[cfg(feature = "alloc")]
+        impl core::convert::TryFrom<alloc::vec::Vec<u8>> for $name {
+            type Error = $crate::errors::UnknownCryptoError;
+
+            #[inline]
+            /// Make an object from a `Vec`, using `from_slice()`.
+            fn try_from(bytes: alloc::vec::Vec<u8>) -> Result<$name, Self::Error> {
+                $name::from_slice(&bytes)
+            }
+        }
+    );
+
+    (secret $name:ident) => (
+        impl_try_from_traits!(@slice $name);
+
+        #[cfg(feature = "alloc")]
+        impl core::convert::TryFrom<alloc::vec::Vec<u8>> for $name {
+            type Error = $crate::errors::UnknownCryptoError;
+
+            #[inline]
+            /// Make an object from a `Vec`, using `from_slice()`. The `Vec` is zeroed
+            /// before it is dropped, whether or not this succeeds.
+            fn try_from(mut bytes: alloc::vec::Vec<u8>) -> Result<$name, Self::Error> {
+                use zeroize::Zeroize;
+                let res = $name::from_slice(&bytes);
+                bytes.iter_mut().zeroize();
+
+                res
+            }
+        }
+    );
+
+    (@slice $name:ident) => (
+        impl core::convert::TryFrom<&[u8]> for $name {
+            type Error = $crate::errors::UnknownCryptoError;
+
+            #[inline]
+            /// Make an object from a byte slice, using `from_slice()`.
+            fn try_from(slice: &[u8]) -> Result<$name, Self::Error> {
+                $name::from_slice(slice)
+            }
+        }
+    );
+}
+
 ///
 /// Function implementation macros
 
@@ -144,7 +269,7 @@ macro_rules! func_from_slice (($name:ident, $lower_bound:expr, $upper_bound:expr
         let slice_len = slice.len();
 
         if !($lower_bound..=$upper_bound).contains(&slice_len) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
         }
 
         let mut value = [0u8; $upper_bound];
@@ -154,17 +279,16 @@ macro_rules! func_from_slice (($name:ident, $lower_bound:expr, $upper_bound:expr
     }
 ));
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 /// Macro to implement a `from_slice()` function. Returns `UnknownCryptoError`
 /// if the slice is empty.
 macro_rules! func_from_slice_variable_size (($name:ident) => (
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    #[cfg(feature = "safe_api")]
     /// Construct from a given byte slice.
     pub fn from_slice(slice: &[u8]) -> Result<$name, UnknownCryptoError> {
         // See issue on `isize` limit: https://github.com/orion-rs/orion/issues/130
         if slice.is_empty() || slice.len() > (isize::MAX as usize) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
         }
 
         Ok($name { value: Vec::from(slice), original_length: slice.len() })
@@ -205,37 +329,46 @@ macro_rules! func_is_empty (() => (
     }
 ));
 
+/// Macro to implement a `to_hex()` function, which returns the bytes
+/// given by $bytes_function as a lowercase hexadecimal `String`.
+macro_rules! func_to_hex (($bytes_function:ident) => (
+    #[cfg(feature = "safe_api")]
+    /// Return the object as a lowercase hexadecimal string, encoded in constant time.
+    /// Not available in `no_std` context.
+    pub fn to_hex(&self) -> String {
+        crate::util::hex_encode_to_string(self.$bytes_function())
+    }
+));
+
 /// Macro to implement a `generate()` function for objects that benefit from
 /// having a CSPRNG available to generate data of a fixed length $gen_length.
 macro_rules! func_generate (($name:ident, $upper_bound:expr, $gen_length:expr) => (
-    #[cfg(feature = "safe_api")]
-    /// Randomly generate using a CSPRNG. Not available in `no_std` context.
+    #[cfg(feature = "getrandom")]
+    /// Randomly generate using a CSPRNG. Requires the `getrandom` feature in `no_std` context.
     pub fn generate() -> $name {
         let mut value = [0u8; $upper_bound];
         // This will not panic on size, unless the newtype has been defined with $upper_bound
         // or $gen_length equal to 0.
-        crate::util::secure_rand_bytes(&mut value[..$gen_length]).unwrap();
+        crate::util::secure_rand_bytes_or_panic(&mut value[..$gen_length]);
 
         $name { value, original_length: $gen_length }
     }
 ));
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 /// Macro to implement a `generate()` function for objects that benefit from
 /// having a CSPRNG available to generate data of a variable length.
 macro_rules! func_generate_variable_size (($name:ident) => (
     #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-    #[cfg(feature = "safe_api")]
-    /// Randomly generate using a CSPRNG. Not available in `no_std` context.
+    /// Randomly generate using a CSPRNG.
     pub fn generate(length: usize) -> Result<$name, UnknownCryptoError> {
         // See issue on `isize` limit: https://github.com/orion-rs/orion/issues/130
         if length < 1 || length > (isize::MAX as usize) {
-            return Err(UnknownCryptoError);
+            return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
         }
 
         let mut value = vec![0u8; length];
-        // This cannot panic on size input due to above length checks.
-        crate::util::secure_rand_bytes(&mut value).unwrap();
+        crate::util::secure_rand_bytes(&mut value)?;
 
         Ok($name { value, original_length: length })
     }
@@ -267,6 +400,29 @@ macro_rules! test_partial_eq (($name:ident, $upper_bound:expr) => (
         // PartialEq<&[u8]>
         assert!($name::from_slice(&[0u8; $upper_bound]).unwrap() == [0u8; $upper_bound].as_ref());
         assert!($name::from_slice(&[0u8; $upper_bound]).unwrap() != [1u8; $upper_bound].as_ref());
+        // ConstantTimeEq
+        use subtle::ConstantTimeEq;
+        assert!(bool::from($name::from_slice(&[0u8; $upper_bound]).unwrap().ct_eq(&$name::from_slice(&[0u8; $upper_bound]).unwrap())));
+        assert!(!bool::from($name::from_slice(&[0u8; $upper_bound]).unwrap().ct_eq(&$name::from_slice(&[1u8; $upper_bound]).unwrap())));
+    }
+));
+
+#[cfg(test)]
+macro_rules! test_try_from (($name:ident, $lower_bound:expr, $upper_bound:expr) => (
+    #[test]
+    fn test_try_from() {
+        use core::convert::TryFrom;
+
+        assert!($name::try_from(&[1u8; $upper_bound][..]).unwrap() == $name::from_slice(&[1u8; $upper_bound]).unwrap());
+        assert!($name::try_from(&[1u8; $lower_bound][..]).is_ok());
+        assert!($name::try_from(&[0u8; 0][..]).is_err());
+
+        #[cfg(feature = "alloc")]
+        {
+            assert!($name::try_from(alloc::vec![1u8; $upper_bound]).unwrap() == $name::from_slice(&[1u8; $upper_bound]).unwrap());
+            assert!($name::try_from(alloc::vec![1u8; $lower_bound]).is_ok());
+            assert!($name::try_from(alloc::vec![0u8; 0]).is_err());
+        }
     }
 ));
 
@@ -337,6 +493,21 @@ macro_rules! test_generate (($name:ident, $gen_length:expr) => (
     }
 ));
 
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+macro_rules! test_to_hex (($name:ident, $upper_bound:expr, $bytes_function:ident) => (
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_to_hex() {
+        let test_value = $name::from_slice(&[0xabu8; $upper_bound]).unwrap();
+        let hex_value = test_value.to_hex();
+
+        assert_eq!(hex_value.len(), $upper_bound * 2);
+        assert_eq!(hex_value, "ab".repeat($upper_bound));
+        assert_eq!(hex_value, hex::encode(test_value.$bytes_function()));
+    }
+));
+
 #[cfg(test)]
 #[cfg(feature = "safe_api")]
 macro_rules! test_omitted_debug (($name:ident, $upper_bound:expr) => (
@@ -421,6 +592,9 @@ macro_rules! construct_secret_key {
         /// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
         /// that the type implements.
         ///
+        /// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+        /// in custom verification logic that should not short-circuit.
+        ///
         /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
         /// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
         /// is implemented in such a way that the comparison happens in constant time. Thus, users should
@@ -446,6 +620,7 @@ macro_rules! construct_secret_key {
         impl_omitted_debug_trait!($name);
         impl_drop_trait!($name);
         impl_ct_partialeq_trait!($name, unprotected_as_bytes);
+        impl_try_from_traits!(secret $name);
 
         impl $name {
             func_from_slice!($name, $lower_bound, $upper_bound);
@@ -461,6 +636,7 @@ macro_rules! construct_secret_key {
 
             test_bound_parameters!($name, $lower_bound, $upper_bound, $gen_length);
             test_from_slice!($name, $lower_bound, $upper_bound);
+            test_try_from!($name, $lower_bound, $upper_bound);
             test_as_bytes_and_get_length!($name, $lower_bound, $upper_bound, unprotected_as_bytes);
             test_partial_eq!($name, $upper_bound);
 
@@ -497,14 +673,18 @@ macro_rules! construct_public {
         #[derive(Clone, Copy)]
         $(#[$meta])*
         ///
+        /// `PartialEq` and [`ConstantTimeEq`](crate::util::ConstantTimeEq) are
+        /// implemented for this type in constant time.
         pub struct $name {
             value: [u8; $upper_bound],
             original_length: usize,
         }
 
         impl_ct_partialeq_trait!($name, as_ref);
+        impl_try_from_traits!($name);
         impl_normal_debug_trait!($name);
         impl_asref_trait!($name);
+        impl_serde_traits!($name, as_ref);
 
         impl $name {
             func_from_slice!($name, $lower_bound, $upper_bound);
@@ -519,6 +699,7 @@ macro_rules! construct_public {
             // generate() function.
             test_bound_parameters!($name, $lower_bound, $upper_bound, $upper_bound);
             test_from_slice!($name, $lower_bound, $upper_bound);
+            test_try_from!($name, $lower_bound, $upper_bound);
             test_as_bytes_and_get_length!($name, $lower_bound, $upper_bound, as_ref);
             test_partial_eq!($name, $upper_bound);
 
@@ -537,14 +718,18 @@ macro_rules! construct_public {
         #[derive(Clone, Copy)]
         $(#[$meta])*
         ///
+        /// `PartialEq` and [`ConstantTimeEq`](crate::util::ConstantTimeEq) are
+        /// implemented for this type in constant time.
         pub struct $name {
             value: [u8; $upper_bound],
             original_length: usize,
         }
 
         impl_ct_partialeq_trait!($name, as_ref);
+        impl_try_from_traits!($name);
         impl_normal_debug_trait!($name);
         impl_asref_trait!($name);
+        impl_serde_traits!($name, as_ref);
 
         impl $name {
             func_from_slice!($name, $lower_bound, $upper_bound);
@@ -558,6 +743,7 @@ macro_rules! construct_public {
             use super::*;
             test_bound_parameters!($name, $lower_bound, $upper_bound, $upper_bound);
             test_from_slice!($name, $lower_bound, $upper_bound);
+            test_try_from!($name, $lower_bound, $upper_bound);
             test_as_bytes_and_get_length!($name, $lower_bound, $upper_bound, as_ref);
             test_partial_eq!($name, $upper_bound);
 
@@ -584,6 +770,9 @@ macro_rules! construct_tag {
         /// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
         /// that the type implements.
         ///
+        /// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+        /// in custom verification logic that should not short-circuit.
+        ///
         /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
         /// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
         /// is implemented in such a way that the comparison happens in constant time. Thus, users should
@@ -612,10 +801,12 @@ macro_rules! construct_tag {
 
         impl_omitted_debug_trait!($name);
         impl_ct_partialeq_trait!($name, unprotected_as_bytes);
+        impl_try_from_traits!(secret $name);
 
         impl $name {
             func_from_slice!($name, $lower_bound, $upper_bound);
             func_unprotected_as_bytes!();
+            func_to_hex!(unprotected_as_bytes);
             func_len!();
             func_is_empty!();
         }
@@ -627,6 +818,7 @@ macro_rules! construct_tag {
             // generate() function.
             test_bound_parameters!($name, $lower_bound, $upper_bound, $upper_bound);
             test_from_slice!($name, $lower_bound, $upper_bound);
+            test_try_from!($name, $lower_bound, $upper_bound);
             test_as_bytes_and_get_length!($name, $lower_bound, $upper_bound, unprotected_as_bytes);
             test_partial_eq!($name, $upper_bound);
 
@@ -636,6 +828,7 @@ macro_rules! construct_tag {
                 use super::*;
 
                 test_omitted_debug!($name, $upper_bound);
+                test_to_hex!($name, $upper_bound, unprotected_as_bytes);
             }
         }
     );
@@ -652,6 +845,9 @@ macro_rules! construct_hmac_key {
         /// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
         /// that the type implements.
         ///
+        /// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+        /// in custom verification logic that should not short-circuit.
+        ///
         /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
         /// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
         /// is implemented in such a way that the comparison happens in constant time. Thus, users should
@@ -677,6 +873,7 @@ macro_rules! construct_hmac_key {
         impl_omitted_debug_trait!($name);
         impl_drop_trait!($name);
         impl_ct_partialeq_trait!($name, unprotected_as_bytes);
+        impl_try_from_traits!(secret $name);
 
         impl $name {
             #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
@@ -726,19 +923,21 @@ macro_rules! construct_hmac_key {
     );
 }
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 /// Macro to construct a type containing sensitive data which is stored on the
 /// heap.
 macro_rules! construct_secret_key_variable_size {
     ($(#[$meta:meta])*
     ($name:ident, $test_module_name:ident, $default_size:expr)) => (
-        #[cfg(feature = "safe_api")]
         $(#[$meta])*
         ///
         /// # Security:
         /// - __**Avoid using**__ `unprotected_as_bytes()` whenever possible, as it breaks all protections
         /// that the type implements.
         ///
+        /// - [`ConstantTimeEq`](crate::util::ConstantTimeEq) is implemented for this type, for use
+        /// in custom verification logic that should not short-circuit.
+        ///
         /// - The trait `PartialEq<&'_ [u8]>` is implemented for this type so that users are not tempted
         /// to call `unprotected_as_bytes` to compare this sensitive value to a byte slice. The trait
         /// is implemented in such a way that the comparison happens in constant time. Thus, users should
@@ -769,6 +968,7 @@ macro_rules! construct_secret_key_variable_size {
         impl_omitted_debug_trait!($name);
         impl_drop_trait!($name);
         impl_ct_partialeq_trait!($name, unprotected_as_bytes);
+        impl_try_from_traits!(secret $name);
         impl_default_trait!($name, $default_size);
 
         impl $name {
@@ -780,10 +980,12 @@ macro_rules! construct_secret_key_variable_size {
         }
 
         #[cfg(test)]
+        #[cfg(feature = "safe_api")]
         mod $test_module_name {
             use super::*;
 
             test_from_slice_variable!($name);
+            test_try_from!($name, 1, $default_size);
             test_as_bytes_and_get_length!($name, 1, $default_size + 1, unprotected_as_bytes);
             test_generate_variable!($name);
             test_omitted_debug!($name, $default_size);
@@ -792,15 +994,18 @@ macro_rules! construct_secret_key_variable_size {
     );
 }
 
-#[cfg(feature = "safe_api")]
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
 /// Macro to construct a type containing non-sensitive which is stored on the
 /// heap.
+///
+/// - $min_size: The minimum length accepted by `from_slice()` and `generate()`.
 macro_rules! construct_salt_variable_size {
     ($(#[$meta:meta])*
-    ($name:ident, $test_module_name:ident, $default_size:expr)) => (
-        #[cfg(feature = "safe_api")]
+    ($name:ident, $test_module_name:ident, $min_size:expr, $default_size:expr)) => (
         $(#[$meta])*
         ///
+        /// `PartialEq` and [`ConstantTimeEq`](crate::util::ConstantTimeEq) are
+        /// implemented for this type in constant time.
         pub struct $name {
             value: Vec<u8>,
             original_length: usize,
@@ -809,22 +1014,71 @@ macro_rules! construct_salt_variable_size {
         impl_normal_debug_trait!($name);
         impl_default_trait!($name, $default_size);
         impl_ct_partialeq_trait!($name, as_ref);
+        impl_try_from_traits!($name);
         impl_asref_trait!($name);
+        impl_serde_traits!($name, as_ref);
 
         impl $name {
-            func_from_slice_variable_size!($name);
+            #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+            /// Construct from a given byte slice.
+            pub fn from_slice(slice: &[u8]) -> Result<$name, UnknownCryptoError> {
+                // See issue on `isize` limit: https://github.com/orion-rs/orion/issues/130
+                if slice.len() < $min_size || slice.len() > (isize::MAX as usize) {
+                    return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
+                }
+
+                Ok($name { value: Vec::from(slice), original_length: slice.len() })
+            }
+
             func_len!();
             func_is_empty!();
-            func_generate_variable_size!($name);
+
+            #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+            /// Randomly generate using a CSPRNG.
+            pub fn generate(length: usize) -> Result<$name, UnknownCryptoError> {
+                // See issue on `isize` limit: https://github.com/orion-rs/orion/issues/130
+                if length < $min_size || length > (isize::MAX as usize) {
+                    return Err(UnknownCryptoError::new($crate::errors::ErrorKind::InvalidLength));
+                }
+
+                let mut value = vec![0u8; length];
+                crate::util::secure_rand_bytes(&mut value)?;
+
+                Ok($name { value, original_length: length })
+            }
         }
 
         #[cfg(test)]
+        #[cfg(feature = "safe_api")]
         mod $test_module_name {
             use super::*;
 
-            test_from_slice_variable!($name);
-            test_as_bytes_and_get_length!($name, 1, $default_size + 1, as_ref);
-            test_generate_variable!($name);
+            test_try_from!($name, $min_size, 128);
+
+            #[test]
+            fn test_from_slice_min_size() {
+                assert!($name::from_slice(&[0u8; 512]).is_ok());
+                assert!($name::from_slice(&[0u8; $min_size]).is_ok());
+                assert!($name::from_slice(&[0u8; $min_size - 1]).is_err());
+                assert!($name::from_slice(&[0u8; 0]).is_err());
+            }
+
+            #[test]
+            fn test_generate_min_size() {
+                assert!($name::generate(0).is_err());
+                assert!($name::generate($min_size - 1).is_err());
+                assert!($name::generate((isize::MAX as usize) + 1).is_err());
+                assert!($name::generate($min_size).is_ok());
+                assert!($name::generate(64).is_ok());
+
+                let test_zero = $name::from_slice(&[0u8; 128]).unwrap();
+                // A random one should never be all 0's.
+                let test_rand = $name::generate(128).unwrap();
+                assert!(test_zero != test_rand);
+                assert!(test_rand.len() == 128);
+            }
+
+            test_as_bytes_and_get_length!($name, $min_size, $default_size + 1, as_ref);
             test_partial_eq!($name, $default_size);
             test_normal_debug!($name, $default_size);
         }
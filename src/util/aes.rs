@@ -0,0 +1,380 @@
+// MIT License
+
+// Copyright (c) 2019-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Constant-time software implementation of the AES block cipher, as specified in
+//! [FIPS 197](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.197-upd1.pdf).
+//!
+//! No lookup tables are used. The S-box is computed as the inversion in `GF(2^8)`
+//! followed by the affine transformation, on all 16 bytes of the state at once.
+//! Hardware AES instructions are not used, because they cannot be reached without
+//! `unsafe` code.
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use zeroize::Zeroize;
+
+/// The blocksize which AES operates on.
+pub(crate) const AES_BLOCKSIZE: usize = 16;
+/// The maximum amount of rounds (AES-256).
+const MAX_ROUNDS: usize = 14;
+
+const LANES_01: u128 = 0x0101_0101_0101_0101_0101_0101_0101_0101;
+const LANES_7F: u128 = 0x7f7f_7f7f_7f7f_7f7f_7f7f_7f7f_7f7f_7f7f;
+
+const RCON: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+#[inline]
+/// Multiply each byte lane by `x` in `GF(2^8)`.
+fn xtime_lanes(a: u128) -> u128 {
+    ((a & LANES_7F) << 1) ^ (((a >> 7) & LANES_01) * 0x1b)
+}
+
+#[inline]
+/// Multiply each byte lane of `a` with the same lane of `b` in `GF(2^8)`.
+fn gf_mul_lanes(mut a: u128, b: u128) -> u128 {
+    let mut acc = 0u128;
+    for i in 0..8 {
+        let mask = ((b >> i) & LANES_01) * 0xff;
+        acc ^= a & mask;
+        a = xtime_lanes(a);
+    }
+
+    acc
+}
+
+#[inline]
+/// Rotate each byte lane left by `n` bits.
+fn rotl_lanes(a: u128, n: u32) -> u128 {
+    let hi = LANES_01.wrapping_mul((0xffu128 << n) & 0xff);
+    let lo = LANES_01.wrapping_mul(0xffu128 >> (8 - n));
+
+    ((a << n) & hi) | ((a >> (8 - n)) & lo)
+}
+
+/// Apply the S-box to each byte lane.
+fn sub_lanes(x: u128) -> u128 {
+    // x^254 is the inverse of x, with 0 mapping to 0.
+    let x2 = gf_mul_lanes(x, x);
+    let x3 = gf_mul_lanes(x2, x);
+    let x6 = gf_mul_lanes(x3, x3);
+    let x12 = gf_mul_lanes(x6, x6);
+    let x15 = gf_mul_lanes(x12, x3);
+    let x30 = gf_mul_lanes(x15, x15);
+    let x60 = gf_mul_lanes(x30, x30);
+    let x120 = gf_mul_lanes(x60, x60);
+    let x240 = gf_mul_lanes(x120, x120);
+    let x252 = gf_mul_lanes(x240, x12);
+    let inv = gf_mul_lanes(x252, x2);
+
+    inv ^ rotl_lanes(inv, 1)
+        ^ rotl_lanes(inv, 2)
+        ^ rotl_lanes(inv, 3)
+        ^ rotl_lanes(inv, 4)
+        ^ LANES_01.wrapping_mul(0x63)
+}
+
+/// Apply the inverse S-box to each byte lane.
+fn inv_sub_lanes(x: u128) -> u128 {
+    let y = rotl_lanes(x, 1) ^ rotl_lanes(x, 3) ^ rotl_lanes(x, 6) ^ LANES_01.wrapping_mul(0x05);
+
+    let y2 = gf_mul_lanes(y, y);
+    let y3 = gf_mul_lanes(y2, y);
+    let y6 = gf_mul_lanes(y3, y3);
+    let y12 = gf_mul_lanes(y6, y6);
+    let y15 = gf_mul_lanes(y12, y3);
+    let y30 = gf_mul_lanes(y15, y15);
+    let y60 = gf_mul_lanes(y30, y30);
+    let y120 = gf_mul_lanes(y60, y60);
+    let y240 = gf_mul_lanes(y120, y120);
+    let y252 = gf_mul_lanes(y240, y12);
+
+    gf_mul_lanes(y252, y2)
+}
+
+fn sub_bytes(state: &mut [u8; AES_BLOCKSIZE]) {
+    *state = sub_lanes(u128::from_le_bytes(*state)).to_le_bytes();
+}
+
+fn inv_sub_bytes(state: &mut [u8; AES_BLOCKSIZE]) {
+    *state = inv_sub_lanes(u128::from_le_bytes(*state)).to_le_bytes();
+}
+
+fn shift_rows(state: &mut [u8; AES_BLOCKSIZE]) {
+    // The state is stored column-major: byte `r + 4c` is row `r`, column `c`.
+    let s = *state;
+    for c in 0..4 {
+        for r in 0..4 {
+            state[r + 4 * c] = s[r + 4 * ((c + r) % 4)];
+        }
+    }
+}
+
+fn inv_shift_rows(state: &mut [u8; AES_BLOCKSIZE]) {
+    let s = *state;
+    for c in 0..4 {
+        for r in 0..4 {
+            state[r + 4 * ((c + r) % 4)] = s[r + 4 * c];
+        }
+    }
+}
+
+#[inline]
+fn xtime(b: u8) -> u8 {
+    (b << 1) ^ (((b >> 7) & 1) * 0x1b)
+}
+
+fn mix_columns(state: &mut [u8; AES_BLOCKSIZE]) {
+    for column in state.chunks_exact_mut(4) {
+        let (a0, a1, a2, a3) = (column[0], column[1], column[2], column[3]);
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] ^= all ^ xtime(a0 ^ a1);
+        column[1] ^= all ^ xtime(a1 ^ a2);
+        column[2] ^= all ^ xtime(a2 ^ a3);
+        column[3] ^= all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inv_mix_columns(state: &mut [u8; AES_BLOCKSIZE]) {
+    // InvMixColumns is MixColumns applied after multiplying columns with {04}x^2 + {05}.
+    for column in state.chunks_exact_mut(4) {
+        let u = xtime(xtime(column[0] ^ column[2]));
+        let v = xtime(xtime(column[1] ^ column[3]));
+        column[0] ^= u;
+        column[1] ^= v;
+        column[2] ^= u;
+        column[3] ^= v;
+    }
+    mix_columns(state);
+}
+
+fn add_round_key(state: &mut [u8; AES_BLOCKSIZE], round_key: &[u8; AES_BLOCKSIZE]) {
+    for (s, k) in state.iter_mut().zip(round_key.iter()) {
+        *s ^= k;
+    }
+}
+
+#[derive(Clone)]
+/// An AES-128, AES-192 or AES-256 key schedule.
+pub(crate) struct Aes {
+    round_keys: [[u8; AES_BLOCKSIZE]; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl Drop for Aes {
+    fn drop(&mut self) {
+        self.round_keys.iter_mut().for_each(|rk| rk.zeroize());
+    }
+}
+
+impl core::fmt::Debug for Aes {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Aes {{ round_keys: [***OMITTED***], rounds: {:?} }}",
+            self.rounds
+        )
+    }
+}
+
+impl Aes {
+    /// Expand `key`, which must be 16, 24 or 32 bytes.
+    pub(crate) fn new(key: &[u8]) -> Result<Self, UnknownCryptoError> {
+        let nk = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            _ => return Err(UnknownCryptoError::new(ErrorKind::InvalidLength)),
+        };
+        let rounds = nk + 6;
+
+        let mut words = [[0u8; 4]; 4 * (MAX_ROUNDS + 1)];
+        for (word, chunk) in words.iter_mut().zip(key.chunks_exact(4)) {
+            word.copy_from_slice(chunk);
+        }
+
+        for i in nk..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % nk == 0 {
+                temp.rotate_left(1);
+                sub_word(&mut temp);
+                temp[0] ^= RCON[i / nk - 1];
+            } else if nk > 6 && i % nk == 4 {
+                sub_word(&mut temp);
+            }
+            for j in 0..4 {
+                words[i][j] = words[i - nk][j] ^ temp[j];
+            }
+            temp.zeroize();
+        }
+
+        let mut round_keys = [[0u8; AES_BLOCKSIZE]; MAX_ROUNDS + 1];
+        for (round_key, four_words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (dst, word) in round_key.chunks_exact_mut(4).zip(four_words.iter()) {
+                dst.copy_from_slice(word);
+            }
+        }
+        words.iter_mut().for_each(|w| w.zeroize());
+
+        Ok(Self { round_keys, rounds })
+    }
+
+    /// Encrypt a single block in-place.
+    pub(crate) fn encrypt_block(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        add_round_key(block, &self.round_keys[0]);
+        for round_key in self.round_keys[1..self.rounds].iter() {
+            sub_bytes(block);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, round_key);
+        }
+        sub_bytes(block);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[self.rounds]);
+    }
+
+    /// Decrypt a single block in-place.
+    pub(crate) fn decrypt_block(&self, block: &mut [u8; AES_BLOCKSIZE]) {
+        add_round_key(block, &self.round_keys[self.rounds]);
+        inv_shift_rows(block);
+        inv_sub_bytes(block);
+        for round_key in self.round_keys[1..self.rounds].iter().rev() {
+            add_round_key(block, round_key);
+            inv_mix_columns(block);
+            inv_shift_rows(block);
+            inv_sub_bytes(block);
+        }
+        add_round_key(block, &self.round_keys[0]);
+    }
+}
+
+fn sub_word(word: &mut [u8; 4]) {
+    let mut block = [0u8; AES_BLOCKSIZE];
+    block[..4].copy_from_slice(word);
+    sub_bytes(&mut block);
+    word.copy_from_slice(&block[..4]);
+    block.zeroize();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encrypt(key: &[u8], block: &[u8; AES_BLOCKSIZE]) -> [u8; AES_BLOCKSIZE] {
+        let mut out = *block;
+        Aes::new(key).unwrap().encrypt_block(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_sbox_values() {
+        let mut block = [0u8; AES_BLOCKSIZE];
+        for (i, b) in block.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        block[15] = 0xff;
+        sub_bytes(&mut block);
+        assert_eq!(block[0], 0x63);
+        assert_eq!(block[1], 0x7c);
+        assert_eq!(block[2], 0x77);
+        assert_eq!(block[15], 0x16);
+    }
+
+    #[test]
+    fn test_inv_sbox_is_inverse() {
+        for start in (0..256usize).step_by(AES_BLOCKSIZE) {
+            let mut block = [0u8; AES_BLOCKSIZE];
+            for (i, b) in block.iter_mut().enumerate() {
+                *b = (start + i) as u8;
+            }
+            let original = block;
+            sub_bytes(&mut block);
+            inv_sub_bytes(&mut block);
+            assert_eq!(block, original);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_is_inverse() {
+        let mut block = [0u8; AES_BLOCKSIZE];
+        for key_len in [16usize, 24, 32].iter() {
+            let aes = Aes::new(&[7u8; 32][..*key_len]).unwrap();
+            for _ in 0..8 {
+                let original = block;
+                aes.encrypt_block(&mut block);
+                assert_ne!(block, original);
+                let next = block;
+                aes.decrypt_block(&mut block);
+                assert_eq!(block, original);
+                block = next;
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_length() {
+        assert!(Aes::new(&[0u8; 0]).is_err());
+        assert!(Aes::new(&[0u8; 15]).is_err());
+        assert!(Aes::new(&[0u8; 16]).is_ok());
+        assert!(Aes::new(&[0u8; 24]).is_ok());
+        assert!(Aes::new(&[0u8; 31]).is_err());
+        assert!(Aes::new(&[0u8; 32]).is_ok());
+        assert!(Aes::new(&[0u8; 33]).is_err());
+    }
+
+    #[test]
+    fn test_fips_197_appendix_c() {
+        let mut key = [0u8; 32];
+        for (i, b) in key.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let pt = [
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+            0xee, 0xff,
+        ];
+
+        assert_eq!(
+            encrypt(&key[..16], &pt),
+            [
+                0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4,
+                0xc5, 0x5a
+            ]
+        );
+        assert_eq!(
+            encrypt(&key[..24], &pt),
+            [
+                0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d,
+                0x71, 0x91
+            ]
+        );
+        assert_eq!(
+            encrypt(&key, &pt),
+            [
+                0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49,
+                0x60, 0x89
+            ]
+        );
+
+        for key_len in [16usize, 24, 32].iter() {
+            let aes = Aes::new(&key[..*key_len]).unwrap();
+            let mut block = encrypt(&key[..*key_len], &pt);
+            aes.decrypt_block(&mut block);
+            assert_eq!(block, pt);
+        }
+    }
+}
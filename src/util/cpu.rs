@@ -0,0 +1,180 @@
+// MIT License
+
+// Copyright (c) 2019-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Selection between the portable and wide-vector implementations of primitives.
+//!
+//! orion forbids `unsafe` code, so it cannot call intrinsics or
+//! `#[target_feature]` functions. A choice between implementations is therefore a
+//! choice between safe code paths whose speed depends on what the compiler was
+//! allowed to emit. A wide-vector path is only ever selected when the matching
+//! target feature was enabled at compile time (i.e. `-C target-feature=+avx2`).
+//! Runtime detection is used to report what the CPU supports and, with `safe_api`,
+//! the wide-vector paths can be switched off to test the portable ones, either by
+//! setting the `ORION_FORCE_PORTABLE` environment variable or with [`force_portable()`].
+//!
+//! Only ChaCha20 (and so XChaCha20 and the AEADs built on them) has a wide-vector path.
+//! Poly1305, BLAKE2b and SHA-2 have a single, portable implementation, so there is
+//! nothing for them to select between.
+
+/// Whether wide-vector code paths were enabled at compile time.
+const COMPILED_WIDE: bool = cfg!(any(target_feature = "avx2", target_feature = "neon"));
+
+/// Whether the compiled-in wide-vector code paths are supported by `features`.
+fn supports_wide(features: Features) -> bool {
+    COMPILED_WIDE && (features.avx2 || features.neon)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// CPU features relevant to orion's implementations.
+pub(crate) struct Features {
+    /// AVX2 on x86/x86_64.
+    pub(crate) avx2: bool,
+    /// NEON on AArch64/ARM.
+    pub(crate) neon: bool,
+    /// AES-NI on x86/x86_64.
+    pub(crate) aes: bool,
+}
+
+/// Detect the features of the running CPU.
+///
+/// Without `safe_api` (`no_std`), only the compile-time target features are reported.
+pub(crate) fn detect() -> Features {
+    #[cfg(all(feature = "safe_api", any(target_arch = "x86", target_arch = "x86_64")))]
+    {
+        Features {
+            avx2: std::is_x86_feature_detected!("avx2"),
+            neon: false,
+            aes: std::is_x86_feature_detected!("aes"),
+        }
+    }
+
+    #[cfg(not(all(feature = "safe_api", any(target_arch = "x86", target_arch = "x86_64"))))]
+    {
+        Features {
+            avx2: cfg!(target_feature = "avx2"),
+            neon: cfg!(target_feature = "neon"),
+            aes: cfg!(target_feature = "aes"),
+        }
+    }
+}
+
+#[cfg(feature = "safe_api")]
+mod state {
+    use core::sync::atomic::{AtomicU8, Ordering};
+
+    const UNINIT: u8 = 0;
+    const PORTABLE: u8 = 1;
+    const WIDE: u8 = 2;
+
+    static WIDE_VECTORS: AtomicU8 = AtomicU8::new(UNINIT);
+
+    pub(super) fn use_wide_vectors() -> bool {
+        match WIDE_VECTORS.load(Ordering::Relaxed) {
+            PORTABLE => false,
+            WIDE => true,
+            _ => {
+                let forced = std::env::var_os("ORION_FORCE_PORTABLE").is_some();
+                let wide = super::supports_wide(super::detect()) && !forced;
+                // A concurrent call to `force_portable()` takes precedence.
+                let _ = WIDE_VECTORS.compare_exchange(
+                    UNINIT,
+                    if wide { WIDE } else { PORTABLE },
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                );
+                WIDE_VECTORS.load(Ordering::Relaxed) == WIDE
+            }
+        }
+    }
+
+    pub(super) fn force_portable(force: bool) {
+        // Without forcing, the selection is made again on next use, so that
+        // `ORION_FORCE_PORTABLE` is respected.
+        WIDE_VECTORS.store(if force { PORTABLE } else { UNINIT }, Ordering::Relaxed);
+    }
+}
+
+#[inline]
+/// Whether wide-vector code paths should be used.
+pub(crate) fn use_wide_vectors() -> bool {
+    #[cfg(feature = "safe_api")]
+    {
+        COMPILED_WIDE && state::use_wide_vectors()
+    }
+
+    #[cfg(not(feature = "safe_api"))]
+    {
+        supports_wide(detect())
+    }
+}
+
+#[cfg(feature = "safe_api")]
+/// Force the use of portable code paths (`true`), or restore the default selection (`false`).
+/// Not available in `no_std` context.
+///
+/// # About:
+/// This has the same effect as setting the `ORION_FORCE_PORTABLE` environment variable,
+/// and is meant for testing and benchmarking the portable code paths on CPUs that support
+/// the wide-vector ones. Both kinds of path produce identical output. The setting applies
+/// to the whole process. Restoring the default selection honours `ORION_FORCE_PORTABLE`.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// util::force_portable(true);
+/// // ... run the code that should use the portable implementations.
+/// util::force_portable(false);
+/// ```
+pub fn force_portable(force: bool) {
+    state::force_portable(force)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_includes_compile_time_features() {
+        let features = detect();
+        // A binary compiled with a target feature can only run on CPUs that have it.
+        assert!(features.avx2 || !cfg!(target_feature = "avx2"));
+        assert!(features.aes || !cfg!(target_feature = "aes"));
+        assert!(features.neon || !cfg!(target_feature = "neon"));
+    }
+
+    #[test]
+    fn test_wide_vectors_require_compile_time_features() {
+        if !COMPILED_WIDE {
+            assert!(!use_wide_vectors());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "safe_api")]
+    fn test_force_portable() {
+        force_portable(true);
+        assert!(!use_wide_vectors());
+        force_portable(false);
+        assert_eq!(use_wide_vectors(), COMPILED_WIDE);
+    }
+}
@@ -20,6 +20,12 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+//! Conversions between bytes and little-endian words.
+//!
+//! The lengths of `src` and `dst` are asserted to match. Every caller passes
+//! buffers whose lengths are fixed at compile time or checked beforehand, so
+//! the asserts only catch bugs in orion and can never be triggered by input.
+
 use core::convert::TryInto;
 use core::mem;
 
@@ -77,9 +83,9 @@ impl_load_into!(u32, u32, from_le_bytes, load_u32_into_le);
 
 impl_load_into!(u64, u64, from_le_bytes, load_u64_into_le);
 
+#[cfg(test)]
 impl_store_into!(u32, to_le_bytes, store_u32_into_le);
 
-#[cfg(any(feature = "safe_api", feature = "alloc", test))]
 impl_store_into!(u64, to_le_bytes, store_u64_into_le);
 
 // Testing public functions in the module.
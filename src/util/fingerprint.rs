@@ -0,0 +1,258 @@
+// MIT License
+
+// Copyright (c) 2020-2021 The orion Developers
+
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::errors::{ErrorKind, UnknownCryptoError};
+use crate::hazardous::hash::blake2b::Hasher;
+use core::fmt;
+use core::str::FromStr;
+
+/// The size (bytes) of a [`Fingerprint`].
+pub const FINGERPRINT_SIZE: usize = 32;
+
+/// The z-base-32 alphabet.
+const ZBASE32_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+/// The amount of z-base-32 characters in the text form of a [`Fingerprint`].
+const ENCODED_LEN: usize = 52;
+/// The amount of characters in each group of the text form of a [`Fingerprint`].
+const GROUP_LEN: usize = 4;
+
+construct_public! {
+    /// A type to represent the fingerprint of a public key.
+    ///
+    /// # About:
+    /// The fingerprint is the BLAKE2b-256 digest of the encoded public key, meaning the
+    /// bytes returned by `as_ref()` on the key. It is computed with the `fingerprint()`
+    /// function of the public key types, such as
+    /// [`mldsa65::PublicKey::fingerprint()`](crate::hazardous::sig::mldsa65::PublicKey::fingerprint).
+    ///
+    /// The text form, given by `Display` and accepted by `FromStr`, is the
+    /// [z-base-32] encoding of the 32 bytes. This gives 52 characters, of which the last
+    /// holds the final bit of the digest followed by four zero bits. The characters are
+    /// grouped in 13 groups of 4, separated by a single space:
+    ///
+    /// `"8gu6 z89p agpk z1bw rzd8 kzq3 b3z3 4dry jf1k d7fk 74t5 u643 uy4o"`
+    ///
+    /// Parsing ignores ASCII whitespace and case, so fingerprints can be copied with other
+    /// grouping as well.
+    ///
+    /// # Security:
+    /// - A fingerprint identifies a key, but does not say which algorithm the key is for.
+    ///   When pinning, store the algorithm along with the fingerprint.
+    /// - All 52 characters must be compared when checking a fingerprint by hand.
+    ///   Comparing only a prefix makes it much easier to create a matching key.
+    ///
+    /// # Errors:
+    /// An error will be returned if:
+    /// - `slice` is not 32 bytes.
+    /// - The text form does not contain exactly 52 z-base-32 characters, or its padding
+    ///   bit is not zero.
+    ///
+    /// # Example:
+    /// ```rust
+    /// use orion::hazardous::sig::mldsa65::KeyPair;
+    /// use orion::util::Fingerprint;
+    ///
+    /// let keypair = KeyPair::generate();
+    /// let fingerprint = keypair.public().fingerprint()?;
+    ///
+    /// // Show the fingerprint to the user, or store it to pin the key.
+    /// let pinned = fingerprint.to_string();
+    ///
+    /// assert_eq!(pinned.parse::<Fingerprint>()?, keypair.public().fingerprint()?);
+    /// # Ok::<(), orion::errors::UnknownCryptoError>(())
+    /// ```
+    /// [z-base-32]: https://philzimmermann.com/docs/human-oriented-base-32-encoding.txt
+    (Fingerprint, test_fingerprint, FINGERPRINT_SIZE, FINGERPRINT_SIZE)
+}
+
+impl_from_trait!(Fingerprint, FINGERPRINT_SIZE);
+
+impl Fingerprint {
+    #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+    /// Compute the fingerprint of the encoded `public_key`.
+    pub(crate) fn of(public_key: &[u8]) -> Result<Self, UnknownCryptoError> {
+        Self::from_slice(Hasher::Blake2b256.digest(public_key)?.as_ref())
+    }
+
+    /// Encode the fingerprint as z-base-32, without grouping.
+    fn encode(&self) -> [u8; ENCODED_LEN] {
+        let mut encoded = [0u8; ENCODED_LEN];
+        let mut position = 0;
+        let mut buffer = 0u32;
+        let mut bits = 0;
+
+        for byte in self.value.iter() {
+            buffer = ((buffer << 8) | u32::from(*byte)) & 0x1fff;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded[position] = ZBASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize];
+                position += 1;
+            }
+        }
+        // The remaining bits are padded with zeroes.
+        encoded[position] = ZBASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize];
+
+        encoded
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.encode().iter().enumerate() {
+            if i > 0 && i % GROUP_LEN == 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", char::from(*c))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = UnknownCryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut value = [0u8; FINGERPRINT_SIZE];
+        let mut position = 0;
+        let mut count = 0;
+        let mut buffer = 0u32;
+        let mut bits = 0;
+
+        for c in s.bytes().filter(|c| !c.is_ascii_whitespace()) {
+            let c = c.to_ascii_lowercase();
+            let digit = match ZBASE32_ALPHABET.iter().position(|a| *a == c) {
+                Some(digit) if count < ENCODED_LEN => digit as u32,
+                _ => return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding)),
+            };
+            count += 1;
+
+            buffer = ((buffer << 5) | digit) & 0x1fff;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                value[position] = (buffer >> bits) as u8;
+                position += 1;
+            }
+        }
+
+        // The four bits left over from the last character are padding.
+        if count != ENCODED_LEN || (buffer & ((1 << bits) - 1)) != 0 {
+            return Err(UnknownCryptoError::new(ErrorKind::InvalidEncoding));
+        }
+
+        Ok(Self::from(value))
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "safe_api")]
+mod public {
+    use super::*;
+
+    // The encoded key is 0..=255, the BLAKE2b-256 digest and z-base-32 encoding were
+    // computed with Python.
+    const KEY_DIGEST: &str = "39a7eb9fedc19aabc83425c6755dd90e6f9d0c804964a1f4aaeea3b9fb599835";
+    const KEY_FINGERPRINT: &str =
+        "8gu6 z89p agpk z1bw rzd8 kzq3 b3z3 4dry jf1k d7fk 74t5 u643 uy4o";
+
+    fn key() -> [u8; 256] {
+        let mut key = [0u8; 256];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        key
+    }
+
+    #[test]
+    fn test_known_answer() {
+        let fingerprint = Fingerprint::of(&key()).unwrap();
+        assert_eq!(fingerprint.as_ref(), &hex::decode(KEY_DIGEST).unwrap()[..]);
+        assert_eq!(fingerprint.to_string(), KEY_FINGERPRINT);
+        assert_eq!(KEY_FINGERPRINT.parse::<Fingerprint>().unwrap(), fingerprint);
+    }
+
+    #[test]
+    fn test_edge_values() {
+        let zeroes = Fingerprint::from([0u8; FINGERPRINT_SIZE]);
+        assert_eq!(zeroes.encode(), [b'y'; ENCODED_LEN]);
+        let ones = Fingerprint::from([0xffu8; FINGERPRINT_SIZE]);
+        let mut expected = [b'9'; ENCODED_LEN];
+        // The final bit of the digest, followed by the padding bits.
+        expected[ENCODED_LEN - 1] = ZBASE32_ALPHABET[0x10];
+        assert_eq!(ones.encode(), expected);
+
+        for fingerprint in [zeroes, ones].iter() {
+            let encoded = fingerprint.encode();
+            let text = core::str::from_utf8(&encoded).unwrap();
+            assert_eq!(&text.parse::<Fingerprint>().unwrap(), fingerprint);
+        }
+    }
+
+    #[test]
+    fn test_parse_grouping_and_case() {
+        let fingerprint = Fingerprint::of(&key()).unwrap();
+        let ungrouped: String = KEY_FINGERPRINT.split(' ').collect();
+        assert_eq!(ungrouped.parse::<Fingerprint>().unwrap(), fingerprint);
+        let uppercase = KEY_FINGERPRINT.to_ascii_uppercase();
+        assert_eq!(uppercase.parse::<Fingerprint>().unwrap(), fingerprint);
+        let spaced = format!("\n {}\t", KEY_FINGERPRINT.replace(' ', "  "));
+        assert_eq!(spaced.parse::<Fingerprint>().unwrap(), fingerprint);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let mut last_char_padded = KEY_FINGERPRINT.to_string();
+        let last = last_char_padded.pop().unwrap();
+        let digit = ZBASE32_ALPHABET
+            .iter()
+            .position(|a| *a == last as u8)
+            .unwrap();
+        // Set a padding bit.
+        last_char_padded.push(char::from(ZBASE32_ALPHABET[digit | 1]));
+
+        let invalid = [
+            String::new(),
+            KEY_FINGERPRINT[..KEY_FINGERPRINT.len() - 1].to_string(),
+            format!("{}y", KEY_FINGERPRINT),
+            // 'l' and 'v' are not in the z-base-32 alphabet.
+            KEY_FINGERPRINT.replacen(&KEY_FINGERPRINT[..1], "l", 1),
+            KEY_FINGERPRINT.replacen(&KEY_FINGERPRINT[..1], "v", 1),
+            KEY_FINGERPRINT.replacen(' ', "-", 1),
+            last_char_padded,
+        ];
+        for text in invalid.iter() {
+            let err = text.parse::<Fingerprint>().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+        }
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_display_from_str(data: Vec<u8>) -> bool {
+        let fingerprint = Fingerprint::of(&data).unwrap();
+
+        fingerprint.to_string().parse::<Fingerprint>().unwrap() == fingerprint
+    }
+}
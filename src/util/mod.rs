@@ -21,7 +21,20 @@
 // SOFTWARE.
 
 use crate::errors;
-use subtle::ConstantTimeEq;
+use core::cmp::Ordering;
+use subtle::{ConditionallySelectable, ConstantTimeGreater};
+
+/// Constant-time equality, implemented by all of orion's key, tag, digest and
+/// other newtypes. Re-exported from the [`subtle`](https://crates.io/crates/subtle) crate.
+pub use subtle::{Choice, ConstantTimeEq};
+
+pub use fingerprint::{Fingerprint, FINGERPRINT_SIZE};
+
+#[cfg(feature = "safe_api")]
+pub use cpu::force_portable;
+
+#[cfg(all(feature = "alloc", feature = "getrandom"))]
+pub use crate::high_level::hltypes::SecretBytes;
 
 /// xor_slices!(src, destination): XOR $src into $destination slice.
 /// Uses iter() and .zip(), so it short-circuits on the slice that has
@@ -34,13 +47,16 @@ macro_rules! xor_slices {
     };
 }
 
+pub(crate) mod aes;
+pub(crate) mod cpu;
 pub(crate) mod endianness;
+mod fingerprint;
 pub(crate) mod u32x4;
 pub(crate) mod u64x4;
 
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
-#[cfg(feature = "safe_api")]
-/// Generate random bytes using a CSPRNG. Not available in `no_std` context.
+#[cfg(feature = "getrandom")]
+/// Generate random bytes using a CSPRNG. Requires the `getrandom` feature in `no_std` context.
 ///
 /// # About:
 /// This function can be used to generate cryptographic keys, salts or other
@@ -58,9 +74,6 @@ pub(crate) mod u64x4;
 /// # Errors:
 /// An error will be returned if:
 /// - `dst` is empty.
-///
-/// # Panics:
-/// A panic will occur if:
 /// - Failure to generate random bytes securely.
 /// - The platform is not supported by [`getrandom`].
 ///
@@ -75,14 +88,298 @@ pub(crate) mod u64x4;
 /// [`getrandom`]: https://github.com/rust-random/getrandom
 pub fn secure_rand_bytes(dst: &mut [u8]) -> Result<(), errors::UnknownCryptoError> {
     if dst.is_empty() {
-        return Err(errors::UnknownCryptoError);
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidLength,
+        ));
+    }
+
+    getrandom::getrandom(dst)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "getrandom")]
+#[allow(clippy::panic)]
+/// Same as [`secure_rand_bytes()`], but panics instead of returning an error. This is
+/// only used by the `generate()` functions and `Default` implementations that cannot
+/// return a `Result`, all of which document this panic.
+pub(crate) fn secure_rand_bytes_or_panic(dst: &mut [u8]) {
+    if secure_rand_bytes(dst).is_err() {
+        panic!("orion: failed to generate random bytes securely");
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Generate a random `u64` using a CSPRNG. Not available in `no_std` context.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let id = util::secure_rand_u64()?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn secure_rand_u64() -> Result<u64, errors::UnknownCryptoError> {
+    let mut bytes = [0u8; 8];
+    secure_rand_bytes(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Generate a random `u32` using a CSPRNG. Not available in `no_std` context.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let id = util::secure_rand_u32()?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn secure_rand_u32() -> Result<u32, errors::UnknownCryptoError> {
+    let mut bytes = [0u8; 4];
+    secure_rand_bytes(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(feature = "safe_api")]
+/// Reduce values from `next` to the range `[0, upper_bound)` without bias,
+/// by rejecting the values that would otherwise make some results more likely.
+fn rand_range_with<F>(upper_bound: u64, mut next: F) -> Result<u64, errors::UnknownCryptoError>
+where
+    F: FnMut() -> Result<u64, errors::UnknownCryptoError>,
+{
+    if upper_bound == 0 {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidParameter,
+        ));
     }
 
-    getrandom::getrandom(dst).unwrap();
+    // 2^64 mod upper_bound. Values below this are rejected, so that the amount of
+    // accepted values is a multiple of upper_bound.
+    let threshold = upper_bound.wrapping_neg() % upper_bound;
+    loop {
+        let value = next()?;
+        if value >= threshold {
+            return Ok(value % upper_bound);
+        }
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Generate a uniformly random integer in the range `[0, upper_bound)` using a CSPRNG.
+/// Not available in `no_std` context.
+///
+/// # About:
+/// Reducing a random integer with `%` makes some results more likely than others,
+/// unless `upper_bound` is a power of two. This function uses rejection sampling,
+/// so every result is equally likely.
+///
+/// # Parameters:
+/// - `upper_bound`: The exclusive upper bound of the range.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `upper_bound` is `0`.
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let dice_roll = util::secure_rand_range(6)? + 1;
+/// assert!((1..=6).contains(&dice_roll));
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn secure_rand_range(upper_bound: u64) -> Result<u64, errors::UnknownCryptoError> {
+    rand_range_with(upper_bound, secure_rand_u64)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Shuffle `slice` uniformly at random using a CSPRNG. Not available in `no_std` context.
+///
+/// # About:
+/// This uses the Fisher-Yates shuffle with [`secure_rand_range()`], so every
+/// permutation is equally likely.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let mut deck: Vec<u8> = (0..52).collect();
+/// util::secure_shuffle(&mut deck)?;
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn secure_shuffle<T>(slice: &mut [T]) -> Result<(), errors::UnknownCryptoError> {
+    for idx in (1..slice.len()).rev() {
+        let other = secure_rand_range(idx as u64 + 1)?;
+        slice.swap(idx, other as usize);
+    }
 
     Ok(())
 }
 
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Generate a passphrase of `wordcount` words using a CSPRNG. Not available in `no_std` context.
+///
+/// # About:
+/// The words are chosen uniformly at random from the BIP39 English wordlist of 2048 words,
+/// so every word adds 11 bits of entropy. They are separated by a single space. The words are
+/// looked up without the memory access pattern depending on which word was chosen.
+///
+/// # Parameters:
+/// - `wordcount`: The amount of words in the passphrase.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `wordcount` is `0`.
+/// - The length of the passphrase would overflow a `usize`.
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Security:
+/// - The returned passphrase is zeroized when dropped, but copies made from it are not.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// // 6 words give 66 bits of entropy.
+/// let passphrase = util::generate_passphrase(6)?;
+/// assert_eq!(passphrase.split(' ').count(), 6);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn generate_passphrase(
+    wordcount: usize,
+) -> Result<zeroize::Zeroizing<String>, errors::UnknownCryptoError> {
+    use crate::high_level::mnemonic::{word_of, MAX_WORD_LEN};
+    use zeroize::Zeroize;
+
+    if wordcount == 0 {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidParameter,
+        ));
+    }
+    // Allocate up front, so the passphrase is never copied by a reallocation.
+    let capacity = match wordcount.checked_mul(MAX_WORD_LEN + 1) {
+        Some(capacity) => capacity,
+        None => {
+            return Err(errors::UnknownCryptoError::new(
+                errors::ErrorKind::InvalidLength,
+            ))
+        }
+    };
+
+    let mut passphrase = zeroize::Zeroizing::new(String::with_capacity(capacity));
+    for idx in 0..wordcount {
+        if idx > 0 {
+            passphrase.push(' ');
+        }
+        let mut word = word_of(secure_rand_range(2048)? as u16);
+        for byte in word[..usize::from(word[MAX_WORD_LEN])].iter() {
+            passphrase.push(char::from(*byte));
+        }
+        word.zeroize();
+    }
+
+    Ok(passphrase)
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+#[cfg(feature = "safe_api")]
+/// Generate a password of `len` characters from `charset` using a CSPRNG. Not available in
+/// `no_std` context.
+///
+/// # About:
+/// Every character is chosen uniformly at random from `charset`, using
+/// [`secure_rand_range()`]. The characters are looked up without the memory access pattern
+/// depending on which character was chosen.
+///
+/// # Parameters:
+/// - `len`: The amount of characters in the password.
+/// - `charset`: The characters to choose from.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `len` is `0`.
+/// - `charset` is empty, contains non-ASCII characters or contains a character more than once.
+/// - Failure to generate random bytes securely.
+/// - The platform is not supported by [`getrandom`].
+///
+/// # Security:
+/// - The returned password is zeroized when dropped, but copies made from it are not.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let charset = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+/// let password = util::generate_password(20, charset)?;
+/// assert_eq!(password.len(), 20);
+/// assert!(password.chars().all(|c| charset.contains(c)));
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+/// [`getrandom`]: https://github.com/rust-random/getrandom
+pub fn generate_password(
+    len: usize,
+    charset: &str,
+) -> Result<zeroize::Zeroizing<String>, errors::UnknownCryptoError> {
+    let charset = charset.as_bytes();
+    let mut seen = [false; 128];
+    for byte in charset.iter() {
+        if !byte.is_ascii() || seen[usize::from(*byte)] {
+            return Err(errors::UnknownCryptoError::new(
+                errors::ErrorKind::InvalidParameter,
+            ));
+        }
+        seen[usize::from(*byte)] = true;
+    }
+    if len == 0 || charset.is_empty() {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidParameter,
+        ));
+    }
+
+    let mut password = zeroize::Zeroizing::new(String::with_capacity(len));
+    for _ in 0..len {
+        let index = secure_rand_range(charset.len() as u64)?;
+        let mut selected = 0u8;
+        for (i, byte) in charset.iter().enumerate() {
+            selected.conditional_assign(byte, (i as u64).ct_eq(&index));
+        }
+        password.push(char::from(selected));
+    }
+
+    Ok(password)
+}
+
 #[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
 /// Compare two equal length slices in constant time.
 ///
@@ -111,11 +408,424 @@ pub fn secure_rand_bytes(dst: &mut [u8]) -> Result<(), errors::UnknownCryptoErro
 /// # Ok::<(), orion::errors::UnknownCryptoError>(())
 /// ```
 pub fn secure_cmp(a: &[u8], b: &[u8]) -> Result<(), errors::UnknownCryptoError> {
+    if a.len() != b.len() {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidLength,
+        ));
+    }
+
     if a.ct_eq(b).into() {
         Ok(())
     } else {
-        Err(errors::UnknownCryptoError)
+        Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::MacMismatch,
+        ))
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Compare two equal length slices lexicographically in constant time.
+///
+/// # About:
+/// The ordering is the same as the one given by `Ord` on byte slices, but the
+/// position of the first differing byte is not revealed. All bytes of both slices
+/// are always processed.
+///
+/// # Parameters:
+/// - `a`: The first slice used in the comparison.
+/// - `b`: The second slice used in the comparison.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `a` and `b` do not have the same length.
+///
+/// # Example:
+/// ```rust
+/// use core::cmp::Ordering;
+/// use orion::util;
+///
+/// assert_eq!(util::secure_cmp_ord(&[1, 2, 3], &[1, 2, 3])?, Ordering::Equal);
+/// assert_eq!(util::secure_cmp_ord(&[1, 2, 3], &[1, 3, 0])?, Ordering::Less);
+/// assert_eq!(util::secure_cmp_ord(&[2, 0, 0], &[1, 9, 9])?, Ordering::Greater);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn secure_cmp_ord(a: &[u8], b: &[u8]) -> Result<Ordering, errors::UnknownCryptoError> {
+    if a.len() != b.len() {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidLength,
+        ));
+    }
+
+    let mut greater = Choice::from(0u8);
+    let mut less = Choice::from(0u8);
+    for (x, y) in a.iter().zip(b.iter()) {
+        // Only the first differing byte may decide the ordering.
+        let undecided = !(greater | less);
+        greater |= undecided & x.ct_gt(y);
+        less |= undecided & y.ct_gt(x);
+    }
+
+    if greater.into() {
+        Ok(Ordering::Greater)
+    } else if less.into() {
+        Ok(Ordering::Less)
+    } else {
+        Ok(Ordering::Equal)
+    }
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Select between two equal length slices in constant time.
+///
+/// # About:
+/// Copies `b` into `dst` if `choose_b` is `true` and `a` otherwise, without
+/// branching on `choose_b`.
+///
+/// # Parameters:
+/// - `a`: The slice copied into `dst` when `choose_b` is `false`.
+/// - `b`: The slice copied into `dst` when `choose_b` is `true`.
+/// - `choose_b`: Which of the two slices to select.
+/// - `dst`: Destination buffer for the selected slice.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `a`, `b` and `dst` do not all have the same length.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let mut dst = [0u8; 3];
+/// util::secure_select(&[1, 1, 1], &[2, 2, 2], true, &mut dst)?;
+/// assert_eq!(dst, [2, 2, 2]);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn secure_select(
+    a: &[u8],
+    b: &[u8],
+    choose_b: bool,
+    dst: &mut [u8],
+) -> Result<(), errors::UnknownCryptoError> {
+    if a.len() != b.len() || a.len() != dst.len() {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidLength,
+        ));
+    }
+
+    let choice = Choice::from(choose_b as u8);
+    for ((out, x), y) in dst.iter_mut().zip(a.iter()).zip(b.iter()) {
+        *out = u8::conditional_select(x, y, choice);
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Swap the contents of two equal length slices in constant time.
+///
+/// # About:
+/// Swaps `a` and `b` if `swap` is `true` and leaves them unchanged otherwise,
+/// without branching on `swap`.
+///
+/// # Parameters:
+/// - `a`: The first slice.
+/// - `b`: The second slice.
+/// - `swap`: Whether to swap the contents of `a` and `b`.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - `a` and `b` do not have the same length.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let mut a = [1u8; 3];
+/// let mut b = [2u8; 3];
+/// util::secure_swap(&mut a, &mut b, true)?;
+/// assert_eq!((a, b), ([2u8; 3], [1u8; 3]));
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn secure_swap(
+    a: &mut [u8],
+    b: &mut [u8],
+    swap: bool,
+) -> Result<(), errors::UnknownCryptoError> {
+    if a.len() != b.len() {
+        return Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidLength,
+        ));
+    }
+
+    let choice = Choice::from(swap as u8);
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        u8::conditional_swap(x, y, choice);
+    }
+
+    Ok(())
+}
+
+#[inline]
+/// Encode the lower four bits of `nibble` as a lowercase hexadecimal character,
+/// without branching or table lookups on the value.
+fn hex_encode_nibble(nibble: u8) -> u8 {
+    let n = i16::from(nibble & 0x0f);
+    // For n < 10, (n - 10) >> 8 is all ones and the offset becomes b'0'.
+    // For n >= 10, it is zero and the offset becomes b'a' - 10.
+    (n + 87 + (((n - 10) >> 8) & -39)) as u8
+}
+
+#[inline]
+/// Decode a single hexadecimal character (either case), without branching or table
+/// lookups on the value. Returns the decoded nibble and a mask that is `0xff`
+/// if `c` was a valid hexadecimal character and `0x00` otherwise.
+fn hex_decode_nibble(c: u8) -> (u8, u8) {
+    let digit = i16::from(c ^ 0x30);
+    let digit_mask = (digit - 10) >> 8;
+    // Clearing bit 5 maps lowercase letters to uppercase.
+    let alpha = i16::from(c & !0x20) - 55;
+    let alpha_mask = !((alpha - 10) >> 8) & ((alpha - 16) >> 8);
+
+    (
+        ((digit & digit_mask) | (alpha & alpha_mask)) as u8,
+        (digit_mask | alpha_mask) as u8,
+    )
+}
+
+#[cfg(feature = "safe_api")]
+/// Append `src` to `dst`. If `dst` must grow, its contents are copied to a new allocation
+/// and the old one is zeroed, instead of letting the `Vec` reallocate and leave a copy of
+/// the contents behind in freed memory.
+pub(crate) fn extend_zeroizing(dst: &mut Vec<u8>, src: &[u8]) {
+    use zeroize::Zeroize;
+
+    if dst.capacity() - dst.len() < src.len() {
+        let capacity = core::cmp::max(dst.len() + src.len(), dst.capacity() * 2);
+        let mut grown = Vec::with_capacity(capacity);
+        grown.extend_from_slice(dst);
+        dst.zeroize();
+        *dst = grown;
+    }
+    // Does not reallocate, as the capacity is sufficient.
+    dst.extend_from_slice(src);
+}
+
+#[cfg(feature = "safe_api")]
+/// Encode bytes as a lowercase hexadecimal `String` in constant time. Unlike
+/// [`hex_encode()`], this cannot fail.
+pub(crate) fn hex_encode_to_string(src: &[u8]) -> String {
+    let mut encoded = String::with_capacity(src.len() * 2);
+    for byte in src.iter() {
+        encoded.push(char::from(hex_encode_nibble(byte >> 4)));
+        encoded.push(char::from(hex_encode_nibble(*byte)));
+    }
+
+    encoded
+}
+
+/// Write bytes to `f` as lowercase hexadecimal in constant time.
+pub(crate) fn hex_fmt(src: &[u8], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    use core::fmt::Write;
+
+    for byte in src.iter() {
+        f.write_char(char::from(hex_encode_nibble(byte >> 4)))?;
+        f.write_char(char::from(hex_encode_nibble(*byte)))?;
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Encode bytes as lowercase hexadecimal in constant time.
+///
+/// # About:
+/// The encoding does not use any table lookups or branches that depend on the
+/// values of `src`, which makes it suitable for encoding secret data such as
+/// keys and tags.
+///
+/// # Parameters:
+/// - `src`: The bytes to encode.
+/// - `dst`: Destination buffer for the hexadecimal characters.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - The length of `dst` is not twice the length of `src`.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let mut encoded = [0u8; 8];
+/// util::hex_encode(&[0xde, 0xad, 0xbe, 0xef], &mut encoded)?;
+/// assert_eq!(&encoded, b"deadbeef");
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn hex_encode(src: &[u8], dst: &mut [u8]) -> Result<(), errors::UnknownCryptoError> {
+    match src.len().checked_mul(2) {
+        Some(encoded_len) if encoded_len == dst.len() => (),
+        _ => {
+            return Err(errors::UnknownCryptoError::new(
+                errors::ErrorKind::InvalidLength,
+            ))
+        }
+    }
+
+    for (byte, chars) in src.iter().zip(dst.chunks_exact_mut(2)) {
+        chars[0] = hex_encode_nibble(byte >> 4);
+        chars[1] = hex_encode_nibble(*byte);
+    }
+
+    Ok(())
+}
+
+#[must_use = "SECURITY WARNING: Ignoring a Result can have real security implications."]
+/// Decode hexadecimal characters to bytes in constant time.
+///
+/// # About:
+/// Both lowercase and uppercase characters are accepted. The decoding does not use
+/// any table lookups or branches that depend on the values of `src`. Only whether
+/// `src` as a whole is valid hexadecimal is revealed.
+///
+/// # Parameters:
+/// - `src`: The hexadecimal characters to decode.
+/// - `dst`: Destination buffer for the decoded bytes.
+///
+/// # Errors:
+/// An error will be returned if:
+/// - The length of `src` is not twice the length of `dst`.
+/// - `src` contains characters that are not hexadecimal.
+///
+/// # Example:
+/// ```rust
+/// use orion::util;
+///
+/// let mut decoded = [0u8; 4];
+/// util::hex_decode(b"DEADbeef", &mut decoded)?;
+/// assert_eq!(decoded, [0xde, 0xad, 0xbe, 0xef]);
+///
+/// assert!(util::hex_decode(b"deadbeeg", &mut decoded).is_err());
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+pub fn hex_decode(src: &[u8], dst: &mut [u8]) -> Result<(), errors::UnknownCryptoError> {
+    match dst.len().checked_mul(2) {
+        Some(encoded_len) if encoded_len == src.len() => (),
+        _ => {
+            return Err(errors::UnknownCryptoError::new(
+                errors::ErrorKind::InvalidLength,
+            ))
+        }
+    }
+
+    let mut valid = 0xffu8;
+    for (chars, byte) in src.chunks_exact(2).zip(dst.iter_mut()) {
+        let (high, high_valid) = hex_decode_nibble(chars[0]);
+        let (low, low_valid) = hex_decode_nibble(chars[1]);
+        *byte = (high << 4) | low;
+        valid &= high_valid & low_valid;
+    }
+
+    if valid.ct_eq(&0xffu8).into() {
+        Ok(())
+    } else {
+        dst.iter_mut().for_each(|byte| *byte = 0);
+        Err(errors::UnknownCryptoError::new(
+            errors::ErrorKind::InvalidEncoding,
+        ))
+    }
+}
+
+#[doc(hidden)]
+/// Decode hexadecimal characters (either case) to a byte array in a `const` context.
+/// This is used by [`secretkey!`](crate::secretkey) and is not part of the public API.
+///
+/// `panic!()` cannot be used in a `const fn` on the MSRV, so invalid input instead
+/// fails by indexing out of bounds. In a `const` item this is a compile-time error.
+/// Unlike [`hex_decode()`], this is __not__ constant time and must not be used on
+/// secret data at runtime.
+pub const fn hex_decode_const<const N: usize>(src: &str) -> [u8; N] {
+    const fn nibble(c: u8) -> u8 {
+        const INVALID_HEX_CHARACTER: [u8; 0] = [];
+        match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => INVALID_HEX_CHARACTER[c as usize],
+        }
+    }
+
+    let src = src.as_bytes();
+    let mut decoded = [0u8; N];
+    let mut idx = 0;
+    while idx < N {
+        decoded[idx] = (nibble(src[2 * idx]) << 4) | nibble(src[2 * idx + 1]);
+        idx += 1;
     }
+
+    // Only index zero is valid, which rejects input that is longer than `2 * N`.
+    // Shorter input has already failed above.
+    let invalid_hex_length = [decoded];
+    invalid_hex_length[src.len() - 2 * N]
+}
+
+#[macro_export]
+/// Construct a fixed-size key, nonce or other value from a hexadecimal string
+/// literal, which is decoded at compile time.
+///
+/// # About:
+/// The macro takes the type to construct and a hex string literal (either case). The
+/// string is decoded in a `const` item, so the result can also be used to define
+/// constants. Any type with a `from_array()` function can be used, which are the
+/// fixed-size types that implement `From<[u8; N]>`.
+///
+/// With only a hex string literal, the decoded `[u8; N]` is returned instead.
+///
+/// # Errors:
+/// Compilation fails if:
+/// - The string contains characters that are not hexadecimal.
+/// - The string has an odd number of characters.
+/// - The decoded length is not the length of the type.
+///
+/// # Security:
+/// - Secret keys defined with this macro are embedded in the compiled binary. This
+///   should only be used for test keys and protocol constants, never for production secrets.
+///
+/// # Example:
+/// ```rust
+/// use orion::hazardous::stream::chacha20::{Nonce, SecretKey};
+///
+/// const NONCE: Nonce = orion::secretkey!(Nonce, "000000000000004a00000000");
+///
+/// let secret_key = orion::secretkey!(
+///     SecretKey,
+///     "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"
+/// );
+/// assert_eq!(secret_key, SecretKey::from_slice(&orion::secretkey!(
+///     "000102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F"
+/// ))?);
+/// assert_eq!(NONCE.as_ref()[7], 0x4a);
+/// # Ok::<(), orion::errors::UnknownCryptoError>(())
+/// ```
+///
+/// Invalid input is rejected at compile time:
+/// ```rust,compile_fail
+/// use orion::hazardous::stream::chacha20::Nonce;
+///
+/// let nonce = orion::secretkey!(Nonce, "000000000000004a0000000g");
+/// ```
+/// ```rust,compile_fail
+/// use orion::hazardous::stream::chacha20::Nonce;
+///
+/// let nonce = orion::secretkey!(Nonce, "000000000000004a");
+/// ```
+macro_rules! secretkey {
+    ($hex:literal) => {{
+        const DECODED: [u8; $hex.len() / 2] = $crate::util::hex_decode_const($hex);
+        DECODED
+    }};
+    ($type:ty, $hex:literal) => {{
+        const DECODED: $type = <$type>::from_array($crate::util::hex_decode_const($hex));
+        DECODED
+    }};
 }
 
 #[cfg(test)]
@@ -136,7 +846,133 @@ mod tests {
         assert!(secure_rand_bytes(&mut dst).is_err());
 
         let err = secure_rand_bytes(&mut dst).unwrap_err();
-        assert_eq!(err, errors::UnknownCryptoError);
+        assert_eq!(err.kind(), errors::ErrorKind::InvalidLength);
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_rand_ints() {
+        let values: Vec<u64> = (0..8).map(|_| secure_rand_u64().unwrap()).collect();
+        assert!(values.iter().any(|v| *v != values[0]));
+        let values: Vec<u32> = (0..8).map(|_| secure_rand_u32().unwrap()).collect();
+        assert!(values.iter().any(|v| *v != values[0]));
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_rand_range() {
+        assert_eq!(
+            secure_rand_range(0).unwrap_err().kind(),
+            errors::ErrorKind::InvalidParameter
+        );
+        assert_eq!(secure_rand_range(1).unwrap(), 0);
+
+        let mut seen = [false; 6];
+        for _ in 0..1000 {
+            let value = secure_rand_range(6).unwrap();
+            assert!(value < 6);
+            seen[value as usize] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+
+        assert!(secure_rand_range(u64::MAX).unwrap() < u64::MAX);
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_rand_range_rejects_biased_values() {
+        // 2^64 mod 3 = 1, so only 0 must be rejected.
+        let mut values = [0u64, 1, 5].iter();
+        let next = || Ok(*values.next().unwrap());
+        assert_eq!(rand_range_with(3, next).unwrap(), 1);
+
+        // 2^64 mod 10 = 6, so 0..6 must be rejected.
+        let mut values = [0u64, 5, 6].iter();
+        let next = || Ok(*values.next().unwrap());
+        assert_eq!(rand_range_with(10, next).unwrap(), 6);
+
+        // Powers of two never reject.
+        let mut values = [0u64].iter();
+        let next = || Ok(*values.next().unwrap());
+        assert_eq!(rand_range_with(16, next).unwrap(), 0);
+
+        let mut values = [u64::MAX - 1, u64::MAX].iter();
+        let next = || Ok(*values.next().unwrap());
+        assert_eq!(rand_range_with(u64::MAX, next).unwrap(), u64::MAX - 1);
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_shuffle() {
+        let mut empty: [u8; 0] = [];
+        secure_shuffle(&mut empty).unwrap();
+        let mut single = [1u8];
+        secure_shuffle(&mut single).unwrap();
+        assert_eq!(single, [1u8]);
+
+        let original: Vec<u16> = (0..256).collect();
+        let mut shuffled = original.clone();
+        secure_shuffle(&mut shuffled).unwrap();
+        assert_ne!(shuffled, original);
+        shuffled.sort_unstable();
+        assert_eq!(shuffled, original);
+
+        // Every position is reachable in a small slice.
+        let mut seen_first = [false; 3];
+        for _ in 0..200 {
+            let mut slice = [0usize, 1, 2];
+            secure_shuffle(&mut slice).unwrap();
+            seen_first[slice[0]] = true;
+        }
+        assert!(seen_first.iter().all(|s| *s));
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_generate_passphrase() {
+        use crate::high_level::mnemonic::MAX_WORD_LEN;
+
+        assert_eq!(
+            generate_passphrase(0).unwrap_err().kind(),
+            errors::ErrorKind::InvalidParameter
+        );
+        assert_eq!(
+            generate_passphrase(usize::MAX).unwrap_err().kind(),
+            errors::ErrorKind::InvalidLength
+        );
+
+        let passphrase = generate_passphrase(1).unwrap();
+        assert!(!passphrase.contains(' '));
+
+        let passphrase = generate_passphrase(24).unwrap();
+        let words: Vec<&str> = passphrase.split(' ').collect();
+        assert_eq!(words.len(), 24);
+        assert!(words
+            .iter()
+            .all(|w| (3..=MAX_WORD_LEN).contains(&w.len())
+                && w.bytes().all(|b| b.is_ascii_lowercase())));
+        assert!(passphrase.capacity() <= 24 * (MAX_WORD_LEN + 1));
+        assert_ne!(*passphrase, *generate_passphrase(24).unwrap());
+    }
+
+    #[cfg(feature = "safe_api")]
+    #[test]
+    fn test_generate_password() {
+        for (len, charset) in [(0, "ab"), (1, ""), (1, "aba"), (1, "ab\u{e9}")].iter() {
+            assert_eq!(
+                generate_password(*len, charset).unwrap_err().kind(),
+                errors::ErrorKind::InvalidParameter
+            );
+        }
+
+        assert_eq!(*generate_password(5, "x").unwrap(), "xxxxx");
+        let password = generate_password(64, "0123456789").unwrap();
+        assert_eq!(password.len(), 64);
+        assert!(password.bytes().all(|b| b.is_ascii_digit()));
+
+        // Every character of the charset is reachable.
+        let password = generate_password(200, "abc").unwrap();
+        assert!("abc".chars().all(|c| password.contains(c)));
     }
 
     #[test]
@@ -155,6 +991,10 @@ mod tests {
 
         assert!(secure_cmp(&buf_1, &buf_2).is_err());
         assert!(secure_cmp(&buf_2, &buf_1).is_err());
+        assert_eq!(
+            secure_cmp(&buf_1, &buf_2).unwrap_err().kind(),
+            errors::ErrorKind::InvalidLength
+        );
     }
 
     #[test]
@@ -164,6 +1004,10 @@ mod tests {
 
         assert!(secure_cmp(&buf_1, &buf_2).is_err());
         assert!(secure_cmp(&buf_2, &buf_1).is_err());
+        assert_eq!(
+            secure_cmp(&buf_1, &buf_2).unwrap_err().kind(),
+            errors::ErrorKind::MacMismatch
+        );
     }
 
     #[test]
@@ -181,4 +1025,176 @@ mod tests {
             secure_cmp(&a, &b).is_err()
         }
     }
+
+    #[test]
+    fn test_hex_encode_nibble() {
+        let expected = b"0123456789abcdef";
+        for n in 0..16u8 {
+            assert_eq!(hex_encode_nibble(n), expected[n as usize]);
+            // Only the lower four bits are encoded.
+            assert_eq!(hex_encode_nibble(n | 0xf0), expected[n as usize]);
+        }
+    }
+
+    #[test]
+    fn test_hex_decode_nibble_all_bytes() {
+        for c in 0..=255u8 {
+            let (value, mask) = hex_decode_nibble(c);
+            match c {
+                b'0'..=b'9' => assert_eq!((value, mask), (c - b'0', 0xff)),
+                b'a'..=b'f' => assert_eq!((value, mask), (c - b'a' + 10, 0xff)),
+                b'A'..=b'F' => assert_eq!((value, mask), (c - b'A' + 10, 0xff)),
+                _ => assert_eq!(mask, 0x00),
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_encode_decode() {
+        let mut encoded = [0u8; 16];
+        hex_encode(
+            &[0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff],
+            &mut encoded,
+        )
+        .unwrap();
+        assert_eq!(&encoded, b"00017f80abcdefff");
+
+        let mut decoded = [0u8; 8];
+        hex_decode(&encoded, &mut decoded).unwrap();
+        assert_eq!(decoded, [0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff]);
+
+        hex_decode(b"00017F80ABCDEFFF", &mut decoded).unwrap();
+        assert_eq!(decoded, [0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff]);
+
+        assert!(hex_encode(&[], &mut []).is_ok());
+        assert!(hex_decode(&[], &mut []).is_ok());
+    }
+
+    #[test]
+    fn test_hex_bad_length_err() {
+        assert!(hex_encode(&[0u8; 4], &mut [0u8; 7]).is_err());
+        assert!(hex_encode(&[0u8; 4], &mut [0u8; 9]).is_err());
+        assert!(hex_encode(&[0u8; 4], &mut [0u8; 4]).is_err());
+
+        assert!(hex_decode(b"0000000", &mut [0u8; 4]).is_err());
+        assert!(hex_decode(b"000000000", &mut [0u8; 4]).is_err());
+        assert!(hex_decode(b"0000", &mut [0u8; 4]).is_err());
+    }
+
+    #[test]
+    fn test_hex_decode_invalid_char_err() {
+        let mut decoded = [0u8; 2];
+        assert!(hex_decode(b"0g00", &mut decoded).is_err());
+        assert!(hex_decode(b"000 ", &mut decoded).is_err());
+        assert!(hex_decode(b"-100", &mut decoded).is_err());
+        assert!(hex_decode(&[b'0', b'0', b'0', 0xc1], &mut decoded).is_err());
+        // The output is not left partially decoded on error.
+        assert_eq!(decoded, [0u8; 2]);
+    }
+
+    #[test]
+    fn test_hex_decode_const() {
+        const DECODED: [u8; 8] = hex_decode_const("00017f80ABCDEFff");
+        assert_eq!(DECODED, [0x00, 0x01, 0x7f, 0x80, 0xab, 0xcd, 0xef, 0xff]);
+        assert_eq!(hex_decode_const::<0>(""), [0u8; 0]);
+
+        const NONCE: crate::hazardous::stream::chacha20::Nonce = crate::secretkey!(
+            crate::hazardous::stream::chacha20::Nonce,
+            "000000090000004a00000000"
+        );
+        assert_eq!(
+            NONCE.as_ref(),
+            &crate::secretkey!("000000090000004a00000000")
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hex_decode_const_invalid_char_panics() {
+        let _ = hex_decode_const::<2>("0g00");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hex_decode_const_too_long_panics() {
+        let _ = hex_decode_const::<2>("00000");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hex_decode_const_too_short_panics() {
+        let _ = hex_decode_const::<2>("000");
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_hex_encode_decode(input: Vec<u8>) -> bool {
+        let mut encoded = vec![0u8; input.len() * 2];
+        hex_encode(&input, &mut encoded).unwrap();
+
+        let mut decoded = vec![0u8; input.len()];
+        hex_decode(&encoded, &mut decoded).unwrap();
+
+        decoded == input && encoded == hex::encode(&input).into_bytes()
+    }
+
+    #[test]
+    fn test_secure_cmp_ord() {
+        assert_eq!(secure_cmp_ord(&[], &[]).unwrap(), Ordering::Equal);
+        assert_eq!(secure_cmp_ord(&[5; 10], &[5; 10]).unwrap(), Ordering::Equal);
+        assert_eq!(secure_cmp_ord(&[0, 255], &[1, 0]).unwrap(), Ordering::Less);
+        assert_eq!(
+            secure_cmp_ord(&[1, 0], &[0, 255]).unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(
+            secure_cmp_ord(&[1, 1, 2], &[1, 1, 3]).unwrap(),
+            Ordering::Less
+        );
+        assert_eq!(
+            secure_cmp_ord(&[1, 1, 3], &[1, 1, 2]).unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(secure_cmp_ord(&[128], &[127]).unwrap(), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_secure_cmp_ord_diff_len() {
+        assert!(secure_cmp_ord(&[0], &[0, 1]).is_err());
+        assert!(secure_cmp_ord(&[0, 1], &[0]).is_err());
+        assert!(secure_cmp_ord(&[], &[0]).is_err());
+    }
+
+    #[test]
+    fn test_secure_select() {
+        let mut dst = [0u8; 4];
+        secure_select(&[1, 2, 3, 4], &[5, 6, 7, 8], false, &mut dst).unwrap();
+        assert_eq!(dst, [1, 2, 3, 4]);
+        secure_select(&[1, 2, 3, 4], &[5, 6, 7, 8], true, &mut dst).unwrap();
+        assert_eq!(dst, [5, 6, 7, 8]);
+
+        assert!(secure_select(&[0; 4], &[0; 3], true, &mut dst).is_err());
+        assert!(secure_select(&[0; 3], &[0; 4], true, &mut dst).is_err());
+        assert!(secure_select(&[0; 3], &[0; 3], true, &mut dst).is_err());
+    }
+
+    #[test]
+    fn test_secure_swap() {
+        let mut a = [1u8, 2, 3];
+        let mut b = [4u8, 5, 6];
+        secure_swap(&mut a, &mut b, false).unwrap();
+        assert_eq!((a, b), ([1, 2, 3], [4, 5, 6]));
+        secure_swap(&mut a, &mut b, true).unwrap();
+        assert_eq!((a, b), ([4, 5, 6], [1, 2, 3]));
+
+        assert!(secure_swap(&mut a, &mut [0u8; 2], true).is_err());
+        assert!(secure_swap(&mut [0u8; 2], &mut b, true).is_err());
+    }
+
+    #[quickcheck]
+    #[cfg(feature = "safe_api")]
+    fn prop_secure_cmp_ord(a: Vec<u8>, b: Vec<u8>) -> bool {
+        let len = core::cmp::min(a.len(), b.len());
+        secure_cmp_ord(&a[..len], &b[..len]).unwrap() == a[..len].cmp(&b[..len])
+    }
 }
@@ -28,6 +28,7 @@ pub(crate) struct U32x4(
     pub(crate) u32,
 );
 
+#[cfg(not(feature = "portable_simd"))]
 impl core::ops::BitXor for U32x4 {
     type Output = Self;
 
@@ -42,6 +43,63 @@ impl core::ops::BitXor for U32x4 {
     }
 }
 
+#[cfg(feature = "portable_simd")]
+impl core::ops::BitXor for U32x4 {
+    type Output = Self;
+
+    #[must_use]
+    fn bitxor(self, _rhs: Self) -> Self::Output {
+        Self::from_simd(self.to_simd() ^ _rhs.to_simd())
+    }
+}
+
+#[cfg(feature = "portable_simd")]
+impl U32x4 {
+    #[inline(always)]
+    fn to_simd(self) -> core::simd::Simd<u32, 4> {
+        core::simd::Simd::from_array([self.0, self.1, self.2, self.3])
+    }
+
+    #[inline(always)]
+    fn from_simd(simd: core::simd::Simd<u32, 4>) -> Self {
+        let [r0, r1, r2, r3] = simd.to_array();
+        Self(r0, r1, r2, r3)
+    }
+
+    #[must_use]
+    pub(crate) fn wrapping_add(self, _rhs: Self) -> Self {
+        // Addition of `Simd` vectors wraps on overflow.
+        Self::from_simd(self.to_simd() + _rhs.to_simd())
+    }
+
+    #[must_use]
+    pub(crate) fn shl_1(self) -> Self {
+        Self::from_simd(self.to_simd().rotate_elements_left::<1>())
+    }
+
+    #[must_use]
+    pub(crate) fn shl_2(self) -> Self {
+        Self::from_simd(self.to_simd().rotate_elements_left::<2>())
+    }
+
+    #[must_use]
+    pub(crate) fn shl_3(self) -> Self {
+        Self::from_simd(self.to_simd().rotate_elements_left::<3>())
+    }
+
+    #[must_use]
+    pub(crate) fn rotate_left(self, n: u32) -> Self {
+        debug_assert!(n > 0 && n < u32::BITS);
+        let simd = self.to_simd();
+        let n = n as u32;
+        let inv = u32::BITS as u32 - n;
+        let n = core::simd::Simd::splat(n);
+        let inv = core::simd::Simd::splat(inv);
+
+        Self::from_simd((simd << n) | (simd >> inv))
+    }
+}
+
 impl zeroize::Zeroize for U32x4 {
     fn zeroize(&mut self) {
         self.0.zeroize();
@@ -52,6 +110,7 @@ impl zeroize::Zeroize for U32x4 {
 }
 
 impl U32x4 {
+    #[cfg(not(feature = "portable_simd"))]
     #[must_use]
     pub(crate) const fn wrapping_add(self, _rhs: Self) -> Self {
         Self(
@@ -62,21 +121,25 @@ impl U32x4 {
         )
     }
 
+    #[cfg(not(feature = "portable_simd"))]
     #[must_use]
     pub(crate) const fn shl_1(self) -> Self {
         Self(self.1, self.2, self.3, self.0)
     }
 
+    #[cfg(not(feature = "portable_simd"))]
     #[must_use]
     pub(crate) const fn shl_2(self) -> Self {
         Self(self.2, self.3, self.0, self.1)
     }
 
+    #[cfg(not(feature = "portable_simd"))]
     #[must_use]
     pub(crate) const fn shl_3(self) -> Self {
         Self(self.3, self.0, self.1, self.2)
     }
 
+    #[cfg(not(feature = "portable_simd"))]
     #[must_use]
     pub(crate) const fn rotate_left(self, n: u32) -> Self {
         Self(
@@ -89,10 +152,12 @@ impl U32x4 {
 
     pub(crate) fn store_into_le(&self, slice_in: &mut [u8]) {
         debug_assert!(slice_in.len() == core::mem::size_of::<u32>() * 4);
-        let mut iter = slice_in.chunks_exact_mut(core::mem::size_of::<u32>());
-        iter.next().unwrap().copy_from_slice(&self.0.to_le_bytes());
-        iter.next().unwrap().copy_from_slice(&self.1.to_le_bytes());
-        iter.next().unwrap().copy_from_slice(&self.2.to_le_bytes());
-        iter.next().unwrap().copy_from_slice(&self.3.to_le_bytes());
+        let words = [self.0, self.1, self.2, self.3];
+        for (word, dst) in words
+            .iter()
+            .zip(slice_in.chunks_exact_mut(core::mem::size_of::<u32>()))
+        {
+            dst.copy_from_slice(&word.to_le_bytes());
+        }
     }
 }
@@ -44,7 +44,7 @@ fn wycheproof_test_runner(
         // Tests that run here have a "invalid" flag set
         let key = match SecretKey::from_slice(&key) {
             Ok(k) => k,
-            Err(UnknownCryptoError) => return Ok(()), // Invalid key size test
+            Err(_) => return Ok(()), // Invalid key size test
         };
 
         // Save the return values from sealing/opening operations
@@ -55,7 +55,7 @@ fn wycheproof_test_runner(
         if is_ietf {
             let nonce = match chacha20poly1305::Nonce::from_slice(&nonce) {
                 Ok(n) => n,
-                Err(UnknownCryptoError) => return Ok(()), // Invalid nonce size test
+                Err(_) => return Ok(()), // Invalid nonce size test
             };
 
             sealres = chacha20poly1305::seal(&key, &nonce, input, Some(aad), &mut dst_ct_out);
@@ -63,7 +63,7 @@ fn wycheproof_test_runner(
         } else {
             let nonce = match xchacha20poly1305::Nonce::from_slice(&nonce) {
                 Ok(n) => n,
-                Err(UnknownCryptoError) => return Ok(()), // Invalid nonce size test
+                Err(_) => return Ok(()), // Invalid nonce size test
             };
 
             sealres = xchacha20poly1305::seal(&key, &nonce, input, Some(aad), &mut dst_ct_out);
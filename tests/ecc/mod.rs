@@ -0,0 +1,2 @@
+pub mod other_ristretto255;
+pub mod rfc_ristretto255;
@@ -0,0 +1,150 @@
+// Testing against outputs of libsodium 1.0.18 (crypto_core_ristretto255_from_hash,
+// crypto_core_ristretto255_scalar_*, crypto_core_ristretto255_add/sub and
+// crypto_scalarmult_ristretto255). The hash-to-group vectors compose
+// expand_message_xmd from the RFC 9380 with crypto_core_ristretto255_from_hash.
+
+#[cfg(test)]
+mod libsodium_ristretto255 {
+
+    use hex::decode;
+    use orion::hazardous::ecc::ristretto255::{Point, Scalar};
+
+    const DST: &[u8] = b"orion-test-ristretto255";
+
+    fn point(hex_str: &str) -> Point {
+        Point::from_slice(&decode(hex_str).unwrap()).unwrap()
+    }
+
+    fn scalar(hex_str: &str) -> Scalar {
+        Scalar::from_slice(&decode(hex_str).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_from_uniform_bytes() {
+        let vectors = [
+            (
+                "cea34b544a5ee55875d70158a634c4aee32e2d32315a138c2203b4bac5686981\
+                 45c7c62a3adc75467ea61a87a2a6f1bcd379feddc5d19e9ca35c0534d56e0131",
+                "3a51d0819ebf7474bef2c05bb68e2a7442ebbe94edab8a6ccc521e8c420a6b75",
+            ),
+            (
+                "fc6f6c7e164f882c37c44b0a626985b75d96ac427998986adc6d0f62d0cbda2a\
+                 e7d378e4f7c717fed1a7184fc2a499aed894a490ab4207986b5628d47ba75762",
+                "f41dbb50aee139d78dbdffae1fcbea7fae43d1d9a7572cb0faea2524087c5f00",
+            ),
+            (
+                "f801e19a7efd826e58a5a4c50d91bc439b6cbbfcf68e4dcb8aa475bc37b1e60c\
+                 05230075f269b42583fa5a28c3d9a5dded8334cbb1a5a318611cb685d149aba3",
+                "3c6c92977e63c6706f04c1347ce9a5103fdbd6d2c9aa3270bfa7a5cebcf6c432",
+            ),
+            (
+                "f4e9f866288ac39a7024cebdd3ba15ad6c7e5b5b437c2a9fd1f27845a36841a6\
+                 1c8fed5498c8f0b3ecf087161f87ed4c5c9a55c3bb826c6291691174f1f568ad",
+                "4428d6dc83091e9061d6d797cf14e1b34cf3423f4dcf740c77e5e1d050ebc92e",
+            ),
+        ];
+
+        for (input, expected) in vectors.iter() {
+            let p = Point::from_uniform_bytes(&decode(input).unwrap()).unwrap();
+            assert_eq!(p.to_bytes()[..], decode(expected).unwrap()[..]);
+        }
+    }
+
+    #[test]
+    fn test_hash_to_group() {
+        let vectors: [(&[u8], &[u8], &str); 3] = [
+            (
+                b"",
+                DST,
+                "8aaf98dc5a0c2c999f4658cd8ab7a48647442f71c58781c98a249194e0d97e0d",
+            ),
+            (
+                b"abc",
+                DST,
+                "c251ca299a24f82837b8ff703a184772d26d90c5983dc82f33b7511faf01336c",
+            ),
+            (
+                &[b'a'; 200],
+                b"QUUX-V01-CS02-with-ristretto255_XMD:SHA-512_R255MAP_RO_",
+                "7c170b7b3fa6f246ee2a4a677a230d4f305f9ba9bf8428c303980ce740556446",
+            ),
+        ];
+
+        for (input, dst, expected) in vectors.iter() {
+            let p = Point::hash_to_group(input, dst).unwrap();
+            assert_eq!(p.to_bytes()[..], decode(expected).unwrap()[..]);
+        }
+    }
+
+    #[test]
+    fn test_hash_to_scalar() {
+        assert_eq!(
+            Scalar::hash_to_scalar(b"abc", DST).unwrap(),
+            scalar("6b466ace20809ba688feb85d3f9e89e64597cb2b403443652a2cfbe00ccc3304")
+        );
+    }
+
+    #[test]
+    fn test_scalar_arithmetic() {
+        let a = scalar("1094d446ea06b822f93cd04c7939cddc73f7ac6b585fe70e4bad8a1e28c24f01");
+        let b = scalar("da6880e65589e05e9e88320a08ac02f3d360b44700d86caabb9bf7fcabb86703");
+
+        assert_eq!(
+            &a + &b,
+            scalar("eafc542d4090988197c5025781e5cfcf475861b3583754b90649821bd47ab704")
+        );
+        assert_eq!(
+            &a - &b,
+            scalar("23ff49bdaee0e91b315195e54f87a9fe9f96f82358877a648f1193217c09e80d")
+        );
+        assert_eq!(
+            &a * &b,
+            scalar("7e550b6b2631647f97466fd7e8f69ec76c147703489f31f3b0e593f23ceb970a")
+        );
+        assert_eq!(
+            -&a,
+            scalar("dd3f2116305c5a35dd5f275665c011388c085394a7a018f1b45275e1d73db00e")
+        );
+        assert_eq!(
+            a.invert().unwrap(),
+            scalar("86e1a440e686566bb841e5b7d6679bce3dd859506c9a48aee702922670516904")
+        );
+    }
+
+    #[test]
+    fn test_scalar_reduce() {
+        let wide = decode(
+            "520ec4cdfc7e8a6bb6319265ded41a6ab1cd5cf3ba393bdda6a35b897c369ec3\
+             671f2eb15568941bd6f092b9aeaa58ac0093e771cff0217386083c75e0ee146a",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Scalar::from_uniform_bytes(&wide).unwrap(),
+            scalar("a5129fac5a7304e5a5a721912621c5facb39dc0eb5b839036012d24cb9c9cb0a")
+        );
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let a = scalar("1094d446ea06b822f93cd04c7939cddc73f7ac6b585fe70e4bad8a1e28c24f01");
+        let b = scalar("da6880e65589e05e9e88320a08ac02f3d360b44700d86caabb9bf7fcabb86703");
+        let p = point("5617711bfa4aae26c803b3859d431a590aed85ce210e2660e97933a6487b8378");
+        let q = point("1e90ad69366a4a528e9dc6a7c238fe3bc14c8e913a4bf7b4e9b2323a223cf205");
+
+        assert_eq!(Point::mul_base(&a), p);
+        assert_eq!(Point::mul_base(&b), q);
+        assert_eq!(
+            p + q,
+            point("d825ce533de086cbce3a12c430833d745a58cf942abc07f18b3daeea4117734c")
+        );
+        assert_eq!(
+            p - q,
+            point("7819f6c967925169a5e191a68197d530f30706d7d6179b4386728c9497882a36")
+        );
+        assert_eq!(
+            p * &b,
+            point("b24fc9a296e31d076c61b5ee91e36337ef22f7ffd94ce410d123038d253e7c13")
+        );
+    }
+}
@@ -2,6 +2,8 @@ pub mod custom_hkdf;
 pub mod custom_pbkdf2;
 #[cfg(feature = "safe_api")]
 pub mod other_argon2i;
+pub mod other_bcrypt_pbkdf;
+pub mod other_concat_kdf;
 pub mod other_hkdf;
 #[cfg(feature = "safe_api")]
 pub mod pynacl_argon2i;
@@ -0,0 +1,88 @@
+// Testing against test vectors from the Go x/crypto bcrypt_pbkdf package (test_case_0)
+// and a key generated with `ssh-keygen -a 8` (test_openssh_key). The remaining
+// vectors were generated with a Python implementation checked against both.
+
+#[cfg(test)]
+mod bcrypt_pbkdf_vectors {
+
+    use hex::decode;
+    use orion::hazardous::kdf::bcrypt_pbkdf;
+
+    fn bcrypt_pbkdf_test_runner(password: &str, salt: &str, rounds: u32, expected: &str) {
+        let password = decode(password).unwrap();
+        let salt = decode(salt).unwrap();
+        let expected = decode(expected).unwrap();
+        let mut actual = vec![0u8; expected.len()];
+
+        bcrypt_pbkdf::derive_key(&password, &salt, rounds, &mut actual).unwrap();
+        assert_eq!(expected, actual);
+        assert!(bcrypt_pbkdf::verify(&expected, &password, &salt, rounds, &mut actual).is_ok());
+    }
+
+    #[test]
+    fn test_case_0() {
+        bcrypt_pbkdf_test_runner(
+            "70617373776f7264",
+            "73616c74",
+            4,
+            "5bbf0cc293587f1c3635555c27796598d47e579071bf427e9d8fbe842aba34d9",
+        );
+    }
+
+    #[test]
+    fn test_case_1() {
+        bcrypt_pbkdf_test_runner(
+            "70617373776f7264",
+            "00",
+            4,
+            "c12b566235eee04c212598970a579a67",
+        );
+    }
+
+    #[test]
+    fn test_case_2() {
+        bcrypt_pbkdf_test_runner("00", "73616c74", 4, "60cf5109beab18e7c274f43ff8332c2cbf0c0e7afea3e5cc47c81bb04be5b90902814240deb2c66e9453f590e902b3e502881d5d2cd34264f68601219fcf8c98");
+    }
+
+    #[test]
+    fn test_case_3() {
+        bcrypt_pbkdf_test_runner(
+            "70617373776f726400",
+            "73616c7400",
+            4,
+            "74d710c5e44c4c94f408fae507b2bf2faa27c8dba9562868b17972eb7f56ac1500be132075",
+        );
+    }
+
+    #[test]
+    fn test_case_4() {
+        bcrypt_pbkdf_test_runner("70617373776f7264", "73616c74", 1, "7a");
+    }
+
+    #[test]
+    fn test_case_5() {
+        bcrypt_pbkdf_test_runner("70617373776f7264", "73616c74", 16, "c339d704ec235f27690d3f12167c05a55bf86d572f270adbf9fe04c379da5f8c7942a939245dbb39ebe26fc2bd19b88b");
+    }
+
+    #[test]
+    fn test_case_6() {
+        bcrypt_pbkdf_test_runner("70617373706872617365", "000102030405060708090a0b0c0d0e0f", 2, "31980aab0f08a0816fe0eda1fe486627931c302e9cc4fc296d556365e6b8493d1c45fbd5119071c4bc9fa44f5edf3280010d04f55a85dcceedf900d0ba5c61cdad2e0269f842fa0b1eac30160ccff59cf5ce26e9ba79c8f57f0011efa1eb827a7b6c3409");
+    }
+
+    #[test]
+    fn test_case_7() {
+        bcrypt_pbkdf_test_runner("70617373776f7264", "73616c74", 1, "7aec9b2bb1f02e7008e54e8542e5f26ebd49cc2380dd664c8bbcfd087cf6031ef4a38f915c564ad45b0bb7c17924e3b75bedf0db8e72cc6c5483dd634f081192148069088d85f93b8ecf709520b5c4dfc37a3997425e5eb1823705756c0659fa69a4c2eecb8f3f4d22d47cce6b7cda19a8f094a623dd12873cd1bd46fcfeadd6e52b463a47c88c7267bad9168524de19d452c8392e5663e3d42eb79b33e4383a28a0d00bd747b44fc22f10e45ea4bb7862b955c72cb98cdf1ff8175d1166d01760c49da544e0cdcb49b8818eddf7d9b2f8010e435475aee3e4ede58ae81d2361da058bb31fc8b4b61e4003f5d15f52debecedbe0a1440625ba5396eb750f7b7f492eddacce542f436572d6926fb69a3dc0647384cc49cc774ee866d8564d2d99de724d0db6afaca50cac5bd8709b4e9d0dedb0a7614d6664b3bbd1dc824e1257bf0c02b9289085e0c0ec26d82ab637e63d64499227964ddc7c476d231884deb7aa4f44776ceb0abd910972c85f40ec79d786c3ab1902077a1ff264fc14cc9f856a2ae0b6f8d331b32928e5645669221e8ee24592f9f5de95b9f9ffa3499d30684e5f8a377de163092cb8e63fa743312fa2eee4e571fe6eab6da30480f6c4d6741252940aae725870f8b8327ac2d9478b3e3c6c83ae905085cefa2ddf91716278a24cd82ef11bf049530baba24bf2537ef288d7bd7cf1bcaa1eda86d701be6279cb8a6b39150ba1650ccedbc212af5c554063b25c4189ec8c97e0871633191db88507592f5ef7238a5e4da3596ebaf2832406c38c7b258cd28f492a0ac57e65e07baa80611ed5540b512982e55736c6bb0db2d0bc595a74aad685477be61d6dee90b65a6edf42f9aadffedc903c5c1cf19ac49971a25d69f8628e41ddb0b4f9057b3ad95ab6c3c1d80dc10798a4beabca55ccbf0ef6ab2594562398d1ef25e0372bd176094a01c8df55e5bac2880015c0bf227f762f67f4777a61f1245b973583b2bb1e2fc28f025831408fe3104cfb37a44c9d40253bd2648309b0bdac3fd95f0d1cce21828d86fb442b82e385c7831f09f963ec90b9a9e449f821fec762fc272935d8af608b7fc796a3baa92b89ce827b2575349fb9ea6fe0feb64ad5a30e32020417052e8120f0a191b48d7728abb698c07ba48a230e575a0c432a7efe9152a9f92cd351459dc5a3bf6cb4555fcfb32bd5d40f839ad1199fe0112c4dbee577cc8ff9998cd753f22c859f7b2eed99d7d64ece1d42027c735ef7f4110eb1eba8d44299693a766f9335e1f9db348a7708a23d9ab017948414bb4633e221278f7424c82e98b63a445c94cc6cb7d5245cddc0bca63aff2979d5f6c8de8c5cbcae6bad77343387ddd65df94a935e4904171b32fbe9ba03b82f646d9248bec1e38b64b1792b9669a6d023ffb56d7bc8bbde0555e5e972875c6f2239ab9e97b4c59fe6");
+    }
+
+    #[test]
+    fn test_openssh_key() {
+        // Passphrase "correct horse". The derived key is the AES-256-CTR key and IV,
+        // which decrypt the private key section of the key file.
+        bcrypt_pbkdf_test_runner(
+            "636f727265637420686f727365",
+            "89218b10b99829f80ceadb597c661643",
+            8,
+            "2b2abd2bfae2c6c5f9e885ac7a5ef2fc12c410a2d255c73344de88d3dd88c4eb5729a0eb0ac86c96ee4992fb668833b8",
+        );
+    }
+}
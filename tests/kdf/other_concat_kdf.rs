@@ -0,0 +1,95 @@
+// Testing against the ECDH-ES key agreement example from RFC 7518, Appendix C
+// (test_rfc7518_appendix_c). The remaining vectors were generated with the
+// ConcatKDFHash implementation of the Python `cryptography` package.
+
+#[cfg(test)]
+mod concat_kdf_vectors {
+
+    use hex::decode;
+    use orion::hazardous::kdf::concat_kdf;
+
+    const RFC7518_Z: &str = "9e56d91d817135d372834283bf84269cfb316ea3da806a48f6daa7798cfe90c4";
+    const RFC7518_OTHER_INFO: &str =
+        "000000074131323847434d00000005416c69636500000003426f6200000080";
+
+    fn sha256_test_runner(shared_secret: &str, fixed_info: &str, expected: &str) {
+        let shared_secret = decode(shared_secret).unwrap();
+        let fixed_info = decode(fixed_info).unwrap();
+        let expected = decode(expected).unwrap();
+        let mut actual = vec![0u8; expected.len()];
+
+        concat_kdf::sha256::derive_key(&shared_secret, &fixed_info, &mut actual).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    fn sha512_test_runner(shared_secret: &str, fixed_info: &str, expected: &str) {
+        let shared_secret = decode(shared_secret).unwrap();
+        let fixed_info = decode(fixed_info).unwrap();
+        let expected = decode(expected).unwrap();
+        let mut actual = vec![0u8; expected.len()];
+
+        concat_kdf::sha512::derive_key(&shared_secret, &fixed_info, &mut actual).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_rfc7518_appendix_c() {
+        sha256_test_runner(
+            RFC7518_Z,
+            RFC7518_OTHER_INFO,
+            "56aa8deaf8236d205c2228cd71a7101a",
+        );
+    }
+
+    #[test]
+    fn test_sha256_multiple_blocks() {
+        sha256_test_runner(
+            RFC7518_Z,
+            RFC7518_OTHER_INFO,
+            "56aa8deaf8236d205c2228cd71a7101aa4a8a036b0436d4f591331c26af44460\
+             b0d12ce7559d8af6f9945ce1dbd17c4541996713b3200dcc6d00f024ae5a3885\
+             498564e877b08c0f7289af4c91e745ce",
+        );
+    }
+
+    #[test]
+    fn test_sha256_empty_inputs() {
+        sha256_test_runner(
+            "",
+            "",
+            "b40711a88c7039756fb8a73827eabe2c0fe5a0346ca7e0a104adc0fc764f528d",
+        );
+    }
+
+    #[test]
+    fn test_sha512() {
+        sha512_test_runner(
+            RFC7518_Z,
+            RFC7518_OTHER_INFO,
+            "518d482b42cad8eb50e01bcd6a9676a1e02d83120fc665d447696a0ce81ab3bd",
+        );
+    }
+
+    #[test]
+    fn test_sha512_multiple_blocks() {
+        sha512_test_runner(
+            RFC7518_Z,
+            RFC7518_OTHER_INFO,
+            "518d482b42cad8eb50e01bcd6a9676a1e02d83120fc665d447696a0ce81ab3bd\
+             2bfbf3dc1f31e54b7b99b0a2a7601dbf2d85042485ad4bc7c1dc19b5c26f3599\
+             2bfed3c5d9aee2ba2115ff1298fdbc6ee5d3755ff49cdae9fa046c30495e89fa\
+             6274c10e0a3e75266da0be395cec96238a375d2d6ae689fc63c4ca589e8a3916\
+             09a334970688b9d65ab6b4bb0cc8d813a2df066cc0a1",
+        );
+    }
+
+    #[test]
+    fn test_sha512_empty_inputs() {
+        sha512_test_runner(
+            "",
+            "",
+            "57c365278e08f99674dd24f08425b17c71b0511dea3b5ffa474deeb26d64cb99\
+             3ebda4650583b29cba6307d7f4dbb42ca11b093de2b8ecab16ff52445401facf",
+        );
+    }
+}
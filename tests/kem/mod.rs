@@ -0,0 +1,2 @@
+pub mod other_hybrid;
+pub mod other_mlkem768;
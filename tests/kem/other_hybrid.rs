@@ -0,0 +1,67 @@
+// Testing against the X25519 and ML-KEM-768 implementations of the Python `cryptography`
+// package (version 48.0, using OpenSSL), composed as described in the X-Wing draft with
+// hashlib for SHAKE256 and SHA3-256. The ciphertexts use a random ephemeral X25519 key
+// and MLKEM768PublicKey.encapsulate().
+
+#[cfg(test)]
+mod hybrid_kem_vectors {
+
+    use hex::decode;
+    use orion::hazardous::kem::hybrid::*;
+    use serde::Deserialize;
+    use std::{fs::File, io::BufReader};
+
+    #[derive(Deserialize, Debug)]
+    struct TestCase {
+        decapsulation_key: String,
+        encapsulation_key: String,
+        ciphertext: String,
+        shared_secret: String,
+    }
+
+    fn test_cases() -> Vec<TestCase> {
+        let file = File::open("./tests/test_data/hybrid_kem_generated.json").unwrap();
+        let reader = BufReader::new(file);
+
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    #[test]
+    fn test_public_key() {
+        for test in test_cases().iter() {
+            let dk =
+                DecapsulationKey::from_slice(&decode(&test.decapsulation_key).unwrap()).unwrap();
+            let ek =
+                EncapsulationKey::from_slice(&decode(&test.encapsulation_key).unwrap()).unwrap();
+            assert_eq!(dk.public_key(), ek);
+        }
+    }
+
+    #[test]
+    fn test_decapsulate() {
+        for test in test_cases().iter() {
+            let dk =
+                DecapsulationKey::from_slice(&decode(&test.decapsulation_key).unwrap()).unwrap();
+            let ct = Ciphertext::from_slice(&decode(&test.ciphertext).unwrap()).unwrap();
+            let ss = decapsulate(&dk, &ct).unwrap();
+            assert_eq!(ss, &decode(&test.shared_secret).unwrap()[..]);
+        }
+    }
+
+    #[test]
+    fn test_decapsulate_modified_ciphertext() {
+        for test in test_cases().iter() {
+            let dk =
+                DecapsulationKey::from_slice(&decode(&test.decapsulation_key).unwrap()).unwrap();
+            let expected = decode(&test.shared_secret).unwrap();
+
+            // Modifying either the ML-KEM-768 or the X25519 part changes the shared secret.
+            for idx in [0, 1087, 1088, HYBRID_CIPHERTEXTSIZE - 1].iter() {
+                let mut ct = decode(&test.ciphertext).unwrap();
+                ct[*idx] ^= 1;
+                let ct = Ciphertext::from_slice(&ct).unwrap();
+                assert_ne!(decapsulate(&dk, &ct).unwrap(), &expected[..]);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Testing against the ML-KEM-768 implementation of the Python `cryptography` package
+// (version 48.0, using OpenSSL). Each key pair is derived from a seed, and the ciphertexts
+// have been generated with MLKEM768PublicKey.encapsulate(). The invalid ciphertexts
+// have one bit flipped, and the rejected shared secrets are SHAKE256(z || c, 32) as
+// in the FIPS 203, Algorithm 18.
+
+#[cfg(test)]
+mod mlkem768_vectors {
+
+    use hex::decode;
+    use orion::hazardous::kem::mlkem768::*;
+    use serde::Deserialize;
+    use std::{fs::File, io::BufReader};
+
+    #[derive(Deserialize, Debug)]
+    struct TestCase {
+        seed: String,
+        ek: String,
+        ciphertext: String,
+        shared_secret: String,
+        invalid_ciphertext: String,
+        rejected_shared_secret: String,
+    }
+
+    fn test_cases() -> Vec<TestCase> {
+        let file = File::open("./tests/test_data/mlkem768_generated.json").unwrap();
+        let reader = BufReader::new(file);
+
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    fn keypair(test: &TestCase) -> KeyPair {
+        KeyPair::from_seed(&Seed::from_slice(&decode(&test.seed).unwrap()).unwrap())
+    }
+
+    #[test]
+    fn test_keypair_from_seed() {
+        for test in test_cases().iter() {
+            let kp = keypair(test);
+            assert_eq!(kp.public().as_ref(), &decode(&test.ek).unwrap()[..]);
+            assert_eq!(&kp.private().public_key(), kp.public());
+
+            let ek = EncapsulationKey::from_slice(&decode(&test.ek).unwrap()).unwrap();
+            assert_eq!(&ek, kp.public());
+        }
+    }
+
+    #[test]
+    fn test_decapsulate() {
+        for test in test_cases().iter() {
+            let kp = keypair(test);
+            let ct = Ciphertext::from_slice(&decode(&test.ciphertext).unwrap()).unwrap();
+            let ss = decapsulate(kp.private(), &ct).unwrap();
+            assert_eq!(ss, &decode(&test.shared_secret).unwrap()[..]);
+
+            // Same result after round-tripping the expanded decapsulation key.
+            let dk = DecapsulationKey::from_slice(kp.private().unprotected_as_bytes()).unwrap();
+            assert_eq!(decapsulate(&dk, &ct).unwrap(), ss);
+        }
+    }
+
+    #[test]
+    fn test_implicit_rejection() {
+        for test in test_cases().iter() {
+            let kp = keypair(test);
+            let ct = Ciphertext::from_slice(&decode(&test.invalid_ciphertext).unwrap()).unwrap();
+            let ss = decapsulate(kp.private(), &ct).unwrap();
+            assert_eq!(ss, &decode(&test.rejected_shared_secret).unwrap()[..]);
+            assert_ne!(ss, &decode(&test.shared_secret).unwrap()[..]);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// Testing against RFC 3394 test vectors and test vectors generated
+// with pyca/cryptography.
+#[cfg(test)]
+mod rfc3394_rfc5649 {
+    use hex::decode;
+    use orion::hazardous::kw::{self, SecretKey};
+
+    const KEK: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn kw_test_runner(key_data: &str, expected: &str) {
+        let kek = SecretKey::from_slice(&decode(KEK).unwrap()).unwrap();
+        let key_data = decode(key_data).unwrap();
+        let expected = decode(expected).unwrap();
+
+        let mut wrapped = vec![0u8; expected.len()];
+        kw::wrap(&kek, &key_data, &mut wrapped).unwrap();
+        assert_eq!(wrapped, expected);
+
+        let mut unwrapped = vec![0u8; key_data.len()];
+        kw::unwrap(&kek, &expected, &mut unwrapped).unwrap();
+        assert_eq!(unwrapped, key_data);
+    }
+
+    fn kwp_test_runner(key_data: &str, expected: &str) {
+        let kek = SecretKey::from_slice(&decode(KEK).unwrap()).unwrap();
+        let key_data = decode(key_data).unwrap();
+        let expected = decode(expected).unwrap();
+
+        let mut wrapped = vec![0u8; expected.len()];
+        kw::wrap_with_padding(&kek, &key_data, &mut wrapped).unwrap();
+        assert_eq!(wrapped, expected);
+
+        let mut unwrapped = vec![0u8; expected.len() - 8];
+        let len = kw::unwrap_with_padding(&kek, &expected, &mut unwrapped).unwrap();
+        assert_eq!(&unwrapped[..len], &key_data[..]);
+    }
+
+    #[test]
+    fn rfc3394_4_3_wrap_128_bits_with_256_bit_kek() {
+        kw_test_runner(
+            "00112233445566778899aabbccddeeff",
+            "64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7",
+        );
+    }
+
+    #[test]
+    fn rfc3394_4_6_wrap_256_bits_with_256_bit_kek() {
+        kw_test_runner(
+            "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f",
+            "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43bfb988b9b7a02dd21",
+        );
+    }
+
+    #[test]
+    fn kwp_7_bytes() {
+        kwp_test_runner("466f7250617369", "443b17837bb39348610d19202df8a1f9");
+    }
+
+    #[test]
+    fn kwp_8_bytes() {
+        kwp_test_runner("0011223344556677", "2bf5af5b28f4cb67cd3e1b1f9ac4049a");
+    }
+
+    #[test]
+    fn kwp_20_bytes() {
+        kwp_test_runner(
+            "c37b7e6492584340bed12207808941155068f738",
+            "29b7fa191c2165684374eee9f74595e2a42bace75c425b3053efa26ffe1bb32f",
+        );
+    }
+}
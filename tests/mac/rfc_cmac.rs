@@ -0,0 +1,53 @@
+// Testing against RFC 4493 test vectors
+#[cfg(test)]
+mod rfc4493_cmac {
+    use hex::decode;
+    use orion::hazardous::mac::cmac::{Cmac, SecretKey, Tag};
+
+    fn cmac_test_runner(key: &str, data: &str, expected: &str) {
+        let key = SecretKey::from_slice(&decode(key).unwrap()).unwrap();
+        let data = decode(data).unwrap();
+        let expected = Tag::from_slice(&decode(expected).unwrap()).unwrap();
+
+        let mut state = Cmac::new(&key);
+        state.update(&data).unwrap();
+        assert_eq!(state.finalize().unwrap(), expected);
+        assert!(Cmac::verify(&expected, &key, &data).is_ok());
+    }
+
+    const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+
+    #[test]
+    fn test_case_1() {
+        cmac_test_runner(KEY, "", "bb1d6929e95937287fa37d129b756746");
+    }
+
+    #[test]
+    fn test_case_2() {
+        cmac_test_runner(
+            KEY,
+            "6bc1bee22e409f96e93d7e117393172a",
+            "070a16b46b4d4144f79bdd9dd04a287c",
+        );
+    }
+
+    #[test]
+    fn test_case_3() {
+        cmac_test_runner(
+            KEY,
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411",
+            "dfa66747de9ae63030ca32611497c827",
+        );
+    }
+
+    #[test]
+    fn test_case_4() {
+        cmac_test_runner(
+            KEY,
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+             30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710",
+            "51f0bebf7e3b9d92fc49741779363cfe",
+        );
+    }
+}
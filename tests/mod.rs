@@ -1,13 +1,21 @@
 #[cfg(feature = "safe_api")]
 #[cfg(test)]
 pub mod aead;
+#[cfg(test)]
+pub mod ecc;
 #[cfg(feature = "safe_api")]
 #[cfg(test)]
 pub mod hash;
 #[cfg(test)]
 pub mod kdf;
 #[cfg(test)]
+pub mod kem;
+#[cfg(test)]
+pub mod kw;
+#[cfg(test)]
 pub mod mac;
+#[cfg(test)]
+pub mod sig;
 #[cfg(feature = "safe_api")]
 #[cfg(test)]
 pub mod stream;
@@ -0,0 +1 @@
+pub mod other_mldsa65;
@@ -0,0 +1,81 @@
+// Testing against the ML-DSA-65 implementation of the Python `cryptography` package
+// (version 48.0, using OpenSSL). Each key pair is derived from a seed, and the signatures
+// have been generated with MLDSA65PrivateKey.sign(), which uses the hedged variant
+// with an empty context string.
+
+#[cfg(test)]
+mod mldsa65_vectors {
+
+    use hex::decode;
+    use orion::hazardous::sig::mldsa65::*;
+    use serde::Deserialize;
+    use std::{fs::File, io::BufReader};
+
+    #[derive(Deserialize, Debug)]
+    struct TestCase {
+        seed: String,
+        public_key: String,
+        message: String,
+        signature: String,
+    }
+
+    fn test_cases() -> Vec<TestCase> {
+        let file = File::open("./tests/test_data/mldsa65_generated.json").unwrap();
+        let reader = BufReader::new(file);
+
+        serde_json::from_reader(reader).unwrap()
+    }
+
+    #[test]
+    fn test_public_key_from_seed() {
+        for test in test_cases().iter() {
+            let sk = SecretKey::from_slice(&decode(&test.seed).unwrap()).unwrap();
+            let pk = PublicKey::from(&sk);
+            assert_eq!(pk.as_ref(), &decode(&test.public_key).unwrap()[..]);
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        for test in test_cases().iter() {
+            let pk = PublicKey::from_slice(&decode(&test.public_key).unwrap()).unwrap();
+            let sig = Signature::from_slice(&decode(&test.signature).unwrap()).unwrap();
+            let message = decode(&test.message).unwrap();
+            assert!(verify(&sig, &pk, &message).is_ok());
+
+            let mut other_message = message.clone();
+            other_message.push(0u8);
+            assert!(verify(&sig, &pk, &other_message).is_err());
+        }
+    }
+
+    #[test]
+    fn test_verify_modified_signature() {
+        for test in test_cases().iter() {
+            let pk = PublicKey::from_slice(&decode(&test.public_key).unwrap()).unwrap();
+            let message = decode(&test.message).unwrap();
+
+            // Flip a bit in the commitment hash, the response and the hint.
+            for idx in [0, 47, 48, 3247, 3248, MLDSA65_SIGNATURESIZE - 1].iter() {
+                let mut sig = decode(&test.signature).unwrap();
+                sig[*idx] ^= 1;
+                let sig = Signature::from_slice(&sig).unwrap();
+                assert!(verify(&sig, &pk, &message).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        for test in test_cases().iter() {
+            let sk = SecretKey::from_slice(&decode(&test.seed).unwrap()).unwrap();
+            let pk = PublicKey::from_slice(&decode(&test.public_key).unwrap()).unwrap();
+            let message = decode(&test.message).unwrap();
+
+            let sig = sign(&sk, &message).unwrap();
+            assert!(verify(&sig, &pk, &message).is_ok());
+            // The deterministic variant always gives the same signature.
+            assert_eq!(sign(&sk, &message).unwrap(), sig);
+        }
+    }
+}
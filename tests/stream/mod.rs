@@ -1,3 +1,4 @@
+pub mod nist_aes256ctr;
 pub mod other_chacha20;
 pub mod rfc_chacha20;
 pub mod rfc_xchacha20;
@@ -0,0 +1,73 @@
+// Testing against NIST SP 800-38A test vectors and test vectors generated
+// with pyca/cryptography.
+#[cfg(test)]
+mod nist_sp800_38a {
+    use hex::decode;
+    use orion::hazardous::stream::aes256ctr::{self, Nonce, SecretKey};
+
+    fn aes256ctr_test_runner(
+        key: &str,
+        nonce: &str,
+        initial_counter: u32,
+        input: &[u8],
+        output: &str,
+    ) {
+        let sk = SecretKey::from_slice(&decode(key).unwrap()).unwrap();
+        let n = Nonce::from_slice(&decode(nonce).unwrap()).unwrap();
+        let expected = decode(output).unwrap();
+
+        let mut dst_out = vec![0u8; input.len()];
+        aes256ctr::encrypt(&sk, &n, initial_counter, input, &mut dst_out).unwrap();
+        assert_eq!(dst_out, expected);
+
+        let mut dst_out_pt = vec![0u8; input.len()];
+        aes256ctr::decrypt(&sk, &n, initial_counter, &expected, &mut dst_out_pt).unwrap();
+        assert_eq!(dst_out_pt, input);
+    }
+
+    #[test]
+    fn f_5_5_ctr_aes256_encrypt() {
+        aes256ctr_test_runner(
+            "603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+            "f0f1f2f3f4f5f6f7f8f9fafb",
+            0xfcfdfeff,
+            &decode(
+                "6bc1bee22e409f96e93d7e117393172a\
+                 ae2d8a571e03ac9c9eb76fac45af8e51\
+                 30c81c46a35ce411e5fbc1191a0a52ef\
+                 f69f2445df4f9b17ad2b417be66c3710",
+            )
+            .unwrap(),
+            "601ec313775789a5b7a7f504bbf3d228\
+             f443e3ca4d62b59aca84e990cacaf5c5\
+             2b0930daa23de94ce87017ba2d84988d\
+             dfc9c58db67aada613c2dd08457941a6",
+        );
+    }
+
+    #[test]
+    fn partial_block() {
+        let input: Vec<u8> = (0..100).map(|i: u32| (i * 7 % 256) as u8).collect();
+        aes256ctr_test_runner(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "6465666768696a6b6c6d6e6f",
+            7,
+            &input,
+            "647f1121bbe6a7f7e7ce70892f482c38fe06ecfbe7de22df1b55687866c66a31\
+             c2cc6eea3d7ab325aa66b59cee8f4a8e62c8c6cd054138aa41c6c0982d2bee3b\
+             560033938ca86c63c96e7840f40a87d3a0061919dc8fbb810337d78a68ff3157\
+             3656631c",
+        );
+    }
+
+    #[test]
+    fn last_counter_blocks() {
+        aes256ctr_test_runner(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "6465666768696a6b6c6d6e6f",
+            0xfffffffe,
+            &[0u8; 32],
+            "438f17426dc6dc07f0bf203c3f22e3e41bb2214fb0897c134b46e804844d5c2d",
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Checks that secret data does not remain in heap memory after it has been freed.
+//!
+//! The global allocator below scans every block that is freed while tracking is enabled,
+//! and records a leak if the block still contains [`NEEDLE`], or if a block of the
+//! size set in `ZERO_CHECK_SIZE` is not entirely zero. All blocks are allocated zeroed,
+//! so no uninitialized memory is ever read. Because the allocator state is global, all
+//! checks are made from a single test.
+
+#![cfg(all(feature = "safe_api", not(miri)))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const NEEDLE: [u8; 32] = *b"orion-heap-zeroization-needle-32";
+
+static TRACKING: AtomicBool = AtomicBool::new(false);
+static LEAKS: AtomicUsize = AtomicUsize::new(0);
+static ZERO_CHECK_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+struct CapturingAllocator;
+
+unsafe impl GlobalAlloc for CapturingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if TRACKING.load(Ordering::SeqCst) {
+            let block = std::slice::from_raw_parts(ptr, layout.size());
+            let contains_needle = block.windows(NEEDLE.len()).any(|w| w == NEEDLE);
+            let must_be_zero = layout.size() == ZERO_CHECK_SIZE.load(Ordering::SeqCst);
+            if contains_needle || (must_be_zero && block.iter().any(|b| *b != 0)) {
+                LEAKS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CapturingAllocator = CapturingAllocator;
+
+/// Run `f` with tracking enabled and assert that no freed block leaked secret data.
+fn assert_no_leaks<F: FnOnce()>(name: &str, zero_check_size: usize, f: F) {
+    LEAKS.store(0, Ordering::SeqCst);
+    ZERO_CHECK_SIZE.store(zero_check_size, Ordering::SeqCst);
+    TRACKING.store(true, Ordering::SeqCst);
+    f();
+    TRACKING.store(false, Ordering::SeqCst);
+    ZERO_CHECK_SIZE.store(0, Ordering::SeqCst);
+
+    assert_eq!(
+        LEAKS.load(Ordering::SeqCst),
+        0,
+        "{} leaked secret data",
+        name
+    );
+}
+
+#[test]
+fn test_freed_memory_is_zeroized() {
+    use orion::{aead, file, kdf, pwhash, vault};
+    use zeroize::Zeroize;
+
+    // Argon2i with 8 KiB of memory allocates its blocks as a single 8192-byte buffer.
+    const ARGON2_BLOCKS_SIZE: usize = 8 * 1024;
+
+    assert_no_leaks("aead::SecretKey", 0, || {
+        let sk = aead::SecretKey::from_slice(&NEEDLE).unwrap();
+        drop(sk);
+    });
+
+    assert_no_leaks("SecretBytes spare capacity", 0, || {
+        let mut bytes = Vec::with_capacity(128);
+        bytes.extend_from_slice(&NEEDLE);
+        bytes.extend_from_slice(&NEEDLE);
+        bytes.truncate(1);
+        drop(orion::util::SecretBytes::from(bytes));
+    });
+
+    assert_no_leaks("aead::open_secret", 0, || {
+        let sk = aead::SecretKey::default();
+        let ciphertext = aead::seal(&sk, &NEEDLE).unwrap();
+        let plaintext = aead::open_secret(&sk, &ciphertext).unwrap();
+        assert_eq!(plaintext.unprotected_as_bytes(), &NEEDLE[..]);
+    });
+
+    #[cfg(feature = "serde")]
+    assert_no_leaks("aead::seal_value", 0, || {
+        let sk = aead::SecretKey::default();
+        let ciphertext = aead::seal_value(&sk, &NEEDLE[..]).unwrap();
+        drop(ciphertext);
+    });
+
+    assert_no_leaks("pwhash::hash_password", ARGON2_BLOCKS_SIZE, || {
+        let password = pwhash::Password::from_slice(&NEEDLE).unwrap();
+        let hash = pwhash::hash_password(&password, 3, 8).unwrap();
+        assert!(pwhash::hash_password_verify(&hash, &password).is_ok());
+    });
+
+    assert_no_leaks("kdf::derive_key", ARGON2_BLOCKS_SIZE, || {
+        let password = kdf::Password::from_slice(&NEEDLE).unwrap();
+        let salt = kdf::Salt::default();
+        let key = kdf::derive_key(&password, &salt, 3, 8, 64).unwrap();
+        drop(key);
+    });
+
+    assert_no_leaks("file::seal_file and file::open_file", 0, || {
+        let sk = aead::SecretKey::default();
+        let plaintext = NEEDLE.repeat(64);
+        let mut sealed = Vec::new();
+        file::seal_file(&sk, &plaintext[..], &mut sealed).unwrap();
+
+        let mut opened = Vec::with_capacity(plaintext.len());
+        file::open_file(&sk, &sealed[..], &mut opened).unwrap();
+        assert_eq!(opened, plaintext);
+
+        let (mut plaintext, mut opened) = (plaintext, opened);
+        plaintext.zeroize();
+        opened.zeroize();
+    });
+
+    assert_no_leaks("vault::Vault", ARGON2_BLOCKS_SIZE, || {
+        let password = kdf::Password::from_slice(b"vault password").unwrap();
+        let mut v = vault::Vault::create_with_params(&password, 3, 8).unwrap();
+        for i in 0..16 {
+            v.put(&format!("entry {}", i), &NEEDLE).unwrap();
+        }
+        let mut written = Vec::new();
+        v.write_to(&mut written).unwrap();
+        drop(v);
+
+        let v = vault::Vault::from_reader(&written[..], &password).unwrap();
+        assert_eq!(
+            v.get("entry 15").unwrap().unprotected_as_bytes(),
+            &NEEDLE[..]
+        );
+    });
+}